@@ -0,0 +1,126 @@
+//! Artwork URL helpers
+//!
+//! Qobuz album cover URLs end in a size suffix
+//! (`https://static.qobuz.com/images/covers/../0060254782058_600.jpg`); the
+//! same cover is served at each size in `COVER_SIZES` and at `max`, the
+//! original upload. Other image URLs (artist pictures, Plex, local files) are
+//! passed through unchanged.
+
+use serde::{Deserialize, Serialize};
+
+/// Square cover sizes (px) Qobuz serves besides `max`
+pub const COVER_SIZES: &[u32] = &[50, 150, 230, 300, 600];
+
+const COVER_PATH: &str = "static.qobuz.com/images/covers/";
+
+/// Artwork quality preference
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtworkQuality {
+    /// 230px covers, for metered or slow connections
+    Low,
+    /// 600px covers (what the API's "large" image is)
+    #[default]
+    Standard,
+    /// Original-resolution covers, for HiDPI/4K displays
+    High,
+}
+
+impl ArtworkQuality {
+    /// Desired cover edge in pixels (`u32::MAX` = original size)
+    pub fn desired_size(self) -> u32 {
+        match self {
+            ArtworkQuality::Low => 230,
+            ArtworkQuality::Standard => 600,
+            ArtworkQuality::High => u32::MAX,
+        }
+    }
+
+    pub fn to_db_value(self) -> &'static str {
+        match self {
+            ArtworkQuality::Low => "low",
+            ArtworkQuality::Standard => "standard",
+            ArtworkQuality::High => "high",
+        }
+    }
+
+    pub fn from_db_value(s: &str) -> Self {
+        match s {
+            "low" => ArtworkQuality::Low,
+            "high" => ArtworkQuality::High,
+            _ => ArtworkQuality::Standard,
+        }
+    }
+}
+
+/// Size suffix for the smallest cover at least `desired_size` px wide,
+/// or `max` if none is large enough
+fn size_suffix(desired_size: u32) -> String {
+    COVER_SIZES
+        .iter()
+        .find(|&&size| size >= desired_size)
+        .map(|size| size.to_string())
+        .unwrap_or_else(|| "max".to_string())
+}
+
+/// Split a Qobuz cover URL into (prefix, size suffix, extension)
+pub(crate) fn split_cover_url(url: &str) -> Option<(&str, &str, &str)> {
+    if !url.contains(COVER_PATH) {
+        return None;
+    }
+    let (stem, ext) = url.rsplit_once('.')?;
+    let (prefix, suffix) = stem.rsplit_once('_')?;
+    let known = suffix == "max" || suffix == "org" || suffix.parse::<u32>().is_ok();
+    if !known || ext.contains('/') {
+        return None;
+    }
+    Some((prefix, suffix, ext))
+}
+
+/// Rewrite `base` to the best matching Qobuz cover size for `desired_size`
+/// pixels. URLs that aren't Qobuz covers are returned unchanged.
+pub fn get_artwork_url(base: &str, desired_size: u32) -> String {
+    match split_cover_url(base) {
+        Some((prefix, _, ext)) => format!("{}_{}.{}", prefix, size_suffix(desired_size), ext),
+        None => base.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COVER: &str = "https://static.qobuz.com/images/covers/58/20/0060254782058_600.jpg";
+
+    #[test]
+    fn picks_smallest_size_that_fits() {
+        assert_eq!(
+            get_artwork_url(COVER, 200),
+            "https://static.qobuz.com/images/covers/58/20/0060254782058_230.jpg"
+        );
+        assert_eq!(
+            get_artwork_url(COVER, 50),
+            "https://static.qobuz.com/images/covers/58/20/0060254782058_50.jpg"
+        );
+        assert_eq!(
+            get_artwork_url(COVER, 1200),
+            "https://static.qobuz.com/images/covers/58/20/0060254782058_max.jpg"
+        );
+        assert_eq!(
+            get_artwork_url(
+                "https://static.qobuz.com/images/covers/58/20/0060254782058_max.jpg",
+                ArtworkQuality::Standard.desired_size()
+            ),
+            COVER
+        );
+    }
+
+    #[test]
+    fn leaves_other_urls_alone() {
+        let artist = "https://static.qobuz.com/images/artists/covers/medium/abc.jpg";
+        assert_eq!(get_artwork_url(artist, 1200), artist);
+        let plex = "http://192.168.1.2:32400/library/metadata/1/thumb/2";
+        assert_eq!(get_artwork_url(plex, 1200), plex);
+        assert_eq!(get_artwork_url("", 600), "");
+    }
+}
@@ -69,6 +69,12 @@ pub fn parse_login_response(response: &serde_json::Value) -> Result<UserSession>
         .unwrap_or("")
         .to_string();
 
+    let zone = user
+        .get("zone")
+        .and_then(|v| v.as_str())
+        .filter(|z| !z.is_empty())
+        .map(|z| z.to_uppercase());
+
     // Check subscription
     let credential = user.get("credential");
     let subscription_label = credential
@@ -130,6 +136,7 @@ pub fn parse_login_response(response: &serde_json::Value) -> Result<UserSession>
         display_name,
         subscription_label,
         subscription_valid_until,
+        zone,
     })
 }
 
@@ -148,4 +155,18 @@ mod tests {
         let sig = sign_get_file_url(123456, 27, 1234567890, "testsecret");
         assert_eq!(sig.len(), 32);
     }
+
+    #[test]
+    fn test_parse_login_response_zone() {
+        let response = serde_json::json!({
+            "user_auth_token": "token",
+            "user": {
+                "id": 42,
+                "zone": "fr",
+                "credential": { "parameters": { "short_label": "Studio" } }
+            }
+        });
+        let session = parse_login_response(&response).unwrap();
+        assert_eq!(session.zone.as_deref(), Some("FR"));
+    }
 }
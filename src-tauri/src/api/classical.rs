@@ -0,0 +1,228 @@
+//! Classical-music metadata (composer, work, movement)
+//!
+//! Qobuz names the work of a classical track in `work` and repeats it in the
+//! title ("Symphony No. 5 in C Minor, Op. 67: I. Allegro con brio"); the
+//! movement is what follows it. Tracks without `work` are split the same way
+//! when the part after the colon starts with a roman numeral ("II. Andante").
+
+use serde::{Deserialize, Serialize};
+
+use super::models::{Album, Track};
+
+/// Consecutive tracks of an album that belong to the same work
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkGroup {
+    pub work: String,
+    pub composer: Option<String>,
+    pub track_ids: Vec<u64>,
+}
+
+/// Split a track title into (work, movement)
+pub fn split_title(title: &str, work: Option<&str>) -> (Option<String>, Option<String>) {
+    let title = title.trim();
+
+    if let Some(work) = work.map(str::trim).filter(|w| !w.is_empty()) {
+        let movement = match strip_prefix_ignore_case(title, work) {
+            Some(rest) => rest.trim_start_matches([':', '-', ',', ' ']).trim(),
+            None => title,
+        };
+        let movement = Some(movement.to_string()).filter(|m| !m.is_empty());
+        return (Some(work.to_string()), movement);
+    }
+
+    for (index, _) in title.match_indices(": ") {
+        let rest = title[index + 2..].trim();
+        if starts_with_movement_number(rest) {
+            let work = title[..index].trim();
+            if !work.is_empty() {
+                return (Some(work.to_string()), Some(rest.to_string()));
+            }
+        }
+    }
+
+    (None, None)
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &s[prefix.len()..])
+}
+
+/// "IV. Finale" or "III: Scherzo"
+///
+/// The numeral must be followed by "." or ":" so that titles like
+/// "Live: I Got Rhythm" aren't taken for a movement.
+fn starts_with_movement_number(s: &str) -> bool {
+    let numeral_len = s
+        .chars()
+        .take_while(|c| matches!(c, 'I' | 'V' | 'X' | 'L'))
+        .count();
+    (1..=6).contains(&numeral_len) && matches!(s[numeral_len..].chars().next(), Some('.' | ':'))
+}
+
+/// Fill in the work and movement of every track of `album`, fall back to the
+/// album composer, and group the tracks by work
+///
+/// `works` stays `None` for albums without any work (i.e. non-classical).
+pub fn apply_classical_fields(album: &mut Album) {
+    let album_composer = album.composer.clone();
+    let Some(tracks) = album.tracks.as_mut() else {
+        return;
+    };
+
+    for track in tracks.items.iter_mut() {
+        let (work, movement) = split_title(&track.title, track.work.as_deref());
+        track.work = work;
+        track.movement = movement;
+        if track.composer.is_none() && track.work.is_some() {
+            track.composer = album_composer.clone();
+        }
+    }
+
+    let works = group_by_work(&tracks.items);
+    album.works = (!works.is_empty()).then_some(works);
+}
+
+/// Group consecutive tracks with the same work
+pub fn group_by_work(tracks: &[Track]) -> Vec<WorkGroup> {
+    let mut groups: Vec<WorkGroup> = Vec::new();
+
+    for track in tracks {
+        let Some(work) = track.work.as_deref() else {
+            continue;
+        };
+        let composer = track.composer.as_ref().map(|c| c.name.clone());
+        match groups.last_mut() {
+            Some(last)
+                if last.work == work
+                    && last.composer == composer
+                    && is_next_track(tracks, last, track) =>
+            {
+                last.track_ids.push(track.id);
+            }
+            _ => groups.push(WorkGroup {
+                work: work.to_string(),
+                composer,
+                track_ids: vec![track.id],
+            }),
+        }
+    }
+
+    groups
+}
+
+/// Whether `track` directly follows the last track of `group`
+fn is_next_track(tracks: &[Track], group: &WorkGroup, track: &Track) -> bool {
+    let last_id = group.track_ids.last().copied();
+    tracks
+        .windows(2)
+        .any(|pair| Some(pair[0].id) == last_id && pair[1].id == track.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(id: u64, title: &str, work: Option<&str>) -> Track {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": title,
+            "work": work,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_split_title_with_work() {
+        assert_eq!(
+            split_title(
+                "Symphony No. 5 in C Minor, Op. 67: I. Allegro con brio",
+                Some("Symphony No. 5 in C Minor, Op. 67"),
+            ),
+            (
+                Some("Symphony No. 5 in C Minor, Op. 67".to_string()),
+                Some("I. Allegro con brio".to_string())
+            )
+        );
+        // Title holds only the movement
+        assert_eq!(
+            split_title("Adagio", Some("Piano Sonata No. 8"))
+                .1
+                .as_deref(),
+            Some("Adagio")
+        );
+        assert_eq!(split_title("Boléro", Some("Boléro")).1, None);
+    }
+
+    #[test]
+    fn test_split_title_without_work() {
+        assert_eq!(
+            split_title(
+                "Cello Suite No. 1 in G Major, BWV 1007: IV. Sarabande",
+                None
+            ),
+            (
+                Some("Cello Suite No. 1 in G Major, BWV 1007".to_string()),
+                Some("IV. Sarabande".to_string())
+            )
+        );
+        assert_eq!(split_title("Live: In Concert", None), (None, None));
+        assert_eq!(split_title("Gershwin: I Got Rhythm", None), (None, None));
+        assert_eq!(split_title("So What", None), (None, None));
+    }
+
+    #[test]
+    fn test_apply_classical_fields_groups_by_work() {
+        let mut album: Album = serde_json::from_value(serde_json::json!({
+            "id": "a1",
+            "composer": { "id": 7, "name": "Ludwig van Beethoven" },
+            "tracks": { "total": 4, "items": [] },
+        }))
+        .unwrap();
+        album.tracks.as_mut().unwrap().items = vec![
+            track(
+                1,
+                "Symphony No. 5: I. Allegro con brio",
+                Some("Symphony No. 5"),
+            ),
+            track(
+                2,
+                "Symphony No. 5: II. Andante con moto",
+                Some("Symphony No. 5"),
+            ),
+            track(3, "Egmont, Op. 84: Overture", None),
+            track(4, "Coriolan Overture", None),
+        ];
+
+        apply_classical_fields(&mut album);
+
+        let tracks = &album.tracks.as_ref().unwrap().items;
+        assert_eq!(tracks[1].movement.as_deref(), Some("II. Andante con moto"));
+        assert_eq!(
+            tracks[0].composer.as_ref().map(|c| c.name.as_str()),
+            Some("Ludwig van Beethoven")
+        );
+        assert!(tracks[3].composer.is_none());
+        assert_eq!(
+            album.works,
+            Some(vec![WorkGroup {
+                work: "Symphony No. 5".to_string(),
+                composer: Some("Ludwig van Beethoven".to_string()),
+                track_ids: vec![1, 2],
+            }])
+        );
+    }
+
+    #[test]
+    fn test_non_consecutive_tracks_start_a_new_group() {
+        let tracks = vec![
+            track(1, "A", Some("Work")),
+            track(2, "Interlude", None),
+            track(3, "B", Some("Work")),
+        ];
+        let groups = group_by_work(&tracks);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].track_ids, vec![3]);
+    }
+}
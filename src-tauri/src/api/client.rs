@@ -1,18 +1,32 @@
 //! Qobuz API client implementation
 
-use reqwest::{Client, StatusCode};
+use futures_util::future::BoxFuture;
+use reqwest::header::HeaderValue;
+use reqwest::{Client, Request, RequestBuilder, Response, StatusCode};
 use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 use super::auth::{get_timestamp, parse_login_response, sign_get_favorites, sign_get_file_url};
 use super::bundle::{extract_bundle_tokens, BundleTokens};
 use super::endpoints::{self, paths};
 use super::error::{ApiError, Result};
 use super::models::*;
+use super::request_policy::{is_transient_error, is_transient_status, RequestPolicy};
 
 const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:120.0) Gecko/20100101 Firefox/120.0";
 
+const AUTH_TOKEN_HEADER: &str = "X-User-Auth-Token";
+
+/// Called once when the session token is rejected and can't be renewed
+pub type AuthExpiredHandler = Arc<dyn Fn() + Send + Sync>;
+
+/// Logs in again after Qobuz rejected the session token. `Ok(None)` means
+/// there is nothing to log in with.
+type SessionRenewer =
+    Arc<dyn Fn(QobuzClient) -> BoxFuture<'static, Result<Option<UserSession>>> + Send + Sync>;
+
 /// Qobuz API client
 pub struct QobuzClient {
     http: Client,
@@ -20,6 +34,13 @@ pub struct QobuzClient {
     session: Arc<RwLock<Option<UserSession>>>,
     validated_secret: Arc<RwLock<Option<String>>>,
     locale: Arc<RwLock<String>>,
+    /// Serializes silent re-logins, so a burst of rejected requests logs in once
+    refresh_lock: Arc<Mutex<()>>,
+    /// Set when the token was rejected and the silent re-login failed
+    session_expired: Arc<AtomicBool>,
+    on_auth_expired: Arc<std::sync::RwLock<Option<AuthExpiredHandler>>>,
+    renew_session: SessionRenewer,
+    request_policy: Arc<std::sync::RwLock<RequestPolicy>>,
 }
 
 impl Clone for QobuzClient {
@@ -30,6 +51,11 @@ impl Clone for QobuzClient {
             session: Arc::clone(&self.session),
             validated_secret: Arc::clone(&self.validated_secret),
             locale: Arc::clone(&self.locale),
+            refresh_lock: Arc::clone(&self.refresh_lock),
+            session_expired: Arc::clone(&self.session_expired),
+            on_auth_expired: Arc::clone(&self.on_auth_expired),
+            renew_session: Arc::clone(&self.renew_session),
+            request_policy: Arc::clone(&self.request_policy),
         }
     }
 }
@@ -37,20 +63,41 @@ impl Clone for QobuzClient {
 impl QobuzClient {
     /// Create a new client
     pub fn new() -> Result<Self> {
-        let http = Client::builder()
-            .user_agent(USER_AGENT)
-            .cookie_store(true)
-            .build()?;
-
+        let request_policy = RequestPolicy::default();
         Ok(Self {
-            http,
+            http: build_http(&request_policy)?,
             tokens: Arc::new(RwLock::new(None)),
             session: Arc::new(RwLock::new(None)),
             validated_secret: Arc::new(RwLock::new(None)),
             locale: Arc::new(RwLock::new("en".to_string())),
+            refresh_lock: Arc::new(Mutex::new(())),
+            session_expired: Arc::new(AtomicBool::new(false)),
+            on_auth_expired: Arc::new(std::sync::RwLock::new(None)),
+            renew_session: Arc::new(|client| Box::pin(login_with_saved_credentials(client))),
+            request_policy: Arc::new(std::sync::RwLock::new(request_policy)),
         })
     }
 
+    /// Current timeouts and retries for API requests
+    pub fn request_policy(&self) -> RequestPolicy {
+        self.request_policy
+            .read()
+            .map(|policy| policy.clone())
+            .unwrap_or_default()
+    }
+
+    /// Change timeouts and retries for API requests. A new connect timeout
+    /// rebuilds the HTTP client.
+    pub fn set_request_policy(&mut self, policy: RequestPolicy) -> Result<()> {
+        if policy.connect_timeout_secs != self.request_policy().connect_timeout_secs {
+            self.http = build_http(&policy)?;
+        }
+        if let Ok(mut current) = self.request_policy.write() {
+            *current = policy;
+        }
+        Ok(())
+    }
+
     /// Initialize client by extracting bundle tokens
     pub async fn init(&self) -> Result<()> {
         let tokens = extract_bundle_tokens(&self.http).await?;
@@ -151,6 +198,7 @@ impl QobuzClient {
                 let json: Value = response.json().await?;
                 let session = parse_login_response(&json)?;
                 *self.session.write().await = Some(session.clone());
+                self.session_expired.store(false, Ordering::SeqCst);
                 Ok(session)
             }
             StatusCode::UNAUTHORIZED => {
@@ -161,14 +209,163 @@ impl QobuzClient {
         }
     }
 
-    /// Check if logged in
+    /// Check if logged in with a token Qobuz still accepts (as far as the
+    /// last request could tell)
     pub async fn is_logged_in(&self) -> bool {
-        self.session.read().await.is_some()
+        self.session.read().await.is_some() && !self.session_expired.load(Ordering::SeqCst)
     }
 
     /// Logout - clear the session
     pub async fn logout(&self) {
         *self.session.write().await = None;
+        self.session_expired.store(false, Ordering::SeqCst);
+    }
+
+    /// Set the callback run when the session expires for good
+    pub fn set_auth_expired_handler(&self, handler: AuthExpiredHandler) {
+        if let Ok(mut guard) = self.on_auth_expired.write() {
+            *guard = Some(handler);
+        }
+    }
+
+    /// Send a request (public for catalog search).
+    ///
+    /// When Qobuz rejects the session token (401), logs in again with the
+    /// saved credentials and retries the request once. Without saved
+    /// credentials, or when that login fails, the session is marked expired
+    /// and the original response is returned.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let policy = self.request_policy();
+        let mut request = request.build()?;
+        if let Some(timeout) = policy.total_timeout() {
+            *request.timeout_mut() = Some(timeout);
+        }
+        let sent_token = request
+            .headers()
+            .get(AUTH_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let retry = request.try_clone();
+
+        let response = self.execute(request, &policy).await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let (Some(sent_token), Some(mut retry)) = (sent_token, retry) else {
+            return Ok(response);
+        };
+        let Some(token) = self.refresh_session(&sent_token).await else {
+            return Ok(response);
+        };
+
+        let token = HeaderValue::from_str(&token)
+            .map_err(|_| ApiError::AuthenticationError("Invalid auth token format".into()))?;
+        retry.headers_mut().insert(AUTH_TOKEN_HEADER, token);
+        log::info!("[API] Retrying {} with the refreshed session", retry.url().path());
+        self.execute(retry, &policy).await
+    }
+
+    /// Execute a request, retrying transient failures as the policy allows
+    async fn execute(&self, mut request: Request, policy: &RequestPolicy) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            // Bodies that can't be cloned (streams) get a single attempt
+            let next = if attempt < policy.retries {
+                request.try_clone()
+            } else {
+                None
+            };
+            let path = request.url().path().to_string();
+            let result = self.http.execute(request).await;
+            let Some(next) = next else {
+                return Ok(result?);
+            };
+            match &result {
+                Ok(response) if is_transient_status(response.status()) => {
+                    log::warn!("[API] {} returned {}, retrying", path, response.status());
+                }
+                Err(e) if is_transient_error(e) => {
+                    log::warn!("[API] {} failed ({}), retrying", path, e);
+                }
+                _ => return Ok(result?),
+            }
+            tokio::time::sleep(policy.backoff(attempt, rand::random::<f64>())).await;
+            attempt += 1;
+            request = next;
+        }
+    }
+
+    /// Send a request and turn a non-success response into a typed
+    /// [`ApiError`] (auth, rate limit, not found or a Qobuz error code).
+    pub async fn send_checked(&self, request: RequestBuilder) -> Result<Response> {
+        let response = self.send(request).await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let path = response.url().path().to_string();
+        let retry_after =
+            (status == StatusCode::TOO_MANY_REQUESTS).then(|| retry_after_secs(&response));
+        let body = response.text().await.unwrap_or_default();
+        let error = ApiError::from_response(status, &body, retry_after);
+        log::warn!("[API] {} failed: {}", path, error);
+        Err(error)
+    }
+
+    /// Log in again after `stale_token` was rejected. Returns the token to
+    /// retry with, or None when the session can't be renewed right now.
+    async fn refresh_session(&self, stale_token: &str) -> Option<String> {
+        let _guard = self.refresh_lock.lock().await;
+
+        // Already failed: wait for the user to log in instead of retrying
+        // on every request
+        if self.session_expired.load(Ordering::SeqCst) {
+            return None;
+        }
+        // Another request renewed the session while we waited
+        match self.auth_token().await {
+            Ok(current) if current != stale_token => return Some(current),
+            Ok(_) => {}
+            // Logged out meanwhile
+            Err(_) => return None,
+        }
+
+        log::info!("[API] Session token rejected, logging in again");
+        match (self.renew_session)(self.clone()).await {
+            Ok(Some(session)) => {
+                log::info!("[API] Session renewed");
+                let token = session.user_auth_token.clone();
+                *self.session.write().await = Some(session);
+                self.session_expired.store(false, Ordering::SeqCst);
+                Some(token)
+            }
+            Ok(None) => {
+                log::warn!("[API] Session expired and no saved credentials to renew it");
+                self.mark_session_expired();
+                None
+            }
+            // Offline for now; the next rejected request tries again
+            Err(ApiError::NetworkError(e)) => {
+                log::warn!("[API] Session renewal failed, network error: {}", e);
+                None
+            }
+            Err(e) => {
+                log::warn!("[API] Session renewal failed: {}", e);
+                self.mark_session_expired();
+                None
+            }
+        }
+    }
+
+    fn mark_session_expired(&self) {
+        if self.session_expired.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let handler = self.on_auth_expired.read().ok().and_then(|guard| guard.clone());
+        if let Some(handler) = handler {
+            handler();
+        }
     }
 
     /// Get current user info (display name, subscription, and expiry if available)
@@ -182,6 +379,11 @@ impl QobuzClient {
         })
     }
 
+    /// Get the account's catalogue zone, if the login response had one
+    pub async fn get_zone(&self) -> Option<String> {
+        self.session.read().await.as_ref().and_then(|s| s.zone.clone())
+    }
+
     /// Get current user ID
     pub async fn get_user_id(&self) -> Option<u64> {
         self.session.read().await.as_ref().map(|s| s.user_id)
@@ -202,7 +404,7 @@ impl QobuzClient {
     /// Build standard API headers.
     /// Always includes X-App-Id. Includes X-User-Auth-Token when logged in.
     async fn api_headers(&self) -> Result<reqwest::header::HeaderMap> {
-        use reqwest::header::{HeaderMap, HeaderValue};
+        use reqwest::header::HeaderMap;
         let mut headers = HeaderMap::new();
 
         let app_id = self.app_id().await?;
@@ -213,7 +415,7 @@ impl QobuzClient {
 
         if let Ok(token) = self.auth_token().await {
             if let Ok(val) = HeaderValue::from_str(&token) {
-                headers.insert("X-User-Auth-Token", val);
+                headers.insert(AUTH_TOKEN_HEADER, val);
             }
         }
 
@@ -222,7 +424,7 @@ impl QobuzClient {
 
     /// Build headers that REQUIRE authentication. Fails if not logged in.
     async fn authenticated_headers(&self) -> Result<reqwest::header::HeaderMap> {
-        use reqwest::header::{HeaderMap, HeaderValue};
+        use reqwest::header::HeaderMap;
         let mut headers = HeaderMap::new();
 
         let app_id = self.app_id().await?;
@@ -233,7 +435,7 @@ impl QobuzClient {
 
         let token = self.auth_token().await?;
         headers.insert(
-            "X-User-Auth-Token",
+            AUTH_TOKEN_HEADER,
             HeaderValue::from_str(&token)
                 .map_err(|_| ApiError::AuthenticationError("Invalid auth token format".into()))?,
         );
@@ -267,11 +469,12 @@ impl QobuzClient {
         }
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&params)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&params),
+            )
             .await?;
         log::debug!("[API] search_albums status={}", http_response.status());
         let response: Value = http_response.json().await?;
@@ -307,11 +510,12 @@ impl QobuzClient {
         }
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&params)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&params),
+            )
             .await?;
         log::debug!("[API] search_tracks status={}", http_response.status());
         let response: Value = http_response.json().await?;
@@ -347,11 +551,12 @@ impl QobuzClient {
         }
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&params)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&params),
+            )
             .await?;
         log::debug!("[API] search_artists status={}", http_response.status());
         let response: Value = http_response.json().await?;
@@ -367,15 +572,16 @@ impl QobuzClient {
     pub async fn get_similar_artists(&self, artist_id: u64, limit: u32, offset: u32) -> Result<SearchResultsPage<Artist>> {
         let url = endpoints::build_url(paths::ARTIST_GET_SIMILAR);
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&[
-                ("artist_id", artist_id.to_string()),
-                ("limit", limit.to_string()),
-                ("offset", offset.to_string()),
-            ])
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&[
+                        ("artist_id", artist_id.to_string()),
+                        ("limit", limit.to_string()),
+                        ("offset", offset.to_string()),
+                    ]),
+            )
             .await?;
         log::debug!("[API] get_similar_artists({}) status={}", artist_id, http_response.status());
         let response: Value = http_response.json().await?;
@@ -393,17 +599,18 @@ impl QobuzClient {
         let locale = self.locale().await;
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&[
-                ("artist_id", artist_id.to_string()),
-                ("extra", "tracks".to_string()),
-                ("lang", locale),
-                ("limit", limit.to_string()),
-                ("offset", offset.to_string()),
-            ])
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&[
+                        ("artist_id", artist_id.to_string()),
+                        ("extra", "tracks".to_string()),
+                        ("lang", locale),
+                        ("limit", limit.to_string()),
+                        ("offset", offset.to_string()),
+                    ]),
+            )
             .await?;
         log::debug!("[API] get_artist_tracks({}) status={}", artist_id, http_response.status());
         let response: Value = http_response.json().await?;
@@ -421,18 +628,19 @@ impl QobuzClient {
     pub async fn get_album(&self, album_id: &str) -> Result<Album> {
         let url = endpoints::build_url(paths::ALBUM_GET);
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&[("album_id", album_id)])
-            .send()
+            .send(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&[("album_id", album_id)]),
+            )
             .await?;
         let status = http_response.status();
         log::debug!("[API] get_album({}) status={}", album_id, status);
 
         if status == StatusCode::NOT_FOUND {
             log::warn!("[API] get_album({}) returned 404 — album not found", album_id);
-            return Err(ApiError::ApiResponse(format!("Album {} not found (404)", album_id)));
+            return Err(ApiError::NotFound(format!("album {}", album_id)));
         }
         if !status.is_success() {
             log::error!("[API] get_album({}) unexpected status={}", album_id, status);
@@ -463,11 +671,12 @@ impl QobuzClient {
         }
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&query),
+            )
             .await?;
         log::debug!("[API] get_featured_albums({}) status={}", featured_type, http_response.status());
         let response: Value = http_response.json().await?;
@@ -492,11 +701,12 @@ impl QobuzClient {
         }
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&query),
+            )
             .await?;
         log::debug!("[API] get_genres(parent={:?}) status={}", parent_id, http_response.status());
         let response: Value = http_response.json().await?;
@@ -526,11 +736,12 @@ impl QobuzClient {
         }
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&query),
+            )
             .await?;
         log::info!("[API] get_discover_index status={}", http_response.status());
         let response: Value = http_response.json().await?;
@@ -571,11 +782,12 @@ impl QobuzClient {
         query.push(("limit", limit.to_string()));
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&query),
+            )
             .await?;
         log::info!("[API] get_discover_albums({}) status={}", endpoint, http_response.status());
         let response: serde_json::Value = http_response.json().await?;
@@ -620,11 +832,12 @@ impl QobuzClient {
 
         // First get raw JSON to debug structure
         let raw_response: serde_json::Value = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&query),
+            )
             .await?
             .json()
             .await?;
@@ -649,10 +862,11 @@ impl QobuzClient {
         let url = endpoints::build_url(paths::PLAYLIST_GET_TAGS);
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?),
+            )
             .await?;
         log::info!("[API] get_playlist_tags status={}", http_response.status());
 
@@ -692,11 +906,12 @@ impl QobuzClient {
     pub async fn get_track(&self, track_id: u64) -> Result<Track> {
         let url = endpoints::build_url(paths::TRACK_GET);
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&[("track_id", track_id.to_string())])
-            .send()
+            .send(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&[("track_id", track_id.to_string())]),
+            )
             .await?;
         let status = http_response.status();
         log::debug!("[API] get_track({}) status={}", track_id, status);
@@ -725,11 +940,12 @@ impl QobuzClient {
         ];
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&query),
+            )
             .await?;
         log::debug!("[API] get_artist_basic({}) status={}", artist_id, http_response.status());
         let response: Value = http_response.json().await?;
@@ -768,11 +984,12 @@ impl QobuzClient {
         }
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&query),
+            )
             .await?;
         log::debug!("[API] get_artist_detail({}) status={}", artist_id, http_response.status());
         let response: Value = http_response.json().await?;
@@ -821,11 +1038,12 @@ impl QobuzClient {
         }
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&query),
+            )
             .await?;
         log::debug!("[API] get_artist({}, albums={}) status={}", artist_id, with_albums, http_response.status());
         let response: Value = http_response.json().await?;
@@ -839,14 +1057,15 @@ impl QobuzClient {
     pub async fn get_playlist_track_ids(&self, playlist_id: u64) -> Result<PlaylistWithTrackIds> {
         let url = endpoints::build_url(paths::PLAYLIST_GET);
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&[
-                ("playlist_id", playlist_id.to_string()),
-                ("extra", "track_ids".to_string()),
-            ])
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&[
+                        ("playlist_id", playlist_id.to_string()),
+                        ("extra", "track_ids".to_string()),
+                    ]),
+            )
             .await?;
         log::debug!("[API] get_playlist_track_ids({}) status={}", playlist_id, http_response.status());
         let response: Value = http_response.json().await?;
@@ -873,11 +1092,12 @@ impl QobuzClient {
         log::debug!("[API] get_tracks_batch POST ({} IDs)", track_ids.len());
 
         let http_response = self
-            .http
-            .post(&url)
-            .headers(headers)
-            .json(&body)
-            .send()
+            .send_checked(
+                self.http
+                    .post(&url)
+                    .headers(headers)
+                    .json(&body),
+            )
             .await?;
 
         let status = http_response.status();
@@ -918,16 +1138,17 @@ impl QobuzClient {
 
         // First page — gives us metadata + total track count
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&[
-                ("playlist_id", playlist_id.to_string()),
-                ("limit", PAGE_SIZE.to_string()),
-                ("offset", "0".to_string()),
-                ("extra", "tracks".to_string()),
-            ])
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&[
+                        ("playlist_id", playlist_id.to_string()),
+                        ("limit", PAGE_SIZE.to_string()),
+                        ("offset", "0".to_string()),
+                        ("extra", "tracks".to_string()),
+                    ]),
+            )
             .await?;
         log::debug!("[API] get_playlist({}) status={}", playlist_id, http_response.status());
         let response: Value = http_response.json().await?;
@@ -1018,17 +1239,18 @@ impl QobuzClient {
         let locale = self.locale().await;
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&[
-                ("label_id", label_id.to_string()),
-                ("extra", "albums".to_string()),
-                ("limit", limit.to_string()),
-                ("offset", offset.to_string()),
-                ("lang", locale),
-            ])
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&[
+                        ("label_id", label_id.to_string()),
+                        ("extra", "albums".to_string()),
+                        ("limit", limit.to_string()),
+                        ("offset", offset.to_string()),
+                        ("lang", locale),
+                    ]),
+            )
             .await?;
         log::debug!("[API] get_label({}) status={}", label_id, http_response.status());
         let response: Value = http_response.json().await?;
@@ -1050,17 +1272,18 @@ impl QobuzClient {
 
         log::debug!("Sending stream URL request...");
         let response = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&[
-                ("track_id", track_id.to_string()),
-                ("format_id", quality.id().to_string()),
-                ("intent", "stream".to_string()),
-                ("request_ts", timestamp.to_string()),
-                ("request_sig", signature),
-            ])
-            .send()
+            .send(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&[
+                        ("track_id", track_id.to_string()),
+                        ("format_id", quality.id().to_string()),
+                        ("intent", "stream".to_string()),
+                        ("request_ts", timestamp.to_string()),
+                        ("request_sig", signature),
+                    ]),
+            )
             .await?;
 
         log::info!("Stream URL response status: {}", response.status());
@@ -1084,6 +1307,7 @@ impl QobuzClient {
                     return Err(ApiError::TrackUnavailable(track_id));
                 }
 
+                let expires_at = StreamUrl::expiry_of(&url, timestamp);
                 Ok(StreamUrl {
                     url,
                     format_id: json["format_id"].as_u64().unwrap_or(0) as u32,
@@ -1092,6 +1316,7 @@ impl QobuzClient {
                     bit_depth: json["bit_depth"].as_u64().map(|v| v as u32),
                     track_id,
                     restrictions,
+                    expires_at,
                 })
             }
             StatusCode::BAD_REQUEST => Err(ApiError::InvalidAppSecret),
@@ -1105,13 +1330,24 @@ impl QobuzClient {
         track_id: u64,
         preferred: Quality,
     ) -> Result<StreamUrl> {
-        log::info!("Getting stream URL with fallback for track {}, preferred quality: {:?}", track_id, preferred);
         let qualities = Quality::fallback_order();
         let start_idx = qualities.iter().position(|q| *q == preferred).unwrap_or(0);
+        self.get_stream_url_with_chain(track_id, &qualities[start_idx..])
+            .await
+    }
+
+    /// Get stream URL trying each tier of `chain` in order. The tier actually
+    /// served is `StreamUrl::achieved_quality`.
+    pub async fn get_stream_url_with_chain(
+        &self,
+        track_id: u64,
+        chain: &[Quality],
+    ) -> Result<StreamUrl> {
+        log::info!("Getting stream URL with fallback for track {}, quality chain: {:?}", track_id, chain);
 
         let mut track_unavailable = false;
 
-        for quality in &qualities[start_idx..] {
+        for quality in chain {
             log::info!("Trying quality: {:?}", quality);
             match self.get_stream_url(track_id, *quality).await {
                 Ok(url) if !url.has_restrictions() => {
@@ -1162,17 +1398,18 @@ impl QobuzClient {
         let signature = sign_get_favorites(timestamp, &secret);
 
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&[
-                ("type", fav_type),
-                ("limit", &limit.to_string()),
-                ("offset", &offset.to_string()),
-                ("request_ts", &timestamp.to_string()),
-                ("request_sig", &signature),
-            ])
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&[
+                        ("type", fav_type),
+                        ("limit", &limit.to_string()),
+                        ("offset", &offset.to_string()),
+                        ("request_ts", &timestamp.to_string()),
+                        ("request_sig", &signature),
+                    ]),
+            )
             .await?;
         log::debug!("[API] get_favorites({}) status={}", fav_type, http_response.status());
         let response: Value = http_response.json().await?;
@@ -1184,10 +1421,11 @@ impl QobuzClient {
     pub async fn get_user_playlists(&self) -> Result<Vec<Playlist>> {
         let url = endpoints::build_url(paths::PLAYLIST_GET_USER_PLAYLISTS);
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?),
+            )
             .await?;
         log::debug!("[API] get_user_playlists status={}", http_response.status());
         let response: Value = http_response.json().await?;
@@ -1204,15 +1442,16 @@ impl QobuzClient {
     pub async fn search_playlists(&self, query: &str, limit: u32, offset: u32) -> Result<SearchResultsPage<Playlist>> {
         let url = endpoints::build_url(paths::PLAYLIST_SEARCH);
         let http_response = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&[
-                ("query", query),
-                ("limit", &limit.to_string()),
-                ("offset", &offset.to_string()),
-            ])
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&[
+                        ("query", query),
+                        ("limit", &limit.to_string()),
+                        ("offset", &offset.to_string()),
+                    ]),
+            )
             .await?;
         log::debug!("[API] search_playlists status={}", http_response.status());
         let response: Value = http_response.json().await?;
@@ -1237,11 +1476,12 @@ impl QobuzClient {
         }
 
         let response: Playlist = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&params)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&params),
+            )
             .await?
             .json()
             .await?;
@@ -1253,12 +1493,13 @@ impl QobuzClient {
     pub async fn delete_playlist(&self, playlist_id: u64) -> Result<()> {
         let url = endpoints::build_url(paths::PLAYLIST_DELETE);
 
-        self.http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&[("playlist_id", playlist_id.to_string())])
-            .send()
-            .await?;
+        self.send_checked(
+            self.http
+                .get(&url)
+                .headers(self.authenticated_headers().await?)
+                .query(&[("playlist_id", playlist_id.to_string())]),
+        )
+        .await?;
 
         Ok(())
     }
@@ -1268,15 +1509,16 @@ impl QobuzClient {
         let url = endpoints::build_url(paths::PLAYLIST_ADD_TRACKS);
         let track_ids_str = track_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
 
-        self.http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&[
-                ("playlist_id", playlist_id.to_string()),
-                ("track_ids", track_ids_str),
-            ])
-            .send()
-            .await?;
+        self.send_checked(
+            self.http
+                .get(&url)
+                .headers(self.authenticated_headers().await?)
+                .query(&[
+                    ("playlist_id", playlist_id.to_string()),
+                    ("track_ids", track_ids_str),
+                ]),
+        )
+        .await?;
 
         Ok(())
     }
@@ -1286,15 +1528,16 @@ impl QobuzClient {
         let url = endpoints::build_url(paths::PLAYLIST_DELETE_TRACKS);
         let track_ids_str = playlist_track_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
 
-        self.http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&[
-                ("playlist_id", playlist_id.to_string()),
-                ("playlist_track_ids", track_ids_str),
-            ])
-            .send()
-            .await?;
+        self.send_checked(
+            self.http
+                .get(&url)
+                .headers(self.authenticated_headers().await?)
+                .query(&[
+                    ("playlist_id", playlist_id.to_string()),
+                    ("playlist_track_ids", track_ids_str),
+                ]),
+        )
+        .await?;
 
         Ok(())
     }
@@ -1315,11 +1558,12 @@ impl QobuzClient {
         }
 
         let response: Playlist = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&params)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&params),
+            )
             .await?
             .json()
             .await?;
@@ -1333,15 +1577,18 @@ impl QobuzClient {
         let type_key = format!("{}_ids", fav_type); // album_ids, track_ids, artist_ids
 
         let response = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&[(&type_key, item_id)])
-            .send()
+            .send(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&[(&type_key, item_id)]),
+            )
             .await?;
 
         if response.status().is_success() {
             Ok(())
+        } else if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            Err(ApiError::RateLimited(retry_after_secs(&response)))
         } else {
             Err(ApiError::ApiResponse(format!("Failed to add favorite: {}", response.status())))
         }
@@ -1353,15 +1600,18 @@ impl QobuzClient {
         let type_key = format!("{}_ids", fav_type);
 
         let response = self
-            .http
-            .get(&url)
-            .headers(self.authenticated_headers().await?)
-            .query(&[(&type_key, item_id)])
-            .send()
+            .send(
+                self.http
+                    .get(&url)
+                    .headers(self.authenticated_headers().await?)
+                    .query(&[(&type_key, item_id)]),
+            )
             .await?;
 
         if response.status().is_success() {
             Ok(())
+        } else if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            Err(ApiError::RateLimited(retry_after_secs(&response)))
         } else {
             Err(ApiError::ApiResponse(format!("Failed to remove favorite: {}", response.status())))
         }
@@ -1385,11 +1635,12 @@ impl QobuzClient {
 
         log::debug!("[API] get_artist_page({}) sort={:?}", artist_id, sort);
         let response: serde_json::Value = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&query),
+            )
             .await?
             .json()
             .await?;
@@ -1419,11 +1670,12 @@ impl QobuzClient {
 
         log::debug!("[API] get_releases_grid({}) type={} limit={} offset={}", artist_id, release_type, limit, offset);
         let response: serde_json::Value = self
-            .http
-            .get(&url)
-            .headers(self.api_headers().await?)
-            .query(&query)
-            .send()
+            .send_checked(
+                self.http
+                    .get(&url)
+                    .headers(self.api_headers().await?)
+                    .query(&query),
+            )
             .await?
             .json()
             .await?;
@@ -1432,8 +1684,222 @@ impl QobuzClient {
     }
 }
 
+/// Seconds to wait according to a 429 response's Retry-After header
+fn retry_after_secs(response: &Response) -> u64 {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(1)
+}
+
+fn build_http(policy: &RequestPolicy) -> Result<Client> {
+    let mut builder = crate::network::http_client::builder(USER_AGENT).cookie_store(true);
+    if let Some(timeout) = policy.connect_timeout() {
+        builder = builder.connect_timeout(timeout);
+    }
+    Ok(builder.build()?)
+}
+
+/// Default [`SessionRenewer`]: log in with the credentials saved by
+/// "remember me"
+async fn login_with_saved_credentials(client: QobuzClient) -> Result<Option<UserSession>> {
+    let creds = crate::credentials::load_qobuz_credentials().map_err(|e| {
+        ApiError::AuthenticationError(format!("Failed to load saved credentials: {}", e))
+    })?;
+    match creds {
+        Some(creds) => client.login(&creds.email, &creds.password).await.map(Some),
+        None => Ok(None),
+    }
+}
+
 impl Default for QobuzClient {
     fn default() -> Self {
         Self::new().expect("Failed to create client")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn session(token: &str) -> UserSession {
+        UserSession {
+            user_auth_token: token.to_string(),
+            user_id: 1,
+            email: "listener@example.com".to_string(),
+            display_name: "Listener".to_string(),
+            subscription_label: "Studio".to_string(),
+            subscription_valid_until: None,
+            zone: None,
+        }
+    }
+
+    type Respond = fn(&str, usize) -> (&'static str, &'static str);
+
+    /// Local HTTP server answering each request with `respond(request, n)`,
+    /// where `n` counts earlier requests. Returns the URL to call and the
+    /// request counter.
+    async fn mock_server(respond: Respond) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let len = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..len]).to_lowercase();
+                    let (status, body) = respond(&request, n);
+                    let response = format!(
+                        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (format!("http://{}/track/get", addr), hits)
+    }
+
+    /// Answers 200 to requests carrying the "fresh" token, 401 to the rest
+    async fn mock_qobuz() -> String {
+        let (url, _) = mock_server(|request, _| {
+            if request.contains("x-user-auth-token: fresh") {
+                ("200 OK", r#"{"ok":true}"#)
+            } else {
+                (
+                    "401 Unauthorized",
+                    r#"{"status":"error","code":401,"message":"User authentication is required."}"#,
+                )
+            }
+        })
+        .await;
+        url
+    }
+
+    fn client_with_retries(retries: u32) -> QobuzClient {
+        let mut client = QobuzClient::new().unwrap();
+        client
+            .set_request_policy(RequestPolicy {
+                retries,
+                backoff_ms: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        client
+    }
+
+    fn client_with_renewer(
+        stale_token: &str,
+        renewed: Option<&'static str>,
+        logins: Arc<AtomicUsize>,
+    ) -> QobuzClient {
+        let mut client = QobuzClient::new().unwrap();
+        *client.session.try_write().unwrap() = Some(session(stale_token));
+        client.renew_session = Arc::new(move |_| {
+            let logins = Arc::clone(&logins);
+            Box::pin(async move {
+                logins.fetch_add(1, Ordering::SeqCst);
+                match renewed {
+                    Some(token) => Ok(Some(session(token))),
+                    None => Err(ApiError::AuthenticationError(
+                        "Invalid credentials".to_string(),
+                    )),
+                }
+            })
+        });
+        client
+    }
+
+    #[tokio::test]
+    async fn rejected_token_is_renewed_once_and_requests_retried() {
+        let url = mock_qobuz().await;
+        let logins = Arc::new(AtomicUsize::new(0));
+        let client = client_with_renewer("stale", Some("fresh"), Arc::clone(&logins));
+
+        let requests = (0..4).map(|_| {
+            let client = client.clone();
+            let url = url.clone();
+            async move {
+                let request = client.http.get(&url).header(AUTH_TOKEN_HEADER, "stale");
+                client
+                    .send_checked(request)
+                    .await
+                    .map(|response| response.status())
+            }
+        });
+        for status in futures_util::future::join_all(requests).await {
+            assert_eq!(status.unwrap(), StatusCode::OK);
+        }
+        assert_eq!(logins.load(Ordering::SeqCst), 1);
+        assert_eq!(client.auth_token().await.unwrap(), "fresh");
+        assert!(client.is_logged_in().await);
+    }
+
+    #[tokio::test]
+    async fn failed_renewal_expires_the_session_once() {
+        let url = mock_qobuz().await;
+        let logins = Arc::new(AtomicUsize::new(0));
+        let expired = Arc::new(AtomicUsize::new(0));
+        let client = client_with_renewer("stale", None, Arc::clone(&logins));
+        let handler_count = Arc::clone(&expired);
+        client.set_auth_expired_handler(Arc::new(move || {
+            handler_count.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        for _ in 0..2 {
+            let request = client.http.get(&url).header(AUTH_TOKEN_HEADER, "stale");
+            let result = client.send_checked(request).await;
+            assert!(matches!(result, Err(ApiError::AuthenticationError(_))));
+        }
+        // The second rejection doesn't try to log in again
+        assert_eq!(logins.load(Ordering::SeqCst), 1);
+        assert_eq!(expired.load(Ordering::SeqCst), 1);
+        assert!(!client.is_logged_in().await);
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried() {
+        let (url, hits) = mock_server(|_, n| {
+            if n < 2 {
+                ("503 Service Unavailable", "")
+            } else {
+                ("200 OK", r#"{"ok":true}"#)
+            }
+        })
+        .await;
+        let client = client_with_retries(3);
+
+        let response = client.send_checked(client.http.get(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let (url, hits) = mock_server(|_, _| {
+            (
+                "404 Not Found",
+                r#"{"status":"error","code":404,"message":"No result matching given argument"}"#,
+            )
+        })
+        .await;
+        let client = client_with_retries(3);
+
+        let result = client.send_checked(client.http.get(&url)).await;
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,5 +1,7 @@
 //! API error types
 
+use reqwest::StatusCode;
+use serde_json::Value;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -42,6 +44,97 @@ pub enum ApiError {
 
     #[error("Rate limited, retry after {0} seconds")]
     RateLimited(u64),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("API error {code}: {message}")]
+    Api { code: u16, message: String },
+}
+
+impl ApiError {
+    /// Classify a non-success Qobuz response.
+    ///
+    /// Qobuz error bodies look like `{"status":"error","code":404,"message":"..."}`;
+    /// the body's code and message are used when present, the HTTP status
+    /// otherwise. `retry_after` is the parsed `Retry-After` header, if any.
+    pub fn from_response(status: StatusCode, body: &str, retry_after: Option<u64>) -> Self {
+        let json: Option<Value> = serde_json::from_str(body).ok();
+        let field = |key: &str| json.as_ref().and_then(|value| value.get(key).cloned());
+
+        let code = field("code")
+            .and_then(|code| code.as_u64().or_else(|| code.as_str()?.parse().ok()))
+            .and_then(|code| u16::try_from(code).ok())
+            .unwrap_or(status.as_u16());
+        let message = field("message")
+            .and_then(|message| message.as_str().map(str::to_string))
+            .filter(|message| !message.is_empty())
+            .unwrap_or_else(|| {
+                status
+                    .canonical_reason()
+                    .unwrap_or("Unexpected response")
+                    .to_string()
+            });
+
+        match status {
+            StatusCode::UNAUTHORIZED => ApiError::AuthenticationError(message),
+            StatusCode::TOO_MANY_REQUESTS => ApiError::RateLimited(retry_after.unwrap_or(1)),
+            StatusCode::NOT_FOUND => ApiError::NotFound(message),
+            _ => ApiError::Api { code, message },
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, ApiError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_map_to_typed_errors() {
+        let auth = ApiError::from_response(
+            StatusCode::UNAUTHORIZED,
+            r#"{"status":"error","code":401,"message":"User authentication is required."}"#,
+            None,
+        );
+        assert!(
+            matches!(&auth, ApiError::AuthenticationError(m) if m == "User authentication is required.")
+        );
+        assert_eq!(
+            auth.to_string(),
+            "Authentication failed: User authentication is required."
+        );
+
+        let limited = ApiError::from_response(StatusCode::TOO_MANY_REQUESTS, "", Some(30));
+        assert!(matches!(limited, ApiError::RateLimited(30)));
+        assert!(matches!(
+            ApiError::from_response(StatusCode::TOO_MANY_REQUESTS, "", None),
+            ApiError::RateLimited(1)
+        ));
+
+        let missing = ApiError::from_response(
+            StatusCode::NOT_FOUND,
+            r#"{"status":"error","code":404,"message":"No result matching given argument"}"#,
+            None,
+        );
+        assert!(
+            matches!(&missing, ApiError::NotFound(m) if m == "No result matching given argument")
+        );
+
+        let bad_sig = ApiError::from_response(
+            StatusCode::BAD_REQUEST,
+            r#"{"status":"error","code":"400","message":"Invalid Request Signature parameter (request_sig)"}"#,
+            None,
+        );
+        assert!(
+            matches!(&bad_sig, ApiError::Api { code: 400, message } if message.contains("request_sig"))
+        );
+
+        // A proxy error page instead of JSON still yields a readable message
+        let gateway =
+            ApiError::from_response(StatusCode::BAD_GATEWAY, "<html>bad gateway</html>", None);
+        assert!(matches!(gateway, ApiError::Api { code: 502, .. }));
+        assert_eq!(gateway.to_string(), "API error 502: Bad Gateway");
+    }
+}
@@ -6,14 +6,19 @@
 //! - Request signing (MD5 signatures)
 //! - All API endpoints (search, albums, tracks, playlists, etc.)
 
+pub mod artwork;
 pub mod auth;
 pub mod bundle;
+pub mod classical;
 pub mod client;
 pub mod endpoints;
 pub mod error;
 pub mod models;
 pub mod performers;
+pub mod request_policy;
+pub mod search_filters;
 
 pub use client::QobuzClient;
 pub use error::ApiError;
+pub use request_policy::RequestPolicy;
 pub use models::*;
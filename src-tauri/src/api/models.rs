@@ -2,6 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::classical::WorkGroup;
+use crate::quality::{sample_rate_hz, QualityInfo};
+
 /// Audio quality format IDs
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[repr(u32)]
@@ -57,8 +60,17 @@ pub struct UserSession {
     pub subscription_label: String,
     #[serde(default)]
     pub subscription_valid_until: Option<String>,
+    /// Country the account's catalogue is served from (e.g. "FR")
+    #[serde(default)]
+    pub zone: Option<String>,
 }
 
+/// Stream URLs this close to expiry (seconds) are re-fetched before use
+pub const STREAM_URL_REFRESH_MARGIN_SECS: u64 = 60;
+
+/// Assumed lifetime of a stream URL that doesn't carry its expiry
+const STREAM_URL_DEFAULT_TTL_SECS: u64 = 10 * 60;
+
 /// Stream URL response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamUrl {
@@ -69,9 +81,34 @@ pub struct StreamUrl {
     pub bit_depth: Option<u32>,
     pub track_id: u64,
     pub restrictions: Vec<StreamRestriction>,
+    /// Unix time (seconds) after which the CDN rejects the URL
+    #[serde(default)]
+    pub expires_at: u64,
 }
 
 impl StreamUrl {
+    /// When a URL fetched at `fetched_at` expires. Qobuz signs the expiry
+    /// into the URL's `etsp` parameter; without it a short lifetime is assumed.
+    pub fn expiry_of(url: &str, fetched_at: u64) -> u64 {
+        let query = url.split_once('?').map(|(_, query)| query).unwrap_or("");
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("etsp="))
+            .and_then(|etsp| etsp.parse().ok())
+            .unwrap_or(fetched_at + STREAM_URL_DEFAULT_TTL_SECS)
+    }
+
+    /// Whether the URL expires within the refresh margin of `now`
+    pub fn needs_refresh(&self, now: u64) -> bool {
+        self.expires_at <= now + STREAM_URL_REFRESH_MARGIN_SECS
+    }
+
+    /// The tier Qobuz actually serves; may be below the requested one when
+    /// the track isn't available in it
+    pub fn achieved_quality(&self) -> Option<Quality> {
+        Quality::from_id(self.format_id)
+    }
+
     /// Check if the stream has restrictions that prevent playback
     pub fn has_restrictions(&self) -> bool {
         self.restrictions.iter().any(|r| {
@@ -114,6 +151,14 @@ pub struct Album {
     pub upc: Option<String>,
     /// Editorial description/review of the album
     pub description: Option<String>,
+    /// Explicit content advisory
+    #[serde(default)]
+    pub parental_warning: bool,
+    /// Composer (classical albums)
+    pub composer: Option<Artist>,
+    /// Tracks grouped by work (classical albums, see `api::classical`)
+    #[serde(default)]
+    pub works: Option<Vec<WorkGroup>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,12 +194,31 @@ pub struct Track {
     pub parental_warning: bool,
     /// Playlist-specific: ID within the playlist (for removal)
     pub playlist_track_id: Option<u64>,
+    /// Playlist-specific: when the track was added (Unix seconds)
+    #[serde(default, alias = "created_at")]
+    pub added_at: Option<i64>,
     /// Performers/credits string from Qobuz (format: "Name, Role - Name, Role")
     pub performers: Option<String>,
     /// Composer information
     pub composer: Option<Artist>,
     /// Copyright information
     pub copyright: Option<String>,
+    /// Classical work the track belongs to
+    pub work: Option<String>,
+    /// Movement within the work (derived from the title)
+    #[serde(default)]
+    pub movement: Option<String>,
+}
+
+impl Track {
+    /// Qobuz reports the sampling rate in kHz
+    pub fn quality_info(&self) -> QualityInfo {
+        QualityInfo::from_specs(
+            self.maximum_bit_depth,
+            self.maximum_sampling_rate.map(sample_rate_hz),
+            None,
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -167,6 +231,14 @@ pub struct AlbumSummary {
     pub image: ImageSet,
     /// Label (if returned in track response)
     pub label: Option<Label>,
+    /// Album artist (if returned in track response)
+    #[serde(default)]
+    pub artist: Option<Artist>,
+    /// Genre (if returned in track response)
+    #[serde(default)]
+    pub genre: Option<Genre>,
+    #[serde(default)]
+    pub release_date_original: Option<String>,
 }
 
 /// Artist model
@@ -334,6 +406,9 @@ pub struct LabelDetail {
 pub struct Genre {
     pub id: u64,
     pub name: String,
+    /// Genre IDs from the top-level genre down to this one
+    #[serde(default)]
+    pub path: Vec<u64>,
 }
 
 /// Genre info with full details (from genre/list endpoint)
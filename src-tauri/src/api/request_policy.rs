@@ -0,0 +1,134 @@
+//! Timeouts and retries for Qobuz API requests
+//!
+//! The defaults match the client's historical behavior: the connect timeout
+//! comes from the network settings, requests have no overall deadline and
+//! nothing is retried. Flaky connections (mobile hotspots, VPNs) can opt into
+//! retries with jittered exponential backoff. Only transient failures are
+//! retried: connection errors, timeouts and 5xx gateway errors, never 4xx.
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Longest accepted connect/total timeout in seconds
+pub const MAX_TIMEOUT_SECS: u32 = 300;
+/// Most retries accepted per request
+pub const MAX_RETRIES: u32 = 5;
+/// Longest accepted base backoff
+pub const MAX_BACKOFF_MS: u64 = 10_000;
+
+const DEFAULT_BACKOFF_MS: u64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestPolicy {
+    /// None uses the connect timeout from the network settings
+    pub connect_timeout_secs: Option<u32>,
+    /// Deadline for a whole request, response body included. None = no limit.
+    pub total_timeout_secs: Option<u32>,
+    /// Extra attempts after a transient failure
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each further one
+    pub backoff_ms: u64,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: None,
+            total_timeout_secs: None,
+            retries: 0,
+            backoff_ms: DEFAULT_BACKOFF_MS,
+        }
+    }
+}
+
+impl RequestPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, timeout) in [
+            ("Connect timeout", self.connect_timeout_secs),
+            ("Total timeout", self.total_timeout_secs),
+        ] {
+            if let Some(secs) = timeout {
+                if secs == 0 || secs > MAX_TIMEOUT_SECS {
+                    return Err(format!(
+                        "{} must be between 1 and {} seconds",
+                        name, MAX_TIMEOUT_SECS
+                    ));
+                }
+            }
+        }
+        if self.retries > MAX_RETRIES {
+            return Err(format!("Retries must be at most {}", MAX_RETRIES));
+        }
+        if self.backoff_ms > MAX_BACKOFF_MS {
+            return Err(format!("Backoff must be at most {} ms", MAX_BACKOFF_MS));
+        }
+        Ok(())
+    }
+
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout_secs
+            .map(|secs| Duration::from_secs(secs as u64))
+    }
+
+    pub fn total_timeout(&self) -> Option<Duration> {
+        self.total_timeout_secs
+            .map(|secs| Duration::from_secs(secs as u64))
+    }
+
+    /// Delay before retry `attempt` (0 for the first retry). `jitter` in
+    /// [0, 1) spreads the delay over the upper half of the exponential step,
+    /// so clients that failed together don't retry together.
+    pub fn backoff(&self, attempt: u32, jitter: f64) -> Duration {
+        let step = self.backoff_ms.saturating_mul(1 << attempt.min(10));
+        let factor = 0.5 + jitter.clamp(0.0, 1.0) / 2.0;
+        Duration::from_millis((step as f64 * factor) as u64)
+    }
+}
+
+/// Whether a failed request may succeed when sent again
+pub fn is_transient_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+/// Whether a response status is a temporary server or gateway failure
+pub fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_exponentially_within_jitter_bounds() {
+        let policy = RequestPolicy {
+            retries: 3,
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(0, 0.0), Duration::from_millis(250));
+        assert_eq!(policy.backoff(0, 1.0), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2, 0.0), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(2, 1.0), Duration::from_millis(2000));
+        // Huge attempt numbers don't overflow
+        assert!(policy.backoff(u32::MAX, 0.5) > Duration::ZERO);
+    }
+
+    #[test]
+    fn out_of_range_policies_are_rejected() {
+        assert!(RequestPolicy::default().validate().is_ok());
+        let zero_timeout = RequestPolicy {
+            total_timeout_secs: Some(0),
+            ..Default::default()
+        };
+        assert!(zero_timeout.validate().is_err());
+        let too_many_retries = RequestPolicy {
+            retries: MAX_RETRIES + 1,
+            ..Default::default()
+        };
+        assert!(too_many_retries.validate().is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! Advanced search filters (genre, release year, explicit content)
+//!
+//! Qobuz's album and track search endpoints take no genre, year or explicit
+//! content parameters, so these filters are applied to each page of results
+//! after it is fetched. Items without the metadata a filter needs are
+//! dropped while that filter is active.
+
+use serde::{Deserialize, Serialize};
+
+use super::models::{Album, Genre, Track};
+
+/// Inclusive release year range; either end may be open
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct YearRange {
+    #[serde(default)]
+    pub from: Option<u16>,
+    #[serde(default)]
+    pub to: Option<u16>,
+}
+
+impl YearRange {
+    pub fn contains(&self, year: u16) -> bool {
+        self.from.is_none_or(|from| year >= from) && self.to.is_none_or(|to| year <= to)
+    }
+
+    fn is_open(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+}
+
+/// Filters for album and track search
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SearchFilters {
+    /// Matches the genre itself and any of its sub-genres
+    #[serde(default)]
+    pub genre_id: Option<u64>,
+    #[serde(default)]
+    pub year_range: Option<YearRange>,
+    /// Drop items with a parental warning
+    #[serde(default)]
+    pub exclude_explicit: bool,
+}
+
+impl SearchFilters {
+    /// Drop open year ranges so an empty filter set is `None`
+    pub fn normalized(mut self) -> Option<Self> {
+        if self.year_range.as_ref().is_some_and(YearRange::is_open) {
+            self.year_range = None;
+        }
+        if self.genre_id.is_none() && self.year_range.is_none() && !self.exclude_explicit {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(YearRange {
+            from: Some(from),
+            to: Some(to),
+        }) = self.year_range
+        {
+            if from > to {
+                return Err(format!("Invalid year range: {} is after {}", from, to));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn matches_album(&self, album: &Album) -> bool {
+        self.matches(
+            album.genre.as_ref(),
+            album.release_date_original.as_deref(),
+            album.parental_warning,
+        )
+    }
+
+    pub fn matches_track(&self, track: &Track) -> bool {
+        let album = track.album.as_ref();
+        self.matches(
+            album.and_then(|a| a.genre.as_ref()),
+            album.and_then(|a| a.release_date_original.as_deref()),
+            track.parental_warning,
+        )
+    }
+
+    fn matches(&self, genre: Option<&Genre>, release_date: Option<&str>, explicit: bool) -> bool {
+        if self.exclude_explicit && explicit {
+            return false;
+        }
+
+        if let Some(genre_id) = self.genre_id {
+            let in_genre = genre.is_some_and(|g| g.id == genre_id || g.path.contains(&genre_id));
+            if !in_genre {
+                return false;
+            }
+        }
+
+        if let Some(ref range) = self.year_range {
+            match release_date.and_then(release_year) {
+                Some(year) if range.contains(year) => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+/// Year of a Qobuz release date ("YYYY-MM-DD")
+fn release_year(date: &str) -> Option<u16> {
+    date.get(..4).and_then(|y| y.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album(genre: Option<(u64, Vec<u64>)>, date: Option<&str>, explicit: bool) -> Album {
+        let mut json = serde_json::json!({
+            "id": "a1",
+            "release_date_original": date,
+            "parental_warning": explicit,
+        });
+        if let Some((id, path)) = genre {
+            json["genre"] = serde_json::json!({ "id": id, "name": "Genre", "path": path });
+        }
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_normalized() {
+        assert_eq!(SearchFilters::default().normalized(), None);
+        let open_range = SearchFilters {
+            year_range: Some(YearRange::default()),
+            ..Default::default()
+        };
+        assert_eq!(open_range.normalized(), None);
+    }
+
+    #[test]
+    fn test_validate_year_range() {
+        let filters = SearchFilters {
+            year_range: Some(YearRange {
+                from: Some(2000),
+                to: Some(1990),
+            }),
+            ..Default::default()
+        };
+        assert!(filters.validate().is_err());
+    }
+
+    #[test]
+    fn test_genre_matches_sub_genres() {
+        let filters = SearchFilters {
+            genre_id: Some(80),
+            ..Default::default()
+        };
+        assert!(filters.matches_album(&album(Some((80, vec![80])), None, false)));
+        assert!(filters.matches_album(&album(Some((112, vec![80, 112])), None, false)));
+        assert!(!filters.matches_album(&album(Some((64, vec![64])), None, false)));
+        assert!(!filters.matches_album(&album(None, None, false)));
+    }
+
+    #[test]
+    fn test_year_range_and_explicit() {
+        let filters = SearchFilters {
+            year_range: Some(YearRange {
+                from: Some(1960),
+                to: None,
+            }),
+            exclude_explicit: true,
+            ..Default::default()
+        };
+        assert!(filters.matches_album(&album(None, Some("1965-03-01"), false)));
+        assert!(!filters.matches_album(&album(None, Some("1959-12-31"), false)));
+        assert!(!filters.matches_album(&album(None, Some("1999-01-01"), true)));
+        assert!(!filters.matches_album(&album(None, None, false)));
+    }
+
+    #[test]
+    fn test_track_uses_album_metadata() {
+        let track: Track = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "album": {
+                "id": "a1",
+                "genre": { "id": 80, "name": "Jazz" },
+                "release_date_original": "1959-08-17"
+            }
+        }))
+        .unwrap();
+        let filters = SearchFilters {
+            genre_id: Some(80),
+            year_range: Some(YearRange {
+                from: Some(1950),
+                to: Some(1959),
+            }),
+            exclude_explicit: true,
+        };
+        assert!(filters.matches_track(&track));
+    }
+}
@@ -5,6 +5,7 @@
 
 use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
@@ -66,13 +67,20 @@ const GENRE_TTL_SECS: i64 = 7 * 24 * 60 * 60;
 
 pub struct ApiCache {
     conn: Connection,
+    /// Lookups answered from the cache / not found or expired, since open
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 impl ApiCache {
     pub fn new(path: &Path) -> Result<Self, String> {
         let conn = Connection::open(path)
             .map_err(|e| format!("Failed to open API cache database: {}", e))?;
-        let cache = Self { conn };
+        let cache = Self {
+            conn,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
         cache.init()?;
         Ok(cache)
     }
@@ -166,6 +174,20 @@ impl ApiCache {
             .unwrap_or(0)
     }
 
+    fn record_lookups(&self, requested: usize, found: usize) {
+        self.hits.fetch_add(found as u64, Ordering::Relaxed);
+        self.misses
+            .fetch_add(requested.saturating_sub(found) as u64, Ordering::Relaxed);
+    }
+
+    /// Hit and miss counts of cache lookups
+    pub fn hit_counts(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
     // ============ Album Cache ============
 
     /// Get a cached album if it exists and hasn't expired
@@ -183,6 +205,7 @@ impl ApiCache {
             .optional()
             .map_err(|e| format!("Failed to query cached album: {}", e))?;
 
+        self.record_lookups(1, result.is_some() as usize);
         Ok(result)
     }
 
@@ -237,6 +260,7 @@ impl ApiCache {
         for row in rows {
             results.push(row.map_err(|e| format!("Failed to read cached album row: {}", e))?);
         }
+        self.record_lookups(album_ids.len(), results.len());
         Ok(results)
     }
 
@@ -278,6 +302,7 @@ impl ApiCache {
         for row in rows {
             results.push(row.map_err(|e| format!("Failed to read cached track row: {}", e))?);
         }
+        self.record_lookups(track_ids.len(), results.len());
         Ok(results)
     }
 
@@ -320,6 +345,7 @@ impl ApiCache {
         for row in rows {
             results.push(row.map_err(|e| format!("Failed to read cached artist row: {}", e))?);
         }
+        self.record_lookups(artist_ids.len(), results.len());
         Ok(results)
     }
 
@@ -340,6 +366,7 @@ impl ApiCache {
             .optional()
             .map_err(|e| format!("Failed to query cached artist: {}", e))?;
 
+        self.record_lookups(1, result.is_some() as usize);
         Ok(result)
     }
 
@@ -372,6 +399,7 @@ impl ApiCache {
             .optional()
             .map_err(|e| format!("Failed to query cached track: {}", e))?;
 
+        self.record_lookups(1, result.is_some() as usize);
         Ok(result)
     }
 
@@ -411,6 +439,7 @@ impl ApiCache {
             .optional()
             .map_err(|e| format!("Failed to query cached genres: {}", e))?;
 
+        self.record_lookups(1, result.is_some() as usize);
         Ok(result)
     }
 
@@ -0,0 +1,156 @@
+//! Prometheus text exposition for `/api/metrics`
+//!
+//! Only aggregates counters kept elsewhere (player, queue, caches); the
+//! endpoint sits behind the same token and LAN checks as the rest of the API.
+
+use std::fmt::Write;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
+
+/// Record the process start for the uptime metric; call first thing in `run`
+pub fn mark_process_start() {
+    PROCESS_START.get_or_init(Instant::now);
+}
+
+/// Time since `mark_process_start` (or since the first call, if never marked)
+pub fn process_uptime() -> Duration {
+    PROCESS_START.get_or_init(Instant::now).elapsed()
+}
+
+/// Values collected for one scrape
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    pub track_id: u64,
+    pub is_playing: bool,
+    pub position_secs: u64,
+    pub queue_length: usize,
+    pub audio_cache_hits: u64,
+    pub audio_cache_misses: u64,
+    pub audio_cache_size_bytes: u64,
+    pub api_cache_hits: u64,
+    pub api_cache_misses: u64,
+    pub offline_cache_size_bytes: u64,
+    pub offline_cache_tracks: usize,
+    pub uptime_secs: u64,
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, value: impl std::fmt::Display) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// Render the snapshot in the Prometheus text format (version 0.0.4)
+pub fn render(s: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    metric(
+        &mut out,
+        "qbz_current_track_id",
+        "gauge",
+        "Qobuz ID of the current track (0 when idle)",
+        s.track_id,
+    );
+    metric(
+        &mut out,
+        "qbz_playing",
+        "gauge",
+        "1 while audio is playing",
+        s.is_playing as u8,
+    );
+    metric(
+        &mut out,
+        "qbz_playback_position_seconds",
+        "gauge",
+        "Position in the current track",
+        s.position_secs,
+    );
+    metric(
+        &mut out,
+        "qbz_queue_length",
+        "gauge",
+        "Tracks in the play queue",
+        s.queue_length,
+    );
+    metric(
+        &mut out,
+        "qbz_audio_cache_hits_total",
+        "counter",
+        "Memory audio cache hits",
+        s.audio_cache_hits,
+    );
+    metric(
+        &mut out,
+        "qbz_audio_cache_misses_total",
+        "counter",
+        "Memory audio cache misses",
+        s.audio_cache_misses,
+    );
+    metric(
+        &mut out,
+        "qbz_audio_cache_size_bytes",
+        "gauge",
+        "Memory audio cache size",
+        s.audio_cache_size_bytes,
+    );
+    metric(
+        &mut out,
+        "qbz_api_cache_hits_total",
+        "counter",
+        "API response cache hits",
+        s.api_cache_hits,
+    );
+    metric(
+        &mut out,
+        "qbz_api_cache_misses_total",
+        "counter",
+        "API response cache misses",
+        s.api_cache_misses,
+    );
+    metric(
+        &mut out,
+        "qbz_offline_cache_size_bytes",
+        "gauge",
+        "Size of tracks cached for offline playback",
+        s.offline_cache_size_bytes,
+    );
+    metric(
+        &mut out,
+        "qbz_offline_cache_tracks",
+        "gauge",
+        "Tracks cached for offline playback",
+        s.offline_cache_tracks,
+    );
+    metric(
+        &mut out,
+        "qbz_uptime_seconds",
+        "gauge",
+        "Seconds since QBZ started",
+        s.uptime_secs,
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_help_type_and_value_lines() {
+        let text = render(&MetricsSnapshot {
+            track_id: 12345,
+            is_playing: true,
+            queue_length: 7,
+            api_cache_hits: 3,
+            ..Default::default()
+        });
+        assert!(text.contains("# TYPE qbz_playing gauge\nqbz_playing 1\n"));
+        assert!(text.contains("\nqbz_current_track_id 12345\n"));
+        assert!(text.contains("\nqbz_queue_length 7\n"));
+        assert!(
+            text.contains("# TYPE qbz_api_cache_hits_total counter\nqbz_api_cache_hits_total 3\n")
+        );
+        assert!(text.ends_with("# TYPE qbz_uptime_seconds gauge\nqbz_uptime_seconds 0\n"));
+    }
+}
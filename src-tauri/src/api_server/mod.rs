@@ -5,23 +5,31 @@ use axum::{
     middleware,
     response::{sse::{Event, Sse}, IntoResponse},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
 use futures_util::stream::Stream;
+use futures_util::{SinkExt, StreamExt};
 use tokio_stream::wrappers::BroadcastStream;
-use tokio_stream::StreamExt;
 use axum_server::{tls_rustls::RustlsConfig, Handle as AxumHandle};
 use base64::Engine;
 use rcgen::{CertificateParams, DistinguishedName, DnType, SanType};
 use serde::{Deserialize, Serialize};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
 use std::path::PathBuf;
-use std::sync::Once;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Once};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::{broadcast, Mutex};
 use tower_http::cors::{AllowOrigin, CorsLayer};
 
+mod metrics;
+mod party;
+
+pub use metrics::mark_process_start;
+pub use party::{PartySessionStatus, PendingContribution};
+use party::{Contribution, PartyGuest, PartyState};
+
 use crate::{
     api::{Album, Artist, SearchResultsPage, Track},
     artist_blacklist::BlacklistState,
@@ -32,8 +40,10 @@ use crate::{
         remote_control_settings::{RemoteControlSettings, RemoteControlSettingsState},
     },
     offline_cache::OfflineCacheState,
+    playback_router,
     player::PlaybackEvent,
-    queue::{QueueState as QueueStateData, QueueTrack},
+    quality::QualityInfo,
+    queue::{QueueState as QueueStateData, QueueTiming, QueueTrack, QueueWindow},
     AppState,
 };
 
@@ -41,7 +51,8 @@ use crate::{
 struct ApiContext {
     app_handle: AppHandle,
     token: String,
-    playback_tx: broadcast::Sender<PlaybackEvent>,
+    events_tx: broadcast::Sender<RemoteEvent>,
+    party: Arc<std::sync::Mutex<PartyState>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -66,9 +77,109 @@ pub struct RemoteControlQr {
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct NowPlayingResponse {
+pub struct RemotePartySession {
+    pub qr_data_url: String,
+    pub url: String,
+    pub require_approval: bool,
+    /// Unix seconds
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NowPlayingResponse {
     playback: crate::player::PlaybackState,
     track: Option<QueueTrack>,
+    /// Quality of the current track, normalized across sources
+    quality: Option<QualityInfo>,
+    artwork_url: Option<String>,
+    /// Synced lyric line at the current position (only when already cached)
+    current_lyric_line: Option<String>,
+    shuffle: bool,
+    repeat: String,
+}
+
+impl NowPlayingResponse {
+    fn new(app_handle: &AppHandle, playback: crate::player::PlaybackState) -> Self {
+        let app_state = app_handle.state::<AppState>();
+        let track = app_state.queue.current_track();
+        let current_lyric_line = track
+            .as_ref()
+            .and_then(|t| cached_lyric_line(app_handle, t, playback.position));
+        Self {
+            artwork_url: track.as_ref().and_then(|t| t.artwork_url.clone()),
+            quality: track.as_ref().map(QueueTrack::quality_info),
+            current_lyric_line,
+            shuffle: app_state.queue.is_shuffle(),
+            repeat: repeat_mode_str(app_state.queue.get_repeat()).to_string(),
+            playback,
+            track,
+        }
+    }
+}
+
+/// Lyric line at `position_secs` from the lyrics cache; never fetches
+fn cached_lyric_line(
+    app_handle: &AppHandle,
+    track: &QueueTrack,
+    position_secs: u64,
+) -> Option<String> {
+    let lyrics_state = app_handle.try_state::<crate::lyrics::LyricsState>()?;
+    // Skip rather than wait if a lyrics lookup holds the cache
+    let guard = lyrics_state.db.try_lock().ok()?;
+    let db = guard.as_ref()?;
+    let cache_key = crate::lyrics::build_cache_key(
+        track.title.trim(),
+        track.artist.trim(),
+        Some(track.duration_secs),
+    );
+    let payload = db
+        .get_by_track_id(track.id)
+        .ok()
+        .flatten()
+        .or_else(|| db.get_by_cache_key(&cache_key).ok().flatten())?;
+    let synced = payload.synced_lrc?;
+    let lines = crate::lyrics::lrc::parse_lrc(&synced);
+    crate::lyrics::lrc::line_at(&lines, position_secs * 1000).map(|line| line.text.clone())
+}
+
+fn repeat_mode_str(mode: crate::queue::RepeatMode) -> &'static str {
+    match mode {
+        crate::queue::RepeatMode::Off => "off",
+        crate::queue::RepeatMode::All => "all",
+        crate::queue::RepeatMode::One => "one",
+    }
+}
+
+/// Update pushed to remote clients over `/api/events` (and `/api/ws`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum RemoteEvent {
+    Playback(PlaybackEvent),
+    /// Tracks added, removed or reordered, or shuffle/repeat changed
+    Queue(QueueStateData),
+    /// The current track changed
+    NowPlaying(NowPlayingResponse),
+}
+
+impl RemoteEvent {
+    /// SSE `event:` name
+    fn event_type(&self) -> &'static str {
+        match self {
+            RemoteEvent::Playback(_) => "playback",
+            RemoteEvent::Queue(_) => "queue",
+            RemoteEvent::NowPlaying(_) => "now_playing",
+        }
+    }
+
+    fn payload_json(&self) -> Option<String> {
+        match self {
+            RemoteEvent::Playback(event) => serde_json::to_string(event),
+            RemoteEvent::Queue(queue) => serde_json::to_string(queue),
+            RemoteEvent::NowPlaying(now_playing) => serde_json::to_string(now_playing),
+        }
+        .ok()
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -101,6 +212,9 @@ struct PlayIndexRequest {
 #[serde(rename_all = "camelCase")]
 struct AddToQueueRequest {
     track: QueueTrack,
+    /// Guest name, only used with a party token
+    #[serde(default)]
+    contributor: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -147,7 +261,11 @@ struct ApiServerInner {
 
 pub struct ApiServerState {
     inner: Mutex<ApiServerInner>,
-    playback_tx: broadcast::Sender<PlaybackEvent>,
+    events_tx: broadcast::Sender<RemoteEvent>,
+    /// Survives server restarts so a settings change doesn't end the party
+    party: Arc<std::sync::Mutex<PartyState>>,
+    /// Track of the last now-playing broadcast (0 = none)
+    now_playing_track: AtomicU64,
 }
 
 impl ApiServerState {
@@ -159,7 +277,9 @@ impl ApiServerState {
                 server: None,
                 last_error: None,
             }),
-            playback_tx: tx,
+            events_tx: tx,
+            party: Arc::new(std::sync::Mutex::new(PartyState::default())),
+            now_playing_track: AtomicU64::new(0),
         }
     }
 
@@ -188,7 +308,8 @@ impl ApiServerState {
         let ctx = ApiContext {
             app_handle: app_handle.clone(),
             token: settings.token.clone(),
-            playback_tx: self.playback_tx.clone(),
+            events_tx: self.events_tx.clone(),
+            party: self.party.clone(),
         };
 
         // Load allowed origins for CORS
@@ -278,15 +399,53 @@ impl ApiServerState {
         }
     }
 
-    pub fn broadcast(&self, event: PlaybackEvent) {
-        let _ = self.playback_tx.send(event);
+    pub fn broadcast(&self, event: RemoteEvent) {
+        let _ = self.events_tx.send(event);
+    }
+
+    fn has_subscribers(&self) -> bool {
+        self.events_tx.receiver_count() > 0
+    }
+
+    fn party(&self) -> std::sync::MutexGuard<'_, PartyState> {
+        self.party.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
 pub fn broadcast_playback_event(app_handle: &AppHandle, event: &PlaybackEvent) {
     if let Some(state) = app_handle.try_state::<ApiServerState>() {
-        state.broadcast(event.clone());
+        state.broadcast(RemoteEvent::Playback(event.clone()));
+    }
+}
+
+/// Push the queue to remote clients after it was changed
+pub fn broadcast_queue_update(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<ApiServerState>() else {
+        return;
+    };
+    if !state.has_subscribers() {
+        return;
+    }
+    let app_state = app_handle.state::<AppState>();
+    state.broadcast(RemoteEvent::Queue(app_state.queue.get_state()));
+}
+
+/// Push the current track to remote clients; a no-op unless the track changed
+pub fn broadcast_now_playing(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<ApiServerState>() else {
+        return;
+    };
+    if !state.has_subscribers() {
+        return;
+    }
+    let app_state = app_handle.state::<AppState>();
+    let Ok(playback) = app_state.player.get_state() else {
+        return;
+    };
+    if state.now_playing_track.swap(playback.track_id, Ordering::Relaxed) == playback.track_id {
+        return;
     }
+    state.broadcast(RemoteEvent::NowPlaying(NowPlayingResponse::new(app_handle, playback)));
 }
 
 /// Emit playback event to both desktop UI (Tauri event) and PWA (WebSocket)
@@ -295,30 +454,24 @@ fn emit_playback_update(app_handle: &AppHandle) {
     let mut event = app_state.player.get_playback_event();
     // Add queue state to event for PWA sync
     event.shuffle = Some(app_state.queue.is_shuffle());
-    event.repeat = Some(match app_state.queue.get_repeat() {
-        crate::queue::RepeatMode::Off => "off".to_string(),
-        crate::queue::RepeatMode::All => "all".to_string(),
-        crate::queue::RepeatMode::One => "one".to_string(),
-    });
+    event.repeat = Some(repeat_mode_str(app_state.queue.get_repeat()).to_string());
     // Emit to desktop UI
     let _ = app_handle.emit("playback:state", &event);
-    // Broadcast to WebSocket clients (PWA)
+    // Broadcast to WebSocket/SSE clients (PWA); now-playing only on a track change
     broadcast_playback_event(app_handle, &event);
+    broadcast_now_playing(app_handle);
 }
 
 /// Emit queue state event to desktop UI when shuffle/repeat changes
 fn emit_queue_state_update(app_handle: &AppHandle) {
     let app_state = app_handle.state::<AppState>();
     let shuffle = app_state.queue.is_shuffle();
-    let repeat = match app_state.queue.get_repeat() {
-        crate::queue::RepeatMode::Off => "off",
-        crate::queue::RepeatMode::All => "all",
-        crate::queue::RepeatMode::One => "one",
-    };
+    let repeat = repeat_mode_str(app_state.queue.get_repeat());
     let _ = app_handle.emit("queue:state", serde_json::json!({
         "shuffle": shuffle,
         "repeat": repeat
     }));
+    broadcast_queue_update(app_handle);
 }
 
 #[tauri::command]
@@ -390,6 +543,13 @@ pub async fn remote_control_get_pairing_qr(
         "version": env!("CARGO_PKG_VERSION"),
     });
 
+    Ok(RemoteControlQr {
+        qr_data_url: qr_data_url(&payload)?,
+        url,
+    })
+}
+
+fn qr_data_url(payload: &serde_json::Value) -> Result<String, String> {
     let svg = qrcode_generator::to_svg_to_string(
         payload.to_string(),
         qrcode_generator::QrCodeEcc::Low,
@@ -398,14 +558,95 @@ pub async fn remote_control_get_pairing_qr(
     ).map_err(|e| format!("QR generation failed: {}", e))?;
 
     let svg_base64 = base64::engine::general_purpose::STANDARD.encode(svg.as_bytes());
-    let data_url = format!("data:image/svg+xml;base64,{}", svg_base64);
+    Ok(format!("data:image/svg+xml;base64,{}", svg_base64))
+}
 
-    Ok(RemoteControlQr {
-        qr_data_url: data_url,
+// ============================================================================
+// Party Session Commands
+// ============================================================================
+
+/// Start a party session: guests pairing with the returned QR code can only
+/// search and add tracks to the queue until it expires
+#[tauri::command]
+pub async fn remote_control_create_party_session(
+    duration_minutes: u32,
+    require_approval: Option<bool>,
+    app: AppHandle,
+) -> Result<RemotePartySession, String> {
+    let settings_state = app.state::<RemoteControlSettingsState>();
+    let settings = settings_state.get_settings()?;
+    if !settings.enabled {
+        return Err("Enable remote control before starting a party session".to_string());
+    }
+
+    let require_approval = require_approval.unwrap_or(false);
+    let api_state = app.state::<ApiServerState>();
+    let (token, status) = {
+        let mut party = api_state.party();
+        let token = party.start(
+            Duration::from_secs(duration_minutes as u64 * 60),
+            require_approval,
+        );
+        (token, party.status())
+    };
+    log::info!(
+        "Party session started (approval required: {}, expires at {:?})",
+        require_approval,
+        status.expires_at
+    );
+
+    let url = get_local_url(settings.port, settings.secure);
+    let payload = serde_json::json!({
+        "url": url,
+        "token": token,
+        "name": get_device_name(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "scope": "party",
+        "expiresAt": status.expires_at,
+    });
+
+    Ok(RemotePartySession {
+        qr_data_url: qr_data_url(&payload)?,
         url,
+        require_approval,
+        expires_at: status.expires_at,
     })
 }
 
+#[tauri::command]
+pub async fn remote_control_get_party_session(app: AppHandle) -> Result<PartySessionStatus, String> {
+    Ok(app.state::<ApiServerState>().party().status())
+}
+
+#[tauri::command]
+pub async fn remote_control_end_party_session(app: AppHandle) -> Result<(), String> {
+    app.state::<ApiServerState>().party().end();
+    log::info!("Party session ended");
+    Ok(())
+}
+
+/// Queue a guest's track that was waiting for approval
+#[tauri::command]
+pub async fn remote_control_approve_contribution(id: u64, app: AppHandle) -> Result<(), String> {
+    let track = app
+        .state::<ApiServerState>()
+        .party()
+        .resolve(id)
+        .ok_or("Contribution not found")?;
+    app.state::<AppState>().queue.add_track(track);
+    broadcast_queue_update(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remote_control_reject_contribution(id: u64, app: AppHandle) -> Result<(), String> {
+    app.state::<ApiServerState>()
+        .party()
+        .resolve(id)
+        .ok_or("Contribution not found")?;
+    Ok(())
+}
+
 pub async fn sync_server(app: &AppHandle) -> Result<(), String> {
     let settings_state = app.state::<RemoteControlSettingsState>();
     let settings = settings_state.get_settings()?;
@@ -460,6 +701,82 @@ pub async fn remote_control_restore_default_origins(
     state.get_origins()
 }
 
+/// The router and the list of (method, path) pairs it serves.
+///
+/// Routes are registered through this so the OpenAPI contract test can
+/// compare them against `docs/openapi.yaml`.
+struct ApiRoutes {
+    router: Router<ApiContext>,
+    #[cfg_attr(not(test), allow(dead_code))]
+    routes: Vec<(Method, &'static str)>,
+}
+
+impl ApiRoutes {
+    fn new() -> Self {
+        Self {
+            router: Router::new(),
+            routes: Vec::new(),
+        }
+    }
+
+    fn get<H, T>(mut self, path: &'static str, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, ApiContext>,
+        T: 'static,
+    {
+        self.router = self.router.route(path, get(handler));
+        self.routes.push((Method::GET, path));
+        self
+    }
+
+    fn post<H, T>(mut self, path: &'static str, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, ApiContext>,
+        T: 'static,
+    {
+        self.router = self.router.route(path, post(handler));
+        self.routes.push((Method::POST, path));
+        self
+    }
+}
+
+fn api_routes() -> ApiRoutes {
+    ApiRoutes::new()
+        .get("/api/ping", ping)
+        .get("/api/cert", get_certificate)
+        .get("/api/docs", get_openapi_spec)
+        .get("/api/status", now_playing)
+        .get("/api/now-playing", now_playing)
+        .post("/api/playback/play", play)
+        .post("/api/playback/pause", pause)
+        .post("/api/playback/next", next_track)
+        .post("/api/playback/previous", previous_track)
+        .post("/api/playback/seek", seek)
+        .post("/api/playback/volume", set_volume)
+        .get("/api/search", search_tracks)
+        .get("/api/search/all", search_all)
+        .get("/api/queue", get_queue)
+        .get("/api/queue/window", get_queue_window)
+        .get("/api/queue/timing", get_queue_timing)
+        .post("/api/queue/add", add_to_queue)
+        .post("/api/queue/add-next", add_to_queue_next)
+        .post("/api/queue/play", play_queue_index)
+        .post("/api/queue/shuffle", set_shuffle)
+        .post("/api/queue/repeat", set_repeat)
+        .get("/api/favorites", get_favorites)
+        .post("/api/favorites/add", add_favorite)
+        .post("/api/favorites/remove", remove_favorite)
+        .post("/api/album/play", play_album)
+        .get("/api/album/:id", get_album)
+        .get("/api/artist/:id", get_artist)
+        .get("/api/playback/preferences", get_playback_preferences)
+        .post("/api/playback/autoplay", set_autoplay)
+        .get("/api/ws", ws_handler)
+        .get("/api/viz", viz_ws_handler)
+        .get("/api/events", sse_handler)
+        .get("/api/metrics", get_metrics)
+}
+
 fn build_router(ctx: ApiContext, allowed_origins: Vec<String>) -> Router {
     let allowed_origins = std::sync::Arc::new(allowed_origins);
     let origins_clone = allowed_origins.clone();
@@ -475,36 +792,8 @@ fn build_router(ctx: ApiContext, allowed_origins: Vec<String>) -> Router {
             header::HeaderName::from_static("x-api-key"),
         ]);
 
-    Router::new()
-        .route("/api/ping", get(ping))
-        .route("/api/cert", get(get_certificate))
-        .route("/api/docs", get(get_openapi_spec))
-        .route("/api/status", get(now_playing))
-        .route("/api/now-playing", get(now_playing))
-        .route("/api/playback/play", post(play))
-        .route("/api/playback/pause", post(pause))
-        .route("/api/playback/next", post(next_track))
-        .route("/api/playback/previous", post(previous_track))
-        .route("/api/playback/seek", post(seek))
-        .route("/api/playback/volume", post(set_volume))
-        .route("/api/search", get(search_tracks))
-        .route("/api/search/all", get(search_all))
-        .route("/api/queue", get(get_queue))
-        .route("/api/queue/add", post(add_to_queue))
-        .route("/api/queue/add-next", post(add_to_queue_next))
-        .route("/api/queue/play", post(play_queue_index))
-        .route("/api/queue/shuffle", post(set_shuffle))
-        .route("/api/queue/repeat", post(set_repeat))
-        .route("/api/favorites", get(get_favorites))
-        .route("/api/favorites/add", post(add_favorite))
-        .route("/api/favorites/remove", post(remove_favorite))
-        .route("/api/album/play", post(play_album))
-        .route("/api/album/:id", get(get_album))
-        .route("/api/artist/:id", get(get_artist))
-        .route("/api/playback/preferences", get(get_playback_preferences))
-        .route("/api/playback/autoplay", post(set_autoplay))
-        .route("/api/ws", get(ws_handler))
-        .route("/api/events", get(sse_handler))
+    api_routes()
+        .router
         .with_state(ctx.clone())
         .layer(middleware::from_fn(lan_only))
         .layer(middleware::from_fn_with_state(ctx, require_token))
@@ -553,16 +842,64 @@ async fn now_playing(State(ctx): State<ApiContext>) -> Result<Json<NowPlayingRes
         .player
         .get_state()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let track = app_state.queue.current_track();
-    Ok(Json(NowPlayingResponse { playback, track }))
+    Ok(Json(NowPlayingResponse::new(&ctx.app_handle, playback)))
 }
 
-async fn play(State(ctx): State<ApiContext>) -> Result<StatusCode, StatusCode> {
+async fn get_metrics(State(ctx): State<ApiContext>) -> Result<impl IntoResponse, StatusCode> {
     let app_state = ctx.app_handle.state::<AppState>();
-    app_state.media_controls.set_playback(true);
-    app_state
+    let playback = app_state
         .player
-        .resume()
+        .get_state()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let audio_cache = app_state.audio_cache.stats();
+
+    let (api_cache_hits, api_cache_misses) =
+        match ctx.app_handle.try_state::<crate::api_cache::ApiCacheState>() {
+            Some(state) => state
+                .cache
+                .lock()
+                .await
+                .as_ref()
+                .map(|cache| cache.hit_counts())
+                .unwrap_or_default(),
+            None => (0, 0),
+        };
+
+    let offline_stats = {
+        let offline_cache = ctx.app_handle.state::<OfflineCacheState>();
+        let guard__ = offline_cache.db.lock().await;
+        guard__
+            .as_ref()
+            .and_then(|db| db.get_stats(&offline_cache.get_cache_path(), None).ok())
+    };
+
+    let snapshot = metrics::MetricsSnapshot {
+        track_id: playback.track_id,
+        is_playing: playback.is_playing,
+        position_secs: playback.position,
+        queue_length: app_state.queue.get_state().total_tracks,
+        audio_cache_hits: audio_cache.l1.hits,
+        audio_cache_misses: audio_cache.l1.misses,
+        audio_cache_size_bytes: audio_cache.l1.bytes_used,
+        api_cache_hits,
+        api_cache_misses,
+        offline_cache_size_bytes: offline_stats.as_ref().map_or(0, |s| s.total_size_bytes),
+        offline_cache_tracks: offline_stats.as_ref().map_or(0, |s| s.ready_tracks),
+        uptime_secs: metrics::process_uptime().as_secs(),
+    };
+
+    let mut headers = header::HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+    );
+    Ok((headers, metrics::render(&snapshot)))
+}
+
+async fn play(State(ctx): State<ApiContext>) -> Result<StatusCode, StatusCode> {
+    let app_state = ctx.app_handle.state::<AppState>();
+    commands::playback::resume_playback(ctx.app_handle.clone(), app_state)
+        .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     emit_playback_update(&ctx.app_handle);
     Ok(StatusCode::NO_CONTENT)
@@ -570,10 +907,8 @@ async fn play(State(ctx): State<ApiContext>) -> Result<StatusCode, StatusCode> {
 
 async fn pause(State(ctx): State<ApiContext>) -> Result<StatusCode, StatusCode> {
     let app_state = ctx.app_handle.state::<AppState>();
-    app_state.media_controls.set_playback(false);
-    app_state
-        .player
-        .pause()
+    commands::playback::pause_playback(ctx.app_handle.clone(), app_state)
+        .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     emit_playback_update(&ctx.app_handle);
     Ok(StatusCode::NO_CONTENT)
@@ -583,8 +918,10 @@ async fn next_track(
     State(ctx): State<ApiContext>,
 ) -> Result<Json<Option<QueueTrack>>, StatusCode> {
     let app_state = ctx.app_handle.state::<AppState>();
-    let next = app_state.queue.next();
-    if let Some(track) = next {
+    let advance = app_state.queue.advance();
+    commands::queue::emit_skipped(&ctx.app_handle, &advance.skipped);
+    commands::radio::schedule_radio_top_up(&ctx.app_handle);
+    if let Some(track) = advance.track {
         let played = play_queue_track(&ctx, track).await?;
         return Ok(Json(Some(played)));
     }
@@ -594,8 +931,12 @@ async fn next_track(
 async fn previous_track(
     State(ctx): State<ApiContext>,
 ) -> Result<Json<Option<QueueTrack>>, StatusCode> {
-    let app_state = ctx.app_handle.state::<AppState>();
-    let prev = app_state.queue.previous();
+    let prev = commands::queue::previous_or_restart(&ctx.app_handle)
+        .await
+        .map_err(|err| {
+            log::error!("Remote control previous failed: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
     if let Some(track) = prev {
         let played = play_queue_track(&ctx, track).await?;
         return Ok(Json(Some(played)));
@@ -611,6 +952,19 @@ async fn play_queue_track(
         return Err(StatusCode::NOT_IMPLEMENTED);
     }
 
+    // While casting, the track goes to the cast device
+    match playback_router::play_track(&ctx.app_handle, &track).await {
+        Ok(true) => {
+            emit_playback_update(&ctx.app_handle);
+            return Ok(track);
+        }
+        Ok(false) => {}
+        Err(err) => {
+            log::error!("Remote control cast play failed: {}", err);
+            return Err(StatusCode::BAD_GATEWAY);
+        }
+    }
+
     let app_state = ctx.app_handle.state::<AppState>();
     let offline_cache = ctx.app_handle.state::<OfflineCacheState>();
     let audio_settings = ctx.app_handle.state::<AudioSettingsState>();
@@ -638,7 +992,8 @@ async fn seek(
     Json(payload): Json<SeekRequest>,
 ) -> Result<StatusCode, StatusCode> {
     let app_state = ctx.app_handle.state::<AppState>();
-    commands::playback::seek(payload.position, app_state)
+    commands::playback::seek(payload.position, ctx.app_handle.clone(), app_state)
+        .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     emit_playback_update(&ctx.app_handle);
     Ok(StatusCode::NO_CONTENT)
@@ -649,7 +1004,8 @@ async fn set_volume(
     Json(payload): Json<VolumeRequest>,
 ) -> Result<StatusCode, StatusCode> {
     let app_state = ctx.app_handle.state::<AppState>();
-    commands::playback::set_volume(payload.volume, app_state)
+    commands::playback::set_volume(payload.volume, ctx.app_handle.clone(), app_state)
+        .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     emit_playback_update(&ctx.app_handle);
     Ok(StatusCode::NO_CONTENT)
@@ -662,6 +1018,18 @@ struct SearchQuery {
     offset: Option<u32>,
 }
 
+#[derive(Deserialize)]
+struct QueueWindowQuery {
+    #[serde(default = "default_center_on_current")]
+    center_on_current: bool,
+    before: Option<usize>,
+    after: Option<usize>,
+}
+
+fn default_center_on_current() -> bool {
+    true
+}
+
 #[derive(Deserialize)]
 struct FavoritesQuery {
     fav_type: String,
@@ -777,12 +1145,49 @@ async fn get_queue(State(ctx): State<ApiContext>) -> Result<Json<QueueStateData>
     Ok(Json(app_state.queue.get_state()))
 }
 
+async fn get_queue_window(
+    State(ctx): State<ApiContext>,
+    Query(query): Query<QueueWindowQuery>,
+) -> Result<Json<QueueWindow>, StatusCode> {
+    let app_state = ctx.app_handle.state::<AppState>();
+    Ok(Json(app_state.queue.get_window(
+        query.center_on_current,
+        query.before.unwrap_or(10).min(200),
+        query.after.unwrap_or(50).min(200),
+    )))
+}
+
+async fn get_queue_timing(State(ctx): State<ApiContext>) -> Result<Json<QueueTiming>, StatusCode> {
+    let app_state = ctx.app_handle.state::<AppState>();
+    let position = app_state.player.state.current_position();
+    Ok(Json(app_state.queue.timing(position)))
+}
+
 async fn add_to_queue(
     State(ctx): State<ApiContext>,
+    guest: Option<Extension<PartyGuest>>,
     Json(payload): Json<AddToQueueRequest>,
 ) -> Result<StatusCode, StatusCode> {
+    let mut track = payload.track;
+    if guest.is_some() {
+        let contribution = ctx
+            .party
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contribute(track, payload.contributor.as_deref())
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        track = match contribution {
+            Contribution::Accepted(track) => track,
+            Contribution::Pending(pending) => {
+                let _ = ctx.app_handle.emit("remote:party-contribution", &pending);
+                return Ok(StatusCode::ACCEPTED);
+            }
+        };
+    }
+
     let app_state = ctx.app_handle.state::<AppState>();
-    app_state.queue.add_track(payload.track);
+    app_state.queue.add_track(track);
+    broadcast_queue_update(&ctx.app_handle);
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -792,6 +1197,7 @@ async fn add_to_queue_next(
 ) -> Result<StatusCode, StatusCode> {
     let app_state = ctx.app_handle.state::<AppState>();
     app_state.queue.add_track_next(payload.track);
+    broadcast_queue_update(&ctx.app_handle);
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -803,20 +1209,7 @@ async fn play_queue_index(
     let track = app_state.queue.play_index(payload.index);
     if let Some(ref t) = track {
         if !t.is_local {
-            let offline_cache = ctx.app_handle.state::<OfflineCacheState>();
-            let audio_settings = ctx.app_handle.state::<AudioSettingsState>();
-            if let Err(err) = commands::playback::play_track(
-                t.id,
-                Some(t.duration_secs),
-                None,
-                app_state,
-                offline_cache,
-                audio_settings,
-            ).await {
-                log::error!("Remote control play_queue_index failed: {}", err);
-                return Err(StatusCode::BAD_GATEWAY);
-            }
-            emit_playback_update(&ctx.app_handle);
+            play_queue_track(&ctx, t.clone()).await?;
         }
     }
     Ok(Json(track))
@@ -828,11 +1221,7 @@ async fn set_shuffle(
 ) -> Result<Json<ShuffleRepeatResponse>, StatusCode> {
     let app_state = ctx.app_handle.state::<AppState>();
     app_state.queue.set_shuffle(payload.enabled);
-    let repeat = match app_state.queue.get_repeat() {
-        crate::queue::RepeatMode::Off => "off",
-        crate::queue::RepeatMode::All => "all",
-        crate::queue::RepeatMode::One => "one",
-    };
+    let repeat = repeat_mode_str(app_state.queue.get_repeat());
     // Notify desktop UI of queue state change
     emit_queue_state_update(&ctx.app_handle);
     Ok(Json(ShuffleRepeatResponse {
@@ -921,6 +1310,7 @@ async fn play_album(
                 .map(|p| p.name.clone())
                 .unwrap_or_else(|| album.artist.name.clone());
             let artwork = album.image.large.clone().or(album.image.small.clone());
+            let hires = t.quality_info().hires;
 
             QueueTrack {
                 id: t.id,
@@ -929,7 +1319,7 @@ async fn play_album(
                 album: album.title.clone(),
                 duration_secs: t.duration as u64,
                 artwork_url: artwork,
-                hires: t.hires,
+                hires,
                 bit_depth: t.maximum_bit_depth,
                 sample_rate: t.maximum_sampling_rate,
                 is_local: false,
@@ -937,6 +1327,9 @@ async fn play_album(
                 artist_id: t.performer.as_ref().map(|p| p.id),
                 streamable: t.streamable,
                 source: Some("qobuz".to_string()),
+                track_number: Some(t.track_number),
+                disc_number: t.media_number,
+                contributor: None,
             }
         }).collect()
     } else {
@@ -952,24 +1345,12 @@ async fn play_album(
     for track in tracks {
         app_state.queue.add_track(track);
     }
+    broadcast_queue_update(&ctx.app_handle);
 
     // Play the first track
     if let Some(first_track) = app_state.queue.play_index(0) {
         if !first_track.is_local {
-            let offline_cache = ctx.app_handle.state::<OfflineCacheState>();
-            let audio_settings = ctx.app_handle.state::<AudioSettingsState>();
-            if let Err(err) = commands::playback::play_track(
-                first_track.id,
-                Some(first_track.duration_secs),
-                None,
-                app_state,
-                offline_cache,
-                audio_settings,
-            ).await {
-                log::error!("Remote control play_album failed: {}", err);
-                return Err(StatusCode::BAD_GATEWAY);
-            }
-            emit_playback_update(&ctx.app_handle);
+            play_queue_track(&ctx, first_track).await?;
         }
     }
 
@@ -1007,13 +1388,14 @@ async fn ws_handler(
     State(ctx): State<ApiContext>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_ws(socket, ctx.playback_tx.subscribe()))
+    ws.on_upgrade(move |socket| handle_ws(socket, ctx.events_tx.subscribe()))
 }
 
-async fn handle_ws(mut socket: WebSocket, mut rx: broadcast::Receiver<PlaybackEvent>) {
+/// The WebSocket keeps its original format: bare playback events only
+async fn handle_ws(mut socket: WebSocket, mut rx: broadcast::Receiver<RemoteEvent>) {
     loop {
         match rx.recv().await {
-            Ok(event) => {
+            Ok(RemoteEvent::Playback(event)) => {
                 let payload = match serde_json::to_string(&event) {
                     Ok(p) => p,
                     Err(_) => continue,
@@ -1022,26 +1404,73 @@ async fn handle_ws(mut socket: WebSocket, mut rx: broadcast::Receiver<PlaybackEv
                     break;
                 }
             }
+            Ok(_) => continue,
             Err(broadcast::error::RecvError::Closed) => break,
             Err(broadcast::error::RecvError::Lagged(_)) => continue,
         }
     }
 }
 
+/// Spectrum frames for remote visualizers. The visualizer only captures and
+/// analyses audio for the remote while at least one of these sockets is open.
+async fn viz_ws_handler(State(ctx): State<ApiContext>, ws: WebSocketUpgrade) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| {
+        let rx = ctx
+            .app_handle
+            .state::<AppState>()
+            .visualizer
+            .subscribe_remote();
+        handle_viz_ws(socket, rx)
+    })
+}
+
+/// Forward binary frames (`visualizer::REMOTE_NUM_BANDS` bytes, one per band)
+/// until the client closes the socket
+async fn handle_viz_ws(socket: WebSocket, mut rx: broadcast::Receiver<Vec<u8>>) {
+    let (mut sender, mut receiver) = socket.split();
+    loop {
+        tokio::select! {
+            frame = rx.recv() => match frame {
+                Ok(bytes) => {
+                    if sender.send(Message::Binary(bytes)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = receiver.next() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        }
+    }
+}
+
+/// SSE frames for one event: a typed frame (`event: playback|queue|now_playing`),
+/// plus for playback the untyped frame that older PWA builds read in `onmessage`
+fn sse_frames(event: &RemoteEvent) -> Vec<Event> {
+    let Some(data) = event.payload_json() else {
+        return Vec::new();
+    };
+    let mut frames = vec![Event::default().event(event.event_type()).data(&data)];
+    if matches!(event, RemoteEvent::Playback(_)) {
+        frames.push(Event::default().data(data));
+    }
+    frames
+}
+
 async fn sse_handler(
     State(ctx): State<ApiContext>,
 ) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
-    let rx = ctx.playback_tx.subscribe();
-    let stream = BroadcastStream::new(rx)
-        .filter_map(|result| {
-            match result {
-                Ok(event) => {
-                    let json = serde_json::to_string(&event).ok()?;
-                    Some(Ok(Event::default().data(json)))
-                }
-                Err(_) => None, // Skip lagged messages
-            }
-        });
+    let rx = ctx.events_tx.subscribe();
+    let stream = BroadcastStream::new(rx).flat_map(|result| {
+        let frames = match result {
+            Ok(event) => sse_frames(&event),
+            Err(_) => Vec::new(), // Skip lagged messages
+        };
+        futures_util::stream::iter(frames.into_iter().map(Ok::<_, std::convert::Infallible>))
+    });
 
     Sse::new(stream).keep_alive(
         axum::response::sse::KeepAlive::new()
@@ -1069,18 +1498,42 @@ async fn require_token(
         return StatusCode::FORBIDDEN.into_response();
     }
 
+    let tokens = request_tokens(&req);
+    if tokens.iter().any(|token| token.as_bytes() == ctx.token.as_bytes()) {
+        return next.run(req).await;
+    }
+
+    // Party guests only get the routes in `party::PARTY_SCOPE`
+    let is_guest = {
+        let mut party = ctx.party.lock().unwrap_or_else(|e| e.into_inner());
+        tokens.iter().any(|token| party.is_party_token(token))
+    };
+    if is_guest {
+        if !party::in_scope(req.method(), path) {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+        let mut req = req;
+        req.extensions_mut().insert(PartyGuest);
+        return next.run(req).await;
+    }
+
+    StatusCode::UNAUTHORIZED.into_response()
+}
+
+/// Tokens sent as `X-API-Key`, `Authorization: Bearer` or `?token=`
+fn request_tokens(req: &axum::http::Request<Body>) -> Vec<&str> {
+    let mut tokens = Vec::new();
+
     if let Some(value) = req.headers().get("x-api-key") {
-        if value.as_bytes() == ctx.token.as_bytes() {
-            return next.run(req).await;
+        if let Ok(token) = value.to_str() {
+            tokens.push(token);
         }
     }
 
     if let Some(value) = req.headers().get(header::AUTHORIZATION) {
         if let Ok(auth) = value.to_str() {
             if let Some(token) = auth.strip_prefix("Bearer ") {
-                if token.as_bytes() == ctx.token.as_bytes() {
-                    return next.run(req).await;
-                }
+                tokens.push(token);
             }
         }
     }
@@ -1089,14 +1542,12 @@ async fn require_token(
         for pair in query.split('&') {
             let mut parts = pair.splitn(2, '=');
             if let (Some("token"), Some(value)) = (parts.next(), parts.next()) {
-                if value.as_bytes() == ctx.token.as_bytes() {
-                    return next.run(req).await;
-                }
+                tokens.push(value);
             }
         }
     }
 
-    StatusCode::UNAUTHORIZED.into_response()
+    tokens
 }
 
 async fn lan_only(
@@ -1239,3 +1690,83 @@ fn ensure_certificate() -> Result<(PathBuf, PathBuf), String> {
 fn get_device_name() -> String {
     std::env::var("HOSTNAME").unwrap_or_else(|_| "QBZ".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// (METHOD, path) pairs declared under `paths:` in the OpenAPI spec
+    fn documented_routes(spec: &str) -> BTreeSet<(String, String)> {
+        let mut routes = BTreeSet::new();
+        let mut in_paths = false;
+        let mut current_path = None;
+        for line in spec.lines() {
+            if !line.starts_with(' ') && !line.is_empty() {
+                in_paths = line == "paths:";
+                continue;
+            }
+            if !in_paths {
+                continue;
+            }
+            if let Some(path) = line.strip_prefix("  /").and_then(|l| l.strip_suffix(':')) {
+                current_path = Some(format!("/{}", path));
+            } else if let Some(method) = line.strip_prefix("    ").and_then(|l| l.strip_suffix(':')) {
+                let is_method = matches!(method, "get" | "post" | "put" | "patch" | "delete");
+                if let (Some(path), true) = (&current_path, is_method) {
+                    routes.insert((method.to_uppercase(), path.clone()));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn openapi_spec_matches_router() {
+        let documented = documented_routes(include_str!("../../../docs/openapi.yaml"));
+        let served: BTreeSet<(String, String)> = api_routes()
+            .routes
+            .into_iter()
+            .map(|(method, path)| {
+                // axum writes path parameters as `:id`, OpenAPI as `{id}`
+                let path = path
+                    .split('/')
+                    .map(|seg| match seg.strip_prefix(':') {
+                        Some(name) => format!("{{{}}}", name),
+                        None => seg.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+                (method.to_string(), path)
+            })
+            .collect();
+
+        let undocumented: Vec<_> = served.difference(&documented).collect();
+        let missing: Vec<_> = documented.difference(&served).collect();
+        assert!(
+            undocumented.is_empty(),
+            "routes missing from docs/openapi.yaml: {:?}",
+            undocumented
+        );
+        assert!(missing.is_empty(), "documented routes not served: {:?}", missing);
+    }
+
+    #[test]
+    fn queue_event_is_typed_and_carries_bare_state() {
+        let event = RemoteEvent::Queue(QueueStateData {
+            current_track: None,
+            current_index: None,
+            upcoming: Vec::new(),
+            history: Vec::new(),
+            shuffle: true,
+            repeat: crate::queue::RepeatMode::Off,
+            total_tracks: 0,
+        });
+        assert_eq!(event.event_type(), "queue");
+        let data: serde_json::Value = serde_json::from_str(&event.payload_json().unwrap()).unwrap();
+        assert_eq!(data["shuffle"], true);
+        assert_eq!(data["total_tracks"], 0);
+        // Only playback updates are duplicated as untyped frames for `onmessage`
+        assert_eq!(sse_frames(&event).len(), 1);
+    }
+}
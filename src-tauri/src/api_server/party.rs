@@ -0,0 +1,246 @@
+//! Party sessions: guest access to the remote API for queueing tracks
+//!
+//! A party session hands out a second, short-lived token that only unlocks
+//! `PARTY_SCOPE` (searching and adding to the queue). Tracks added with it are
+//! tagged with the guest's name and, if the host asked for it, wait in
+//! `pending` until approved from the desktop app. The session expires on its
+//! own; every lookup drops it once its time is up.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use axum::http::Method;
+use base64::Engine;
+use rand::RngCore;
+use serde::Serialize;
+
+use crate::queue::QueueTrack;
+
+/// Routes a party token may call
+pub const PARTY_SCOPE: &[(Method, &str)] = &[
+    (Method::GET, "/api/search"),
+    (Method::POST, "/api/queue/add"),
+];
+
+pub const MIN_DURATION: Duration = Duration::from_secs(5 * 60);
+pub const MAX_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Longest contributor name kept on a track
+const MAX_CONTRIBUTOR_LEN: usize = 40;
+
+/// Marks a request authenticated with the party token
+#[derive(Debug, Clone, Copy)]
+pub struct PartyGuest;
+
+#[derive(Debug, Clone)]
+struct PartySession {
+    token: String,
+    require_approval: bool,
+    expires_at: Instant,
+    expires_at_unix: u64,
+}
+
+/// A guest's track waiting for the host's approval
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingContribution {
+    pub id: u64,
+    pub track: QueueTrack,
+    pub contributor: Option<String>,
+}
+
+/// What the host sees of the running party session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartySessionStatus {
+    pub active: bool,
+    pub require_approval: bool,
+    /// Unix seconds
+    pub expires_at: Option<u64>,
+    pub pending: Vec<PendingContribution>,
+}
+
+/// Where a guest's track ended up
+#[derive(Debug)]
+pub enum Contribution {
+    /// Ready to be added to the queue
+    Accepted(QueueTrack),
+    /// Waiting for approval
+    Pending(PendingContribution),
+}
+
+#[derive(Debug, Default)]
+pub struct PartyState {
+    session: Option<PartySession>,
+    pending: Vec<PendingContribution>,
+    next_id: u64,
+}
+
+impl PartyState {
+    /// Start a new session (replacing any running one) and return its token
+    pub fn start(&mut self, duration: Duration, require_approval: bool) -> String {
+        let duration = duration.clamp(MIN_DURATION, MAX_DURATION);
+        let mut bytes = [0u8; 24];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+
+        let now_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.session = Some(PartySession {
+            token: token.clone(),
+            require_approval,
+            expires_at: Instant::now() + duration,
+            expires_at_unix: now_unix + duration.as_secs(),
+        });
+        self.pending.clear();
+        token
+    }
+
+    pub fn end(&mut self) {
+        self.session = None;
+        self.pending.clear();
+    }
+
+    /// Drop the session once it has expired
+    fn expire(&mut self, now: Instant) {
+        if self.session.as_ref().is_some_and(|s| now >= s.expires_at) {
+            log::info!("Party session expired");
+            self.end();
+        }
+    }
+
+    /// Whether `token` is the token of the running session
+    pub fn is_party_token(&mut self, token: &str) -> bool {
+        self.expire(Instant::now());
+        self.session
+            .as_ref()
+            .is_some_and(|s| !token.is_empty() && s.token.as_bytes() == token.as_bytes())
+    }
+
+    pub fn status(&mut self) -> PartySessionStatus {
+        self.expire(Instant::now());
+        PartySessionStatus {
+            active: self.session.is_some(),
+            require_approval: self.session.as_ref().is_some_and(|s| s.require_approval),
+            expires_at: self.session.as_ref().map(|s| s.expires_at_unix),
+            pending: self.pending.clone(),
+        }
+    }
+
+    /// Tag a guest's track and either accept it or park it for approval
+    pub fn contribute(
+        &mut self,
+        mut track: QueueTrack,
+        contributor: Option<&str>,
+    ) -> Option<Contribution> {
+        self.expire(Instant::now());
+        let session = self.session.as_ref()?;
+
+        let contributor = contributor
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| name.chars().take(MAX_CONTRIBUTOR_LEN).collect::<String>());
+        track.contributor = contributor.clone();
+
+        if !session.require_approval {
+            return Some(Contribution::Accepted(track));
+        }
+
+        self.next_id += 1;
+        let pending = PendingContribution {
+            id: self.next_id,
+            track,
+            contributor,
+        };
+        self.pending.push(pending.clone());
+        Some(Contribution::Pending(pending))
+    }
+
+    /// Remove a pending contribution; approving returns its track
+    pub fn resolve(&mut self, id: u64) -> Option<QueueTrack> {
+        let index = self.pending.iter().position(|p| p.id == id)?;
+        Some(self.pending.remove(index).track)
+    }
+}
+
+/// Whether a party token may call `method` `path`
+pub fn in_scope(method: &Method, path: &str) -> bool {
+    PARTY_SCOPE
+        .iter()
+        .any(|(allowed_method, allowed_path)| allowed_method == method && *allowed_path == path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(id: u64) -> QueueTrack {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": "Track",
+            "artist": "Artist",
+            "album": "Album",
+            "duration_secs": 180,
+            "artwork_url": null,
+            "bit_depth": null,
+            "sample_rate": null,
+            "album_id": null,
+            "artist_id": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_scope_is_limited_to_search_and_queue_add() {
+        assert!(in_scope(&Method::GET, "/api/search"));
+        assert!(in_scope(&Method::POST, "/api/queue/add"));
+        assert!(!in_scope(&Method::POST, "/api/queue/add-next"));
+        assert!(!in_scope(&Method::POST, "/api/playback/next"));
+        assert!(!in_scope(&Method::GET, "/api/queue"));
+    }
+
+    #[test]
+    fn test_token_is_only_valid_while_the_session_runs() {
+        let mut party = PartyState::default();
+        assert!(!party.is_party_token(""));
+
+        let token = party.start(Duration::from_secs(60), false);
+        assert!(party.is_party_token(&token));
+        assert!(!party.is_party_token("other"));
+
+        // Force expiry
+        party.expire(Instant::now() + MAX_DURATION + Duration::from_secs(1));
+        assert!(!party.is_party_token(&token));
+        assert!(!party.status().active);
+    }
+
+    #[test]
+    fn test_contributions_are_tagged_and_wait_for_approval() {
+        let mut party = PartyState::default();
+        assert!(party.contribute(track(1), Some("Ana")).is_none());
+
+        party.start(Duration::from_secs(600), true);
+        let Some(Contribution::Pending(pending)) = party.contribute(track(1), Some("  Ana "))
+        else {
+            panic!("expected a pending contribution");
+        };
+        assert_eq!(pending.contributor.as_deref(), Some("Ana"));
+        assert_eq!(party.status().pending.len(), 1);
+
+        let approved = party.resolve(pending.id).unwrap();
+        assert_eq!(approved.contributor.as_deref(), Some("Ana"));
+        assert!(party.status().pending.is_empty());
+        assert!(party.resolve(pending.id).is_none());
+    }
+
+    #[test]
+    fn test_contributions_without_approval_are_accepted() {
+        let mut party = PartyState::default();
+        party.start(Duration::from_secs(600), false);
+        match party.contribute(track(2), None) {
+            Some(Contribution::Accepted(track)) => assert_eq!(track.contributor, None),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Provides local artist blacklist functionality with:
 //! - O(1) lookup performance via in-memory HashSet
+//! - Track checks covering performers, album artists and credits
 //! - SQLite persistence
 //! - Global enable/disable toggle (feature flag)
 //!
@@ -17,16 +18,24 @@
 //!     continue;
 //! }
 //! ```
+//!
+//! Anything that surfaces tracks (radio, recommendations, autoplay, the
+//! queue) should go through `is_track_blacklisted()` or
+//! `is_queue_track_blacklisted()` rather than checking the performer alone.
 
 pub mod models;
 pub mod service;
 
-pub use models::{BlacklistSettings, BlacklistedArtist};
+pub use models::{BlacklistImportSummary, BlacklistSettings, BlacklistedArtist};
 pub use service::BlacklistService;
 
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Mutex;
 
+use crate::api::Track;
+use crate::queue::QueueTrack;
+
 /// Thread-safe state wrapper for the blacklist service
 pub struct BlacklistState {
     pub service: Mutex<Option<BlacklistService>>,
@@ -82,6 +91,43 @@ impl BlacklistState {
             .unwrap_or(false)
     }
 
+    /// Check if an artist is blacklisted by name, for credits without an ID
+    pub fn is_name_blacklisted(&self, artist_name: &str) -> bool {
+        self.service
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|s| s.is_name_blacklisted(artist_name)))
+            .unwrap_or(false)
+    }
+
+    /// Check if a track involves a blacklisted artist (performer, album
+    /// artist or performing credit)
+    pub fn is_track_blacklisted(&self, track: &Track) -> bool {
+        self.service
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|s| s.is_track_blacklisted(track)))
+            .unwrap_or(false)
+    }
+
+    /// Check if a queue track's artist is blacklisted
+    pub fn is_queue_track_blacklisted(&self, track: &QueueTrack) -> bool {
+        self.service
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|s| s.is_queue_track_blacklisted(track)))
+            .unwrap_or(false)
+    }
+
+    /// Snapshot of the blacklisted IDs (empty if the feature is disabled)
+    pub fn blacklisted_ids(&self) -> HashSet<u64> {
+        self.service
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|s| s.blacklisted_ids()))
+            .unwrap_or_default()
+    }
+
     /// Check if the feature is enabled
     #[inline]
     pub fn is_enabled(&self) -> bool {
@@ -142,4 +188,18 @@ impl BlacklistState {
         guard.as_ref().ok_or("No active session - please log in")?
             .clear_all()
     }
+
+    /// Export the blacklist as JSON
+    pub fn export_json(&self) -> Result<String, String> {
+        let guard = self.service.lock().map_err(|_| "Failed to acquire lock")?;
+        guard.as_ref().ok_or("No active session - please log in")?
+            .export_json()
+    }
+
+    /// Import a blacklist from JSON, merging with or replacing the current one
+    pub fn import_json(&self, json: &str, merge: bool) -> Result<BlacklistImportSummary, String> {
+        let guard = self.service.lock().map_err(|_| "Failed to acquire lock")?;
+        guard.as_ref().ok_or("No active session - please log in")?
+            .import_json(json, merge)
+    }
 }
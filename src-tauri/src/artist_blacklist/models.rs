@@ -23,3 +23,37 @@ impl Default for BlacklistSettings {
         Self { enabled: true }
     }
 }
+
+/// Current version of the blacklist export format
+pub const BLACKLIST_EXPORT_VERSION: u32 = 1;
+
+/// Portable blacklist, for backup and moving between machines.
+///
+/// Names are included so a human can review the file, and so entries can be
+/// re-added by hand if Qobuz ever changes an artist's ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlacklistExport {
+    pub version: u32,
+    pub exported_at: i64,
+    pub artists: Vec<BlacklistExportEntry>,
+}
+
+/// A single exported blacklist entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlacklistExportEntry {
+    pub artist_id: u64,
+    pub artist_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// Result of importing a blacklist
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BlacklistImportSummary {
+    /// Entries written to the blacklist
+    pub added: usize,
+    /// Entries already present (merge) or duplicated in the file
+    pub skipped: usize,
+    /// Entries rejected as invalid (missing ID or name)
+    pub invalid: usize,
+}
@@ -4,18 +4,45 @@
 //! with SQLite persistence.
 
 use rusqlite::{params, Connection};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
 
-use super::models::{BlacklistSettings, BlacklistedArtist};
+use super::models::{
+    BlacklistExport, BlacklistExportEntry, BlacklistImportSummary, BlacklistSettings,
+    BlacklistedArtist, BLACKLIST_EXPORT_VERSION,
+};
+use crate::api::performers::parse_performers;
+use crate::api::Track;
+use crate::queue::QueueTrack;
+
+/// Credit roles that don't make someone a performer on the track
+const NON_PERFORMING_ROLES: &[&str] = &[
+    "Composer",
+    "Lyricist",
+    "Writer",
+    "Producer",
+    "Engineer",
+    "Mixer",
+    "Arranger",
+    "Publisher",
+];
+
+fn is_performing_credit(roles: &[String]) -> bool {
+    roles.is_empty()
+        || roles
+            .iter()
+            .any(|role| !NON_PERFORMING_ROLES.iter().any(|np| role.contains(np)))
+}
 
 /// Artist blacklist service with O(1) lookup performance
 pub struct BlacklistService {
     conn: Connection,
     /// In-memory set for O(1) lookups
     blacklisted_ids: RwLock<HashSet<u64>>,
+    /// Lowercased names by artist ID, for credits that carry no ID
+    blacklisted_names: RwLock<HashMap<u64, String>>,
     /// Feature flag - when false, is_blacklisted() always returns false
     enabled: AtomicBool,
 }
@@ -35,6 +62,7 @@ impl BlacklistService {
         let service = Self {
             conn,
             blacklisted_ids: RwLock::new(HashSet::new()),
+            blacklisted_names: RwLock::new(HashMap::new()),
             enabled: AtomicBool::new(true),
         };
 
@@ -81,21 +109,29 @@ impl BlacklistService {
     fn load_from_db(&self) -> Result<(), String> {
         let mut stmt = self
             .conn
-            .prepare("SELECT artist_id FROM artist_blacklist")
+            .prepare("SELECT artist_id, artist_name FROM artist_blacklist")
             .map_err(|e| format!("Failed to prepare blacklist query: {}", e))?;
 
-        let ids: Vec<u64> = stmt
-            .query_map([], |row| row.get(0))
+        let entries: Vec<(u64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
             .map_err(|e| format!("Failed to query blacklist: {}", e))?
             .filter_map(|r| r.ok())
             .collect();
 
-        let count = ids.len();
+        let count = entries.len();
         let mut set = self
             .blacklisted_ids
             .write()
             .map_err(|_| "Failed to acquire write lock")?;
-        *set = ids.into_iter().collect();
+        let mut names = self
+            .blacklisted_names
+            .write()
+            .map_err(|_| "Failed to acquire write lock")?;
+        *set = entries.iter().map(|(id, _)| *id).collect();
+        *names = entries
+            .into_iter()
+            .map(|(id, name)| (id, name.to_lowercase()))
+            .collect();
 
         log::info!(
             "[Blacklist] Loaded {} blacklisted artists into memory",
@@ -140,6 +176,77 @@ impl BlacklistService {
             .unwrap_or(false)
     }
 
+    /// Check if an artist is blacklisted by name (case-insensitive)
+    ///
+    /// For credits that carry no artist ID. Returns false if the feature is
+    /// disabled.
+    pub fn is_name_blacklisted(&self, artist_name: &str) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let needle = artist_name.trim().to_lowercase();
+        if needle.is_empty() {
+            return false;
+        }
+
+        self.blacklisted_names
+            .read()
+            .map(|names| names.values().any(|name| *name == needle))
+            .unwrap_or(false)
+    }
+
+    /// Check if a track involves a blacklisted artist
+    ///
+    /// Covers the main performer, the album artist and anyone performing in
+    /// the credits (matched by name, as credits carry no IDs).
+    pub fn is_track_blacklisted(&self, track: &Track) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        if let Some(ref performer) = track.performer {
+            if self.is_blacklisted(performer.id) || self.is_name_blacklisted(&performer.name) {
+                return true;
+            }
+        }
+
+        if let Some(artist) = track.album.as_ref().and_then(|a| a.artist.as_ref()) {
+            if self.is_blacklisted(artist.id) || self.is_name_blacklisted(&artist.name) {
+                return true;
+            }
+        }
+
+        track.performers.as_deref().is_some_and(|credits| {
+            parse_performers(credits)
+                .iter()
+                .filter(|p| is_performing_credit(&p.roles))
+                .any(|p| self.is_name_blacklisted(&p.name))
+        })
+    }
+
+    /// Check if a queue track's artist is blacklisted
+    pub fn is_queue_track_blacklisted(&self, track: &QueueTrack) -> bool {
+        match track.artist_id {
+            Some(artist_id) => self.is_blacklisted(artist_id),
+            None => self.is_name_blacklisted(&track.artist),
+        }
+    }
+
+    /// Snapshot of the blacklisted IDs, for filtering off the calling thread
+    ///
+    /// Empty if the feature is disabled.
+    pub fn blacklisted_ids(&self) -> HashSet<u64> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return HashSet::new();
+        }
+
+        self.blacklisted_ids
+            .read()
+            .map(|set| set.clone())
+            .unwrap_or_default()
+    }
+
     /// Add an artist to the blacklist
     pub fn add(
         &self,
@@ -164,6 +271,9 @@ impl BlacklistService {
         if let Ok(mut set) = self.blacklisted_ids.write() {
             set.insert(artist_id);
         }
+        if let Ok(mut names) = self.blacklisted_names.write() {
+            names.insert(artist_id, artist_name.to_lowercase());
+        }
 
         log::info!(
             "[Blacklist] Added artist: {} (id={})",
@@ -186,6 +296,9 @@ impl BlacklistService {
         if let Ok(mut set) = self.blacklisted_ids.write() {
             set.remove(&artist_id);
         }
+        if let Ok(mut names) = self.blacklisted_names.write() {
+            names.remove(&artist_id);
+        }
 
         log::info!("[Blacklist] Removed artist id={}", artist_id);
         Ok(())
@@ -262,10 +375,112 @@ impl BlacklistService {
         if let Ok(mut set) = self.blacklisted_ids.write() {
             set.clear();
         }
+        if let Ok(mut names) = self.blacklisted_names.write() {
+            names.clear();
+        }
 
         log::info!("[Blacklist] Cleared all entries");
         Ok(())
     }
+
+    /// Export the blacklist as pretty-printed JSON
+    pub fn export_json(&self) -> Result<String, String> {
+        let export = BlacklistExport {
+            version: BLACKLIST_EXPORT_VERSION,
+            exported_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64,
+            artists: self
+                .get_all()?
+                .into_iter()
+                .map(|a| BlacklistExportEntry {
+                    artist_id: a.artist_id,
+                    artist_name: a.artist_name,
+                    notes: a.notes,
+                })
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&export)
+            .map_err(|e| format!("Failed to serialize blacklist: {}", e))
+    }
+
+    /// Import a blacklist exported by `export_json`
+    ///
+    /// With `merge`, entries are added alongside the current blacklist and
+    /// existing ones are kept as they are; otherwise the blacklist is
+    /// replaced. The file is validated before anything is changed.
+    pub fn import_json(&self, json: &str, merge: bool) -> Result<BlacklistImportSummary, String> {
+        let export: BlacklistExport =
+            serde_json::from_str(json).map_err(|e| format!("Invalid blacklist file: {}", e))?;
+        if export.version > BLACKLIST_EXPORT_VERSION {
+            return Err(format!(
+                "Unsupported blacklist file version {} (expected {} or older)",
+                export.version, BLACKLIST_EXPORT_VERSION
+            ));
+        }
+
+        let mut summary = BlacklistImportSummary::default();
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        for entry in export.artists {
+            if entry.artist_id == 0 || entry.artist_name.trim().is_empty() {
+                summary.invalid += 1;
+            } else if !seen.insert(entry.artist_id) || (merge && self.is_known(entry.artist_id)) {
+                summary.skipped += 1;
+            } else {
+                entries.push(entry);
+            }
+        }
+
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start import: {}", e))?;
+        if !merge {
+            tx.execute("DELETE FROM artist_blacklist", [])
+                .map_err(|e| format!("Failed to clear blacklist: {}", e))?;
+        }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        for entry in &entries {
+            tx.execute(
+                "INSERT OR REPLACE INTO artist_blacklist (artist_id, artist_name, added_at, notes)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    entry.artist_id as i64,
+                    entry.artist_name.trim(),
+                    now,
+                    entry.notes
+                ],
+            )
+            .map_err(|e| format!("Failed to import artist: {}", e))?;
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit import: {}", e))?;
+        summary.added = entries.len();
+
+        self.load_from_db()?;
+        log::info!(
+            "[Blacklist] Imported {} artists ({} skipped, {} invalid, merge={})",
+            summary.added,
+            summary.skipped,
+            summary.invalid,
+            merge
+        );
+        Ok(summary)
+    }
+
+    /// Whether an artist is in the blacklist, regardless of the enabled flag
+    fn is_known(&self, artist_id: u64) -> bool {
+        self.blacklisted_ids
+            .read()
+            .map(|set| set.contains(&artist_id))
+            .unwrap_or(false)
+    }
 }
 
 #[cfg(test)]
@@ -336,4 +551,160 @@ mod tests {
         assert_eq!(all[1].artist_name, "Artist B");
         assert_eq!(all[1].notes, Some("test note".to_string()));
     }
+
+    fn track(json: serde_json::Value) -> Track {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_track_checks_every_artist_credit() {
+        let service = create_test_service();
+        service.add(7, "Blocked Artist", None).unwrap();
+
+        let cases = [
+            (
+                "performer",
+                track(serde_json::json!({ "performer": { "id": 7, "name": "Blocked Artist" } })),
+                true,
+            ),
+            (
+                "album artist",
+                track(serde_json::json!({
+                    "performer": { "id": 1, "name": "Someone" },
+                    "album": { "id": "a1", "artist": { "id": 7, "name": "Blocked Artist" } }
+                })),
+                true,
+            ),
+            (
+                "featured credit",
+                track(serde_json::json!({
+                    "performer": { "id": 1, "name": "Someone" },
+                    "performers": "Someone, MainArtist - blocked artist, FeaturedArtist"
+                })),
+                true,
+            ),
+            (
+                "songwriting credit",
+                track(serde_json::json!({
+                    "performer": { "id": 1, "name": "Someone" },
+                    "performers": "Someone, MainArtist - Blocked Artist, Composer, Lyricist"
+                })),
+                false,
+            ),
+            (
+                "unrelated",
+                track(serde_json::json!({ "performer": { "id": 1, "name": "Someone" } })),
+                false,
+            ),
+        ];
+
+        for (label, t, expected) in &cases {
+            assert_eq!(service.is_track_blacklisted(t), *expected, "{}", label);
+        }
+
+        service.set_enabled(false).unwrap();
+        for (label, t, _) in &cases {
+            assert!(!service.is_track_blacklisted(t), "{} while disabled", label);
+        }
+        assert!(service.blacklisted_ids().is_empty());
+    }
+
+    #[test]
+    fn test_names_follow_add_and_remove() {
+        let service = create_test_service();
+
+        service.add(7, "Blocked Artist", None).unwrap();
+        assert!(service.is_name_blacklisted("BLOCKED ARTIST"));
+        assert_eq!(service.blacklisted_ids(), HashSet::from([7]));
+
+        let queue_track = |artist_id: Option<u64>, artist: &str| -> QueueTrack {
+            serde_json::from_value(serde_json::json!({
+                "id": 1,
+                "title": "Track",
+                "artist": artist,
+                "album": "Album",
+                "duration_secs": 180,
+                "artwork_url": null,
+                "bit_depth": null,
+                "sample_rate": null,
+                "album_id": null,
+                "artist_id": artist_id
+            }))
+            .unwrap()
+        };
+        assert!(service.is_queue_track_blacklisted(&queue_track(Some(7), "Renamed")));
+        assert!(service.is_queue_track_blacklisted(&queue_track(None, "Blocked Artist")));
+        assert!(!service.is_queue_track_blacklisted(&queue_track(Some(8), "Blocked Artist")));
+
+        service.remove(7).unwrap();
+        assert!(!service.is_name_blacklisted("Blocked Artist"));
+        assert!(service.blacklisted_ids().is_empty());
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let source = create_test_service();
+        source.add(1, "Artist A", None).unwrap();
+        source.add(2, "Artist B", Some("test note")).unwrap();
+        let json = source.export_json().unwrap();
+        assert!(json.contains("Artist A"));
+
+        let target = create_test_service();
+        target.add(2, "Artist B", Some("kept")).unwrap();
+        target.add(3, "Artist C", None).unwrap();
+
+        let summary = target.import_json(&json, true).unwrap();
+        assert_eq!(
+            summary,
+            BlacklistImportSummary {
+                added: 1,
+                skipped: 1,
+                invalid: 0
+            }
+        );
+        let all = target.get_all().unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[1].notes, Some("kept".to_string()));
+
+        let summary = target.import_json(&json, false).unwrap();
+        assert_eq!(summary.added, 2);
+        assert!(!target.is_blacklisted(3));
+        assert!(target.is_blacklisted(1));
+        assert!(target.is_name_blacklisted("artist b"));
+    }
+
+    #[test]
+    fn test_import_rejects_bad_input() {
+        let service = create_test_service();
+        service.add(5, "Existing", None).unwrap();
+
+        assert!(service.import_json("not json", false).is_err());
+        assert!(service
+            .import_json(r#"{"version": 99, "exported_at": 0, "artists": []}"#, false)
+            .is_err());
+        // A rejected file leaves the blacklist untouched
+        assert!(service.is_blacklisted(5));
+
+        let summary = service
+            .import_json(
+                r#"{"version": 1, "exported_at": 0, "artists": [
+                    {"artist_id": 0, "artist_name": "No ID"},
+                    {"artist_id": 8, "artist_name": "  "},
+                    {"artist_id": 9, "artist_name": "Valid"},
+                    {"artist_id": 9, "artist_name": "Valid again"}
+                ]}"#,
+                true,
+            )
+            .unwrap();
+        assert_eq!(
+            summary,
+            BlacklistImportSummary {
+                added: 1,
+                skipped: 1,
+                invalid: 2
+            }
+        );
+        assert!(service.is_blacklisted(9));
+        assert!(service.is_blacklisted(5));
+    }
 }
@@ -18,7 +18,7 @@ use rodio::{
 use std::process::Command;
 
 /// Common audio sample rates to check for device support
-const COMMON_SAMPLE_RATES: &[u32] = &[
+pub(crate) const COMMON_SAMPLE_RATES: &[u32] = &[
     44100,  // CD quality
     48000,  // DVD/DAT quality
     88200,  // 2x CD
@@ -461,6 +461,13 @@ impl AlsaBackend {
             None => true, // Default: try hw first
         };
 
+        // Why the direct hw: path wasn't taken, reported with the stream
+        let mut fallback_reason = if try_hw_first {
+            None
+        } else {
+            Some("plughw selected in the ALSA plugin setting".to_string())
+        };
+
         if try_hw_first {
             log::info!(
                 "[ALSA Backend] Attempting DIRECT hw stream: {} ({}Hz, {}ch)",
@@ -469,7 +476,7 @@ impl AlsaBackend {
                 config.channels
             );
 
-            match super::AlsaDirectStream::new(&hw_device, config.sample_rate, config.channels) {
+            match super::AlsaDirectStream::new(&hw_device, config.sample_rate, config.channels, config.buffer_frames, config.period_frames) {
                 Ok(stream) => {
                     log::info!("[ALSA Backend] ✓ Direct hw stream created successfully");
                     return Some(Ok((stream, super::backend::BitPerfectMode::DirectHardware)));
@@ -488,6 +495,7 @@ impl AlsaBackend {
                     }
 
                     log::info!("[ALSA Backend] Format unsupported on hw, trying plughw fallback...");
+                    fallback_reason = Some(format!("{} rejected the format ({})", hw_device, error));
                 }
             }
         }
@@ -500,9 +508,10 @@ impl AlsaBackend {
             config.channels
         );
 
-        match super::AlsaDirectStream::new(&plughw_device, config.sample_rate, config.channels) {
-            Ok(stream) => {
+        match super::AlsaDirectStream::new(&plughw_device, config.sample_rate, config.channels, config.buffer_frames, config.period_frames) {
+            Ok(mut stream) => {
                 log::info!("[ALSA Backend] ✓ plughw stream created (bit-perfect with format conversion)");
+                stream.set_path(super::backend::BitPerfectMode::PluginFallback, fallback_reason);
                 Some(Ok((stream, super::backend::BitPerfectMode::PluginFallback)))
             }
             Err(e) => {
@@ -643,7 +652,7 @@ impl AudioBackend for AlsaBackend {
     fn create_output_stream(
         &self,
         config: &BackendConfig,
-    ) -> BackendResult<(OutputStream, OutputStreamHandle)> {
+    ) -> BackendResult<(OutputStream, OutputStreamHandle, BufferSize)> {
         log::info!(
             "[ALSA Backend] Creating stream: {}Hz, {} channels, exclusive: {}, plugin: {:?}",
             config.sample_rate,
@@ -679,12 +688,7 @@ impl AudioBackend for AlsaBackend {
         let stream_config = StreamConfig {
             channels: config.channels,
             sample_rate: SampleRate(config.sample_rate),
-            buffer_size: if config.exclusive_mode {
-                // Smaller buffer for exclusive mode = lower latency
-                BufferSize::Fixed(512)
-            } else {
-                BufferSize::Default
-            },
+            buffer_size: BufferSize::Default,
         };
 
         // Check if device supports this configuration
@@ -693,12 +697,14 @@ impl AudioBackend for AlsaBackend {
             .map_err(|e| format!("Failed to get supported configs: {}", e))?;
 
         let mut found_matching = false;
+        let mut supported_buffer: Option<SupportedBufferSize> = None;
         for range in supported_configs {
             if range.channels() == config.channels
                 && config.sample_rate >= range.min_sample_rate().0
                 && config.sample_rate <= range.max_sample_rate().0
             {
                 found_matching = true;
+                supported_buffer = Some(*range.buffer_size());
                 log::info!(
                     "[ALSA Backend] Device supports {}Hz (range: {}-{}Hz)",
                     config.sample_rate,
@@ -724,8 +730,16 @@ impl AudioBackend for AlsaBackend {
             SampleFormat::F32,
         );
 
+        // Apply the user's buffer size (or the exclusive-mode default), clamped to the device range
+        let buffer_size = crate::audio::resolve_buffer_size(
+            config.buffer_frames,
+            config.exclusive_mode,
+            supported_buffer.as_ref(),
+        );
+        log::info!("[ALSA Backend] Buffer size: {:?}", buffer_size);
+
         // Create OutputStream with custom config
-        let stream = OutputStream::try_from_device_config(&device, supported_config)
+        let stream = OutputStream::try_from_device_config_with_buffer_size(&device, supported_config, buffer_size)
             .map_err(|e| {
                 if config.exclusive_mode {
                     format!(
@@ -743,7 +757,7 @@ impl AudioBackend for AlsaBackend {
             config.exclusive_mode
         );
 
-        Ok(stream)
+        Ok((stream.0, stream.1, buffer_size))
     }
 
     fn is_available(&self) -> bool {
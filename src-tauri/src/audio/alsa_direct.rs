@@ -8,7 +8,39 @@ use alsa::pcm::{Access, Format, HwParams, PCM};
 #[cfg(target_os = "linux")]
 use alsa::{Direction, ValueOr};
 use std::sync::atomic::AtomicBool;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::Serialize;
+
+use super::backend::BitPerfectMode;
+use super::AudioDiagnostic;
+
+/// Broken pipe: the hardware buffer ran dry (underrun)
+#[cfg(target_os = "linux")]
+const EPIPE: i32 = 32;
+
+/// Which ALSA path the output actually took, for `get_hardware_audio_status`
+#[derive(Debug, Clone, Serialize)]
+pub struct AlsaPathReport {
+    /// DirectHardware (hw:), PluginFallback (plughw:) or Disabled (shared
+    /// device, mixed through dmix)
+    pub mode: BitPerfectMode,
+    /// Device that was opened
+    pub device_id: String,
+    pub requested_sample_rate: u32,
+    /// Rate the hardware accepted (0 = unknown)
+    pub sample_rate: u32,
+    /// Sample format sent to the device (e.g. "S24_3LE")
+    pub format: Option<String>,
+    /// Negotiated hardware buffer and period sizes in frames (0 = unknown)
+    pub buffer_frames: u32,
+    pub period_frames: u32,
+    /// The device took the requested rate as-is on a direct hw: path
+    /// (plughw and dmix may convert behind our back)
+    pub format_unchanged: bool,
+    /// Why the output is not on a direct hw: path
+    pub fallback_reason: Option<String>,
+}
 
 /// Direct ALSA PCM stream for hw: devices
 pub struct AlsaDirectStream {
@@ -18,18 +50,42 @@ pub struct AlsaDirectStream {
     sample_rate: u32,
     channels: u16,
     format: Format,
+    format_name: &'static str,
     device_id: String,
+    /// Rate asked for; `sample_rate` is what the hardware accepted
+    requested_sample_rate: u32,
+    /// Negotiated hardware buffer size in frames
+    buffer_frames: u32,
+    /// Negotiated period size in frames
+    period_frames: u32,
+    /// hw: or plughw:, and why hw: wasn't used (set by the ALSA backend)
+    mode: BitPerfectMode,
+    fallback_reason: Option<String>,
+    /// Xrun counters, attached by the player once the stream is in use
+    diagnostic: OnceLock<AudioDiagnostic>,
 }
 
 impl AlsaDirectStream {
     /// Create new ALSA direct stream
+    ///
+    /// `buffer_frames` overrides the rate-based default hardware buffer size,
+    /// `period_frames` the default period of 1/10 of the buffer.
+    /// Too-small values may cause xruns (audible dropouts).
     #[cfg(target_os = "linux")]
-    pub fn new(device_id: &str, sample_rate: u32, channels: u16) -> Result<Self, String> {
+    pub fn new(
+        device_id: &str,
+        sample_rate: u32,
+        channels: u16,
+        buffer_frames: Option<u32>,
+        period_frames: Option<u32>,
+    ) -> Result<Self, String> {
         log::info!(
-            "[ALSA Direct] Opening device: {} ({}Hz, {}ch)",
+            "[ALSA Direct] Opening device: {} ({}Hz, {}ch, buffer: {:?}, period: {:?})",
             device_id,
             sample_rate,
-            channels
+            channels,
+            buffer_frames,
+            period_frames
         );
 
         // Open PCM device
@@ -37,7 +93,7 @@ impl AlsaDirectStream {
             .map_err(|e| format!("Failed to open ALSA device '{}': {}", device_id, e))?;
 
         // Set hardware parameters and auto-detect best format
-        let selected_format = {
+        let (selected_format, format_name) = {
             let hwp = HwParams::any(&pcm)
                 .map_err(|e| format!("Failed to get hardware params: {}", e))?;
 
@@ -60,12 +116,12 @@ impl AlsaDirectStream {
             for (format, name) in &format_priority {
                 if hwp.set_format(*format).is_ok() {
                     log::info!("[ALSA Direct] Selected format: {}", name);
-                    selected_format = Some(*format);
+                    selected_format = Some((*format, *name));
                     break;
                 }
             }
 
-            let format = selected_format
+            let (format, format_name) = selected_format
                 .ok_or_else(|| "No supported audio format found (tried S24_3LE, S32LE, S24LE, S16LE, FloatLE)".to_string())?;
 
             // Set channels
@@ -76,8 +132,10 @@ impl AlsaDirectStream {
             hwp.set_rate(sample_rate, ValueOr::Nearest)
                 .map_err(|e| format!("Failed to set sample rate: {}", e))?;
 
-            // Set buffer size (larger buffer for high-res audio)
-            let buffer_size = if sample_rate >= 192000 {
+            // Set buffer size (user override, else larger buffer for high-res audio)
+            let buffer_size = if let Some(frames) = buffer_frames {
+                frames as i64
+            } else if sample_rate >= 192000 {
                 // 500ms buffer for 192kHz+ (like MPD config)
                 (sample_rate / 2) as i64
             } else if sample_rate >= 96000 {
@@ -91,8 +149,12 @@ impl AlsaDirectStream {
             hwp.set_buffer_size_near(buffer_size)
                 .map_err(|e| format!("Failed to set buffer size: {}", e))?;
 
-            // Set period size (1/10 of buffer)
-            hwp.set_period_size_near(buffer_size / 10, ValueOr::Nearest)
+            // Set period size (user override, else 1/10 of buffer); at least
+            // two periods must fit in the buffer
+            let period_size = period_frames
+                .map(|frames| (frames as i64).min(buffer_size / 2))
+                .unwrap_or(buffer_size / 10);
+            hwp.set_period_size_near(period_size, ValueOr::Nearest)
                 .map_err(|e| format!("Failed to set period size: {}", e))?;
 
             // Apply hardware parameters
@@ -102,9 +164,34 @@ impl AlsaDirectStream {
             log::info!("[ALSA Direct] Hardware configured: {}Hz, {}ch, buffer: {} frames, format: {:?}",
                 sample_rate, channels, buffer_size, format);
 
-            format
+            (format, format_name)
         };
 
+        // Read back what the hardware actually accepted (set_*_near may round)
+        let (negotiated_rate, negotiated_buffer, negotiated_period) = pcm
+            .hw_params_current()
+            .map(|hwp| {
+                (
+                    hwp.get_rate().unwrap_or(sample_rate),
+                    hwp.get_buffer_size().unwrap_or(0).max(0) as u32,
+                    hwp.get_period_size().unwrap_or(0).max(0) as u32,
+                )
+            })
+            .unwrap_or((sample_rate, 0, 0));
+        log::info!(
+            "[ALSA Direct] Negotiated rate: {}Hz, buffer: {} frames, period: {} frames",
+            negotiated_rate,
+            negotiated_buffer,
+            negotiated_period
+        );
+        if negotiated_rate != sample_rate {
+            log::warn!(
+                "[ALSA Direct] Device changed the rate: requested {}Hz, got {}Hz",
+                sample_rate,
+                negotiated_rate
+            );
+        }
+
         // Prepare device for playback
         pcm.prepare()
             .map_err(|e| format!("Failed to prepare PCM: {}", e))?;
@@ -112,13 +199,80 @@ impl AlsaDirectStream {
         Ok(Self {
             pcm: Arc::new(Mutex::new(pcm)),
             is_playing: Arc::new(AtomicBool::new(false)),
-            sample_rate,
+            sample_rate: negotiated_rate,
             channels,
             format: selected_format,
+            format_name,
             device_id: device_id.to_string(),
+            requested_sample_rate: sample_rate,
+            buffer_frames: negotiated_buffer,
+            period_frames: negotiated_period,
+            mode: BitPerfectMode::DirectHardware,
+            fallback_reason: None,
+            diagnostic: OnceLock::new(),
         })
     }
 
+    /// Attach the diagnostic that receives xrun counters for this stream.
+    pub fn attach_diagnostic(&self, diagnostic: AudioDiagnostic) {
+        diagnostic.set_stream_geometry(self.buffer_frames, self.period_frames);
+        diagnostic.set_alsa_path(Some(self.path_report()));
+        let _ = self.diagnostic.set(diagnostic);
+    }
+
+    /// Record how this stream was reached (plughw fallback and why)
+    pub fn set_path(&mut self, mode: BitPerfectMode, fallback_reason: Option<String>) {
+        self.mode = mode;
+        self.fallback_reason = fallback_reason;
+    }
+
+    /// The ALSA path this stream took
+    pub fn path_report(&self) -> AlsaPathReport {
+        AlsaPathReport {
+            mode: self.mode,
+            device_id: self.device_id.clone(),
+            requested_sample_rate: self.requested_sample_rate,
+            sample_rate: self.sample_rate,
+            format: Some(self.format_name.to_string()),
+            buffer_frames: self.buffer_frames,
+            period_frames: self.period_frames,
+            format_unchanged: self.mode == BitPerfectMode::DirectHardware
+                && self.sample_rate == self.requested_sample_rate,
+            fallback_reason: self.fallback_reason.clone(),
+        }
+    }
+
+    /// Recover from a failed write (`snd_pcm_recover`) and record the xrun.
+    ///
+    /// EPIPE means the buffer underran: the writer could not keep up, either
+    /// because the buffer is too small or the CPU was starved.
+    #[cfg(target_os = "linux")]
+    fn recover(&self, pcm: &PCM, err: alsa::Error) -> Result<(), String> {
+        let errno = err.errno() as i32;
+        let underrun = errno == EPIPE;
+        let result = pcm.recover(errno, false);
+
+        if let Some(diagnostic) = self.diagnostic.get() {
+            diagnostic.record_xrun(errno, underrun, result.is_ok());
+        }
+
+        match result {
+            Ok(()) => {
+                if underrun {
+                    log::warn!(
+                        "[ALSA Direct] Underrun recovered (buffer: {} frames, period: {} frames)",
+                        self.buffer_frames,
+                        self.period_frames
+                    );
+                } else {
+                    log::warn!("[ALSA Direct] Recovered from PCM error: {}", err);
+                }
+                Ok(())
+            }
+            Err(recover_err) => Err(format!("Failed to recover from error: {}", recover_err)),
+        }
+    }
+
     /// Write audio samples to ALSA (auto-converts from i16 based on detected format)
     #[cfg(target_os = "linux")]
     pub fn write(&self, samples_i16: &[i16]) -> Result<(), String> {
@@ -143,14 +297,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             Format::S32LE => {
@@ -170,14 +317,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             Format::S16LE => {
@@ -192,14 +332,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             Format::S243LE => {
@@ -227,14 +360,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error (S24_3LE)");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             Format::S24LE => {
@@ -255,14 +381,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             _ => {
@@ -293,14 +412,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             Format::S32LE => {
@@ -320,14 +432,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             Format::S24LE => {
@@ -351,14 +456,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             Format::S243LE => {
@@ -384,14 +482,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error (S24_3LE)");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             Format::S16LE => {
@@ -411,14 +502,7 @@ impl AlsaDirectStream {
                         }
                         Ok(())
                     }
-                    Err(e) => {
-                        if let Err(recover_err) = pcm.recover(e.errno() as i32, false) {
-                            Err(format!("Failed to recover from error: {}", recover_err))
-                        } else {
-                            log::warn!("[ALSA Direct] Recovered from PCM error");
-                            Ok(())
-                        }
-                    }
+                    Err(e) => self.recover(&pcm, e),
                 }
             }
             _ => {
@@ -462,6 +546,16 @@ impl AlsaDirectStream {
         &self.device_id
     }
 
+    /// Negotiated hardware buffer size in frames (0 = unknown)
+    pub fn buffer_frames(&self) -> u32 {
+        self.buffer_frames
+    }
+
+    /// Negotiated period size in frames (0 = unknown)
+    pub fn period_frames(&self) -> u32 {
+        self.period_frames
+    }
+
     /// Try to set hardware volume via ALSA mixer
     ///
     /// Returns error if:
@@ -518,7 +612,13 @@ impl AlsaDirectStream {
 
 #[cfg(not(target_os = "linux"))]
 impl AlsaDirectStream {
-    pub fn new(_device_id: &str, _sample_rate: u32, _channels: u16) -> Result<Self, String> {
+    pub fn new(
+        _device_id: &str,
+        _sample_rate: u32,
+        _channels: u16,
+        _buffer_frames: Option<u32>,
+        _period_frames: Option<u32>,
+    ) -> Result<Self, String> {
         Err("ALSA Direct is only available on Linux".to_string())
     }
 
@@ -3,6 +3,7 @@
 //! Provides a unified interface for different audio backends (PipeWire, ALSA, PulseAudio)
 //! allowing users to choose their preferred audio stack.
 
+use rodio::cpal::{BufferSize, SupportedBufferSize};
 use rodio::{OutputStream, OutputStreamHandle};
 use serde::{Deserialize, Serialize};
 
@@ -112,6 +113,62 @@ pub struct BackendConfig {
 
     /// Exclusive mode flag
     pub exclusive_mode: bool,
+
+    /// Requested buffer size in frames (None = backend default)
+    pub buffer_frames: Option<u32>,
+
+    /// Requested ALSA Direct period size in frames (None = 1/10 of the buffer)
+    pub period_frames: Option<u32>,
+}
+
+/// Buffer size used in exclusive mode when the user hasn't set one (lower latency)
+pub const EXCLUSIVE_BUFFER_FRAMES: u32 = 512;
+
+/// Resolve the requested buffer size against what the device reports.
+///
+/// `None` keeps the backend default, except in exclusive mode which falls back
+/// to `EXCLUSIVE_BUFFER_FRAMES`. A fixed size is clamped into the device's
+/// `SupportedBufferSize` range when the device reports one.
+pub fn resolve_buffer_size(
+    requested_frames: Option<u32>,
+    exclusive_mode: bool,
+    supported: Option<&SupportedBufferSize>,
+) -> BufferSize {
+    let Some(frames) = requested_frames.or(exclusive_mode.then_some(EXCLUSIVE_BUFFER_FRAMES))
+    else {
+        return BufferSize::Default;
+    };
+
+    let clamped = match supported {
+        Some(SupportedBufferSize::Range { min, max }) => frames.clamp(*min, *max),
+        _ => frames,
+    };
+
+    if clamped != frames {
+        log::warn!(
+            "Requested buffer of {} frames is outside the device range, using {} frames",
+            frames,
+            clamped
+        );
+    }
+
+    BufferSize::Fixed(clamped)
+}
+
+/// Frame count of a resolved buffer size (0 = backend default)
+pub fn buffer_size_frames(buffer_size: &BufferSize) -> u32 {
+    match buffer_size {
+        BufferSize::Fixed(frames) => *frames,
+        BufferSize::Default => 0,
+    }
+}
+
+/// Estimate output latency in milliseconds for a buffer of `frames` at `sample_rate`
+pub fn buffer_latency_ms(frames: u32, sample_rate: u32) -> Option<f64> {
+    if frames == 0 || sample_rate == 0 {
+        return None;
+    }
+    Some(frames as f64 * 1000.0 / sample_rate as f64)
 }
 
 /// Result type for backend operations
@@ -213,11 +270,13 @@ pub trait AudioBackend: Send + Sync {
     /// Enumerate available audio devices for this backend
     fn enumerate_devices(&self) -> BackendResult<Vec<AudioDevice>>;
 
-    /// Create an output stream for the given configuration
+    /// Create an output stream for the given configuration.
+    ///
+    /// Also returns the buffer size the stream was opened with.
     fn create_output_stream(
         &self,
         config: &BackendConfig,
-    ) -> BackendResult<(OutputStream, OutputStreamHandle)>;
+    ) -> BackendResult<(OutputStream, OutputStreamHandle, BufferSize)>;
 
     /// Check if this backend is available on the current system
     fn is_available(&self) -> bool;
@@ -309,3 +368,47 @@ impl BackendManager {
             .unwrap_or(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_buffer_size() {
+        let range = SupportedBufferSize::Range {
+            min: 256,
+            max: 4096,
+        };
+
+        // No request: backend default, or the exclusive-mode fallback
+        assert_eq!(
+            resolve_buffer_size(None, false, Some(&range)),
+            BufferSize::Default
+        );
+        assert_eq!(
+            resolve_buffer_size(None, true, None),
+            BufferSize::Fixed(EXCLUSIVE_BUFFER_FRAMES)
+        );
+
+        // Requests are clamped into the device range
+        assert_eq!(
+            resolve_buffer_size(Some(1024), false, Some(&range)),
+            BufferSize::Fixed(1024)
+        );
+        assert_eq!(
+            resolve_buffer_size(Some(64), true, Some(&range)),
+            BufferSize::Fixed(256)
+        );
+        assert_eq!(
+            resolve_buffer_size(Some(16384), false, Some(&range)),
+            BufferSize::Fixed(4096)
+        );
+        assert_eq!(
+            resolve_buffer_size(Some(16384), false, Some(&SupportedBufferSize::Unknown)),
+            BufferSize::Fixed(16384)
+        );
+
+        assert_eq!(buffer_size_frames(&BufferSize::Fixed(256)), 256);
+        assert_eq!(buffer_size_frames(&BufferSize::Default), 0);
+    }
+}
@@ -0,0 +1,217 @@
+//! Bit-perfect verification
+//!
+//! Compares the decoded source format against the negotiated output stream
+//! and what the device reports, and lists everything that breaks bit-perfect
+//! playback (resampling, software volume, normalization, mixing).
+
+use serde::Serialize;
+
+use super::{AlsaPlugin, AudioBackendType};
+
+/// Snapshot of the pipeline used to evaluate bit-perfect status
+#[derive(Debug, Clone, Default)]
+pub struct BitPerfectInputs {
+    /// Decoded source sample rate (0 = nothing playing)
+    pub source_sample_rate: u32,
+    /// Decoded source bit depth
+    pub source_bit_depth: u32,
+    /// Sample rate the output stream was opened at (0 = no stream)
+    pub output_sample_rate: u32,
+    /// True when writing straight to hardware via ALSA Direct
+    pub alsa_direct: bool,
+    /// Sample rate the device reports (/proc/asound), if known
+    pub hardware_sample_rate: Option<u32>,
+    /// Sample format the device reports (e.g. "S32_LE"), if known
+    pub hardware_format: Option<String>,
+    pub backend_type: Option<AudioBackendType>,
+    pub alsa_plugin: Option<AlsaPlugin>,
+    pub exclusive_mode: bool,
+    /// Player volume (0.0 - 1.0)
+    pub volume: f32,
+    /// True when volume is applied by the DAC mixer instead of in software
+    pub hardware_volume: bool,
+    /// Normalization gain currently applied (None = off)
+    pub normalization_gain: Option<f32>,
+    /// True when the equalizer is processing the output
+    pub eq_active: bool,
+}
+
+/// Result returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitPerfectStatus {
+    pub bit_perfect: bool,
+    /// Everything breaking bit-perfect playback (empty when bit-perfect)
+    pub reasons: Vec<String>,
+    pub source_sample_rate: u32,
+    pub source_bit_depth: u32,
+    pub output_sample_rate: u32,
+    pub hardware_sample_rate: Option<u32>,
+    pub hardware_format: Option<String>,
+}
+
+/// Effective integer bits of an ALSA format name (None for float/unknown)
+pub(crate) fn format_bits(format: &str) -> Option<u32> {
+    let upper = format.to_ascii_uppercase();
+    if upper.starts_with("S16") || upper.starts_with("U16") {
+        Some(16)
+    } else if upper.starts_with("S24") || upper.starts_with("U24") {
+        Some(24)
+    } else if upper.starts_with("S32") || upper.starts_with("U32") {
+        Some(32)
+    } else {
+        None
+    }
+}
+
+fn backend_name(backend: Option<AudioBackendType>) -> &'static str {
+    match backend {
+        Some(AudioBackendType::PipeWire) => "PipeWire",
+        Some(AudioBackendType::Alsa) => "ALSA",
+        Some(AudioBackendType::Pulse) => "PulseAudio",
+        None => "the system default output",
+    }
+}
+
+/// Evaluate whether the current pipeline is bit-perfect
+pub fn evaluate(inputs: &BitPerfectInputs) -> BitPerfectStatus {
+    let mut reasons = Vec::new();
+
+    if inputs.source_sample_rate == 0 {
+        reasons.push("Nothing is playing".to_string());
+    } else {
+        if inputs.output_sample_rate != 0 && inputs.output_sample_rate != inputs.source_sample_rate {
+            reasons.push(format!(
+                "Resampling: source is {}Hz, output stream is {}Hz",
+                inputs.source_sample_rate, inputs.output_sample_rate
+            ));
+        }
+
+        if let Some(hw_rate) = inputs.hardware_sample_rate {
+            if hw_rate != inputs.source_sample_rate {
+                reasons.push(format!(
+                    "Device is running at {}Hz, source is {}Hz",
+                    hw_rate, inputs.source_sample_rate
+                ));
+            }
+        }
+
+        if let Some(ref format) = inputs.hardware_format {
+            if let Some(bits) = format_bits(format) {
+                if bits < inputs.source_bit_depth {
+                    reasons.push(format!(
+                        "Device format {} truncates the {}-bit source",
+                        format, inputs.source_bit_depth
+                    ));
+                }
+            }
+        }
+    }
+
+    if inputs.volume < 1.0 && !(inputs.alsa_direct && inputs.hardware_volume) {
+        reasons.push(format!(
+            "Software volume at {:.0}%",
+            inputs.volume * 100.0
+        ));
+    }
+
+    if let Some(gain) = inputs.normalization_gain {
+        if (gain - 1.0).abs() > f32::EPSILON {
+            reasons.push(format!("Volume normalization applied (gain {:.3})", gain));
+        }
+    }
+
+    if inputs.eq_active {
+        reasons.push("Equalizer applied".to_string());
+    }
+
+    if inputs.alsa_direct {
+        if inputs.alsa_plugin == Some(AlsaPlugin::PlugHw) {
+            reasons.push("plughw may convert the sample format".to_string());
+        }
+    } else if inputs.backend_type == Some(AudioBackendType::Alsa) {
+        match inputs.alsa_plugin {
+            Some(AlsaPlugin::Pcm) | None => {
+                reasons.push("ALSA default device mixes with other applications (dmix)".to_string());
+            }
+            Some(AlsaPlugin::PlugHw) => {
+                reasons.push("plughw may convert the sample format".to_string());
+            }
+            Some(AlsaPlugin::Hw) => {}
+        }
+    } else if !inputs.exclusive_mode {
+        reasons.push(format!(
+            "Shared mode: {} mixes with other applications",
+            backend_name(inputs.backend_type)
+        ));
+    }
+
+    BitPerfectStatus {
+        bit_perfect: reasons.is_empty(),
+        reasons,
+        source_sample_rate: inputs.source_sample_rate,
+        source_bit_depth: inputs.source_bit_depth,
+        output_sample_rate: inputs.output_sample_rate,
+        hardware_sample_rate: inputs.hardware_sample_rate,
+        hardware_format: inputs.hardware_format.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alsa_direct_24_96() -> BitPerfectInputs {
+        BitPerfectInputs {
+            source_sample_rate: 96000,
+            source_bit_depth: 24,
+            output_sample_rate: 96000,
+            alsa_direct: true,
+            hardware_sample_rate: Some(96000),
+            hardware_format: Some("S32_LE".to_string()),
+            backend_type: Some(AudioBackendType::Alsa),
+            alsa_plugin: Some(AlsaPlugin::Hw),
+            exclusive_mode: true,
+            volume: 1.0,
+            hardware_volume: false,
+            normalization_gain: None,
+            eq_active: false,
+        }
+    }
+
+    #[test]
+    fn alsa_direct_at_full_volume_is_bit_perfect() {
+        let status = evaluate(&alsa_direct_24_96());
+        assert!(status.bit_perfect, "unexpected reasons: {:?}", status.reasons);
+    }
+
+    #[test]
+    fn resampling_and_software_volume_are_reported() {
+        let mut inputs = alsa_direct_24_96();
+        inputs.output_sample_rate = 48000;
+        inputs.volume = 0.5;
+        let status = evaluate(&inputs);
+        assert!(!status.bit_perfect);
+        assert_eq!(status.reasons.len(), 2);
+    }
+
+    #[test]
+    fn hardware_volume_keeps_alsa_direct_bit_perfect() {
+        let mut inputs = alsa_direct_24_96();
+        inputs.volume = 0.5;
+        inputs.hardware_volume = true;
+        assert!(evaluate(&inputs).bit_perfect);
+    }
+
+    #[test]
+    fn shared_pipewire_and_16_bit_device_are_reported() {
+        let mut inputs = alsa_direct_24_96();
+        inputs.alsa_direct = false;
+        inputs.backend_type = Some(AudioBackendType::PipeWire);
+        inputs.exclusive_mode = false;
+        inputs.hardware_format = Some("S16_LE".to_string());
+        let status = evaluate(&inputs);
+        assert!(status.reasons.iter().any(|r| r.contains("Shared mode")));
+        assert!(status.reasons.iter().any(|r| r.contains("truncates")));
+    }
+}
@@ -0,0 +1,352 @@
+//! Output device format probing
+//!
+//! Expands what a device reports (CPAL config ranges) into the concrete
+//! (sample rate, bit depth, channels) combinations it accepts, and maps those
+//! onto Qobuz quality tiers so the UI can tell which tiers play bit-perfect.
+
+use std::sync::{LazyLock, Mutex};
+
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use rodio::cpal::{SampleFormat, SupportedStreamConfigRange};
+use serde::Serialize;
+
+use super::alsa_backend::{resolve_stable_to_current_hw, COMMON_SAMPLE_RATES};
+use crate::api::models::Quality;
+
+/// One configuration reported by the device: a channel count and sample
+/// format over a (possibly single-valued) sample rate range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigRange {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    /// Integer bits the format carries losslessly
+    pub bit_depth: u16,
+}
+
+impl ConfigRange {
+    pub fn from_cpal(range: &SupportedStreamConfigRange) -> Self {
+        Self {
+            channels: range.channels(),
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            bit_depth: lossless_bits(range.sample_format()),
+        }
+    }
+}
+
+/// Integer bits a sample format holds without rounding: f32 has a 24-bit
+/// mantissa, so it carries 24-bit PCM exactly but not more
+fn lossless_bits(format: SampleFormat) -> u16 {
+    match format {
+        SampleFormat::F32 => 24,
+        SampleFormat::F64 => 32,
+        other => (other.sample_size() * 8) as u16,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct SupportedFormat {
+    pub sample_rate: u32,
+    pub bit_depth: u16,
+    pub channels: u16,
+}
+
+/// What the device can do for one Qobuz tier
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QualitySupport {
+    pub quality: Quality,
+    /// Bit depth the tier is delivered in
+    pub bit_depth: u16,
+    /// Tier sample rates the device plays natively in stereo
+    pub native_rates: Vec<u32>,
+    /// Tier sample rates that would be resampled
+    pub unsupported_rates: Vec<u32>,
+    /// Every track of the tier plays without resampling or truncation
+    pub bit_perfect: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeviceFormatMatrix {
+    pub device_id: String,
+    pub formats: Vec<SupportedFormat>,
+    /// Highest tier first
+    pub qualities: Vec<QualitySupport>,
+}
+
+/// Bit depth and sample rates Qobuz delivers a tier in
+fn tier_formats(quality: Quality) -> (u16, &'static [u32]) {
+    match quality {
+        Quality::Mp3 | Quality::Lossless => (16, &[44100]),
+        Quality::HiRes => (24, &[44100, 48000, 88200, 96000]),
+        Quality::UltraHiRes => (24, &[176400, 192000]),
+    }
+}
+
+/// Concrete formats covered by the device's config ranges
+///
+/// Devices report either discrete rates (min == max, typical for ALSA hw)
+/// or broad ranges (plug devices, PipeWire); a range is expanded to the
+/// standard audio rates inside it.
+pub fn expand_ranges(ranges: &[ConfigRange]) -> Vec<SupportedFormat> {
+    let mut formats: Vec<SupportedFormat> = ranges
+        .iter()
+        .flat_map(|range| {
+            let rates: Vec<u32> = if range.min_sample_rate == range.max_sample_rate {
+                vec![range.min_sample_rate]
+            } else {
+                COMMON_SAMPLE_RATES
+                    .iter()
+                    .copied()
+                    .filter(|rate| (range.min_sample_rate..=range.max_sample_rate).contains(rate))
+                    .collect()
+            };
+            rates.into_iter().map(|sample_rate| SupportedFormat {
+                sample_rate,
+                bit_depth: range.bit_depth,
+                channels: range.channels,
+            })
+        })
+        .collect();
+    formats.sort_unstable();
+    formats.dedup();
+    formats
+}
+
+/// Map supported formats onto the Qobuz tiers, highest first
+///
+/// A tier rate is native when the device takes it in stereo at the tier's
+/// bit depth or wider (narrower samples are padded, not altered).
+pub fn quality_matrix(formats: &[SupportedFormat]) -> Vec<QualitySupport> {
+    Quality::fallback_order()
+        .iter()
+        .map(|&quality| {
+            let (bit_depth, rates) = tier_formats(quality);
+            let (native_rates, unsupported_rates): (Vec<u32>, Vec<u32>) =
+                rates.iter().partition(|&&rate| {
+                    formats.iter().any(|format| {
+                        format.sample_rate == rate
+                            && format.channels == 2
+                            && format.bit_depth >= bit_depth
+                    })
+                });
+            QualitySupport {
+                quality,
+                bit_depth,
+                bit_perfect: unsupported_rates.is_empty(),
+                native_rates,
+                unsupported_rates,
+            }
+        })
+        .collect()
+}
+
+/// Sample rate Qobuz delivers a track mastered at `track_rate` in for a tier.
+/// Tiers above CD halve the master rate until it fits (176.4k -> 88.2k), so
+/// the rate family is kept; CD and MP3 are always 44.1k.
+fn delivered_rate(quality: Quality, track_rate: u32) -> u32 {
+    let max_rate = match quality {
+        Quality::Mp3 | Quality::Lossless => return 44100,
+        Quality::HiRes => 96000,
+        Quality::UltraHiRes => 192000,
+    };
+    let mut rate = track_rate;
+    while rate > max_rate {
+        rate /= 2;
+    }
+    rate
+}
+
+/// Highest tier up to `preferred` the device plays a track mastered at
+/// `track_rate` in without resampling. Falls back to `preferred` when no tier
+/// plays natively, since a lower tier would then lose quality for nothing.
+pub fn quality_for_track(
+    preferred: Quality,
+    track_rate: u32,
+    formats: &[SupportedFormat],
+) -> Quality {
+    Quality::fallback_order()
+        .iter()
+        .copied()
+        .filter(|&quality| quality <= preferred && quality != Quality::Mp3)
+        .find(|&quality| {
+            let (bit_depth, _) = tier_formats(quality);
+            let rate = delivered_rate(quality, track_rate);
+            formats.iter().any(|format| {
+                format.sample_rate == rate && format.channels == 2 && format.bit_depth >= bit_depth
+            })
+        })
+        .unwrap_or(preferred)
+}
+
+/// Last probe and its outcome. A device's formats don't change while it is
+/// plugged in; failures (e.g. device busy) are kept too so status polling
+/// doesn't re-enumerate devices. `refresh` probes again.
+static PROBE_CACHE: LazyLock<Mutex<Option<(String, Result<DeviceFormatMatrix, String>)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Format matrix of `device_id` (None = system default), probed once and
+/// then served from cache unless `refresh` is set
+pub fn device_format_matrix(
+    device_id: Option<&str>,
+    refresh: bool,
+) -> Result<DeviceFormatMatrix, String> {
+    let host = rodio::cpal::default_host();
+    let device_id = match device_id {
+        Some(id) => resolve_stable_to_current_hw(id).unwrap_or_else(|| id.to_string()),
+        None => host
+            .default_output_device()
+            .and_then(|device| device.name().ok())
+            .ok_or("No default output device")?,
+    };
+
+    if !refresh {
+        if let Ok(guard) = PROBE_CACHE.lock() {
+            if let Some((_, result)) = guard.as_ref().filter(|(id, _)| *id == device_id) {
+                return result.clone();
+            }
+        }
+    }
+
+    let result = probe(&host, &device_id);
+    match &result {
+        Ok(matrix) => log::info!(
+            "Probed {}: {} formats, bit-perfect tiers: {:?}",
+            device_id,
+            matrix.formats.len(),
+            matrix
+                .qualities
+                .iter()
+                .filter(|q| q.bit_perfect)
+                .map(|q| q.quality)
+                .collect::<Vec<_>>()
+        ),
+        Err(e) => log::warn!("Format probe failed: {}", e),
+    }
+    if let Ok(mut guard) = PROBE_CACHE.lock() {
+        *guard = Some((device_id, result.clone()));
+    }
+    result
+}
+
+fn probe(host: &rodio::cpal::Host, device_id: &str) -> Result<DeviceFormatMatrix, String> {
+    let device = host
+        .output_devices()
+        .map_err(|e| format!("Failed to enumerate devices: {}", e))?
+        .find(|device| device.name().ok().as_deref() == Some(device_id))
+        .ok_or_else(|| format!("Device {} can't be probed directly", device_id))?;
+    // Fails while another application holds a hw: device exclusively
+    let ranges: Vec<ConfigRange> = device
+        .supported_output_configs()
+        .map_err(|e| format!("Failed to query {}: {}", device_id, e))?
+        .map(|range| ConfigRange::from_cpal(&range))
+        .collect();
+
+    let formats = expand_ranges(&ranges);
+    Ok(DeviceFormatMatrix {
+        device_id: device_id.to_string(),
+        qualities: quality_matrix(&formats),
+        formats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(channels: u16, min: u32, max: u32, bit_depth: u16) -> ConfigRange {
+        ConfigRange {
+            channels,
+            min_sample_rate: min,
+            max_sample_rate: max,
+            bit_depth,
+        }
+    }
+
+    fn support(matrix: &[QualitySupport], quality: Quality) -> &QualitySupport {
+        matrix.iter().find(|q| q.quality == quality).unwrap()
+    }
+
+    #[test]
+    fn discrete_96k_dac_maps_to_quality_matrix() {
+        // A typical USB DAC on hw: 16 and 32-bit stereo at discrete rates
+        // up to 96 kHz, plus an 8-channel mode
+        let mut ranges = Vec::new();
+        for rate in [44100, 48000, 88200, 96000] {
+            ranges.push(range(2, rate, rate, 16));
+            ranges.push(range(2, rate, rate, 32));
+        }
+        ranges.push(range(8, 192000, 192000, 32));
+
+        let formats = expand_ranges(&ranges);
+        assert_eq!(formats.len(), 9);
+        let matrix = quality_matrix(&formats);
+
+        let ultra = support(&matrix, Quality::UltraHiRes);
+        assert!(!ultra.bit_perfect);
+        assert!(ultra.native_rates.is_empty());
+        assert_eq!(ultra.unsupported_rates, vec![176400, 192000]);
+
+        let hires = support(&matrix, Quality::HiRes);
+        assert!(hires.bit_perfect);
+        assert_eq!(hires.native_rates, vec![44100, 48000, 88200, 96000]);
+
+        assert!(support(&matrix, Quality::Lossless).bit_perfect);
+        assert!(support(&matrix, Quality::Mp3).bit_perfect);
+        assert_eq!(matrix[0].quality, Quality::UltraHiRes);
+    }
+
+    #[test]
+    fn broad_ranges_expand_to_standard_rates() {
+        // 16-bit only over a continuous range: hi-res gets truncated
+        let formats = expand_ranges(&[range(2, 8000, 192000, 16)]);
+        let rates: Vec<u32> = formats.iter().map(|f| f.sample_rate).collect();
+        assert_eq!(rates, vec![44100, 48000, 88200, 96000, 176400, 192000]);
+
+        let matrix = quality_matrix(&formats);
+        assert!(support(&matrix, Quality::Lossless).bit_perfect);
+        let hires = support(&matrix, Quality::HiRes);
+        assert!(!hires.bit_perfect);
+        assert_eq!(hires.unsupported_rates.len(), 4);
+    }
+
+    #[test]
+    fn unsupported_track_rate_falls_back_to_next_tier() {
+        // 24-bit stereo DAC that tops out at 96 kHz
+        let formats = expand_ranges(&[
+            range(2, 44100, 44100, 24),
+            range(2, 48000, 48000, 24),
+            range(2, 88200, 88200, 24),
+            range(2, 96000, 96000, 24),
+        ]);
+
+        // 192k master: Hi-Res+ would need 192k, Hi-Res delivers it at 96k
+        assert_eq!(
+            quality_for_track(Quality::UltraHiRes, 192000, &formats),
+            Quality::HiRes
+        );
+        // 176.4k master: Hi-Res keeps the 44.1k family at 88.2k
+        assert_eq!(
+            quality_for_track(Quality::UltraHiRes, 176400, &formats),
+            Quality::HiRes
+        );
+        // A 96k master plays natively at the top tier
+        assert_eq!(
+            quality_for_track(Quality::UltraHiRes, 96000, &formats),
+            Quality::UltraHiRes
+        );
+        // The user's preference stays the upper bound
+        assert_eq!(
+            quality_for_track(Quality::Lossless, 192000, &formats),
+            Quality::Lossless
+        );
+
+        // 48k-only device: no tier delivers a 192k master at 48k, so the
+        // preference is kept rather than dropping to a tier that resamples too
+        let formats_48k = expand_ranges(&[range(2, 48000, 48000, 24)]);
+        assert_eq!(
+            quality_for_track(Quality::UltraHiRes, 192000, &formats_48k),
+            Quality::UltraHiRes
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Output device hotplug detection
+//!
+//! Polls the configured backend's device list, reports devices being added or
+//! removed, and tracks when the selected output device disappears (e.g. a USB
+//! DAC is unplugged) or comes back.
+//! When playing on the system default output of PipeWire/PulseAudio, it also
+//! watches the default sink so a route change (Bluetooth headphones dropping
+//! out) can pause playback instead of moving it to the speakers.
+//! The polling thread itself lives in the app setup, which owns the player.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::{normalize_device_id_to_stable, AudioBackendType, BackendManager};
+
+/// How often the device list is polled
+pub const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Ids of the output devices the backend currently lists.
+///
+/// Returns None when the backend cannot enumerate devices, so a transient
+/// enumeration failure is not mistaken for unplugged devices.
+pub fn list_devices(backend_type: Option<AudioBackendType>) -> Option<Vec<String>> {
+    let backend = BackendManager::create_backend(backend_type.unwrap_or_default()).ok()?;
+    let devices = backend.enumerate_devices().ok()?;
+    Some(devices.into_iter().map(|d| d.id).collect())
+}
+
+/// Whether `device_id` is in a device list from [`list_devices`]
+pub fn is_device_present(devices: &[String], device_id: &str) -> bool {
+    let wanted = normalize_device_id_to_stable(device_id);
+    devices
+        .iter()
+        .any(|id| id == device_id || normalize_device_id_to_stable(id) == wanted)
+}
+
+/// Name of the PipeWire/PulseAudio default sink.
+///
+/// Returns None for backends that do not route through the sound server, or
+/// when the server cannot be queried.
+pub fn default_sink(backend_type: Option<AudioBackendType>) -> Option<String> {
+    if backend_type.unwrap_or_default() == AudioBackendType::Alsa {
+        return None;
+    }
+    let output = Command::new("pactl")
+        .arg("get-default-sink")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let sink = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!sink.is_empty()).then_some(sink)
+}
+
+/// Whether a `pactl subscribe` line can mean the default route changed
+fn is_route_event(line: &str) -> bool {
+    line.contains("on server") || line.contains("on sink #")
+}
+
+/// Wake the monitor as soon as the sound server reports a default-sink or
+/// sink change, rather than waiting for the next poll.
+///
+/// Runs `pactl subscribe` on its own thread until the receiver goes away.
+/// Returns false if it could not be started (no pactl); polling still works.
+pub fn spawn_route_listener(wake: Sender<()>) -> bool {
+    let mut child = match Command::new("pactl")
+        .arg("subscribe")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            log::debug!("Route listener unavailable: {}", e);
+            return false;
+        }
+    };
+    let Some(stdout) = child.stdout.take() else {
+        let _ = child.kill();
+        return false;
+    };
+
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if is_route_event(&line) && wake.send(()).is_err() {
+                break;
+            }
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    });
+    true
+}
+
+/// Presence transition of the watched device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceChange {
+    Disappeared,
+    Reappeared,
+}
+
+/// Tracks the watched device's presence between polls
+#[derive(Debug, Default)]
+pub struct DeviceWatch {
+    device_id: Option<String>,
+    present: Option<bool>,
+}
+
+impl DeviceWatch {
+    /// Feed a poll result; returns the transition, if any.
+    ///
+    /// Switching to a different device resets the watch without reporting a change.
+    pub fn update(&mut self, device_id: &str, present: Option<bool>) -> Option<DeviceChange> {
+        if self.device_id.as_deref() != Some(device_id) {
+            self.device_id = Some(device_id.to_string());
+            self.present = present;
+            return None;
+        }
+
+        let present = present?;
+        let change = match (self.present, present) {
+            (Some(true), false) => Some(DeviceChange::Disappeared),
+            (Some(false), true) => Some(DeviceChange::Reappeared),
+            _ => None,
+        };
+        self.present = Some(present);
+        change
+    }
+
+    /// Stop watching (no device selected)
+    pub fn reset(&mut self) {
+        self.device_id = None;
+        self.present = None;
+    }
+}
+
+/// Payload of the `audio:devices_changed` event: device ids that appeared
+/// and disappeared since the previous poll
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeviceListDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl DeviceListDiff {
+    pub fn between(previous: &[String], current: &[String]) -> Self {
+        Self {
+            added: current
+                .iter()
+                .filter(|id| !previous.contains(id))
+                .cloned()
+                .collect(),
+            removed: previous
+                .iter()
+                .filter(|id| !current.contains(id))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Tracks the backend's device list between polls
+#[derive(Debug, Default)]
+pub struct DeviceListWatch {
+    backend_type: Option<AudioBackendType>,
+    devices: Option<Vec<String>>,
+}
+
+impl DeviceListWatch {
+    /// Feed a poll result; returns what changed, if anything.
+    ///
+    /// A failed enumeration is ignored, and switching backends resets the
+    /// watch without reporting the other backend's devices as changes.
+    pub fn update(
+        &mut self,
+        backend_type: Option<AudioBackendType>,
+        devices: Option<&[String]>,
+    ) -> Option<DeviceListDiff> {
+        if self.backend_type != backend_type {
+            self.backend_type = backend_type;
+            self.devices = None;
+        }
+        let devices = devices?;
+        let previous = self.devices.replace(devices.to_vec())?;
+        let diff = DeviceListDiff::between(&previous, devices);
+        (!diff.is_empty()).then_some(diff)
+    }
+}
+
+/// Default-sink transition while playing on the system default output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteChange {
+    /// The default sink switched away from `from`, the sink we were playing on
+    Away { from: String, to: String },
+    /// The default sink switched back to the one we were playing on
+    Back { sink: String },
+}
+
+/// Tracks the default sink between polls
+#[derive(Debug, Default)]
+pub struct RouteWatch {
+    current: Option<String>,
+    /// Sink we were on before the route moved away, until it comes back
+    original: Option<String>,
+}
+
+impl RouteWatch {
+    /// Feed the current default sink; returns the transition, if any.
+    ///
+    /// An unknown sink (query failed) is ignored rather than treated as a change.
+    pub fn update(&mut self, sink: Option<&str>) -> Option<RouteChange> {
+        let sink = sink?;
+        let current = self.current.replace(sink.to_string())?;
+        if current == sink {
+            return None;
+        }
+
+        if self.original.as_deref() == Some(sink) {
+            self.original = None;
+            return Some(RouteChange::Back {
+                sink: sink.to_string(),
+            });
+        }
+        let from = self.original.get_or_insert(current).clone();
+        Some(RouteChange::Away {
+            from,
+            to: sink.to_string(),
+        })
+    }
+
+    /// Forget the route (an explicit device is selected, or playback moved on)
+    pub fn reset(&mut self) {
+        self.current = None;
+        self.original = None;
+    }
+
+    /// Accept the current default sink as the one we play on
+    pub fn settle(&mut self) {
+        self.original = None;
+    }
+}
+
+/// Payload of the `audio:device-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceChangedEvent {
+    pub device: String,
+    pub available: bool,
+    /// True when playback was resumed automatically after reconnecting
+    pub resumed: bool,
+    /// True while the player is on the system default output because
+    /// `device` went missing
+    pub fallback_to_default: bool,
+}
+
+/// Payload of the `audio:route-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteChangedEvent {
+    /// The new default sink
+    pub sink: String,
+    /// True when the route switched back to the sink we were playing on
+    pub returned: bool,
+    /// True when playback was paused because of this change
+    pub paused: bool,
+    /// True when playback was resumed automatically because of this change
+    pub resumed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_unplug_and_replug() {
+        let mut watch = DeviceWatch::default();
+        assert_eq!(watch.update("hw:1,0", Some(true)), None);
+        assert_eq!(watch.update("hw:1,0", None), None);
+        assert_eq!(watch.update("hw:1,0", Some(false)), Some(DeviceChange::Disappeared));
+        assert_eq!(watch.update("hw:1,0", Some(false)), None);
+        assert_eq!(watch.update("hw:1,0", Some(true)), Some(DeviceChange::Reappeared));
+    }
+
+    #[test]
+    fn reports_route_away_and_back() {
+        let mut watch = RouteWatch::default();
+        assert_eq!(watch.update(Some("bluez_output.headphones")), None);
+        assert_eq!(watch.update(None), None);
+        assert_eq!(
+            watch.update(Some("alsa_output.speakers")),
+            Some(RouteChange::Away {
+                from: "bluez_output.headphones".into(),
+                to: "alsa_output.speakers".into()
+            })
+        );
+        // Hopping to another sink still reports the original one
+        assert_eq!(
+            watch.update(Some("alsa_output.hdmi")),
+            Some(RouteChange::Away {
+                from: "bluez_output.headphones".into(),
+                to: "alsa_output.hdmi".into()
+            })
+        );
+        assert_eq!(
+            watch.update(Some("bluez_output.headphones")),
+            Some(RouteChange::Back {
+                sink: "bluez_output.headphones".into()
+            })
+        );
+        assert_eq!(watch.update(Some("bluez_output.headphones")), None);
+    }
+
+    #[test]
+    fn settled_route_is_not_reported_back() {
+        let mut watch = RouteWatch::default();
+        watch.update(Some("a"));
+        watch.update(Some("b"));
+        watch.settle();
+        assert!(matches!(watch.update(Some("a")), Some(RouteChange::Away { .. })));
+        assert!(is_route_event("Event 'change' on server #4294967295"));
+        assert!(is_route_event("Event 'remove' on sink #57"));
+        assert!(!is_route_event("Event 'change' on sink-input #60"));
+    }
+
+    #[test]
+    fn device_list_diff_reports_added_and_removed() {
+        let ids = |list: &[&str]| list.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let before = ids(&["default", "hw:0,0", "hw:1,0"]);
+        let after = ids(&["default", "hw:0,0", "hw:2,0", "hw:3,0"]);
+
+        let diff = DeviceListDiff::between(&before, &after);
+        assert_eq!(diff.added, ids(&["hw:2,0", "hw:3,0"]));
+        assert_eq!(diff.removed, ids(&["hw:1,0"]));
+        assert!(DeviceListDiff::between(&after, &after).is_empty());
+
+        let mut watch = DeviceListWatch::default();
+        let alsa = Some(AudioBackendType::Alsa);
+        assert_eq!(watch.update(alsa, Some(&before[..])), None);
+        // A failed enumeration is not an unplug
+        assert_eq!(watch.update(alsa, None), None);
+        assert_eq!(watch.update(alsa, Some(&before[..])), None);
+        assert_eq!(watch.update(alsa, Some(&after[..])), Some(diff));
+        // Another backend lists different ids; that is not a hotplug
+        let pipewire = Some(AudioBackendType::PipeWire);
+        assert_eq!(watch.update(pipewire, Some(&before[..])), None);
+    }
+
+    #[test]
+    fn switching_device_does_not_report_change() {
+        let mut watch = DeviceWatch::default();
+        watch.update("hw:1,0", Some(true));
+        assert_eq!(watch.update("hw:2,0", Some(false)), None);
+    }
+}
@@ -6,14 +6,20 @@
 //!
 //! Works for both rodio (PipeWire/ALSA via CPAL) and ALSA Direct paths
 //! via a transparent Source wrapper.
+//!
+//! Also collects xrun (underrun) counters reported by the ALSA Direct
+//! output, so users can tell a too-small buffer from a starved CPU.
 
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use rodio::Source;
 use serde::Serialize;
 
+use super::AlsaPathReport;
+
 // ---------------------------------------------------------------------------
 // Shared diagnostic state (atomics — safe to clone across threads)
 // ---------------------------------------------------------------------------
@@ -25,8 +31,20 @@ pub struct AudioDiagnostic {
     sample_count: Arc<AtomicU64>,
     sample_rate: Arc<AtomicU32>,
     channels: Arc<AtomicU32>,
+    // Xrun tracking (ALSA Direct)
+    underruns: Arc<AtomicU64>,
+    recoveries: Arc<AtomicU64>,
+    recovery_failures: Arc<AtomicU64>,
+    buffer_frames: Arc<AtomicU32>,
+    period_frames: Arc<AtomicU32>,
+    recent_events: Arc<Mutex<VecDeque<XrunEvent>>>,
+    // ALSA path of the stream in use (None for non-ALSA-Direct streams)
+    alsa_path: Arc<Mutex<Option<AlsaPathReport>>>,
 }
 
+/// Maximum number of xrun events kept for the diagnostics report
+const MAX_XRUN_EVENTS: usize = 32;
+
 impl AudioDiagnostic {
     pub fn new() -> Self {
         Self {
@@ -35,6 +53,13 @@ impl AudioDiagnostic {
             sample_count: Arc::new(AtomicU64::new(0)),
             sample_rate: Arc::new(AtomicU32::new(0)),
             channels: Arc::new(AtomicU32::new(0)),
+            underruns: Arc::new(AtomicU64::new(0)),
+            recoveries: Arc::new(AtomicU64::new(0)),
+            recovery_failures: Arc::new(AtomicU64::new(0)),
+            buffer_frames: Arc::new(AtomicU32::new(0)),
+            period_frames: Arc::new(AtomicU32::new(0)),
+            recent_events: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_XRUN_EVENTS))),
+            alsa_path: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -107,6 +132,83 @@ impl AudioDiagnostic {
             effective_bits,
         }
     }
+
+    /// Record the buffer geometry of the stream currently in use.
+    pub fn set_stream_geometry(&self, buffer_frames: u32, period_frames: u32) {
+        self.buffer_frames.store(buffer_frames, Ordering::SeqCst);
+        self.period_frames.store(period_frames, Ordering::SeqCst);
+    }
+
+    /// Record which ALSA path the stream in use took
+    pub fn set_alsa_path(&self, report: Option<AlsaPathReport>) {
+        if let Ok(mut path) = self.alsa_path.lock() {
+            *path = report;
+        }
+    }
+
+    pub fn alsa_path(&self) -> Option<AlsaPathReport> {
+        self.alsa_path.lock().ok().and_then(|path| path.clone())
+    }
+
+    /// Record a PCM write error and whether `snd_pcm_recover` succeeded.
+    ///
+    /// `underrun` is true for EPIPE (buffer ran dry); other errors (e.g. ESTRPIPE
+    /// after a system suspend) are still logged as recovery events.
+    pub fn record_xrun(&self, errno: i32, underrun: bool, recovered: bool) {
+        if underrun {
+            self.underruns.fetch_add(1, Ordering::Relaxed);
+        }
+        if recovered {
+            self.recoveries.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.recovery_failures.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        if let Ok(mut events) = self.recent_events.lock() {
+            if events.len() >= MAX_XRUN_EVENTS {
+                events.pop_front();
+            }
+            events.push_back(XrunEvent {
+                timestamp_ms,
+                errno,
+                underrun,
+                recovered,
+            });
+        }
+    }
+
+    /// Snapshot of xrun counters and recent recovery events.
+    pub fn xrun_report(&self) -> XrunReport {
+        let recent_events = self
+            .recent_events
+            .lock()
+            .map(|events| events.iter().cloned().collect())
+            .unwrap_or_default();
+
+        XrunReport {
+            underruns: self.underruns.load(Ordering::SeqCst),
+            recoveries: self.recoveries.load(Ordering::SeqCst),
+            recovery_failures: self.recovery_failures.load(Ordering::SeqCst),
+            buffer_frames: self.buffer_frames.load(Ordering::SeqCst),
+            period_frames: self.period_frames.load(Ordering::SeqCst),
+            recent_events,
+        }
+    }
+
+    /// Clear xrun counters and the event log (geometry is kept).
+    pub fn reset_xruns(&self) {
+        self.underruns.store(0, Ordering::SeqCst);
+        self.recoveries.store(0, Ordering::SeqCst);
+        self.recovery_failures.store(0, Ordering::SeqCst);
+        if let Ok(mut events) = self.recent_events.lock() {
+            events.clear();
+        }
+    }
 }
 
 impl Default for AudioDiagnostic {
@@ -131,6 +233,30 @@ pub struct BitDepthResult {
     pub effective_bits: u32,
 }
 
+/// A single PCM error seen by the ALSA Direct writer
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrunEvent {
+    pub timestamp_ms: u64,
+    pub errno: i32,
+    pub underrun: bool,
+    pub recovered: bool,
+}
+
+/// Xrun counters returned by `get_audio_diagnostics`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrunReport {
+    pub underruns: u64,
+    pub recoveries: u64,
+    pub recovery_failures: u64,
+    /// Hardware buffer size in frames (0 = no ALSA Direct stream yet)
+    pub buffer_frames: u32,
+    /// Hardware period size in frames (0 = no ALSA Direct stream yet)
+    pub period_frames: u32,
+    pub recent_events: Vec<XrunEvent>,
+}
+
 // ---------------------------------------------------------------------------
 // Source wrapper — transparent tap for bit-depth capture
 // ---------------------------------------------------------------------------
@@ -0,0 +1,410 @@
+//! Parametric equalizer
+//!
+//! Peaking biquad filters (RBJ cookbook) applied to the decoded f32 samples,
+//! plus a master preamp. The filter chain reads its configuration from a
+//! shared `EqControl` and rebuilds coefficients when it changes, so edits
+//! take effect mid-track without reopening the stream.
+//!
+//! EQ alters every sample, so it breaks bit-perfect output. On bit-perfect
+//! paths (ALSA Direct, DAC passthrough) it is bypassed unless the user sets
+//! `apply_when_bit_perfect`.
+
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use rodio::Source;
+use serde::{Deserialize, Serialize};
+
+use super::db_to_linear;
+
+/// Maximum number of bands in a config
+pub const MAX_EQ_BANDS: usize = 16;
+
+/// One peaking band
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EqBand {
+    /// Center frequency in Hz
+    pub frequency: f32,
+    /// Boost/cut in dB
+    pub gain_db: f32,
+    /// Bandwidth (higher = narrower)
+    pub q: f32,
+}
+
+/// Equalizer configuration persisted in `AudioSettings`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EqConfig {
+    /// False = bypass
+    #[serde(default)]
+    pub enabled: bool,
+    /// Master gain applied before the bands (use a negative value to leave
+    /// headroom for boosts)
+    #[serde(default)]
+    pub preamp_db: f32,
+    #[serde(default)]
+    pub bands: Vec<EqBand>,
+    /// Apply EQ even on bit-perfect paths (ALSA Direct / DAC passthrough)
+    #[serde(default)]
+    pub apply_when_bit_perfect: bool,
+}
+
+impl Default for EqConfig {
+    fn default() -> Self {
+        Self::preset(EqPreset::Flat)
+    }
+}
+
+/// Built-in starting points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EqPreset {
+    Flat,
+    BassBoost,
+    Vocal,
+}
+
+impl EqPreset {
+    pub const ALL: [EqPreset; 3] = [EqPreset::Flat, EqPreset::BassBoost, EqPreset::Vocal];
+}
+
+/// A preset with its configuration, for the frontend preset picker
+#[derive(Debug, Clone, Serialize)]
+pub struct NamedEqPreset {
+    pub preset: EqPreset,
+    pub config: EqConfig,
+}
+
+fn band(frequency: f32, gain_db: f32, q: f32) -> EqBand {
+    EqBand { frequency, gain_db, q }
+}
+
+impl EqConfig {
+    /// Preset configuration (disabled, so selecting a preset does not
+    /// silently break bit-perfect playback)
+    pub fn preset(preset: EqPreset) -> Self {
+        let (preamp_db, bands) = match preset {
+            EqPreset::Flat => (0.0, Vec::new()),
+            EqPreset::BassBoost => (
+                -6.0,
+                vec![band(60.0, 6.0, 0.7), band(150.0, 3.0, 0.9)],
+            ),
+            EqPreset::Vocal => (
+                -4.0,
+                vec![
+                    band(250.0, -2.0, 1.0),
+                    band(1500.0, 3.0, 1.0),
+                    band(3000.0, 4.0, 1.0),
+                    band(8000.0, 1.0, 1.0),
+                ],
+            ),
+        };
+        Self {
+            enabled: false,
+            preamp_db,
+            bands,
+            apply_when_bit_perfect: false,
+        }
+    }
+
+    /// Clamp values to sane ranges and drop excess bands
+    pub fn sanitized(mut self) -> Self {
+        self.preamp_db = self.preamp_db.clamp(-24.0, 12.0);
+        self.bands.truncate(MAX_EQ_BANDS);
+        for b in &mut self.bands {
+            b.frequency = b.frequency.clamp(20.0, 20000.0);
+            b.gain_db = b.gain_db.clamp(-24.0, 24.0);
+            b.q = b.q.clamp(0.1, 10.0);
+        }
+        self
+    }
+
+    /// True when the config changes the signal at all
+    pub fn is_effective(&self) -> bool {
+        self.enabled
+            && (self.preamp_db != 0.0 || self.bands.iter().any(|b| b.gain_db != 0.0))
+    }
+}
+
+/// Shared EQ state between the player and the filter chain in the audio path
+#[derive(Debug)]
+pub struct EqControl {
+    config: RwLock<EqConfig>,
+    /// True when the current output is a bit-perfect path
+    bit_perfect_output: AtomicBool,
+    /// Bumped on every change so filters know to rebuild
+    generation: AtomicU64,
+}
+
+impl EqControl {
+    pub fn new(config: EqConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            bit_perfect_output: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> EqConfig {
+        self.config.read().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: EqConfig) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config;
+        }
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn set_bit_perfect_output(&self, bit_perfect: bool) {
+        if self.bit_perfect_output.swap(bit_perfect, Ordering::SeqCst) != bit_perfect {
+            self.generation.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// True when EQ is actually being applied to the output
+    pub fn is_active(&self) -> bool {
+        let config = self.config();
+        config.is_effective()
+            && (config.apply_when_bit_perfect || !self.bit_perfect_output.load(Ordering::SeqCst))
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+}
+
+/// Normalized biquad coefficients
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+impl Biquad {
+    /// RBJ peaking EQ; None when the band is a no-op or above Nyquist
+    fn peaking(band: &EqBand, sample_rate: u32) -> Option<Self> {
+        let fs = sample_rate as f64;
+        let freq = band.frequency as f64;
+        if band.gain_db == 0.0 || freq <= 0.0 || freq >= fs / 2.0 {
+            return None;
+        }
+
+        let a = 10f64.powf(band.gain_db as f64 / 40.0);
+        let w0 = 2.0 * PI * freq / fs;
+        let alpha = w0.sin() / (2.0 * band.q.max(0.1) as f64);
+        let cos_w0 = w0.cos();
+
+        let a0 = 1.0 + alpha / a;
+        Some(Self {
+            b0: (1.0 + alpha * a) / a0,
+            b1: (-2.0 * cos_w0) / a0,
+            b2: (1.0 - alpha * a) / a0,
+            a1: (-2.0 * cos_w0) / a0,
+            a2: (1.0 - alpha / a) / a0,
+        })
+    }
+}
+
+/// Per-channel filter memory (transposed direct form II)
+#[derive(Debug, Clone, Copy, Default)]
+struct BiquadState {
+    z1: f64,
+    z2: f64,
+}
+
+impl BiquadState {
+    #[inline]
+    fn process(&mut self, c: &Biquad, x: f64) -> f64 {
+        let y = c.b0 * x + self.z1;
+        self.z1 = c.b1 * x - c.a1 * y + self.z2;
+        self.z2 = c.b2 * x - c.a2 * y;
+        y
+    }
+}
+
+/// Source wrapper applying the equalizer
+pub struct Equalizer<S>
+where
+    S: Source<Item = f32>,
+{
+    inner: S,
+    control: Arc<EqControl>,
+    generation: u64,
+    active: bool,
+    preamp: f32,
+    filters: Vec<Biquad>,
+    /// `filters.len() * channels` states, band-major
+    states: Vec<BiquadState>,
+    channels: usize,
+    channel: usize,
+}
+
+impl<S> Equalizer<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, control: Arc<EqControl>) -> Self {
+        let channels = source.channels().max(1) as usize;
+        let mut eq = Self {
+            inner: source,
+            control,
+            generation: u64::MAX,
+            active: false,
+            preamp: 1.0,
+            filters: Vec::new(),
+            states: Vec::new(),
+            channels,
+            channel: 0,
+        };
+        eq.refresh();
+        eq
+    }
+
+    /// Rebuild coefficients if the config changed. Filter memory is kept when
+    /// the band count is unchanged so live edits do not click.
+    fn refresh(&mut self) {
+        let generation = self.control.generation();
+        if generation == self.generation {
+            return;
+        }
+        self.generation = generation;
+
+        self.active = self.control.is_active();
+        if !self.active {
+            self.filters.clear();
+            self.states.clear();
+            return;
+        }
+
+        let config = self.control.config();
+        let sample_rate = self.inner.sample_rate();
+        self.preamp = db_to_linear(config.preamp_db);
+        let filters: Vec<Biquad> = config
+            .bands
+            .iter()
+            .filter_map(|b| Biquad::peaking(b, sample_rate))
+            .collect();
+        if filters.len() != self.filters.len() {
+            self.states = vec![BiquadState::default(); filters.len() * self.channels];
+        }
+        self.filters = filters;
+    }
+}
+
+impl<S> Iterator for Equalizer<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // Only pick up config changes on frame boundaries
+        if self.channel == 0 {
+            self.refresh();
+        }
+
+        let sample = self.inner.next()?;
+        let channel = self.channel;
+        self.channel = (self.channel + 1) % self.channels;
+
+        if !self.active {
+            // Bypass: pass samples through untouched
+            return Some(sample);
+        }
+
+        let mut y = (sample * self.preamp) as f64;
+        for (i, coeffs) in self.filters.iter().enumerate() {
+            y = self.states[i * self.channels + channel].process(coeffs, y);
+        }
+        Some((y as f32).clamp(-1.0, 1.0))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S> Source for Equalizer<S>
+where
+    S: Source<Item = f32>,
+{
+    #[inline]
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    fn sine(freq: f32, sample_rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|n| 0.25 * (2.0 * std::f32::consts::PI * freq * n as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0f32, |m, s| m.max(s.abs()))
+    }
+
+    #[test]
+    fn bypass_is_bit_identical() {
+        let input = sine(1000.0, 48000, 4800);
+        let control = Arc::new(EqControl::new(EqConfig::preset(EqPreset::BassBoost)));
+        let output: Vec<f32> =
+            Equalizer::new(SamplesBuffer::new(1, 48000, input.clone()), control).collect();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn peaking_band_boosts_center_frequency() {
+        let input = sine(1000.0, 48000, 48000);
+        let config = EqConfig {
+            enabled: true,
+            preamp_db: 0.0,
+            bands: vec![band(1000.0, 6.0, 1.0)],
+            apply_when_bit_perfect: false,
+        };
+        let control = Arc::new(EqControl::new(config));
+        let output: Vec<f32> = Equalizer::new(SamplesBuffer::new(1, 48000, input.clone()), control).collect();
+        // Skip the filter's settling time
+        let gain = peak(&output[24000..]) / peak(&input[24000..]);
+        assert!((gain - db_to_linear(6.0)).abs() < 0.05, "gain {}", gain);
+    }
+
+    #[test]
+    fn bit_perfect_output_bypasses_unless_overridden() {
+        let mut config = EqConfig::preset(EqPreset::Vocal);
+        config.enabled = true;
+        let control = EqControl::new(config.clone());
+        control.set_bit_perfect_output(true);
+        assert!(!control.is_active());
+        config.apply_when_bit_perfect = true;
+        control.set_config(config);
+        assert!(control.is_active());
+    }
+}
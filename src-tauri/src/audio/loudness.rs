@@ -4,6 +4,7 @@
 //! for volume normalization. When normalization is disabled, this module is
 //! not invoked and the audio pipeline remains bit-perfect.
 
+use serde::{Deserialize, Serialize};
 use symphonia::core::formats::FormatReader;
 use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag, Value};
 use symphonia::core::formats::FormatOptions;
@@ -19,6 +20,66 @@ pub struct ReplayGainData {
     pub gain_db: f32,
     /// Peak sample value (0.0-1.0+), used for clipping prevention
     pub peak: Option<f32>,
+    /// Album gain in dB, keeps the relative loudness between an album's tracks
+    pub album_gain_db: Option<f32>,
+    /// Album peak sample value
+    pub album_peak: Option<f32>,
+}
+
+impl ReplayGainData {
+    /// Album-level gain and peak, if the file carries album gain
+    pub fn album(&self) -> Option<ReplayGainData> {
+        self.album_gain_db.map(|gain_db| ReplayGainData {
+            gain_db,
+            peak: self.album_peak.or(self.peak),
+            album_gain_db: self.album_gain_db,
+            album_peak: self.album_peak,
+        })
+    }
+}
+
+/// Which ReplayGain value normalization uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayGainMode {
+    /// Album gain while playing an album, track gain otherwise
+    #[default]
+    Auto,
+    Track,
+    Album,
+}
+
+impl ReplayGainMode {
+    /// Mode to use for the current playback context (never `Auto`)
+    pub fn resolve(self, album_context: bool) -> ReplayGainMode {
+        match self {
+            ReplayGainMode::Auto if album_context => ReplayGainMode::Album,
+            ReplayGainMode::Auto => ReplayGainMode::Track,
+            mode => mode,
+        }
+    }
+}
+
+/// ReplayGain values found in a file's tags
+#[derive(Default)]
+struct GainTags {
+    track_gain: Option<f32>,
+    track_peak: Option<f32>,
+    album_gain: Option<f32>,
+    album_peak: Option<f32>,
+}
+
+impl GainTags {
+    fn into_data(self) -> Option<ReplayGainData> {
+        // Files tagged with album gain only still get normalized
+        let gain_db = self.track_gain.or(self.album_gain)?;
+        Some(ReplayGainData {
+            gain_db,
+            peak: self.track_peak.or(self.album_peak),
+            album_gain_db: self.album_gain,
+            album_peak: self.album_peak,
+        })
+    }
 }
 
 /// Wrapper to make Cursor<Vec<u8>> implement MediaSource
@@ -53,9 +114,9 @@ impl MediaSource for CursorMediaSource {
 
 /// Extract ReplayGain metadata from raw audio file bytes.
 ///
-/// Searches for ReplayGain track gain and peak values in:
-/// - Vorbis comments (FLAC, Ogg): `REPLAYGAIN_TRACK_GAIN`, `REPLAYGAIN_TRACK_PEAK`
-/// - ID3v2 TXXX frames: `replaygain_track_gain`
+/// Searches for ReplayGain track and album gain/peak values in:
+/// - Vorbis comments (FLAC, Ogg): `REPLAYGAIN_TRACK_GAIN`, `REPLAYGAIN_ALBUM_GAIN`, ...
+/// - ID3v2 TXXX frames: `replaygain_track_gain`, `replaygain_album_gain`
 /// - Standard tag keys mapped by Symphonia
 ///
 /// Returns `None` if no ReplayGain metadata is found.
@@ -84,27 +145,28 @@ pub fn extract_replaygain(data: &[u8]) -> Option<ReplayGainData> {
     };
 
     // Collect all tags from both the probe metadata and the format reader metadata
-    let mut gain_db: Option<f32> = None;
-    let mut peak: Option<f32> = None;
+    let mut tags = GainTags::default();
 
     // Check probe-level metadata (container-level tags)
     if let Some(metadata) = probed.metadata.get() {
         if let Some(rev) = metadata.current() {
-            extract_from_tags(rev.tags(), &mut gain_db, &mut peak);
+            extract_from_tags(rev.tags(), &mut tags);
         }
     }
 
     // Check format-level metadata (in-stream tags, e.g., Vorbis comments in FLAC)
-    if gain_db.is_none() {
+    if tags.track_gain.is_none() {
         let fmt_metadata = probed.format.metadata();
         if let Some(rev) = fmt_metadata.current() {
-            extract_from_tags(rev.tags(), &mut gain_db, &mut peak);
+            extract_from_tags(rev.tags(), &mut tags);
         }
     }
 
-    gain_db.map(|db| {
-        log::info!("Loudness: found ReplayGain: {:.2} dB, peak: {:?}", db, peak);
-        ReplayGainData { gain_db: db, peak }
+    tags.into_data().inspect(|rg| {
+        log::info!(
+            "Loudness: found ReplayGain: {:.2} dB, peak: {:?}, album: {:?} dB",
+            rg.gain_db, rg.peak, rg.album_gain_db
+        );
     })
 }
 
@@ -113,59 +175,63 @@ pub fn extract_replaygain(data: &[u8]) -> Option<ReplayGainData> {
 /// This is used when we already have a probed format reader and don't want
 /// to re-probe the data.
 pub fn extract_replaygain_from_reader(format: &mut dyn FormatReader) -> Option<ReplayGainData> {
-    let mut gain_db: Option<f32> = None;
-    let mut peak: Option<f32> = None;
+    let mut tags = GainTags::default();
 
     let metadata = format.metadata();
     if let Some(rev) = metadata.current() {
-        extract_from_tags(rev.tags(), &mut gain_db, &mut peak);
+        extract_from_tags(rev.tags(), &mut tags);
     }
 
-    gain_db.map(|db| {
-        log::info!("Loudness: found ReplayGain (streaming): {:.2} dB, peak: {:?}", db, peak);
-        ReplayGainData { gain_db: db, peak }
+    tags.into_data().inspect(|rg| {
+        log::info!(
+            "Loudness: found ReplayGain (streaming): {:.2} dB, peak: {:?}, album: {:?} dB",
+            rg.gain_db, rg.peak, rg.album_gain_db
+        );
     })
 }
 
 /// Search tags for ReplayGain values.
-fn extract_from_tags(tags: &[Tag], gain_db: &mut Option<f32>, peak: &mut Option<f32>) {
+fn extract_from_tags(tags: &[Tag], found: &mut GainTags) {
     for tag in tags {
         // Check Symphonia's standard tag key mapping first
         if let Some(std_key) = tag.std_key {
             match std_key {
                 StandardTagKey::ReplayGainTrackGain => {
                     if let Some(g) = parse_gain_value(&tag.value) {
-                        *gain_db = Some(g);
+                        found.track_gain = Some(g);
                     }
                 }
                 StandardTagKey::ReplayGainTrackPeak => {
                     if let Some(p) = parse_peak_value(&tag.value) {
-                        *peak = Some(p);
+                        found.track_peak = Some(p);
                     }
                 }
-                _ => {}
-            }
-        }
-
-        // Also check raw tag keys (case-insensitive) for formats where
-        // Symphonia might not map to StandardTagKey
-        let key_lower = tag.key.to_lowercase();
-        match key_lower.as_str() {
-            "replaygain_track_gain" => {
-                if gain_db.is_none() {
+                StandardTagKey::ReplayGainAlbumGain => {
                     if let Some(g) = parse_gain_value(&tag.value) {
-                        *gain_db = Some(g);
+                        found.album_gain = Some(g);
                     }
                 }
-            }
-            "replaygain_track_peak" => {
-                if peak.is_none() {
+                StandardTagKey::ReplayGainAlbumPeak => {
                     if let Some(p) = parse_peak_value(&tag.value) {
-                        *peak = Some(p);
+                        found.album_peak = Some(p);
                     }
                 }
+                _ => {}
             }
-            _ => {}
+        }
+
+        // Also check raw tag keys (case-insensitive) for formats where
+        // Symphonia might not map to StandardTagKey
+        let key_lower = tag.key.to_lowercase();
+        let (slot, value) = match key_lower.as_str() {
+            "replaygain_track_gain" => (&mut found.track_gain, parse_gain_value(&tag.value)),
+            "replaygain_track_peak" => (&mut found.track_peak, parse_peak_value(&tag.value)),
+            "replaygain_album_gain" => (&mut found.album_gain, parse_gain_value(&tag.value)),
+            "replaygain_album_peak" => (&mut found.album_peak, parse_peak_value(&tag.value)),
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = value;
         }
     }
 }
@@ -283,7 +349,7 @@ mod tests {
     #[test]
     fn test_calculate_gain_factor_at_reference() {
         // At -18 LUFS target (ReplayGain reference), gain_db should pass through directly
-        let rg = ReplayGainData { gain_db: -3.0, peak: Some(0.9) };
+        let rg = ReplayGainData { gain_db: -3.0, peak: Some(0.9), album_gain_db: None, album_peak: None };
         let factor = calculate_gain_factor(&rg, -18.0);
         // -3 dB → ~0.708
         assert!((factor - 0.708).abs() < 0.01);
@@ -292,7 +358,7 @@ mod tests {
     #[test]
     fn test_calculate_gain_factor_with_target_adjustment() {
         // At -14 LUFS target, we add +4 dB to the RG gain
-        let rg = ReplayGainData { gain_db: -3.0, peak: Some(0.5) };
+        let rg = ReplayGainData { gain_db: -3.0, peak: Some(0.5), album_gain_db: None, album_peak: None };
         let factor = calculate_gain_factor(&rg, -14.0);
         // -3 + 4 = +1 dB → ~1.122
         assert!((factor - 1.122).abs() < 0.01);
@@ -301,7 +367,7 @@ mod tests {
     #[test]
     fn test_clipping_prevention_with_peak() {
         // High positive gain but peak close to 1.0 — should be capped
-        let rg = ReplayGainData { gain_db: 10.0, peak: Some(0.95) };
+        let rg = ReplayGainData { gain_db: 10.0, peak: Some(0.95), album_gain_db: None, album_peak: None };
         let factor = calculate_gain_factor(&rg, -18.0);
         // max_safe_gain = 1/0.95 ≈ 1.053, which is less than db_to_linear(10) ≈ 3.162
         assert!((factor - (1.0 / 0.95)).abs() < 0.01);
@@ -310,7 +376,7 @@ mod tests {
     #[test]
     fn test_clipping_prevention_without_peak() {
         // High gain without peak data — capped at +6 dB
-        let rg = ReplayGainData { gain_db: 12.0, peak: None };
+        let rg = ReplayGainData { gain_db: 12.0, peak: None, album_gain_db: None, album_peak: None };
         let factor = calculate_gain_factor(&rg, -18.0);
         assert!((factor - db_to_linear(6.0)).abs() < 0.01);
     }
@@ -327,6 +393,23 @@ mod tests {
         assert!((parse_gain_value(&Value::Float(-6.54)).unwrap() - (-6.54)).abs() < 0.001);
     }
 
+    #[test]
+    fn test_album_gain_and_mode() {
+        let rg = ReplayGainData { gain_db: -3.0, peak: Some(0.5), album_gain_db: Some(-6.0), album_peak: None };
+        let album = rg.album().unwrap();
+        assert_eq!(album.gain_db, -6.0);
+        // Falls back to the track peak for clipping prevention
+        assert_eq!(album.peak, Some(0.5));
+
+        let track_only = ReplayGainData { gain_db: -3.0, peak: None, album_gain_db: None, album_peak: None };
+        assert!(track_only.album().is_none());
+
+        assert_eq!(ReplayGainMode::Auto.resolve(true), ReplayGainMode::Album);
+        assert_eq!(ReplayGainMode::Auto.resolve(false), ReplayGainMode::Track);
+        assert_eq!(ReplayGainMode::Track.resolve(true), ReplayGainMode::Track);
+        assert_eq!(ReplayGainMode::Album.resolve(false), ReplayGainMode::Album);
+    }
+
     #[test]
     fn test_parse_peak_value() {
         assert!((parse_peak_value(&Value::String("0.988553".to_string())).unwrap() - 0.988553).abs() < 0.0001);
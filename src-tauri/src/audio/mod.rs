@@ -9,11 +9,17 @@ pub mod alsa_backend;
 pub mod pulse_backend;
 pub mod alsa_direct;
 pub mod diagnostic;
+pub mod bitperfect;
+pub mod capabilities;
+pub mod device_monitor;
 pub mod loudness;
 pub mod dynamic_amplify;
+pub mod silence_trim;
+pub mod equalizer;
 pub mod analyzer_tap;
 pub mod loudness_cache;
 pub mod loudness_analyzer;
+pub mod waveform;
 
 // Re-export commonly used types
 pub use backend::{
@@ -24,12 +30,24 @@ pub use backend::{
     BackendConfig,
     BackendManager,
     BackendResult,
+    buffer_latency_ms,
+    buffer_size_frames,
+    resolve_buffer_size,
 };
-pub use alsa_direct::AlsaDirectStream;
+pub use alsa_direct::{AlsaDirectStream, AlsaPathReport};
 pub use alsa_backend::{normalize_device_id_to_stable, resolve_stable_to_current_hw};
-pub use diagnostic::{AudioDiagnostic, DiagnosticSource, BitDepthResult};
-pub use loudness::{ReplayGainData, extract_replaygain, calculate_gain_factor, db_to_linear};
+pub use bitperfect::{BitPerfectInputs, BitPerfectStatus};
+pub use capabilities::{DeviceFormatMatrix, QualitySupport};
+pub use device_monitor::{
+    DeviceChange, DeviceChangedEvent, DeviceListDiff, DeviceListWatch, DeviceWatch, RouteChange,
+    RouteChangedEvent, RouteWatch, DEVICE_POLL_INTERVAL,
+};
+pub use diagnostic::{AudioDiagnostic, DiagnosticSource, BitDepthResult, XrunEvent, XrunReport};
+pub use loudness::{ReplayGainData, ReplayGainMode, extract_replaygain, calculate_gain_factor, db_to_linear};
 pub use dynamic_amplify::DynamicAmplify;
+pub use silence_trim::{SilenceTrim, TrimState};
+pub use equalizer::{EqBand, EqConfig, EqControl, EqPreset, Equalizer, NamedEqPreset};
 pub use analyzer_tap::{AnalyzerTap, AnalyzerMessage};
 pub use loudness_cache::LoudnessCache;
 pub use loudness_analyzer::LoudnessAnalyzer;
+pub use waveform::{CueRange, TrackWaveform, WaveformCache, WaveformKey, WAVEFORM_POINTS};
@@ -145,7 +145,7 @@ impl AudioBackend for PipeWireBackend {
     fn create_output_stream(
         &self,
         config: &BackendConfig,
-    ) -> BackendResult<(OutputStream, OutputStreamHandle)> {
+    ) -> BackendResult<(OutputStream, OutputStreamHandle, BufferSize)> {
         let target_sink = config.device_id.clone();
 
         // Temporarily set default sink to target (if specified)
@@ -230,11 +230,7 @@ impl AudioBackend for PipeWireBackend {
         let stream_config = StreamConfig {
             channels: config.channels,
             sample_rate: SampleRate(config.sample_rate),
-            buffer_size: if config.exclusive_mode {
-                BufferSize::Fixed(512)  // Lower latency for exclusive mode
-            } else {
-                BufferSize::Default
-            },
+            buffer_size: BufferSize::Default,
         };
 
         // Check if device supports this configuration
@@ -243,12 +239,14 @@ impl AudioBackend for PipeWireBackend {
             .map_err(|e| format!("Failed to get supported configs: {}", e))?;
 
         let mut found_matching = false;
+        let mut supported_buffer: Option<SupportedBufferSize> = None;
         for range in supported_configs {
             if range.channels() == config.channels
                 && config.sample_rate >= range.min_sample_rate().0
                 && config.sample_rate <= range.max_sample_rate().0
             {
                 found_matching = true;
+                supported_buffer = Some(*range.buffer_size());
                 log::info!(
                     "[PipeWire Backend] Device supports {}Hz (range: {}-{}Hz)",
                     config.sample_rate,
@@ -274,13 +272,21 @@ impl AudioBackend for PipeWireBackend {
             SampleFormat::F32,
         );
 
+        // Apply the user's buffer size (or the exclusive-mode default), clamped to the device range
+        let buffer_size = crate::audio::resolve_buffer_size(
+            config.buffer_frames,
+            config.exclusive_mode,
+            supported_buffer.as_ref(),
+        );
+        log::info!("[PipeWire Backend] Buffer size: {:?}", buffer_size);
+
         // Create OutputStream with custom config
-        let stream = OutputStream::try_from_device_config(&device, supported_config)
+        let stream = OutputStream::try_from_device_config_with_buffer_size(&device, supported_config, buffer_size)
             .map_err(|e| format!("Failed to create output stream at {}Hz: {}", config.sample_rate, e))?;
 
         log::info!("[PipeWire Backend] Output stream created successfully at {}Hz", config.sample_rate);
 
-        Ok(stream)
+        Ok((stream.0, stream.1, buffer_size))
     }
 
     fn is_available(&self) -> bool {
@@ -5,6 +5,7 @@
 
 use super::backend::{AudioBackend, AudioBackendType, AudioDevice, BackendConfig, BackendResult};
 use super::pipewire_backend::PipeWireBackend;
+use rodio::cpal::BufferSize;
 use rodio::{OutputStream, OutputStreamHandle};
 
 pub struct PulseBackend {
@@ -33,7 +34,7 @@ impl AudioBackend for PulseBackend {
     fn create_output_stream(
         &self,
         config: &BackendConfig,
-    ) -> BackendResult<(OutputStream, OutputStreamHandle)> {
+    ) -> BackendResult<(OutputStream, OutputStreamHandle, BufferSize)> {
         // Delegate to PipeWire backend (same mechanism)
         self.inner.create_output_stream(config)
     }
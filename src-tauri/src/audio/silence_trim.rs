@@ -0,0 +1,365 @@
+//! Leading/trailing silence trimming at track boundaries.
+//!
+//! Frames where every channel stays below the threshold count as silent.
+//! Nothing here reads ahead on the audio thread: up to `MAX_TRIM` of leading
+//! silence is skipped when the source is built (on the player thread), and
+//! the trailing silence is found by [`scan_tail`] on a background thread over
+//! a second decode of the file. Once the scan is done, playback stops where
+//! the trailing silence starts.
+//!
+//! Stretches longer than `MAX_TRIM` are considered intentional (hidden tracks)
+//! and played as is. While `keep_trailing` is set (the next track continues the
+//! same album) trailing silence is kept, so albums stay gapless as mastered.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use rodio::Source;
+
+use super::db_to_linear;
+
+/// Longest silence trimmed at either end of a track
+pub const MAX_TRIM: Duration = Duration::from_secs(10);
+
+/// Silence at the end of a track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SilentTail {
+    /// First frame of the trailing silence
+    pub start_frame: u64,
+    /// Length of the trailing silence in frames
+    pub frames: u64,
+}
+
+/// Trimming state of one track, shared by its sources, the tail scan and
+/// the player
+pub struct TrimState {
+    /// Linear amplitude at or below which a sample counts as silent
+    threshold: f32,
+    sample_rate: AtomicU32,
+    lead_frames: AtomicU64,
+    tail_dropped_frames: AtomicU64,
+    tail: OnceLock<SilentTail>,
+}
+
+impl TrimState {
+    pub fn new(threshold_db: f32) -> Self {
+        Self {
+            threshold: db_to_linear(threshold_db),
+            sample_rate: AtomicU32::new(0),
+            lead_frames: AtomicU64::new(0),
+            tail_dropped_frames: AtomicU64::new(0),
+            tail: OnceLock::new(),
+        }
+    }
+
+    /// Milliseconds of silence skipped so far
+    pub fn trimmed_ms(&self) -> u64 {
+        let sample_rate = self.sample_rate.load(Ordering::Relaxed) as u64;
+        if sample_rate == 0 {
+            return 0;
+        }
+        let frames = self.lead_frames.load(Ordering::Relaxed)
+            + self.tail_dropped_frames.load(Ordering::Relaxed);
+        frames * 1000 / sample_rate
+    }
+
+    /// Decode `source` to its end and record its trailing silence.
+    /// Expensive: call off the audio and player threads.
+    pub fn scan(&self, source: impl Source<Item = f32>) {
+        if let Some(tail) = scan_tail(source, self.threshold) {
+            let _ = self.tail.set(tail);
+        }
+    }
+
+    fn is_silent(&self, frame: &[f32]) -> bool {
+        frame.iter().all(|s| s.abs() <= self.threshold)
+    }
+}
+
+/// Trailing silence of `source`, if there is any to trim
+///
+/// `threshold` is linear. Tracks that are silent throughout, or end in more
+/// than `MAX_TRIM` of silence, have nothing to trim.
+pub fn scan_tail(mut source: impl Source<Item = f32>, threshold: f32) -> Option<SilentTail> {
+    let channels = source.channels().max(1) as usize;
+    let max_frames = MAX_TRIM.as_secs() * source.sample_rate().max(1) as u64;
+
+    let mut frames = 0u64;
+    let mut audible_end = 0u64;
+    'frames: loop {
+        let mut silent = true;
+        for channel in 0..channels {
+            match source.next() {
+                Some(sample) => silent &= sample.abs() <= threshold,
+                None if channel == 0 => break 'frames,
+                None => break,
+            }
+        }
+        frames += 1;
+        if !silent {
+            audible_end = frames;
+        }
+    }
+
+    let trailing = frames - audible_end;
+    (audible_end > 0 && trailing > 0 && trailing <= max_frames).then_some(SilentTail {
+        start_frame: audible_end,
+        frames: trailing,
+    })
+}
+
+pub struct SilenceTrim<S>
+where
+    S: Source<Item = f32>,
+{
+    inner: S,
+    channels: usize,
+    sample_rate: u32,
+    keep_trailing: Arc<AtomicBool>,
+    state: Arc<TrimState>,
+    /// First audible frame, read while skipping the leading silence
+    first_frame: VecDeque<f32>,
+    /// Frame of the original track the next sample belongs to
+    frame_pos: u64,
+    /// Samples of the current frame already returned
+    frame_sample: usize,
+    ended: bool,
+}
+
+impl<S> SilenceTrim<S>
+where
+    S: Source<Item = f32>,
+{
+    /// Wrap `source`, which starts at `start_frame` of the track.
+    ///
+    /// With `trim_leading` the leading silence is read and dropped right
+    /// here, so build the source off the audio thread.
+    pub fn new(
+        mut source: S,
+        state: Arc<TrimState>,
+        start_frame: u64,
+        trim_leading: bool,
+        keep_trailing: Arc<AtomicBool>,
+    ) -> Self {
+        let channels = source.channels().max(1) as usize;
+        let sample_rate = source.sample_rate().max(1);
+        state.sample_rate.store(sample_rate, Ordering::Relaxed);
+
+        let mut first_frame = VecDeque::with_capacity(channels);
+        let mut skipped = 0u64;
+        if trim_leading {
+            let max_frames = MAX_TRIM.as_secs() * sample_rate as u64;
+            let mut frame = Vec::with_capacity(channels);
+            loop {
+                frame.clear();
+                frame.extend(source.by_ref().take(channels));
+                if frame.is_empty() {
+                    break;
+                }
+                if !state.is_silent(&frame) || skipped >= max_frames {
+                    first_frame.extend(frame.iter().copied());
+                    break;
+                }
+                skipped += 1;
+            }
+            state.lead_frames.store(skipped, Ordering::Relaxed);
+        }
+
+        Self {
+            inner: source,
+            channels,
+            sample_rate,
+            keep_trailing,
+            state,
+            first_frame,
+            frame_pos: start_frame + skipped,
+            frame_sample: 0,
+            ended: false,
+        }
+    }
+
+    /// Whether playback reached the trailing silence and should stop
+    fn reached_tail(&self) -> bool {
+        let Some(tail) = self.state.tail.get() else {
+            return false;
+        };
+        if self.frame_pos < tail.start_frame || self.keep_trailing.load(Ordering::Relaxed) {
+            return false;
+        }
+        let dropped = (tail.start_frame + tail.frames).saturating_sub(self.frame_pos);
+        self.state
+            .tail_dropped_frames
+            .store(dropped, Ordering::Relaxed);
+        true
+    }
+}
+
+impl<S> Iterator for SilenceTrim<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended {
+            return None;
+        }
+        if self.frame_sample == 0 && self.reached_tail() {
+            self.ended = true;
+            return None;
+        }
+
+        let sample = match self.first_frame.pop_front() {
+            Some(sample) => sample,
+            None => self.inner.next()?,
+        };
+        self.frame_sample += 1;
+        if self.frame_sample == self.channels {
+            self.frame_sample = 0;
+            self.frame_pos += 1;
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.first_frame.len(), None)
+    }
+}
+
+impl<S> Source for SilenceTrim<S>
+where
+    S: Source<Item = f32>,
+{
+    #[inline]
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> u16 {
+        self.channels as u16
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    const RATE: u32 = 100;
+
+    /// Stereo buffer: `lead` silent frames, `tone` loud frames, `tail` silent frames
+    fn track(lead: usize, tone: usize, tail: usize) -> SamplesBuffer<f32> {
+        let mut samples = vec![0.0001; lead * 2];
+        samples.extend(vec![0.5; tone * 2]);
+        samples.extend(vec![0.0; tail * 2]);
+        SamplesBuffer::new(2, RATE, samples)
+    }
+
+    /// Trim state with the tail of `source` already scanned
+    fn scanned(source: SamplesBuffer<f32>) -> Arc<TrimState> {
+        let state = Arc::new(TrimState::new(-60.0));
+        state.scan(source);
+        state
+    }
+
+    #[test]
+    fn test_trims_leading_and_trailing_silence() {
+        let state = scanned(track(50, 20, 80));
+        let trim = SilenceTrim::new(
+            track(50, 20, 80),
+            state.clone(),
+            0,
+            true,
+            Arc::new(AtomicBool::new(false)),
+        );
+        let out: Vec<f32> = trim.collect();
+        assert_eq!(out, vec![0.5; 40]);
+        assert_eq!(state.trimmed_ms(), 1300);
+    }
+
+    #[test]
+    fn test_plays_tail_until_scanned() {
+        // Without a finished scan the whole track is played
+        let state = Arc::new(TrimState::new(-60.0));
+        let trim = SilenceTrim::new(
+            track(0, 20, 30),
+            state,
+            0,
+            true,
+            Arc::new(AtomicBool::new(false)),
+        );
+        assert_eq!(trim.count(), (20 + 30) * 2);
+    }
+
+    #[test]
+    fn test_keeps_inner_and_album_silence() {
+        // Silence between two loud parts is always played
+        let mut samples = vec![0.5; 20];
+        samples.extend(vec![0.0; 40]);
+        samples.extend(vec![0.5; 20]);
+        let state = scanned(SamplesBuffer::new(2, RATE, samples.clone()));
+        let trim = SilenceTrim::new(
+            SamplesBuffer::new(2, RATE, samples.clone()),
+            state,
+            0,
+            true,
+            Arc::new(AtomicBool::new(false)),
+        );
+        assert_eq!(trim.collect::<Vec<f32>>(), samples);
+
+        // Album continuation: leading trim off, trailing silence kept
+        let trim = SilenceTrim::new(
+            track(5, 20, 30),
+            scanned(track(5, 20, 30)),
+            0,
+            false,
+            Arc::new(AtomicBool::new(true)),
+        );
+        assert_eq!(trim.count(), (5 + 20 + 30) * 2);
+    }
+
+    #[test]
+    fn test_seeked_source_stops_at_the_tail() {
+        // The source starts 10 frames into the tone
+        let mut samples = vec![0.5; 10 * 2];
+        samples.extend(vec![0.0; 30 * 2]);
+        let trim = SilenceTrim::new(
+            SamplesBuffer::new(2, RATE, samples),
+            scanned(track(0, 20, 30)),
+            10,
+            false,
+            Arc::new(AtomicBool::new(false)),
+        );
+        assert_eq!(trim.count(), 10 * 2);
+    }
+
+    #[test]
+    fn test_long_silence_is_kept() {
+        let max_frames = MAX_TRIM.as_secs() as usize * RATE as usize;
+        assert_eq!(scan_tail(track(0, 10, max_frames + 10), 0.001), None);
+        assert_eq!(
+            scan_tail(track(0, 10, max_frames), 0.001),
+            Some(SilentTail {
+                start_frame: 10,
+                frames: max_frames as u64,
+            })
+        );
+        // Nothing audible at all
+        assert_eq!(scan_tail(track(0, 0, 20), 0.001), None);
+    }
+}
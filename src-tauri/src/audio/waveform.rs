@@ -0,0 +1,588 @@
+//! Waveform overview for the seekbar
+//!
+//! Decodes a whole track once and reduces it to a fixed number of
+//! peak/RMS points. Results are kept in memory for the session; waveforms of
+//! local files are also persisted (by path and cue range, invalidated when
+//! the file's modification time or size changes) in
+//! `dirs::data_dir()/qbz/waveform_cache.db` so repeated plays are instant.
+//! Qobuz waveforms are not persisted since the audio itself is only cached
+//! temporarily.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::Cursor;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use symphonia::default::{get_codecs, get_probe};
+
+/// Number of points in a waveform
+pub const WAVEFORM_POINTS: usize = 1000;
+
+/// Frames reduced into one block while decoding (~12ms at 44.1kHz)
+const BLOCK_FRAMES: u64 = 512;
+
+/// Waveforms kept in memory (local and Qobuz combined)
+const MAX_MEMORY_ENTRIES: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackWaveform {
+    pub track_id: u64,
+    /// Peak amplitude per point, 0.0..=1.0
+    pub peaks: Vec<f32>,
+    /// RMS amplitude per point, 0.0..=1.0
+    pub rms: Vec<f32>,
+    pub duration_secs: f64,
+}
+
+/// Peak and energy of a run of decoded frames
+#[derive(Debug, Clone, Copy, Default)]
+struct Block {
+    peak: f32,
+    sum_sq: f64,
+    samples: u64,
+}
+
+impl Block {
+    fn merge(&mut self, other: &Block) {
+        self.peak = self.peak.max(other.peak);
+        self.sum_sq += other.sum_sq;
+        self.samples += other.samples;
+    }
+
+    fn rms(&self) -> f32 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        (self.sum_sq / self.samples as f64).sqrt() as f32
+    }
+}
+
+/// Reduce blocks to `points` peak/RMS values
+fn downsample(blocks: &[Block], points: usize) -> (Vec<f32>, Vec<f32>) {
+    if blocks.is_empty() || points == 0 {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mut peaks = Vec::with_capacity(points);
+    let mut rms = Vec::with_capacity(points);
+    for i in 0..points {
+        let start = i * blocks.len() / points;
+        let end = ((i + 1) * blocks.len() / points).clamp(start + 1, blocks.len());
+        let mut point = Block::default();
+        for block in &blocks[start..end] {
+            point.merge(block);
+        }
+        peaks.push(point.peak.min(1.0));
+        rms.push(point.rms().min(1.0));
+    }
+    (peaks, rms)
+}
+
+/// Decode `data` and build its waveform.
+///
+/// `range` limits the waveform to a cue track inside a larger file.
+pub fn compute_waveform(
+    track_id: u64,
+    data: &[u8],
+    range: Option<CueRange>,
+    points: usize,
+) -> Result<TrackWaveform, String> {
+    let mss = MediaSourceStream::new(Box::new(Cursor::new(data.to_vec())), Default::default());
+    let mut probed = get_probe()
+        .format(
+            &Hint::new(),
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Waveform probe failed: {}", e))?;
+
+    let track = probed
+        .format
+        .default_track()
+        .ok_or_else(|| "Waveform: no supported audio tracks".to_string())?;
+    let audio_track_id = track.id;
+    let mut decoder = get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Waveform decoder init failed: {}", e))?;
+
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut current = Block::default();
+    let mut frames_in_block = 0u64;
+    let mut total_frames = 0u64;
+    let mut position = 0u64;
+    let mut sample_rate = 0u32;
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match probed.format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(_)) => break,
+            Err(e) => return Err(format!("Waveform read error: {}", e)),
+        };
+        if packet.track_id() != audio_track_id {
+            continue;
+        }
+
+        let audio_buf = match decoder.decode(&packet) {
+            Ok(buf) => buf,
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(SymphoniaError::ResetRequired) => {
+                decoder.reset();
+                continue;
+            }
+            Err(e) => return Err(format!("Waveform decode error: {}", e)),
+        };
+
+        let spec = *audio_buf.spec();
+        let channels = spec.channels.count().max(1);
+        sample_rate = spec.rate;
+
+        let needs_buffer = sample_buf
+            .as_ref()
+            .is_none_or(|buf| buf.capacity() < audio_buf.capacity() * channels);
+        if needs_buffer {
+            sample_buf = Some(SampleBuffer::new(audio_buf.capacity() as u64, spec));
+        }
+        let buf = sample_buf.as_mut().expect("sample buffer allocated above");
+        buf.copy_interleaved_ref(audio_buf);
+
+        let (start_frame, end_frame) = match range {
+            Some(range) => range.frames(sample_rate),
+            None => (0, u64::MAX),
+        };
+        if position >= end_frame {
+            break;
+        }
+
+        for frame in buf.samples().chunks(channels) {
+            position += 1;
+            if position <= start_frame {
+                continue;
+            }
+            if position > end_frame {
+                break;
+            }
+            for &sample in frame {
+                current.peak = current.peak.max(sample.abs());
+                current.sum_sq += (sample as f64) * (sample as f64);
+            }
+            current.samples += frame.len() as u64;
+            frames_in_block += 1;
+            total_frames += 1;
+            if frames_in_block == BLOCK_FRAMES {
+                blocks.push(current);
+                current = Block::default();
+                frames_in_block = 0;
+            }
+        }
+    }
+
+    if frames_in_block > 0 {
+        blocks.push(current);
+    }
+    if blocks.is_empty() || sample_rate == 0 {
+        return Err("Waveform decode produced no audio".to_string());
+    }
+
+    let (peaks, rms) = downsample(&blocks, points);
+    Ok(TrackWaveform {
+        track_id,
+        peaks,
+        rms,
+        duration_secs: total_frames as f64 / sample_rate as f64,
+    })
+}
+
+fn encode_points(points: &[f32]) -> Vec<u8> {
+    points.iter().flat_map(|p| p.to_le_bytes()).collect()
+}
+
+fn decode_points(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+/// Part of a file covered by a cue track, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CueRange {
+    pub start_ms: u64,
+    /// None for the last track of the sheet, which runs to the end of the file
+    pub end_ms: Option<u64>,
+}
+
+impl CueRange {
+    pub fn from_secs(start_secs: f64, end_secs: Option<f64>) -> Self {
+        Self {
+            start_ms: (start_secs.max(0.0) * 1000.0).round() as u64,
+            end_ms: end_secs.map(|end| (end.max(0.0) * 1000.0).round() as u64),
+        }
+    }
+
+    /// First and last frame of the range at `sample_rate`
+    fn frames(&self, sample_rate: u32) -> (u64, u64) {
+        let to_frames = |ms: u64| ms * sample_rate as u64 / 1000;
+        (
+            to_frames(self.start_ms),
+            self.end_ms.map_or(u64::MAX, to_frames),
+        )
+    }
+}
+
+/// Cache key of a waveform
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WaveformKey {
+    Qobuz(u64),
+    /// Local files are keyed by path, since library ids are per user. Cue
+    /// tracks share a file and are told apart by their range.
+    Local {
+        path: String,
+        range: Option<CueRange>,
+    },
+}
+
+impl WaveformKey {
+    /// Row key in `local_file_waveform`: the path, plus the range for cue tracks
+    fn db_key(path: &str, range: Option<CueRange>) -> String {
+        match range {
+            None => path.to_string(),
+            Some(CueRange { start_ms, end_ms }) => format!(
+                "{}#cue={}-{}",
+                path,
+                start_ms,
+                end_ms.map_or_else(String::new, |end| end.to_string())
+            ),
+        }
+    }
+}
+
+struct MemoryEntry {
+    waveform: TrackWaveform,
+    /// File stamp of a local waveform when it was computed or loaded
+    stamp: Option<(i64, i64)>,
+}
+
+struct MemoryCache {
+    entries: HashMap<WaveformKey, MemoryEntry>,
+    order: VecDeque<WaveformKey>,
+}
+
+pub struct WaveformCache {
+    memory: Mutex<MemoryCache>,
+    /// Disk store for local tracks; None if the database couldn't be opened
+    conn: Option<Mutex<Connection>>,
+}
+
+impl WaveformCache {
+    pub fn new() -> Self {
+        let conn = match Self::open_db() {
+            Ok(conn) => Some(Mutex::new(conn)),
+            Err(e) => {
+                log::warn!("[WaveformCache] {}. Local waveforms won't be persisted.", e);
+                None
+            }
+        };
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Option<Mutex<Connection>>) -> Self {
+        Self {
+            memory: Mutex::new(MemoryCache {
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+            conn,
+        }
+    }
+
+    fn open_db() -> Result<Connection, String> {
+        let data_dir = dirs::data_dir()
+            .ok_or_else(|| "Could not determine data directory".to_string())?
+            .join("qbz");
+
+        std::fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+        let db_path = data_dir.join("waveform_cache.db");
+        let conn = Connection::open(&db_path)
+            .map_err(|e| format!("Failed to open waveform cache database: {}", e))?;
+
+        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
+            .map_err(|e| format!("Failed to enable WAL for waveform cache database: {}", e))?;
+        Self::create_tables(&conn)?;
+
+        log::info!("[WaveformCache] Opened at {}", db_path.display());
+        Ok(conn)
+    }
+
+    fn create_tables(conn: &Connection) -> Result<(), String> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS local_file_waveform (
+                file_path TEXT PRIMARY KEY,
+                peaks BLOB NOT NULL,
+                rms BLOB NOT NULL,
+                duration_secs REAL NOT NULL,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            )",
+        )
+        .map_err(|e| format!("Failed to create waveform table: {}", e))?;
+
+        // Migration: rows without a stamp never match and get recomputed
+        for column in ["file_mtime_ms INTEGER", "file_size INTEGER"] {
+            let _ = conn.execute(
+                &format!("ALTER TABLE local_file_waveform ADD COLUMN {}", column),
+                [],
+            );
+        }
+        Ok(())
+    }
+
+    /// Look up the waveform of `track_id`, falling back to disk for local
+    /// files. Local waveforms only match while the file is unchanged.
+    pub fn get(&self, track_id: u64, key: &WaveformKey) -> Option<TrackWaveform> {
+        let stamp = match key {
+            WaveformKey::Local { path, .. } => Some(file_stamp(path)?),
+            WaveformKey::Qobuz(_) => None,
+        };
+        let cached = {
+            let mut memory = self.memory.lock().ok()?;
+            match memory.entries.get(key) {
+                Some(entry) if entry.stamp == stamp => Some(entry.waveform.clone()),
+                Some(_) => {
+                    memory.entries.remove(key);
+                    memory.order.retain(|k| k != key);
+                    None
+                }
+                None => None,
+            }
+        };
+        let waveform = match (cached, key) {
+            (Some(waveform), _) => waveform,
+            (None, WaveformKey::Local { path, range }) => {
+                let waveform = self.load_local(path, *range)?;
+                self.remember(key.clone(), waveform.clone(), stamp);
+                waveform
+            }
+            (None, WaveformKey::Qobuz(_)) => return None,
+        };
+        Some(TrackWaveform {
+            track_id,
+            ..waveform
+        })
+    }
+
+    /// Store a freshly computed waveform
+    pub fn insert(&self, key: WaveformKey, waveform: TrackWaveform) {
+        let stamp = match &key {
+            WaveformKey::Local { path, range } => self.store_local(path, *range, &waveform),
+            WaveformKey::Qobuz(_) => None,
+        };
+        if matches!(key, WaveformKey::Local { .. }) && stamp.is_none() {
+            // The file is gone; nothing to validate a cached copy against
+            return;
+        }
+        self.remember(key, waveform, stamp);
+    }
+
+    fn remember(&self, key: WaveformKey, waveform: TrackWaveform, stamp: Option<(i64, i64)>) {
+        let Ok(mut memory) = self.memory.lock() else {
+            return;
+        };
+        let entry = MemoryEntry { waveform, stamp };
+        if memory.entries.insert(key.clone(), entry).is_none() {
+            memory.order.push_back(key);
+        }
+        while memory.order.len() > MAX_MEMORY_ENTRIES {
+            if let Some(oldest) = memory.order.pop_front() {
+                memory.entries.remove(&oldest);
+            }
+        }
+    }
+
+    fn load_local(&self, path: &str, range: Option<CueRange>) -> Option<TrackWaveform> {
+        let (mtime_ms, size) = file_stamp(path)?;
+        let conn = self.conn.as_ref()?.lock().ok()?;
+        conn.query_row(
+            "SELECT peaks, rms, duration_secs FROM local_file_waveform
+             WHERE file_path = ?1 AND file_mtime_ms = ?2 AND file_size = ?3",
+            params![WaveformKey::db_key(path, range), mtime_ms, size],
+            |row| {
+                let peaks: Vec<u8> = row.get(0)?;
+                let rms: Vec<u8> = row.get(1)?;
+                Ok(TrackWaveform {
+                    track_id: 0,
+                    peaks: decode_points(&peaks),
+                    rms: decode_points(&rms),
+                    duration_secs: row.get(2)?,
+                })
+            },
+        )
+        .ok()
+    }
+
+    /// Persist a local waveform; returns the file stamp it was stored under
+    fn store_local(
+        &self,
+        path: &str,
+        range: Option<CueRange>,
+        waveform: &TrackWaveform,
+    ) -> Option<(i64, i64)> {
+        let (mtime_ms, size) = file_stamp(path)?;
+        let Some(conn) = self.conn.as_ref().and_then(|conn| conn.lock().ok()) else {
+            return Some((mtime_ms, size));
+        };
+        let result = conn.execute(
+            "INSERT OR REPLACE INTO local_file_waveform
+                (file_path, peaks, rms, duration_secs, created_at, file_mtime_ms, file_size)
+             VALUES (?1, ?2, ?3, ?4, strftime('%s', 'now'), ?5, ?6)",
+            params![
+                WaveformKey::db_key(path, range),
+                encode_points(&waveform.peaks),
+                encode_points(&waveform.rms),
+                waveform.duration_secs,
+                mtime_ms,
+                size
+            ],
+        );
+        if let Err(e) = result {
+            log::warn!(
+                "[WaveformCache] Failed to store waveform for {}: {}",
+                path,
+                e
+            );
+        }
+        Some((mtime_ms, size))
+    }
+}
+
+/// Modification time (unix ms) and size of a local file; a persisted
+/// waveform only matches while both are unchanged
+fn file_stamp(path: &str) -> Option<(i64, i64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((modified.as_millis() as i64, metadata.len() as i64))
+}
+
+impl Default for WaveformCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(peak: f32) -> Block {
+        Block {
+            peak,
+            sum_sq: (peak as f64) * (peak as f64),
+            samples: 1,
+        }
+    }
+
+    #[test]
+    fn test_downsample_merges_blocks() {
+        let blocks: Vec<Block> = [0.1, 0.5, 0.2, 0.8].iter().map(|&p| block(p)).collect();
+        let (peaks, rms) = downsample(&blocks, 2);
+        assert_eq!(peaks, vec![0.5, 0.8]);
+        let expected = ((0.01f64 + 0.25) / 2.0).sqrt() as f32;
+        assert!((rms[0] - expected).abs() < 1e-6);
+
+        // Fewer blocks than points repeats blocks rather than leaving gaps
+        let (peaks, _) = downsample(&blocks, 8);
+        assert_eq!(peaks.len(), 8);
+        assert_eq!(peaks[7], 0.8);
+    }
+
+    #[test]
+    fn test_local_waveforms_persist() {
+        let conn = Connection::open_in_memory().unwrap();
+        WaveformCache::create_tables(&conn).unwrap();
+        let cache = WaveformCache::with_connection(Some(Mutex::new(conn)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.flac");
+        std::fs::write(&path, b"audio").unwrap();
+        let local = WaveformKey::Local {
+            path: path.to_string_lossy().to_string(),
+            range: None,
+        };
+        let waveform = TrackWaveform {
+            track_id: 7,
+            peaks: vec![0.25, 1.0],
+            rms: vec![0.125, 0.5],
+            duration_secs: 2.0,
+        };
+        cache.insert(local.clone(), waveform.clone());
+        cache.insert(WaveformKey::Qobuz(7), waveform.clone());
+
+        // Drop the in-memory copies; only the local one comes back from disk,
+        // under whatever id the library now gives the file
+        cache.memory.lock().unwrap().entries.clear();
+        assert_eq!(
+            cache.get(9, &local),
+            Some(TrackWaveform {
+                track_id: 9,
+                ..waveform
+            })
+        );
+        assert_eq!(cache.get(7, &WaveformKey::Qobuz(7)), None);
+
+        // A changed file no longer matches, in memory or on disk
+        std::fs::write(&path, b"re-encoded audio").unwrap();
+        assert_eq!(cache.get(9, &local), None);
+        cache.memory.lock().unwrap().entries.clear();
+        assert_eq!(cache.get(9, &local), None);
+    }
+
+    #[test]
+    fn test_cue_tracks_of_one_file_are_kept_apart() {
+        let conn = Connection::open_in_memory().unwrap();
+        WaveformCache::create_tables(&conn).unwrap();
+        let cache = WaveformCache::with_connection(Some(Mutex::new(conn)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("album.flac").to_string_lossy().to_string();
+        std::fs::write(&path, b"audio").unwrap();
+        let track = |start: f64, end: Option<f64>| WaveformKey::Local {
+            path: path.clone(),
+            range: Some(CueRange::from_secs(start, end)),
+        };
+        let waveform = |duration_secs: f64| TrackWaveform {
+            track_id: 1,
+            peaks: vec![0.5],
+            rms: vec![0.25],
+            duration_secs,
+        };
+        cache.insert(track(0.0, Some(180.0)), waveform(180.0));
+        cache.insert(track(180.0, None), waveform(240.0));
+        cache.memory.lock().unwrap().entries.clear();
+
+        let first = cache.get(1, &track(0.0, Some(180.0))).unwrap();
+        let last = cache.get(1, &track(180.0, None)).unwrap();
+        assert_eq!(first.duration_secs, 180.0);
+        assert_eq!(last.duration_secs, 240.0);
+        assert_eq!(cache.get(1, &track(60.0, Some(180.0))), None);
+    }
+
+    #[test]
+    fn test_cue_range_frames() {
+        let range = CueRange::from_secs(1.5, Some(2.0));
+        assert_eq!(range.frames(44_100), (66_150, 88_200));
+        assert_eq!(
+            CueRange::from_secs(3.0, None).frames(1000),
+            (3000, u64::MAX)
+        );
+    }
+}
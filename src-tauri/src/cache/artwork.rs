@@ -0,0 +1,326 @@
+//! Disk cache for remote artwork
+//!
+//! Covers are stored under their URL, so each size of a cover is a separate
+//! file (`{hash}_{size}.{ext}`), and the size label is kept in the name for the
+//! usage report. Least recently used files are deleted once the cache grows
+//! past its limit.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::api::artwork::split_cover_url;
+
+/// Default artwork cache size in MB
+pub const DEFAULT_ARTWORK_CACHE_MB: u32 = 200;
+/// Smallest artwork cache accepted
+pub const MIN_ARTWORK_CACHE_MB: u32 = 20;
+/// Largest artwork cache accepted
+pub const MAX_ARTWORK_CACHE_MB: u32 = 10 * 1024;
+
+/// Disk usage for one artwork size
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ArtworkSizeUsage {
+    /// Qobuz size label ("230", "600", "max") or "original" for other sources
+    pub size: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtworkCacheReport {
+    pub files: usize,
+    pub bytes_used: u64,
+    pub max_bytes: u64,
+    /// Largest usage first
+    pub by_size: Vec<ArtworkSizeUsage>,
+}
+
+struct CachedFile {
+    path: PathBuf,
+    size_label: String,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+pub struct ArtworkCache {
+    cache_dir: PathBuf,
+    max_size_bytes: AtomicU64,
+    /// Serializes writes and evictions
+    write_lock: Mutex<()>,
+}
+
+impl ArtworkCache {
+    pub fn new(max_size_bytes: u64) -> Result<Self, String> {
+        let cache_dir = dirs::cache_dir()
+            .ok_or("Could not determine cache directory")?
+            .join("qbz")
+            .join("remote_artwork");
+        Self::at(cache_dir, max_size_bytes)
+    }
+
+    fn at(cache_dir: PathBuf, max_size_bytes: u64) -> Result<Self, String> {
+        fs::create_dir_all(&cache_dir)
+            .map_err(|e| format!("Failed to create artwork cache directory: {}", e))?;
+
+        log::info!(
+            "Artwork cache initialized at {:?} (max {} MB)",
+            cache_dir,
+            max_size_bytes / (1024 * 1024)
+        );
+
+        Ok(Self {
+            cache_dir,
+            max_size_bytes: AtomicU64::new(max_size_bytes),
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    fn file_name(url: &str) -> String {
+        let hash = format!("{:x}", Sha256::digest(url.as_bytes()));
+        let (size, ext) = match split_cover_url(url) {
+            Some((_, size, ext)) => (size, ext),
+            None => ("original", "img"),
+        };
+        format!("{}_{}.{}", &hash[..24], size, ext)
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        self.cache_dir.join(Self::file_name(url))
+    }
+
+    /// Cached file for `url`, marking it as recently used
+    pub fn get(&self, url: &str) -> Option<PathBuf> {
+        let path = self.path_for(url);
+        if !path.is_file() {
+            return None;
+        }
+        let _ = filetime::set_file_mtime(&path, filetime::FileTime::now());
+        Some(path)
+    }
+
+    /// Store `data` for `url`, evicting older files if over the limit
+    pub fn insert(&self, url: &str, data: &[u8]) -> Result<PathBuf, String> {
+        let max_size = self.max_size_bytes.load(Ordering::Relaxed);
+        if data.len() as u64 > max_size {
+            return Err("Image is larger than the artwork cache".to_string());
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let path = self.path_for(url);
+        let tmp = path.with_extension("part");
+        fs::write(&tmp, data).map_err(|e| format!("Failed to write artwork: {}", e))?;
+        fs::rename(&tmp, &path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            format!("Failed to write artwork: {}", e)
+        })?;
+
+        self.evict_until(max_size);
+        Ok(path)
+    }
+
+    /// Change the maximum size, deleting least recently used files until
+    /// the cache fits
+    pub fn resize(&self, max_size_bytes: u64) {
+        self.max_size_bytes.store(max_size_bytes, Ordering::Relaxed);
+        let _guard = self.write_lock.lock().unwrap();
+        self.evict_until(max_size_bytes);
+        log::info!(
+            "Artwork cache resized to {} MB",
+            max_size_bytes / (1024 * 1024)
+        );
+    }
+
+    fn files(&self) -> Vec<CachedFile> {
+        let Ok(entries) = fs::read_dir(&self.cache_dir) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                if !meta.is_file() {
+                    return None;
+                }
+                let path = entry.path();
+                let size_label = size_label(&path)?;
+                Some(CachedFile {
+                    path,
+                    size_label,
+                    bytes: meta.len(),
+                    modified: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                })
+            })
+            .collect()
+    }
+
+    /// Delete least recently used files until at most `limit` bytes are used
+    fn evict_until(&self, limit: u64) {
+        let mut files = self.files();
+        let mut used: u64 = files.iter().map(|f| f.bytes).sum();
+        if used <= limit {
+            return;
+        }
+
+        files.sort_by_key(|f| f.modified);
+        let mut evicted = 0;
+        for file in files {
+            if used <= limit {
+                break;
+            }
+            if fs::remove_file(&file.path).is_ok() {
+                used = used.saturating_sub(file.bytes);
+                evicted += 1;
+            }
+        }
+        log::debug!("Evicted {} files from artwork cache", evicted);
+    }
+
+    /// Delete every cached image, returning the bytes freed
+    pub fn clear(&self) -> u64 {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut cleared = 0;
+        for file in self.files() {
+            if fs::remove_file(&file.path).is_ok() {
+                cleared += file.bytes;
+            } else {
+                log::warn!("Failed to remove cache file {:?}", file.path);
+            }
+        }
+        log::info!("Artwork cache cleared ({} KB)", cleared / 1024);
+        cleared
+    }
+
+    pub fn report(&self) -> ArtworkCacheReport {
+        let files = self.files();
+        let mut by_size: Vec<ArtworkSizeUsage> = Vec::new();
+        for file in &files {
+            match by_size.iter_mut().find(|u| u.size == file.size_label) {
+                Some(usage) => {
+                    usage.files += 1;
+                    usage.bytes += file.bytes;
+                }
+                None => by_size.push(ArtworkSizeUsage {
+                    size: file.size_label.clone(),
+                    files: 1,
+                    bytes: file.bytes,
+                }),
+            }
+        }
+        by_size.sort_by_key(|usage| std::cmp::Reverse(usage.bytes));
+
+        ArtworkCacheReport {
+            files: files.len(),
+            bytes_used: files.iter().map(|f| f.bytes).sum(),
+            max_bytes: self.max_size_bytes.load(Ordering::Relaxed),
+            by_size,
+        }
+    }
+}
+
+/// Size label from a cache file name (`{hash}_{size}.{ext}`); None for
+/// anything else in the directory (e.g. partial writes)
+fn size_label(path: &Path) -> Option<String> {
+    if path.extension()? == "part" {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    let (_, size) = stem.split_once('_')?;
+    Some(size.to_string())
+}
+
+/// Managed wrapper; the cache is None if its directory couldn't be created
+pub struct ArtworkCacheState {
+    pub cache: Option<ArtworkCache>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(max_size_bytes: u64) -> ArtworkCache {
+        let dir = std::env::temp_dir().join(format!(
+            "qbz-artwork-test-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        ArtworkCache::at(dir, max_size_bytes).unwrap()
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = temp_cache(250);
+        let a = "https://static.qobuz.com/images/covers/aa/aa/a_600.jpg";
+        let b = "https://static.qobuz.com/images/covers/bb/bb/b_600.jpg";
+        let c = "https://static.qobuz.com/images/covers/cc/cc/c_230.jpg";
+
+        let path_a = cache.insert(a, &[0; 100]).unwrap();
+        let path_b = cache.insert(b, &[0; 100]).unwrap();
+        let old = filetime::FileTime::from_unix_time(1_000, 0);
+        filetime::set_file_mtime(&path_a, old).unwrap();
+        filetime::set_file_mtime(&path_b, old).unwrap();
+        // Using A makes B the oldest
+        assert!(cache.get(a).is_some());
+
+        cache.insert(c, &[0; 100]).unwrap();
+        assert!(cache.get(a).is_some());
+        assert!(cache.get(b).is_none());
+        assert!(cache.get(c).is_some());
+
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
+
+    #[test]
+    fn report_groups_by_size() {
+        let cache = temp_cache(10_000);
+        cache
+            .insert(
+                "https://static.qobuz.com/images/covers/aa/aa/a_600.jpg",
+                &[0; 300],
+            )
+            .unwrap();
+        cache
+            .insert(
+                "https://static.qobuz.com/images/covers/bb/bb/b_600.jpg",
+                &[0; 300],
+            )
+            .unwrap();
+        cache
+            .insert(
+                "https://static.qobuz.com/images/covers/aa/aa/a_max.jpg",
+                &[0; 900],
+            )
+            .unwrap();
+
+        let report = cache.report();
+        assert_eq!(report.files, 3);
+        assert_eq!(report.bytes_used, 1500);
+        assert_eq!(
+            report.by_size,
+            vec![
+                ArtworkSizeUsage {
+                    size: "max".to_string(),
+                    files: 1,
+                    bytes: 900
+                },
+                ArtworkSizeUsage {
+                    size: "600".to_string(),
+                    files: 2,
+                    bytes: 600
+                },
+            ]
+        );
+
+        assert_eq!(cache.clear(), 1500);
+        assert_eq!(cache.report().files, 0);
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
+}
@@ -1,26 +1,52 @@
 //! Audio caching module
 //!
 //! Provides two-level caching for audio data:
-//! - L1: In-memory LRU cache (fast, limited to ~300MB)
-//! - L2: Disk-based playback cache (slower, larger ~500MB)
+//! - L1: In-memory LRU cache (fast, 300MB by default)
+//! - L2: Disk-based playback cache (slower, 500MB by default)
+//!
+//! Both limits are user settings and can be changed at runtime (`resize`).
 //!
 //! Flow:
 //! 1. When a track is evicted from memory, it's saved to disk cache
 //! 2. When loading, check memory -> disk -> network
 
+pub mod artwork;
 pub mod playback_cache;
 
-pub use playback_cache::{PlaybackCache, PlaybackCacheStats};
+pub use artwork::{ArtworkCache, ArtworkCacheReport, ArtworkCacheState};
+pub use playback_cache::PlaybackCache;
 
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
+/// Default L1 (memory) size in MB
+pub const DEFAULT_MEMORY_CACHE_MB: u32 = 300;
+/// Default L2 (disk) size in MB
+pub const DEFAULT_DISK_CACHE_MB: u32 = 500;
+/// Smallest size accepted for either tier; one Hi-Res album track can exceed
+/// 100MB, and anything larger than the limit is never cached
+pub const MIN_CACHE_MB: u32 = 100;
+/// Largest memory cache accepted
+pub const MAX_MEMORY_CACHE_MB: u32 = 8 * 1024;
+/// Largest disk cache accepted
+pub const MAX_DISK_CACHE_MB: u32 = 100 * 1024;
+
+/// Bytes in `mb` megabytes
+pub fn mb_to_bytes(mb: u32) -> u64 {
+    mb as u64 * 1024 * 1024
+}
+
 /// Cached audio data for a track
 #[derive(Clone)]
 pub struct CachedTrack {
     pub track_id: u64,
     pub data: Vec<u8>,
     pub size_bytes: usize,
+    /// Qobuz format the data was fetched at (None = unknown, e.g. from the disk cache)
+    pub format_id: Option<u32>,
+    /// True when prefetched below the playback quality (see `prefetch_quality`)
+    pub reduced_quality: bool,
 }
 
 /// Internal cache state - all in one struct to avoid deadlocks
@@ -38,15 +64,20 @@ struct CacheState {
 /// Audio cache manager with LRU eviction and disk spillover
 pub struct AudioCache {
     state: Mutex<CacheState>,
-    /// Maximum cache size in bytes
-    max_size_bytes: usize,
+    /// Maximum cache size in bytes (adjustable at runtime, see `resize`)
+    max_size_bytes: AtomicUsize,
     /// Optional disk-based L2 cache for evicted tracks
     playback_cache: Option<Arc<PlaybackCache>>,
+    /// Lookups served from memory / not found, since startup
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// Tracks pushed out of memory to make room, since startup
+    evictions: AtomicU64,
 }
 
 impl Default for AudioCache {
     fn default() -> Self {
-        Self::new(300 * 1024 * 1024) // 300MB for ~3-4 Hi-Res tracks
+        Self::new(mb_to_bytes(DEFAULT_MEMORY_CACHE_MB) as usize) // ~3-4 Hi-Res tracks
     }
 }
 
@@ -60,8 +91,11 @@ impl AudioCache {
                 current_size: 0,
                 fetching: HashSet::new(),
             }),
-            max_size_bytes,
+            max_size_bytes: AtomicUsize::new(max_size_bytes),
             playback_cache: None,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
         }
     }
 
@@ -74,8 +108,11 @@ impl AudioCache {
                 current_size: 0,
                 fetching: HashSet::new(),
             }),
-            max_size_bytes,
+            max_size_bytes: AtomicUsize::new(max_size_bytes),
             playback_cache: Some(playback_cache),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
         }
     }
 
@@ -99,8 +136,10 @@ impl AudioCache {
             // Update access order (move to back = most recently used)
             state.access_order.retain(|&id| id != track_id);
             state.access_order.push(track_id);
+            self.hits.fetch_add(1, Ordering::Relaxed);
             log::debug!("Cache hit for track {}", track_id);
         } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
             log::debug!("Cache miss for track {}", track_id);
         }
 
@@ -129,46 +168,39 @@ impl AudioCache {
 
     /// Insert a track into cache, evicting old entries to disk if needed
     pub fn insert(&self, track_id: u64, data: Vec<u8>) {
+        self.insert_with_format(track_id, data, None, false);
+    }
+
+    /// Insert a track along with the Qobuz format it was fetched at
+    pub fn insert_with_format(
+        &self,
+        track_id: u64,
+        data: Vec<u8>,
+        format_id: Option<u32>,
+        reduced_quality: bool,
+    ) {
         let size = data.len();
+        let max_size = self.max_size_bytes.load(Ordering::Relaxed);
 
         // Don't cache if track is larger than max cache size
-        if size > self.max_size_bytes {
+        if size > max_size {
             log::warn!(
                 "Track {} ({} bytes) too large for cache (max {} bytes)",
                 track_id,
                 size,
-                self.max_size_bytes
+                max_size
             );
             return;
         }
 
         // Collect tracks to evict (to avoid holding lock while writing to disk)
-        let mut tracks_to_spill: Vec<CachedTrack> = Vec::new();
-
-        {
+        let tracks_to_spill = {
             let mut state = self.state.lock().unwrap();
-
-            // Evict old entries to make room
-            while state.current_size + size > self.max_size_bytes && !state.access_order.is_empty() {
-                let oldest_id = state.access_order.remove(0);
-                if let Some(track) = state.tracks.remove(&oldest_id) {
-                    state.current_size = state.current_size.saturating_sub(track.size_bytes);
-                    log::debug!(
-                        "Evicting track {} ({} bytes) from memory cache",
-                        oldest_id,
-                        track.size_bytes
-                    );
-                    tracks_to_spill.push(track);
-                }
-            }
-        }
+            self.evict_until(&mut state, max_size - size)
+        };
 
         // Spill evicted tracks to disk cache (outside of lock)
-        if let Some(playback_cache) = &self.playback_cache {
-            for track in tracks_to_spill {
-                playback_cache.insert(track.track_id, &track.data);
-            }
-        }
+        self.spill(tracks_to_spill);
 
         let mut state = self.state.lock().unwrap();
 
@@ -181,6 +213,8 @@ impl AudioCache {
             track_id,
             data,
             size_bytes: size,
+            format_id,
+            reduced_quality,
         };
 
         state.tracks.insert(track_id, cached);
@@ -195,10 +229,55 @@ impl AudioCache {
             track_id,
             size,
             state.current_size,
-            self.max_size_bytes
+            max_size
         );
     }
 
+    /// Change the maximum size, evicting least recently used tracks to disk
+    /// until the cache fits the new limit
+    pub fn resize(&self, max_size_bytes: usize) {
+        self.max_size_bytes.store(max_size_bytes, Ordering::Relaxed);
+
+        let tracks_to_spill = {
+            let mut state = self.state.lock().unwrap();
+            self.evict_until(&mut state, max_size_bytes)
+        };
+        log::info!(
+            "Memory cache resized to {} MB ({} tracks evicted)",
+            max_size_bytes / (1024 * 1024),
+            tracks_to_spill.len()
+        );
+        self.spill(tracks_to_spill);
+    }
+
+    /// Remove least recently used tracks until at most `limit` bytes are used
+    fn evict_until(&self, state: &mut CacheState, limit: usize) -> Vec<CachedTrack> {
+        let mut evicted = Vec::new();
+        while state.current_size > limit && !state.access_order.is_empty() {
+            let oldest_id = state.access_order.remove(0);
+            if let Some(track) = state.tracks.remove(&oldest_id) {
+                state.current_size = state.current_size.saturating_sub(track.size_bytes);
+                log::debug!(
+                    "Evicting track {} ({} bytes) from memory cache",
+                    oldest_id,
+                    track.size_bytes
+                );
+                evicted.push(track);
+            }
+        }
+        self.evictions.fetch_add(evicted.len() as u64, Ordering::Relaxed);
+        evicted
+    }
+
+    /// Save evicted tracks to the disk cache (call without holding the lock)
+    fn spill(&self, tracks: Vec<CachedTrack>) {
+        if let Some(playback_cache) = &self.playback_cache {
+            for track in tracks {
+                playback_cache.insert(track.track_id, &track.data);
+            }
+        }
+    }
+
     /// Clear all cached data (both L1 memory and L2 disk caches)
     pub fn clear(&self) {
         let mut state = self.state.lock().unwrap();
@@ -215,23 +294,97 @@ impl AudioCache {
         }
     }
 
-    /// Get cache statistics
+    /// Get statistics for both cache tiers
     pub fn stats(&self) -> CacheStats {
-        let state = self.state.lock().unwrap();
+        let (l1, fetching_count) = {
+            let state = self.state.lock().unwrap();
+            let l1 = TierStats {
+                entries: state.tracks.len(),
+                bytes_used: state.current_size as u64,
+                max_bytes: self.max_size_bytes.load(Ordering::Relaxed) as u64,
+                hits: self.hits.load(Ordering::Relaxed),
+                misses: self.misses.load(Ordering::Relaxed),
+                evictions: self.evictions.load(Ordering::Relaxed),
+            };
+            (l1, state.fetching.len())
+        };
+
         CacheStats {
-            cached_tracks: state.tracks.len(),
-            current_size_bytes: state.current_size,
-            max_size_bytes: self.max_size_bytes,
-            fetching_count: state.fetching.len(),
+            l1,
+            l2: self
+                .playback_cache
+                .as_ref()
+                .map(|cache| cache.stats())
+                .unwrap_or_default(),
+            fetching_count,
         }
     }
 }
 
-/// Cache statistics
+/// Usage and counters of one cache tier (counters run since startup)
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct TierStats {
+    pub entries: usize,
+    pub bytes_used: u64,
+    pub max_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// Cache statistics: L1 memory and L2 disk (all zero when disk spillover
+/// is unavailable)
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct CacheStats {
-    pub cached_tracks: usize,
-    pub current_size_bytes: usize,
-    pub max_size_bytes: usize,
+    pub l1: TierStats,
+    pub l2: TierStats,
     pub fetching_count: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_eviction_counts() {
+        let cache = AudioCache::new(10);
+        cache.insert(1, vec![0; 4]);
+        cache.insert(2, vec![0; 4]);
+        assert!(cache.get(1).is_some());
+        cache.insert(3, vec![0; 4]);
+
+        assert!(cache.contains(1));
+        assert!(!cache.contains(2));
+        assert!(cache.get(2).is_none());
+
+        let stats = cache.stats();
+        assert_eq!(stats.l1.entries, 2);
+        assert_eq!(stats.l1.bytes_used, 8);
+        assert_eq!(stats.l1.hits, 1);
+        assert_eq!(stats.l1.misses, 1);
+        assert_eq!(stats.l1.evictions, 1);
+        assert_eq!(stats.l2.max_bytes, 0);
+    }
+
+    #[test]
+    fn test_resize_evicts_down_to_limit() {
+        let cache = AudioCache::new(12);
+        for id in 1..=3 {
+            cache.insert(id, vec![0; 4]);
+        }
+        cache.get(1);
+
+        cache.resize(5);
+        assert!(cache.contains(1));
+        assert!(!cache.contains(2));
+        assert!(!cache.contains(3));
+
+        let stats = cache.stats();
+        assert_eq!(stats.l1.max_bytes, 5);
+        assert_eq!(stats.l1.evictions, 2);
+
+        // Tracks over the new limit are no longer cached
+        cache.insert(4, vec![0; 6]);
+        assert!(!cache.contains(4));
+    }
+}
@@ -7,9 +7,12 @@ use std::collections::HashMap;
 use std::fs;
 use std::io::{Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 use std::time::SystemTime;
 
+use super::TierStats;
+
 /// Entry metadata for tracking cache usage
 #[derive(Debug, Clone)]
 struct CacheEntry {
@@ -32,8 +35,13 @@ pub struct PlaybackCache {
     state: Mutex<PlaybackCacheState>,
     /// Cache directory path
     cache_dir: PathBuf,
-    /// Maximum cache size in bytes (default: 500MB)
-    max_size_bytes: u64,
+    /// Maximum cache size in bytes (default: 500MB, see `resize`)
+    max_size_bytes: AtomicU64,
+    /// Lookups served from disk / not found, since startup
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// Files deleted to make room, since startup
+    evictions: AtomicU64,
 }
 
 impl PlaybackCache {
@@ -54,7 +62,10 @@ impl PlaybackCache {
                 current_size: 0,
             }),
             cache_dir,
-            max_size_bytes,
+            max_size_bytes: AtomicU64::new(max_size_bytes),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
         };
 
         // Scan existing files to rebuild state
@@ -133,6 +144,7 @@ impl PlaybackCache {
             if let Some(entry) = state.entries.remove(&track_id) {
                 state.current_size = state.current_size.saturating_sub(entry.size_bytes);
             }
+            self.misses.fetch_add(1, Ordering::Relaxed);
             return None;
         }
 
@@ -148,6 +160,7 @@ impl PlaybackCache {
 
                     // Touch file to update filesystem access time
                     let _ = filetime::set_file_atime(&path, filetime::FileTime::now());
+                    self.hits.fetch_add(1, Ordering::Relaxed);
 
                     log::debug!(
                         "Playback cache hit for track {} ({} bytes)",
@@ -157,11 +170,13 @@ impl PlaybackCache {
                     Some(data)
                 } else {
                     log::warn!("Failed to read playback cache file for track {}", track_id);
+                    self.misses.fetch_add(1, Ordering::Relaxed);
                     None
                 }
             }
             Err(e) => {
                 log::warn!("Failed to open playback cache file for track {}: {}", track_id, e);
+                self.misses.fetch_add(1, Ordering::Relaxed);
                 None
             }
         }
@@ -170,20 +185,21 @@ impl PlaybackCache {
     /// Insert a track into the cache (called when evicting from memory cache)
     pub fn insert(&self, track_id: u64, data: &[u8]) {
         let size = data.len() as u64;
+        let max_size = self.max_size_bytes.load(Ordering::Relaxed);
 
         // Don't cache if larger than max size
-        if size > self.max_size_bytes {
+        if size > max_size {
             log::debug!(
                 "Track {} too large for playback cache ({} MB > {} MB)",
                 track_id,
                 size / (1024 * 1024),
-                self.max_size_bytes / (1024 * 1024)
+                max_size / (1024 * 1024)
             );
             return;
         }
 
         // Evict old entries if needed
-        self.evict_if_needed(size);
+        self.evict_until(max_size - size);
 
         let path = self.track_path(track_id);
 
@@ -214,7 +230,7 @@ impl PlaybackCache {
                         track_id,
                         size / 1024,
                         state.current_size / (1024 * 1024),
-                        self.max_size_bytes / (1024 * 1024)
+                        max_size / (1024 * 1024)
                     );
                 } else {
                     log::warn!("Failed to write playback cache file for track {}", track_id);
@@ -227,11 +243,19 @@ impl PlaybackCache {
         }
     }
 
-    /// Evict oldest entries to make room for new data
-    fn evict_if_needed(&self, needed_bytes: u64) {
+    /// Change the maximum size, deleting least recently used files until
+    /// the cache fits the new limit
+    pub fn resize(&self, max_size_bytes: u64) {
+        self.max_size_bytes.store(max_size_bytes, Ordering::Relaxed);
+        self.evict_until(max_size_bytes);
+        log::info!("Playback cache resized to {} MB", max_size_bytes / (1024 * 1024));
+    }
+
+    /// Evict oldest entries until at most `limit` bytes are used
+    fn evict_until(&self, limit: u64) {
         let mut state = self.state.lock().unwrap();
 
-        while state.current_size + needed_bytes > self.max_size_bytes && !state.entries.is_empty() {
+        while state.current_size > limit && !state.entries.is_empty() {
             // Find oldest entry
             let oldest_id = state
                 .entries
@@ -242,6 +266,7 @@ impl PlaybackCache {
             if let Some(track_id) = oldest_id {
                 if let Some(entry) = state.entries.remove(&track_id) {
                     state.current_size = state.current_size.saturating_sub(entry.size_bytes);
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
 
                     // Delete file
                     let path = self.cache_dir.join(format!("{}.audio", track_id));
@@ -277,20 +302,15 @@ impl PlaybackCache {
     }
 
     /// Get cache statistics
-    pub fn stats(&self) -> PlaybackCacheStats {
+    pub fn stats(&self) -> TierStats {
         let state = self.state.lock().unwrap();
-        PlaybackCacheStats {
-            cached_tracks: state.entries.len(),
-            current_size_bytes: state.current_size,
-            max_size_bytes: self.max_size_bytes,
+        TierStats {
+            entries: state.entries.len(),
+            bytes_used: state.current_size,
+            max_bytes: self.max_size_bytes.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
         }
     }
 }
-
-/// Playback cache statistics
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct PlaybackCacheStats {
-    pub cached_tracks: usize,
-    pub current_size_bytes: u64,
-    pub max_size_bytes: u64,
-}
@@ -2,8 +2,13 @@
 //!
 //! Since rust_cast uses Rc (not Arc), it cannot be shared across threads.
 //! This module provides a thread-safe wrapper using channels.
+//!
+//! The thread also notices when a device stops answering heartbeats and
+//! reports the lost connection on a separate channel (see `take_loss_receiver`).
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
@@ -53,9 +58,19 @@ pub enum CastCommand {
     Shutdown,
 }
 
+/// A connection that dropped without `disconnect` being called
+#[derive(Debug, Clone)]
+pub struct ConnectionLost {
+    /// Connection the loss refers to (see `ChromecastHandle::connection_id`)
+    pub connection_id: u64,
+    pub reason: String,
+}
+
 /// Thread-safe handle to communicate with the Chromecast thread
 pub struct ChromecastHandle {
     sender: Sender<CastCommand>,
+    connection_id: Arc<AtomicU64>,
+    loss_receiver: Mutex<Option<Receiver<ConnectionLost>>>,
     _thread: JoinHandle<()>,
 }
 
@@ -63,16 +78,31 @@ impl ChromecastHandle {
     /// Start the Chromecast handler thread
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::channel();
+        let (loss_tx, loss_rx) = mpsc::channel();
+        let connection_id = Arc::new(AtomicU64::new(0));
+        let thread_connection_id = connection_id.clone();
         let thread = thread::spawn(move || {
-            chromecast_thread_main(receiver);
+            chromecast_thread_main(receiver, loss_tx, thread_connection_id);
         });
 
         Self {
             sender,
+            connection_id,
+            loss_receiver: Mutex::new(Some(loss_rx)),
             _thread: thread,
         }
     }
 
+    /// Take the receiver for unexpected disconnects (only one listener)
+    pub fn take_loss_receiver(&self) -> Option<Receiver<ConnectionLost>> {
+        self.loss_receiver.lock().ok()?.take()
+    }
+
+    /// ID of the most recent connection; bumped on every connect
+    pub fn connection_id(&self) -> u64 {
+        self.connection_id.load(Ordering::SeqCst)
+    }
+
     /// Connect to a Chromecast device
     pub fn connect(&self, ip: String, port: u16) -> Result<(), CastError> {
         let (reply_tx, reply_rx) = mpsc::channel();
@@ -211,18 +241,51 @@ impl Drop for ChromecastHandle {
 }
 
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(25);
+/// Heartbeat interval after a failed heartbeat
+const HEARTBEAT_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+/// Failed heartbeats in a row before the device is considered gone
+const MAX_HEARTBEAT_FAILURES: u32 = 3;
 
 /// Main loop for the Chromecast thread
-fn chromecast_thread_main(receiver: Receiver<CastCommand>) {
+fn chromecast_thread_main(
+    receiver: Receiver<CastCommand>,
+    loss_tx: Sender<ConnectionLost>,
+    connection_id: Arc<AtomicU64>,
+) {
     let mut connection: Option<CastDeviceConnection> = None;
+    let mut heartbeat_failures: u32 = 0;
 
     loop {
-        let command = match receiver.recv_timeout(HEARTBEAT_INTERVAL) {
+        let timeout = if heartbeat_failures > 0 {
+            HEARTBEAT_RETRY_INTERVAL
+        } else {
+            HEARTBEAT_INTERVAL
+        };
+        let command = match receiver.recv_timeout(timeout) {
             Ok(cmd) => cmd,
             Err(RecvTimeoutError::Timeout) => {
-                if let Some(conn) = connection.as_ref() {
-                    if let Err(err) = conn.heartbeat() {
-                        log::warn!("Chromecast heartbeat failed: {}", err);
+                let Some(conn) = connection.as_ref() else {
+                    continue;
+                };
+                match conn.heartbeat() {
+                    Ok(()) => heartbeat_failures = 0,
+                    Err(err) => {
+                        heartbeat_failures += 1;
+                        log::warn!(
+                            "Chromecast heartbeat failed ({}/{}): {}",
+                            heartbeat_failures,
+                            MAX_HEARTBEAT_FAILURES,
+                            err
+                        );
+                        if heartbeat_failures >= MAX_HEARTBEAT_FAILURES {
+                            log::warn!("Chromecast connection lost");
+                            connection = None;
+                            heartbeat_failures = 0;
+                            let _ = loss_tx.send(ConnectionLost {
+                                connection_id: connection_id.load(Ordering::SeqCst),
+                                reason: err.to_string(),
+                            });
+                        }
                     }
                 }
                 continue;
@@ -236,6 +299,8 @@ fn chromecast_thread_main(receiver: Receiver<CastCommand>) {
                 match result {
                     Ok(conn) => {
                         connection = Some(conn);
+                        heartbeat_failures = 0;
+                        connection_id.fetch_add(1, Ordering::SeqCst);
                         let _ = reply.send(Ok(()));
                     }
                     Err(e) => {
@@ -251,6 +316,7 @@ fn chromecast_thread_main(receiver: Receiver<CastCommand>) {
                     Ok(())
                 };
                 connection = None;
+                heartbeat_failures = 0;
                 let _ = reply.send(result);
             }
 
@@ -1,7 +1,9 @@
 //! Tauri commands for Chromecast casting
 
 use std::sync::Arc;
-use tauri::State;
+use std::time::Instant;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio::sync::Mutex;
 
 use crate::api::models::Quality;
@@ -9,8 +11,11 @@ use crate::AppState;
 use crate::cast::{
     CastError, CastStatus, CastPositionInfo, DeviceDiscovery, DiscoveredDevice, MediaMetadata, MediaServer,
 };
-use crate::cast::chromecast_thread::ChromecastHandle;
+use crate::cast::chromecast_thread::{ChromecastHandle, ConnectionLost};
+use crate::config::audio_settings::AudioSettingsState;
 use crate::library::{AudioFormat, LibraryState};
+use crate::offline_cache::OfflineCacheState;
+use crate::playback_router::{self, PlaybackRoute, RouteDevice};
 
 /// Cast state shared across commands
 /// Uses a dedicated thread for Chromecast operations since rust_cast is not thread-safe
@@ -20,6 +25,53 @@ pub struct CastState {
     /// Media server is lazily initialized on first cast operation to save CPU when not casting
     pub media_server: Arc<Mutex<Option<MediaServer>>>,
     pub connected_device_ip: Arc<Mutex<Option<String>>>,
+    /// What the device is playing, to hand playback back if it drops off
+    pub playback: Arc<Mutex<CastPlayback>>,
+    /// Track moved to the local player after a lost connection
+    pub local_takeover: Arc<Mutex<Option<u64>>>,
+}
+
+/// Last known Chromecast playback, kept up to date by the cast commands
+#[derive(Debug, Clone, Default)]
+pub struct CastPlayback {
+    /// Qobuz track ID (None for library tracks, which cannot be handed back)
+    pub track_id: Option<u64>,
+    pub duration_secs: Option<u64>,
+    pub position_secs: f64,
+    pub is_playing: bool,
+    /// When `position_secs` was last updated
+    pub updated_at: Option<Instant>,
+}
+
+impl CastPlayback {
+    fn set_position(&mut self, position_secs: f64) {
+        self.position_secs = position_secs;
+        self.updated_at = Some(Instant::now());
+    }
+
+    /// Position extrapolated from the last report while playing
+    pub fn estimated_position(&self, now: Instant) -> f64 {
+        let mut position = self.position_secs;
+        if self.is_playing {
+            if let Some(at) = self.updated_at {
+                position += now.saturating_duration_since(at).as_secs_f64();
+            }
+        }
+        match self.duration_secs {
+            Some(duration) => position.min(duration as f64),
+            None => position,
+        }
+    }
+}
+
+/// Payload of the `cast:disconnected` event
+#[derive(Debug, Clone, Serialize)]
+pub struct CastDisconnectedEvent {
+    pub reason: String,
+    pub track_id: Option<u64>,
+    pub position_secs: u64,
+    /// True when playback continued on the local player
+    pub resumed_locally: bool,
 }
 
 impl CastState {
@@ -30,9 +82,24 @@ impl CastState {
             // Don't start media server until needed - saves CPU when not casting
             media_server: Arc::new(Mutex::new(None)),
             connected_device_ip: Arc::new(Mutex::new(None)),
+            playback: Arc::new(Mutex::new(CastPlayback::default())),
+            local_takeover: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// Stop the local player if it still plays a track taken over from the
+    /// device, so casting again does not play it twice
+    async fn end_local_takeover(&self, app_state: &AppState) {
+        if let Some(track_id) = self.local_takeover.lock().await.take() {
+            if app_state.player.state.current_track_id() == track_id
+                && app_state.player.state.is_playing()
+            {
+                log::info!("Casting again, pausing local playback of track {}", track_id);
+                let _ = app_state.player.pause();
+            }
+        }
+    }
+
     /// Get or create the media server (lazy initialization)
     pub async fn get_or_create_media_server(&self) -> Result<(), CastError> {
         let mut server_guard = self.media_server.lock().await;
@@ -71,7 +138,11 @@ pub async fn cast_get_devices(
 // === Connection ===
 
 #[tauri::command]
-pub async fn cast_connect(device_id: String, state: State<'_, CastState>) -> Result<(), String> {
+pub async fn cast_connect(
+    device_id: String,
+    app: AppHandle,
+    state: State<'_, CastState>,
+) -> Result<(), String> {
     let device = {
         let discovery = state.discovery.lock().await;
         discovery
@@ -89,18 +160,27 @@ pub async fn cast_connect(device_id: String, state: State<'_, CastState>) -> Res
         let mut connected = state.connected_device_ip.lock().await;
         *connected = Some(device.ip.clone());
     }
+    playback_router::set_route(
+        &app,
+        PlaybackRoute::Chromecast(RouteDevice {
+            id: device.id,
+            name: device.name,
+        }),
+    );
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn cast_disconnect(state: State<'_, CastState>) -> Result<(), String> {
+pub async fn cast_disconnect(app: AppHandle, state: State<'_, CastState>) -> Result<(), String> {
     state.chromecast.disconnect().map_err(|e| e.to_string())?;
 
     {
         let mut connected = state.connected_device_ip.lock().await;
         *connected = None;
     }
+    *state.playback.lock().await = CastPlayback::default();
+    playback_router::reset_route_if(&app, |route| matches!(route, PlaybackRoute::Chromecast(_)));
 
     Ok(())
 }
@@ -112,7 +192,13 @@ pub async fn cast_get_status(state: State<'_, CastState>) -> Result<CastStatus,
 
 #[tauri::command]
 pub async fn cast_get_position(state: State<'_, CastState>) -> Result<CastPositionInfo, String> {
-    state.chromecast.get_media_position().map_err(|e| e.to_string())
+    let info = state.chromecast.get_media_position().map_err(|e| e.to_string())?;
+    {
+        let mut playback = state.playback.lock().await;
+        playback.set_position(info.position_secs);
+        playback.is_playing = matches!(info.player_state.as_str(), "PLAYING" | "BUFFERING");
+    }
+    Ok(info)
 }
 
 // === Playback ===
@@ -162,16 +248,28 @@ pub async fn cast_play_track(
         url.ok_or_else(|| "Failed to build media URL".to_string())?
     };
 
+    let duration_secs = metadata.duration_secs;
+    state.end_local_takeover(&app_state).await;
     state
         .chromecast
         .load_media(url, content_type, metadata)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    *state.playback.lock().await = CastPlayback {
+        track_id: Some(track_id),
+        duration_secs,
+        is_playing: true,
+        updated_at: Some(Instant::now()),
+        ..Default::default()
+    };
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn cast_play_local_track(
     track_id: i64,
     state: State<'_, CastState>,
+    app_state: State<'_, AppState>,
     library_state: State<'_, LibraryState>,
 ) -> Result<(), String> {
     let track = {
@@ -213,30 +311,49 @@ pub async fn cast_play_local_track(
 
     let content_type = content_type_from_format(&track.format).to_string();
 
+    state.end_local_takeover(&app_state).await;
     state
         .chromecast
         .load_media(url, content_type, metadata)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    // Library tracks are not handed back to the local player on disconnect
+    *state.playback.lock().await = CastPlayback::default();
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn cast_play(state: State<'_, CastState>) -> Result<(), String> {
-    state.chromecast.play().map_err(|e| e.to_string())
+    state.chromecast.play().map_err(|e| e.to_string())?;
+    let mut playback = state.playback.lock().await;
+    let position = playback.estimated_position(Instant::now());
+    playback.set_position(position);
+    playback.is_playing = true;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn cast_pause(state: State<'_, CastState>) -> Result<(), String> {
-    state.chromecast.pause().map_err(|e| e.to_string())
+    state.chromecast.pause().map_err(|e| e.to_string())?;
+    let mut playback = state.playback.lock().await;
+    let position = playback.estimated_position(Instant::now());
+    playback.set_position(position);
+    playback.is_playing = false;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn cast_stop(state: State<'_, CastState>) -> Result<(), String> {
-    state.chromecast.stop().map_err(|e| e.to_string())
+    state.chromecast.stop().map_err(|e| e.to_string())?;
+    *state.playback.lock().await = CastPlayback::default();
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn cast_seek(position_secs: f64, state: State<'_, CastState>) -> Result<(), String> {
-    state.chromecast.seek(position_secs).map_err(|e| e.to_string())
+    state.chromecast.seek(position_secs).map_err(|e| e.to_string())?;
+    state.playback.lock().await.set_position(position_secs);
+    Ok(())
 }
 
 #[tauri::command]
@@ -244,6 +361,85 @@ pub async fn cast_set_volume(volume: f32, state: State<'_, CastState>) -> Result
     state.chromecast.set_volume(volume).map_err(|e| e.to_string())
 }
 
+// === Connection loss ===
+
+/// Handle a Chromecast that dropped off the network: emit `cast:disconnected`
+/// and, when enabled, continue the track on the local player.
+pub async fn handle_connection_lost(app: &AppHandle, lost: ConnectionLost) {
+    let state = app.state::<CastState>();
+    if lost.connection_id != state.chromecast.connection_id() {
+        // Already reconnected; the new connection owns playback
+        return;
+    }
+    log::warn!("Cast device disconnected unexpectedly: {}", lost.reason);
+
+    *state.connected_device_ip.lock().await = None;
+    playback_router::reset_route_if(app, |route| matches!(route, PlaybackRoute::Chromecast(_)));
+    let playback = std::mem::take(&mut *state.playback.lock().await);
+    let position_secs = playback.estimated_position(Instant::now()) as u64;
+
+    let mut resumed_locally = false;
+    if let Some(track_id) = playback.track_id.filter(|_| playback.is_playing) {
+        if auto_resume_local_enabled(app) {
+            match resume_locally(app, track_id, playback.duration_secs, position_secs).await {
+                Ok(()) => resumed_locally = true,
+                Err(e) => log::error!("Failed to resume track {} locally: {}", track_id, e),
+            }
+        }
+    }
+
+    // A reconnect while the local player was starting takes precedence
+    if resumed_locally && lost.connection_id != state.chromecast.connection_id() {
+        let _ = app.state::<AppState>().player.pause();
+        resumed_locally = false;
+    }
+    if resumed_locally {
+        *state.local_takeover.lock().await = playback.track_id;
+    }
+
+    let _ = app.emit(
+        "cast:disconnected",
+        &CastDisconnectedEvent {
+            reason: lost.reason,
+            track_id: playback.track_id,
+            position_secs,
+            resumed_locally,
+        },
+    );
+}
+
+fn auto_resume_local_enabled(app: &AppHandle) -> bool {
+    app.try_state::<AudioSettingsState>()
+        .and_then(|state| {
+            let guard = state.store.lock().ok()?;
+            guard.as_ref()?.get_settings().ok()
+        })
+        .map(|settings| settings.auto_resume_local_on_cast_loss)
+        .unwrap_or(true)
+}
+
+async fn resume_locally(
+    app: &AppHandle,
+    track_id: u64,
+    duration_secs: Option<u64>,
+    position_secs: u64,
+) -> Result<(), String> {
+    log::info!("Resuming track {} locally at {}s", track_id, position_secs);
+    crate::commands::playback::play_track(
+        track_id,
+        duration_secs,
+        None,
+        app.state::<AppState>(),
+        app.state::<OfflineCacheState>(),
+        app.state::<AudioSettingsState>(),
+    )
+    .await?;
+    if position_secs > 0 {
+        crate::commands::playback::seek(position_secs, app.clone(), app.state::<AppState>()).await?;
+    }
+    Ok(())
+}
+
 async fn download_audio(url: &str) -> Result<Vec<u8>, String> {
     use std::time::Duration;
 
@@ -283,3 +479,27 @@ fn content_type_from_format(format: &AudioFormat) -> &'static str {
         AudioFormat::Unknown => "application/octet-stream",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn estimated_position_advances_only_while_playing() {
+        let start = Instant::now();
+        let mut playback = CastPlayback {
+            track_id: Some(1),
+            duration_secs: Some(100),
+            position_secs: 40.0,
+            is_playing: true,
+            updated_at: Some(start),
+        };
+        let later = start + Duration::from_secs(5);
+        assert_eq!(playback.estimated_position(later) as u64, 45);
+        assert_eq!(playback.estimated_position(start + Duration::from_secs(500)), 100.0);
+
+        playback.is_playing = false;
+        assert_eq!(playback.estimated_position(later), 40.0);
+    }
+}
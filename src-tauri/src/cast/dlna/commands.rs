@@ -1,7 +1,7 @@
 //! Tauri commands for DLNA/UPnP casting
 
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
 
 use crate::api::models::Quality;
@@ -10,6 +10,7 @@ use crate::cast::dlna::{
     DiscoveredDlnaDevice, DlnaConnection, DlnaDiscovery, DlnaError, DlnaMetadata, DlnaPositionInfo, DlnaStatus,
 };
 use crate::cast::MediaServer;
+use crate::playback_router::{self, PlaybackRoute, RouteDevice};
 
 /// DLNA state shared across commands
 pub struct DlnaState {
@@ -69,7 +70,11 @@ pub async fn dlna_get_devices(
 // === Connection ===
 
 #[tauri::command]
-pub async fn dlna_connect(device_id: String, state: State<'_, DlnaState>) -> Result<(), String> {
+pub async fn dlna_connect(
+    device_id: String,
+    app: AppHandle,
+    state: State<'_, DlnaState>,
+) -> Result<(), String> {
     let device = {
         let discovery = state.discovery.lock().await;
         discovery
@@ -78,19 +83,29 @@ pub async fn dlna_connect(device_id: String, state: State<'_, DlnaState>) -> Res
             .map_err(|e| e.to_string())?
     };
 
+    let route_device = RouteDevice {
+        id: device.id.clone(),
+        name: device.name.clone(),
+    };
     let connection = DlnaConnection::connect(device).await.map_err(|e| e.to_string())?;
-    let mut state_connection = state.connection.lock().await;
-    *state_connection = Some(connection);
+    {
+        let mut state_connection = state.connection.lock().await;
+        *state_connection = Some(connection);
+    }
+    playback_router::set_route(&app, PlaybackRoute::Dlna(route_device));
     Ok(())
 }
 
 #[tauri::command]
-pub async fn dlna_disconnect(state: State<'_, DlnaState>) -> Result<(), String> {
-    let mut connection = state.connection.lock().await;
-    if let Some(conn) = connection.as_mut() {
-        conn.disconnect().map_err(|e| e.to_string())?;
+pub async fn dlna_disconnect(app: AppHandle, state: State<'_, DlnaState>) -> Result<(), String> {
+    {
+        let mut connection = state.connection.lock().await;
+        if let Some(conn) = connection.as_mut() {
+            conn.disconnect().map_err(|e| e.to_string())?;
+        }
+        *connection = None;
     }
-    *connection = None;
+    playback_router::reset_route_if(&app, |route| matches!(route, PlaybackRoute::Dlna(_)));
     Ok(())
 }
 
@@ -1,8 +1,11 @@
 //! Local HTTP server for Chromecast media streaming
+//!
+//! Supports single byte ranges (`Range: bytes=...`, answered with 206 Partial
+//! Content) so cast devices can seek and probe without fetching the whole file.
 
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -23,10 +26,23 @@ struct MediaEntry {
 
 #[derive(Clone)]
 enum MediaSource {
-    Data(Vec<u8>),
+    Data(Arc<[u8]>),
     File(PathBuf),
 }
 
+/// How a `Range` header applies to an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteRange {
+    /// No (usable) range: send everything
+    Full,
+    /// Inclusive start and end offsets
+    Partial(u64, u64),
+    /// The range starts past the end of the entry
+    Unsatisfiable,
+}
+
+type MediaResponse = Response<Box<dyn Read + Send>>;
+
 /// Simple HTTP server for audio streaming
 pub struct MediaServer {
     port: u16,
@@ -111,7 +127,7 @@ impl MediaServer {
         let entry = MediaEntry {
             content_type: content_type.to_string(),
             size: data.len() as u64,
-            source: MediaSource::Data(data),
+            source: MediaSource::Data(data.into()),
         };
 
         if let Ok(mut entries) = self.entries.lock() {
@@ -190,20 +206,21 @@ fn handle_request(
     url: &str,
     request: &tiny_http::Request,
     entries: &Arc<Mutex<HashMap<u64, MediaEntry>>>,
-) -> Response<std::io::Cursor<Vec<u8>>> {
+) -> MediaResponse {
     // Log all incoming requests for debugging
     log::info!("MediaServer: {} request from {:?} for {}", method, request.remote_addr(), url);
     
-    if method != &Method::Get {
-        log::warn!("MediaServer: Rejected non-GET request: {}", method);
-        return Response::from_data(Vec::new()).with_status_code(StatusCode(405));
+    // tiny_http leaves out the body of HEAD responses
+    if method != &Method::Get && method != &Method::Head {
+        log::warn!("MediaServer: Rejected request: {}", method);
+        return empty_response(405);
     }
 
     let id = match parse_audio_id(url) {
         Some(id) => id,
         None => {
             log::warn!("MediaServer: 404 - Could not parse audio ID from URL: {}", url);
-            return Response::from_data(Vec::new()).with_status_code(StatusCode(404));
+            return empty_response(404);
         }
     };
 
@@ -214,7 +231,7 @@ fn handle_request(
         }
         None => {
             log::warn!("MediaServer: 404 - No entry found for ID: {}", id);
-            return Response::from_data(Vec::new()).with_status_code(StatusCode(404));
+            return empty_response(404);
         }
     };
 
@@ -224,54 +241,67 @@ fn handle_request(
         .find(|h| h.field.equiv("Range"))
         .map(|h| h.value.as_str());
 
-    let range = range_header.and_then(|header| parse_range(header, entry.size));
-
-    let (data, status_code, content_range) = match read_range(&entry, range) {
-        Ok(result) => result,
-        Err(_) => return Response::from_data(Vec::new()).with_status_code(StatusCode(500)),
+    let (start, end, status_code) = match parse_range(range_header, entry.size) {
+        ByteRange::Full => (0, entry.size, 200),
+        ByteRange::Partial(start, end) => (start, end + 1, 206),
+        ByteRange::Unsatisfiable => {
+            log::warn!("MediaServer: 416 - Range {:?} outside {} bytes", range_header, entry.size);
+            return empty_response(416)
+                .with_header(header("Content-Range", &format!("bytes */{}", entry.size)));
+        }
     };
 
-    let mut response = Response::from_data(data)
-        .with_status_code(status_code)
-        .with_header(header("Content-Type", &entry.content_type))
-        .with_header(header("Accept-Ranges", "bytes"));
+    let body = match open_body(&entry, start, end) {
+        Ok(body) => body,
+        Err(e) => {
+            log::error!("MediaServer: Failed to read ID {}: {}", id, e);
+            return empty_response(500);
+        }
+    };
 
-    if let Some(content_range) = content_range {
-        response = response.with_header(header("Content-Range", &content_range));
+    let mut response = Response::new(
+        StatusCode(status_code),
+        vec![
+            header("Content-Type", &entry.content_type),
+            header("Accept-Ranges", "bytes"),
+        ],
+        body,
+        Some((end - start) as usize),
+        None,
+    );
+    if status_code == 206 {
+        response = response.with_header(header(
+            "Content-Range",
+            &format!("bytes {}-{}/{}", start, end - 1, entry.size),
+        ));
     }
 
     response
 }
 
-fn read_range(
-    entry: &MediaEntry,
-    range: Option<(u64, u64)>,
-) -> Result<(Vec<u8>, StatusCode, Option<String>), std::io::Error> {
-    let (start, end, status_code, content_range) = if let Some((start, end)) = range {
-        let content_range = format!("bytes {}-{}/{}", start, end, entry.size);
-        (start, end, StatusCode(206), Some(content_range))
-    } else {
-        (0, entry.size.saturating_sub(1), StatusCode(200), None)
-    };
-
-    let mut buffer = Vec::new();
+fn empty_response(status_code: u16) -> MediaResponse {
+    Response::new(
+        StatusCode(status_code),
+        Vec::new(),
+        Box::new(std::io::empty()),
+        Some(0),
+        None,
+    )
+}
 
+/// Reader for bytes `start..end` of an entry (files are streamed, not buffered)
+fn open_body(entry: &MediaEntry, start: u64, end: u64) -> Result<Box<dyn Read + Send>, std::io::Error> {
     match &entry.source {
         MediaSource::Data(data) => {
-            let end = end.min(entry.size.saturating_sub(1)) as usize;
-            let start = start.min(end as u64) as usize;
-            buffer.extend_from_slice(&data[start..=end]);
+            let slice = data[start as usize..end as usize].to_vec();
+            Ok(Box::new(Cursor::new(slice)))
         }
         MediaSource::File(path) => {
             let mut file = File::open(path)?;
             file.seek(SeekFrom::Start(start))?;
-            let length = (end - start + 1) as usize;
-            buffer.resize(length, 0);
-            file.read_exact(&mut buffer)?;
+            Ok(Box::new(file.take(end - start)))
         }
     }
-
-    Ok((buffer, status_code, content_range))
 }
 
 fn parse_audio_id(url: &str) -> Option<u64> {
@@ -283,38 +313,47 @@ fn parse_audio_id(url: &str) -> Option<u64> {
     parts[1].parse().ok()
 }
 
-fn parse_range(header: &str, total: u64) -> Option<(u64, u64)> {
-    if !header.starts_with("bytes=") {
-        return None;
+/// Resolve a `Range` header against an entry of `total` bytes.
+///
+/// Malformed headers and multi-range requests are ignored (full response),
+/// as RFC 9110 allows.
+fn parse_range(header: Option<&str>, total: u64) -> ByteRange {
+    let Some(range) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if range.contains(',') {
+        return ByteRange::Full;
     }
-
-    let range = header.trim_start_matches("bytes=");
-    let mut parts = range.split('-');
-    let start_str = parts.next().unwrap_or("");
-    let end_str = parts.next().unwrap_or("");
+    let Some((start_str, end_str)) = range.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
 
     if start_str.is_empty() {
-        let suffix = end_str.parse::<u64>().ok()?;
-        if suffix == 0 {
-            return None;
-        }
-        let start = total.saturating_sub(suffix);
-        let end = total.saturating_sub(1);
-        return Some((start, end));
-    }
-
-    let start = start_str.parse::<u64>().ok()?;
-    if start >= total {
-        return None;
+        // Suffix range: the last N bytes
+        return match end_str.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if total == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => ByteRange::Partial(total.saturating_sub(suffix), total - 1),
+            Err(_) => ByteRange::Full,
+        };
     }
 
+    let Ok(start) = start_str.parse::<u64>() else {
+        return ByteRange::Full;
+    };
     let end = if end_str.is_empty() {
-        total.saturating_sub(1)
+        u64::MAX
     } else {
-        end_str.parse::<u64>().ok()?.min(total.saturating_sub(1))
+        match end_str.parse::<u64>() {
+            Ok(end) if end >= start => end,
+            _ => return ByteRange::Full,
+        }
     };
 
-    Some((start, end))
+    if start >= total {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial(start, end.min(total - 1))
 }
 
 fn header(name: &str, value: &str) -> Header {
@@ -352,3 +391,54 @@ fn content_type_for_path(path: &Path) -> String {
         _ => "application/octet-stream".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::TcpStream;
+
+    #[test]
+    fn parses_single_byte_ranges() {
+        assert_eq!(parse_range(None, 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=10-19"), 100), ByteRange::Partial(10, 19));
+        assert_eq!(parse_range(Some("bytes=90-"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=90-500"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=-30"), 100), ByteRange::Partial(70, 99));
+        assert_eq!(parse_range(Some("bytes=-300"), 100), ByteRange::Partial(0, 99));
+        assert_eq!(parse_range(Some("bytes=100-"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=-0"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=20-10"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("items=0-1"), 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn serves_partial_content_for_open_ended_range() {
+        let mut server = MediaServer::start().expect("start media server");
+        let data: Vec<u8> = (0..4000u32).map(|i| (i % 251) as u8).collect();
+        server.register_audio(42, data.clone(), "audio/flac");
+
+        let mut stream = TcpStream::connect(("127.0.0.1", server.port())).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        write!(
+            stream,
+            "GET /audio/42 HTTP/1.1\r\nHost: localhost\r\nRange: bytes=1000-\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw).unwrap();
+
+        let split = raw.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8_lossy(&raw[..split]).to_lowercase();
+        let body = &raw[split + 4..];
+
+        assert!(head.starts_with("http/1.1 206"), "{}", head);
+        assert!(head.contains("content-range: bytes 1000-3999/4000"), "{}", head);
+        assert!(head.contains("content-length: 3000"), "{}", head);
+        assert!(head.contains("accept-ranges: bytes"), "{}", head);
+        assert_eq!(body, &data[1000..]);
+
+        server.stop();
+    }
+}
@@ -2,7 +2,9 @@
 
 use tauri::State;
 
-use crate::artist_blacklist::{BlacklistSettings, BlacklistState, BlacklistedArtist};
+use crate::artist_blacklist::{
+    BlacklistImportSummary, BlacklistSettings, BlacklistState, BlacklistedArtist,
+};
 
 /// Get all blacklisted artists
 #[tauri::command]
@@ -65,3 +67,21 @@ pub fn get_blacklist_count(state: State<BlacklistState>) -> Result<usize, String
 pub fn clear_artist_blacklist(state: State<BlacklistState>) -> Result<(), String> {
     state.clear_all()
 }
+
+/// Export the blacklist as JSON (artist IDs and names) for backup or sharing
+#[tauri::command]
+pub fn export_artist_blacklist(state: State<BlacklistState>) -> Result<String, String> {
+    state.export_json()
+}
+
+/// Import a blacklist exported by `export_artist_blacklist`
+///
+/// `merge` keeps the current entries; otherwise the blacklist is replaced.
+#[tauri::command]
+pub fn import_artist_blacklist(
+    json: String,
+    merge: bool,
+    state: State<BlacklistState>,
+) -> Result<BlacklistImportSummary, String> {
+    state.import_json(&json, merge)
+}
@@ -0,0 +1,87 @@
+//! Remote artwork commands
+//!
+//! Resolve cover URLs for the user's artwork quality and serve them from the
+//! on-disk artwork cache. Local library artwork has its own cache
+//! (`library_clear_artwork_cache`).
+
+use tauri::State;
+
+use crate::api::artwork;
+use crate::cache::{ArtworkCacheReport, ArtworkCacheState};
+use crate::config::artwork_settings::ArtworkSettingsState;
+
+/// Pixel size to request: the explicit size if given, otherwise the
+/// configured quality
+fn target_size(desired_size: Option<u32>, settings: &ArtworkSettingsState) -> u32 {
+    desired_size.unwrap_or_else(|| settings.quality().desired_size())
+}
+
+/// Best matching Qobuz cover URL for `desired_size` pixels (None = the
+/// configured artwork quality). Non-Qobuz URLs are returned unchanged.
+#[tauri::command]
+pub fn get_artwork_url(
+    base: String,
+    desired_size: Option<u32>,
+    settings: State<'_, ArtworkSettingsState>,
+) -> String {
+    artwork::get_artwork_url(&base, target_size(desired_size, &settings))
+}
+
+/// Local file path of the artwork for `url`, downloading it into the cache
+/// if needed
+#[tauri::command]
+pub async fn get_cached_artwork(
+    url: String,
+    desired_size: Option<u32>,
+    settings: State<'_, ArtworkSettingsState>,
+    cache_state: State<'_, ArtworkCacheState>,
+) -> Result<String, String> {
+    let cache = cache_state
+        .cache
+        .as_ref()
+        .ok_or("Artwork cache unavailable")?;
+    let url = artwork::get_artwork_url(&url, target_size(desired_size, &settings));
+
+    if let Some(path) = cache.get(&url) {
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    log::debug!("Command: get_cached_artwork fetching {}", url);
+    let response = crate::network::http_client::shared()
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch artwork: {}", e))?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read artwork: {}", e))?;
+
+    let path = cache.insert(&url, &bytes)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Disk usage of the artwork cache, broken down by cover size
+#[tauri::command]
+pub fn get_artwork_cache_report(
+    cache_state: State<'_, ArtworkCacheState>,
+) -> Result<ArtworkCacheReport, String> {
+    log::info!("Command: get_artwork_cache_report");
+    cache_state
+        .cache
+        .as_ref()
+        .map(|cache| cache.report())
+        .ok_or_else(|| "Artwork cache unavailable".to_string())
+}
+
+/// Delete all cached remote artwork, returning the bytes freed
+#[tauri::command]
+pub fn clear_artwork_cache(cache_state: State<'_, ArtworkCacheState>) -> Result<u64, String> {
+    log::info!("Command: clear_artwork_cache");
+    Ok(cache_state
+        .cache
+        .as_ref()
+        .map(|cache| cache.clear())
+        .unwrap_or(0))
+}
@@ -1,6 +1,8 @@
 //! Tauri commands for audio backend management
 
-use crate::audio::{AlsaPlugin, AudioBackendType, AudioDevice, BackendManager};
+use crate::audio::{
+    capabilities, AlsaPlugin, AudioBackendType, AudioDevice, BackendManager, DeviceFormatMatrix,
+};
 use serde::{Deserialize, Serialize};
 
 /// Backend information for UI display
@@ -167,6 +169,17 @@ pub fn get_linux_distro() -> LinuxDistroInfo {
     }
 }
 
+/// Every (sample rate, bit depth, channels) combination the device accepts
+/// and which Qobuz tiers it plays bit-perfect (None = system default device)
+#[tauri::command]
+pub fn get_device_format_matrix(
+    device_id: Option<String>,
+    refresh: Option<bool>,
+) -> Result<DeviceFormatMatrix, String> {
+    log::info!("Command: get_device_format_matrix({:?})", device_id);
+    capabilities::device_format_matrix(device_id.as_deref(), refresh.unwrap_or(false))
+}
+
 /// DAC capabilities information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DacCapabilities {
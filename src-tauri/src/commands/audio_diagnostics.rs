@@ -5,7 +5,12 @@ use std::fs;
 
 use tauri::Manager;
 use crate::AppState;
-use crate::audio::BitDepthResult;
+use crate::audio::backend::BitPerfectMode;
+use crate::audio::{
+    bitperfect, capabilities, AlsaDirectStream, AlsaPathReport, AlsaPlugin, AudioBackendType,
+    BitDepthResult, BitPerfectInputs, BitPerfectStatus, QualitySupport, XrunReport,
+};
+use crate::config::audio_settings::AudioSettings;
 
 /// Hardware audio status
 #[derive(Debug, Clone, serde::Serialize)]
@@ -16,11 +21,40 @@ pub struct HardwareAudioStatus {
     pub hardware_format: Option<String>,
     /// Whether hardware is currently active
     pub is_active: bool,
+    /// ALSA path of the current output (ALSA backend only): hw: vs plughw:
+    /// vs shared dmix, negotiated period/buffer and why hw: wasn't used
+    pub alsa_path: Option<AlsaPathReport>,
+    /// Qobuz tiers the selected device plays bit-perfect (None when it
+    /// can't be probed, e.g. a PipeWire sink)
+    pub quality_support: Option<Vec<QualitySupport>>,
 }
 
-/// Get actual hardware audio status by reading /proc/asound
+/// Get actual hardware audio status by reading /proc/asound, plus the ALSA
+/// path the player's output took
 #[tauri::command]
-pub fn get_hardware_audio_status() -> Result<HardwareAudioStatus, String> {
+pub fn get_hardware_audio_status(app: tauri::AppHandle) -> Result<HardwareAudioStatus, String> {
+    let mut status = read_hardware_audio_status()?;
+
+    let state = app.state::<AppState>();
+    let settings = state.player.current_settings();
+    status.quality_support =
+        capabilities::device_format_matrix(settings.output_device.as_deref(), false)
+            .ok()
+            .map(|matrix| matrix.qualities);
+
+    let output_sample_rate = state.player.state.get_output_sample_rate();
+    if output_sample_rate != 0 {
+        status.alsa_path = state
+            .player
+            .diagnostic
+            .alsa_path()
+            .or_else(|| shared_alsa_path(&settings, output_sample_rate, &status));
+    }
+    Ok(status)
+}
+
+/// Read the first active PCM's hw_params from /proc/asound
+fn read_hardware_audio_status() -> Result<HardwareAudioStatus, String> {
     // Read all hw_params files from /proc/asound
     let proc_pattern = "/proc/asound/card*/pcm*/sub*/hw_params";
     
@@ -64,6 +98,8 @@ pub fn get_hardware_audio_status() -> Result<HardwareAudioStatus, String> {
                     hardware_sample_rate: sample_rate,
                     hardware_format: format,
                     is_active: true,
+                    alsa_path: None,
+                    quality_support: None,
                 });
             }
         }
@@ -74,6 +110,43 @@ pub fn get_hardware_audio_status() -> Result<HardwareAudioStatus, String> {
         hardware_sample_rate: None,
         hardware_format: None,
         is_active: false,
+        alsa_path: None,
+        quality_support: None,
+    })
+}
+
+/// Report for an ALSA backend output that went through CPAL instead of ALSA
+/// Direct, i.e. the shared device mixed by dmix (None for other backends)
+fn shared_alsa_path(
+    settings: &AudioSettings,
+    output_sample_rate: u32,
+    hardware: &HardwareAudioStatus,
+) -> Option<AlsaPathReport> {
+    if settings.backend_type != Some(AudioBackendType::Alsa) {
+        return None;
+    }
+    let device_id = settings
+        .output_device
+        .clone()
+        .unwrap_or_else(|| "default".to_string());
+    let reason = if settings.alsa_plugin == Some(AlsaPlugin::Pcm) {
+        "PCM selected in the ALSA plugin setting, output is mixed by dmix".to_string()
+    } else if !AlsaDirectStream::is_hw_device(&device_id) {
+        format!("{} is not a hw: device, output is mixed by dmix", device_id)
+    } else {
+        "ALSA Direct was not used, output is mixed by dmix".to_string()
+    };
+
+    Some(AlsaPathReport {
+        mode: BitPerfectMode::Disabled,
+        device_id,
+        requested_sample_rate: output_sample_rate,
+        sample_rate: hardware.hardware_sample_rate.unwrap_or(0),
+        format: hardware.hardware_format.clone(),
+        buffer_frames: 0,
+        period_frames: 0,
+        format_unchanged: false,
+        fallback_reason: Some(reason),
     })
 }
 
@@ -107,3 +180,166 @@ pub fn stop_bitdepth_capture(
     let state = app.state::<AppState>();
     Ok(state.player.diagnostic.stop_and_analyze())
 }
+
+/// Get output xrun diagnostics: underrun count, current period/buffer size
+/// and recent recovery events.
+///
+/// Only ALSA Direct reports xruns. A rising underrun count with a small
+/// period points at the buffer size; occasional bursts point at CPU starvation.
+#[tauri::command]
+pub fn get_audio_diagnostics(
+    app: tauri::AppHandle,
+) -> Result<XrunReport, String> {
+    let state = app.state::<AppState>();
+    Ok(state.player.diagnostic.xrun_report())
+}
+
+/// Report whether playback is currently bit-perfect.
+///
+/// Compares the decoded source format against the negotiated output stream
+/// and the format the device reports, listing anything that breaks it
+/// (resampling, software volume, normalization, shared-mode mixing).
+#[tauri::command]
+pub fn get_bitperfect_status(
+    app: tauri::AppHandle,
+) -> Result<BitPerfectStatus, String> {
+    let state = app.state::<AppState>();
+    let player_state = &state.player.state;
+    let settings = state.player.current_settings();
+
+    let hardware = read_hardware_audio_status().ok().filter(|h| h.is_active);
+
+    let inputs = BitPerfectInputs {
+        source_sample_rate: player_state.get_sample_rate(),
+        source_bit_depth: player_state.get_bit_depth(),
+        output_sample_rate: player_state.get_output_sample_rate(),
+        alsa_direct: player_state.is_output_alsa_direct(),
+        hardware_sample_rate: hardware.as_ref().and_then(|h| h.hardware_sample_rate),
+        hardware_format: hardware.and_then(|h| h.hardware_format),
+        backend_type: settings.backend_type,
+        alsa_plugin: settings.alsa_plugin,
+        exclusive_mode: settings.exclusive_mode,
+        volume: player_state.volume(),
+        hardware_volume: settings.alsa_hardware_volume,
+        normalization_gain: player_state.get_normalization_gain(),
+        eq_active: state.player.equalizer.is_active(),
+    };
+
+    let status = bitperfect::evaluate(&inputs);
+    log::info!(
+        "Command: get_bitperfect_status -> bit_perfect: {}, reasons: {:?}",
+        status.bit_perfect,
+        status.reasons
+    );
+    Ok(status)
+}
+
+/// Sample rates accepted by `test_audio_output`
+const TEST_SAMPLE_RATE_RANGE: std::ops::RangeInclusive<u32> = 8_000..=768_000;
+
+/// Result of `test_audio_output`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AudioOutputTestResult {
+    /// Whether the output stream opened and the tone played
+    pub opened: bool,
+    /// Why the stream couldn't be opened
+    pub error: Option<String>,
+    pub device: Option<String>,
+    pub backend_type: Option<AudioBackendType>,
+    pub alsa_direct: bool,
+    pub requested_sample_rate: u32,
+    pub requested_bit_depth: u32,
+    /// Format the hardware reported while the tone played
+    pub negotiated_sample_rate: Option<u32>,
+    pub negotiated_format: Option<String>,
+    /// Whether the hardware ran at the requested rate with at least the
+    /// requested bit depth (None when the hardware format couldn't be read;
+    /// float formats only check the rate)
+    pub exact_format: Option<bool>,
+}
+
+/// Play a short sine sweep through `device` (or the configured device) at
+/// the given format, using the configured backend, exclusive mode and DAC
+/// passthrough settings.
+///
+/// Lets users check that a device and format work before committing to them.
+/// Refuses while the player holds the output device.
+#[tauri::command]
+pub async fn test_audio_output(
+    device: Option<String>,
+    sample_rate: u32,
+    bit_depth: u32,
+    app: tauri::AppHandle,
+) -> Result<AudioOutputTestResult, String> {
+    log::info!(
+        "Command: test_audio_output (device: {:?}, {}Hz, {}-bit)",
+        device,
+        sample_rate,
+        bit_depth
+    );
+    if !TEST_SAMPLE_RATE_RANGE.contains(&sample_rate) {
+        return Err(format!("Unsupported sample rate: {}Hz", sample_rate));
+    }
+    if ![16, 24, 32].contains(&bit_depth) {
+        return Err(format!("Unsupported bit depth: {}", bit_depth));
+    }
+
+    let state = app.state::<AppState>();
+    if state.player.state.is_playing() || state.player.state.get_output_sample_rate() != 0 {
+        return Err("Stop playback before testing the audio output".to_string());
+    }
+
+    let mut settings = state.player.current_settings();
+    if device.is_some() {
+        settings.output_device = device;
+    }
+    let device = settings.output_device.clone();
+    let backend_type = settings.backend_type;
+
+    let outcome = tauri::async_runtime::spawn_blocking(move || {
+        crate::player::play_test_tone(&settings, sample_rate, bit_depth, || {
+            read_hardware_audio_status().ok().filter(|h| h.is_active)
+        })
+    })
+    .await
+    .map_err(|e| format!("Audio output test failed: {}", e))?;
+
+    let mut result = AudioOutputTestResult {
+        opened: false,
+        error: None,
+        device,
+        backend_type,
+        alsa_direct: false,
+        requested_sample_rate: sample_rate,
+        requested_bit_depth: bit_depth,
+        negotiated_sample_rate: None,
+        negotiated_format: None,
+        exact_format: None,
+    };
+    match outcome {
+        Ok((alsa_direct, hardware)) => {
+            result.opened = true;
+            result.alsa_direct = alsa_direct;
+            if let Some(hardware) = hardware {
+                result.exact_format = hardware.hardware_sample_rate.map(|rate| {
+                    let bits = hardware.hardware_format.as_deref().and_then(bitperfect::format_bits);
+                    rate == sample_rate && bits.map_or(true, |bits| bits >= bit_depth)
+                });
+                result.negotiated_sample_rate = hardware.hardware_sample_rate;
+                result.negotiated_format = hardware.hardware_format;
+            }
+        }
+        Err(e) => {
+            log::warn!("Audio output test: stream failed to open: {}", e);
+            result.error = Some(e);
+        }
+    }
+    log::info!(
+        "Audio output test: opened: {}, negotiated: {:?}Hz {:?}, exact: {:?}",
+        result.opened,
+        result.negotiated_sample_rate,
+        result.negotiated_format,
+        result.exact_format
+    );
+    Ok(result)
+}
@@ -6,7 +6,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use crate::credentials;
 use crate::AppState;
 use crate::api::error::ApiError;
-use crate::config::SubscriptionStateState;
+use crate::api::RequestPolicy;
+use crate::config::{RegionSettingsState, SubscriptionStateState};
 use crate::offline_cache::OfflineCacheState;
 
 #[derive(serde::Serialize)]
@@ -288,10 +289,36 @@ pub async fn auto_login(
     }
 }
 
+/// Report the UI language. It is used for API requests unless the user has
+/// picked a different content language.
 #[tauri::command]
-pub async fn set_api_locale(locale: String, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn set_api_locale(
+    locale: String,
+    state: State<'_, AppState>,
+    region_state: State<'_, RegionSettingsState>,
+) -> Result<(), String> {
     log::info!("Command: set_api_locale {}", locale);
+    region_state.set_ui_locale(&locale);
     let client = state.client.read().await;
-    client.set_locale(locale).await;
+    client.set_locale(region_state.effective_locale()).await;
     Ok(())
 }
+
+/// Timeouts and retries used for Qobuz API requests
+#[tauri::command]
+pub async fn get_request_policy(state: State<'_, AppState>) -> Result<RequestPolicy, String> {
+    Ok(state.client.read().await.request_policy())
+}
+
+/// Change timeouts and retries for Qobuz API requests (advanced setting,
+/// kept until restart)
+#[tauri::command]
+pub async fn set_request_policy(
+    policy: RequestPolicy,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    log::info!("Command: set_request_policy {:?}", policy);
+    policy.validate()?;
+    let mut client = state.client.write().await;
+    client.set_request_policy(policy).map_err(|e| e.to_string())
+}
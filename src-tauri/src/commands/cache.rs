@@ -3,7 +3,9 @@
 use tauri::State;
 
 use crate::api_cache::ApiCacheState;
-use crate::cache::CacheStats;
+use crate::cache::{mb_to_bytes, CacheStats, MAX_DISK_CACHE_MB, MAX_MEMORY_CACHE_MB, MIN_CACHE_MB};
+use crate::config::audio_settings::AudioSettingsState;
+use crate::user_data::{compute_usage, UsageBreakdown, UsageRoots, UserDataPaths};
 use crate::AppState;
 
 /// Get cache statistics
@@ -13,6 +15,78 @@ pub fn get_cache_stats(state: State<'_, AppState>) -> CacheStats {
     state.audio_cache.stats()
 }
 
+/// Change the L1 (memory) and L2 (disk) cache limits, evicting down if
+/// shrinking. The limits are saved with the audio settings and used on the
+/// next start. Returns the updated stats so the UI can show current usage.
+#[tauri::command]
+pub fn set_cache_limits(
+    l1_bytes: u64,
+    l2_bytes: u64,
+    state: State<'_, AppState>,
+    audio_settings: State<'_, AudioSettingsState>,
+) -> Result<CacheStats, String> {
+    log::info!("Command: set_cache_limits l1={} l2={}", l1_bytes, l2_bytes);
+
+    let memory_mb = limit_mb(l1_bytes, MAX_MEMORY_CACHE_MB, "Memory")?;
+    let disk_mb = limit_mb(l2_bytes, MAX_DISK_CACHE_MB, "Disk")?;
+
+    {
+        let guard = audio_settings
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.set_cache_limits_mb(memory_mb, disk_mb)?;
+    }
+
+    apply_cache_limits(&state, memory_mb, disk_mb);
+    Ok(state.audio_cache.stats())
+}
+
+/// Resize both cache tiers
+pub fn apply_cache_limits(state: &AppState, memory_mb: u32, disk_mb: u32) {
+    state.audio_cache.resize(mb_to_bytes(memory_mb) as usize);
+    if let Some(playback_cache) = state.audio_cache.get_playback_cache() {
+        playback_cache.resize(mb_to_bytes(disk_mb));
+    }
+}
+
+fn limit_mb(bytes: u64, max_mb: u32, tier: &str) -> Result<u32, String> {
+    let mb = bytes / (1024 * 1024);
+    if mb < MIN_CACHE_MB as u64 || mb > max_mb as u64 {
+        return Err(format!(
+            "{} cache limit must be between {} MB and {} MB",
+            tier, MIN_CACHE_MB, max_mb
+        ));
+    }
+    Ok(mb as u32)
+}
+
+/// Disk usage of the active user's data, per subsystem
+///
+/// Walking the cache directories can take a while, so it runs on the
+/// blocking pool and the result is reused for a short while; `refresh`
+/// skips that (e.g. right after clearing a cache).
+#[tauri::command]
+pub async fn get_storage_usage(
+    refresh: Option<bool>,
+    user_paths: State<'_, UserDataPaths>,
+) -> Result<UsageBreakdown, String> {
+    log::info!("Command: get_storage_usage");
+    if !refresh.unwrap_or(false) {
+        if let Some(usage) = user_paths.cached_usage() {
+            return Ok(usage);
+        }
+    }
+
+    let roots = UsageRoots::for_user(&user_paths)?;
+    let usage = tokio::task::spawn_blocking(move || compute_usage(&roots))
+        .await
+        .map_err(|e| format!("Storage usage task failed: {}", e))?;
+    user_paths.store_usage(usage.clone());
+    Ok(usage)
+}
+
 /// Clear the audio cache
 #[tauri::command]
 pub fn clear_cache(state: State<'_, AppState>) -> Result<(), String> {
@@ -3,8 +3,14 @@
 use serde::Serialize;
 use tauri::State;
 
-use crate::api::models::Album;
+use crate::api::models::{Album, Track};
 use crate::api::performers::{parse_performers, Performer};
+use crate::commands::musicbrainz::fetch_release_credits;
+use crate::musicbrainz::credits::{
+    collect_people, group_by_role, merge_credits, CreditedPerson, PersonCredit, ReleaseCredits,
+    RoleCredits,
+};
+use crate::musicbrainz::MusicBrainzSharedState;
 use crate::AppState;
 
 /// Track credits with parsed performers
@@ -51,6 +57,30 @@ pub struct AlbumInfo {
     pub description: Option<String>,
 }
 
+/// Track credits with performers merged from Qobuz and MusicBrainz
+#[derive(Debug, Clone, Serialize)]
+pub struct DetailedTrackCredits {
+    #[serde(flatten)]
+    pub credits: TrackCredits,
+    pub disc_number: u32,
+    /// Everyone credited on the track, each person once
+    pub people: Vec<PersonCredit>,
+    /// The same people grouped by role
+    pub roles: Vec<RoleCredits>,
+}
+
+/// Album credits with performer/role resolution across all tracks
+#[derive(Debug, Clone, Serialize)]
+pub struct DetailedAlbumCredits {
+    pub album: AlbumInfo,
+    pub tracks: Vec<DetailedTrackCredits>,
+    /// Credits MusicBrainz lists for the whole release (e.g. producer)
+    pub album_roles: Vec<RoleCredits>,
+    /// Everyone credited on the album, with the tracks they appear on
+    pub people: Vec<CreditedPerson>,
+    pub musicbrainz_release_id: Option<String>,
+}
+
 /// Format duration in seconds to "Xm Ys" or "Xh Ym" format
 fn format_duration(seconds: u32) -> String {
     let hours = seconds / 3600;
@@ -112,6 +142,34 @@ fn album_to_info(album: &Album) -> AlbumInfo {
     }
 }
 
+/// Convert API Track to TrackCredits
+fn track_credits(album: &Album, track: &Track) -> TrackCredits {
+    let performers = track
+        .performers
+        .as_ref()
+        .map(|p| parse_performers(p))
+        .unwrap_or_default();
+
+    TrackCredits {
+        id: track.id,
+        number: track.track_number,
+        title: track.title.clone(),
+        artist: track
+            .performer
+            .as_ref()
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| album.artist.name.clone()),
+        duration: format_track_duration(track.duration),
+        duration_seconds: track.duration,
+        performers,
+        copyright: track.copyright.clone(),
+        album_id: Some(album.id.clone()),
+        artist_id: track.performer.as_ref().and_then(|p| {
+            if p.id > 0 { Some(p.id) } else { None }
+        }),
+    }
+}
+
 /// Get album credits with all tracks and parsed performers
 #[tauri::command]
 pub async fn get_album_credits(
@@ -133,43 +191,77 @@ pub async fn get_album_credits(
 
     // Process tracks with performers
     let tracks: Vec<TrackCredits> = album
+        .tracks
+        .as_ref()
+        .map(|tc| tc.items.iter().map(|track| track_credits(&album, track)).collect())
+        .unwrap_or_default();
+
+    Ok(AlbumCredits {
+        album: album_info,
+        tracks,
+    })
+}
+
+/// Get album credits with Qobuz performers merged with MusicBrainz
+/// relationships, grouped by role and de-duplicated across tracks
+#[tauri::command]
+pub async fn get_album_credits_detailed(
+    album_id: String,
+    state: State<'_, AppState>,
+    mb_state: State<'_, MusicBrainzSharedState>,
+) -> Result<DetailedAlbumCredits, String> {
+    log::info!("Command: get_album_credits_detailed {}", album_id);
+
+    let album = {
+        let client = state.client.read().await;
+        client
+            .get_album(&album_id)
+            .await
+            .map_err(|e| format!("Failed to get album: {}", e))?
+    };
+
+    let release = fetch_release_credits(&mb_state, &album).await;
+    let (musicbrainz_release_id, release_credits) = match release {
+        Some((mbid, credits)) => (Some(mbid), credits),
+        None => (None, ReleaseCredits::default()),
+    };
+
+    let tracks: Vec<DetailedTrackCredits> = album
         .tracks
         .as_ref()
         .map(|tc| {
             tc.items
                 .iter()
                 .map(|track| {
-                    let performers = track
-                        .performers
-                        .as_ref()
-                        .map(|p| parse_performers(p))
-                        .unwrap_or_default();
-
-                    TrackCredits {
-                        id: track.id,
-                        number: track.track_number,
-                        title: track.title.clone(),
-                        artist: track
-                            .performer
-                            .as_ref()
-                            .map(|p| p.name.clone())
-                            .unwrap_or_else(|| album.artist.name.clone()),
-                        duration: format_track_duration(track.duration),
-                        duration_seconds: track.duration,
-                        performers,
-                        copyright: track.copyright.clone(),
-                        album_id: Some(album.id.clone()),
-                        artist_id: track.performer.as_ref().and_then(|p| {
-                            if p.id > 0 { Some(p.id) } else { None }
-                        }),
+                    let credits = track_credits(&album, track);
+                    let disc_number = track.media_number.unwrap_or(1);
+                    let people = merge_credits(
+                        &credits.performers,
+                        release_credits.track(disc_number, track.track_number),
+                    );
+                    DetailedTrackCredits {
+                        roles: group_by_role(&people),
+                        credits,
+                        disc_number,
+                        people,
                     }
                 })
                 .collect()
         })
         .unwrap_or_default();
 
-    Ok(AlbumCredits {
-        album: album_info,
+    let album_people = merge_credits(&[], &release_credits.album);
+    let track_people: Vec<(u64, &[PersonCredit])> = tracks
+        .iter()
+        .map(|t| (t.credits.id, t.people.as_slice()))
+        .collect();
+    let people = collect_people(&album_people, &track_people);
+
+    Ok(DetailedAlbumCredits {
+        album: album_to_info(&album),
+        album_roles: group_by_role(&album_people),
         tracks,
+        people,
+        musicbrainz_release_id,
     })
 }
@@ -0,0 +1,151 @@
+//! Diagnostics bundle for bug reports
+//!
+//! Collects the information maintainers usually have to ask for (versions,
+//! platform, graphics, audio path, cache, offline state, recent logs) into a
+//! single JSON document. Log lines go through the same redaction as log uploads.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::audio::BitPerfectStatus;
+use crate::cache::CacheStats;
+use crate::commands::audio_backends::{get_linux_distro, LinuxDistroInfo};
+use crate::commands::audio_diagnostics::{
+    get_bitperfect_status, get_hardware_audio_status, HardwareAudioStatus,
+};
+use crate::commands::playback::{get_audio_output_status, AudioOutputStatus};
+use crate::config::audio_settings::AudioSettings;
+use crate::config::graphics_settings::{
+    get_graphics_settings, get_graphics_startup_status, GraphicsSettings, GraphicsSettingsState,
+    GraphicsStartupStatus,
+};
+use crate::offline::{OfflineState, OfflineStatus};
+use crate::player::DecodedFormatInfo;
+use crate::AppState;
+
+/// Number of recent log lines included in the bundle
+const LOG_TAIL_LINES: usize = 300;
+
+#[derive(Serialize)]
+pub struct DiagnosticsBundle {
+    /// Unix timestamp (seconds) when the bundle was generated
+    pub generated_at: u64,
+    pub app_version: String,
+    pub platform: PlatformInfo,
+    pub graphics: GraphicsDiagnostics,
+    pub audio: AudioDiagnostics,
+    pub cache: CacheStats,
+    /// None if no user session is active
+    pub offline: Option<OfflineStatus>,
+    /// Most recent backend log lines, redacted
+    pub log_tail: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PlatformInfo {
+    pub os: String,
+    pub arch: String,
+    pub kernel: Option<String>,
+    pub distro: LinuxDistroInfo,
+    pub desktop: Option<String>,
+    pub session_type: Option<String>,
+    pub flatpak: bool,
+}
+
+#[derive(Serialize)]
+pub struct GraphicsDiagnostics {
+    pub startup: GraphicsStartupStatus,
+    pub settings: Option<GraphicsSettings>,
+}
+
+#[derive(Serialize)]
+pub struct AudioDiagnostics {
+    /// Configured backend, device, plugin, etc.
+    pub settings: AudioSettings,
+    pub output: Option<AudioOutputStatus>,
+    /// What the hardware actually negotiated (from /proc/asound)
+    pub hardware: Option<HardwareAudioStatus>,
+    /// Format of the track currently being decoded
+    pub format: Option<DecodedFormatInfo>,
+    pub bitperfect: Option<BitPerfectStatus>,
+}
+
+async fn collect_bundle(app: &AppHandle) -> DiagnosticsBundle {
+    let state = app.state::<AppState>();
+
+    let generated_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let platform = PlatformInfo {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        kernel: std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .ok()
+            .map(|s| s.trim().to_string()),
+        distro: get_linux_distro(),
+        desktop: std::env::var("XDG_CURRENT_DESKTOP").ok(),
+        session_type: std::env::var("XDG_SESSION_TYPE").ok(),
+        flatpak: crate::flatpak::is_flatpak(),
+    };
+
+    let graphics = GraphicsDiagnostics {
+        startup: get_graphics_startup_status(),
+        settings: app
+            .try_state::<GraphicsSettingsState>()
+            .and_then(|s| get_graphics_settings(s).ok()),
+    };
+
+    let audio = AudioDiagnostics {
+        settings: state.player.current_settings(),
+        output: get_audio_output_status(state.clone()).ok(),
+        hardware: get_hardware_audio_status(app.clone()).ok(),
+        format: state.player.state.format_info(),
+        bitperfect: get_bitperfect_status(app.clone()).ok(),
+    };
+
+    let offline = match app.try_state::<OfflineState>() {
+        Some(offline_state) => crate::offline::commands::get_offline_status(offline_state)
+            .await
+            .ok(),
+        None => None,
+    };
+
+    let secrets = crate::logging::known_secrets(app).await;
+    let logs = crate::logging::get_logs();
+    let log_tail = logs[logs.len().saturating_sub(LOG_TAIL_LINES)..]
+        .iter()
+        .map(|line| crate::logging::redact(line, &secrets))
+        .collect();
+
+    DiagnosticsBundle {
+        generated_at,
+        app_version: app.package_info().version.to_string(),
+        platform,
+        graphics,
+        audio,
+        cache: state.audio_cache.stats(),
+        offline,
+        log_tail,
+    }
+}
+
+// Tauri commands
+
+/// Everything needed for a bug report, as one JSON document
+#[tauri::command]
+pub async fn get_diagnostics_bundle(app: AppHandle) -> Result<DiagnosticsBundle, String> {
+    log::info!("Command: get_diagnostics_bundle");
+    Ok(collect_bundle(&app).await)
+}
+
+/// Write the diagnostics bundle as pretty-printed JSON to `path`
+#[tauri::command]
+pub async fn save_diagnostics_bundle(path: String, app: AppHandle) -> Result<(), String> {
+    log::info!("Command: save_diagnostics_bundle -> {}", path);
+    let bundle = collect_bundle(&app).await;
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize diagnostics: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write diagnostics: {}", e))
+}
@@ -0,0 +1,81 @@
+//! Parametric equalizer commands
+//!
+//! The config is persisted in audio settings and pushed to the player's
+//! `EqControl`, so changes apply to the playing track immediately.
+
+use tauri::State;
+
+use crate::audio::{EqConfig, EqPreset, NamedEqPreset};
+use crate::config::audio_settings::AudioSettingsState;
+use crate::AppState;
+
+fn save_eq_config(
+    audio_settings: &State<'_, AudioSettingsState>,
+    config: &EqConfig,
+) -> Result<(), String> {
+    let guard = audio_settings
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_eq_config(config)
+}
+
+/// Get the current equalizer configuration
+#[tauri::command]
+pub fn get_eq_config(state: State<'_, AppState>) -> Result<EqConfig, String> {
+    Ok(state.player.equalizer.config())
+}
+
+/// Replace the equalizer configuration (values are clamped to sane ranges)
+#[tauri::command]
+pub fn set_eq_config(
+    config: EqConfig,
+    state: State<'_, AppState>,
+    audio_settings: State<'_, AudioSettingsState>,
+) -> Result<EqConfig, String> {
+    let config = config.sanitized();
+    log::info!(
+        "Command: set_eq_config enabled={} preamp={}dB bands={}",
+        config.enabled,
+        config.preamp_db,
+        config.bands.len()
+    );
+    save_eq_config(&audio_settings, &config)?;
+    state.player.equalizer.set_config(config.clone());
+    Ok(config)
+}
+
+/// Enable or bypass the equalizer without touching the bands
+#[tauri::command]
+pub fn set_eq_enabled(
+    enabled: bool,
+    state: State<'_, AppState>,
+    audio_settings: State<'_, AudioSettingsState>,
+) -> Result<(), String> {
+    log::info!("Command: set_eq_enabled {}", enabled);
+    let mut config = state.player.equalizer.config();
+    config.enabled = enabled;
+    save_eq_config(&audio_settings, &config)?;
+    state.player.equalizer.set_config(config);
+    Ok(())
+}
+
+/// Whether EQ is currently processing the output (false when bypassed,
+/// including the automatic bypass on bit-perfect paths)
+#[tauri::command]
+pub fn is_eq_active(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.player.equalizer.is_active())
+}
+
+/// Built-in presets (Flat, Bass Boost, Vocal)
+#[tauri::command]
+pub fn get_eq_presets() -> Result<Vec<NamedEqPreset>, String> {
+    Ok(EqPreset::ALL
+        .iter()
+        .map(|&preset| NamedEqPreset {
+            preset,
+            config: EqConfig::preset(preset),
+        })
+        .collect())
+}
@@ -1,11 +1,46 @@
 //! Favorites-related Tauri commands
+//!
+//! Batch changes (`add_favorites_batch` / `remove_favorites_batch`) update the
+//! local favorites cache right away and are queued in the offline store; a
+//! background sync sends them to Qobuz, coalescing per item, and reverts the
+//! cache for items that keep failing (emitting `favorites:reverted`).
+//!
+//! `add_favorites` / `remove_favorites` instead wait for Qobuz and report the
+//! outcome of every item; only the items that succeeded touch the cache.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 
+use crate::api::{ApiError, QobuzClient};
 use crate::api_cache::ApiCacheState;
+use crate::config::favorites_cache::FavoritesCacheState;
+use crate::offline::{FavoriteAction, OfflineState, QueuedFavorite};
 use crate::AppState;
 
+/// favorite/create and favorite/delete accept comma-separated IDs
+const FAVORITE_BATCH_SIZE: usize = 50;
+
+/// Failed sync attempts before a queued change is reverted in the cache
+const MAX_FAVORITE_SYNC_ATTEMPTS: u32 = 5;
+
+/// How often the background sync retries queued changes
+pub const FAVORITE_SYNC_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+/// Favorite requests in flight at once for `add_favorites` / `remove_favorites`
+const FAVORITE_CONCURRENCY: usize = 4;
+
+/// Retries of a rate-limited favorite request, with exponential backoff
+const FAVORITE_RATE_LIMIT_RETRIES: u32 = 3;
+const FAVORITE_BACKOFF_BASE: Duration = Duration::from_millis(500);
+
+static FAVORITE_SYNC_RUNNING: AtomicBool = AtomicBool::new(false);
+static FAVORITE_SYNC_REQUESTED: AtomicBool = AtomicBool::new(false);
+
 /// Get user's favorites
 /// fav_type can be: "albums", "tracks", or "artists"
 #[tauri::command]
@@ -42,25 +77,7 @@ pub async fn add_favorite(
         .map_err(|e| format!("Failed to add favorite: {}", e))?;
 
     // Invalidate stale cache entry so next fetch gets fresh data
-    {
-        let guard = cache_state.cache.lock().await;
-        if let Some(cache) = guard.as_ref() {
-            match fav_type.as_str() {
-                "album" => { let _ = cache.invalidate_album(&item_id); }
-                "track" => {
-                    if let Ok(id) = item_id.parse::<u64>() {
-                        let _ = cache.invalidate_track(id);
-                    }
-                }
-                "artist" => {
-                    if let Ok(id) = item_id.parse::<u64>() {
-                        let _ = cache.invalidate_artist(id);
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
+    invalidate_cached_items(&cache_state, &fav_type, std::slice::from_ref(&item_id)).await;
 
     Ok(())
 }
@@ -83,25 +100,686 @@ pub async fn remove_favorite(
         .map_err(|e| format!("Failed to remove favorite: {}", e))?;
 
     // Invalidate stale cache entry so next fetch gets fresh data
-    {
-        let guard = cache_state.cache.lock().await;
-        if let Some(cache) = guard.as_ref() {
-            match fav_type.as_str() {
-                "album" => { let _ = cache.invalidate_album(&item_id); }
-                "track" => {
-                    if let Ok(id) = item_id.parse::<u64>() {
-                        let _ = cache.invalidate_track(id);
-                    }
+    invalidate_cached_items(&cache_state, &fav_type, std::slice::from_ref(&item_id)).await;
+
+    Ok(())
+}
+
+/// Queued changes that could not be synced and were undone locally
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertedFavorite {
+    pub fav_type: String,
+    pub item_id: String,
+    /// The change that was undone
+    pub action: FavoriteAction,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FavoriteSyncResult {
+    pub synced: u32,
+    pub failed: u32,
+    pub reverted: u32,
+}
+
+/// Add items to favorites without waiting for Qobuz.
+/// The local cache is updated immediately; the change is synced in the background.
+/// fav_type can be: "album", "track", or "artist"
+#[tauri::command]
+pub async fn add_favorites_batch(
+    fav_type: String,
+    item_ids: Vec<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    log::info!(
+        "Command: add_favorites_batch type={} count={}",
+        fav_type,
+        item_ids.len()
+    );
+    queue_favorite_change(&app, &fav_type, &item_ids, FavoriteAction::Add)
+}
+
+/// Remove items from favorites without waiting for Qobuz.
+/// The local cache is updated immediately; the change is synced in the background.
+/// fav_type can be: "album", "track", or "artist"
+#[tauri::command]
+pub async fn remove_favorites_batch(
+    fav_type: String,
+    item_ids: Vec<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    log::info!(
+        "Command: remove_favorites_batch type={} count={}",
+        fav_type,
+        item_ids.len()
+    );
+    queue_favorite_change(&app, &fav_type, &item_ids, FavoriteAction::Remove)
+}
+
+/// An item of an `add_favorites` / `remove_favorites` call
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteItem {
+    /// "album", "track", or "artist"
+    pub fav_type: String,
+    pub item_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteItemResult {
+    pub fav_type: String,
+    pub item_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Add items to favorites on Qobuz and report the result of each, in the
+/// order given. Items can mix types.
+#[tauri::command]
+pub async fn add_favorites(
+    items: Vec<FavoriteItem>,
+    state: State<'_, AppState>,
+    cache_state: State<'_, ApiCacheState>,
+    favorites_cache: State<'_, FavoritesCacheState>,
+) -> Result<Vec<FavoriteItemResult>, String> {
+    log::info!("Command: add_favorites count={}", items.len());
+    let client = state.client.read().await;
+    let results =
+        change_favorites(&*client, items, FavoriteAction::Add, FAVORITE_BACKOFF_BASE).await;
+    record_changed_favorites(&results, FavoriteAction::Add, &favorites_cache, &cache_state).await;
+    Ok(results)
+}
+
+/// Remove items from favorites on Qobuz and report the result of each, in
+/// the order given. Items can mix types.
+#[tauri::command]
+pub async fn remove_favorites(
+    items: Vec<FavoriteItem>,
+    state: State<'_, AppState>,
+    cache_state: State<'_, ApiCacheState>,
+    favorites_cache: State<'_, FavoritesCacheState>,
+) -> Result<Vec<FavoriteItemResult>, String> {
+    log::info!("Command: remove_favorites count={}", items.len());
+    let client = state.client.read().await;
+    let results =
+        change_favorites(&*client, items, FavoriteAction::Remove, FAVORITE_BACKOFF_BASE).await;
+    record_changed_favorites(&results, FavoriteAction::Remove, &favorites_cache, &cache_state)
+        .await;
+    Ok(results)
+}
+
+/// The favorite request `change_favorites` makes, so it can run without Qobuz in tests
+trait FavoriteApi {
+    async fn change_favorite(
+        &self,
+        fav_type: &str,
+        item_id: &str,
+        action: FavoriteAction,
+    ) -> Result<(), ApiError>;
+}
+
+impl FavoriteApi for QobuzClient {
+    async fn change_favorite(
+        &self,
+        fav_type: &str,
+        item_id: &str,
+        action: FavoriteAction,
+    ) -> Result<(), ApiError> {
+        match action {
+            FavoriteAction::Add => self.add_favorite(fav_type, item_id).await,
+            FavoriteAction::Remove => self.remove_favorite(fav_type, item_id).await,
+        }
+    }
+}
+
+/// Apply `action` to every item, `FAVORITE_CONCURRENCY` at a time, retrying
+/// rate-limited requests after the longer of Retry-After and an exponential
+/// backoff from `backoff_base`. Results are in the order of `items`.
+async fn change_favorites(
+    client: &impl FavoriteApi,
+    items: Vec<FavoriteItem>,
+    action: FavoriteAction,
+    backoff_base: Duration,
+) -> Vec<FavoriteItemResult> {
+    // Owned items: borrowed ones held across awaits make the command future
+    // not general enough for the handler
+    stream::iter(items)
+        .map(|item| async move {
+            let outcome = match validate_favorite_item(&item) {
+                Ok(()) => change_favorite_with_retry(client, &item, action, backoff_base).await,
+                Err(e) => Err(e),
+            };
+            if let Err(ref e) = outcome {
+                log::warn!(
+                    "Failed to {} favorite {} {}: {}",
+                    action.as_str(),
+                    item.fav_type,
+                    item.item_id,
+                    e
+                );
+            }
+            FavoriteItemResult {
+                fav_type: item.fav_type,
+                item_id: item.item_id,
+                success: outcome.is_ok(),
+                error: outcome.err(),
+            }
+        })
+        .buffered(FAVORITE_CONCURRENCY)
+        .collect()
+        .await
+}
+
+fn validate_favorite_item(item: &FavoriteItem) -> Result<(), String> {
+    match item.fav_type.as_str() {
+        "album" => Ok(()),
+        "track" | "artist" if item.item_id.parse::<i64>().is_ok() => Ok(()),
+        "track" | "artist" => Err(format!("Invalid {} id: {}", item.fav_type, item.item_id)),
+        other => Err(format!("Unknown favorite type: {}", other)),
+    }
+}
+
+async fn change_favorite_with_retry(
+    client: &impl FavoriteApi,
+    item: &FavoriteItem,
+    action: FavoriteAction,
+    backoff_base: Duration,
+) -> Result<(), String> {
+    let mut attempt = 0;
+    loop {
+        match client
+            .change_favorite(&item.fav_type, &item.item_id, action)
+            .await
+        {
+            Ok(()) => return Ok(()),
+            Err(ApiError::RateLimited(retry_after)) if attempt < FAVORITE_RATE_LIMIT_RETRIES => {
+                let wait = Duration::from_secs(retry_after).max(backoff_base * 2u32.pow(attempt));
+                log::debug!(
+                    "Favorite {} {} rate limited, retrying in {:?}",
+                    item.fav_type,
+                    item.item_id,
+                    wait
+                );
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+/// Apply the successful changes to the favorites cache and invalidate their
+/// API cache entries. Failed items are left as they were.
+async fn record_changed_favorites(
+    results: &[FavoriteItemResult],
+    action: FavoriteAction,
+    favorites_cache: &FavoritesCacheState,
+    cache_state: &ApiCacheState,
+) {
+    for fav_type in ["track", "album", "artist"] {
+        let ids: Vec<String> = results
+            .iter()
+            .filter(|r| r.success && r.fav_type == fav_type)
+            .map(|r| r.item_id.clone())
+            .collect();
+        if ids.is_empty() {
+            continue;
+        }
+        if let Err(e) = apply_to_favorites_cache(favorites_cache, fav_type, &ids, action) {
+            log::warn!("Failed to update favorites cache: {}", e);
+        }
+        invalidate_cached_items(cache_state, fav_type, &ids).await;
+    }
+}
+
+/// Send queued favorite changes to Qobuz now (e.g. when going back online)
+#[tauri::command]
+pub async fn sync_queued_favorites(app: AppHandle) -> Result<FavoriteSyncResult, String> {
+    log::info!("Command: sync_queued_favorites");
+    sync_favorite_queue(&app).await
+}
+
+/// Get count of favorite changes waiting to be synced
+#[tauri::command]
+pub fn get_queued_favorite_count(offline_state: State<'_, OfflineState>) -> Result<u32, String> {
+    let guard__ = offline_state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard__
+        .as_ref()
+        .ok_or("No active session - please log in")?;
+    store.get_queued_favorite_count()
+}
+
+fn queue_favorite_change(
+    app: &AppHandle,
+    fav_type: &str,
+    item_ids: &[String],
+    action: FavoriteAction,
+) -> Result<(), String> {
+    if !matches!(fav_type, "track" | "album" | "artist") {
+        return Err(format!("Unknown favorite type: {}", fav_type));
+    }
+    if fav_type != "album" {
+        if let Some(bad) = item_ids.iter().find(|id| id.parse::<i64>().is_err()) {
+            return Err(format!("Invalid {} id: {}", fav_type, bad));
+        }
+    }
+    if item_ids.is_empty() {
+        return Ok(());
+    }
+
+    apply_to_favorites_cache(
+        &app.state::<FavoritesCacheState>(),
+        fav_type,
+        item_ids,
+        action,
+    )?;
+    with_offline_store(app, |store| {
+        store.queue_favorites(fav_type, item_ids, action)
+    })?;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = sync_favorite_queue(&app).await {
+            log::debug!("Favorite sync skipped: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Sync the favorite queue to Qobuz. Only one sync runs at a time; a request
+/// made while one is running makes it go again once done.
+pub async fn sync_favorite_queue(app: &AppHandle) -> Result<FavoriteSyncResult, String> {
+    if FAVORITE_SYNC_RUNNING.swap(true, Ordering::SeqCst) {
+        FAVORITE_SYNC_REQUESTED.store(true, Ordering::SeqCst);
+        return Ok(FavoriteSyncResult::default());
+    }
+
+    let mut total = FavoriteSyncResult::default();
+    let outcome = loop {
+        FAVORITE_SYNC_REQUESTED.store(false, Ordering::SeqCst);
+        match sync_favorite_queue_once(app).await {
+            Ok(result) => {
+                total.synced += result.synced;
+                total.failed += result.failed;
+                total.reverted += result.reverted;
+            }
+            Err(e) => break Err(e),
+        }
+        if !FAVORITE_SYNC_REQUESTED.load(Ordering::SeqCst) {
+            break Ok(total);
+        }
+    };
+    FAVORITE_SYNC_RUNNING.store(false, Ordering::SeqCst);
+    outcome
+}
+
+async fn sync_favorite_queue_once(app: &AppHandle) -> Result<FavoriteSyncResult, String> {
+    let mut result = FavoriteSyncResult::default();
+
+    let queued = with_offline_store(app, |store| {
+        if store.get_settings()?.manual_offline_mode {
+            return Ok(Vec::new());
+        }
+        store.get_queued_favorites()
+    })?;
+    if queued.is_empty() {
+        return Ok(result);
+    }
+
+    let state = app.state::<AppState>();
+    let client = state.client.read().await;
+    if !client.is_logged_in().await {
+        return Err("Not logged in".to_string());
+    }
+
+    let mut reverted = Vec::new();
+    'groups: for fav_type in ["track", "album", "artist"] {
+        for action in [FavoriteAction::Add, FavoriteAction::Remove] {
+            let group: Vec<&QueuedFavorite> = queued
+                .iter()
+                .filter(|f| f.fav_type == fav_type && f.action == action)
+                .collect();
+
+            for chunk in group.chunks(FAVORITE_BATCH_SIZE) {
+                let ids: Vec<String> = chunk.iter().map(|f| f.item_id.clone()).collect();
+                let batch_error =
+                    match send_favorite_change(&client, fav_type, &ids.join(","), action).await {
+                        Ok(()) => {
+                            result.synced += finish_synced(app, fav_type, &ids, action).await?;
+                            continue;
+                        }
+                        Err(e) => e,
+                    };
+
+                // Offline: leave everything queued without counting an attempt
+                if !crate::offline::check_network_connectivity().await {
+                    log::info!("Favorite sync paused, no network: {}", batch_error);
+                    break 'groups;
                 }
-                "artist" => {
-                    if let Ok(id) = item_id.parse::<u64>() {
-                        let _ = cache.invalidate_artist(id);
+
+                // Retry one by one so a single bad ID doesn't hold back the rest
+                for favorite in chunk {
+                    let ids = std::slice::from_ref(&favorite.item_id);
+                    let error = if chunk.len() == 1 {
+                        batch_error.clone()
+                    } else {
+                        match send_favorite_change(&client, fav_type, &favorite.item_id, action)
+                            .await
+                        {
+                            Ok(()) => {
+                                result.synced += finish_synced(app, fav_type, ids, action).await?;
+                                continue;
+                            }
+                            Err(e) => e,
+                        }
+                    };
+
+                    result.failed += 1;
+                    log::warn!(
+                        "Failed to sync favorite {} {} ({}): {}",
+                        fav_type,
+                        favorite.item_id,
+                        action.as_str(),
+                        error
+                    );
+                    if favorite.attempts + 1 < MAX_FAVORITE_SYNC_ATTEMPTS {
+                        with_offline_store(app, |store| {
+                            store.record_favorite_sync_failure(fav_type, ids, action, &error)
+                        })?;
+                        continue;
+                    }
+
+                    // Give up: undo the local change unless a newer one replaced it
+                    let removed = with_offline_store(app, |store| {
+                        store.remove_queued_favorites(fav_type, ids, action)
+                    })?;
+                    if removed > 0 {
+                        let undo = match action {
+                            FavoriteAction::Add => FavoriteAction::Remove,
+                            FavoriteAction::Remove => FavoriteAction::Add,
+                        };
+                        apply_to_favorites_cache(
+                            &app.state::<FavoritesCacheState>(),
+                            fav_type,
+                            ids,
+                            undo,
+                        )?;
+                        result.reverted += 1;
+                        reverted.push(RevertedFavorite {
+                            fav_type: fav_type.to_string(),
+                            item_id: favorite.item_id.clone(),
+                            action,
+                            error,
+                        });
                     }
                 }
-                _ => {}
             }
         }
     }
 
+    if !reverted.is_empty() {
+        log::warn!(
+            "Reverted {} favorite changes that could not be synced",
+            reverted.len()
+        );
+        let _ = app.emit("favorites:reverted", &reverted);
+    }
+    log::info!(
+        "Favorite sync: {} synced, {} failed, {} reverted",
+        result.synced,
+        result.failed,
+        result.reverted
+    );
+    Ok(result)
+}
+
+async fn send_favorite_change(
+    client: &QobuzClient,
+    fav_type: &str,
+    item_ids: &str,
+    action: FavoriteAction,
+) -> Result<(), String> {
+    let sent = match action {
+        FavoriteAction::Add => client.add_favorite(fav_type, item_ids).await,
+        FavoriteAction::Remove => client.remove_favorite(fav_type, item_ids).await,
+    };
+    sent.map_err(|e| e.to_string())
+}
+
+/// Drop synced items from the queue and invalidate their API cache entries
+async fn finish_synced(
+    app: &AppHandle,
+    fav_type: &str,
+    item_ids: &[String],
+    action: FavoriteAction,
+) -> Result<u32, String> {
+    with_offline_store(app, |store| {
+        store.remove_queued_favorites(fav_type, item_ids, action)
+    })?;
+    invalidate_cached_items(&app.state::<ApiCacheState>(), fav_type, item_ids).await;
+    Ok(item_ids.len() as u32)
+}
+
+fn with_offline_store<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&crate::offline::OfflineStore) -> Result<T, String>,
+) -> Result<T, String> {
+    let offline_state = app.state::<OfflineState>();
+    let guard__ = offline_state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard__
+        .as_ref()
+        .ok_or("No active session - please log in")?;
+    f(store)
+}
+
+/// Re-apply queued changes of `fav_type` to the favorites cache, so a
+/// full sync from the API doesn't undo changes Qobuz hasn't seen yet
+pub fn reapply_queued_favorites(
+    offline_state: &OfflineState,
+    cache_state: &FavoritesCacheState,
+    fav_type: &str,
+) -> Result<(), String> {
+    let queued = {
+        let guard__ = offline_state
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        match guard__.as_ref() {
+            Some(store) => store.get_queued_favorites()?,
+            None => return Ok(()),
+        }
+    };
+    for favorite in queued.iter().filter(|f| f.fav_type == fav_type) {
+        apply_to_favorites_cache(
+            cache_state,
+            fav_type,
+            std::slice::from_ref(&favorite.item_id),
+            favorite.action,
+        )?;
+    }
+    Ok(())
+}
+
+/// Apply a favorite change to the local favorites cache.
+/// Track and artist IDs must already be validated as integers.
+fn apply_to_favorites_cache(
+    cache_state: &FavoritesCacheState,
+    fav_type: &str,
+    item_ids: &[String],
+    action: FavoriteAction,
+) -> Result<(), String> {
+    let guard = cache_state
+        .store
+        .lock()
+        .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    for item_id in item_ids {
+        let numeric_id = || {
+            item_id
+                .parse::<i64>()
+                .map_err(|_| format!("Invalid {} id: {}", fav_type, item_id))
+        };
+        match (fav_type, action) {
+            ("track", FavoriteAction::Add) => store.add_favorite_track(numeric_id()?)?,
+            ("track", FavoriteAction::Remove) => store.remove_favorite_track(numeric_id()?)?,
+            ("album", FavoriteAction::Add) => store.add_favorite_album(item_id)?,
+            ("album", FavoriteAction::Remove) => store.remove_favorite_album(item_id)?,
+            ("artist", FavoriteAction::Add) => store.add_favorite_artist(numeric_id()?)?,
+            ("artist", FavoriteAction::Remove) => store.remove_favorite_artist(numeric_id()?)?,
+            _ => return Err(format!("Unknown favorite type: {}", fav_type)),
+        }
+    }
     Ok(())
 }
+
+/// Invalidate stale API cache entries so the next fetch gets fresh data
+async fn invalidate_cached_items(cache_state: &ApiCacheState, fav_type: &str, item_ids: &[String]) {
+    let guard = cache_state.cache.lock().await;
+    let Some(cache) = guard.as_ref() else {
+        return;
+    };
+    for item_id in item_ids {
+        match fav_type {
+            "album" => {
+                let _ = cache.invalidate_album(item_id);
+            }
+            "track" => {
+                if let Ok(id) = item_id.parse::<u64>() {
+                    let _ = cache.invalidate_track(id);
+                }
+            }
+            "artist" => {
+                if let Ok(id) = item_id.parse::<u64>() {
+                    let _ = cache.invalidate_artist(id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Fails the listed IDs and rate-limits others a number of times first
+    #[derive(Default)]
+    struct FakeFavorites {
+        failing: Vec<&'static str>,
+        rate_limited: Mutex<HashMap<&'static str, u32>>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl FavoriteApi for FakeFavorites {
+        async fn change_favorite(
+            &self,
+            fav_type: &str,
+            item_id: &str,
+            action: FavoriteAction,
+        ) -> Result<(), ApiError> {
+            self.calls.lock().unwrap().push(format!(
+                "{} {} {}",
+                action.as_str(),
+                fav_type,
+                item_id
+            ));
+            if let Some(left) = self.rate_limited.lock().unwrap().get_mut(item_id) {
+                if *left > 0 {
+                    *left -= 1;
+                    return Err(ApiError::RateLimited(0));
+                }
+            }
+            if self.failing.contains(&item_id) {
+                return Err(ApiError::ApiResponse(
+                    "Failed to add favorite: 404".to_string(),
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    fn item(fav_type: &str, item_id: &str) -> FavoriteItem {
+        FavoriteItem {
+            fav_type: fav_type.to_string(),
+            item_id: item_id.to_string(),
+        }
+    }
+
+    fn run<T>(fut: impl std::future::Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(fut)
+    }
+
+    #[test]
+    fn batch_reports_exactly_the_failed_items() {
+        let client = FakeFavorites {
+            failing: vec!["2", "abc"],
+            rate_limited: Mutex::new(HashMap::from([("3", 2), ("5", 10)])),
+            ..Default::default()
+        };
+        let items = vec![
+            item("track", "1"),
+            item("track", "2"),
+            item("album", "3"),
+            item("artist", "x"),
+            item("album", "abc"),
+            item("track", "5"),
+            item("playlist", "6"),
+        ];
+
+        let results = run(change_favorites(
+            &client,
+            items,
+            FavoriteAction::Add,
+            Duration::ZERO,
+        ));
+
+        let outcome: Vec<(&str, bool)> = results
+            .iter()
+            .map(|r| (r.item_id.as_str(), r.success))
+            .collect();
+        assert_eq!(
+            outcome,
+            vec![
+                ("1", true),
+                ("2", false),
+                // Succeeds after two rate-limited attempts
+                ("3", true),
+                ("x", false),
+                ("abc", false),
+                // Still rate limited after the last retry
+                ("5", false),
+                ("6", false),
+            ]
+        );
+        assert!(results.iter().all(|r| r.success == r.error.is_none()));
+        assert_eq!(results[3].error.as_deref(), Some("Invalid artist id: x"));
+
+        // Invalid items never reach the API
+        let calls = client.calls.lock().unwrap();
+        assert!(!calls
+            .iter()
+            .any(|c| c.ends_with(" x") || c.contains("playlist")));
+        let attempts = |id: &str| {
+            calls
+                .iter()
+                .filter(|c| c.ends_with(&format!(" {}", id)))
+                .count()
+        };
+        assert_eq!(attempts("3"), 3);
+        assert_eq!(attempts("5"), FAVORITE_RATE_LIMIT_RETRIES as usize + 1);
+    }
+}
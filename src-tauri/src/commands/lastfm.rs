@@ -2,7 +2,8 @@
 
 use tauri::State;
 
-use crate::lastfm::{LastFmClient, LastFmSession};
+use crate::lastfm::{BatchScrobble, LastFmClient, LastFmSession, ScrobbleOutcome, SCROBBLE_BATCH_SIZE};
+use crate::offline::OfflineState;
 use crate::AppState;
 
 /// Check if Last.fm has embedded (build-time) credentials
@@ -87,6 +88,18 @@ pub async fn lastfm_disconnect(state: State<'_, AppState>) -> Result<(), String>
     Ok(())
 }
 
+/// Enable or disable scrobbling (the backend reports "now playing" itself)
+#[tauri::command]
+pub async fn lastfm_set_scrobbling_enabled(
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    log::info!("Command: lastfm_set_scrobbling_enabled - {}", enabled);
+    let mut client = state.lastfm.lock().await;
+    client.set_scrobbling_enabled(enabled);
+    Ok(())
+}
+
 /// Scrobble a track to Last.fm
 #[tauri::command]
 pub async fn lastfm_scrobble(
@@ -117,3 +130,115 @@ pub async fn lastfm_now_playing(
         .update_now_playing(&artist, &track, album.as_deref())
         .await
 }
+
+/// Result of flushing the offline scrobble queue
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ScrobbleFlushResult {
+    /// Accepted by Last.fm
+    pub sent: u32,
+    /// Rejected for good by Last.fm (e.g. older than 14 days); removed from the queue
+    pub ignored: u32,
+    /// Not recorded; kept queued for the next flush
+    pub failed: u32,
+}
+
+/// Submit the offline scrobble queue to Last.fm in batches of 50.
+///
+/// Only scrobbles Last.fm accepted (or permanently ignored) leave the queue;
+/// request failures and retryable rejections stay queued for the next flush.
+#[tauri::command]
+pub async fn flush_scrobble_queue(
+    state: State<'_, AppState>,
+    offline_state: State<'_, OfflineState>,
+) -> Result<ScrobbleFlushResult, String> {
+    log::info!("Command: flush_scrobble_queue");
+
+    let queued = {
+        let guard__ = offline_state
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard__
+            .as_ref()
+            .ok_or("No active session - please log in")?;
+        store.get_queued_scrobbles(u32::MAX)?
+    };
+
+    let mut result = ScrobbleFlushResult::default();
+    if queued.is_empty() {
+        return Ok(result);
+    }
+
+    let client = state.lastfm.lock().await;
+    let mut batches = queued.chunks(SCROBBLE_BATCH_SIZE);
+
+    for batch in batches.by_ref() {
+        let entries: Vec<BatchScrobble> = batch
+            .iter()
+            .map(|s| BatchScrobble {
+                artist: s.artist.clone(),
+                track: s.track.clone(),
+                album: s.album.clone(),
+                timestamp: s.timestamp.max(0) as u64,
+            })
+            .collect();
+
+        let outcomes = match client.scrobble_batch(&entries).await {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                // Nothing in this batch was recorded; keep it and the rest queued
+                log::warn!("Scrobble queue flush stopped: {}", e);
+                result.failed += batch.len() as u32;
+                break;
+            }
+        };
+
+        let mut done_ids = Vec::new();
+        let mut hit_limit = false;
+        for (scrobble, outcome) in batch.iter().zip(&outcomes) {
+            match outcome {
+                ScrobbleOutcome::Accepted => {
+                    result.sent += 1;
+                    done_ids.push(scrobble.id);
+                }
+                ignored if ignored.is_retryable() => {
+                    hit_limit = true;
+                    result.failed += 1;
+                }
+                ScrobbleOutcome::Ignored { code, message } => {
+                    log::info!(
+                        "Last.fm ignored queued scrobble {} - {} ({}: {})",
+                        scrobble.artist, scrobble.track, code, message
+                    );
+                    result.ignored += 1;
+                    done_ids.push(scrobble.id);
+                }
+            }
+        }
+
+        {
+            let guard__ = offline_state
+                .store
+                .lock()
+                .map_err(|e| format!("Lock error: {}", e))?;
+            let store = guard__
+                .as_ref()
+                .ok_or("No active session - please log in")?;
+            store.mark_scrobbles_sent(&done_ids)?;
+        }
+
+        if hit_limit {
+            log::warn!("Last.fm daily scrobble limit reached, keeping the rest queued");
+            break;
+        }
+    }
+
+    // Batches never attempted stay queued as well
+    result.failed += batches.map(|b| b.len() as u32).sum::<u32>();
+
+    log::info!(
+        "Scrobble queue flush: sent {}, ignored {}, kept {}",
+        result.sent, result.ignored, result.failed
+    );
+    Ok(result)
+}
@@ -5,7 +5,8 @@
 use tauri::State;
 
 use crate::listenbrainz::{
-    AdditionalInfo, ListenBrainzSharedState, ListenBrainzStatus, QueuedListen, UserInfo,
+    AdditionalInfo, ListenBrainzSharedState, ListenBrainzStatus, QueuedListen, SubmitError,
+    UserInfo, IMPORT_BATCH_SIZE,
 };
 
 /// Get ListenBrainz connection status
@@ -224,6 +225,10 @@ pub async fn listenbrainz_mark_sent(
 }
 
 /// Flush queue (submit all pending listens)
+///
+/// Listens go out in import batches of 50. A batch ListenBrainz refuses is
+/// retried one listen at a time so a single bad entry does not hold back the
+/// rest; anything not accepted stays queued for the next flush.
 #[tauri::command]
 pub async fn listenbrainz_flush_queue(
     state: State<'_, ListenBrainzSharedState>,
@@ -233,8 +238,8 @@ pub async fn listenbrainz_flush_queue(
     // Get pending listens
     let pending = {
         let cache_opt__ = state.cache.lock().await;
-    let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
-        cache.get_queued_listens(50)?
+        let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
+        cache.get_queued_listens(u32::MAX)?
     };
 
     if pending.is_empty() {
@@ -242,48 +247,53 @@ pub async fn listenbrainz_flush_queue(
     }
 
     let client = state.client.lock().await;
-    let mut sent_ids = Vec::new();
-
-    for listen in &pending {
-        // Build additional info
-        let mut info = AdditionalInfo::new();
-        info.recording_mbid = listen.recording_mbid.clone();
-        info.release_mbid = listen.release_mbid.clone();
-        info.artist_mbids = listen.artist_mbids.clone();
-        info.isrc = listen.isrc.clone();
-        info.duration_ms = listen.duration_ms;
-
-        match client
-            .submit_listen(
-                &listen.artist_name,
-                &listen.track_name,
-                listen.release_name.as_deref(),
-                listen.listened_at,
-                Some(info),
-            )
-            .await
-        {
-            Ok(()) => {
-                sent_ids.push(listen.id);
+    let mut sent_count = 0u32;
+
+    for batch in pending.chunks(IMPORT_BATCH_SIZE) {
+        let mut sent_ids = Vec::new();
+        let mut failed_ids = Vec::new();
+        let mut stop = false;
+
+        match client.submit_import(batch).await {
+            Ok(()) => sent_ids.extend(batch.iter().map(|l| l.id)),
+            Err(SubmitError::Rejected(e)) => {
+                log::warn!("ListenBrainz refused a batch of {} listens, retrying individually: {}", batch.len(), e);
+                for listen in batch {
+                    match client.submit_import(std::slice::from_ref(listen)).await {
+                        Ok(()) => sent_ids.push(listen.id),
+                        Err(e) => {
+                            log::warn!("Failed to submit queued listen {}: {}", listen.id, e);
+                            failed_ids.push(listen.id);
+                            if matches!(e, SubmitError::Unavailable(_)) {
+                                stop = true;
+                                break;
+                            }
+                        }
+                    }
+                }
             }
-            Err(e) => {
-                log::warn!("Failed to submit queued listen {}: {}", listen.id, e);
-                // Increment attempt count
-                let cache_opt__ = state.cache.lock().await;
-                let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
-                let _ = cache.increment_attempts(listen.id);
+            Err(SubmitError::Unavailable(e)) => {
+                log::warn!("ListenBrainz queue flush stopped: {}", e);
+                failed_ids.extend(batch.iter().map(|l| l.id));
+                stop = true;
             }
         }
-    }
 
-    // Mark successful ones as sent
-    if !sent_ids.is_empty() {
-        let cache_opt__ = state.cache.lock().await;
-    let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
-        cache.mark_listens_sent(&sent_ids)?;
+        {
+            let cache_opt__ = state.cache.lock().await;
+            let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
+            cache.mark_listens_sent(&sent_ids)?;
+            for id in &failed_ids {
+                let _ = cache.increment_attempts(*id);
+            }
+        }
+        sent_count += sent_ids.len() as u32;
+
+        if stop {
+            break;
+        }
     }
 
-    let sent_count = sent_ids.len() as u32;
     log::info!("ListenBrainz queue flush: sent {}/{} listens", sent_count, pending.len());
 
     Ok(sent_count)
@@ -0,0 +1,76 @@
+//! Data migration commands
+//!
+//! Preview what the startup migrations (App ID rename, flat-to-user layout)
+//! would relocate, and confirm or roll back the last one.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::migration::{self, MigrationRecord, PlannedMove};
+use crate::user_data::UserDataPaths;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationPreview {
+    /// Directories the App ID migration would copy
+    pub app_id: Vec<PlannedMove>,
+    /// Files and directories the flat-to-user migration would relocate for
+    /// the given (or last) user; empty when already migrated
+    pub flat_to_user: Vec<PlannedMove>,
+    pub total_bytes: u64,
+    /// Last migration, still waiting to be confirmed or rolled back
+    pub pending: Option<MigrationRecord>,
+}
+
+/// Report what the data migrations would do, without touching any file
+#[tauri::command]
+pub fn preview_migration(
+    user_id: Option<u64>,
+    user_paths: State<'_, UserDataPaths>,
+) -> Result<MigrationPreview, String> {
+    log::info!("Command: preview_migration");
+    let global_data_dir = UserDataPaths::global_data_dir()?;
+    let global_cache_dir = UserDataPaths::global_cache_dir()?;
+
+    let app_id = crate::flatpak::plan_app_id_migration()?;
+    let flat_to_user = user_id
+        .or_else(|| user_paths.current_user_id())
+        .or_else(UserDataPaths::load_last_user_id)
+        .map(|uid| migration::plan_flat_to_user(&global_data_dir, &global_cache_dir, uid))
+        .unwrap_or_default();
+    let total_bytes = app_id
+        .iter()
+        .chain(flat_to_user.iter())
+        .map(|planned| planned.bytes)
+        .sum();
+
+    Ok(MigrationPreview {
+        app_id,
+        flat_to_user,
+        total_bytes,
+        pending: migration::last_migration(&global_data_dir),
+    })
+}
+
+/// Accept the last migration and delete the data it left behind. Returns
+/// the bytes freed.
+#[tauri::command]
+pub fn confirm_migration() -> Result<u64, String> {
+    log::info!("Command: confirm_migration");
+    migration::confirm_migration_at(&UserDataPaths::global_data_dir()?)
+}
+
+/// Restore the layout from before the last migration
+///
+/// Only while logged out, since the migrated databases are open otherwise.
+/// Restart the app afterwards; the migrations run again on the next start
+/// or login.
+#[tauri::command]
+pub fn rollback_last_migration(
+    user_paths: State<'_, UserDataPaths>,
+) -> Result<MigrationRecord, String> {
+    log::info!("Command: rollback_last_migration");
+    if user_paths.current_user_id().is_some() {
+        return Err("Log out before rolling back a migration".to_string());
+    }
+    migration::rollback_migration_at(&UserDataPaths::global_data_dir()?)
+}
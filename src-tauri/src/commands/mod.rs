@@ -3,18 +3,24 @@
 //! Exposes backend functionality to the frontend via IPC
 
 pub mod artist_blacklist;
+pub mod artwork;
 pub mod audio_backends;
 pub mod audio_diagnostics;
 pub mod auth;
 pub mod factory_reset;
 pub mod cache;
 pub mod credits;
+pub mod diagnostics;
+pub mod equalizer;
 pub mod favorites;
 pub mod lastfm;
 pub mod listenbrainz;
+pub mod migration;
 pub mod musician;
 pub mod musicbrainz;
+pub mod navigation;
 pub mod notification;
+pub mod offline_search;
 pub mod playback;
 pub mod playback_context;
 pub mod playlist;
@@ -28,20 +34,27 @@ pub mod smart_playlists;
 pub mod remote_metadata;
 pub mod user_session;
 pub mod visualizer;
+pub mod waveform;
 
 pub use artist_blacklist::*;
+pub use artwork::*;
 pub use audio_backends::*;
 pub use audio_diagnostics::*;
 pub use auth::*;
 pub use cache::*;
 pub use factory_reset::*;
 pub use credits::*;
+pub use diagnostics::*;
+pub use equalizer::*;
 pub use favorites::*;
 pub use lastfm::*;
 pub use listenbrainz::*;
+pub use migration::*;
 pub use musician::*;
 pub use musicbrainz::*;
+pub use navigation::*;
 pub use notification::*;
+pub use offline_search::*;
 pub use playback::*;
 pub use playback_context::*;
 pub use playlist::*;
@@ -55,3 +68,4 @@ pub use smart_playlists::*;
 pub use remote_metadata::*;
 pub use user_session::*;
 pub use visualizer::*;
+pub use waveform::*;
@@ -4,6 +4,8 @@
 
 use tauri::State;
 
+use crate::api::models::Album;
+use crate::musicbrainz::credits::ReleaseCredits;
 use crate::musicbrainz::{
     ArtistRelationships, CacheStats, MatchConfidence, MusicBrainzSharedState, ResolvedArtist,
     ResolvedRelease, ResolvedTrack,
@@ -16,13 +18,25 @@ pub async fn musicbrainz_resolve_track(
     title: String,
     artist: String,
     state: State<'_, MusicBrainzSharedState>,
+) -> Result<ResolvedTrack, String> {
+    resolve_track(&state, isrc.as_deref(), &title, &artist).await
+}
+
+/// Resolve a track by ISRC, then by title and artist
+///
+/// Shared with the backend "now playing" reports.
+pub async fn resolve_track(
+    state: &MusicBrainzSharedState,
+    isrc: Option<&str>,
+    title: &str,
+    artist: &str,
 ) -> Result<ResolvedTrack, String> {
     if !state.client.is_enabled().await {
         return Ok(ResolvedTrack::empty());
     }
 
     // Check cache first (short lock)
-    if let Some(ref isrc_val) = isrc {
+    if let Some(isrc_val) = isrc {
         let cached = {
             let cache_opt__ = state.cache.lock().await;
             let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
@@ -35,7 +49,7 @@ pub async fn musicbrainz_resolve_track(
     }
 
     // Try ISRC lookup
-    if let Some(ref isrc_val) = isrc {
+    if let Some(isrc_val) = isrc {
         match state.client.search_recording_by_isrc(isrc_val).await {
             Ok(response) => {
                 if let Some(recording) = response.recordings.first() {
@@ -56,7 +70,7 @@ pub async fn musicbrainz_resolve_track(
     }
 
     // Fallback to title+artist search
-    match state.client.search_recording(&title, &artist).await {
+    match state.client.search_recording(title, artist).await {
         Ok(response) => {
             if let Some(recording) = response
                 .recordings
@@ -65,7 +79,7 @@ pub async fn musicbrainz_resolve_track(
             {
                 let resolved = recording_to_resolved(recording);
                 // Cache by ISRC if available
-                if let Some(ref isrc_val) = isrc {
+                if let Some(isrc_val) = isrc {
                     let cache_opt__ = state.cache.lock().await;
                     let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
                     let _ = cache.set_recording(isrc_val, &resolved);
@@ -79,7 +93,7 @@ pub async fn musicbrainz_resolve_track(
     }
 
     // Cache negative result
-    if let Some(ref isrc_val) = isrc {
+    if let Some(isrc_val) = isrc {
         let empty = ResolvedTrack::empty();
         let cache_opt__ = state.cache.lock().await;
         let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
@@ -95,52 +109,66 @@ pub async fn musicbrainz_resolve_artist(
     name: String,
     state: State<'_, MusicBrainzSharedState>,
 ) -> Result<ResolvedArtist, String> {
+    resolve_artist(&state, &name).await
+}
+
+/// Resolve an artist name, going through the cache
+///
+/// Shared with the local library's canonical name resolution.
+pub async fn resolve_artist(
+    state: &MusicBrainzSharedState,
+    name: &str,
+) -> Result<ResolvedArtist, String> {
+    Ok(try_resolve_artist(state, name)
+        .await?
+        .unwrap_or_else(ResolvedArtist::empty))
+}
+
+/// Like `resolve_artist`, but `None` when no answer was obtained (integration
+/// disabled or the search failed) rather than a confirmed no-match.
+///
+/// Failed searches are not cached, so a later call asks again.
+pub async fn try_resolve_artist(
+    state: &MusicBrainzSharedState,
+    name: &str,
+) -> Result<Option<ResolvedArtist>, String> {
     if !state.client.is_enabled().await {
-        return Ok(ResolvedArtist::empty());
+        return Ok(None);
     }
 
     // Check cache first
     let cached = {
         let cache_opt__ = state.cache.lock().await;
         let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
-        cache.get_artist(&name).ok().flatten()
+        cache.get_artist(name).ok().flatten()
     };
     if let Some(cached) = cached {
         log::debug!("MusicBrainz cache hit for artist: {}", name);
-        return Ok(cached);
+        return Ok(Some(cached));
     }
 
-    match state.client.search_artist(&name).await {
-        Ok(response) => {
-            if let Some(artist) = response
-                .artists
-                .iter()
-                .find(|a| a.score.unwrap_or(0) >= 90)
-            {
-                let resolved = artist_to_resolved(artist);
-                // Cache the result
-                {
-                    let cache_opt__ = state.cache.lock().await;
-                    let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
-                    let _ = cache.set_artist(&name, &resolved);
-                }
-                return Ok(resolved);
-            }
-        }
+    let response = match state.client.search_artist(name).await {
+        Ok(response) => response,
         Err(e) => {
             log::warn!("MusicBrainz artist search failed: {}", e);
+            return Ok(None);
         }
-    }
+    };
 
-    // Cache negative result
-    let empty = ResolvedArtist::empty();
+    // Cache the match, or the confirmed absence of one
+    let resolved = response
+        .artists
+        .iter()
+        .find(|a| a.score.unwrap_or(0) >= 90)
+        .map(artist_to_resolved)
+        .unwrap_or_else(ResolvedArtist::empty);
     {
         let cache_opt__ = state.cache.lock().await;
         let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
-        let _ = cache.set_artist(&name, &empty);
+        let _ = cache.set_artist(name, &resolved);
     }
 
-    Ok(empty)
+    Ok(Some(resolved))
 }
 
 /// Resolve a release to MusicBrainz by UPC/barcode
@@ -150,13 +178,24 @@ pub async fn musicbrainz_resolve_release(
     title: String,
     artist: String,
     state: State<'_, MusicBrainzSharedState>,
+) -> Result<ResolvedRelease, String> {
+    resolve_release(&state, upc.as_deref(), &title, &artist).await
+}
+
+/// Resolve a release by UPC, falling back to title + artist, going through
+/// the cache
+pub async fn resolve_release(
+    state: &MusicBrainzSharedState,
+    upc: Option<&str>,
+    title: &str,
+    artist: &str,
 ) -> Result<ResolvedRelease, String> {
     if !state.client.is_enabled().await {
         return Ok(ResolvedRelease::empty());
     }
 
     // Check cache first
-    if let Some(ref upc_val) = upc {
+    if let Some(upc_val) = upc {
         let cached = {
             let cache_opt__ = state.cache.lock().await;
             let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
@@ -169,7 +208,7 @@ pub async fn musicbrainz_resolve_release(
     }
 
     // Try barcode lookup
-    if let Some(ref upc_val) = upc {
+    if let Some(upc_val) = upc {
         match state.client.search_release_by_barcode(upc_val).await {
             Ok(response) => {
                 if let Some(release) = response.releases.first() {
@@ -190,7 +229,7 @@ pub async fn musicbrainz_resolve_release(
     }
 
     // Fallback to title+artist search
-    match state.client.search_release(&title, &artist).await {
+    match state.client.search_release(title, artist).await {
         Ok(response) => {
             if let Some(release) = response
                 .releases
@@ -199,7 +238,7 @@ pub async fn musicbrainz_resolve_release(
             {
                 let resolved = release_to_resolved(release);
                 // Cache by UPC if available
-                if let Some(ref upc_val) = upc {
+                if let Some(upc_val) = upc {
                     let cache_opt__ = state.cache.lock().await;
                     let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
                     let _ = cache.set_release(upc_val, &resolved);
@@ -213,7 +252,7 @@ pub async fn musicbrainz_resolve_release(
     }
 
     // Cache negative result
-    if let Some(ref upc_val) = upc {
+    if let Some(upc_val) = upc {
         let empty = ResolvedRelease::empty();
         let cache_opt__ = state.cache.lock().await;
         let cache = cache_opt__.as_ref().ok_or("No active session - please log in")?;
@@ -223,6 +262,38 @@ pub async fn musicbrainz_resolve_release(
     Ok(ResolvedRelease::empty())
 }
 
+/// Find the album on MusicBrainz and fetch its credit relationships
+///
+/// Returns `None` when MusicBrainz is disabled or has no match, so callers
+/// fall back to what Qobuz provides.
+pub async fn fetch_release_credits(
+    mb_state: &MusicBrainzSharedState,
+    album: &Album,
+) -> Option<(String, ReleaseCredits)> {
+    if !mb_state.client.is_enabled().await {
+        return None;
+    }
+
+    let resolved = resolve_release(
+        mb_state,
+        album.upc.as_deref(),
+        &album.title,
+        &album.artist.name,
+    )
+    .await
+    .map_err(|e| log::warn!("MusicBrainz release resolution failed: {}", e))
+    .ok()?;
+    let mbid = resolved.mbid?;
+
+    match mb_state.client.get_release_with_credits(&mbid).await {
+        Ok(release) => Some((mbid, ReleaseCredits::from_release(&release))),
+        Err(e) => {
+            log::warn!("MusicBrainz credits lookup failed for {}: {}", mbid, e);
+            None
+        }
+    }
+}
+
 /// Get artist relationships by MBID (for Stage 3)
 #[tauri::command]
 pub async fn musicbrainz_get_artist_relationships(
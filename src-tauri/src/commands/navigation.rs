@@ -0,0 +1,146 @@
+//! "Go to album" / "Go to artist" resolution for a track
+//!
+//! Queue tracks don't always carry album/artist IDs (depending on how they
+//! were queued), so these commands look them up: Qobuz tracks via the API
+//! cache or `track/get`, local tracks via the library database and Plex
+//! tracks via the Plex cache.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::api::models::Track;
+use crate::api_cache::ApiCacheState;
+use crate::library::commands::LibraryState;
+use crate::AppState;
+
+/// Where "go to album" should navigate
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlbumTarget {
+    Qobuz { album_id: String },
+    Local { album_group_key: String },
+    Plex { album_key: String },
+}
+
+/// Where "go to artist" should navigate
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArtistTarget {
+    Qobuz { artist_id: u64 },
+    Local { name: String },
+    Plex { name: String },
+}
+
+/// Qobuz track from the API cache, falling back to the API (and caching it)
+async fn qobuz_track(
+    track_id: u64,
+    state: &AppState,
+    cache_state: &ApiCacheState,
+) -> Result<Track, String> {
+    {
+        let guard__ = cache_state.cache.lock().await;
+        if let Some(cache) = guard__.as_ref() {
+            if let Ok(Some(json)) = cache.get_track(track_id, None) {
+                if let Ok(track) = serde_json::from_str::<Track>(&json) {
+                    return Ok(track);
+                }
+            }
+        }
+    }
+
+    let track = {
+        let client = state.client.read().await;
+        client
+            .get_track(track_id)
+            .await
+            .map_err(|e| format!("Failed to get track: {}", e))?
+    };
+
+    if let Ok(json) = serde_json::to_string(&track) {
+        let guard__ = cache_state.cache.lock().await;
+        if let Some(cache) = guard__.as_ref() {
+            let _ = cache.set_track(track_id, &json);
+        }
+    }
+    Ok(track)
+}
+
+async fn local_track(
+    track_id: u64,
+    library_state: &LibraryState,
+) -> Result<Option<crate::library::LocalTrack>, String> {
+    let guard__ = library_state.db.lock().await;
+    let db = guard__
+        .as_ref()
+        .ok_or("No active session - please log in")?;
+    db.get_track(track_id as i64).map_err(|e| e.to_string())
+}
+
+/// Resolve the album page for a track. `source` is the queue track's source
+/// ("qobuz", "local" or "plex"; defaults to Qobuz).
+#[tauri::command]
+pub async fn resolve_track_album(
+    track_id: u64,
+    source: Option<String>,
+    state: State<'_, AppState>,
+    cache_state: State<'_, ApiCacheState>,
+    library_state: State<'_, LibraryState>,
+) -> Result<Option<AlbumTarget>, String> {
+    log::debug!("Command: resolve_track_album {} ({:?})", track_id, source);
+
+    match source.as_deref() {
+        Some("local") => Ok(local_track(track_id, &library_state)
+            .await?
+            .map(|track| track.album_group_key)
+            .filter(|key| !key.trim().is_empty())
+            .map(|album_group_key| AlbumTarget::Local { album_group_key })),
+        Some("plex") => Ok(crate::plex::plex_cache_track_album(track_id)?
+            .map(|(_, album_key)| AlbumTarget::Plex { album_key })),
+        _ => {
+            let track = qobuz_track(track_id, &state, &cache_state).await?;
+            Ok(track
+                .album
+                .map(|album| album.id)
+                .filter(|id| !id.is_empty())
+                .map(|album_id| AlbumTarget::Qobuz { album_id }))
+        }
+    }
+}
+
+/// Resolve the artist page for a track. For Qobuz tracks this is the main
+/// performer, falling back to the album artist.
+#[tauri::command]
+pub async fn resolve_track_artist(
+    track_id: u64,
+    source: Option<String>,
+    state: State<'_, AppState>,
+    cache_state: State<'_, ApiCacheState>,
+    library_state: State<'_, LibraryState>,
+) -> Result<Option<ArtistTarget>, String> {
+    log::debug!("Command: resolve_track_artist {} ({:?})", track_id, source);
+
+    match source.as_deref() {
+        Some("local") => Ok(local_track(track_id, &library_state)
+            .await?
+            .map(|track| track.album_artist.unwrap_or(track.artist))
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| ArtistTarget::Local { name })),
+        Some("plex") => Ok(crate::plex::plex_cache_track_album(track_id)?
+            .map(|(name, _)| ArtistTarget::Plex { name })),
+        _ => {
+            let track = qobuz_track(track_id, &state, &cache_state).await?;
+            let artist_id = track
+                .performer
+                .map(|artist| artist.id)
+                .filter(|&id| id != 0)
+                .or_else(|| {
+                    track
+                        .album
+                        .and_then(|album| album.artist)
+                        .map(|artist| artist.id)
+                        .filter(|&id| id != 0)
+                });
+            Ok(artist_id.map(|artist_id| ArtistTarget::Qobuz { artist_id }))
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Offline-aware search
+//!
+//! Searches everything playable without Qobuz (local library, Plex cache and
+//! the offline cache) in one call, adding Qobuz results when online. The
+//! offline module's status decides which sources take part.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::api::Track;
+use crate::artist_blacklist::BlacklistState;
+use crate::config::DownloadSettingsState;
+use crate::library::{LibraryState, LocalTrack};
+use crate::offline::{OfflineState, OfflineStatus};
+use crate::offline_cache::{CachedTrackInfo, OfflineCacheState};
+use crate::plex::PlexCachedTrack;
+use crate::AppState;
+
+/// Results per source when no limit is given
+const DEFAULT_SOURCE_LIMIT: u32 = 50;
+
+/// Where a search result can be played from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSource {
+    Local,
+    Plex,
+    OfflineCache,
+    Qobuz,
+}
+
+/// Search results grouped by source
+#[derive(Debug, Clone, Serialize)]
+pub struct OfflineSearchResults {
+    pub query: String,
+    pub offline: bool,
+    pub local: Vec<LocalTrack>,
+    pub plex: Vec<PlexCachedTrack>,
+    pub offline_cache: Vec<CachedTrackInfo>,
+    pub qobuz: Vec<Track>,
+    /// Sources that were searched successfully
+    pub sources: Vec<SearchSource>,
+}
+
+impl OfflineSearchResults {
+    /// Drop hits that are the same Qobuz track as a higher-priority source:
+    /// local copies win over the offline cache, and both win over streaming
+    fn dedupe(&mut self) {
+        let local_ids: HashSet<u64> = self
+            .local
+            .iter()
+            .filter_map(|track| track.qobuz_track_id)
+            .map(|id| id as u64)
+            .collect();
+        self.offline_cache
+            .retain(|track| !local_ids.contains(&track.track_id));
+
+        let cached_ids: HashSet<u64> = self.offline_cache.iter().map(|t| t.track_id).collect();
+        self.qobuz
+            .retain(|track| !local_ids.contains(&track.id) && !cached_ids.contains(&track.id));
+    }
+}
+
+/// Search local library, Plex cache and offline cache in parallel, plus Qobuz
+/// when online and `include_qobuz` isn't false.
+///
+/// A failing source is logged and left out of `sources` rather than failing
+/// the whole search.
+#[tauri::command]
+pub async fn search_offline(
+    query: String,
+    limit: Option<u32>,
+    include_qobuz: Option<bool>,
+    state: State<'_, AppState>,
+    blacklist_state: State<'_, BlacklistState>,
+    library_state: State<'_, LibraryState>,
+    download_settings_state: State<'_, DownloadSettingsState>,
+    offline_state: State<'_, OfflineState>,
+    offline_cache_state: State<'_, OfflineCacheState>,
+) -> Result<OfflineSearchResults, String> {
+    let query = query.trim().to_string();
+    let limit = limit.unwrap_or(DEFAULT_SOURCE_LIMIT);
+    log::info!("Command: search_offline \"{}\"", query);
+
+    let status = crate::offline::commands::get_offline_status(offline_state.clone()).await?;
+    let mut results = OfflineSearchResults {
+        query: query.clone(),
+        offline: status.is_offline,
+        local: Vec::new(),
+        plex: Vec::new(),
+        offline_cache: Vec::new(),
+        qobuz: Vec::new(),
+        sources: Vec::new(),
+    };
+    if query.is_empty() {
+        return Ok(results);
+    }
+
+    let exclude_network_folders =
+        status.is_offline && !network_folders_allowed(&status, &offline_state);
+    let search_qobuz = !status.is_offline && include_qobuz.unwrap_or(true);
+
+    let local = crate::library::commands::library_search(
+        query.clone(),
+        Some(limit),
+        Some(exclude_network_folders),
+        library_state,
+        download_settings_state,
+    );
+    let plex = {
+        let query = query.clone();
+        async move {
+            tokio::task::spawn_blocking(move || {
+                crate::plex::plex_cache_search_tracks(query, Some(limit))
+            })
+            .await
+            .map_err(|e| format!("Plex search task failed: {}", e))?
+        }
+    };
+    let offline_cache = async {
+        let guard = offline_cache_state.db.lock().await;
+        let db = guard.as_ref().ok_or("No active session - please log in")?;
+        db.search_ready(&query, limit)
+    };
+    let qobuz = async {
+        if !search_qobuz {
+            return None;
+        }
+        let client = state.client.read().await;
+        let result = client
+            .search_tracks(&query, limit, 0, None)
+            .await
+            .map(|page| {
+                page.items
+                    .into_iter()
+                    .filter(|track| !blacklist_state.is_track_blacklisted(track))
+                    .collect::<Vec<_>>()
+            })
+            .map_err(|e| e.to_string());
+        Some(result)
+    };
+
+    let (local, plex, offline_cache, qobuz) = tokio::join!(local, plex, offline_cache, qobuz);
+
+    results.local = take_source(&mut results.sources, SearchSource::Local, local);
+    results.plex = take_source(&mut results.sources, SearchSource::Plex, plex);
+    results.offline_cache = take_source(
+        &mut results.sources,
+        SearchSource::OfflineCache,
+        offline_cache,
+    );
+    if let Some(qobuz) = qobuz {
+        results.qobuz = take_source(&mut results.sources, SearchSource::Qobuz, qobuz);
+    }
+    results.dedupe();
+
+    Ok(results)
+}
+
+/// Network folders are unreachable offline, unless the user opted to keep
+/// showing them in manual offline mode
+fn network_folders_allowed(status: &OfflineStatus, offline_state: &OfflineState) -> bool {
+    if !status.manual_mode_enabled {
+        return false;
+    }
+    let Ok(guard) = offline_state.store.lock() else {
+        return false;
+    };
+    guard
+        .as_ref()
+        .and_then(|store| store.get_settings().ok())
+        .is_some_and(|settings| settings.show_network_folders_in_manual_offline)
+}
+
+fn take_source<T>(
+    sources: &mut Vec<SearchSource>,
+    source: SearchSource,
+    result: Result<Vec<T>, String>,
+) -> Vec<T> {
+    match result {
+        Ok(items) => {
+            sources.push(source);
+            items
+        }
+        Err(e) => {
+            log::warn!("Offline search: {:?} source failed: {}", source, e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(track_id: u64) -> CachedTrackInfo {
+        CachedTrackInfo {
+            track_id,
+            title: "Cached".to_string(),
+            artist: "Artist".to_string(),
+            album: None,
+            album_id: None,
+            duration_secs: 200,
+            file_size_bytes: 0,
+            quality: String::new(),
+            bit_depth: None,
+            sample_rate: None,
+            status: crate::offline_cache::OfflineCacheStatus::Ready,
+            progress_percent: 100,
+            error_message: None,
+            created_at: String::new(),
+            last_accessed_at: String::new(),
+        }
+    }
+
+    fn qobuz(id: u64) -> Track {
+        serde_json::from_value(serde_json::json!({ "id": id, "title": "Streamed" })).unwrap()
+    }
+
+    #[test]
+    fn test_dedupe_prefers_cached_over_streaming() {
+        let mut results = OfflineSearchResults {
+            query: "q".to_string(),
+            offline: false,
+            local: Vec::new(),
+            plex: Vec::new(),
+            offline_cache: vec![cached(1), cached(2)],
+            qobuz: vec![qobuz(1), qobuz(3)],
+            sources: Vec::new(),
+        };
+        results.dedupe();
+        assert_eq!(results.offline_cache.len(), 2);
+        let ids: Vec<u64> = results.qobuz.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![3]);
+    }
+}
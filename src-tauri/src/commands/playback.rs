@@ -1,20 +1,27 @@
 //! Playback-related Tauri commands
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 use tokio::sync::RwLock;
 
+use crate::api::auth::get_timestamp;
 use crate::api::client::QobuzClient;
-use crate::api::models::Quality;
+use crate::api::ApiError;
+use crate::api::models::{Quality, StreamUrl};
 use crate::cache::AudioCache;
-use crate::config::audio_settings::AudioSettingsState;
+use crate::config::audio_settings::{AudioSettingsState, QualityLimitStrategy};
+use crate::data_usage::{self, DataUsageCategory};
 use crate::offline_cache::OfflineCacheState;
-use crate::player::PlaybackState;
+use crate::playback_router::{self, PlaybackRoute, TransportAction};
+use crate::player::{
+    DecodedFormatInfo, PlaybackState, SleepTimer, SleepTimerAction, SleepTimerStatus, TrackSource,
+};
 use crate::queue::QueueManager;
 use crate::AppState;
 
 /// Convert quality string from frontend to Quality enum
-fn parse_quality(quality_str: Option<&str>) -> Quality {
+pub(crate) fn parse_quality(quality_str: Option<&str>) -> Quality {
     match quality_str {
         Some("MP3") => Quality::Mp3,
         Some("CD Quality") => Quality::Lossless,
@@ -26,7 +33,6 @@ fn parse_quality(quality_str: Option<&str>) -> Quality {
 
 /// Limit quality based on device's max sample rate
 /// This ensures bit-perfect playback by not requesting tracks that exceed device capabilities
-#[allow(dead_code)]
 fn limit_quality_for_device(quality: Quality, max_sample_rate: Option<u32>) -> Quality {
     let Some(max_rate) = max_sample_rate else {
         return quality; // No limit if device max rate unknown
@@ -68,12 +74,266 @@ fn limit_quality_for_device(quality: Quality, max_sample_rate: Option<u32>) -> Q
     }
 }
 
+/// How the device limit applied to a track
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DeviceQualityLimit {
+    pub strategy: QualityLimitStrategy,
+    pub device_max_sample_rate: u32,
+    /// Highest tier the device plays natively
+    pub device_quality: Quality,
+    /// Tier requested from Qobuz
+    pub requested: Quality,
+}
+
+/// Apply the device limit to `quality` with `strategy`: `RequestLower` requests
+/// the tier the device plays natively, `FetchHiresAndResample` keeps `quality`.
+/// The limit is returned only when the device can't play `quality` natively.
+fn apply_device_limit(
+    quality: Quality,
+    max_sample_rate: Option<u32>,
+    strategy: QualityLimitStrategy,
+) -> (Quality, Option<DeviceQualityLimit>) {
+    let device_quality = limit_quality_for_device(quality, max_sample_rate);
+    let Some(device_max_sample_rate) = max_sample_rate.filter(|_| device_quality != quality) else {
+        return (quality, None);
+    };
+    let requested = match strategy {
+        QualityLimitStrategy::RequestLower => device_quality,
+        QualityLimitStrategy::FetchHiresAndResample => {
+            log::info!(
+                "[Quality Limit] Device max {}Hz, fetching {} and resampling",
+                device_max_sample_rate,
+                quality.label()
+            );
+            quality
+        }
+    };
+    let limit = DeviceQualityLimit {
+        strategy,
+        device_max_sample_rate,
+        device_quality,
+        requested,
+    };
+    (requested, Some(limit))
+}
+
+/// Device limit from audio settings (when `limit_quality_to_device` is on).
+/// Opt-in since 1.1.9, when it was on by default and caused incorrect downgrades (#45).
+fn limit_quality_for_device_settings(
+    quality: Quality,
+    audio_settings: &AudioSettingsState,
+) -> (Quality, Option<DeviceQualityLimit>) {
+    let settings = audio_settings
+        .store
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().and_then(|s| s.get_settings().ok()));
+    match settings {
+        Some(s) if s.limit_quality_to_device => {
+            apply_device_limit(quality, s.device_max_sample_rate, s.quality_limit_strategy)
+        }
+        _ => (quality, None),
+    }
+}
+
+/// Per-track device limit (with `limit_quality_to_device` and `RequestLower`):
+/// the highest tier up to `quality` the output device plays `track_id` in
+/// without resampling, from the probed format matrix. Tracks of unknown
+/// sample rate and devices that can't be probed keep `quality`.
+async fn limit_quality_for_track(
+    quality: Quality,
+    track_id: u64,
+    queue: &QueueManager,
+    audio_settings: &AudioSettingsState,
+) -> Quality {
+    let output_device = audio_settings
+        .store
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().and_then(|s| s.get_settings().ok()))
+        .filter(|s| {
+            s.limit_quality_to_device
+                && s.quality_limit_strategy == QualityLimitStrategy::RequestLower
+        })
+        .map(|s| s.output_device);
+    let Some(output_device) = output_device else {
+        return quality;
+    };
+    let Some(track_rate) = queue
+        .find_track(track_id)
+        .and_then(|track| track.sample_rate)
+        .map(|rate| crate::quality::sample_rate_hz(rate) as u32)
+    else {
+        return quality;
+    };
+
+    // The first probe enumerates devices
+    let matrix = tokio::task::spawn_blocking(move || {
+        crate::audio::capabilities::device_format_matrix(output_device.as_deref(), false)
+    })
+    .await;
+    let Ok(Ok(matrix)) = matrix else {
+        return quality;
+    };
+
+    let chosen =
+        crate::audio::capabilities::quality_for_track(quality, track_rate, &matrix.formats);
+    if chosen != quality {
+        log::info!(
+            "[Quality Limit] Track {} is {}Hz, not native on {}: requesting {} instead of {}",
+            track_id,
+            track_rate,
+            matrix.device_id,
+            chosen.label(),
+            quality.label()
+        );
+    }
+    chosen
+}
+
+/// Cap quality at CD quality on a metered connection (when enabled in audio settings)
+async fn limit_quality_for_connection(
+    quality: Quality,
+    audio_settings: &AudioSettingsState,
+) -> Quality {
+    let enabled = audio_settings
+        .store
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().and_then(|s| s.get_settings().ok()))
+        .is_some_and(|s| s.limit_quality_on_metered);
+    if !enabled || quality <= Quality::Lossless {
+        return quality;
+    }
+
+    let metered = tokio::task::spawn_blocking(crate::network::get_connection_type)
+        .await
+        .is_ok_and(|connection| connection.is_metered());
+    if metered {
+        log::info!(
+            "[Quality Limit] Metered connection, limiting {} to Lossless (44.1kHz)",
+            quality.label()
+        );
+        return Quality::Lossless;
+    }
+    quality
+}
+
+/// Last average download speed of a full track in MB/s (f64 bits, 0 = not measured)
+static LAST_DOWNLOAD_SPEED: AtomicU64 = AtomicU64::new(0);
+
+fn record_download_speed(speed_mbps: f64) {
+    if speed_mbps.is_finite() && speed_mbps > 0.0 {
+        LAST_DOWNLOAD_SPEED.store(speed_mbps.to_bits(), Ordering::Relaxed);
+    }
+}
+
+fn last_download_speed() -> Option<f64> {
+    match LAST_DOWNLOAD_SPEED.load(Ordering::Relaxed) {
+        0 => None,
+        bits => Some(f64::from_bits(bits)),
+    }
+}
+
+/// Download speed (MB/s) at which a quality downloads several times faster
+/// than real time, so prefetching it cannot endanger gapless continuity
+fn ample_speed_mbps(quality: Quality) -> f64 {
+    match quality {
+        Quality::Mp3 => 0.2,
+        Quality::Lossless => 0.8,
+        Quality::HiRes => 2.0,
+        Quality::UltraHiRes => 5.0,
+    }
+}
+
+/// Quality to prefetch upcoming tracks at: the configured prefetch tier when it
+/// is below the playback quality, unless the last downloads were fast enough to
+/// prefetch at full quality anyway
+fn prefetch_quality_for(
+    playback_quality: Quality,
+    prefetch_quality: Option<Quality>,
+    speed_mbps: Option<f64>,
+) -> Quality {
+    let Some(prefetch_quality) = prefetch_quality.filter(|q| *q < playback_quality) else {
+        return playback_quality;
+    };
+    if speed_mbps.is_some_and(|speed| speed >= ample_speed_mbps(playback_quality)) {
+        return playback_quality;
+    }
+    prefetch_quality
+}
+
+/// Prefetch settings: (prefetch quality, re-fetch reduced tracks at full quality)
+fn prefetch_settings(audio_settings: &AudioSettingsState) -> (Option<Quality>, bool) {
+    audio_settings
+        .store
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().and_then(|s| s.get_settings().ok()))
+        .map(|s| {
+            (
+                s.prefetch_quality.as_deref().map(|q| parse_quality(Some(q))),
+                s.upgrade_prefetched_tracks,
+            )
+        })
+        .unwrap_or((None, true))
+}
+
+/// Configured quality fallback order, highest priority first
+fn quality_fallback_order(audio_settings: &AudioSettingsState) -> Vec<Quality> {
+    audio_settings
+        .store
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().and_then(|s| s.get_settings().ok()))
+        .map(|s| {
+            s.quality_fallback_order
+                .iter()
+                .map(|tier| parse_quality(Some(tier)))
+                .collect()
+        })
+        .unwrap_or_else(|| Quality::fallback_order().to_vec())
+}
+
+/// Tiers to request for `preferred`: the fallback order without the tiers
+/// above `preferred` (just `preferred` if that leaves nothing)
+fn quality_chain(preferred: Quality, order: &[Quality]) -> Vec<Quality> {
+    let chain: Vec<Quality> = order.iter().copied().filter(|q| *q <= preferred).collect();
+    if chain.is_empty() {
+        vec![preferred]
+    } else {
+        chain
+    }
+}
+
+/// The stream is in a lower tier than requested. Playback goes on; the
+/// frontend tells the user.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct QualityDowngrade {
+    pub requested: Quality,
+    pub achieved: Quality,
+}
+
+fn quality_downgrade(requested: Quality, achieved: Option<Quality>) -> Option<QualityDowngrade> {
+    let achieved = achieved?;
+    (achieved < requested).then_some(QualityDowngrade { requested, achieved })
+}
+
 /// Result from play_track command with format info
 #[derive(serde::Serialize)]
 pub struct PlayTrackResult {
     /// The actual format_id returned by Qobuz (5=MP3, 6=FLAC 16-bit, 7=24-bit, 27=Hi-Res)
-    /// None when playing from cache (format unknown)
+    /// None when the format is unknown (offline or disk cache)
     pub format_id: Option<u32>,
+    /// Tier of the playing data (from `format_id`)
+    pub quality: Option<Quality>,
+    /// Set when the stream was resolved below the requested tier
+    pub downgrade: Option<QualityDowngrade>,
+    /// Set when the device can't play the preferred tier natively and
+    /// `limit_quality_to_device` is on: which strategy was used
+    pub device_limit: Option<DeviceQualityLimit>,
+    /// True when the stream failed and the offline copy is playing instead
+    pub playing_from_cache: bool,
 }
 
 /// Play a track by ID (with caching support)
@@ -88,14 +348,25 @@ pub async fn play_track(
 ) -> Result<PlayTrackResult, String> {
     let preferred_quality = parse_quality(quality.as_deref());
 
-    // NOTE: limit_quality_to_device disabled in 1.1.9 — was causing incorrect downgrades (#45)
-    // The feature code is preserved but disconnected until the detection logic is reliable.
+    let (preferred_quality, device_limit) =
+        limit_quality_for_device_settings(preferred_quality, &audio_settings);
+    let preferred_quality = limit_quality_for_connection(preferred_quality, &audio_settings).await;
+    let preferred_quality =
+        limit_quality_for_track(preferred_quality, track_id, &state.queue, &audio_settings).await;
 
     log::info!(
         "Command: play_track {} (duration: {:?}s, quality_str={:?}, parsed={:?}, format_id={})",
         track_id, duration_secs, quality, preferred_quality, preferred_quality.id()
     );
 
+    let (prefetch_quality, upgrade_prefetched) = prefetch_settings(&audio_settings);
+
+    // Silence trimming keeps the lead-in of a track continuing the album
+    state
+        .player
+        .state
+        .set_keep_lead_in(state.queue.current_continues_album());
+
     // First check offline cache (persistent disk cache)
     {
         let cached_path = {
@@ -122,6 +393,8 @@ pub async fn play_track(
 
                 state.player.play_data(audio_data, track_id)?;
 
+                state.player.state.set_track_source(TrackSource::Qobuz);
+
                 // Check if prefetch should be skipped (streaming_only mode)
                 let skip_prefetch = {
                     let guard = audio_settings.store.lock().map_err(|e| format!("Lock error: {}", e))?;
@@ -134,10 +407,11 @@ pub async fn play_track(
                     state.audio_cache.clone(),
                     &state.queue,
                     preferred_quality,
+                    prefetch_quality,
                     skip_prefetch,
                 );
 
-                return Ok(PlayTrackResult { format_id: None });
+                return Ok(PlayTrackResult { format_id: None, quality: None, downgrade: None, device_limit: None, playing_from_cache: false });
             }
         }
     }
@@ -147,7 +421,26 @@ pub async fn play_track(
     // Check if track is in memory cache (L1)
     if let Some(cached) = cache.get(track_id) {
         log::info!("[CACHE HIT] Track {} from MEMORY cache ({} bytes) - instant playback", track_id, cached.size_bytes);
+        let (format_id, reduced_quality) = (cached.format_id, cached.reduced_quality);
         state.player.play_data(cached.data, track_id)?;
+        state.player.state.set_track_source(TrackSource::Qobuz);
+        state.player.state.set_track_format(format_id, reduced_quality);
+
+        if reduced_quality {
+            log::info!(
+                "Track {} was prefetched at reduced quality (format_id={:?})",
+                track_id, format_id
+            );
+            if upgrade_prefetched {
+                spawn_quality_upgrade(
+                    state.client.clone(),
+                    state.audio_cache.clone(),
+                    track_id,
+                    preferred_quality,
+                    format_id,
+                );
+            }
+        }
 
         // Check if prefetch should be skipped (streaming_only mode)
         let skip_prefetch = {
@@ -161,10 +454,17 @@ pub async fn play_track(
             state.audio_cache.clone(),
             &state.queue,
             preferred_quality,
+            prefetch_quality,
             skip_prefetch,
         );
 
-        return Ok(PlayTrackResult { format_id: None });
+        return Ok(PlayTrackResult {
+            format_id,
+            quality: format_id.and_then(Quality::from_id),
+            downgrade: None,
+            device_limit,
+            playing_from_cache: false,
+        });
     }
 
     // Check if track is in playback cache (L2 - disk)
@@ -177,6 +477,8 @@ pub async fn play_track(
 
             state.player.play_data(audio_data, track_id)?;
 
+            state.player.state.set_track_source(TrackSource::Qobuz);
+
             // Check if prefetch should be skipped (streaming_only mode)
             let skip_prefetch = {
                 let guard = audio_settings.store.lock().map_err(|e| format!("Lock error: {}", e))?;
@@ -189,10 +491,11 @@ pub async fn play_track(
                 state.audio_cache.clone(),
                 &state.queue,
                 preferred_quality,
+                prefetch_quality,
                 skip_prefetch,
             );
 
-            return Ok(PlayTrackResult { format_id: None });
+            return Ok(PlayTrackResult { format_id: None, quality: None, downgrade: None, device_limit: None, playing_from_cache: false });
         }
     }
 
@@ -216,67 +519,139 @@ pub async fn play_track(
         stream_first_enabled, streaming_only, buffer_seconds
     );
 
-    let client = state.client.read().await;
+    // Not cached and known to be unplayable: fail fast so the frontend skips it
+    if state.queue.is_current_unstreamable(track_id) {
+        return Err(ApiError::TrackUnavailable(track_id).to_string());
+    }
 
-    // Get the stream URL with preferred quality
-    let stream_url = client
-        .get_stream_url_with_fallback(track_id, preferred_quality)
-        .await
-        .map_err(|e| format!("Failed to get stream URL: {}", e))?;
+    let chain = quality_chain(preferred_quality, &quality_fallback_order(&audio_settings));
 
-    log::info!("Got stream URL for track {}", track_id);
+    let network_result: Result<PlayTrackResult, String> = async {
+        let client = state.client.read().await;
 
-    if stream_first_enabled {
-        // Use streaming playback - start playing before full download
-        log::info!("[STREAMING] Track {} - streaming from network (cache_after: {})", track_id, !streaming_only);
+        // Get the stream URL, falling back through the configured tiers
+        let stream_url = match client.get_stream_url_with_chain(track_id, &chain).await {
+            Ok(stream_url) => stream_url,
+            Err(e) => {
+                if matches!(e, ApiError::TrackUnavailable(_)) {
+                    // Skip it on later passes through the queue (repeat all, going back)
+                    state.queue.mark_unstreamable(track_id);
+                }
+                return Err(format!("Failed to get stream URL: {}", e));
+            }
+        };
 
-        // Get content length, audio info, and measured speed via HEAD request
-        let stream_info = get_stream_info(&stream_url.url).await?;
+        log::info!("Got stream URL for track {}", track_id);
 
-        log::info!(
-            "Stream info: {:.2} MB, {}Hz, {} channels, {}-bit, {:.1} MB/s",
-            stream_info.content_length as f64 / (1024.0 * 1024.0),
-            stream_info.sample_rate,
-            stream_info.channels,
-            stream_info.bit_depth,
-            stream_info.speed_mbps
-        );
+        let achieved_quality = stream_url.achieved_quality();
+        let downgrade = quality_downgrade(chain[0], achieved_quality);
+        if let Some(downgrade) = &downgrade {
+            log::warn!(
+                "Track {} not available in {}, playing {}",
+                track_id,
+                downgrade.requested.label(),
+                downgrade.achieved.label()
+            );
+        }
 
-        // Start streaming playback with dynamic buffer based on measured speed
-        // The player will use from_speed_mbps internally
-        let buffer_writer = state.player.play_streaming_dynamic(
-            track_id,
-            stream_info.sample_rate,
-            stream_info.channels,
-            stream_info.bit_depth,
-            stream_info.content_length,
-            stream_info.speed_mbps,
-            duration_secs.unwrap_or(0), // Use 0 if not provided
-        )?;
-
-        // Release client lock before spawning background download
-        drop(client);
+        if stream_first_enabled {
+            // Use streaming playback - start playing before full download
+            log::info!("[STREAMING] Track {} - streaming from network (cache_after: {})", track_id, !streaming_only);
 
-        // Spawn background task to download and push data to buffer
-        let url = stream_url.url.clone();
-        let cache_clone = cache.clone();
-        let content_len = stream_info.content_length;
-        let skip_cache = streaming_only;
-        tokio::spawn(async move {
-            match download_and_stream(&url, buffer_writer, track_id, cache_clone, content_len, skip_cache).await {
-                Ok(()) => {
-                    if skip_cache {
-                        log::info!("[STREAMING COMPLETE] Track {} - NOT cached (streaming_only mode)", track_id);
-                    } else {
-                        log::info!("[STREAMING COMPLETE] Track {} - cached for instant replay", track_id);
-                    }
-                },
-                Err(e) => log::error!("[STREAMING ERROR] Track {}: {}", track_id, e),
-            }
-        });
+            // Get content length, audio info, and measured speed via HEAD request
+            let stream_info = get_stream_info(&stream_url.url).await?;
+
+            log::info!(
+                "Stream info: {:.2} MB, {}Hz, {} channels, {}-bit, {:.1} MB/s",
+                stream_info.content_length as f64 / (1024.0 * 1024.0),
+                stream_info.sample_rate,
+                stream_info.channels,
+                stream_info.bit_depth,
+                stream_info.speed_mbps
+            );
+
+            // Start streaming playback with dynamic buffer based on measured speed
+            // The player will use from_speed_mbps internally
+            let buffer_writer = state.player.play_streaming_dynamic(
+                track_id,
+                stream_info.sample_rate,
+                stream_info.channels,
+                stream_info.bit_depth,
+                stream_info.content_length,
+                stream_info.speed_mbps,
+                duration_secs.unwrap_or(0), // Use 0 if not provided
+            )?;
+            state.player.state.set_track_format(Some(stream_url.format_id), false);
+            state.player.state.set_track_source(TrackSource::Qobuz);
+
+            // Release client lock before spawning background download
+            drop(client);
+
+            // Spawn background task to download and push data to buffer
+            let url = stream_url.url.clone();
+            let cache_clone = cache.clone();
+            let content_len = stream_info.content_length;
+            let skip_cache = streaming_only;
+            let format_id = stream_url.format_id;
+            tokio::spawn(async move {
+                match download_and_stream(&url, buffer_writer, track_id, format_id, cache_clone, content_len, skip_cache).await {
+                    Ok(()) => {
+                        if skip_cache {
+                            log::info!("[STREAMING COMPLETE] Track {} - NOT cached (streaming_only mode)", track_id);
+                        } else {
+                            log::info!("[STREAMING COMPLETE] Track {} - cached for instant replay", track_id);
+                        }
+                    },
+                    Err(e) => log::error!("[STREAMING ERROR] Track {}: {}", track_id, e),
+                }
+            });
+
+            // Capture format_id before returning
+            let actual_format_id = stream_url.format_id;
 
-        // Capture format_id before returning
-        let actual_format_id = stream_url.format_id;
+            // Prefetch next track in background
+            spawn_prefetch(
+                state.client.clone(),
+                state.audio_cache.clone(),
+                &state.queue,
+                preferred_quality,
+                prefetch_quality,
+                streaming_only,
+            );
+
+            return Ok(PlayTrackResult {
+                format_id: Some(actual_format_id),
+                quality: achieved_quality,
+                downgrade,
+                device_limit,
+                playing_from_cache: false,
+            });
+        }
+
+        // Standard download path (streaming disabled)
+        log::info!("[DOWNLOAD] Track {} - full download before playback (cache_after: {})", track_id, !streaming_only);
+
+        // Download the audio
+        let audio_data = download_audio(&stream_url.url).await?;
+        let data_size = audio_data.len();
+
+        // Cache it (unless streaming_only mode)
+        if !streaming_only {
+            cache.insert_with_format(track_id, audio_data.clone(), Some(stream_url.format_id), false);
+            log::info!("[CACHED] Track {} stored in memory cache", track_id);
+        } else {
+            log::info!("[NOT CACHED] Track {} - streaming_only mode active", track_id);
+        }
+
+        // Play it
+        state.player.play_data(audio_data, track_id)?;
+        state.player.state.set_track_source(TrackSource::Qobuz);
+        state.player.state.set_track_format(Some(stream_url.format_id), false);
+
+        log::info!("Playing track {} ({} bytes)", track_id, data_size);
+
+        // Release client lock before prefetching
+        drop(client);
 
         // Prefetch next track in background
         spawn_prefetch(
@@ -284,45 +659,54 @@ pub async fn play_track(
             state.audio_cache.clone(),
             &state.queue,
             preferred_quality,
+            prefetch_quality,
             streaming_only,
         );
 
-        return Ok(PlayTrackResult { format_id: Some(actual_format_id) });
+        Ok(PlayTrackResult {
+            format_id: Some(stream_url.format_id),
+            quality: achieved_quality,
+            downgrade,
+            device_limit,
+            playing_from_cache: false,
+        })
     }
+    .await;
 
-    // Standard download path (streaming disabled)
-    log::info!("[DOWNLOAD] Track {} - full download before playback (cache_after: {})", track_id, !streaming_only);
-
-    // Download the audio
-    let audio_data = download_audio(&stream_url.url).await?;
-    let data_size = audio_data.len();
-
-    // Cache it (unless streaming_only mode)
-    if !streaming_only {
-        cache.insert(track_id, audio_data.clone());
-        log::info!("[CACHED] Track {} stored in memory cache", track_id);
-    } else {
-        log::info!("[NOT CACHED] Track {} - streaming_only mode active", track_id);
+    match network_result {
+        Ok(result) => Ok(result),
+        Err(e) => {
+            // The stream failed: fall back to the offline copy when there is one
+            let Some(path) = offline_cached_path(&offline_cache, track_id).await else {
+                return Err(e);
+            };
+            log::warn!(
+                "[OFFLINE FALLBACK] Track {} stream failed ({}), playing cached copy: {:?}",
+                track_id, e, path
+            );
+            let audio_data = std::fs::read(&path)
+                .map_err(|read_err| format!("{} (cached copy unreadable: {})", e, read_err))?;
+            state.player.play_data(audio_data, track_id)?;
+            state.player.state.set_track_source(TrackSource::Qobuz);
+            state.player.state.set_playing_from_cache(true);
+            Ok(PlayTrackResult { format_id: None, quality: None, downgrade: None, device_limit: None, playing_from_cache: true })
+        }
     }
+}
 
-    // Play it
-    state.player.play_data(audio_data, track_id)?;
-
-    log::info!("Playing track {} ({} bytes)", track_id, data_size);
-
-    // Release client lock before prefetching
-    drop(client);
-
-    // Prefetch next track in background
-    spawn_prefetch(
-        state.client.clone(),
-        state.audio_cache.clone(),
-        &state.queue,
-        preferred_quality,
-        streaming_only,
-    );
-
-    Ok(PlayTrackResult { format_id: Some(stream_url.format_id) })
+/// Path of the ready offline copy of a track, if it exists on disk
+pub(crate) async fn offline_cached_path(
+    offline_cache: &OfflineCacheState,
+    track_id: u64,
+) -> Option<std::path::PathBuf> {
+    let db_opt__ = offline_cache.db.lock().await;
+    let db = db_opt__.as_ref()?;
+    if !db.is_cached(track_id).unwrap_or(false) {
+        return None;
+    }
+    let path = std::path::PathBuf::from(db.get_file_path(track_id).ok()??);
+    let _ = db.touch(track_id);
+    path.exists().then_some(path)
 }
 
 /// Prefetch a track into the in-memory cache without starting playback
@@ -332,16 +716,22 @@ pub async fn prefetch_track(
     quality: Option<String>,
     state: State<'_, AppState>,
     offline_cache: State<'_, OfflineCacheState>,
-    _audio_settings: State<'_, AudioSettingsState>,
+    audio_settings: State<'_, AudioSettingsState>,
 ) -> Result<(), String> {
     let preferred_quality = parse_quality(quality.as_deref());
 
-    // NOTE: limit_quality_to_device disabled in 1.1.9 — was causing incorrect downgrades (#45)
-    // The feature code is preserved but disconnected until the detection logic is reliable.
+    let (preferred_quality, _) =
+        limit_quality_for_device_settings(preferred_quality, &audio_settings);
+    let preferred_quality = limit_quality_for_connection(preferred_quality, &audio_settings).await;
+    let preferred_quality =
+        limit_quality_for_track(preferred_quality, track_id, &state.queue, &audio_settings).await;
+
+    let (prefetch_quality, _) = prefetch_settings(&audio_settings);
+    let fetch_quality = prefetch_quality_for(preferred_quality, prefetch_quality, last_download_speed());
 
     log::info!(
-        "Command: prefetch_track {} (quality_str={:?}, parsed={:?}, format_id={})",
-        track_id, quality, preferred_quality, preferred_quality.id()
+        "Command: prefetch_track {} (quality_str={:?}, parsed={:?}, format_id={}, prefetch format_id={})",
+        track_id, quality, preferred_quality, preferred_quality.id(), fetch_quality.id()
     );
 
     let cache = state.audio_cache.clone();
@@ -382,13 +772,18 @@ pub async fn prefetch_track(
 
         let client = state.client.read().await;
         let stream_url = client
-            .get_stream_url_with_fallback(track_id, preferred_quality)
+            .get_stream_url_with_fallback(track_id, fetch_quality)
             .await
             .map_err(|e| format!("Failed to get stream URL: {}", e))?;
         drop(client);
 
         let audio_data = download_audio(&stream_url.url).await?;
-        cache.insert(track_id, audio_data);
+        cache.insert_with_format(
+            track_id,
+            audio_data,
+            Some(stream_url.format_id),
+            fetch_quality < preferred_quality,
+        );
         Ok(())
     }
     .await;
@@ -401,17 +796,12 @@ pub async fn prefetch_track(
 async fn download_audio(url: &str) -> Result<Vec<u8>, String> {
     use std::time::Duration;
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(120))
-        .connect_timeout(Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
     log::info!("Caching audio...");
+    let start_time = std::time::Instant::now();
 
-    let response = client
+    let response = crate::network::http_client::shared()
         .get(url)
-        .header("User-Agent", "Mozilla/5.0")
+        .timeout(Duration::from_secs(120))
         .send()
         .await
         .map_err(|e| format!("Failed to fetch audio: {}", e))?;
@@ -424,6 +814,12 @@ async fn download_audio(url: &str) -> Result<Vec<u8>, String> {
         .bytes()
         .await
         .map_err(|e| format!("Failed to read audio bytes: {}", e))?;
+    data_usage::record(DataUsageCategory::Cache, bytes.len() as u64);
+
+    let elapsed = start_time.elapsed().as_secs_f64();
+    if elapsed > 0.0 {
+        record_download_speed(bytes.len() as f64 / elapsed / (1024.0 * 1024.0));
+    }
 
     log::info!("Cached {} bytes", bytes.len());
     Ok(bytes.to_vec())
@@ -442,16 +838,8 @@ pub struct StreamInfo {
 async fn get_stream_info(url: &str) -> Result<StreamInfo, String> {
     use std::time::{Duration, Instant};
 
-    lazy_static::lazy_static! {
-        // Reuse a static client to avoid intermittent builder errors from creating too many clients
-        static ref HTTP_CLIENT: reqwest::Client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .connect_timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
-    }
-
-    let client = &*HTTP_CLIENT;
+    // Shared client: avoids intermittent builder errors from creating too many clients
+    let client = crate::network::http_client::shared();
 
     // Retry HEAD request up to 3 times with small delay to handle transient failures
     let mut head_response = None;
@@ -459,7 +847,7 @@ async fn get_stream_info(url: &str) -> Result<StreamInfo, String> {
     for attempt in 0..3 {
         match client
             .head(url)
-            .header("User-Agent", "Mozilla/5.0")
+            .timeout(Duration::from_secs(30))
             .send()
             .await
         {
@@ -496,7 +884,7 @@ async fn get_stream_info(url: &str) -> Result<StreamInfo, String> {
     
     let range_response = client
         .get(url)
-        .header("User-Agent", "Mozilla/5.0")
+        .timeout(Duration::from_secs(30))
         .header("Range", "bytes=0-65535")
         .send()
         .await
@@ -510,6 +898,7 @@ async fn get_stream_info(url: &str) -> Result<StreamInfo, String> {
         .bytes()
         .await
         .map_err(|e| format!("Failed to read initial bytes: {}", e))?;
+    data_usage::record(DataUsageCategory::Streaming, initial_bytes.len() as u64);
 
     let elapsed = start_time.elapsed();
     let bytes_downloaded = initial_bytes.len() as f64;
@@ -631,57 +1020,132 @@ fn extract_audio_format_from_header(data: &[u8]) -> Result<(u32, u16, u32), Stri
     }
 }
 
+/// No data for this long restarts the download from the last byte received
+const STREAM_RESUME_AFTER: std::time::Duration = std::time::Duration::from_secs(15);
+/// Download restarts per track before giving up
+const MAX_STREAM_RESUMES: u32 = 3;
+
+/// Feed `on_chunk` the body opened by `open(offset)` until `content_length`
+/// bytes arrived. A connection that errors, ends early or sends nothing for
+/// `resume_after` is reopened at the next missing byte, up to
+/// `MAX_STREAM_RESUMES` times. Returns the bytes received.
+async fn pump_stream<B, S, F, Fut>(
+    mut open: F,
+    content_length: u64,
+    resume_after: std::time::Duration,
+    mut on_chunk: impl FnMut(&[u8]),
+) -> Result<u64, String>
+where
+    B: AsRef<[u8]>,
+    S: futures_util::Stream<Item = Result<B, String>> + Unpin,
+    F: FnMut(u64) -> Fut,
+    Fut: std::future::Future<Output = Result<S, String>>,
+{
+    use futures_util::StreamExt;
+
+    let mut stream = open(0).await?;
+    let mut bytes_received = 0u64;
+    let mut resumes = 0;
+
+    loop {
+        let failure = match tokio::time::timeout(resume_after, stream.next()).await {
+            Ok(Some(Ok(chunk))) => {
+                bytes_received += chunk.as_ref().len() as u64;
+                on_chunk(chunk.as_ref());
+                continue;
+            }
+            Ok(None) if content_length == 0 || bytes_received >= content_length => {
+                return Ok(bytes_received);
+            }
+            Ok(None) => "connection closed early".to_string(),
+            Ok(Some(Err(e))) => e,
+            Err(_) => format!("no data for {}s", resume_after.as_secs()),
+        };
+
+        if resumes == MAX_STREAM_RESUMES {
+            return Err(format!(
+                "Stream stalled after {} attempts: {}",
+                resumes + 1,
+                failure
+            ));
+        }
+        resumes += 1;
+        log::warn!(
+            "Stream interrupted ({}), resuming from byte {} (attempt {}/{})",
+            failure,
+            bytes_received,
+            resumes,
+            MAX_STREAM_RESUMES
+        );
+        stream = open(bytes_received).await?;
+    }
+}
+
+/// Request the stream body from byte `offset` on
+async fn open_stream(
+    url: &str,
+    offset: u64,
+) -> Result<impl futures_util::Stream<Item = Result<impl AsRef<[u8]>, String>> + Unpin, String> {
+    use futures_util::StreamExt;
+
+    let mut request = crate::network::http_client::shared()
+        .get(url)
+        .timeout(std::time::Duration::from_secs(300)); // Longer timeout for streaming
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to start stream: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Stream request failed: {}", response.status()));
+    }
+    // A full response to a range request would restart the data from byte 0
+    if offset > 0 && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err("Stream server can't resume a download".to_string());
+    }
+
+    Ok(response
+        .bytes_stream()
+        .map(|chunk| chunk.map_err(|e| format!("Stream chunk error: {}", e)))
+        .boxed())
+}
+
 /// Download audio chunks and stream them to the buffer writer
 /// Also caches the complete data when download finishes (unless skip_cache is true)
 async fn download_and_stream(
     url: &str,
     writer: crate::player::BufferWriter,
     track_id: u64,
+    format_id: u32,
     cache: Arc<AudioCache>,
     content_length: u64,
     skip_cache: bool,
 ) -> Result<(), String> {
     use std::time::{Duration, Instant};
-    use futures_util::StreamExt;
-
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(300)) // Longer timeout for streaming
-        .connect_timeout(Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
     log::info!("Starting streaming cache for track {} ({:.2} MB total)", 
         track_id, 
         content_length as f64 / (1024.0 * 1024.0)
     );
 
-    let response = client
-        .get(url)
-        .header("User-Agent", "Mozilla/5.0")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to start stream: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Stream request failed: {}", response.status()));
-    }
-
     let mut all_data = Vec::with_capacity(content_length as usize);
-    let mut stream = response.bytes_stream();
     let mut bytes_received = 0u64;
     let start_time = Instant::now();
     let mut last_log_time = Instant::now();
     let mut last_log_bytes = 0u64;
 
-    while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| format!("Stream chunk error: {}", e))?;
+    let on_chunk = |chunk: &[u8]| {
         bytes_received += chunk.len() as u64;
+        data_usage::record(DataUsageCategory::Streaming, chunk.len() as u64);
 
         // Accumulate for caching
-        all_data.extend_from_slice(&chunk);
+        all_data.extend_from_slice(chunk);
 
         // Push to streaming buffer
-        if let Err(e) = writer.push_chunk(&chunk) {
+        if let Err(e) = writer.push_chunk(chunk) {
             log::error!("Failed to push chunk to buffer: {}", e);
         }
 
@@ -719,6 +1183,12 @@ async fn download_and_stream(
             last_log_time = now;
             last_log_bytes = bytes_received;
         }
+    };
+    let open = |offset| open_stream(url, offset);
+    if let Err(e) = pump_stream(open, content_length, STREAM_RESUME_AFTER, on_chunk).await {
+        // Fail the reader too, instead of leaving it waiting for data
+        let _ = writer.error(e.clone());
+        return Err(e);
     }
 
     // Mark stream as complete
@@ -728,6 +1198,7 @@ async fn download_and_stream(
 
     let total_time = start_time.elapsed();
     let avg_speed = (bytes_received as f64 / total_time.as_secs_f64()) / (1024.0 * 1024.0);
+    record_download_speed(avg_speed);
     
     log::info!(
         "Streaming cache complete: {:.2} MB in {:.1}s ({:.2} MB/s avg)",
@@ -741,7 +1212,7 @@ async fn download_and_stream(
         log::info!("Streaming-only mode: skipping cache for track {}", track_id);
     } else {
         log::info!("Caching track {} for future playback", track_id);
-        cache.insert(track_id, all_data);
+        cache.insert_with_format(track_id, all_data, Some(format_id), false);
     }
 
     Ok(())
@@ -767,11 +1238,14 @@ lazy_static::lazy_static! {
 
 /// Spawn background tasks to prefetch upcoming Qobuz tracks
 /// For mixed playlists, we look further ahead to find Qobuz tracks past local ones
+/// Tracks are fetched at the prefetch quality when one is configured (see
+/// `prefetch_quality_for`) and flagged as reduced in the cache
 fn spawn_prefetch(
     client: Arc<RwLock<QobuzClient>>,
     cache: Arc<AudioCache>,
     queue: &QueueManager,
-    quality: Quality,
+    playback_quality: Quality,
+    prefetch_quality: Option<Quality>,
     streaming_only: bool,
 ) {
     // Skip prefetch entirely in streaming_only mode
@@ -780,6 +1254,16 @@ fn spawn_prefetch(
         return;
     }
 
+    let quality = prefetch_quality_for(playback_quality, prefetch_quality, last_download_speed());
+    let reduced_quality = quality < playback_quality;
+    if reduced_quality {
+        log::info!(
+            "[PREFETCH] Using {} (playback: {})",
+            quality.label(),
+            playback_quality.label()
+        );
+    }
+
     // Look further ahead to find Qobuz tracks in mixed playlists
     let upcoming_tracks = queue.peek_upcoming(PREFETCH_LOOKAHEAD);
 
@@ -846,18 +1330,22 @@ fn spawn_prefetch(
                     .get_stream_url_with_fallback(track_id, quality)
                     .await
                     .map_err(|e| format!("Failed to get stream URL: {}", e))?;
+                let stream_url = fresh_stream_url(stream_url, get_timestamp(), || {
+                    client_guard.get_stream_url_with_fallback(track_id, quality)
+                })
+                .await?;
                 drop(client_guard);
 
                 let data = download_audio(&stream_url.url).await?;
-                Ok::<Vec<u8>, String>(data)
+                Ok::<(Vec<u8>, u32), String>((data, stream_url.format_id))
             }
             .await;
 
             match result {
-                Ok(data) => {
+                Ok((data, format_id)) => {
                     // Small delay before cache insertion to avoid potential race with audio thread
                     tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-                    cache_clone.insert(track_id, data);
+                    cache_clone.insert_with_format(track_id, data, Some(format_id), reduced_quality);
                     log::info!("Prefetch complete for track {}", track_id);
                 }
                 Err(e) => {
@@ -871,37 +1359,132 @@ fn spawn_prefetch(
     }
 }
 
-/// Pause playback
+/// Re-fetch `stream_url` when it expires within the refresh margin of `now`,
+/// so a download never starts on a URL the CDN is about to reject
+async fn fresh_stream_url<F, Fut>(
+    stream_url: StreamUrl,
+    now: u64,
+    refetch: F,
+) -> Result<StreamUrl, String>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<StreamUrl, ApiError>>,
+{
+    if !stream_url.needs_refresh(now) {
+        return Ok(stream_url);
+    }
+    log::info!(
+        "Stream URL for track {} expires at {}, fetching a new one",
+        stream_url.track_id,
+        stream_url.expires_at
+    );
+    refetch()
+        .await
+        .map_err(|e| format!("Failed to refresh stream URL: {}", e))
+}
+
+/// Re-fetch a track that was prefetched at the reduced prefetch quality at the
+/// playback quality, replacing the cached copy for the next play or seek back
+fn spawn_quality_upgrade(
+    client: Arc<RwLock<QobuzClient>>,
+    cache: Arc<AudioCache>,
+    track_id: u64,
+    quality: Quality,
+    cached_format_id: Option<u32>,
+) {
+    if cache.is_fetching(track_id) {
+        return;
+    }
+    cache.mark_fetching(track_id);
+
+    tokio::spawn(async move {
+        let _permit = match PREFETCH_SEMAPHORE.acquire().await {
+            Ok(permit) => permit,
+            Err(_) => {
+                cache.unmark_fetching(track_id);
+                return;
+            }
+        };
+
+        let result = async {
+            let client_guard = client.read().await;
+            let stream_url = client_guard
+                .get_stream_url_with_fallback(track_id, quality)
+                .await
+                .map_err(|e| format!("Failed to get stream URL: {}", e))?;
+            let stream_url = fresh_stream_url(stream_url, get_timestamp(), || {
+                client_guard.get_stream_url_with_fallback(track_id, quality)
+            })
+            .await?;
+            drop(client_guard);
+
+            // The track may not exist in a higher quality than the prefetched copy
+            if cached_format_id.is_some_and(|format_id| stream_url.format_id <= format_id) {
+                return Ok(None);
+            }
+            let data = download_audio(&stream_url.url).await?;
+            Ok::<_, String>(Some((data, stream_url.format_id)))
+        }
+        .await;
+
+        match result {
+            Ok(Some((data, format_id))) => {
+                cache.insert_with_format(track_id, data, Some(format_id), false);
+                log::info!("Upgraded cached track {} to format_id={}", track_id, format_id);
+            }
+            Ok(None) => log::debug!("Track {} has no higher quality to upgrade to", track_id),
+            Err(e) => log::warn!("Quality upgrade failed for track {}: {}", track_id, e),
+        }
+
+        cache.unmark_fetching(track_id);
+    });
+}
+
+/// Pause playback (on the cast device while casting)
 #[tauri::command]
-pub fn pause_playback(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn pause_playback(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     log::info!("Command: pause_playback");
     state.media_controls.set_playback(false);
+    if playback_router::dispatch(&app, TransportAction::Pause).await? {
+        return Ok(());
+    }
     state.player.pause()
 }
 
-/// Resume playback
+/// Resume playback (on the cast device while casting)
 #[tauri::command]
-pub fn resume_playback(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn resume_playback(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     log::info!("Command: resume_playback");
     state.media_controls.set_playback(true);
+    if playback_router::dispatch(&app, TransportAction::Resume).await? {
+        return Ok(());
+    }
     state.player.resume()
 }
 
-/// Stop playback
+/// Stop playback (on the cast device while casting)
 #[tauri::command]
-pub fn stop_playback(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn stop_playback(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     log::info!("Command: stop_playback");
     state.media_controls.set_stopped();
+    if playback_router::dispatch(&app, TransportAction::Stop).await? {
+        return Ok(());
+    }
     state.player.stop()
 }
 
 /// Queue next track for gapless playback (cache-only, no download)
 /// Returns true if gapless was queued, false if track not cached or ineligible
+///
+/// `quality` is the playback quality, used to upgrade a copy that was
+/// prefetched at the reduced prefetch quality (as `play_track` does).
 #[tauri::command]
 pub async fn play_next_gapless(
     track_id: u64,
+    quality: Option<String>,
     state: State<'_, AppState>,
     offline_cache: State<'_, OfflineCacheState>,
+    audio_settings: State<'_, AudioSettingsState>,
 ) -> Result<bool, String> {
     log::info!("Command: play_next_gapless for track {}", track_id);
 
@@ -925,7 +1508,7 @@ pub async fn play_next_gapless(
                 log::info!("[GAPLESS] Track {} from OFFLINE cache", track_id);
                 let audio_data = std::fs::read(path)
                     .map_err(|e| format!("Failed to read cached file: {}", e))?;
-                state.player.play_next(audio_data, track_id)?;
+                state.player.play_next(audio_data, track_id, None, false)?;
                 return Ok(true);
             }
         }
@@ -935,7 +1518,24 @@ pub async fn play_next_gapless(
     let cache = state.audio_cache.clone();
     if let Some(cached) = cache.get(track_id) {
         log::info!("[GAPLESS] Track {} from MEMORY cache ({} bytes)", track_id, cached.size_bytes);
-        state.player.play_next(cached.data, track_id)?;
+        let (format_id, reduced_quality) = (cached.format_id, cached.reduced_quality);
+        state.player.play_next(cached.data, track_id, format_id, reduced_quality)?;
+
+        let (_, upgrade_prefetched) = prefetch_settings(&audio_settings);
+        if reduced_quality && upgrade_prefetched {
+            let (preferred_quality, _) =
+                limit_quality_for_device_settings(parse_quality(quality.as_deref()), &audio_settings);
+            let preferred_quality = limit_quality_for_connection(preferred_quality, &audio_settings).await;
+            let preferred_quality =
+                limit_quality_for_track(preferred_quality, track_id, &state.queue, &audio_settings).await;
+            spawn_quality_upgrade(
+                state.client.clone(),
+                state.audio_cache.clone(),
+                track_id,
+                preferred_quality,
+                format_id,
+            );
+        }
         return Ok(true);
     }
 
@@ -943,7 +1543,7 @@ pub async fn play_next_gapless(
     if let Some(playback_cache) = cache.get_playback_cache() {
         if let Some(audio_data) = playback_cache.get(track_id) {
             log::info!("[GAPLESS] Track {} from DISK cache ({} bytes)", track_id, audio_data.len());
-            state.player.play_next(audio_data, track_id)?;
+            state.player.play_next(audio_data, track_id, None, false)?;
             return Ok(true);
         }
     }
@@ -975,21 +1575,27 @@ pub fn set_media_metadata(
     Ok(())
 }
 
-/// Set volume (0.0 - 1.0)
+/// Set volume (0.0 - 1.0), on the cast device while casting
 #[tauri::command]
-pub fn set_volume(volume: f32, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn set_volume(volume: f32, app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     // Skip logging if volume is the same (reduces log spam from MPRIS polling)
     let current = state.player.state.volume();
     if (volume - current).abs() >= 0.001 {
         log::info!("Command: set_volume {}", volume);
     }
+    if playback_router::dispatch(&app, TransportAction::SetVolume(volume)).await? {
+        return Ok(());
+    }
     state.player.set_volume(volume)
 }
 
-/// Seek to position in seconds
+/// Seek to position in seconds, on the cast device while casting
 #[tauri::command]
-pub fn seek(position: u64, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn seek(position: u64, app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     log::info!("Command: seek {}", position);
+    if playback_router::dispatch(&app, TransportAction::Seek(position)).await? {
+        return Ok(());
+    }
     let result = state.player.seek(position);
 
     // Update MPRIS with new position
@@ -1002,6 +1608,50 @@ pub fn seek(position: u64, state: State<'_, AppState>) -> Result<(), String> {
     result
 }
 
+/// Start a sleep timer (replaces any running timer)
+#[tauri::command]
+pub fn set_sleep_timer(
+    duration_secs: u64,
+    action: SleepTimerAction,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    log::info!("Command: set_sleep_timer {}s {:?}", duration_secs, action);
+    if duration_secs == 0 {
+        return Err("Sleep timer duration must be greater than zero".to_string());
+    }
+    state.player.set_sleep_timer(SleepTimer {
+        duration: std::time::Duration::from_secs(duration_secs),
+        action,
+    });
+    Ok(())
+}
+
+/// Get the running sleep timer (None if no timer is set)
+#[tauri::command]
+pub fn get_sleep_timer(state: State<'_, AppState>) -> Result<Option<SleepTimerStatus>, String> {
+    Ok(state.player.get_sleep_timer())
+}
+
+/// Cancel the sleep timer
+#[tauri::command]
+pub fn cancel_sleep_timer(state: State<'_, AppState>) -> Result<(), String> {
+    log::info!("Command: cancel_sleep_timer");
+    state.player.cancel_sleep_timer();
+    Ok(())
+}
+
+/// Get the sink playback is currently routed to
+#[tauri::command]
+pub fn get_playback_route(state: State<'_, AppState>) -> PlaybackRoute {
+    state.router.route()
+}
+
+/// Get the decoded format of the current track (None when nothing was played)
+#[tauri::command]
+pub fn get_current_format_info(state: State<'_, AppState>) -> Option<DecodedFormatInfo> {
+    state.player.state.format_info()
+}
+
 /// Get current playback state (also updates MPRIS progress)
 #[tauri::command]
 pub fn get_playback_state(state: State<'_, AppState>) -> Result<PlaybackState, String> {
@@ -1141,6 +1791,10 @@ pub fn get_audio_devices() -> Result<Vec<AudioDevice>, String> {
 pub struct AudioOutputStatus {
     pub device_name: Option<String>,
     pub is_playing: bool,
+    /// Effective output buffer size in frames (None if no stream is open)
+    pub buffer_frames: Option<u32>,
+    /// Latency estimate derived from the buffer size and current sample rate
+    pub latency_ms: Option<f64>,
 }
 
 /// Get current audio output status (what device is actually being used)
@@ -1150,10 +1804,17 @@ pub fn get_audio_output_status(
 ) -> Result<AudioOutputStatus, String> {
     let device_name = state.player.state.current_device();
     let is_playing = state.player.state.is_playing();
+    let frames = state.player.state.get_output_buffer_frames();
+    let buffer_frames = if frames > 0 { Some(frames) } else { None };
+    let latency_ms = buffer_frames.and_then(|f| {
+        crate::audio::buffer_latency_ms(f, state.player.state.get_sample_rate())
+    });
 
     Ok(AudioOutputStatus {
         device_name,
         is_playing,
+        buffer_frames,
+        latency_ms,
     })
 }
 
@@ -1342,3 +2003,196 @@ pub fn debug_get_cpal_devices() -> Result<Vec<String>, String> {
     log::info!("CPAL devices: {:?}", devices);
     Ok(devices)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefetch_uses_lower_tier_on_slow_links() {
+        let slow = Some(0.5);
+        assert_eq!(
+            prefetch_quality_for(Quality::UltraHiRes, Some(Quality::Lossless), slow),
+            Quality::Lossless
+        );
+        // Unknown speed: stay on the safe side
+        assert_eq!(
+            prefetch_quality_for(Quality::HiRes, Some(Quality::Mp3), None),
+            Quality::Mp3
+        );
+    }
+
+    #[test]
+    fn quality_chain_follows_configured_order() {
+        let order = Quality::fallback_order();
+        assert_eq!(
+            quality_chain(Quality::HiRes, order),
+            vec![Quality::HiRes, Quality::Lossless, Quality::Mp3]
+        );
+        // MP3 left out of the order: never fall back to it
+        let lossless_only = [Quality::UltraHiRes, Quality::HiRes, Quality::Lossless];
+        assert_eq!(quality_chain(Quality::Lossless, &lossless_only), vec![Quality::Lossless]);
+        // Nothing at or below the preferred tier: request it anyway
+        assert_eq!(quality_chain(Quality::Mp3, &lossless_only), vec![Quality::Mp3]);
+    }
+
+    #[test]
+    fn device_limit_follows_strategy() {
+        let (quality, limit) = apply_device_limit(
+            Quality::UltraHiRes,
+            Some(48000),
+            QualityLimitStrategy::RequestLower,
+        );
+        assert_eq!(quality, Quality::Lossless);
+        assert_eq!(limit.unwrap().device_quality, Quality::Lossless);
+
+        let (quality, limit) = apply_device_limit(
+            Quality::UltraHiRes,
+            Some(96000),
+            QualityLimitStrategy::FetchHiresAndResample,
+        );
+        assert_eq!(quality, Quality::UltraHiRes);
+        let limit = limit.unwrap();
+        assert_eq!(limit.device_quality, Quality::HiRes);
+        assert_eq!(limit.requested, Quality::UltraHiRes);
+
+        // Device plays the tier natively, or its rate is unknown: no limit
+        assert_eq!(
+            apply_device_limit(Quality::HiRes, Some(96000), QualityLimitStrategy::RequestLower),
+            (Quality::HiRes, None)
+        );
+        assert_eq!(
+            apply_device_limit(Quality::UltraHiRes, None, QualityLimitStrategy::RequestLower),
+            (Quality::UltraHiRes, None)
+        );
+    }
+
+    #[test]
+    fn downgrade_only_below_requested_tier() {
+        assert_eq!(
+            quality_downgrade(Quality::UltraHiRes, Some(Quality::Lossless)),
+            Some(QualityDowngrade {
+                requested: Quality::UltraHiRes,
+                achieved: Quality::Lossless,
+            })
+        );
+        assert_eq!(quality_downgrade(Quality::HiRes, Some(Quality::HiRes)), None);
+        assert_eq!(quality_downgrade(Quality::HiRes, None), None);
+    }
+
+    #[test]
+    fn prefetch_keeps_playback_quality_when_possible() {
+        // No prefetch tier configured
+        assert_eq!(prefetch_quality_for(Quality::HiRes, None, Some(0.1)), Quality::HiRes);
+        // Prefetch tier is not lower than playback
+        assert_eq!(
+            prefetch_quality_for(Quality::Lossless, Some(Quality::HiRes), Some(0.1)),
+            Quality::Lossless
+        );
+        // Bandwidth is ample for the playback quality
+        assert_eq!(
+            prefetch_quality_for(Quality::UltraHiRes, Some(Quality::Lossless), Some(12.0)),
+            Quality::UltraHiRes
+        );
+    }
+
+    #[tokio::test]
+    async fn expiring_stream_url_is_refreshed_before_download() {
+        let now = 1_700_000_000;
+        let stream_url = |expires_at: u64| StreamUrl {
+            url: format!("https://streaming.example/file?etsp={}&hmac=x", expires_at),
+            format_id: 27,
+            mime_type: "audio/flac".to_string(),
+            sampling_rate: 96.0,
+            bit_depth: Some(24),
+            track_id: 42,
+            restrictions: vec![],
+            expires_at,
+        };
+        assert_eq!(
+            StreamUrl::expiry_of(&stream_url(now + 1800).url, now),
+            now + 1800
+        );
+        // No signed expiry: assume a short lifetime
+        assert_eq!(
+            StreamUrl::expiry_of("https://streaming.example/file", now),
+            now + 600
+        );
+
+        // Fetched long ago and about to expire: a new URL is fetched
+        let refreshed = fresh_stream_url(stream_url(now + 30), now, || async {
+            Ok(stream_url(now + 1800))
+        })
+        .await
+        .unwrap();
+        assert_eq!(refreshed.expires_at, now + 1800);
+
+        // Still valid: used as is
+        let kept = fresh_stream_url(stream_url(now + 600), now, || async {
+            panic!("a valid URL must not be re-fetched")
+        })
+        .await
+        .unwrap();
+        assert_eq!(kept.expires_at, now + 600);
+    }
+
+    type MockStream = futures_util::stream::BoxStream<'static, Result<Vec<u8>, String>>;
+
+    /// A connection serving `data` from `offset` that goes quiet after
+    /// `hang_after` bytes (None = serves everything)
+    fn mock_connection(data: &[u8], offset: u64, hang_after: Option<usize>) -> MockStream {
+        use futures_util::StreamExt;
+        let rest = &data[offset as usize..];
+        let sent = hang_after.map_or(rest.len(), |n| n.min(rest.len()));
+        let chunks: Vec<Result<Vec<u8>, String>> =
+            rest[..sent].chunks(64).map(|c| Ok(c.to_vec())).collect();
+        let stream = futures_util::stream::iter(chunks);
+        match hang_after {
+            Some(_) => stream.chain(futures_util::stream::pending()).boxed(),
+            None => stream.boxed(),
+        }
+    }
+
+    #[tokio::test]
+    async fn stalled_stream_resumes_from_last_byte() {
+        let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let mut offsets = Vec::new();
+        let mut received = Vec::new();
+        let result = pump_stream(
+            |offset| {
+                offsets.push(offset);
+                // The first connection stalls after 300 bytes
+                let hang_after = (offset == 0).then_some(300);
+                std::future::ready(Ok(mock_connection(&data, offset, hang_after)))
+            },
+            data.len() as u64,
+            std::time::Duration::from_millis(50),
+            |chunk| received.extend_from_slice(chunk),
+        )
+        .await;
+
+        assert_eq!(result, Ok(1000));
+        assert_eq!(offsets, vec![0, 300]);
+        assert_eq!(received, data);
+    }
+
+    #[tokio::test]
+    async fn stream_that_never_recovers_fails_clearly() {
+        let data = vec![0u8; 1000];
+        let mut connections = 0;
+        let result = pump_stream(
+            |offset| {
+                connections += 1;
+                std::future::ready(Ok(mock_connection(&data, offset, Some(100))))
+            },
+            data.len() as u64,
+            std::time::Duration::from_millis(20),
+            |_| {},
+        )
+        .await;
+
+        let error = result.unwrap_err();
+        assert!(error.starts_with("Stream stalled after 4 attempts: no data"), "{}", error);
+        assert_eq!(connections, MAX_STREAM_RESUMES + 1);
+    }
+}
@@ -4,6 +4,30 @@ use tauri::State;
 use crate::playback_context::{PlaybackContext, ContextType, ContentSource};
 use crate::AppState;
 
+/// Parse a context type as sent by the frontend
+pub(crate) fn parse_context_type(context_type: &str) -> Result<ContextType, String> {
+    match context_type {
+        "album" => Ok(ContextType::Album),
+        "playlist" => Ok(ContextType::Playlist),
+        "artist_top" => Ok(ContextType::ArtistTop),
+        "home_list" => Ok(ContextType::HomeList),
+        "favorites" => Ok(ContextType::Favorites),
+        "local_library" => Ok(ContextType::LocalLibrary),
+        "radio" => Ok(ContextType::Radio),
+        _ => Err(format!("Invalid context type: {}", context_type)),
+    }
+}
+
+/// Parse a content source as sent by the frontend
+pub(crate) fn parse_content_source(source: &str) -> Result<ContentSource, String> {
+    match source {
+        "qobuz" => Ok(ContentSource::Qobuz),
+        "local" => Ok(ContentSource::Local),
+        "plex" => Ok(ContentSource::Plex),
+        _ => Err(format!("Invalid source: {}", source)),
+    }
+}
+
 /// Get the current playback context
 #[tauri::command]
 pub fn get_playback_context(state: State<'_, AppState>) -> Option<PlaybackContext> {
@@ -21,25 +45,8 @@ pub fn set_playback_context(
     start_position: usize,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    // Parse context type
-    let ctx_type = match context_type.as_str() {
-        "album" => ContextType::Album,
-        "playlist" => ContextType::Playlist,
-        "artist_top" => ContextType::ArtistTop,
-        "home_list" => ContextType::HomeList,
-        "favorites" => ContextType::Favorites,
-        "local_library" => ContextType::LocalLibrary,
-        "radio" => ContextType::Radio,
-        _ => return Err(format!("Invalid context type: {}", context_type)),
-    };
-
-    // Parse source
-    let content_source = match source.as_str() {
-        "qobuz" => ContentSource::Qobuz,
-        "local" => ContentSource::Local,
-        "plex" => ContentSource::Plex,
-        _ => return Err(format!("Invalid source: {}", source)),
-    };
+    let ctx_type = parse_context_type(&context_type)?;
+    let content_source = parse_content_source(&source)?;
 
     let context = PlaybackContext::new(
         ctx_type,
@@ -50,6 +57,7 @@ pub fn set_playback_context(
         start_position,
     );
 
+    state.player.state.set_album_context(context.context_type == ContextType::Album);
     state.context.set_context(context);
     Ok(())
 }
@@ -57,6 +65,7 @@ pub fn set_playback_context(
 /// Clear the current playback context
 #[tauri::command]
 pub fn clear_playback_context(state: State<'_, AppState>) {
+    state.player.state.set_album_context(false);
     state.context.clear_context();
 }
 
@@ -1,11 +1,15 @@
 //! Playlist-related Tauri commands
 
+use std::collections::HashMap;
+
 use serde::Serialize;
 use tauri::State;
 
 use crate::api::models::{Playlist, PlaylistDuplicateResult, PlaylistWithTrackIds, SearchResultsPage, Track};
 use crate::api::performers::{parse_performers, Performer};
 use crate::library::commands::LibraryState;
+use crate::library::playlist_view::{apply_order, custom_positions, view_order, ViewEntry};
+use crate::offline::{check_network_connectivity, OfflineState};
 use crate::AppState;
 
 /// Track info with parsed performers for display
@@ -33,7 +37,9 @@ pub async fn get_user_playlists(
 #[tauri::command]
 pub async fn get_playlist(
     playlist_id: u64,
+    apply_view: Option<bool>,
     state: State<'_, AppState>,
+    library_state: State<'_, LibraryState>,
 ) -> Result<Playlist, String> {
     let cmd_start = std::time::Instant::now();
     log::debug!("Command: get_playlist {}", playlist_id);
@@ -41,11 +47,20 @@ pub async fn get_playlist(
     let client = state.client.read().await;
     let lock_elapsed = cmd_start.elapsed();
 
-    let result = client
+    let mut result = client
         .get_playlist(playlist_id)
         .await
         .map_err(|e| format!("Failed to get playlist: {}", e));
 
+    // Return the tracks the way the user left the playlist view
+    if apply_view != Some(false) {
+        if let Ok(playlist) = result.as_mut() {
+            if let Err(e) = apply_playlist_view(playlist, &library_state).await {
+                log::warn!("Failed to apply view of playlist {}: {}", playlist_id, e);
+            }
+        }
+    }
+
     let total_elapsed = cmd_start.elapsed();
     log::info!(
         "Command: get_playlist {} — lock: {:.1}ms, total: {:.1}ms, tracks: {}",
@@ -58,6 +73,61 @@ pub async fn get_playlist(
     result
 }
 
+/// Sort, group and filter the tracks of `playlist` by its persisted view settings
+async fn apply_playlist_view(
+    playlist: &mut Playlist,
+    library_state: &LibraryState,
+) -> Result<(), String> {
+    let guard__ = library_state.db.lock().await;
+    let Some(db) = guard__.as_ref() else {
+        return Ok(());
+    };
+    let Some(settings) = db.get_playlist_settings(playlist.id).map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+    let Some(container) = playlist.tracks.as_mut() else {
+        return Ok(());
+    };
+
+    let custom = if settings.sort_by == "custom" {
+        custom_positions(&db.get_playlist_custom_order(playlist.id).map_err(|e| e.to_string())?)
+    } else {
+        HashMap::new()
+    };
+    let local_copies = if settings.offline_only {
+        let ids: Vec<u64> = container.items.iter().map(|t| t.id).collect();
+        Some(db.get_tracks_with_local_copies(&ids).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+
+    let tracks = std::mem::take(&mut container.items);
+    let entries: Vec<ViewEntry> = tracks
+        .iter()
+        .enumerate()
+        .map(|(position, track)| {
+            let album = track.album.as_ref();
+            ViewEntry {
+                title: &track.title,
+                artist: track.performer.as_ref().map(|p| p.name.as_str()).unwrap_or_default(),
+                album: album.map(|a| a.title.as_str()).unwrap_or_default(),
+                label: album.and_then(|a| a.label.as_ref()).map(|l| l.name.as_str()),
+                duration_secs: track.duration as u64,
+                position,
+                added_at: track.added_at,
+                custom_position: custom.get(&(track.id as i64, false)).copied(),
+            }
+        })
+        .collect();
+
+    let mut order = view_order(&entries, &settings);
+    if let Some(local_copies) = local_copies {
+        order.retain(|&index| local_copies.contains(&tracks[index].id));
+    }
+    container.items = apply_order(tracks, &order);
+    Ok(())
+}
+
 /// Get playlist metadata + ordered track IDs (lightweight, no full track data)
 #[tauri::command]
 pub async fn get_playlist_track_ids(
@@ -208,6 +278,169 @@ pub async fn add_tracks_to_playlist(
         .map_err(|e| format!("Failed to add tracks to playlist: {}", e))
 }
 
+/// Result of saving the queue as a playlist
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedQueuePlaylist {
+    /// Qobuz playlist ID (None when saved offline)
+    pub playlist_id: Option<u64>,
+    /// Pending playlist ID, synced to Qobuz once back online
+    pub pending_id: Option<i64>,
+    /// Tracks saved as Qobuz tracks (including local files ripped from Qobuz)
+    pub qobuz_tracks: usize,
+    /// Local files linked to the playlist
+    pub local_tracks: usize,
+    /// Plex tracks and local files no longer in the library
+    pub skipped_tracks: usize,
+}
+
+/// Save the whole queue, in play order, as a new playlist.
+///
+/// Qobuz tracks go to Qobuz; local files are linked through the local-track
+/// playlist storage at their place in the queue (or added as their Qobuz
+/// original when known). Plex tracks can't be saved and are skipped. When
+/// offline the playlist is queued for pending playlist sync instead, which
+/// puts local files after the Qobuz tracks.
+#[tauri::command]
+pub async fn save_queue_as_playlist(
+    name: String,
+    description: Option<String>,
+    is_public: Option<bool>,
+    state: State<'_, AppState>,
+    library_state: State<'_, LibraryState>,
+    offline_state: State<'_, OfflineState>,
+) -> Result<SavedQueuePlaylist, String> {
+    let tracks = state.queue.tracks_in_play_order();
+    log::info!("Command: save_queue_as_playlist \"{}\" ({} tracks)", name, tracks.len());
+
+    if tracks.is_empty() {
+        return Err("The queue is empty".to_string());
+    }
+
+    let mut qobuz_ids: Vec<u64> = Vec::new();
+    // (local track ID, file path, position in the saved playlist)
+    let mut local_links: Vec<(i64, String, usize)> = Vec::new();
+    let mut skipped_tracks = 0;
+    {
+        let guard__ = library_state.db.lock().await;
+        for track in &tracks {
+            if track.source.as_deref() == Some("plex") {
+                skipped_tracks += 1;
+            } else if track.is_local {
+                let local = match guard__.as_ref() {
+                    Some(db) => db
+                        .get_track(track.id as i64)
+                        .map_err(|e| format!("Failed to get local track: {}", e))?,
+                    None => None,
+                };
+                match local {
+                    Some(local) => match local.qobuz_track_id {
+                        Some(qobuz_id) if qobuz_id > 0 => qobuz_ids.push(qobuz_id as u64),
+                        _ => {
+                            let position = qobuz_ids.len() + local_links.len();
+                            local_links.push((local.id, local.file_path, position));
+                        }
+                    },
+                    None => skipped_tracks += 1,
+                }
+            } else {
+                qobuz_ids.push(track.id);
+            }
+        }
+    }
+
+    let manual_offline = {
+        let guard__ = offline_state
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard__
+            .as_ref()
+            .ok_or("No active session - please log in")?;
+        store.get_settings()?.manual_offline_mode
+    };
+
+    if manual_offline || !check_network_connectivity().await {
+        let local_paths: Vec<String> = local_links
+            .iter()
+            .map(|(_, path, _)| path.clone())
+            .collect();
+        let guard__ = offline_state
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard__
+            .as_ref()
+            .ok_or("No active session - please log in")?;
+        let pending_id = store.create_pending_playlist(
+            &name,
+            description.as_deref(),
+            is_public.unwrap_or(false),
+            &qobuz_ids,
+            &local_paths,
+        )?;
+        log::info!("Queue saved offline as pending playlist {}", pending_id);
+        return Ok(SavedQueuePlaylist {
+            playlist_id: None,
+            pending_id: Some(pending_id),
+            qobuz_tracks: qobuz_ids.len(),
+            local_tracks: local_paths.len(),
+            skipped_tracks,
+        });
+    }
+
+    let playlist = {
+        let client = state.client.read().await;
+        let playlist = client
+            .create_playlist(&name, description.as_deref(), is_public.unwrap_or(false))
+            .await
+            .map_err(|e| format!("Failed to create playlist: {}", e))?;
+        if !qobuz_ids.is_empty() {
+            if let Err(e) = client.add_tracks_to_playlist(playlist.id, &qobuz_ids).await {
+                // Don't leave an empty playlist behind
+                if let Err(delete_err) = client.delete_playlist(playlist.id).await {
+                    log::warn!(
+                        "Failed to delete playlist {} after a failed save: {}",
+                        playlist.id,
+                        delete_err
+                    );
+                }
+                return Err(format!("Failed to add tracks to playlist: {}", e));
+            }
+        }
+        playlist
+    };
+
+    // The playlist exists on Qobuz at this point: local files that can't be
+    // linked are reported as skipped rather than failing the save
+    let mut local_tracks = 0;
+    if !local_links.is_empty() {
+        let guard__ = library_state.db.lock().await;
+        for (local_track_id, _, position) in &local_links {
+            let linked = match guard__.as_ref() {
+                Some(db) => db
+                    .add_local_track_to_playlist(playlist.id, *local_track_id, *position as i32)
+                    .map_err(|e| e.to_string()),
+                None => Err("No active session".to_string()),
+            };
+            match linked {
+                Ok(()) => local_tracks += 1,
+                Err(e) => {
+                    log::warn!("Failed to link local track {}: {}", local_track_id, e);
+                    skipped_tracks += 1;
+                }
+            }
+        }
+    }
+
+    Ok(SavedQueuePlaylist {
+        playlist_id: Some(playlist.id),
+        pending_id: None,
+        qobuz_tracks: qobuz_ids.len(),
+        local_tracks,
+        skipped_tracks,
+    })
+}
+
 /// Remove tracks from a playlist.
 /// Accepts either playlist_track_ids (direct Qobuz IDs) or regular track_ids.
 /// When track_ids are provided (and playlist_track_ids is empty), resolves them
@@ -287,28 +520,98 @@ pub async fn update_playlist(
         .map_err(|e| format!("Failed to update playlist: {}", e))
 }
 
+/// track/getList accepts at most this many IDs per call
+const TRACK_LIST_CHUNK_SIZE: usize = 50;
+/// Concurrent track/getList requests when fetching large ID lists
+const TRACK_LIST_CONCURRENCY: usize = 4;
+
+/// Tracks fetched by ID, plus the IDs that couldn't be fetched
+#[derive(Debug, Clone, Serialize)]
+pub struct TracksByIdsResult {
+    /// Found tracks, in the order they were requested
+    pub tracks: Vec<Track>,
+    pub failed_ids: Vec<u64>,
+}
+
+/// Order `found` by `ids` (duplicates repeated); IDs with no entry are returned
+/// separately, once each
+fn order_by_ids<T: Clone>(ids: &[u64], found: &HashMap<u64, T>) -> (Vec<T>, Vec<u64>) {
+    let mut items = Vec::with_capacity(ids.len());
+    let mut missing = Vec::new();
+    for id in ids {
+        match found.get(id) {
+            Some(item) => items.push(item.clone()),
+            None if !missing.contains(id) => missing.push(*id),
+            None => {}
+        }
+    }
+    (items, missing)
+}
+
 /// Get multiple tracks by their IDs
+///
+/// IDs are fetched in track/getList-sized chunks, a few at a time. A chunk
+/// that fails is retried track by track, and whatever still can't be fetched
+/// ends up in `failed_ids` instead of failing the whole call.
 #[tauri::command]
 pub async fn get_tracks_by_ids(
     track_ids: Vec<u64>,
     state: State<'_, AppState>,
-) -> Result<Vec<crate::api::models::Track>, String> {
+) -> Result<TracksByIdsResult, String> {
+    use futures_util::stream::{self, StreamExt};
+
     log::debug!("Command: get_tracks_by_ids ({} tracks)", track_ids.len());
 
-    let client = state.client.read().await;
-    let mut tracks = Vec::new();
-
-    for track_id in track_ids {
-        match client.get_track(track_id).await {
-            Ok(track) => tracks.push(track),
-            Err(e) => {
-                log::warn!("Failed to get track {}: {}", track_id, e);
-                // Continue with other tracks even if one fails
+    let client = state.client.read().await.clone();
+    let mut unique_ids = track_ids.clone();
+    unique_ids.sort_unstable();
+    unique_ids.dedup();
+
+    // Owned chunks: borrowed slices held across awaits make the command future
+    // not general enough for the handler
+    let id_chunks: Vec<Vec<u64>> = unique_ids
+        .chunks(TRACK_LIST_CHUNK_SIZE)
+        .map(<[u64]>::to_vec)
+        .collect();
+    let chunks: Vec<Vec<Track>> = stream::iter(id_chunks)
+        .map(|chunk| {
+            let client = client.clone();
+            async move {
+                match client.get_tracks_batch(&chunk).await {
+                    Ok(tracks) => tracks,
+                    Err(e) => {
+                        log::warn!(
+                            "track/getList failed for {} IDs, fetching one by one: {}",
+                            chunk.len(),
+                            e
+                        );
+                        let mut tracks = Vec::with_capacity(chunk.len());
+                        for &track_id in &chunk {
+                            match client.get_track(track_id).await {
+                                Ok(track) => tracks.push(track),
+                                Err(e) => log::warn!("Failed to get track {}: {}", track_id, e),
+                            }
+                        }
+                        tracks
+                    }
+                }
             }
-        }
+        })
+        .buffer_unordered(TRACK_LIST_CONCURRENCY)
+        .collect()
+        .await;
+
+    let found: HashMap<u64, Track> = chunks
+        .into_iter()
+        .flatten()
+        .map(|track| (track.id, track))
+        .collect();
+    let (tracks, failed_ids) = order_by_ids(&track_ids, &found);
+    if !failed_ids.is_empty() {
+        log::warn!("get_tracks_by_ids: {} of {} IDs failed", failed_ids.len(), unique_ids.len());
     }
 
-    Ok(tracks)
+    Ok(TracksByIdsResult { tracks, failed_ids })
 }
 
 /// Get the current user's Qobuz ID
@@ -410,3 +713,16 @@ pub async fn get_track_info(
 
     Ok(TrackInfo { track, performers })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_by_ids_keeps_request_order_and_reports_missing() {
+        let found: HashMap<u64, &str> = [(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+        let (items, missing) = order_by_ids(&[3, 9, 1, 3, 9, 2], &found);
+        assert_eq!(items, vec!["c", "a", "c", "b"]);
+        assert_eq!(missing, vec![9]);
+    }
+}
@@ -1,33 +1,33 @@
 //! Queue management Tauri commands
 
-use tauri::State;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 use crate::artist_blacklist::BlacklistState;
-use crate::queue::{QueueState, QueueTrack, RepeatMode};
-use crate::AppState;
-
-/// Check if a track's artist is blacklisted
-fn is_track_blacklisted(track: &QueueTrack, blacklist: &BlacklistState) -> bool {
-    if let Some(artist_id) = track.artist_id {
-        blacklist.is_blacklisted(artist_id)
-    } else {
-        false
-    }
-}
+use crate::commands::playback_context::{parse_content_source, parse_context_type};
+use crate::config::playback_preferences::{
+    EndOfQueueBehavior, PlaybackPreferencesState, DEFAULT_PREVIOUS_RESTART_THRESHOLD_SECS,
+};
+use crate::playback_context::{ContextType, PlaybackContext};
+use crate::queue::{shuffle_once, QueueState, QueueTiming, QueueTrack, QueueWindow, RepeatMode, SkippedTrack};
+use crate::reco_store::RecoState;
+use crate::{api_server, AppState};
 
 /// Add a track to the queue
 #[tauri::command]
 pub fn add_to_queue(
     track: QueueTrack,
+    app: AppHandle,
     state: State<'_, AppState>,
     blacklist: State<'_, BlacklistState>,
 ) -> Result<(), String> {
-    if is_track_blacklisted(&track, &blacklist) {
+    if blacklist.is_queue_track_blacklisted(&track) {
         log::debug!("Skipping blacklisted track: {} by {}", track.title, track.artist);
         return Ok(());
     }
     log::info!("Command: add_to_queue - {} by {}", track.title, track.artist);
     state.queue.add_track(track);
+    api_server::broadcast_queue_update(&app);
     Ok(())
 }
 
@@ -35,15 +35,17 @@ pub fn add_to_queue(
 #[tauri::command]
 pub fn add_to_queue_next(
     track: QueueTrack,
+    app: AppHandle,
     state: State<'_, AppState>,
     blacklist: State<'_, BlacklistState>,
 ) -> Result<(), String> {
-    if is_track_blacklisted(&track, &blacklist) {
+    if blacklist.is_queue_track_blacklisted(&track) {
         log::debug!("Skipping blacklisted track (next): {} by {}", track.title, track.artist);
         return Ok(());
     }
     log::info!("Command: add_to_queue_next - {} by {}", track.title, track.artist);
     state.queue.add_track_next(track);
+    api_server::broadcast_queue_update(&app);
     Ok(())
 }
 
@@ -51,15 +53,17 @@ pub fn add_to_queue_next(
 #[tauri::command]
 pub fn add_tracks_to_queue(
     tracks: Vec<QueueTrack>,
+    app: AppHandle,
     state: State<'_, AppState>,
     blacklist: State<'_, BlacklistState>,
 ) -> Result<(), String> {
     let filtered: Vec<QueueTrack> = tracks
         .into_iter()
-        .filter(|t| !is_track_blacklisted(t, &blacklist))
+        .filter(|t| !blacklist.is_queue_track_blacklisted(t))
         .collect();
     log::info!("Command: add_tracks_to_queue - {} tracks (after blacklist filter)", filtered.len());
     state.queue.add_tracks(filtered);
+    api_server::broadcast_queue_update(&app);
     Ok(())
 }
 
@@ -68,38 +72,145 @@ pub fn add_tracks_to_queue(
 pub fn set_queue(
     tracks: Vec<QueueTrack>,
     start_index: Option<usize>,
+    app: AppHandle,
     state: State<'_, AppState>,
     blacklist: State<'_, BlacklistState>,
 ) -> Result<(), String> {
     let filtered: Vec<QueueTrack> = tracks
         .into_iter()
-        .filter(|t| !is_track_blacklisted(t, &blacklist))
+        .filter(|t| !blacklist.is_queue_track_blacklisted(t))
         .collect();
     log::info!("Command: set_queue - {} tracks (after blacklist filter), start at {:?}", filtered.len(), start_index);
     state.queue.set_queue(filtered, start_index);
+    api_server::broadcast_queue_update(&app);
     Ok(())
 }
 
+/// Where a collection played with `play_collection` comes from
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionContext {
+    pub context_type: String,
+    pub id: String,
+    pub label: String,
+    pub source: String,
+}
+
+/// Replace the queue with a collection (album, playlist, ...) and play it.
+///
+/// When `shuffle` is set the tracks are shuffled once before being queued;
+/// the global shuffle mode is left as it is, so the next collection plays
+/// in order unless shuffle is toggled on. Returns the track to start with.
+#[tauri::command]
+pub fn play_collection(
+    tracks: Vec<QueueTrack>,
+    shuffle: bool,
+    start_index: Option<usize>,
+    context: Option<CollectionContext>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    blacklist: State<'_, BlacklistState>,
+) -> Result<Option<QueueTrack>, String> {
+    let mut filtered: Vec<QueueTrack> = tracks
+        .into_iter()
+        .filter(|t| !blacklist.is_queue_track_blacklisted(t))
+        .collect();
+
+    // A shuffled collection always starts from the top of the new order
+    let start_index = if shuffle {
+        shuffle_once(&mut filtered);
+        0
+    } else {
+        start_index
+            .unwrap_or(0)
+            .min(filtered.len().saturating_sub(1))
+    };
+
+    log::info!(
+        "Command: play_collection - {} tracks (after blacklist filter), shuffle: {}, start at {}",
+        filtered.len(),
+        shuffle,
+        start_index
+    );
+
+    if let Some(ctx) = context {
+        let track_ids: Vec<u64> = filtered.iter().map(|t| t.id).collect();
+        let context = PlaybackContext::new(
+            parse_context_type(&ctx.context_type)?,
+            ctx.id,
+            ctx.label,
+            parse_content_source(&ctx.source)?,
+            track_ids,
+            start_index,
+        );
+        // Album gapless boundaries only make sense in album order
+        state
+            .player
+            .state
+            .set_album_context(!shuffle && context.context_type == ContextType::Album);
+        state.context.set_context(context);
+    }
+
+    let first = filtered.get(start_index).cloned();
+    let start = first.as_ref().map(|_| start_index);
+    state.queue.set_queue(filtered, start);
+    api_server::broadcast_queue_update(&app);
+    Ok(first)
+}
+
 /// Clear the queue
 #[tauri::command]
-pub fn clear_queue(state: State<'_, AppState>) -> Result<(), String> {
+pub fn clear_queue(state: State<'_, AppState>, app: AppHandle) -> Result<(), String> {
     log::info!("Command: clear_queue");
     state.queue.clear();
+    api_server::broadcast_queue_update(&app);
     Ok(())
 }
 
 /// Remove a track from the queue by index
 #[tauri::command]
-pub fn remove_from_queue(index: usize, state: State<'_, AppState>) -> Result<Option<QueueTrack>, String> {
+pub fn remove_from_queue(index: usize, state: State<'_, AppState>, app: AppHandle) -> Result<Option<QueueTrack>, String> {
     log::info!("Command: remove_from_queue - index {}", index);
-    Ok(state.queue.remove_track(index))
+    let result = state.queue.remove_track(index);
+    api_server::broadcast_queue_update(&app);
+    Ok(result)
 }
 
 /// Move a track from one position to another in the queue
 #[tauri::command]
-pub fn move_queue_track(from_index: usize, to_index: usize, state: State<'_, AppState>) -> Result<bool, String> {
+pub fn move_queue_track(from_index: usize, to_index: usize, state: State<'_, AppState>, app: AppHandle) -> Result<bool, String> {
     log::info!("Command: move_queue_track - from {} to {}", from_index, to_index);
-    Ok(state.queue.move_track(from_index, to_index))
+    let result = state.queue.move_track(from_index, to_index);
+    api_server::broadcast_queue_update(&app);
+    Ok(result)
+}
+
+/// Move several tracks as one block (multi-select drag and drop)
+#[tauri::command]
+pub fn move_queue_tracks(indices: Vec<usize>, to_index: usize, state: State<'_, AppState>, app: AppHandle) -> Result<bool, String> {
+    log::info!("Command: move_queue_tracks - {:?} to {}", indices, to_index);
+    let result = state.queue.move_tracks(indices, to_index);
+    api_server::broadcast_queue_update(&app);
+    Ok(result)
+}
+
+/// Insert tracks at a position in the queue
+#[tauri::command]
+pub fn insert_tracks_in_queue(
+    index: usize,
+    tracks: Vec<QueueTrack>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    blacklist: State<'_, BlacklistState>,
+) -> Result<(), String> {
+    let filtered: Vec<QueueTrack> = tracks
+        .into_iter()
+        .filter(|t| !blacklist.is_queue_track_blacklisted(t))
+        .collect();
+    log::info!("Command: insert_tracks_in_queue - {} tracks at {} (after blacklist filter)", filtered.len(), index);
+    state.queue.insert_tracks_at(index, filtered);
+    api_server::broadcast_queue_update(&app);
+    Ok(())
 }
 
 /// Get current track in queue
@@ -114,18 +225,48 @@ pub fn peek_next_track(state: State<'_, AppState>) -> Result<Option<QueueTrack>,
     Ok(state.queue.peek_next())
 }
 
-/// Advance to next track and return it
+/// Advance to next track and return it, skipping tracks that can't be streamed
 #[tauri::command]
-pub fn next_track(state: State<'_, AppState>) -> Result<Option<QueueTrack>, String> {
+pub fn next_track(app: AppHandle, state: State<'_, AppState>) -> Result<Option<QueueTrack>, String> {
     log::info!("Command: next_track");
-    Ok(state.queue.next())
+    let advance = state.queue.advance();
+    emit_skipped(&app, &advance.skipped);
+    super::radio::schedule_radio_top_up(&app);
+    Ok(advance.track)
+}
+
+/// Emit `playback:skipped` for each track the queue passed over
+pub(crate) fn emit_skipped(app: &AppHandle, skipped: &[SkippedTrack]) {
+    for track in skipped {
+        log::info!("Skipping track {} ({:?})", track.track_id, track.reason);
+        let _ = app.emit("playback:skipped", track);
+    }
+}
+
+/// Restart the current track if it has played past the user's threshold,
+/// otherwise step back in the queue. Returns the track to play, or None when
+/// the current track was restarted or there is nothing before it.
+pub(crate) async fn previous_or_restart(app: &AppHandle) -> Result<Option<QueueTrack>, String> {
+    let state = app.state::<AppState>();
+    let threshold = app
+        .state::<PlaybackPreferencesState>()
+        .get_preferences()
+        .map(|p| p.previous_restart_threshold_secs)
+        .unwrap_or(DEFAULT_PREVIOUS_RESTART_THRESHOLD_SECS);
+
+    if threshold > 0 && state.player.state.current_position() > u64::from(threshold) {
+        log::info!("Previous: restarting current track");
+        crate::commands::playback::seek(0, app.clone(), state.clone()).await?;
+        return Ok(None);
+    }
+    Ok(state.queue.previous())
 }
 
-/// Go to previous track and return it
+/// Go to previous track and return it (None if the current track restarted)
 #[tauri::command]
-pub fn previous_track(state: State<'_, AppState>) -> Result<Option<QueueTrack>, String> {
+pub async fn previous_track(app: AppHandle) -> Result<Option<QueueTrack>, String> {
     log::info!("Command: previous_track");
-    Ok(state.queue.previous())
+    previous_or_restart(&app).await
 }
 
 /// Jump to a specific track by index
@@ -137,9 +278,10 @@ pub fn play_queue_index(index: usize, state: State<'_, AppState>) -> Result<Opti
 
 /// Set shuffle mode
 #[tauri::command]
-pub fn set_shuffle(enabled: bool, state: State<'_, AppState>) -> Result<(), String> {
+pub fn set_shuffle(enabled: bool, state: State<'_, AppState>, app: AppHandle) -> Result<(), String> {
     log::info!("Command: set_shuffle - {}", enabled);
     state.queue.set_shuffle(enabled);
+    api_server::broadcast_queue_update(&app);
     Ok(())
 }
 
@@ -151,7 +293,7 @@ pub fn get_shuffle(state: State<'_, AppState>) -> Result<bool, String> {
 
 /// Set repeat mode
 #[tauri::command]
-pub fn set_repeat(mode: String, state: State<'_, AppState>) -> Result<(), String> {
+pub fn set_repeat(mode: String, state: State<'_, AppState>, app: AppHandle) -> Result<(), String> {
     log::info!("Command: set_repeat - {}", mode);
     let repeat_mode = match mode.to_lowercase().as_str() {
         "all" => RepeatMode::All,
@@ -159,6 +301,7 @@ pub fn set_repeat(mode: String, state: State<'_, AppState>) -> Result<(), String
         _ => RepeatMode::Off,
     };
     state.queue.set_repeat(repeat_mode);
+    api_server::broadcast_queue_update(&app);
     Ok(())
 }
 
@@ -178,3 +321,122 @@ pub fn get_repeat(state: State<'_, AppState>) -> Result<String, String> {
 pub fn get_queue_state(state: State<'_, AppState>) -> Result<QueueState, String> {
     Ok(state.queue.get_state())
 }
+
+/// Get a slice of the queue around the current track (for virtualized lists).
+/// Prefer this over `get_queue_state` for large queues such as radio.
+#[tauri::command]
+pub fn get_queue_window(
+    center_on_current: bool,
+    before: usize,
+    after: usize,
+    state: State<'_, AppState>,
+) -> Result<QueueWindow, String> {
+    Ok(state.queue.get_window(center_on_current, before, after))
+}
+
+/// Get total, elapsed and remaining time of the queue (seconds)
+#[tauri::command]
+pub fn get_queue_timing(state: State<'_, AppState>) -> Result<QueueTiming, String> {
+    let position = state.player.state.current_position();
+    Ok(state.queue.timing(position))
+}
+
+/// Get the current queue index ("jump to now playing")
+#[tauri::command]
+pub fn get_current_queue_index(state: State<'_, AppState>) -> Result<Option<usize>, String> {
+    Ok(state.queue.current_index())
+}
+
+/// What the queue did after its last track finished (also emitted as `queue:ended`)
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueEndOutcome {
+    /// The behavior that fired; `stop` when radio could not be started
+    pub behavior: EndOfQueueBehavior,
+    /// Track to play next (None = stop)
+    pub track: Option<QueueTrack>,
+    /// Radio session id when a radio was started
+    pub radio_session_id: Option<String>,
+    /// Label of the album/playlist/artist context the radio continues
+    pub continued_from: Option<String>,
+}
+
+/// Apply the end-of-queue preference once the last track has finished
+#[tauri::command]
+pub async fn handle_queue_end(
+    finished_track_id: Option<u64>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    blacklist_state: State<'_, BlacklistState>,
+    reco_state: State<'_, RecoState>,
+    playback_prefs: State<'_, PlaybackPreferencesState>,
+) -> Result<QueueEndOutcome, String> {
+    let prefs = playback_prefs.get_preferences().unwrap_or_default();
+    let behavior = prefs.end_of_queue;
+    log::info!("Command: handle_queue_end - {:?}", behavior);
+
+    // An exhausted album/playlist/artist context can roll into radio seeded
+    // from its last track (unless the queue is set to loop)
+    if let Some(track_id) = finished_track_id {
+        state.context.set_position(track_id);
+    }
+    let continued_from = state.context.get_context().filter(|ctx| {
+        behavior != EndOfQueueBehavior::LoopQueue
+            && ctx.continues_into_radio(prefs.autoplay_mode, prefs.radio_after_source)
+    });
+    let behavior = if continued_from.is_some() {
+        EndOfQueueBehavior::StartRadio
+    } else {
+        behavior
+    };
+
+    let outcome = match behavior {
+        EndOfQueueBehavior::Stop => None,
+        EndOfQueueBehavior::LoopQueue => state.queue.restart().map(|track| QueueEndOutcome {
+            behavior,
+            track: Some(track),
+            radio_session_id: None,
+            continued_from: None,
+        }),
+        EndOfQueueBehavior::StartRadio => match finished_track_id {
+            Some(track_id) => match super::radio::create_infinite_radio(
+                vec![track_id],
+                state.clone(),
+                blacklist_state,
+                reco_state,
+                playback_prefs,
+            )
+            .await
+            {
+                Ok(session_id) => {
+                    if let Some(source) = &continued_from {
+                        log::info!("[Queue] {} ended, continuing with radio", source.label);
+                        state.context.set_label(source.radio_continuation_label());
+                    }
+                    Some(QueueEndOutcome {
+                        behavior,
+                        track: state.queue.current_track(),
+                        radio_session_id: Some(session_id),
+                        continued_from: continued_from.map(|source| source.label),
+                    })
+                }
+                Err(e) => {
+                    log::warn!("[Queue] Could not start radio at end of queue: {}", e);
+                    None
+                }
+            },
+            None => None,
+        },
+    };
+
+    let outcome = outcome.unwrap_or(QueueEndOutcome {
+        behavior: EndOfQueueBehavior::Stop,
+        track: None,
+        radio_session_id: None,
+        continued_from: None,
+    });
+    if outcome.track.is_some() {
+        api_server::broadcast_queue_update(&app);
+    }
+    let _ = app.emit("queue:ended", &outcome);
+    Ok(outcome)
+}
@@ -2,16 +2,107 @@
 //!
 //! Creates and manages radio sessions for infinite playback discovery
 
-use tauri::State;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager, State};
 use tokio::task;
 
-use crate::api::Track;
+use crate::api::{QobuzClient, Track};
 use crate::artist_blacklist::BlacklistState;
+use crate::config::playback_preferences::{
+    PlaybackPreferencesState, DEFAULT_RADIO_FRESHNESS_DAYS, DEFAULT_RADIO_QUEUE_AHEAD,
+};
 use crate::playback_context::{ContentSource, ContextType, PlaybackContext};
 use crate::queue::QueueTrack;
 use crate::radio_engine::{BuildRadioOptions, RadioEngine, RadioPoolBuilder};
+use crate::reco_store::RecoState;
 use crate::AppState;
 
+/// Deprioritize tracks played within the radio freshness window (in any radio
+/// session or elsewhere, per the play history) in a new session's pool, so
+/// radio doesn't replay the same tracks every session
+async fn apply_radio_freshness(
+    session_id: &str,
+    reco_state: &RecoState,
+    playback_prefs: &PlaybackPreferencesState,
+) {
+    let days = playback_prefs
+        .get_preferences()
+        .map(|p| p.radio_freshness_days)
+        .unwrap_or(DEFAULT_RADIO_FRESHNESS_DAYS);
+    if days == 0 {
+        return;
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let since_ts = now - i64::from(days) * 24 * 60 * 60;
+
+    let played_track_ids = {
+        let guard = reco_state.db.lock().await;
+        match guard.as_ref().map(|db| db.get_played_track_ids_since(since_ts)) {
+            Some(Ok(ids)) => ids,
+            Some(Err(e)) => {
+                log::warn!("[Radio] Failed to read play history: {}", e);
+                Vec::new()
+            }
+            None => Vec::new(),
+        }
+    };
+
+    let session_id = session_id.to_string();
+    let result = task::spawn_blocking(move || -> Result<u32, String> {
+        let radio_db = crate::radio_engine::db::RadioDb::open_default()?;
+        radio_db.mark_stale_tracks(&session_id, since_ts, &played_track_ids)
+    })
+    .await
+    .map_err(|e| format!("Radio freshness task failed: {}", e))
+    .and_then(|r| r);
+
+    match result {
+        Ok(stale) => log::info!(
+            "[Radio] {} recently played tracks deprioritized (last {} days)",
+            stale,
+            days
+        ),
+        Err(e) => log::warn!("[Radio] Failed to apply radio freshness: {}", e),
+    }
+}
+
+/// Fetch full track details from Qobuz, dropping tracks that involve a
+/// blacklisted artist (performer, album artist or performing credit)
+async fn fetch_radio_tracks(
+    client: &QobuzClient,
+    track_ids: impl IntoIterator<Item = u64>,
+    blacklist_state: &BlacklistState,
+) -> Vec<Track> {
+    let mut tracks = Vec::new();
+    let mut blacklist_skipped = 0;
+    for track_id in track_ids {
+        match client.get_track(track_id).await {
+            Ok(track) => {
+                if blacklist_state.is_track_blacklisted(&track) {
+                    log::debug!("[Radio] Skipping blacklisted artist track: {}", track.title);
+                    blacklist_skipped += 1;
+                    continue;
+                }
+                tracks.push(track);
+            }
+            Err(e) => {
+                log::warn!("[Radio] Failed to fetch track {}: {}", track_id, e);
+            }
+        }
+    }
+
+    if blacklist_skipped > 0 {
+        log::info!("[Radio] Skipped {} tracks from blacklisted artists", blacklist_skipped);
+    }
+
+    tracks
+}
+
 /// Create an artist radio session
 ///
 /// This creates a new radio session based on an artist and populates the queue
@@ -22,6 +113,8 @@ pub async fn create_artist_radio(
     artist_name: String,
     state: State<'_, AppState>,
     blacklist_state: State<'_, BlacklistState>,
+    reco_state: State<'_, RecoState>,
+    playback_prefs: State<'_, PlaybackPreferencesState>,
 ) -> Result<String, String> {
     log::info!("[Radio] Creating artist radio for: {} (ID: {})", artist_name, artist_id);
 
@@ -42,6 +135,7 @@ pub async fn create_artist_radio(
     .map_err(|e| format!("Radio task failed: {}", e))??;
 
     log::info!("[Radio] Artist radio session created: {}", session_id);
+    apply_radio_freshness(&session_id, &reco_state, &playback_prefs).await;
 
     // Get client again for fetching tracks
     let client = state.client.read().await;
@@ -49,9 +143,10 @@ pub async fn create_artist_radio(
     // Generate track IDs from radio engine
     let track_ids = task::spawn_blocking({
         let session_id = session_id.clone();
+        let excluded_artists = blacklist_state.blacklisted_ids();
         move || -> Result<Vec<u64>, String> {
             let radio_db = crate::radio_engine::db::RadioDb::open_default()?;
-            let radio_engine = RadioEngine::new(radio_db);
+            let radio_engine = RadioEngine::new(radio_db).with_excluded_artists(excluded_artists);
 
             let mut tracks_with_distance = Vec::new();
 
@@ -86,34 +181,7 @@ pub async fn create_artist_radio(
     .map_err(|e| format!("Track generation task failed: {}", e))??;
 
     // Fetch full track details from Qobuz, filtering blacklisted artists
-    let mut tracks = Vec::new();
-    let mut blacklist_skipped = 0;
-    for track_id in track_ids {
-        match client.get_track(track_id).await {
-            Ok(track) => {
-                // Check if track's artist is blacklisted
-                if let Some(ref performer) = track.performer {
-                    if blacklist_state.is_blacklisted(performer.id) {
-                        log::debug!(
-                            "[Radio] Skipping blacklisted artist track: {} - {}",
-                            performer.name,
-                            track.title
-                        );
-                        blacklist_skipped += 1;
-                        continue;
-                    }
-                }
-                tracks.push(track);
-            }
-            Err(e) => {
-                log::warn!("[Radio] Failed to fetch track {}: {}", track_id, e);
-            }
-        }
-    }
-
-    if blacklist_skipped > 0 {
-        log::info!("[Radio] Skipped {} tracks from blacklisted artists", blacklist_skipped);
-    }
+    let tracks = fetch_radio_tracks(&client, track_ids, &blacklist_state).await;
 
     if tracks.is_empty() {
         return Err("Failed to generate any radio tracks".to_string());
@@ -137,6 +205,7 @@ pub async fn create_artist_radio(
         track_ids,
         0,
     );
+    state.player.state.set_album_context(false);
     state.context.set_context(context);
 
     log::info!("[Radio] Artist radio ready: {}", session_id);
@@ -155,6 +224,8 @@ pub async fn create_track_radio(
     artist_id: u64,
     state: State<'_, AppState>,
     blacklist_state: State<'_, BlacklistState>,
+    reco_state: State<'_, RecoState>,
+    playback_prefs: State<'_, PlaybackPreferencesState>,
 ) -> Result<String, String> {
     log::info!(
         "[Radio] Creating track radio for: {} (Track ID: {}, Artist ID: {})",
@@ -180,6 +251,7 @@ pub async fn create_track_radio(
     .map_err(|e| format!("Radio task failed: {}", e))??;
 
     log::info!("[Radio] Track radio session created: {}", session_id);
+    apply_radio_freshness(&session_id, &reco_state, &playback_prefs).await;
 
     // Get client again for fetching tracks
     let client = state.client.read().await;
@@ -187,10 +259,11 @@ pub async fn create_track_radio(
     // Generate track IDs from radio engine
     let track_ids = task::spawn_blocking({
         let session_id = session_id.clone();
+        let excluded_artists = blacklist_state.blacklisted_ids();
         let seed_track_id = track_id;
         move || -> Result<Vec<u64>, String> {
             let radio_db = crate::radio_engine::db::RadioDb::open_default()?;
-            let radio_engine = RadioEngine::new(radio_db);
+            let radio_engine = RadioEngine::new(radio_db).with_excluded_artists(excluded_artists);
 
             let mut tracks_with_source = Vec::new();
 
@@ -225,34 +298,7 @@ pub async fn create_track_radio(
     .map_err(|e| format!("Track generation task failed: {}", e))??;
 
     // Fetch full track details from Qobuz, filtering blacklisted artists
-    let mut tracks = Vec::new();
-    let mut blacklist_skipped = 0;
-    for track_id in track_ids {
-        match client.get_track(track_id).await {
-            Ok(track) => {
-                // Check if track's artist is blacklisted
-                if let Some(ref performer) = track.performer {
-                    if blacklist_state.is_blacklisted(performer.id) {
-                        log::debug!(
-                            "[Radio] Skipping blacklisted artist track: {} - {}",
-                            performer.name,
-                            track.title
-                        );
-                        blacklist_skipped += 1;
-                        continue;
-                    }
-                }
-                tracks.push(track);
-            }
-            Err(e) => {
-                log::warn!("[Radio] Failed to fetch track {}: {}", track_id, e);
-            }
-        }
-    }
-
-    if blacklist_skipped > 0 {
-        log::info!("[Radio] Skipped {} tracks from blacklisted artists", blacklist_skipped);
-    }
+    let tracks = fetch_radio_tracks(&client, track_ids, &blacklist_state).await;
 
     if tracks.is_empty() {
         return Err("Failed to generate any radio tracks".to_string());
@@ -276,6 +322,7 @@ pub async fn create_track_radio(
         track_ids,
         0,
     );
+    state.player.state.set_album_context(false);
     state.context.set_context(context);
 
     log::info!("[Radio] Track radio ready: {}", session_id);
@@ -320,7 +367,7 @@ fn track_to_queue_track(track: &Track) -> QueueTrack {
         album,
         duration_secs: track.duration as u64,
         artwork_url,
-        hires: track.hires,
+        hires: track.quality_info().hires,
         bit_depth: track.maximum_bit_depth,
         sample_rate: track.maximum_sampling_rate,
         is_local: false,
@@ -328,32 +375,75 @@ fn track_to_queue_track(track: &Track) -> QueueTrack {
         artist_id,
         streamable: track.streamable,
         source: Some("qobuz".to_string()),
+        track_number: Some(track.track_number),
+        disc_number: track.media_number,
+        contributor: None,
     }
 }
 
+/// Played radio tracks kept in the queue behind the current one (matches the
+/// queue's history ring, so "previous" still has somewhere to go)
+pub const RADIO_HISTORY_WINDOW: usize = 50;
+
+/// Tracks missing from the radio queue before a background top-up runs, so
+/// Qobuz isn't asked for one track at a time
+const RADIO_TOP_UP_BATCH: usize = 10;
+
+/// Set while a background top-up is in flight
+static RADIO_TOP_UP_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn radio_queue_ahead(playback_prefs: &PlaybackPreferencesState) -> usize {
+    playback_prefs
+        .get_preferences()
+        .map(|p| p.radio_queue_ahead)
+        .unwrap_or(DEFAULT_RADIO_QUEUE_AHEAD) as usize
+}
+
 /// Refill the radio queue with more tracks
 ///
-/// Called when the queue is running low (e.g., < 10 tracks remaining).
-/// Generates 20 more tracks and appends them to the queue.
+/// Drops played tracks beyond the history window, then tops the upcoming
+/// tracks back up to the radio queue size setting.
 #[tauri::command]
 pub async fn refill_radio_queue(
     session_id: String,
     state: State<'_, AppState>,
     blacklist_state: State<'_, BlacklistState>,
+    playback_prefs: State<'_, PlaybackPreferencesState>,
+) -> Result<u32, String> {
+    let queue_ahead = radio_queue_ahead(&playback_prefs);
+    refill_radio(&state, &blacklist_state, &session_id, queue_ahead).await
+}
+
+async fn refill_radio(
+    state: &AppState,
+    blacklist_state: &BlacklistState,
+    session_id: &str,
+    queue_ahead: usize,
 ) -> Result<u32, String> {
-    log::info!("[Radio] Refilling queue for session: {}", session_id);
+    let trimmed = state.queue.trim_played(RADIO_HISTORY_WINDOW);
+    if trimmed > 0 {
+        log::info!("[Radio] Dropped {} played tracks from the queue", trimmed);
+    }
+
+    let wanted = queue_ahead.saturating_sub(state.queue.upcoming_count());
+    if wanted == 0 {
+        log::debug!("[Radio] Queue already holds {} upcoming tracks", queue_ahead);
+        return Ok(0);
+    }
+    log::info!("[Radio] Refilling queue for session {}: {} tracks", session_id, wanted);
 
     // Generate more track IDs from radio engine
     let track_ids = task::spawn_blocking({
-        let session_id = session_id.clone();
+        let session_id = session_id.to_string();
+        let excluded_artists = blacklist_state.blacklisted_ids();
         move || -> Result<Vec<u64>, String> {
             let radio_db = crate::radio_engine::db::RadioDb::open_default()?;
-            let radio_engine = RadioEngine::new(radio_db);
+            let radio_engine = RadioEngine::new(radio_db).with_excluded_artists(excluded_artists);
 
             let mut track_ids = Vec::new();
 
-            // Generate 25 tracks to ensure we get ~20 after API failures
-            for _ in 0..25 {
+            // Generate a few extra to make up for API failures
+            for _ in 0..wanted + 5 {
                 match radio_engine.next_track(&session_id) {
                     Ok(radio_track) => {
                         track_ids.push(radio_track.track_id);
@@ -377,35 +467,8 @@ pub async fn refill_radio_queue(
     }
 
     // Fetch full track details from Qobuz, filtering blacklisted artists
-    let client = state.client.read().await;
-    let mut tracks = Vec::new();
-    let mut blacklist_skipped = 0;
-    for track_id in track_ids.iter().take(20) {
-        match client.get_track(*track_id).await {
-            Ok(track) => {
-                // Check if track's artist is blacklisted
-                if let Some(ref performer) = track.performer {
-                    if blacklist_state.is_blacklisted(performer.id) {
-                        log::debug!(
-                            "[Radio] Skipping blacklisted artist track during refill: {} - {}",
-                            performer.name,
-                            track.title
-                        );
-                        blacklist_skipped += 1;
-                        continue;
-                    }
-                }
-                tracks.push(track);
-            }
-            Err(e) => {
-                log::warn!("[Radio] Failed to fetch track {} during refill: {}", track_id, e);
-            }
-        }
-    }
-
-    if blacklist_skipped > 0 {
-        log::info!("[Radio] Refill skipped {} tracks from blacklisted artists", blacklist_skipped);
-    }
+    let client = state.client.read().await.clone();
+    let tracks = fetch_radio_tracks(&client, track_ids.into_iter().take(wanted), blacklist_state).await;
 
     let added_count = tracks.len() as u32;
 
@@ -414,14 +477,9 @@ pub async fn refill_radio_queue(
         return Ok(0);
     }
 
-    log::info!("[Radio] Fetched {} tracks for refill", added_count);
-
     // Convert to QueueTrack format and add to queue
     let queue_tracks: Vec<QueueTrack> = tracks.iter().map(track_to_queue_track).collect();
-
-    for track in queue_tracks {
-        state.queue.add_track(track);
-    }
+    state.queue.add_tracks(queue_tracks);
 
     // Update playback context with new track IDs
     let new_track_ids: Vec<u64> = tracks.iter().map(|t| t.id).collect();
@@ -432,11 +490,42 @@ pub async fn refill_radio_queue(
     Ok(added_count)
 }
 
-/// Get the current queue size (for checking if refill is needed)
+/// Keep a playing radio topped up; called after the queue advances
+///
+/// Runs in the background and does nothing outside a radio context or while
+/// another top-up is still running.
+pub(crate) fn schedule_radio_top_up(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let session_id = match state.context.get_context() {
+        Some(context) if context.context_type == ContextType::Radio => context.id,
+        _ => return,
+    };
+
+    let queue_ahead = radio_queue_ahead(&app.state::<PlaybackPreferencesState>());
+    if queue_ahead.saturating_sub(state.queue.upcoming_count()) < RADIO_TOP_UP_BATCH {
+        return;
+    }
+    if RADIO_TOP_UP_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let blacklist_state = app.state::<BlacklistState>();
+        match refill_radio(&state, &blacklist_state, &session_id, queue_ahead).await {
+            Ok(added) if added > 0 => crate::api_server::broadcast_queue_update(&app),
+            Ok(_) => {}
+            Err(e) => log::warn!("[Radio] Background top-up failed: {}", e),
+        }
+        RADIO_TOP_UP_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Number of upcoming tracks in the queue (for checking if refill is needed)
 #[tauri::command]
 pub fn get_queue_remaining(state: State<'_, AppState>) -> u32 {
-    let queue_state = state.queue.get_state();
-    queue_state.upcoming.len() as u32
+    state.queue.upcoming_count() as u32
 }
 
 /// Create an infinite radio session based on recent tracks
@@ -448,6 +537,8 @@ pub async fn create_infinite_radio(
     recent_track_ids: Vec<u64>,
     state: State<'_, AppState>,
     blacklist_state: State<'_, BlacklistState>,
+    reco_state: State<'_, RecoState>,
+    playback_prefs: State<'_, PlaybackPreferencesState>,
 ) -> Result<String, String> {
     if recent_track_ids.is_empty() {
         return Err("No recent tracks provided for infinite radio".to_string());
@@ -496,18 +587,21 @@ pub async fn create_infinite_radio(
     .map_err(|e| format!("Infinite radio task failed: {}", e))??;
 
     log::info!("[Radio] Infinite radio session created: {}", session_id);
+    apply_radio_freshness(&session_id, &reco_state, &playback_prefs).await;
+    let queue_ahead = radio_queue_ahead(&playback_prefs);
 
     // Generate initial tracks
     let track_ids = task::spawn_blocking({
         let session_id = session_id.clone();
+        let excluded_artists = blacklist_state.blacklisted_ids();
         move || -> Result<Vec<u64>, String> {
             let radio_db = crate::radio_engine::db::RadioDb::open_default()?;
-            let radio_engine = RadioEngine::new(radio_db);
+            let radio_engine = RadioEngine::new(radio_db).with_excluded_artists(excluded_artists);
 
             let mut track_ids = Vec::new();
 
-            // Generate 60 tracks for initial queue
-            for _ in 0..60 {
+            // Generate extra tracks to make up for API failures
+            for _ in 0..queue_ahead + 10 {
                 match radio_engine.next_track(&session_id) {
                     Ok(radio_track) => {
                         track_ids.push(radio_track.track_id);
@@ -519,41 +613,16 @@ pub async fn create_infinite_radio(
                 }
             }
 
-            Ok(track_ids.into_iter().take(50).collect())
+            Ok(track_ids)
         }
     })
     .await
     .map_err(|e| format!("Track generation task failed: {}", e))??;
 
     // Fetch full track details from Qobuz, filtering blacklisted artists
-    let mut tracks = Vec::new();
-    let mut blacklist_skipped = 0;
-    for track_id in track_ids {
-        match client.get_track(track_id).await {
-            Ok(track) => {
-                // Check if track's artist is blacklisted
-                if let Some(ref performer) = track.performer {
-                    if blacklist_state.is_blacklisted(performer.id) {
-                        log::debug!(
-                            "[Radio] Skipping blacklisted artist track in infinite radio: {} - {}",
-                            performer.name,
-                            track.title
-                        );
-                        blacklist_skipped += 1;
-                        continue;
-                    }
-                }
-                tracks.push(track);
-            }
-            Err(e) => {
-                log::warn!("[Radio] Failed to fetch track {}: {}", track_id, e);
-            }
-        }
-    }
-
-    if blacklist_skipped > 0 {
-        log::info!("[Radio] Infinite radio skipped {} tracks from blacklisted artists", blacklist_skipped);
-    }
+    // (the current track plus the upcoming ones)
+    let mut tracks = fetch_radio_tracks(&client, track_ids, &blacklist_state).await;
+    tracks.truncate(queue_ahead + 1);
 
     if tracks.is_empty() {
         return Err("Failed to generate any infinite radio tracks".to_string());
@@ -577,6 +646,7 @@ pub async fn create_infinite_radio(
         track_ids,
         0,
     );
+    state.player.state.set_album_context(false);
     state.context.set_context(context);
 
     log::info!("[Radio] Infinite radio ready: {}", session_id);
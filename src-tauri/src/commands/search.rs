@@ -3,11 +3,24 @@
 use tauri::State;
 
 use crate::api::{endpoints, endpoints::paths, Album, Artist, ArtistAlbums, DiscoverAlbum, DiscoverData, DiscoverResponse, DiscoverPlaylistsResponse, LabelDetail, PageArtistResponse, Playlist, PlaylistTag, ReleasesGridResponse, SearchResultsPage, Track, TracksContainer};
+use crate::api::classical::apply_classical_fields;
+use crate::api::search_filters::SearchFilters;
 use crate::api_cache::ApiCacheState;
 use crate::artist_blacklist::BlacklistState;
+use crate::commands::musicbrainz::fetch_release_credits;
+use crate::lyrics::normalize;
+use crate::musicbrainz::credits::MbWork;
+use crate::musicbrainz::MusicBrainzSharedState;
 use crate::AppState;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Raw pages scanned at most per filtered search request, so a narrow filter
+/// can't turn one request into dozens of API calls
+const MAX_FILTERED_SCAN_PAGES: u32 = 5;
 
 /// Most popular item from catalog search - can be a track, album, or artist
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,71 +40,234 @@ pub struct SearchAllResults {
     pub most_popular: Option<MostPopularItem>,
 }
 
+/// A page of album/track search results after blacklist and search filters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilteredSearchPage<T> {
+    #[serde(flatten)]
+    pub page: SearchResultsPage<T>,
+    /// Filters applied to this page, for showing active filter chips
+    pub applied_filters: Option<SearchFilters>,
+    /// Qobuz offset to request the next page from, or None at the end.
+    /// With filters active this runs ahead of `offset + items.len()`.
+    pub next_offset: Option<u32>,
+}
+
+/// How track results are reordered among versions of the same recording
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityRanking {
+    /// Keep Qobuz's order
+    #[default]
+    Off,
+    /// Move the best version of each track to where its first version was
+    PreferBest,
+    /// Keep only the best version of each track
+    CollapseDuplicates,
+}
+
+/// Sort key for a track version, higher is better: streamable first, then
+/// hi-res, lossless, bit depth and sample rate
+fn quality_rank(track: &Track) -> (bool, bool, bool, u32, u32) {
+    let quality = track.quality_info();
+    (
+        track.streamable,
+        quality.hires && track.hires_streamable,
+        quality.lossless,
+        quality.bit_depth.unwrap_or(0),
+        quality.sample_rate.unwrap_or(0.0) as u32,
+    )
+}
+
+/// Reorder near-duplicate tracks (same normalized title and performer) so the
+/// highest-quality version comes first, optionally dropping the others.
+/// Only duplicates within `tracks` are considered, not across pages.
+/// Returns the number of tracks dropped.
+pub fn rank_tracks_by_quality(tracks: &mut Vec<Track>, ranking: QualityRanking) -> usize {
+    if ranking == QualityRanking::Off {
+        return 0;
+    }
+
+    let mut groups: Vec<Vec<Track>> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    for track in tracks.drain(..) {
+        let performer = track.performer.as_ref().map(|p| p.name.as_str());
+        let key = format!(
+            "{}::{}",
+            normalize(performer.unwrap_or_default()),
+            normalize(&track.title)
+        );
+        match group_index.get(&key) {
+            Some(&index) => groups[index].push(track),
+            None => {
+                group_index.insert(key, groups.len());
+                groups.push(vec![track]);
+            }
+        }
+    }
+
+    let mut dropped = 0;
+    for mut group in groups {
+        // Stable, so equal versions keep Qobuz's order
+        group.sort_by_key(|track| Reverse(quality_rank(track)));
+        if ranking == QualityRanking::CollapseDuplicates {
+            dropped += group.len() - 1;
+            group.truncate(1);
+        }
+        tracks.extend(group);
+    }
+    dropped
+}
+
+/// Fetch up to `limit` results starting at the raw Qobuz `offset`, keeping
+/// only items that pass `keep`.
+///
+/// Without search filters a single page is fetched, as before. With filters,
+/// further pages are scanned until the page is full, the results run out or
+/// `MAX_FILTERED_SCAN_PAGES` is reached; `next_offset` tells the caller where
+/// to resume, so paging never skips or repeats an item.
+async fn search_filtered<T, E, F, Fut>(
+    offset: u32,
+    limit: u32,
+    filters: Option<SearchFilters>,
+    mut fetch: F,
+    keep: impl Fn(&T) -> bool,
+) -> Result<FilteredSearchPage<T>, String>
+where
+    E: std::fmt::Display,
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<SearchResultsPage<T>, E>>,
+{
+    let mut items = Vec::new();
+    let mut raw_offset = offset;
+    let mut total;
+    let mut dropped = 0u32;
+    let mut pages = 0;
+
+    loop {
+        let page = fetch(raw_offset).await.map_err(|e| e.to_string())?;
+        pages += 1;
+        total = page.total;
+        let fetched = page.items.len();
+
+        for item in page.items {
+            if items.len() as u32 >= limit {
+                break;
+            }
+            raw_offset += 1;
+            if keep(&item) {
+                items.push(item);
+            } else {
+                dropped += 1;
+            }
+        }
+
+        let exhausted = fetched == 0 || raw_offset >= total;
+        if exhausted {
+            total = raw_offset;
+        }
+        if exhausted
+            || filters.is_none()
+            || items.len() as u32 >= limit
+            || pages >= MAX_FILTERED_SCAN_PAGES
+        {
+            break;
+        }
+    }
+
+    if dropped > 0 {
+        log::debug!("[Search] Filtered {} items from search results", dropped);
+    }
+
+    Ok(FilteredSearchPage {
+        next_offset: (raw_offset < total).then_some(raw_offset),
+        // Adjust total to reflect filtered count (approximate)
+        page: SearchResultsPage {
+            items,
+            total: total.saturating_sub(dropped),
+            offset,
+            limit,
+        },
+        applied_filters: filters,
+    })
+}
+
+/// Search albums. `filters` narrows by genre, release year and explicit
+/// content; pages should then be requested from `next_offset`.
 #[tauri::command]
 pub async fn search_albums(
     query: String,
     limit: Option<u32>,
     offset: Option<u32>,
     search_type: Option<String>,
+    filters: Option<SearchFilters>,
     state: State<'_, AppState>,
     blacklist_state: State<'_, BlacklistState>,
-) -> Result<SearchResultsPage<Album>, String> {
-    let mut results = {
-        let client = state.client.read().await;
-        client
-            .search_albums(&query, limit.unwrap_or(20), offset.unwrap_or(0), search_type.as_deref())
-            .await
-            .map_err(|e| e.to_string())?
-    };
-
-    // Filter out albums from blacklisted artists
-    let original_count = results.items.len();
-    results.items.retain(|album| !blacklist_state.is_blacklisted(album.artist.id));
-
-    let filtered_count = original_count - results.items.len();
-    if filtered_count > 0 {
-        log::debug!("[Blacklist] Filtered {} albums from search results", filtered_count);
-        results.total = results.total.saturating_sub(filtered_count as u32);
+) -> Result<FilteredSearchPage<Album>, String> {
+    let filters = filters.and_then(SearchFilters::normalized);
+    if let Some(ref f) = filters {
+        f.validate()?;
     }
+    let limit = limit.unwrap_or(20);
 
-    Ok(results)
+    let client = state.client.read().await;
+    let client = &*client;
+    let query = query.as_str();
+    let search_type = search_type.as_deref();
+    search_filtered(
+        offset.unwrap_or(0),
+        limit,
+        filters.clone(),
+        |raw_offset| client.search_albums(query, limit, raw_offset, search_type),
+        // Filter out albums from blacklisted artists
+        |album: &Album| {
+            !blacklist_state.is_blacklisted(album.artist.id)
+                && filters.as_ref().is_none_or(|f| f.matches_album(album))
+        },
+    )
+    .await
 }
 
+/// Search tracks. `filters` narrows by genre, release year and explicit
+/// content; pages should then be requested from `next_offset`.
+/// `quality_ranking` puts the best version of duplicate tracks first.
 #[tauri::command]
 pub async fn search_tracks(
     query: String,
     limit: Option<u32>,
     offset: Option<u32>,
     search_type: Option<String>,
+    filters: Option<SearchFilters>,
+    quality_ranking: Option<QualityRanking>,
     state: State<'_, AppState>,
     blacklist_state: State<'_, BlacklistState>,
-) -> Result<SearchResultsPage<Track>, String> {
-    let mut results = {
-        let client = state.client.read().await;
-        client
-            .search_tracks(&query, limit.unwrap_or(20), offset.unwrap_or(0), search_type.as_deref())
-            .await
-            .map_err(|e| e.to_string())?
-    };
-
-    // Filter out tracks from blacklisted artists
-    let original_count = results.items.len();
-    results.items.retain(|track| {
-        if let Some(ref performer) = track.performer {
-            !blacklist_state.is_blacklisted(performer.id)
-        } else {
-            true // Keep tracks without performer info
-        }
-    });
-
-    let filtered_count = original_count - results.items.len();
-    if filtered_count > 0 {
-        log::debug!("[Blacklist] Filtered {} tracks from search results", filtered_count);
-        // Adjust total to reflect filtered count (approximate)
-        results.total = results.total.saturating_sub(filtered_count as u32);
+) -> Result<FilteredSearchPage<Track>, String> {
+    let filters = filters.and_then(SearchFilters::normalized);
+    if let Some(ref f) = filters {
+        f.validate()?;
     }
+    let limit = limit.unwrap_or(20);
 
-    Ok(results)
+    let client = state.client.read().await;
+    let client = &*client;
+    let query = query.as_str();
+    let search_type = search_type.as_deref();
+    let mut page = search_filtered(
+        offset.unwrap_or(0),
+        limit,
+        filters.clone(),
+        |raw_offset| client.search_tracks(query, limit, raw_offset, search_type),
+        // Filter out tracks from blacklisted artists
+        |track: &Track| {
+            !blacklist_state.is_track_blacklisted(track)
+                && filters.as_ref().is_none_or(|f| f.matches_track(track))
+        },
+    )
+    .await?;
+
+    let ranking = quality_ranking.unwrap_or_default();
+    let dropped = rank_tracks_by_quality(&mut page.page.items, ranking);
+    page.page.total = page.page.total.saturating_sub(dropped as u32);
+    Ok(page)
 }
 
 #[tauri::command]
@@ -127,6 +303,7 @@ pub async fn search_artists(
 #[tauri::command]
 pub async fn search_all(
     query: String,
+    quality_ranking: Option<QualityRanking>,
     state: State<'_, AppState>,
     blacklist_state: State<'_, BlacklistState>,
 ) -> Result<SearchAllResults, String> {
@@ -138,7 +315,7 @@ pub async fn search_all(
     // Acquire lock only for HTTP request, drop before parsing
     let response: Value = {
         let client = state.client.read().await;
-        client
+        let request = client
             .get_http()
             .get(&url)
             .header("X-App-Id", client.app_id().await.map_err(|e| e.to_string())?)
@@ -147,8 +324,9 @@ pub async fn search_all(
                 ("query", query.as_str()),
                 ("limit", "30"),
                 ("offset", "0"),
-            ])
-            .send()
+            ]);
+        client
+            .send_checked(request)
             .await
             .map_err(|e| format!("Request failed: {}", e))?
             .json()
@@ -265,6 +443,11 @@ pub async fn search_all(
         tracks.total = tracks.total.saturating_sub(filtered_tracks as u32);
     }
 
+    // Prefer the best version of tracks listed more than once
+    let ranking = quality_ranking.unwrap_or_default();
+    let collapsed_tracks = rank_tracks_by_quality(&mut tracks.items, ranking);
+    tracks.total = tracks.total.saturating_sub(collapsed_tracks as u32);
+
     // Filter blacklisted artists
     let original_artist_count = artists.items.len();
     artists.items.retain(|artist| !blacklist_state.is_blacklisted(artist.id));
@@ -293,17 +476,115 @@ pub async fn search_all(
     })
 }
 
+/// Get an album with its tracks
+///
+/// Classical tracks get their work and movement filled in and the album's
+/// tracks are grouped by work in `works`. With `group_by_work`, works of a
+/// classical album that Qobuz doesn't name are looked up on MusicBrainz.
 #[tauri::command]
 pub async fn get_album(
     album_id: String,
+    group_by_work: Option<bool>,
     state: State<'_, AppState>,
     cache_state: State<'_, ApiCacheState>,
+    mb_state: State<'_, MusicBrainzSharedState>,
+) -> Result<Album, String> {
+    let mut album = fetch_album(&album_id, &state, &cache_state).await?;
+
+    apply_classical_fields(&mut album);
+    if group_by_work.unwrap_or(false) {
+        apply_musicbrainz_works(&mb_state, &mut album).await;
+    }
+
+    Ok(album)
+}
+
+/// Fill in works Qobuz doesn't name from the MusicBrainz release
+///
+/// Only classical albums (some work already known) are looked up, and only
+/// when a track is still missing its work.
+async fn apply_musicbrainz_works(mb_state: &MusicBrainzSharedState, album: &mut Album) {
+    let missing_work = album
+        .tracks
+        .as_ref()
+        .is_some_and(|tc| tc.items.iter().any(|t| t.work.is_none()));
+    if album.works.is_none() || !missing_work {
+        return;
+    }
+
+    let Some(works) = album_works(mb_state, album).await else {
+        return;
+    };
+    let Some(tracks) = album.tracks.as_mut() else {
+        return;
+    };
+
+    for track in tracks.items.iter_mut().filter(|t| t.work.is_none()) {
+        let disc = track.media_number.unwrap_or(1);
+        let work = works
+            .iter()
+            .find(|(d, position, _)| *d == disc && *position == track.track_number)
+            .map(|(_, _, work)| work);
+        if let Some(work) = work {
+            track.work = Some(work.title.clone());
+            if track.composer.is_none() && !work.composers.is_empty() {
+                track.composer = Some(Artist {
+                    name: work.composers.join(", "),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    apply_classical_fields(album);
+}
+
+/// Works of the album's tracks as (disc, position, work)
+///
+/// Looked up once per album: the release search and credits lookup are
+/// rate-limited, so the result is kept in the MusicBrainz cache. Failed
+/// lookups aren't cached and are retried the next time the album is opened.
+async fn album_works(
+    mb_state: &MusicBrainzSharedState,
+    album: &Album,
+) -> Option<Vec<(u32, u32, MbWork)>> {
+    let cached = {
+        let cache_opt__ = mb_state.cache.lock().await;
+        cache_opt__
+            .as_ref()
+            .and_then(|cache| cache.get_album_works(&album.id).ok().flatten())
+    };
+    if let Some(works) = cached {
+        log::debug!("MusicBrainz cache hit for album works: {}", album.id);
+        return Some(works);
+    }
+
+    let (_, credits) = fetch_release_credits(mb_state, album).await?;
+    let works: Vec<(u32, u32, MbWork)> = credits
+        .works
+        .into_iter()
+        .map(|((disc, position), work)| (disc, position, work))
+        .collect();
+
+    let cache_opt__ = mb_state.cache.lock().await;
+    if let Some(cache) = cache_opt__.as_ref() {
+        let _ = cache.set_album_works(&album.id, &works);
+    }
+
+    Some(works)
+}
+
+/// Album from the API cache, or from the API (then cached)
+async fn fetch_album(
+    album_id: &str,
+    state: &AppState,
+    cache_state: &ApiCacheState,
 ) -> Result<Album, String> {
     // Check cache first
     {
         let guard__ = cache_state.cache.lock().await;
         let cache = guard__.as_ref().ok_or("No active session - please log in")?;
-        match cache.get_album(&album_id, None) {
+        match cache.get_album(album_id, None) {
             Ok(Some(cached_data)) => {
                 log::debug!("Cache hit for album {}", album_id);
                 return serde_json::from_str(&cached_data)
@@ -320,7 +601,7 @@ pub async fn get_album(
     log::debug!("Cache miss for album {}, fetching from API", album_id);
     let album = {
         let client = state.client.read().await;
-        client.get_album(&album_id).await.map_err(|e| e.to_string())?
+        client.get_album(album_id).await.map_err(|e| e.to_string())?
     };
 
     // Cache the result
@@ -329,7 +610,7 @@ pub async fn get_album(
         let cache = guard__.as_ref().ok_or("No active session - please log in")?;
         let json = serde_json::to_string(&album)
             .map_err(|e| format!("Failed to serialize album: {}", e))?;
-        let _ = cache.set_album(&album_id, &json);
+        let _ = cache.set_album(album_id, &json);
     }
 
     Ok(album)
@@ -780,3 +1061,129 @@ pub async fn get_releases_grid(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fake catalog of `total` items (0..total), served `limit` at a time
+    async fn fetch_page(
+        total: u32,
+        limit: u32,
+        offset: u32,
+    ) -> Result<SearchResultsPage<u32>, String> {
+        Ok(SearchResultsPage {
+            items: (offset..total.min(offset + limit)).collect(),
+            total,
+            offset,
+            limit,
+        })
+    }
+
+    fn run<T>(fut: impl Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(fut)
+    }
+
+    #[test]
+    fn filtered_paging_neither_skips_nor_repeats() {
+        let filters = Some(SearchFilters {
+            exclude_explicit: true,
+            ..Default::default()
+        });
+        let mut offset = Some(0);
+        let mut seen = Vec::new();
+        while let Some(from) = offset {
+            let page = run(search_filtered(
+                from,
+                10,
+                filters.clone(),
+                |raw| fetch_page(50, 10, raw),
+                |n: &u32| n % 3 == 0,
+            ))
+            .unwrap();
+            assert!(page.page.items.len() <= 10);
+            assert_eq!(page.applied_filters, filters);
+            seen.extend(page.page.items);
+            offset = page.next_offset;
+        }
+        assert_eq!(seen, (0..50).filter(|n| n % 3 == 0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn unfiltered_search_fetches_one_page() {
+        let page = run(search_filtered(
+            0,
+            10,
+            None,
+            |raw| fetch_page(50, 10, raw),
+            |n: &u32| *n != 3,
+        ))
+        .unwrap();
+        assert_eq!(page.page.items.len(), 9);
+        assert_eq!(page.next_offset, Some(10));
+        assert_eq!(page.page.total, 49);
+    }
+
+    #[test]
+    fn narrow_filter_stops_after_max_pages() {
+        let page = run(search_filtered(
+            0,
+            10,
+            Some(SearchFilters {
+                genre_id: Some(1),
+                ..Default::default()
+            }),
+            |raw| fetch_page(1000, 10, raw),
+            |_: &u32| false,
+        ))
+        .unwrap();
+        assert!(page.page.items.is_empty());
+        assert_eq!(page.next_offset, Some(10 * MAX_FILTERED_SCAN_PAGES));
+    }
+
+    fn track(id: u64, title: &str, artist: &str, bit_depth: u32, rate_khz: f64) -> Track {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": title,
+            "performer": { "id": 1, "name": artist },
+            "maximum_bit_depth": bit_depth,
+            "maximum_sampling_rate": rate_khz,
+            "hires": bit_depth > 16,
+            "hires_streamable": bit_depth > 16,
+            "streamable": true,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn quality_ranking_prefers_best_version() {
+        let results = vec![
+            track(1, "Song", "Band", 16, 44.1),
+            track(2, "Other", "Band", 16, 44.1),
+            track(3, "song ", "band", 24, 96.0),
+            track(4, "Song", "Someone Else", 24, 192.0),
+        ];
+        let ids = |tracks: &[Track]| tracks.iter().map(|t| t.id).collect::<Vec<_>>();
+
+        let mut off = results.clone();
+        assert_eq!(rank_tracks_by_quality(&mut off, QualityRanking::Off), 0);
+        assert_eq!(ids(&off), vec![1, 2, 3, 4]);
+
+        let mut preferred = results.clone();
+        assert_eq!(
+            rank_tracks_by_quality(&mut preferred, QualityRanking::PreferBest),
+            0
+        );
+        assert_eq!(ids(&preferred), vec![3, 1, 2, 4]);
+
+        let mut collapsed = results;
+        assert_eq!(
+            rank_tracks_by_quality(&mut collapsed, QualityRanking::CollapseDuplicates),
+            1
+        );
+        assert_eq!(ids(&collapsed), vec![3, 2, 4]);
+    }
+}
@@ -2,11 +2,12 @@
 //!
 //! After login, the frontend calls `activate_user_session` with the Qobuz user_id.
 //! This initializes all per-user database stores at the user-scoped directory.
-//! On logout, `deactivate_user_session` tears everything down.
+//! On logout, `deactivate_user_session` tears everything down, and
+//! `switch_user_session` does both in one call when changing accounts.
 
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use tauri::{Manager, State};
+use tauri::Manager;
 
 use crate::api_cache::ApiCacheState;
 use crate::artist_blacklist::BlacklistState;
@@ -18,6 +19,7 @@ use crate::config::{
     favorites_preferences::FavoritesPreferencesState,
     legal_settings::{LegalSettingsState, LegalSettingsStore},
     playback_preferences::PlaybackPreferencesState,
+    region_settings::RegionSettingsState,
     remote_control_settings::{AllowedOriginsState, RemoteControlSettingsState},
     subscription_state::{SubscriptionStateState, SubscriptionStateStore},
     tray_settings::TraySettingsState,
@@ -68,34 +70,86 @@ pub fn get_last_user_id() -> Option<u64> {
 /// per-user database stores at `~/.local/share/qbz/users/{user_id}/`
 /// and cache stores at `~/.cache/qbz/users/{user_id}/`.
 #[tauri::command]
-pub async fn activate_user_session(
-    app: tauri::AppHandle,
-    user_id: u64,
-    user_paths: State<'_, UserDataPaths>,
-    session_store: State<'_, SessionStoreState>,
-    favorites_cache: State<'_, FavoritesCacheState>,
-    subscription_state: State<'_, SubscriptionStateState>,
-    playback_prefs: State<'_, PlaybackPreferencesState>,
-    favorites_prefs: State<'_, FavoritesPreferencesState>,
-    download_settings: State<'_, DownloadSettingsState>,
-    audio_settings: State<'_, AudioSettingsState>,
-    tray_settings: State<'_, TraySettingsState>,
-    remote_control_settings: State<'_, RemoteControlSettingsState>,
-    allowed_origins: State<'_, AllowedOriginsState>,
-    legal_settings: State<'_, LegalSettingsState>,
-    updates: State<'_, UpdatesState>,
-    library: State<'_, LibraryState>,
-    reco: State<'_, RecoState>,
-    api_cache: State<'_, ApiCacheState>,
-    artist_vectors: State<'_, ArtistVectorStoreState>,
-    blacklist: State<'_, BlacklistState>,
-    offline: State<'_, OfflineState>,
-    offline_cache: State<'_, OfflineCacheState>,
-    lyrics: State<'_, LyricsState>,
-    musicbrainz: State<'_, MusicBrainzSharedState>,
-    listenbrainz: State<'_, ListenBrainzSharedState>,
-) -> Result<(), String> {
+pub async fn activate_user_session(app: tauri::AppHandle, user_id: u64) -> Result<(), String> {
     log::info!("Activating user session for user_id={}", user_id);
+    init_user_stores(&app, user_id).await?;
+    log::info!("User session activated for user_id={}", user_id);
+    Ok(())
+}
+
+/// Deactivate the per-user session on logout.
+///
+/// Tears down all per-user stores, closing database connections.
+#[tauri::command]
+pub async fn deactivate_user_session(app: tauri::AppHandle) -> Result<(), String> {
+    log::info!("Deactivating user session");
+    teardown_user_stores(&app).await?;
+
+    // Clear the active user and persisted last_user_id
+    app.state::<UserDataPaths>().clear_user();
+    UserDataPaths::clear_last_user_id();
+
+    log::info!("User session deactivated");
+    Ok(())
+}
+
+/// Switch to another account without restarting.
+///
+/// Stops playback, clears the queue and re-points every per-user store at
+/// `user_id`'s directories. Device-level state is kept: the audio engine
+/// and output stay open and a connected cast device stays connected. The
+/// Qobuz client must already be logged in as the new account.
+#[tauri::command]
+pub async fn switch_user_session(app: tauri::AppHandle, user_id: u64) -> Result<(), String> {
+    let logged_in_as = app
+        .state::<crate::AppState>()
+        .client
+        .read()
+        .await
+        .get_user_id()
+        .await;
+    if logged_in_as != Some(user_id) {
+        return Err(format!(
+            "Cannot switch to user_id={}: Qobuz client is logged in as {:?}",
+            user_id, logged_in_as
+        ));
+    }
+
+    let previous = app.state::<UserDataPaths>().current_user_id();
+    log::info!("Switching user session from {:?} to user_id={}", previous, user_id);
+
+    {
+        let app_state = app.state::<crate::AppState>();
+        if let Err(e) = crate::commands::playback::stop_playback(app.clone(), app_state.clone()).await {
+            log::warn!("Failed to stop playback before switching users: {}", e);
+        }
+        app_state.queue.clear();
+        app_state.context.clear_context();
+        crate::api_server::broadcast_queue_update(&app);
+    }
+
+    if previous.is_some() {
+        teardown_user_stores(&app).await?;
+    }
+    init_user_stores(&app, user_id).await?;
+
+    log::info!("User session switched to user_id={}", user_id);
+    Ok(())
+}
+
+/// Ids of accounts that have per-user data on this machine
+#[tauri::command]
+pub fn get_saved_user_ids() -> Vec<u64> {
+    UserDataPaths::saved_user_ids()
+}
+
+/// Initialize every per-user store at `user_id`'s directories
+async fn init_user_stores(app: &tauri::AppHandle, user_id: u64) -> Result<(), String> {
+    let user_paths = app.state::<UserDataPaths>();
+    let subscription_state = app.state::<SubscriptionStateState>();
+    let audio_settings = app.state::<AudioSettingsState>();
+    let library = app.state::<LibraryState>();
+    let offline_cache = app.state::<OfflineCacheState>();
 
     // Set the active user for path resolution
     user_paths.set_user(user_id);
@@ -121,32 +175,64 @@ pub async fn activate_user_session(
 
     // Initialize all per-user states at the user directory.
     // Data-dir stores:
-    session_store.init_at(&data_dir)?;
-    favorites_cache.init_at(&data_dir)?;
-    playback_prefs.init_at(&data_dir)?;
-    favorites_prefs.init_at(&data_dir)?;
+    app.state::<SessionStoreState>().init_at(&data_dir)?;
+    app.state::<FavoritesCacheState>().init_at(&data_dir)?;
+    app.state::<PlaybackPreferencesState>().init_at(&data_dir)?;
+    app.state::<FavoritesPreferencesState>().init_at(&data_dir)?;
     audio_settings.init_at(&data_dir)?;
-    tray_settings.init_at(&data_dir)?;
-    remote_control_settings.init_at(&data_dir)?;
-    allowed_origins.init_at(&data_dir)?;
-    updates.init_at(&data_dir)?;
+    app.state::<TraySettingsState>().init_at(&data_dir)?;
+    app.state::<RegionSettingsState>().init_at(&data_dir)?;
+    app.state::<RemoteControlSettingsState>().init_at(&data_dir)?;
+    app.state::<AllowedOriginsState>().init_at(&data_dir)?;
+    app.state::<UpdatesState>().init_at(&data_dir)?;
     library.init_at(&data_dir).await?;
-    reco.init_at(&data_dir).await?;
-    api_cache.init_at(&data_dir).await?;
-    artist_vectors.init_at(&data_dir).await?;
-    blacklist.init_at(&data_dir)?;
-    offline.init_at(&data_dir)?;
-    musicbrainz.init_at(&data_dir).await?;
-    listenbrainz.init_at(&data_dir).await?;
+    app.state::<RecoState>().init_at(&data_dir).await?;
+    app.state::<ApiCacheState>().init_at(&data_dir).await?;
+    app.state::<ArtistVectorStoreState>().init_at(&data_dir).await?;
+    app.state::<BlacklistState>().init_at(&data_dir)?;
+    app.state::<OfflineState>().init_at(&data_dir)?;
+    app.state::<MusicBrainzSharedState>().init_at(&data_dir).await?;
+    app.state::<ListenBrainzSharedState>().init_at(&data_dir).await?;
+
+    // Request content in the user's saved language (or the UI language)
+    let locale = app.state::<RegionSettingsState>().effective_locale();
+    app.state::<crate::AppState>()
+        .client
+        .read()
+        .await
+        .set_locale(locale)
+        .await;
+
+    // Apply the user's EQ and cache limits now; other audio settings take
+    // effect on device reinit
+    if let Ok(guard) = audio_settings.store.lock() {
+        if let Some(settings) = guard.as_ref().and_then(|store| store.get_settings().ok()) {
+            let app_state = app.state::<crate::AppState>();
+            crate::commands::cache::apply_cache_limits(
+                &app_state,
+                settings.memory_cache_mb,
+                settings.disk_cache_mb,
+            );
+            app_state.player.equalizer.set_config(settings.eq_config);
+        }
+    }
 
     // Type-alias states (no init_at method — init inline)
     init_type_alias_state(&*subscription_state, &data_dir, SubscriptionStateStore::new_at)?;
-    init_type_alias_state(&*download_settings, &data_dir, DownloadSettingsStore::new_at)?;
-    init_type_alias_state(&*legal_settings, &data_dir, LegalSettingsStore::new_at)?;
+    init_type_alias_state(
+        &*app.state::<DownloadSettingsState>(),
+        &data_dir,
+        DownloadSettingsStore::new_at,
+    )?;
+    init_type_alias_state(
+        &*app.state::<LegalSettingsState>(),
+        &data_dir,
+        LegalSettingsStore::new_at,
+    )?;
 
     // Cache-dir stores:
     offline_cache.init_at(&cache_dir).await?;
-    lyrics.init_at(&cache_dir).await?;
+    app.state::<LyricsState>().init_at(&cache_dir).await?;
 
     // Run deferred subscription purge check (was removed from startup)
     let now = std::time::SystemTime::now()
@@ -194,71 +280,42 @@ pub async fn activate_user_session(
         }
     });
 
-    log::info!("User session activated for user_id={}", user_id);
     Ok(())
 }
 
-/// Deactivate the per-user session on logout.
-///
-/// Tears down all per-user stores, closing database connections.
-#[tauri::command]
-pub async fn deactivate_user_session(
-    user_paths: State<'_, UserDataPaths>,
-    session_store: State<'_, SessionStoreState>,
-    favorites_cache: State<'_, FavoritesCacheState>,
-    subscription_state: State<'_, SubscriptionStateState>,
-    playback_prefs: State<'_, PlaybackPreferencesState>,
-    favorites_prefs: State<'_, FavoritesPreferencesState>,
-    download_settings: State<'_, DownloadSettingsState>,
-    audio_settings: State<'_, AudioSettingsState>,
-    tray_settings: State<'_, TraySettingsState>,
-    remote_control_settings: State<'_, RemoteControlSettingsState>,
-    allowed_origins: State<'_, AllowedOriginsState>,
-    legal_settings: State<'_, LegalSettingsState>,
-    updates: State<'_, UpdatesState>,
-    library: State<'_, LibraryState>,
-    reco: State<'_, RecoState>,
-    api_cache: State<'_, ApiCacheState>,
-    artist_vectors: State<'_, ArtistVectorStoreState>,
-    blacklist: State<'_, BlacklistState>,
-    offline: State<'_, OfflineState>,
-    offline_cache: State<'_, OfflineCacheState>,
-    lyrics: State<'_, LyricsState>,
-    musicbrainz: State<'_, MusicBrainzSharedState>,
-    listenbrainz: State<'_, ListenBrainzSharedState>,
-) -> Result<(), String> {
-    log::info!("Deactivating user session");
-
-    // Teardown all per-user stores (closes DB connections)
-    session_store.teardown();
-    favorites_cache.teardown()?;
-    playback_prefs.teardown()?;
-    favorites_prefs.teardown()?;
-    audio_settings.teardown()?;
-    tray_settings.teardown()?;
-    remote_control_settings.teardown()?;
-    allowed_origins.teardown()?;
-    updates.teardown();
-    library.teardown().await;
-    reco.teardown().await;
-    api_cache.teardown().await;
-    artist_vectors.teardown().await;
-    blacklist.teardown();
-    offline.teardown();
-    offline_cache.teardown().await;
-    lyrics.teardown().await;
-    musicbrainz.teardown().await;
-    listenbrainz.teardown().await;
+/// Tear down every per-user store, closing database connections
+async fn teardown_user_stores(app: &tauri::AppHandle) -> Result<(), String> {
+    app.state::<SessionStoreState>().teardown();
+    app.state::<FavoritesCacheState>().teardown()?;
+    app.state::<PlaybackPreferencesState>().teardown()?;
+    app.state::<FavoritesPreferencesState>().teardown()?;
+    app.state::<AudioSettingsState>().teardown()?;
+    app.state::<TraySettingsState>().teardown()?;
+    app.state::<RegionSettingsState>().teardown()?;
+    let locale = app.state::<RegionSettingsState>().effective_locale();
+    app.state::<crate::AppState>()
+        .client
+        .read()
+        .await
+        .set_locale(locale)
+        .await;
+    app.state::<RemoteControlSettingsState>().teardown()?;
+    app.state::<AllowedOriginsState>().teardown()?;
+    app.state::<UpdatesState>().teardown();
+    app.state::<LibraryState>().teardown().await;
+    app.state::<RecoState>().teardown().await;
+    app.state::<ApiCacheState>().teardown().await;
+    app.state::<ArtistVectorStoreState>().teardown().await;
+    app.state::<BlacklistState>().teardown();
+    app.state::<OfflineState>().teardown();
+    app.state::<OfflineCacheState>().teardown().await;
+    app.state::<LyricsState>().teardown().await;
+    app.state::<MusicBrainzSharedState>().teardown().await;
+    app.state::<ListenBrainzSharedState>().teardown().await;
 
     // Type-alias states
-    teardown_type_alias_state(&*subscription_state);
-    teardown_type_alias_state(&*download_settings);
-    teardown_type_alias_state(&*legal_settings);
-
-    // Clear the active user and persisted last_user_id
-    user_paths.clear_user();
-    UserDataPaths::clear_last_user_id();
-
-    log::info!("User session deactivated");
+    teardown_type_alias_state(&*app.state::<SubscriptionStateState>());
+    teardown_type_alias_state(&*app.state::<DownloadSettingsState>());
+    teardown_type_alias_state(&*app.state::<LegalSettingsState>());
     Ok(())
 }
@@ -3,6 +3,7 @@
 //! Tauri commands for controlling the audio visualizer.
 
 use tauri::State;
+use crate::visualizer::VisualizerConfig;
 use crate::AppState;
 
 /// Enable or disable the audio visualizer
@@ -17,3 +18,16 @@ pub fn set_visualizer_enabled(enabled: bool, state: State<'_, AppState>) -> Resu
 pub fn is_visualizer_enabled(state: State<'_, AppState>) -> bool {
     state.visualizer.is_enabled()
 }
+
+/// Tell the visualizer whether the UI is visible (hidden = no FFT work)
+#[tauri::command]
+pub fn set_visualizer_visible(visible: bool, state: State<'_, AppState>) -> Result<(), String> {
+    state.visualizer.set_ui_visible(visible);
+    Ok(())
+}
+
+/// Get the visualizer settings and its current (possibly throttled) rate
+#[tauri::command]
+pub fn get_visualizer_config(state: State<'_, AppState>) -> VisualizerConfig {
+    state.visualizer.config()
+}
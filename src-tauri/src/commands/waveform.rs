@@ -0,0 +1,120 @@
+//! Waveform commands
+//!
+//! The seekbar asks for the waveform once the track is playing. It is built
+//! from audio that is already on this machine (library file, playback caches
+//! or offline copy), so generating it never competes with playback start.
+
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::audio::{waveform, CueRange, TrackWaveform, WaveformKey, WAVEFORM_POINTS};
+use crate::commands::playback::offline_cached_path;
+use crate::library::commands::LibraryState;
+use crate::offline_cache::OfflineCacheState;
+use crate::AppState;
+
+/// Cache key of a track: the file path (and cue range) for library tracks,
+/// the id otherwise
+async fn waveform_key(
+    track_id: u64,
+    is_local: bool,
+    library_state: &LibraryState,
+) -> Result<WaveformKey, String> {
+    if !is_local {
+        return Ok(WaveformKey::Qobuz(track_id));
+    }
+    let guard__ = library_state.db.lock().await;
+    let db = guard__
+        .as_ref()
+        .ok_or("No active session - please log in")?;
+    let track = db
+        .get_track(track_id as i64)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Track not found".to_string())?;
+    let range = track
+        .cue_start_secs
+        .map(|start| CueRange::from_secs(start, track.cue_end_secs));
+    Ok(WaveformKey::Local {
+        path: track.file_path,
+        range,
+    })
+}
+
+/// Read a file off the async runtime
+async fn read_file(path: PathBuf) -> Result<Vec<u8>, String> {
+    tokio::task::spawn_blocking(move || std::fs::read(path))
+        .await
+        .map_err(|e| format!("File read task failed: {}", e))?
+        .map_err(|e| format!("Failed to read file: {}", e))
+}
+
+/// Audio bytes of a track, if they are available locally
+async fn local_audio_data(
+    key: &WaveformKey,
+    state: &AppState,
+    offline_cache: &OfflineCacheState,
+) -> Result<Option<Vec<u8>>, String> {
+    let track_id = match key {
+        WaveformKey::Local { path, .. } => return read_file(PathBuf::from(path)).await.map(Some),
+        WaveformKey::Qobuz(track_id) => *track_id,
+    };
+
+    if let Some(cached) = state.audio_cache.get(track_id) {
+        return Ok(Some(cached.data));
+    }
+    if let Some(data) = state
+        .audio_cache
+        .get_playback_cache()
+        .and_then(|cache| cache.get(track_id))
+    {
+        return Ok(Some(data));
+    }
+    if let Some(path) = offline_cached_path(offline_cache, track_id).await {
+        return read_file(path).await.map(Some);
+    }
+    Ok(None)
+}
+
+/// Get the seekbar waveform of a track, generating it on first request.
+///
+/// Returns None while the audio isn't available locally yet (e.g. a Qobuz
+/// track that is still streaming); the caller should ask again later.
+#[tauri::command]
+pub async fn get_track_waveform(
+    track_id: u64,
+    source: Option<String>,
+    state: State<'_, AppState>,
+    library_state: State<'_, LibraryState>,
+    offline_cache: State<'_, OfflineCacheState>,
+) -> Result<Option<TrackWaveform>, String> {
+    let is_local = source.as_deref() == Some("local");
+    let key = waveform_key(track_id, is_local, &library_state).await?;
+    if let Some(waveform) = state.waveforms.get(track_id, &key) {
+        return Ok(Some(waveform));
+    }
+
+    let Some(data) = local_audio_data(&key, &state, &offline_cache).await? else {
+        log::debug!("Waveform for track {} not available yet", track_id);
+        return Ok(None);
+    };
+
+    let range = match &key {
+        WaveformKey::Local { range, .. } => *range,
+        WaveformKey::Qobuz(_) => None,
+    };
+    let waveform = tokio::task::spawn_blocking(move || {
+        waveform::compute_waveform(track_id, &data, range, WAVEFORM_POINTS)
+    })
+    .await
+    .map_err(|e| format!("Waveform task failed: {}", e))??;
+
+    log::info!(
+        "Generated waveform for track {} ({:.0}s, local={})",
+        track_id,
+        waveform.duration_secs,
+        is_local
+    );
+    state.waveforms.insert(key, waveform.clone());
+    Ok(Some(waveform))
+}
@@ -0,0 +1,217 @@
+//! Artwork settings
+//!
+//! Stores device-level preferences for remote artwork:
+//! - quality: which Qobuz cover size to request (low / standard / high)
+//! - cache_mb: size limit of the on-disk artwork cache
+
+use log::info;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::api::artwork::ArtworkQuality;
+use crate::cache::artwork::{
+    ArtworkCacheState, DEFAULT_ARTWORK_CACHE_MB, MAX_ARTWORK_CACHE_MB, MIN_ARTWORK_CACHE_MB,
+};
+use crate::cache::mb_to_bytes;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtworkSettings {
+    pub quality: ArtworkQuality,
+    pub cache_mb: u32,
+}
+
+impl Default for ArtworkSettings {
+    fn default() -> Self {
+        Self {
+            quality: ArtworkQuality::default(),
+            cache_mb: DEFAULT_ARTWORK_CACHE_MB,
+        }
+    }
+}
+
+pub struct ArtworkSettingsStore {
+    conn: Connection,
+}
+
+impl ArtworkSettingsStore {
+    fn open_at(dir: &Path, db_name: &str) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+        let db_path = dir.join(db_name);
+        let conn = Connection::open(&db_path)
+            .map_err(|e| format!("Failed to open artwork settings database: {}", e))?;
+
+        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
+            .map_err(|e| format!("Failed to enable WAL for artwork settings database: {}", e))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS artwork_settings (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                quality TEXT NOT NULL DEFAULT 'standard',
+                cache_mb INTEGER NOT NULL DEFAULT 200
+            );",
+        )
+        .map_err(|e| format!("Failed to create artwork settings table: {}", e))?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO artwork_settings (id, quality, cache_mb)
+            VALUES (1, ?1, ?2)",
+            params![
+                ArtworkQuality::default().to_db_value(),
+                DEFAULT_ARTWORK_CACHE_MB
+            ],
+        )
+        .map_err(|e| format!("Failed to insert default artwork settings: {}", e))?;
+
+        info!("[ArtworkSettings] Database initialized");
+
+        Ok(Self { conn })
+    }
+
+    pub fn new() -> Result<Self, String> {
+        let data_dir = dirs::data_dir()
+            .ok_or("Could not determine data directory")?
+            .join("qbz");
+        Self::open_at(&data_dir, "artwork_settings.db")
+    }
+
+    pub fn get_settings(&self) -> Result<ArtworkSettings, String> {
+        self.conn
+            .query_row(
+                "SELECT quality, cache_mb FROM artwork_settings WHERE id = 1",
+                [],
+                |row| {
+                    let quality: String = row.get(0)?;
+                    let cache_mb: i64 = row.get(1)?;
+                    Ok(ArtworkSettings {
+                        quality: ArtworkQuality::from_db_value(&quality),
+                        cache_mb: cache_mb as u32,
+                    })
+                },
+            )
+            .map_err(|e| format!("Failed to get artwork settings: {}", e))
+    }
+
+    pub fn set_quality(&self, quality: ArtworkQuality) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE artwork_settings SET quality = ?1 WHERE id = 1",
+                params![quality.to_db_value()],
+            )
+            .map_err(|e| format!("Failed to set artwork quality: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_cache_mb(&self, cache_mb: u32) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE artwork_settings SET cache_mb = ?1 WHERE id = 1",
+                params![cache_mb],
+            )
+            .map_err(|e| format!("Failed to set artwork cache_mb: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Global state wrapper for thread-safe access
+pub struct ArtworkSettingsState {
+    pub store: Arc<Mutex<Option<ArtworkSettingsStore>>>,
+}
+
+impl ArtworkSettingsState {
+    pub fn new() -> Result<Self, String> {
+        let store = ArtworkSettingsStore::new()?;
+        Ok(Self {
+            store: Arc::new(Mutex::new(Some(store))),
+        })
+    }
+
+    pub fn new_empty() -> Self {
+        Self {
+            store: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn get_settings(&self) -> Result<ArtworkSettings, String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock artwork settings store".to_string())?;
+        let store = guard
+            .as_ref()
+            .ok_or("Artwork settings store not initialized")?;
+        store.get_settings()
+    }
+
+    /// Configured quality, or the default if the store is unavailable
+    pub fn quality(&self) -> ArtworkQuality {
+        self.get_settings()
+            .map(|settings| settings.quality)
+            .unwrap_or_default()
+    }
+
+    pub fn set_quality(&self, quality: ArtworkQuality) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock artwork settings store".to_string())?;
+        let store = guard
+            .as_ref()
+            .ok_or("Artwork settings store not initialized")?;
+        store.set_quality(quality)
+    }
+
+    pub fn set_cache_mb(&self, cache_mb: u32) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock artwork settings store".to_string())?;
+        let store = guard
+            .as_ref()
+            .ok_or("Artwork settings store not initialized")?;
+        store.set_cache_mb(cache_mb)
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_artwork_settings(
+    state: tauri::State<ArtworkSettingsState>,
+) -> Result<ArtworkSettings, String> {
+    state.get_settings()
+}
+
+#[tauri::command]
+pub fn set_artwork_quality(
+    quality: ArtworkQuality,
+    state: tauri::State<ArtworkSettingsState>,
+) -> Result<(), String> {
+    info!("[ArtworkSettings] Setting quality to {:?}", quality);
+    state.set_quality(quality)
+}
+
+/// Change the artwork cache limit, evicting least recently used files if
+/// shrinking
+#[tauri::command]
+pub fn set_artwork_cache_limit_mb(
+    cache_mb: u32,
+    state: tauri::State<ArtworkSettingsState>,
+    cache_state: tauri::State<ArtworkCacheState>,
+) -> Result<(), String> {
+    if !(MIN_ARTWORK_CACHE_MB..=MAX_ARTWORK_CACHE_MB).contains(&cache_mb) {
+        return Err(format!(
+            "Artwork cache limit must be between {} MB and {} MB",
+            MIN_ARTWORK_CACHE_MB, MAX_ARTWORK_CACHE_MB
+        ));
+    }
+    info!("[ArtworkSettings] Setting cache_mb to {}", cache_mb);
+    state.set_cache_mb(cache_mb)?;
+    if let Some(cache) = cache_state.cache.as_ref() {
+        cache.resize(mb_to_bytes(cache_mb));
+    }
+    Ok(())
+}
@@ -2,12 +2,24 @@
 //!
 //! Stores user preferences for audio output device, exclusive mode, and DAC passthrough.
 
-use crate::audio::{AlsaPlugin, AudioBackendType};
+use crate::audio::{AlsaPlugin, AudioBackendType, EqConfig, ReplayGainMode};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+/// What to do with tracks above the device's max sample rate when
+/// `limit_quality_to_device` is on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityLimitStrategy {
+    /// Request the highest tier the device plays natively (less bandwidth, no resampling)
+    #[default]
+    RequestLower,
+    /// Fetch the full-quality source and let the output resample it
+    FetchHiresAndResample,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioSettings {
     pub output_device: Option<String>, // None = system default
@@ -39,6 +51,135 @@ pub struct AudioSettings {
     /// When true, tracks with the same format are cross-faded seamlessly via Rodio Sink queueing.
     /// Only works with cached tracks on Rodio backend (not ALSA Direct or streaming).
     pub gapless_enabled: bool,
+    /// Output buffer size in frames (None = backend default).
+    /// Clamped to the device's supported range. For ALSA Direct this sets the hardware
+    /// buffer size (period = 1/10 of it unless `alsa_period_frames` is set).
+    /// Too-small values may cause xruns (dropouts).
+    #[serde(default)]
+    pub buffer_frames: Option<u32>,
+    /// When true, playback resumes automatically when the output device
+    /// is plugged back in after it disappeared mid-playback, or when the
+    /// system default output switches back after a route-change pause.
+    #[serde(default)]
+    pub auto_resume_on_reconnect: bool,
+    /// Parametric equalizer (see audio::equalizer)
+    #[serde(default)]
+    pub eq_config: EqConfig,
+    /// Seconds of audio to re-buffer after a streaming stall before resuming
+    #[serde(default)]
+    pub stream_rebuffer_seconds: u8,
+    /// How long to wait for the streaming buffer (initial or re-buffer) before giving up
+    #[serde(default)]
+    pub stream_max_wait_secs: u32,
+    /// Stream at most CD quality while on a metered connection
+    #[serde(default)]
+    pub limit_quality_on_metered: bool,
+    /// When true, playback moves back to the local player at the last known
+    /// position when a Chromecast drops off the network mid-session.
+    #[serde(default = "default_auto_resume_local_on_cast_loss")]
+    pub auto_resume_local_on_cast_loss: bool,
+    /// Quality used to prefetch upcoming tracks ("MP3", "CD Quality", "Hi-Res", "Hi-Res+").
+    /// None = same as playback. A lower tier keeps gapless continuity on slow links.
+    #[serde(default)]
+    pub prefetch_quality: Option<String>,
+    /// Re-fetch tracks prefetched at the lower prefetch quality at full quality
+    /// when they are played (the upgraded copy is used on the next play or seek back)
+    #[serde(default = "default_upgrade_prefetched_tracks")]
+    pub upgrade_prefetched_tracks: bool,
+    /// L1 (memory) audio cache size in MB
+    #[serde(default = "default_memory_cache_mb")]
+    pub memory_cache_mb: u32,
+    /// L2 (disk) playback cache size in MB
+    #[serde(default = "default_disk_cache_mb")]
+    pub disk_cache_mb: u32,
+    /// Skip leading/trailing silence at track boundaries (kept between
+    /// contiguous album tracks). Changes samples, so off by default.
+    #[serde(default)]
+    pub silence_trim_enabled: bool,
+    /// Level below which audio counts as silence, in dBFS
+    #[serde(default = "default_silence_trim_threshold_db")]
+    pub silence_trim_threshold_db: f32,
+    /// ReplayGain value used by normalization (auto = album gain when playing an album)
+    #[serde(default)]
+    pub replaygain_mode: ReplayGainMode,
+    /// How long the output device is held after pausing before it is released.
+    /// Longer avoids the re-acquire gap some DACs have on quick resumes; shorter
+    /// lets the device and audio server idle sooner, which saves battery.
+    #[serde(default = "default_pause_release_ms")]
+    pub pause_release_ms: u32,
+    /// Never release the output device while paused (no re-acquire latency,
+    /// but the device and audio server stay awake)
+    #[serde(default)]
+    pub keep_device_open: bool,
+    /// Tiers tried in order when the requested one isn't available for a track
+    /// ("Hi-Res+", "Hi-Res", "CD Quality", "MP3"). Tiers above the playback
+    /// quality are skipped; leaving one out means never falling back to it.
+    #[serde(default = "default_quality_fallback_order")]
+    pub quality_fallback_order: Vec<String>,
+    /// Stop playback and release the device (and cast connection) after being
+    /// paused this long, in minutes (0 = never)
+    #[serde(default)]
+    pub inactivity_stop_minutes: u32,
+    /// Also stop, and disconnect, a cast device left paused that long
+    #[serde(default)]
+    pub inactivity_stop_while_casting: bool,
+    /// ALSA Direct period size in frames (None = 1/10 of the buffer).
+    /// Capped at half the buffer so at least two periods fit.
+    #[serde(default)]
+    pub alsa_period_frames: Option<u32>,
+    /// How `limit_quality_to_device` limits tracks above the device's rate
+    #[serde(default)]
+    pub quality_limit_strategy: QualityLimitStrategy,
+    /// Pause when the system default output switches away from the sink we
+    /// were playing on (e.g. Bluetooth headphones disconnect), instead of
+    /// continuing on the new default. Only applies to the system default
+    /// output on PipeWire/PulseAudio.
+    #[serde(default = "default_pause_on_audio_route_change")]
+    pub pause_on_audio_route_change: bool,
+    /// Move playback back to `output_device` when it is plugged in again
+    /// after the player fell back to the system default. Off keeps playing on
+    /// the default until the user picks the device.
+    #[serde(default = "default_switch_to_reconnected_device")]
+    pub switch_to_reconnected_device: bool,
+}
+
+fn default_pause_on_audio_route_change() -> bool {
+    true
+}
+
+fn default_switch_to_reconnected_device() -> bool {
+    true
+}
+
+fn default_auto_resume_local_on_cast_loss() -> bool {
+    true
+}
+
+fn default_upgrade_prefetched_tracks() -> bool {
+    true
+}
+
+/// Known quality tiers, highest first (also the default fallback order)
+pub const QUALITY_TIERS: [&str; 4] = ["Hi-Res+", "Hi-Res", "CD Quality", "MP3"];
+
+fn default_quality_fallback_order() -> Vec<String> {
+    QUALITY_TIERS.iter().map(|tier| tier.to_string()).collect()
+}
+
+fn default_memory_cache_mb() -> u32 {
+    crate::cache::DEFAULT_MEMORY_CACHE_MB
+}
+
+fn default_disk_cache_mb() -> u32 {
+    crate::cache::DEFAULT_DISK_CACHE_MB
+}
+
+fn default_silence_trim_threshold_db() -> f32 {
+    DEFAULT_SILENCE_TRIM_THRESHOLD_DB
+}
+
+fn default_pause_release_ms() -> u32 {
+    DEFAULT_PAUSE_RELEASE_MS
 }
 
 impl Default for AudioSettings {
@@ -59,10 +200,58 @@ impl Default for AudioSettings {
             normalization_enabled: false, // Off by default — preserves bit-perfect pipeline
             normalization_target_lufs: -14.0, // Spotify/YouTube standard
             gapless_enabled: false, // Off by default — user opts in
+            buffer_frames: None, // Backend default
+            auto_resume_on_reconnect: false, // Off by default — reconnect only reinitializes
+            eq_config: EqConfig::default(), // Flat, bypassed
+            stream_rebuffer_seconds: 3, // 3 seconds of audio
+            stream_max_wait_secs: 30, // 30 seconds
+            limit_quality_on_metered: false, // Disabled by default
+            auto_resume_local_on_cast_loss: true, // Enabled by default
+            prefetch_quality: None, // Same as playback quality
+            upgrade_prefetched_tracks: true, // Enabled by default
+            memory_cache_mb: crate::cache::DEFAULT_MEMORY_CACHE_MB, // ~3-4 Hi-Res tracks
+            disk_cache_mb: crate::cache::DEFAULT_DISK_CACHE_MB,
+            silence_trim_enabled: false, // Off by default — preserves bit-perfect pipeline
+            silence_trim_threshold_db: DEFAULT_SILENCE_TRIM_THRESHOLD_DB,
+            replaygain_mode: ReplayGainMode::Auto, // Album gain for albums, track gain otherwise
+            pause_release_ms: DEFAULT_PAUSE_RELEASE_MS,
+            keep_device_open: false, // Release the device when paused
+            quality_fallback_order: default_quality_fallback_order(), // Every tier, highest first
+            inactivity_stop_minutes: 0, // Never stop on inactivity
+            inactivity_stop_while_casting: false,
+            alsa_period_frames: None, // 1/10 of the buffer
+            quality_limit_strategy: QualityLimitStrategy::RequestLower,
+            pause_on_audio_route_change: true, // Don't keep playing on the speakers
+            switch_to_reconnected_device: true,
         }
     }
 }
 
+/// Smallest output buffer accepted from the user (frames)
+pub const MIN_BUFFER_FRAMES: u32 = 64;
+/// Largest output buffer accepted from the user (frames)
+pub const MAX_BUFFER_FRAMES: u32 = 65536;
+
+/// Smallest ALSA period accepted from the user (frames)
+pub const MIN_PERIOD_FRAMES: u32 = 32;
+/// Largest ALSA period accepted from the user (frames)
+pub const MAX_PERIOD_FRAMES: u32 = MAX_BUFFER_FRAMES / 2;
+
+/// Default silence threshold for trimming (dBFS)
+pub const DEFAULT_SILENCE_TRIM_THRESHOLD_DB: f32 = -60.0;
+/// Quietest accepted silence threshold (dBFS)
+pub const MIN_SILENCE_TRIM_THRESHOLD_DB: f32 = -90.0;
+/// Loudest accepted silence threshold (dBFS)
+pub const MAX_SILENCE_TRIM_THRESHOLD_DB: f32 = -30.0;
+
+/// Default hold of the output device after pausing (ms)
+pub const DEFAULT_PAUSE_RELEASE_MS: u32 = 2000;
+/// Longest accepted hold after pausing (ms); use `keep_device_open` beyond that
+pub const MAX_PAUSE_RELEASE_MS: u32 = 300_000;
+
+/// Longest accepted inactivity timeout (minutes)
+pub const MAX_INACTIVITY_STOP_MINUTES: u32 = 24 * 60;
+
 pub struct AudioSettingsStore {
     conn: Connection,
 }
@@ -139,6 +328,98 @@ impl AudioSettingsStore {
             "ALTER TABLE audio_settings ADD COLUMN gapless_enabled INTEGER DEFAULT 0",
             [],
         );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN buffer_frames INTEGER",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN auto_resume_on_reconnect INTEGER DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN eq_config TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN stream_rebuffer_seconds INTEGER DEFAULT 3",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN stream_max_wait_secs INTEGER DEFAULT 30",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN limit_quality_on_metered INTEGER DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN auto_resume_local_on_cast_loss INTEGER DEFAULT 1",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN prefetch_quality TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN upgrade_prefetched_tracks INTEGER DEFAULT 1",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN memory_cache_mb INTEGER DEFAULT 300",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN disk_cache_mb INTEGER DEFAULT 500",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN silence_trim_enabled INTEGER DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN silence_trim_threshold_db REAL DEFAULT -60.0",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN replaygain_mode TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN pause_release_ms INTEGER DEFAULT 2000",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN keep_device_open INTEGER DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN quality_fallback_order TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN inactivity_stop_minutes INTEGER DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN inactivity_stop_while_casting INTEGER DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN alsa_period_frames INTEGER",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN quality_limit_strategy TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN pause_on_audio_route_change INTEGER DEFAULT 1",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE audio_settings ADD COLUMN switch_to_reconnected_device INTEGER DEFAULT 1",
+            [],
+        );
 
         Ok(Self { conn })
     }
@@ -157,7 +438,7 @@ impl AudioSettingsStore {
     pub fn get_settings(&self) -> Result<AudioSettings, String> {
         self.conn
             .query_row(
-                "SELECT output_device, exclusive_mode, dac_passthrough, preferred_sample_rate, backend_type, alsa_plugin, alsa_hardware_volume, stream_first_track, stream_buffer_seconds, streaming_only, limit_quality_to_device, device_max_sample_rate, normalization_enabled, normalization_target_lufs, gapless_enabled FROM audio_settings WHERE id = 1",
+                "SELECT output_device, exclusive_mode, dac_passthrough, preferred_sample_rate, backend_type, alsa_plugin, alsa_hardware_volume, stream_first_track, stream_buffer_seconds, streaming_only, limit_quality_to_device, device_max_sample_rate, normalization_enabled, normalization_target_lufs, gapless_enabled, buffer_frames, auto_resume_on_reconnect, eq_config, stream_rebuffer_seconds, stream_max_wait_secs, limit_quality_on_metered, auto_resume_local_on_cast_loss, prefetch_quality, upgrade_prefetched_tracks, memory_cache_mb, disk_cache_mb, silence_trim_enabled, silence_trim_threshold_db, replaygain_mode, pause_release_ms, keep_device_open, quality_fallback_order, inactivity_stop_minutes, inactivity_stop_while_casting, alsa_period_frames, quality_limit_strategy, pause_on_audio_route_change, switch_to_reconnected_device FROM audio_settings WHERE id = 1",
                 [],
                 |row| {
                     // Parse backend_type from JSON string
@@ -186,6 +467,29 @@ impl AudioSettingsStore {
                         normalization_enabled: row.get::<_, Option<i64>>(12)?.unwrap_or(0) != 0,
                         normalization_target_lufs: row.get::<_, Option<f64>>(13)?.unwrap_or(-14.0) as f32,
                         gapless_enabled: row.get::<_, Option<i64>>(14)?.unwrap_or(0) != 0,
+                        buffer_frames: row.get::<_, Option<i64>>(15)?.map(|f| f as u32),
+                        auto_resume_on_reconnect: row.get::<_, Option<i64>>(16)?.unwrap_or(0) != 0,
+                        eq_config: row.get::<_, Option<String>>(17)?.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
+                        stream_rebuffer_seconds: row.get::<_, Option<i64>>(18)?.unwrap_or(3) as u8,
+                        stream_max_wait_secs: row.get::<_, Option<i64>>(19)?.unwrap_or(30) as u32,
+                        limit_quality_on_metered: row.get::<_, Option<i64>>(20)?.unwrap_or(0) != 0,
+                        auto_resume_local_on_cast_loss: row.get::<_, Option<i64>>(21)?.unwrap_or(1) != 0,
+                        prefetch_quality: row.get(22)?,
+                        upgrade_prefetched_tracks: row.get::<_, Option<i64>>(23)?.unwrap_or(1) != 0,
+                        memory_cache_mb: row.get::<_, Option<i64>>(24)?.map(|mb| mb as u32).unwrap_or_else(default_memory_cache_mb),
+                        disk_cache_mb: row.get::<_, Option<i64>>(25)?.map(|mb| mb as u32).unwrap_or_else(default_disk_cache_mb),
+                        silence_trim_enabled: row.get::<_, Option<i64>>(26)?.unwrap_or(0) != 0,
+                        silence_trim_threshold_db: row.get::<_, Option<f64>>(27)?.map(|db| db as f32).unwrap_or(DEFAULT_SILENCE_TRIM_THRESHOLD_DB),
+                        replaygain_mode: row.get::<_, Option<String>>(28)?.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
+                        pause_release_ms: row.get::<_, Option<i64>>(29)?.map(|ms| ms as u32).unwrap_or(DEFAULT_PAUSE_RELEASE_MS),
+                        keep_device_open: row.get::<_, Option<i64>>(30)?.unwrap_or(0) != 0,
+                        quality_fallback_order: row.get::<_, Option<String>>(31)?.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_else(default_quality_fallback_order),
+                        inactivity_stop_minutes: row.get::<_, Option<i64>>(32)?.map(|m| m as u32).unwrap_or(0),
+                        inactivity_stop_while_casting: row.get::<_, Option<i64>>(33)?.unwrap_or(0) != 0,
+                        alsa_period_frames: row.get::<_, Option<i64>>(34)?.map(|f| f as u32),
+                        quality_limit_strategy: row.get::<_, Option<String>>(35)?.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
+                        pause_on_audio_route_change: row.get::<_, Option<i64>>(36)?.unwrap_or(1) != 0,
+                        switch_to_reconnected_device: row.get::<_, Option<i64>>(37)?.unwrap_or(1) != 0,
                     })
                 },
             )
@@ -344,6 +648,29 @@ impl AudioSettingsStore {
         Ok(())
     }
 
+    pub fn set_buffer_frames(&self, frames: Option<u32>) -> Result<(), String> {
+        // Clamp to a sane range; the device range is applied when the stream is opened
+        let clamped = frames.map(|f| f.clamp(MIN_BUFFER_FRAMES, MAX_BUFFER_FRAMES));
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET buffer_frames = ?1 WHERE id = 1",
+                params![clamped.map(|f| f as i64)],
+            )
+            .map_err(|e| format!("Failed to set buffer frames: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_alsa_period_frames(&self, frames: Option<u32>) -> Result<(), String> {
+        let clamped = frames.map(|f| f.clamp(MIN_PERIOD_FRAMES, MAX_PERIOD_FRAMES));
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET alsa_period_frames = ?1 WHERE id = 1",
+                params![clamped.map(|f| f as i64)],
+            )
+            .map_err(|e| format!("Failed to set ALSA period frames: {}", e))?;
+        Ok(())
+    }
+
     pub fn set_normalization_target_lufs(&self, target: f32) -> Result<(), String> {
         self.conn
             .execute(
@@ -354,6 +681,221 @@ impl AudioSettingsStore {
         Ok(())
     }
 
+    pub fn set_auto_resume_on_reconnect(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET auto_resume_on_reconnect = ?1 WHERE id = 1",
+                params![enabled as i64],
+            )
+            .map_err(|e| format!("Failed to set auto resume on reconnect: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_eq_config(&self, config: &EqConfig) -> Result<(), String> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| format!("Failed to serialize EQ config: {}", e))?;
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET eq_config = ?1 WHERE id = 1",
+                params![json],
+            )
+            .map_err(|e| format!("Failed to set EQ config: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_stream_rebuffer_seconds(&self, seconds: u8) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET stream_rebuffer_seconds = ?1 WHERE id = 1",
+                params![seconds.clamp(1, 30) as i64],
+            )
+            .map_err(|e| format!("Failed to set stream rebuffer seconds: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_stream_max_wait_secs(&self, seconds: u32) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET stream_max_wait_secs = ?1 WHERE id = 1",
+                params![seconds.clamp(5, 300) as i64],
+            )
+            .map_err(|e| format!("Failed to set stream max wait: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_limit_quality_on_metered(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET limit_quality_on_metered = ?1 WHERE id = 1",
+                params![enabled as i64],
+            )
+            .map_err(|e| format!("Failed to set limit quality on metered: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_pause_on_audio_route_change(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET pause_on_audio_route_change = ?1 WHERE id = 1",
+                params![enabled as i64],
+            )
+            .map_err(|e| format!("Failed to set pause_on_audio_route_change: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_switch_to_reconnected_device(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET switch_to_reconnected_device = ?1 WHERE id = 1",
+                params![enabled as i64],
+            )
+            .map_err(|e| format!("Failed to set switch_to_reconnected_device: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_auto_resume_local_on_cast_loss(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET auto_resume_local_on_cast_loss = ?1 WHERE id = 1",
+                params![enabled as i64],
+            )
+            .map_err(|e| format!("Failed to set auto_resume_local_on_cast_loss: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_prefetch_quality(&self, quality: Option<String>) -> Result<(), String> {
+        if let Some(q) = quality.as_deref() {
+            if !matches!(q, "MP3" | "CD Quality" | "Hi-Res" | "Hi-Res+") {
+                return Err(format!("Unknown prefetch quality: {}", q));
+            }
+        }
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET prefetch_quality = ?1 WHERE id = 1",
+                params![quality],
+            )
+            .map_err(|e| format!("Failed to set prefetch_quality: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_upgrade_prefetched_tracks(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET upgrade_prefetched_tracks = ?1 WHERE id = 1",
+                params![enabled as i64],
+            )
+            .map_err(|e| format!("Failed to set upgrade_prefetched_tracks: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_cache_limits_mb(&self, memory_mb: u32, disk_mb: u32) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET memory_cache_mb = ?1, disk_cache_mb = ?2 WHERE id = 1",
+                params![memory_mb as i64, disk_mb as i64],
+            )
+            .map_err(|e| format!("Failed to set cache limits: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_silence_trim(&self, enabled: bool, threshold_db: f32) -> Result<(), String> {
+        let threshold_db = threshold_db.clamp(MIN_SILENCE_TRIM_THRESHOLD_DB, MAX_SILENCE_TRIM_THRESHOLD_DB);
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET silence_trim_enabled = ?1, silence_trim_threshold_db = ?2 WHERE id = 1",
+                params![enabled as i64, threshold_db as f64],
+            )
+            .map_err(|e| format!("Failed to set silence trimming: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_replaygain_mode(&self, mode: ReplayGainMode) -> Result<(), String> {
+        let mode_json = serde_json::to_string(&mode)
+            .map_err(|e| format!("Failed to serialize ReplayGain mode: {}", e))?;
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET replaygain_mode = ?1 WHERE id = 1",
+                params![mode_json],
+            )
+            .map_err(|e| format!("Failed to set ReplayGain mode: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_quality_limit_strategy(&self, strategy: QualityLimitStrategy) -> Result<(), String> {
+        let strategy_json = serde_json::to_string(&strategy)
+            .map_err(|e| format!("Failed to serialize quality limit strategy: {}", e))?;
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET quality_limit_strategy = ?1 WHERE id = 1",
+                params![strategy_json],
+            )
+            .map_err(|e| format!("Failed to set quality limit strategy: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_pause_release_ms(&self, ms: u32) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET pause_release_ms = ?1 WHERE id = 1",
+                params![ms.min(MAX_PAUSE_RELEASE_MS) as i64],
+            )
+            .map_err(|e| format!("Failed to set pause_release_ms: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_keep_device_open(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET keep_device_open = ?1 WHERE id = 1",
+                params![enabled as i64],
+            )
+            .map_err(|e| format!("Failed to set keep_device_open: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_inactivity_stop_minutes(&self, minutes: u32) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET inactivity_stop_minutes = ?1 WHERE id = 1",
+                params![minutes.min(MAX_INACTIVITY_STOP_MINUTES) as i64],
+            )
+            .map_err(|e| format!("Failed to set inactivity_stop_minutes: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_inactivity_stop_while_casting(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET inactivity_stop_while_casting = ?1 WHERE id = 1",
+                params![enabled as i64],
+            )
+            .map_err(|e| format!("Failed to set inactivity_stop_while_casting: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_quality_fallback_order(&self, order: &[String]) -> Result<(), String> {
+        if order.is_empty() {
+            return Err("Quality fallback order needs at least one tier".to_string());
+        }
+        for (index, tier) in order.iter().enumerate() {
+            if !QUALITY_TIERS.contains(&tier.as_str()) {
+                return Err(format!("Unknown quality tier: {}", tier));
+            }
+            if order[..index].contains(tier) {
+                return Err(format!("Quality tier listed twice: {}", tier));
+            }
+        }
+        let order_json = serde_json::to_string(order)
+            .map_err(|e| format!("Failed to serialize quality fallback order: {}", e))?;
+        self.conn
+            .execute(
+                "UPDATE audio_settings SET quality_fallback_order = ?1 WHERE id = 1",
+                params![order_json],
+            )
+            .map_err(|e| format!("Failed to set quality_fallback_order: {}", e))?;
+        Ok(())
+    }
+
     /// Reset all audio settings to their default values
     pub fn reset_all(&self) -> Result<AudioSettings, String> {
         let defaults = AudioSettings::default();
@@ -385,7 +927,30 @@ impl AudioSettingsStore {
                     device_max_sample_rate = ?12,
                     normalization_enabled = ?13,
                     normalization_target_lufs = ?14,
-                    gapless_enabled = ?15
+                    gapless_enabled = ?15,
+                    buffer_frames = ?16,
+                    auto_resume_on_reconnect = ?17,
+                    eq_config = ?18,
+                    stream_rebuffer_seconds = ?19,
+                    stream_max_wait_secs = ?20,
+                    limit_quality_on_metered = ?21,
+                    auto_resume_local_on_cast_loss = ?22,
+                    prefetch_quality = ?23,
+                    upgrade_prefetched_tracks = ?24,
+                    memory_cache_mb = ?25,
+                    disk_cache_mb = ?26,
+                    silence_trim_enabled = ?27,
+                    silence_trim_threshold_db = ?28,
+                    replaygain_mode = ?29,
+                    pause_release_ms = ?30,
+                    keep_device_open = ?31,
+                    quality_fallback_order = ?32,
+                    inactivity_stop_minutes = ?33,
+                    inactivity_stop_while_casting = ?34,
+                    alsa_period_frames = ?35,
+                    quality_limit_strategy = ?36,
+                    pause_on_audio_route_change = ?37,
+                    switch_to_reconnected_device = ?38
                 WHERE id = 1",
                 params![
                     defaults.output_device,
@@ -403,6 +968,29 @@ impl AudioSettingsStore {
                     defaults.normalization_enabled as i64,
                     defaults.normalization_target_lufs as f64,
                     defaults.gapless_enabled as i64,
+                    defaults.buffer_frames.map(|f| f as i64),
+                    defaults.auto_resume_on_reconnect as i64,
+                    serde_json::to_string(&defaults.eq_config).ok(),
+                    defaults.stream_rebuffer_seconds as i64,
+                    defaults.stream_max_wait_secs as i64,
+                    defaults.limit_quality_on_metered as i64,
+                    defaults.auto_resume_local_on_cast_loss as i64,
+                    defaults.prefetch_quality,
+                    defaults.upgrade_prefetched_tracks as i64,
+                    defaults.memory_cache_mb as i64,
+                    defaults.disk_cache_mb as i64,
+                    defaults.silence_trim_enabled as i64,
+                    defaults.silence_trim_threshold_db as f64,
+                    serde_json::to_string(&defaults.replaygain_mode).ok(),
+                    defaults.pause_release_ms as i64,
+                    defaults.keep_device_open as i64,
+                    serde_json::to_string(&defaults.quality_fallback_order).ok(),
+                    defaults.inactivity_stop_minutes as i64,
+                    defaults.inactivity_stop_while_casting as i64,
+                    defaults.alsa_period_frames.map(|f| f as i64),
+                    serde_json::to_string(&defaults.quality_limit_strategy).ok(),
+                    defaults.pause_on_audio_route_change as i64,
+                    defaults.switch_to_reconnected_device as i64,
                 ],
             )
             .map_err(|e| format!("Failed to reset audio settings: {}", e))?;
@@ -617,6 +1205,20 @@ pub fn set_audio_limit_quality_to_device(
     store.set_limit_quality_to_device(enabled)
 }
 
+#[tauri::command]
+pub fn set_audio_quality_limit_strategy(
+    state: tauri::State<'_, AudioSettingsState>,
+    strategy: QualityLimitStrategy,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_quality_limit_strategy {:?}", strategy);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_quality_limit_strategy(strategy)
+}
+
 #[tauri::command]
 pub fn set_audio_device_max_sample_rate(
     state: tauri::State<'_, AudioSettingsState>,
@@ -675,10 +1277,289 @@ pub fn set_audio_gapless_enabled(
     store.set_gapless_enabled(enabled)
 }
 
+/// Set the output buffer size in frames (None = backend default).
+/// Takes effect the next time the output stream is opened.
+#[tauri::command]
+pub fn set_audio_buffer_frames(
+    state: tauri::State<'_, AudioSettingsState>,
+    frames: Option<u32>,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_buffer_frames {:?}", frames);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_buffer_frames(frames)
+}
+
+/// Set the ALSA Direct period size in frames (None = 1/10 of the buffer).
+/// Takes effect the next time the output stream is opened.
+#[tauri::command]
+pub fn set_audio_alsa_period_frames(
+    state: tauri::State<'_, AudioSettingsState>,
+    app_state: tauri::State<'_, crate::AppState>,
+    frames: Option<u32>,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_alsa_period_frames {:?}", frames);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_alsa_period_frames(frames)?;
+    app_state.player.reload_settings(store.get_settings()?)
+}
+
+#[tauri::command]
+pub fn set_audio_auto_resume_on_reconnect(
+    state: tauri::State<'_, AudioSettingsState>,
+    enabled: bool,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_auto_resume_on_reconnect {:?}", enabled);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_auto_resume_on_reconnect(enabled)
+}
+
+#[tauri::command]
+pub fn set_audio_stream_rebuffer_seconds(
+    state: tauri::State<'_, AudioSettingsState>,
+    seconds: u8,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_stream_rebuffer_seconds {:?}", seconds);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_stream_rebuffer_seconds(seconds)
+}
+
+#[tauri::command]
+pub fn set_audio_stream_max_wait_secs(
+    state: tauri::State<'_, AudioSettingsState>,
+    seconds: u32,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_stream_max_wait_secs {:?}", seconds);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_stream_max_wait_secs(seconds)
+}
+
+#[tauri::command]
+pub fn set_audio_limit_quality_on_metered(
+    state: tauri::State<'_, AudioSettingsState>,
+    enabled: bool,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_limit_quality_on_metered {:?}", enabled);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_limit_quality_on_metered(enabled)
+}
+
+#[tauri::command]
+pub fn set_audio_pause_on_audio_route_change(
+    state: tauri::State<'_, AudioSettingsState>,
+    enabled: bool,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_pause_on_audio_route_change {:?}", enabled);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_pause_on_audio_route_change(enabled)
+}
+
+#[tauri::command]
+pub fn set_audio_switch_to_reconnected_device(
+    state: tauri::State<'_, AudioSettingsState>,
+    enabled: bool,
+) -> Result<(), String> {
+    log::info!(
+        "Command: set_audio_switch_to_reconnected_device {:?}",
+        enabled
+    );
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_switch_to_reconnected_device(enabled)
+}
+
+#[tauri::command]
+pub fn set_audio_auto_resume_local_on_cast_loss(
+    state: tauri::State<'_, AudioSettingsState>,
+    enabled: bool,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_auto_resume_local_on_cast_loss {:?}", enabled);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_auto_resume_local_on_cast_loss(enabled)
+}
+
+#[tauri::command]
+pub fn set_audio_prefetch_quality(
+    state: tauri::State<'_, AudioSettingsState>,
+    quality: Option<String>,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_prefetch_quality {:?}", quality);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_prefetch_quality(quality)
+}
+
+#[tauri::command]
+pub fn set_audio_upgrade_prefetched_tracks(
+    state: tauri::State<'_, AudioSettingsState>,
+    enabled: bool,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_upgrade_prefetched_tracks {:?}", enabled);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_upgrade_prefetched_tracks(enabled)
+}
+
+/// Enable/disable silence trimming at track boundaries. Applied to the player
+/// right away (from the next track on).
+#[tauri::command]
+pub fn set_audio_silence_trim(
+    state: tauri::State<'_, AudioSettingsState>,
+    app_state: tauri::State<'_, crate::AppState>,
+    enabled: bool,
+    threshold_db: f32,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_silence_trim {} ({} dB)", enabled, threshold_db);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_silence_trim(enabled, threshold_db)?;
+    app_state.player.reload_settings(store.get_settings()?)
+}
+
+#[tauri::command]
+pub fn set_audio_replaygain_mode(
+    state: tauri::State<'_, AudioSettingsState>,
+    app_state: tauri::State<'_, crate::AppState>,
+    mode: ReplayGainMode,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_replaygain_mode {:?}", mode);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_replaygain_mode(mode)?;
+    app_state.player.reload_settings(store.get_settings()?)
+}
+
+#[tauri::command]
+pub fn set_audio_pause_release_ms(
+    state: tauri::State<'_, AudioSettingsState>,
+    app_state: tauri::State<'_, crate::AppState>,
+    ms: u32,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_pause_release_ms {}", ms);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_pause_release_ms(ms)?;
+    app_state.player.reload_settings(store.get_settings()?)
+}
+
+#[tauri::command]
+pub fn set_audio_keep_device_open(
+    state: tauri::State<'_, AudioSettingsState>,
+    app_state: tauri::State<'_, crate::AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_keep_device_open {}", enabled);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_keep_device_open(enabled)?;
+    app_state.player.reload_settings(store.get_settings()?)
+}
+
+#[tauri::command]
+pub fn set_audio_inactivity_stop_minutes(
+    state: tauri::State<'_, AudioSettingsState>,
+    app_state: tauri::State<'_, crate::AppState>,
+    minutes: u32,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_inactivity_stop_minutes {}", minutes);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_inactivity_stop_minutes(minutes)?;
+    app_state.player.reload_settings(store.get_settings()?)
+}
+
+#[tauri::command]
+pub fn set_audio_inactivity_stop_while_casting(
+    state: tauri::State<'_, AudioSettingsState>,
+    app_state: tauri::State<'_, crate::AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_inactivity_stop_while_casting {}", enabled);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_inactivity_stop_while_casting(enabled)?;
+    app_state.player.reload_settings(store.get_settings()?)
+}
+
+/// Set the order of quality tiers tried when a track isn't available in the
+/// playback quality (see `AudioSettings::quality_fallback_order`)
+#[tauri::command]
+pub fn set_audio_quality_fallback_order(
+    state: tauri::State<'_, AudioSettingsState>,
+    order: Vec<String>,
+) -> Result<(), String> {
+    log::info!("Command: set_audio_quality_fallback_order {:?}", order);
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_quality_fallback_order(&order)
+}
+
 #[tauri::command]
 pub fn reset_audio_settings(
     audio_state: tauri::State<'_, AudioSettingsState>,
     playback_state: tauri::State<'_, crate::config::playback_preferences::PlaybackPreferencesState>,
+    app_state: tauri::State<'_, crate::AppState>,
 ) -> Result<AudioSettings, String> {
     log::info!("Command: reset_audio_settings (resetting audio + playback to defaults)");
 
@@ -689,6 +1570,14 @@ pub fn reset_audio_settings(
         .map_err(|e| format!("Lock error: {}", e))?;
     let store = guard.as_ref().ok_or("No active session - please log in")?;
     let defaults = store.reset_all()?;
+    drop(guard);
+
+    // The cache sizes apply right away, like set_cache_limits
+    crate::commands::cache::apply_cache_limits(
+        &app_state,
+        defaults.memory_cache_mb,
+        defaults.disk_cache_mb,
+    );
 
     // Reset playback preferences
     let pb_guard = playback_state
@@ -1,16 +1,20 @@
 //! Download settings persistence
 //!
-//! Stores user preferences for download path and library integration.
+//! Stores user preferences for download path, file naming and library integration.
 
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+use crate::offline_cache::naming::{NamingFields, NamingTemplate, DEFAULT_NAMING_TEMPLATE};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadSettings {
     pub download_root: String,
     pub show_in_library: bool,
+    /// File naming template for downloads (see `offline_cache::naming`)
+    pub naming_template: String,
 }
 
 impl Default for DownloadSettings {
@@ -25,6 +29,7 @@ impl Default for DownloadSettings {
         Self {
             download_root: default_root,
             show_in_library: false,
+            naming_template: DEFAULT_NAMING_TEMPLATE.to_string(),
         }
     }
 }
@@ -56,6 +61,12 @@ impl DownloadSettingsStore {
         )
         .map_err(|e| format!("Failed to create download settings table: {}", e))?;
 
+        // Migration: Add new columns if they don't exist (for existing databases)
+        let _ = conn.execute(
+            "ALTER TABLE download_settings ADD COLUMN naming_template TEXT",
+            [],
+        );
+
         conn.execute(
             "INSERT OR IGNORE INTO download_settings (id, download_root, show_in_library)
              VALUES (1, ?1, 0)",
@@ -80,12 +91,15 @@ impl DownloadSettingsStore {
     pub fn get_settings(&self) -> Result<DownloadSettings, String> {
         self.conn
             .query_row(
-                "SELECT download_root, show_in_library FROM download_settings WHERE id = 1",
+                "SELECT download_root, show_in_library, naming_template FROM download_settings WHERE id = 1",
                 [],
                 |row| {
                     Ok(DownloadSettings {
                         download_root: row.get(0)?,
                         show_in_library: row.get::<_, i64>(1)? != 0,
+                        naming_template: row
+                            .get::<_, Option<String>>(2)?
+                            .unwrap_or_else(|| DEFAULT_NAMING_TEMPLATE.to_string()),
                     })
                 },
             )
@@ -111,6 +125,16 @@ impl DownloadSettingsStore {
             .map_err(|e| format!("Failed to set show_in_library: {}", e))?;
         Ok(())
     }
+
+    pub fn set_naming_template(&self, template: &str) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE download_settings SET naming_template = ?1 WHERE id = 1",
+                params![template],
+            )
+            .map_err(|e| format!("Failed to set naming template: {}", e))?;
+        Ok(())
+    }
 }
 
 pub type DownloadSettingsState = Arc<Mutex<Option<DownloadSettingsStore>>>;
@@ -168,6 +192,45 @@ pub fn set_show_downloads_in_library(
     store.set_show_in_library(show)
 }
 
+#[tauri::command]
+pub fn set_download_naming_template(
+    template: String,
+    state: tauri::State<DownloadSettingsState>,
+) -> Result<(), String> {
+    log::info!("Command: set_download_naming_template to: {}", template);
+    NamingTemplate::parse(&template)?;
+
+    let guard = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.set_naming_template(template.trim())
+}
+
+/// Path a download would get, for a template (the saved one by default) and a
+/// sample track (a built-in one by default)
+#[tauri::command]
+pub fn preview_download_path(
+    template: Option<String>,
+    sample_track: Option<NamingFields>,
+    state: tauri::State<DownloadSettingsState>,
+) -> Result<String, String> {
+    let settings = {
+        let guard = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.get_settings()?
+    };
+
+    let template = NamingTemplate::parse(template.as_deref().unwrap_or(&settings.naming_template))?;
+    let mut fields = sample_track.unwrap_or_else(NamingFields::sample);
+    if fields.format.is_empty() {
+        fields.format = "flac".to_string();
+    }
+
+    Ok(template
+        .render(Path::new(&settings.download_root), &fields, false)
+        .to_string_lossy()
+        .to_string())
+}
+
 #[tauri::command]
 pub fn validate_download_root(path: String) -> Result<bool, String> {
     log::info!("Command: validate_download_root: {}", path);
@@ -8,12 +8,37 @@
 //! Sync strategy:
 //! - On login: Fetch all favorites from API and populate local cache
 //! - On toggle: API call first, then update local cache on success
+//! - On batch changes: local cache first, queued and synced to Qobuz in the
+//!   background (see `commands::favorites`); reverted if the sync keeps failing
 //! - FavoritesView reads from API and syncs local cache
+//!
+//! Sync can optionally carry display metadata (title, artist, genre, quality)
+//! so the cache getters can sort and filter in SQL instead of the frontend.
+//!
+//! Favorite tracks are periodically re-checked against the API so tracks
+//! Qobuz has pulled can be surfaced (see `check_favorites_availability`).
+//!
+//! Favorites can be exported to JSON with their display metadata and
+//! imported back (see `import_favorites_json`), which favorites the items on
+//! Qobuz and then updates the cache.
 
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, Connection};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::api::QobuzClient;
+
+/// A favorite track is not re-checked for availability more often than this
+pub const AVAILABILITY_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How often the background availability check runs
+pub const AVAILABILITY_BACKGROUND_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// track/getList accepts at most 50 IDs per call
+const AVAILABILITY_BATCH_SIZE: usize = 50;
 
 /// Represents a cached favorite track
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +58,182 @@ pub struct CachedFavoriteArtist {
     pub artist_id: i64,
 }
 
+/// A favorite track that is no longer streamable on Qobuz
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnavailableFavorite {
+    pub track_id: i64,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    /// When availability was last checked (unix seconds)
+    pub last_checked: i64,
+}
+
+/// Favorite track with the metadata used for sorting/filtering
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FavoriteTrackEntry {
+    pub track_id: i64,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub genre: Option<String>,
+    #[serde(default)]
+    pub release_year: Option<i32>,
+    #[serde(default)]
+    pub hires: bool,
+    #[serde(default)]
+    pub bit_depth: Option<u32>,
+    /// When the track was favorited (unix seconds), if the API reports it
+    #[serde(default)]
+    pub added_at: Option<i64>,
+}
+
+/// Favorite album with the metadata used for sorting/filtering
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FavoriteAlbumEntry {
+    pub album_id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub genre: Option<String>,
+    #[serde(default)]
+    pub release_year: Option<i32>,
+    #[serde(default)]
+    pub hires: bool,
+    #[serde(default)]
+    pub bit_depth: Option<u32>,
+    /// When the album was favorited (unix seconds), if the API reports it
+    #[serde(default)]
+    pub added_at: Option<i64>,
+}
+
+/// Favorite artist with the metadata used for sorting
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FavoriteArtistEntry {
+    pub artist_id: i64,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// When the artist was favorited (unix seconds), if the API reports it
+    #[serde(default)]
+    pub added_at: Option<i64>,
+}
+
+/// Current version of the favorites export format
+pub const FAVORITES_EXPORT_VERSION: u32 = 1;
+
+/// Portable favorites, for backup and moving between accounts or machines.
+/// Titles and names are included so a human can review the file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FavoritesExport {
+    pub version: u32,
+    pub exported_at: i64,
+    #[serde(default)]
+    pub tracks: Vec<FavoriteTrackEntry>,
+    #[serde(default)]
+    pub albums: Vec<FavoriteAlbumEntry>,
+    #[serde(default)]
+    pub artists: Vec<FavoriteArtistEntry>,
+}
+
+/// Result of importing favorites
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FavoritesImportSummary {
+    /// Items favorited on Qobuz
+    pub added: usize,
+    /// Items unfavorited because they were missing from the file (replace only)
+    pub removed: usize,
+    /// Items already favorited or duplicated in the file
+    pub skipped: usize,
+    /// Entries rejected as invalid (missing ID)
+    pub invalid: usize,
+    /// Items the API refused to add or remove
+    pub failed: usize,
+}
+
+/// Sort order for cached favorites
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FavoritesSort {
+    #[default]
+    DateAdded,
+    Artist,
+    Title,
+    ReleaseYear,
+}
+
+/// Sort and filter parameters for the cached favorites getters.
+///
+/// Quality and genre filters only apply to tracks and albums; artists
+/// support sorting only (Artist and Title both sort by name).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FavoritesQuery {
+    #[serde(default)]
+    pub sort: FavoritesSort,
+    /// Reverse the natural order (newest first, A-Z, newest release first)
+    #[serde(default)]
+    pub reverse: bool,
+    /// Only hi-res items
+    #[serde(default)]
+    pub hires_only: bool,
+    /// Minimum bit depth (e.g. 24)
+    #[serde(default)]
+    pub min_bit_depth: Option<u32>,
+    /// Case-insensitive genre match
+    #[serde(default)]
+    pub genre: Option<String>,
+}
+
+impl FavoritesQuery {
+    /// WHERE clause and its parameters for a table with quality/genre columns
+    fn where_clause(&self) -> (String, Vec<rusqlite::types::Value>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if self.hires_only {
+            conditions.push("hires = 1".to_string());
+        }
+        if let Some(bits) = self.min_bit_depth {
+            values.push(rusqlite::types::Value::Integer(bits as i64));
+            conditions.push(format!("bit_depth >= ?{}", values.len()));
+        }
+        if let Some(ref genre) = self.genre {
+            values.push(rusqlite::types::Value::Text(genre.clone()));
+            conditions.push(format!("genre = ?{} COLLATE NOCASE", values.len()));
+        }
+
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!(" WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+
+    /// ORDER BY clause; `title_col` is the column used for Title sorting.
+    /// Rows without metadata always sort last; ties keep the date-added order.
+    fn order_clause(&self, title_col: &str, artist_col: &str) -> String {
+        let (col, natural_desc) = match self.sort {
+            FavoritesSort::DateAdded => ("position", true),
+            FavoritesSort::Artist => (artist_col, false),
+            FavoritesSort::Title => (title_col, false),
+            FavoritesSort::ReleaseYear => ("release_year", true),
+        };
+        let desc = natural_desc != self.reverse;
+        let collate = match self.sort {
+            FavoritesSort::Artist | FavoritesSort::Title => " COLLATE NOCASE",
+            _ => "",
+        };
+        format!(
+            " ORDER BY {col} IS NULL, {col}{collate} {dir}, position {dir}",
+            col = col,
+            collate = collate,
+            dir = if desc { "DESC" } else { "ASC" }
+        )
+    }
+}
+
 pub struct FavoritesCacheStore {
     conn: Connection,
 }
@@ -77,6 +278,36 @@ impl FavoritesCacheStore {
         )
         .map_err(|e| format!("Failed to create favorite_artists table: {}", e))?;
 
+        // Migrations: sort/filter metadata (NULL until synced with metadata)
+        for table in ["favorite_tracks", "favorite_albums"] {
+            for column in [
+                "title TEXT",
+                "artist TEXT",
+                "genre TEXT",
+                "release_year INTEGER",
+                "hires INTEGER NOT NULL DEFAULT 0",
+                "bit_depth INTEGER",
+                "added_at INTEGER",
+            ] {
+                let _ = conn.execute(&format!("ALTER TABLE {} ADD COLUMN {}", table, column), []);
+            }
+        }
+        let _ = conn.execute(
+            "ALTER TABLE favorite_tracks ADD COLUMN unavailable INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute("ALTER TABLE favorite_tracks ADD COLUMN last_checked INTEGER", []);
+        let _ = conn.execute("ALTER TABLE favorite_artists ADD COLUMN name TEXT", []);
+        let _ = conn.execute("ALTER TABLE favorite_artists ADD COLUMN added_at INTEGER", []);
+        // Date-added order (higher = added later). Integer primary keys make
+        // rowid the Qobuz ID, so insertion order has to be stored explicitly.
+        for table in ["favorite_tracks", "favorite_albums", "favorite_artists"] {
+            let _ = conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN position INTEGER", table),
+                [],
+            );
+        }
+
         Ok(Self { conn })
     }
 
@@ -91,6 +322,28 @@ impl FavoritesCacheStore {
         Self::open_at(base_dir, "favorites_cache.db")
     }
 
+    fn query_ids<T: rusqlite::types::FromSql>(
+        &self,
+        sql: &str,
+        values: Vec<rusqlite::types::Value>,
+        what: &str,
+    ) -> Result<Vec<T>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(sql)
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = stmt
+            .query_map(params_from_iter(values.iter()), |row| row.get(0))
+            .map_err(|e| format!("Failed to query favorite {}: {}", what, e))?;
+
+        let mut ids = Vec::new();
+        for row in rows {
+            ids.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+        }
+        Ok(ids)
+    }
+
     // ============ Track favorites ============
 
     pub fn get_favorite_track_ids(&self) -> Result<Vec<i64>, String> {
@@ -110,6 +363,17 @@ impl FavoritesCacheStore {
         Ok(ids)
     }
 
+    /// Favorite track IDs sorted and filtered in SQL
+    pub fn query_favorite_track_ids(&self, query: &FavoritesQuery) -> Result<Vec<i64>, String> {
+        let (where_sql, values) = query.where_clause();
+        let sql = format!(
+            "SELECT track_id FROM favorite_tracks{}{}",
+            where_sql,
+            query.order_clause("title", "artist")
+        );
+        self.query_ids(&sql, values, "tracks")
+    }
+
     pub fn is_track_favorite(&self, track_id: i64) -> Result<bool, String> {
         let mut stmt = self
             .conn
@@ -126,7 +390,8 @@ impl FavoritesCacheStore {
     pub fn add_favorite_track(&self, track_id: i64) -> Result<(), String> {
         self.conn
             .execute(
-                "INSERT OR IGNORE INTO favorite_tracks (track_id) VALUES (?1)",
+                "INSERT OR IGNORE INTO favorite_tracks (track_id, position)
+                 VALUES (?1, (SELECT COALESCE(MAX(position), 0) + 1 FROM favorite_tracks))",
                 params![track_id],
             )
             .map_err(|e| format!("Failed to add favorite track: {}", e))?;
@@ -144,22 +409,130 @@ impl FavoritesCacheStore {
     }
 
     pub fn sync_favorite_tracks(&self, track_ids: &[i64]) -> Result<(), String> {
+        let entries: Vec<FavoriteTrackEntry> = track_ids
+            .iter()
+            .map(|&track_id| FavoriteTrackEntry {
+                track_id,
+                ..Default::default()
+            })
+            .collect();
+        self.sync_favorite_track_entries(&entries)
+    }
+
+    /// Replace cached favorite tracks, storing their sort/filter metadata.
+    /// Availability check results survive the sync for tracks still in the list.
+    pub fn sync_favorite_track_entries(&self, entries: &[FavoriteTrackEntry]) -> Result<(), String> {
+        let checks = self.availability_checks()?;
+
         // Clear existing and insert new
         self.conn
             .execute("DELETE FROM favorite_tracks", [])
             .map_err(|e| format!("Failed to clear favorite tracks: {}", e))?;
 
-        for &track_id in track_ids {
+        // The API lists newest first; number oldest first so position follows date added
+        for (position, entry) in entries.iter().rev().enumerate() {
             self.conn
                 .execute(
-                    "INSERT INTO favorite_tracks (track_id) VALUES (?1)",
-                    params![track_id],
+                    "INSERT OR IGNORE INTO favorite_tracks
+                        (track_id, title, artist, genre, release_year, hires, bit_depth, added_at,
+                         unavailable, last_checked, position)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    params![
+                        entry.track_id,
+                        entry.title,
+                        entry.artist,
+                        entry.genre,
+                        entry.release_year,
+                        entry.hires,
+                        entry.bit_depth,
+                        entry.added_at,
+                        checks.get(&entry.track_id).map(|c| c.0).unwrap_or(false),
+                        checks.get(&entry.track_id).map(|c| c.1),
+                        position as i64 + 1,
+                    ],
                 )
                 .map_err(|e| format!("Failed to insert favorite track: {}", e))?;
         }
         Ok(())
     }
 
+    /// (unavailable, last_checked) for every track checked at least once
+    fn availability_checks(&self) -> Result<HashMap<i64, (bool, i64)>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT track_id, unavailable, last_checked FROM favorite_tracks
+                 WHERE last_checked IS NOT NULL",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))
+            .map_err(|e| format!("Failed to query availability checks: {}", e))?;
+
+        let mut checks = HashMap::new();
+        for row in rows {
+            let (track_id, check) = row.map_err(|e| format!("Failed to read row: {}", e))?;
+            checks.insert(track_id, check);
+        }
+        Ok(checks)
+    }
+
+    /// Favorite track IDs never checked or last checked before `checked_before`
+    pub fn track_ids_due_for_check(&self, checked_before: i64) -> Result<Vec<i64>, String> {
+        self.query_ids(
+            "SELECT track_id FROM favorite_tracks
+             WHERE last_checked IS NULL OR last_checked < ?1",
+            vec![rusqlite::types::Value::Integer(checked_before)],
+            "tracks",
+        )
+    }
+
+    /// Record an availability check result
+    pub fn set_track_availability(
+        &self,
+        track_id: i64,
+        unavailable: bool,
+        checked_at: i64,
+    ) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE favorite_tracks SET unavailable = ?1, last_checked = ?2 WHERE track_id = ?3",
+                params![unavailable, checked_at, track_id],
+            )
+            .map_err(|e| format!("Failed to update track availability: {}", e))?;
+        Ok(())
+    }
+
+    /// Favorite tracks marked unavailable by the last check, most recently added first
+    pub fn get_unavailable_favorites(&self) -> Result<Vec<UnavailableFavorite>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT track_id, title, artist, last_checked FROM favorite_tracks
+                 WHERE unavailable = 1
+                 ORDER BY position IS NULL, position DESC",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(UnavailableFavorite {
+                    track_id: row.get(0)?,
+                    title: row.get(1)?,
+                    artist: row.get(2)?,
+                    last_checked: row.get::<_, Option<i64>>(3)?.unwrap_or(0),
+                })
+            })
+            .map_err(|e| format!("Failed to query unavailable favorites: {}", e))?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+        }
+        Ok(result)
+    }
+
     // ============ Album favorites ============
 
     pub fn get_favorite_album_ids(&self) -> Result<Vec<String>, String> {
@@ -179,6 +552,17 @@ impl FavoritesCacheStore {
         Ok(ids)
     }
 
+    /// Favorite album IDs sorted and filtered in SQL
+    pub fn query_favorite_album_ids(&self, query: &FavoritesQuery) -> Result<Vec<String>, String> {
+        let (where_sql, values) = query.where_clause();
+        let sql = format!(
+            "SELECT album_id FROM favorite_albums{}{}",
+            where_sql,
+            query.order_clause("title", "artist")
+        );
+        self.query_ids(&sql, values, "albums")
+    }
+
     pub fn is_album_favorite(&self, album_id: &str) -> Result<bool, String> {
         let mut stmt = self
             .conn
@@ -195,7 +579,8 @@ impl FavoritesCacheStore {
     pub fn add_favorite_album(&self, album_id: &str) -> Result<(), String> {
         self.conn
             .execute(
-                "INSERT OR IGNORE INTO favorite_albums (album_id) VALUES (?1)",
+                "INSERT OR IGNORE INTO favorite_albums (album_id, position)
+                 VALUES (?1, (SELECT COALESCE(MAX(position), 0) + 1 FROM favorite_albums))",
                 params![album_id],
             )
             .map_err(|e| format!("Failed to add favorite album: {}", e))?;
@@ -213,15 +598,41 @@ impl FavoritesCacheStore {
     }
 
     pub fn sync_favorite_albums(&self, album_ids: &[String]) -> Result<(), String> {
+        let entries: Vec<FavoriteAlbumEntry> = album_ids
+            .iter()
+            .map(|album_id| FavoriteAlbumEntry {
+                album_id: album_id.clone(),
+                ..Default::default()
+            })
+            .collect();
+        self.sync_favorite_album_entries(&entries)
+    }
+
+    /// Replace cached favorite albums, storing their sort/filter metadata
+    pub fn sync_favorite_album_entries(&self, entries: &[FavoriteAlbumEntry]) -> Result<(), String> {
         self.conn
             .execute("DELETE FROM favorite_albums", [])
             .map_err(|e| format!("Failed to clear favorite albums: {}", e))?;
 
-        for album_id in album_ids {
+        // The API lists newest first; number oldest first so position follows date added
+        for (position, entry) in entries.iter().rev().enumerate() {
             self.conn
                 .execute(
-                    "INSERT INTO favorite_albums (album_id) VALUES (?1)",
-                    params![album_id],
+                    "INSERT OR IGNORE INTO favorite_albums
+                        (album_id, title, artist, genre, release_year, hires, bit_depth, added_at,
+                         position)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        entry.album_id,
+                        entry.title,
+                        entry.artist,
+                        entry.genre,
+                        entry.release_year,
+                        entry.hires,
+                        entry.bit_depth,
+                        entry.added_at,
+                        position as i64 + 1,
+                    ],
                 )
                 .map_err(|e| format!("Failed to insert favorite album: {}", e))?;
         }
@@ -247,6 +658,20 @@ impl FavoritesCacheStore {
         Ok(ids)
     }
 
+    /// Favorite artist IDs sorted in SQL (filters do not apply to artists)
+    pub fn query_favorite_artist_ids(&self, query: &FavoritesQuery) -> Result<Vec<i64>, String> {
+        let order = match query.sort {
+            FavoritesSort::ReleaseYear => FavoritesQuery {
+                sort: FavoritesSort::DateAdded,
+                ..query.clone()
+            }
+            .order_clause("name", "name"),
+            _ => query.order_clause("name", "name"),
+        };
+        let sql = format!("SELECT artist_id FROM favorite_artists{}", order);
+        self.query_ids(&sql, Vec::new(), "artists")
+    }
+
     pub fn is_artist_favorite(&self, artist_id: i64) -> Result<bool, String> {
         let mut stmt = self
             .conn
@@ -263,7 +688,8 @@ impl FavoritesCacheStore {
     pub fn add_favorite_artist(&self, artist_id: i64) -> Result<(), String> {
         self.conn
             .execute(
-                "INSERT OR IGNORE INTO favorite_artists (artist_id) VALUES (?1)",
+                "INSERT OR IGNORE INTO favorite_artists (artist_id, position)
+                 VALUES (?1, (SELECT COALESCE(MAX(position), 0) + 1 FROM favorite_artists))",
                 params![artist_id],
             )
             .map_err(|e| format!("Failed to add favorite artist: {}", e))?;
@@ -281,21 +707,181 @@ impl FavoritesCacheStore {
     }
 
     pub fn sync_favorite_artists(&self, artist_ids: &[i64]) -> Result<(), String> {
+        let entries: Vec<FavoriteArtistEntry> = artist_ids
+            .iter()
+            .map(|&artist_id| FavoriteArtistEntry {
+                artist_id,
+                ..Default::default()
+            })
+            .collect();
+        self.sync_favorite_artist_entries(&entries)
+    }
+
+    /// Replace cached favorite artists, storing their sort metadata
+    pub fn sync_favorite_artist_entries(&self, entries: &[FavoriteArtistEntry]) -> Result<(), String> {
         self.conn
             .execute("DELETE FROM favorite_artists", [])
             .map_err(|e| format!("Failed to clear favorite artists: {}", e))?;
 
-        for &artist_id in artist_ids {
+        // The API lists newest first; number oldest first so position follows date added
+        for (position, entry) in entries.iter().rev().enumerate() {
             self.conn
                 .execute(
-                    "INSERT INTO favorite_artists (artist_id) VALUES (?1)",
-                    params![artist_id],
+                    "INSERT OR IGNORE INTO favorite_artists (artist_id, name, added_at, position)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        entry.artist_id,
+                        entry.name,
+                        entry.added_at,
+                        position as i64 + 1
+                    ],
                 )
                 .map_err(|e| format!("Failed to insert favorite artist: {}", e))?;
         }
         Ok(())
     }
 
+    // ============ Export / import ============
+
+    /// All cached favorites with their metadata, newest first
+    pub fn export_entries(&self) -> Result<FavoritesExport, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT track_id, title, artist, genre, release_year, hires, bit_depth, added_at
+                 FROM favorite_tracks ORDER BY position IS NULL, position DESC",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let tracks = stmt
+            .query_map([], |row| {
+                Ok(FavoriteTrackEntry {
+                    track_id: row.get(0)?,
+                    title: row.get(1)?,
+                    artist: row.get(2)?,
+                    genre: row.get(3)?,
+                    release_year: row.get(4)?,
+                    hires: row.get(5)?,
+                    bit_depth: row.get(6)?,
+                    added_at: row.get(7)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query favorite tracks: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read row: {}", e))?;
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT album_id, title, artist, genre, release_year, hires, bit_depth, added_at
+                 FROM favorite_albums ORDER BY position IS NULL, position DESC",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let albums = stmt
+            .query_map([], |row| {
+                Ok(FavoriteAlbumEntry {
+                    album_id: row.get(0)?,
+                    title: row.get(1)?,
+                    artist: row.get(2)?,
+                    genre: row.get(3)?,
+                    release_year: row.get(4)?,
+                    hires: row.get(5)?,
+                    bit_depth: row.get(6)?,
+                    added_at: row.get(7)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query favorite albums: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read row: {}", e))?;
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT artist_id, name, added_at FROM favorite_artists
+                 ORDER BY position IS NULL, position DESC",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let artists = stmt
+            .query_map([], |row| {
+                Ok(FavoriteArtistEntry {
+                    artist_id: row.get(0)?,
+                    name: row.get(1)?,
+                    added_at: row.get(2)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query favorite artists: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read row: {}", e))?;
+
+        Ok(FavoritesExport {
+            version: FAVORITES_EXPORT_VERSION,
+            exported_at: unix_now(),
+            tracks,
+            albums,
+            artists,
+        })
+    }
+
+    /// Add a favorite track with its metadata (no-op if already cached)
+    pub fn add_favorite_track_entry(&self, entry: &FavoriteTrackEntry) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO favorite_tracks
+                    (track_id, title, artist, genre, release_year, hires, bit_depth, added_at,
+                     position)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
+                         (SELECT COALESCE(MAX(position), 0) + 1 FROM favorite_tracks))",
+                params![
+                    entry.track_id,
+                    entry.title,
+                    entry.artist,
+                    entry.genre,
+                    entry.release_year,
+                    entry.hires,
+                    entry.bit_depth,
+                    entry.added_at,
+                ],
+            )
+            .map_err(|e| format!("Failed to add favorite track: {}", e))?;
+        Ok(())
+    }
+
+    /// Add a favorite album with its metadata (no-op if already cached)
+    pub fn add_favorite_album_entry(&self, entry: &FavoriteAlbumEntry) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO favorite_albums
+                    (album_id, title, artist, genre, release_year, hires, bit_depth, added_at,
+                     position)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
+                         (SELECT COALESCE(MAX(position), 0) + 1 FROM favorite_albums))",
+                params![
+                    entry.album_id,
+                    entry.title,
+                    entry.artist,
+                    entry.genre,
+                    entry.release_year,
+                    entry.hires,
+                    entry.bit_depth,
+                    entry.added_at,
+                ],
+            )
+            .map_err(|e| format!("Failed to add favorite album: {}", e))?;
+        Ok(())
+    }
+
+    /// Add a favorite artist with its metadata (no-op if already cached)
+    pub fn add_favorite_artist_entry(&self, entry: &FavoriteArtistEntry) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO favorite_artists (artist_id, name, added_at, position)
+                 VALUES (?1, ?2, ?3,
+                         (SELECT COALESCE(MAX(position), 0) + 1 FROM favorite_artists))",
+                params![entry.artist_id, entry.name, entry.added_at],
+            )
+            .map_err(|e| format!("Failed to add favorite artist: {}", e))?;
+        Ok(())
+    }
+
     // ============ Clear all (for logout) ============
 
     pub fn clear_all(&self) -> Result<(), String> {
@@ -312,6 +898,340 @@ impl FavoritesCacheStore {
     }
 }
 
+// ============ Availability check ============
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Re-check cached favorite tracks against the API and return the ones that
+/// are no longer streamable.
+///
+/// Tracks checked within `AVAILABILITY_CHECK_INTERVAL` are skipped unless
+/// `force` is set. A track missing from the batch response stays unchecked
+/// and is retried on the next run. The store lock is never held across API
+/// calls, and the client lock only for one batch request at a time.
+pub async fn check_favorites_availability(
+    client: &tokio::sync::RwLock<QobuzClient>,
+    cache: &FavoritesCacheState,
+    force: bool,
+) -> Result<Vec<UnavailableFavorite>, String> {
+    let now = unix_now();
+    let checked_before = if force {
+        i64::MAX
+    } else {
+        now - AVAILABILITY_CHECK_INTERVAL.as_secs() as i64
+    };
+
+    let due = {
+        let guard = cache
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.track_ids_due_for_check(checked_before)?
+    };
+
+    if !due.is_empty() {
+        log::info!("Checking availability of {} favorite tracks", due.len());
+    }
+
+    for batch in due.chunks(AVAILABILITY_BATCH_SIZE) {
+        let ids: Vec<u64> = batch.iter().map(|&id| id as u64).collect();
+        // Only hold the client lock for one request at a time
+        let result = client.read().await.get_tracks_batch(&ids).await;
+        let tracks = match result {
+            Ok(tracks) => tracks,
+            Err(e) => {
+                // Leave the rest unchecked so the next run retries them
+                log::warn!("Favorites availability check failed: {}", e);
+                break;
+            }
+        };
+
+        // Tracks missing from the response are unknown, not unavailable: leave
+        // them unchecked so the next run asks again
+        let guard = cache
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        for track in &tracks {
+            store.set_track_availability(track.id as i64, !track.streamable, now)?;
+        }
+    }
+
+    let guard = cache
+        .store
+        .lock()
+        .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    store.get_unavailable_favorites()
+}
+
+// ============ Import ============
+
+/// What an import changes for one kind of favorite
+struct ImportPlan<E> {
+    add: Vec<E>,
+    remove: Vec<String>,
+}
+
+/// Split validated entries into additions and (for replace) removals
+fn plan_import<E>(
+    entries: Vec<E>,
+    id_of: impl Fn(&E) -> String,
+    current: &HashSet<String>,
+    merge: bool,
+    summary: &mut FavoritesImportSummary,
+) -> ImportPlan<E> {
+    let mut seen = HashSet::new();
+    let mut add = Vec::new();
+    for entry in entries {
+        let id = id_of(&entry);
+        if id.is_empty() || id == "0" {
+            summary.invalid += 1;
+        } else if !seen.insert(id.clone()) || current.contains(&id) {
+            summary.skipped += 1;
+        } else {
+            add.push(entry);
+        }
+    }
+
+    let remove = if merge {
+        Vec::new()
+    } else {
+        current
+            .iter()
+            .filter(|id| !seen.contains(*id))
+            .cloned()
+            .collect()
+    };
+
+    ImportPlan { add, remove }
+}
+
+/// Everything an import changes, worked out before any API call
+struct FavoritesImportPlan {
+    tracks: ImportPlan<FavoriteTrackEntry>,
+    albums: ImportPlan<FavoriteAlbumEntry>,
+    artists: ImportPlan<FavoriteArtistEntry>,
+    summary: FavoritesImportSummary,
+}
+
+impl FavoritesImportPlan {
+    /// Favorites the import would remove from Qobuz (replace only)
+    fn removals(&self) -> usize {
+        self.tracks.remove.len() + self.albums.remove.len() + self.artists.remove.len()
+    }
+
+    /// The summary the import reports if every API call succeeds
+    fn preview(&self) -> FavoritesImportSummary {
+        FavoritesImportSummary {
+            added: self.tracks.add.len() + self.albums.add.len() + self.artists.add.len(),
+            removed: self.removals(),
+            ..self.summary.clone()
+        }
+    }
+}
+
+/// Validate an exported favorites file and plan it against the cache
+fn plan_favorites_import(
+    cache: &FavoritesCacheState,
+    json: &str,
+    merge: bool,
+) -> Result<FavoritesImportPlan, String> {
+    let export: FavoritesExport =
+        serde_json::from_str(json).map_err(|e| format!("Invalid favorites file: {}", e))?;
+    if export.version > FAVORITES_EXPORT_VERSION {
+        return Err(format!(
+            "Unsupported favorites file version {} (expected {} or older)",
+            export.version, FAVORITES_EXPORT_VERSION
+        ));
+    }
+
+    let (current_tracks, current_albums, current_artists) = {
+        let guard = cache
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        (
+            store
+                .get_favorite_track_ids()?
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<HashSet<_>>(),
+            store.get_favorite_album_ids()?.into_iter().collect::<HashSet<_>>(),
+            store
+                .get_favorite_artist_ids()?
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<HashSet<_>>(),
+        )
+    };
+
+    let mut summary = FavoritesImportSummary::default();
+    let tracks = plan_import(
+        export.tracks,
+        |e| e.track_id.to_string(),
+        &current_tracks,
+        merge,
+        &mut summary,
+    );
+    let albums = plan_import(
+        export.albums,
+        |e| e.album_id.trim().to_string(),
+        &current_albums,
+        merge,
+        &mut summary,
+    );
+    let artists = plan_import(
+        export.artists,
+        |e| e.artist_id.to_string(),
+        &current_artists,
+        merge,
+        &mut summary,
+    );
+
+    Ok(FavoritesImportPlan {
+        tracks,
+        albums,
+        artists,
+        summary,
+    })
+}
+
+/// What `import_favorites_json` would do, without calling the API
+pub fn preview_favorites_import_json(
+    cache: &FavoritesCacheState,
+    json: &str,
+    merge: bool,
+) -> Result<FavoritesImportSummary, String> {
+    Ok(plan_favorites_import(cache, json, merge)?.preview())
+}
+
+/// Import favorites exported by `export_favorites`
+///
+/// Items are favorited on Qobuz first and cached on success. With `merge`
+/// the current favorites are kept; otherwise favorites missing from the
+/// file are removed, which is refused unless `confirm_removals` is set
+/// (show `preview_favorites_import_json` first). The file is validated
+/// before any API call, and the store lock is never held across API calls.
+pub async fn import_favorites_json(
+    client: &QobuzClient,
+    cache: &FavoritesCacheState,
+    json: &str,
+    merge: bool,
+    confirm_removals: bool,
+) -> Result<FavoritesImportSummary, String> {
+    let plan = plan_favorites_import(cache, json, merge)?;
+    if plan.removals() > 0 && !confirm_removals {
+        return Err(format!(
+            "Import would remove {} favorites from Qobuz; confirm the removal to continue",
+            plan.removals()
+        ));
+    }
+    let FavoritesImportPlan {
+        tracks,
+        albums,
+        artists,
+        mut summary,
+    } = plan;
+
+    // Favorite on Qobuz, then cache what the API accepted
+    let mut added_tracks = Vec::new();
+    for entry in tracks.add {
+        match client.add_favorite("track", &entry.track_id.to_string()).await {
+            Ok(()) => added_tracks.push(entry),
+            Err(e) => {
+                log::warn!("Failed to import favorite track {}: {}", entry.track_id, e);
+                summary.failed += 1;
+            }
+        }
+    }
+    let mut added_albums = Vec::new();
+    for entry in albums.add {
+        match client.add_favorite("album", entry.album_id.trim()).await {
+            Ok(()) => added_albums.push(entry),
+            Err(e) => {
+                log::warn!("Failed to import favorite album {}: {}", entry.album_id, e);
+                summary.failed += 1;
+            }
+        }
+    }
+    let mut added_artists = Vec::new();
+    for entry in artists.add {
+        match client.add_favorite("artist", &entry.artist_id.to_string()).await {
+            Ok(()) => added_artists.push(entry),
+            Err(e) => {
+                log::warn!("Failed to import favorite artist {}: {}", entry.artist_id, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    let mut removed = Vec::new();
+    for (fav_type, ids) in [
+        ("track", tracks.remove),
+        ("album", albums.remove),
+        ("artist", artists.remove),
+    ] {
+        for id in ids {
+            match client.remove_favorite(fav_type, &id).await {
+                Ok(()) => removed.push((fav_type, id)),
+                Err(e) => {
+                    log::warn!("Failed to remove favorite {} {}: {}", fav_type, id, e);
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+
+    let guard = cache
+        .store
+        .lock()
+        .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    // Files list newest first; cache oldest first so the newest get the highest position
+    for entry in added_tracks.iter().rev() {
+        store.add_favorite_track_entry(entry)?;
+    }
+    for entry in added_albums.iter().rev() {
+        let entry = FavoriteAlbumEntry {
+            album_id: entry.album_id.trim().to_string(),
+            ..entry.clone()
+        };
+        store.add_favorite_album_entry(&entry)?;
+    }
+    for entry in added_artists.iter().rev() {
+        store.add_favorite_artist_entry(entry)?;
+    }
+    for (fav_type, id) in &removed {
+        match *fav_type {
+            "track" => store.remove_favorite_track(id.parse().unwrap_or_default())?,
+            "album" => store.remove_favorite_album(id)?,
+            _ => store.remove_favorite_artist(id.parse().unwrap_or_default())?,
+        }
+    }
+
+    summary.added = added_tracks.len() + added_albums.len() + added_artists.len();
+    summary.removed = removed.len();
+    log::info!(
+        "Imported favorites: {} added, {} removed, {} skipped, {} invalid, {} failed (merge={})",
+        summary.added,
+        summary.removed,
+        summary.skipped,
+        summary.invalid,
+        summary.failed,
+        merge
+    );
+    Ok(summary)
+}
+
 // ============ Tauri State ============
 
 pub struct FavoritesCacheState {
@@ -354,9 +1274,10 @@ impl FavoritesCacheState {
 
 // ============ Tauri Commands ============
 
-/// Get all cached favorite track IDs
+/// Get cached favorite track IDs, optionally sorted/filtered
 #[tauri::command]
 pub fn get_cached_favorite_tracks(
+    query: Option<FavoritesQuery>,
     state: tauri::State<FavoritesCacheState>,
 ) -> Result<Vec<i64>, String> {
     let guard = state
@@ -364,12 +1285,16 @@ pub fn get_cached_favorite_tracks(
         .lock()
         .map_err(|_| "Failed to lock favorites cache store".to_string())?;
     let store = guard.as_ref().ok_or("No active session - please log in")?;
-    store.get_favorite_track_ids()
+    match query {
+        Some(query) => store.query_favorite_track_ids(&query),
+        None => store.get_favorite_track_ids(),
+    }
 }
 
-/// Get all cached favorite album IDs
+/// Get cached favorite album IDs, optionally sorted/filtered
 #[tauri::command]
 pub fn get_cached_favorite_albums(
+    query: Option<FavoritesQuery>,
     state: tauri::State<FavoritesCacheState>,
 ) -> Result<Vec<String>, String> {
     let guard = state
@@ -377,12 +1302,16 @@ pub fn get_cached_favorite_albums(
         .lock()
         .map_err(|_| "Failed to lock favorites cache store".to_string())?;
     let store = guard.as_ref().ok_or("No active session - please log in")?;
-    store.get_favorite_album_ids()
+    match query {
+        Some(query) => store.query_favorite_album_ids(&query),
+        None => store.get_favorite_album_ids(),
+    }
 }
 
-/// Get all cached favorite artist IDs
+/// Get cached favorite artist IDs, optionally sorted/filtered
 #[tauri::command]
 pub fn get_cached_favorite_artists(
+    query: Option<FavoritesQuery>,
     state: tauri::State<FavoritesCacheState>,
 ) -> Result<Vec<i64>, String> {
     let guard = state
@@ -390,7 +1319,10 @@ pub fn get_cached_favorite_artists(
         .lock()
         .map_err(|_| "Failed to lock favorites cache store".to_string())?;
     let store = guard.as_ref().ok_or("No active session - please log in")?;
-    store.get_favorite_artist_ids()
+    match query {
+        Some(query) => store.query_favorite_artist_ids(&query),
+        None => store.get_favorite_artist_ids(),
+    }
 }
 
 /// Add a track to local favorites cache (call after API success)
@@ -477,46 +1409,101 @@ pub fn uncache_favorite_artist(
     store.remove_favorite_artist(artist_id)
 }
 
-/// Sync track favorites from a list of IDs (call after fetching from API)
+/// Sync track favorites from a list of IDs (call after fetching from API).
+/// When `entries` is given it replaces the ID list and carries sort/filter metadata.
+/// Changes still queued for Qobuz are re-applied on top.
 #[tauri::command]
 pub fn sync_cached_favorite_tracks(
     track_ids: Vec<i64>,
+    entries: Option<Vec<FavoriteTrackEntry>>,
     state: tauri::State<FavoritesCacheState>,
+    offline_state: tauri::State<crate::offline::OfflineState>,
 ) -> Result<(), String> {
-    let guard = state
-        .store
-        .lock()
-        .map_err(|_| "Failed to lock favorites cache store".to_string())?;
-    let store = guard.as_ref().ok_or("No active session - please log in")?;
-    store.sync_favorite_tracks(&track_ids)
+    {
+        let guard = state
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        match entries {
+            Some(entries) => store.sync_favorite_track_entries(&entries)?,
+            None => store.sync_favorite_tracks(&track_ids)?,
+        }
+    }
+    crate::commands::reapply_queued_favorites(&offline_state, &state, "track")
 }
 
-/// Sync album favorites from a list of IDs (call after fetching from API)
+/// Sync album favorites from a list of IDs (call after fetching from API).
+/// When `entries` is given it replaces the ID list and carries sort/filter metadata.
+/// Changes still queued for Qobuz are re-applied on top.
 #[tauri::command]
 pub fn sync_cached_favorite_albums(
     album_ids: Vec<String>,
+    entries: Option<Vec<FavoriteAlbumEntry>>,
     state: tauri::State<FavoritesCacheState>,
+    offline_state: tauri::State<crate::offline::OfflineState>,
 ) -> Result<(), String> {
-    let guard = state
-        .store
-        .lock()
-        .map_err(|_| "Failed to lock favorites cache store".to_string())?;
-    let store = guard.as_ref().ok_or("No active session - please log in")?;
-    store.sync_favorite_albums(&album_ids)
+    {
+        let guard = state
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        match entries {
+            Some(entries) => store.sync_favorite_album_entries(&entries)?,
+            None => store.sync_favorite_albums(&album_ids)?,
+        }
+    }
+    crate::commands::reapply_queued_favorites(&offline_state, &state, "album")
 }
 
-/// Sync artist favorites from a list of IDs (call after fetching from API)
+/// Sync artist favorites from a list of IDs (call after fetching from API).
+/// When `entries` is given it replaces the ID list and carries sort/filter metadata.
+/// Changes still queued for Qobuz are re-applied on top.
 #[tauri::command]
 pub fn sync_cached_favorite_artists(
     artist_ids: Vec<i64>,
+    entries: Option<Vec<FavoriteArtistEntry>>,
     state: tauri::State<FavoritesCacheState>,
+    offline_state: tauri::State<crate::offline::OfflineState>,
 ) -> Result<(), String> {
+    {
+        let guard = state
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        match entries {
+            Some(entries) => store.sync_favorite_artist_entries(&entries)?,
+            None => store.sync_favorite_artists(&artist_ids)?,
+        }
+    }
+    crate::commands::reapply_queued_favorites(&offline_state, &state, "artist")
+}
+
+/// Check favorite tracks for ones Qobuz no longer streams.
+/// Recently checked tracks are skipped unless `force` is true.
+#[tauri::command]
+pub async fn find_unavailable_favorites(
+    force: Option<bool>,
+    app_state: tauri::State<'_, crate::AppState>,
+    state: tauri::State<'_, FavoritesCacheState>,
+) -> Result<Vec<UnavailableFavorite>, String> {
+    log::info!("Command: find_unavailable_favorites force={:?}", force);
+    check_favorites_availability(&app_state.client, &state, force.unwrap_or(false)).await
+}
+
+/// Get favorite tracks marked unavailable by the last check (no API calls)
+#[tauri::command]
+pub fn get_unavailable_favorites(
+    state: tauri::State<FavoritesCacheState>,
+) -> Result<Vec<UnavailableFavorite>, String> {
     let guard = state
         .store
         .lock()
         .map_err(|_| "Failed to lock favorites cache store".to_string())?;
     let store = guard.as_ref().ok_or("No active session - please log in")?;
-    store.sync_favorite_artists(&artist_ids)
+    store.get_unavailable_favorites()
 }
 
 /// Clear all cached favorites (call on logout)
@@ -529,3 +1516,252 @@ pub fn clear_favorites_cache(state: tauri::State<FavoritesCacheState>) -> Result
     let store = guard.as_ref().ok_or("No active session - please log in")?;
     store.clear_all()
 }
+
+/// Export cached favorites (with titles and names) as JSON for backup
+#[tauri::command]
+pub fn export_favorites(state: tauri::State<FavoritesCacheState>) -> Result<String, String> {
+    let guard = state
+        .store
+        .lock()
+        .map_err(|_| "Failed to lock favorites cache store".to_string())?;
+    let store = guard.as_ref().ok_or("No active session - please log in")?;
+    let export = store.export_entries()?;
+    serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize favorites: {}", e))
+}
+
+/// Counts an import would produce, without touching Qobuz. Show this (in
+/// particular `removed`) before a replace import.
+#[tauri::command]
+pub fn preview_favorites_import(
+    json: String,
+    merge: bool,
+    state: tauri::State<FavoritesCacheState>,
+) -> Result<FavoritesImportSummary, String> {
+    log::info!("Command: preview_favorites_import merge={}", merge);
+    preview_favorites_import_json(&state, &json, merge)
+}
+
+/// Import favorites exported by `export_favorites`, favoriting them on Qobuz.
+/// `merge` keeps the current favorites; otherwise missing ones are removed,
+/// which requires `confirm_removals` after the user saw the preview.
+#[tauri::command]
+pub async fn import_favorites(
+    json: String,
+    merge: bool,
+    confirm_removals: Option<bool>,
+    app_state: tauri::State<'_, crate::AppState>,
+    state: tauri::State<'_, FavoritesCacheState>,
+) -> Result<FavoritesImportSummary, String> {
+    log::info!("Command: import_favorites merge={}", merge);
+    let client = app_state.client.read().await;
+    import_favorites_json(
+        &client,
+        &state,
+        &json,
+        merge,
+        confirm_removals.unwrap_or(false),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn track(
+        track_id: i64,
+        title: &str,
+        year: Option<i32>,
+        genre: &str,
+        bits: u32,
+    ) -> FavoriteTrackEntry {
+        FavoriteTrackEntry {
+            track_id,
+            title: Some(title.to_string()),
+            artist: Some(format!("Artist {}", title)),
+            genre: Some(genre.to_string()),
+            release_year: year,
+            hires: bits > 16,
+            bit_depth: Some(bits),
+            added_at: None,
+        }
+    }
+
+    fn sorted(sort: FavoritesSort, reverse: bool) -> FavoritesQuery {
+        FavoritesQuery {
+            sort,
+            reverse,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn date_added_follows_api_order_not_ids() {
+        let dir = tempdir().unwrap();
+        let store = FavoritesCacheStore::new_at(dir.path()).unwrap();
+
+        // API order is newest first; IDs deliberately out of order
+        store
+            .sync_favorite_track_entries(&[
+                track(5, "b", None, "Jazz", 16),
+                track(900, "a", None, "Jazz", 16),
+                track(42, "c", None, "Jazz", 16),
+            ])
+            .unwrap();
+        let newest_first = sorted(FavoritesSort::DateAdded, false);
+        assert_eq!(
+            store.query_favorite_track_ids(&newest_first).unwrap(),
+            vec![5, 900, 42]
+        );
+        assert_eq!(
+            store
+                .query_favorite_track_ids(&sorted(FavoritesSort::DateAdded, true))
+                .unwrap(),
+            vec![42, 900, 5]
+        );
+
+        // A favorite added afterwards is the newest
+        store.add_favorite_track(1).unwrap();
+        assert_eq!(
+            store.query_favorite_track_ids(&newest_first).unwrap(),
+            vec![1, 5, 900, 42]
+        );
+
+        store.sync_favorite_artists(&[7, 3]).unwrap();
+        store.add_favorite_artist(9).unwrap();
+        assert_eq!(
+            store.query_favorite_artist_ids(&newest_first).unwrap(),
+            vec![9, 7, 3]
+        );
+    }
+
+    #[test]
+    fn unavailable_favorites_list_most_recently_added_first() {
+        let dir = tempdir().unwrap();
+        let store = FavoritesCacheStore::new_at(dir.path()).unwrap();
+        store.sync_favorite_tracks(&[5, 900, 42]).unwrap();
+        for track_id in [5, 900, 42] {
+            store.set_track_availability(track_id, true, 100).unwrap();
+        }
+
+        let ids: Vec<i64> = store
+            .get_unavailable_favorites()
+            .unwrap()
+            .iter()
+            .map(|fav| fav.track_id)
+            .collect();
+        assert_eq!(ids, vec![5, 900, 42]);
+    }
+
+    #[test]
+    fn export_lists_newest_first() {
+        let dir = tempdir().unwrap();
+        let store = FavoritesCacheStore::new_at(dir.path()).unwrap();
+        store.sync_favorite_tracks(&[5, 900, 42]).unwrap();
+        store
+            .add_favorite_track_entry(&track(7, "new", None, "Jazz", 16))
+            .unwrap();
+
+        let export = store.export_entries().unwrap();
+        let ids: Vec<i64> = export.tracks.iter().map(|t| t.track_id).collect();
+        assert_eq!(ids, vec![7, 5, 900, 42]);
+    }
+
+    #[test]
+    fn replace_import_preview_counts_removals() {
+        let dir = tempdir().unwrap();
+        let cache = FavoritesCacheState::new_empty();
+        cache.init_at(dir.path()).unwrap();
+        cache
+            .store
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .sync_favorite_tracks(&[1, 2, 3])
+            .unwrap();
+
+        let json = serde_json::to_string(&FavoritesExport {
+            version: FAVORITES_EXPORT_VERSION,
+            tracks: vec![
+                track(3, "kept", None, "Jazz", 16),
+                track(4, "new", None, "Jazz", 16),
+            ],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let replace = preview_favorites_import_json(&cache, &json, false).unwrap();
+        assert_eq!(replace.added, 1);
+        assert_eq!(replace.removed, 2);
+        assert_eq!(replace.skipped, 1);
+
+        let merge = preview_favorites_import_json(&cache, &json, true).unwrap();
+        assert_eq!(merge.removed, 0);
+    }
+
+    #[test]
+    fn sorts_by_metadata_with_missing_values_last() {
+        let dir = tempdir().unwrap();
+        let store = FavoritesCacheStore::new_at(dir.path()).unwrap();
+        store
+            .sync_favorite_track_entries(&[
+                track(1, "beta", Some(1999), "Jazz", 16),
+                track(2, "Alpha", None, "Jazz", 16),
+                track(3, "gamma", Some(2020), "Jazz", 16),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            store
+                .query_favorite_track_ids(&sorted(FavoritesSort::Title, false))
+                .unwrap(),
+            vec![2, 1, 3]
+        );
+        assert_eq!(
+            store
+                .query_favorite_track_ids(&sorted(FavoritesSort::ReleaseYear, false))
+                .unwrap(),
+            vec![3, 1, 2]
+        );
+        assert_eq!(
+            store
+                .query_favorite_track_ids(&sorted(FavoritesSort::ReleaseYear, true))
+                .unwrap(),
+            vec![1, 3, 2]
+        );
+    }
+
+    #[test]
+    fn filters_by_quality_and_genre() {
+        let dir = tempdir().unwrap();
+        let store = FavoritesCacheStore::new_at(dir.path()).unwrap();
+        store
+            .sync_favorite_track_entries(&[
+                track(1, "a", None, "Jazz", 24),
+                track(2, "b", None, "Rock", 24),
+                track(3, "c", None, "jazz", 16),
+            ])
+            .unwrap();
+
+        let hires = FavoritesQuery {
+            hires_only: true,
+            ..Default::default()
+        };
+        assert_eq!(store.query_favorite_track_ids(&hires).unwrap(), vec![1, 2]);
+
+        let jazz = FavoritesQuery {
+            genre: Some("JAZZ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(store.query_favorite_track_ids(&jazz).unwrap(), vec![1, 3]);
+
+        let jazz_24 = FavoritesQuery {
+            min_bit_depth: Some(24),
+            ..jazz
+        };
+        assert_eq!(store.query_favorite_track_ids(&jazz_24).unwrap(), vec![1]);
+    }
+}
@@ -0,0 +1,160 @@
+//! Media key settings
+//!
+//! Stores user preferences for hardware media keys:
+//! - global_media_keys: Grab Play/Pause/Next/Previous globally (Linux), for
+//!   desktops that don't forward them to MPRIS. Off by default so QBZ doesn't
+//!   steal the keys from other media players.
+
+use log::info;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+
+use crate::media_controls::GlobalMediaKeysStatus;
+use crate::AppState;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaKeysSettings {
+    /// Grab the hardware media keys even when QBZ isn't focused
+    pub global_media_keys: bool,
+}
+
+pub struct MediaKeysSettingsStore {
+    conn: Connection,
+}
+
+impl MediaKeysSettingsStore {
+    fn open_at(dir: &Path, db_name: &str) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+        let db_path = dir.join(db_name);
+        let conn = Connection::open(&db_path)
+            .map_err(|e| format!("Failed to open media key settings database: {}", e))?;
+
+        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
+            .map_err(|e| format!("Failed to enable WAL for media key settings database: {}", e))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS media_keys_settings (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                global_media_keys INTEGER NOT NULL DEFAULT 0
+            );",
+        )
+        .map_err(|e| format!("Failed to create media key settings table: {}", e))?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO media_keys_settings (id, global_media_keys)
+            VALUES (1, 0)",
+            [],
+        )
+        .map_err(|e| format!("Failed to insert default media key settings: {}", e))?;
+
+        info!("[MediaKeysSettings] Database initialized");
+
+        Ok(Self { conn })
+    }
+
+    pub fn new() -> Result<Self, String> {
+        let data_dir = dirs::data_dir()
+            .ok_or("Could not determine data directory")?
+            .join("qbz");
+        Self::open_at(&data_dir, "media_keys_settings.db")
+    }
+
+    pub fn get_settings(&self) -> Result<MediaKeysSettings, String> {
+        self.conn
+            .query_row(
+                "SELECT global_media_keys FROM media_keys_settings WHERE id = 1",
+                [],
+                |row| {
+                    let global_media_keys: i32 = row.get(0)?;
+                    Ok(MediaKeysSettings {
+                        global_media_keys: global_media_keys != 0,
+                    })
+                },
+            )
+            .map_err(|e| format!("Failed to get media key settings: {}", e))
+    }
+
+    pub fn set_global_media_keys(&self, value: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE media_keys_settings SET global_media_keys = ?1 WHERE id = 1",
+                params![if value { 1 } else { 0 }],
+            )
+            .map_err(|e| format!("Failed to set global_media_keys: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Global state wrapper for thread-safe access
+pub struct MediaKeysSettingsState {
+    pub store: Arc<Mutex<Option<MediaKeysSettingsStore>>>,
+}
+
+impl MediaKeysSettingsState {
+    pub fn new() -> Result<Self, String> {
+        let store = MediaKeysSettingsStore::new()?;
+        Ok(Self {
+            store: Arc::new(Mutex::new(Some(store))),
+        })
+    }
+
+    pub fn new_empty() -> Self {
+        Self {
+            store: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn get_settings(&self) -> Result<MediaKeysSettings, String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock media key settings store".to_string())?;
+        let store = guard
+            .as_ref()
+            .ok_or("Media key settings store not initialized")?;
+        store.get_settings()
+    }
+
+    pub fn set_global_media_keys(&self, value: bool) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock media key settings store".to_string())?;
+        let store = guard
+            .as_ref()
+            .ok_or("Media key settings store not initialized")?;
+        store.set_global_media_keys(value)
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_media_keys_settings(
+    state: tauri::State<MediaKeysSettingsState>,
+) -> Result<MediaKeysSettings, String> {
+    state.get_settings()
+}
+
+#[tauri::command]
+pub fn set_global_media_keys(
+    value: bool,
+    app: AppHandle,
+    state: tauri::State<MediaKeysSettingsState>,
+    app_state: tauri::State<AppState>,
+) -> Result<(), String> {
+    info!("[MediaKeysSettings] Setting global_media_keys to {}", value);
+    state.set_global_media_keys(value)?;
+    app_state.media_controls.set_global_media_keys(value, app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_global_media_keys_status(app_state: tauri::State<AppState>) -> GlobalMediaKeysStatus {
+    app_state.media_controls.global_media_keys_status()
+}
@@ -9,16 +9,20 @@
 //! - Local playlists
 //! - Cached favorites
 
+pub mod artwork_settings;
 pub mod audio_settings;
 pub mod developer_settings;
 pub mod download_settings;
 pub mod graphics_settings;
+pub mod network_settings;
 pub mod playback_preferences;
 pub mod favorites_preferences;
 pub mod favorites_cache;
 pub mod subscription_state;
 pub mod tray_settings;
 pub mod window_settings;
+pub mod media_keys_settings;
+pub mod region_settings;
 pub mod legal_settings;
 pub mod remote_control_settings;
 
@@ -38,12 +42,15 @@ pub use download_settings::{
     DownloadSettingsState,
     get_download_settings,
     set_download_root,
+    set_download_naming_template,
+    preview_download_path,
     set_show_downloads_in_library,
     validate_download_root,
 };
 
 pub use playback_preferences::{
     AutoplayMode,
+    EndOfQueueBehavior,
     PlaybackPreferences,
     PlaybackPreferencesState,
     get_playback_preferences,
@@ -76,6 +83,8 @@ pub use tray_settings::{
 
 pub use favorites_cache::{
     FavoritesCacheState,
+    FavoritesQuery,
+    FavoritesSort,
     get_cached_favorite_tracks,
     get_cached_favorite_albums,
     get_cached_favorite_artists,
@@ -88,7 +97,11 @@ pub use favorites_cache::{
     sync_cached_favorite_tracks,
     sync_cached_favorite_albums,
     sync_cached_favorite_artists,
+    find_unavailable_favorites,
+    get_unavailable_favorites,
     clear_favorites_cache,
+    export_favorites,
+    import_favorites,
 };
 
 pub use legal_settings::{
@@ -130,3 +143,27 @@ pub use window_settings::{
     get_window_settings,
     set_use_system_titlebar,
 };
+
+pub use media_keys_settings::{
+    MediaKeysSettings,
+    MediaKeysSettingsState,
+    get_media_keys_settings,
+    set_global_media_keys,
+    get_global_media_keys_status,
+};
+
+pub use region_settings::{
+    RegionSettings,
+    RegionSettingsState,
+    get_available_locales,
+    get_region_settings,
+    set_content_locale,
+};
+
+pub use artwork_settings::{
+    ArtworkSettings,
+    ArtworkSettingsState,
+    get_artwork_settings,
+    set_artwork_quality,
+    set_artwork_cache_limit_mb,
+};
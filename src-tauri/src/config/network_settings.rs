@@ -0,0 +1,203 @@
+//! Network settings persistence
+//!
+//! Proxy, User-Agent and timeouts for the shared HTTP client
+//! (`network::http_client`). Device-level (not per-user), like the
+//! developer settings.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u32 = 10;
+pub const DEFAULT_READ_TIMEOUT_SECS: u32 = 30;
+
+/// Longest accepted connect/read timeout in seconds
+pub const MAX_TIMEOUT_SECS: u32 = 300;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    /// Proxy URL (http:// or https://). None falls back to the
+    /// `HTTP_PROXY`/`HTTPS_PROXY` environment variables.
+    pub proxy: Option<String>,
+    /// User-Agent sent with every request. None keeps each service's default.
+    pub user_agent: Option<String>,
+    pub connect_timeout_secs: u32,
+    /// Longest wait for the next chunk of a response (not the whole download)
+    pub read_timeout_secs: u32,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            user_agent: None,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            read_timeout_secs: DEFAULT_READ_TIMEOUT_SECS,
+        }
+    }
+}
+
+pub struct NetworkSettingsStore {
+    conn: Connection,
+}
+
+impl NetworkSettingsStore {
+    pub fn new() -> Result<Self, String> {
+        let data_dir = dirs::data_dir()
+            .ok_or("Could not determine data directory")?
+            .join("qbz");
+
+        std::fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+        let db_path = data_dir.join("network_settings.db");
+        let conn = Connection::open(&db_path)
+            .map_err(|e| format!("Failed to open network settings database: {}", e))?;
+
+        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
+            .map_err(|e| format!("Failed to enable WAL for network settings database: {}", e))?;
+
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS network_settings (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                proxy TEXT,
+                user_agent TEXT,
+                connect_timeout_secs INTEGER NOT NULL DEFAULT {connect},
+                read_timeout_secs INTEGER NOT NULL DEFAULT {read}
+            );
+            INSERT OR IGNORE INTO network_settings (id) VALUES (1);",
+            connect = DEFAULT_CONNECT_TIMEOUT_SECS,
+            read = DEFAULT_READ_TIMEOUT_SECS,
+        ))
+        .map_err(|e| format!("Failed to create network settings table: {}", e))?;
+
+        Ok(Self { conn })
+    }
+
+    pub fn get_settings(&self) -> Result<NetworkSettings, String> {
+        self.conn
+            .query_row(
+                "SELECT proxy, user_agent, connect_timeout_secs, read_timeout_secs
+                 FROM network_settings WHERE id = 1",
+                [],
+                |row| {
+                    Ok(NetworkSettings {
+                        proxy: row.get(0)?,
+                        user_agent: row.get(1)?,
+                        connect_timeout_secs: row.get::<_, i64>(2)?.max(1) as u32,
+                        read_timeout_secs: row.get::<_, i64>(3)?.max(1) as u32,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| format!("Failed to get network settings: {}", e))
+            .map(Option::unwrap_or_default)
+    }
+
+    pub fn set_settings(&self, settings: &NetworkSettings) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE network_settings
+                 SET proxy = ?1, user_agent = ?2, connect_timeout_secs = ?3, read_timeout_secs = ?4
+                 WHERE id = 1",
+                params![
+                    settings.proxy,
+                    settings.user_agent,
+                    settings.connect_timeout_secs,
+                    settings.read_timeout_secs
+                ],
+            )
+            .map_err(|e| format!("Failed to save network settings: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Thread-safe wrapper for Tauri state management
+pub struct NetworkSettingsState {
+    pub store: Arc<Mutex<Option<NetworkSettingsStore>>>,
+}
+
+impl NetworkSettingsState {
+    pub fn new() -> Result<Self, String> {
+        Ok(Self {
+            store: Arc::new(Mutex::new(Some(NetworkSettingsStore::new()?))),
+        })
+    }
+
+    pub fn new_empty() -> Self {
+        Self {
+            store: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn get_settings(&self) -> Result<NetworkSettings, String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard
+            .as_ref()
+            .ok_or("Network settings store not initialized")?;
+        store.get_settings()
+    }
+}
+
+/// Trim optional text settings, treating blank values as unset
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_network_settings(
+    state: tauri::State<'_, NetworkSettingsState>,
+) -> Result<NetworkSettings, String> {
+    state.get_settings()
+}
+
+/// Save network settings and rebuild the shared HTTP client.
+///
+/// Clients that services built at startup (Qobuz, Plex, Discogs) pick the
+/// change up after a restart.
+#[tauri::command]
+pub fn set_network_settings(
+    proxy: Option<String>,
+    user_agent: Option<String>,
+    connect_timeout_secs: Option<u32>,
+    read_timeout_secs: Option<u32>,
+    state: tauri::State<'_, NetworkSettingsState>,
+) -> Result<NetworkSettings, String> {
+    let settings = NetworkSettings {
+        proxy: non_empty(proxy),
+        user_agent: non_empty(user_agent),
+        connect_timeout_secs: connect_timeout_secs
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS),
+        read_timeout_secs: read_timeout_secs
+            .unwrap_or(DEFAULT_READ_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS),
+    };
+    log::info!(
+        "Command: set_network_settings (proxy: {}, custom user agent: {}, timeouts: {}s/{}s)",
+        settings.proxy.is_some(),
+        settings.user_agent.is_some(),
+        settings.connect_timeout_secs,
+        settings.read_timeout_secs
+    );
+
+    // Validate before persisting so a bad proxy URL never sticks
+    crate::network::http_client::configure(&settings)?;
+
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard
+        .as_ref()
+        .ok_or("Network settings store not initialized")?;
+    store.set_settings(&settings)?;
+    Ok(settings)
+}
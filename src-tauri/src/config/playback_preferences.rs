@@ -45,10 +45,91 @@ impl AutoplayMode {
     }
 }
 
+/// What happens once the last track of the queue has finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EndOfQueueBehavior {
+    /// Stop playback
+    #[default]
+    #[serde(rename = "stop")]
+    Stop,
+    /// Start an infinite radio seeded from the finished track
+    #[serde(rename = "radio")]
+    StartRadio,
+    /// Start over from the top of the queue
+    #[serde(rename = "loop")]
+    LoopQueue,
+}
+
+impl EndOfQueueBehavior {
+    fn to_db_value(self) -> &'static str {
+        match self {
+            EndOfQueueBehavior::Stop => "stop",
+            EndOfQueueBehavior::StartRadio => "radio",
+            EndOfQueueBehavior::LoopQueue => "loop",
+        }
+    }
+
+    fn from_db_value(s: &str) -> Self {
+        match s {
+            "radio" => EndOfQueueBehavior::StartRadio,
+            "loop" => EndOfQueueBehavior::LoopQueue,
+            _ => EndOfQueueBehavior::Stop,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaybackPreferences {
     pub autoplay_mode: AutoplayMode,
     pub show_context_icon: bool,
+    /// Radio skips tracks played within this many days while fresh ones are
+    /// left (0 = off)
+    #[serde(default = "default_radio_freshness_days")]
+    pub radio_freshness_days: u32,
+    #[serde(default)]
+    pub end_of_queue: EndOfQueueBehavior,
+    /// "Previous" restarts the current track once it has played longer than
+    /// this many seconds (0 = always go to the previous track)
+    #[serde(default = "default_previous_restart_threshold_secs")]
+    pub previous_restart_threshold_secs: u32,
+    /// Radio keeps this many upcoming tracks queued, topping up as tracks play
+    #[serde(default = "default_radio_queue_ahead")]
+    pub radio_queue_ahead: u32,
+    /// When an album, playlist or artist context runs out, roll into radio
+    /// seeded from its last track (continue mode only)
+    #[serde(default)]
+    pub radio_after_source: bool,
+}
+
+/// Default radio freshness window in days
+pub const DEFAULT_RADIO_FRESHNESS_DAYS: u32 = 7;
+
+/// Longest accepted radio freshness window in days
+pub const MAX_RADIO_FRESHNESS_DAYS: u32 = 90;
+
+fn default_radio_freshness_days() -> u32 {
+    DEFAULT_RADIO_FRESHNESS_DAYS
+}
+
+/// Default previous-restart threshold in seconds
+pub const DEFAULT_PREVIOUS_RESTART_THRESHOLD_SECS: u32 = 3;
+
+/// Longest accepted previous-restart threshold in seconds
+pub const MAX_PREVIOUS_RESTART_THRESHOLD_SECS: u32 = 30;
+
+fn default_previous_restart_threshold_secs() -> u32 {
+    DEFAULT_PREVIOUS_RESTART_THRESHOLD_SECS
+}
+
+/// Default number of upcoming radio tracks kept in the queue
+pub const DEFAULT_RADIO_QUEUE_AHEAD: u32 = 50;
+
+/// Accepted range for the radio queue size
+pub const MIN_RADIO_QUEUE_AHEAD: u32 = 10;
+pub const MAX_RADIO_QUEUE_AHEAD: u32 = 200;
+
+fn default_radio_queue_ahead() -> u32 {
+    DEFAULT_RADIO_QUEUE_AHEAD
 }
 
 impl Default for PlaybackPreferences {
@@ -56,6 +137,11 @@ impl Default for PlaybackPreferences {
         Self {
             autoplay_mode: AutoplayMode::ContinueWithinSource,
             show_context_icon: false,
+            radio_freshness_days: DEFAULT_RADIO_FRESHNESS_DAYS,
+            end_of_queue: EndOfQueueBehavior::Stop,
+            previous_restart_threshold_secs: DEFAULT_PREVIOUS_RESTART_THRESHOLD_SECS,
+            radio_queue_ahead: DEFAULT_RADIO_QUEUE_AHEAD,
+            radio_after_source: false,
         }
     }
 }
@@ -117,7 +203,116 @@ impl PlaybackPreferencesStore {
             info!("[PlaybackPrefs] Migration successful");
         }
 
-        // Step 4: Insert default row if it doesn't exist
+        // Step 4: Add radio_freshness_days (later migration, same pattern)
+        let freshness_exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('playback_preferences') WHERE name='radio_freshness_days'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                }
+            )
+            .unwrap_or(false);
+
+        if !freshness_exists {
+            info!("[PlaybackPrefs] Migrating: adding radio_freshness_days column");
+            conn.execute(
+                &format!(
+                    "ALTER TABLE playback_preferences ADD COLUMN radio_freshness_days INTEGER NOT NULL DEFAULT {}",
+                    DEFAULT_RADIO_FRESHNESS_DAYS
+                ),
+                []
+            ).map_err(|e| format!("Failed to add radio_freshness_days column: {}", e))?;
+        }
+
+        // Step 5: Add end_of_queue
+        let end_of_queue_exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('playback_preferences') WHERE name='end_of_queue'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                }
+            )
+            .unwrap_or(false);
+
+        if !end_of_queue_exists {
+            info!("[PlaybackPrefs] Migrating: adding end_of_queue column");
+            conn.execute(
+                "ALTER TABLE playback_preferences ADD COLUMN end_of_queue TEXT NOT NULL DEFAULT 'stop'",
+                []
+            ).map_err(|e| format!("Failed to add end_of_queue column: {}", e))?;
+        }
+
+        // Step 6: Add previous_restart_threshold_secs
+        let threshold_exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('playback_preferences') WHERE name='previous_restart_threshold_secs'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                }
+            )
+            .unwrap_or(false);
+
+        if !threshold_exists {
+            info!("[PlaybackPrefs] Migrating: adding previous_restart_threshold_secs column");
+            conn.execute(
+                &format!(
+                    "ALTER TABLE playback_preferences ADD COLUMN previous_restart_threshold_secs INTEGER NOT NULL DEFAULT {}",
+                    DEFAULT_PREVIOUS_RESTART_THRESHOLD_SECS
+                ),
+                []
+            ).map_err(|e| format!("Failed to add previous_restart_threshold_secs column: {}", e))?;
+        }
+
+        // Step 7: Add radio_queue_ahead
+        let queue_ahead_exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('playback_preferences') WHERE name='radio_queue_ahead'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                }
+            )
+            .unwrap_or(false);
+
+        if !queue_ahead_exists {
+            info!("[PlaybackPrefs] Migrating: adding radio_queue_ahead column");
+            conn.execute(
+                &format!(
+                    "ALTER TABLE playback_preferences ADD COLUMN radio_queue_ahead INTEGER NOT NULL DEFAULT {}",
+                    DEFAULT_RADIO_QUEUE_AHEAD
+                ),
+                []
+            ).map_err(|e| format!("Failed to add radio_queue_ahead column: {}", e))?;
+        }
+
+        // Step 8: Add radio_after_source
+        let radio_after_source_exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('playback_preferences') WHERE name='radio_after_source'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                }
+            )
+            .unwrap_or(false);
+
+        if !radio_after_source_exists {
+            info!("[PlaybackPrefs] Migrating: adding radio_after_source column");
+            conn.execute(
+                "ALTER TABLE playback_preferences ADD COLUMN radio_after_source INTEGER NOT NULL DEFAULT 0",
+                []
+            ).map_err(|e| format!("Failed to add radio_after_source column: {}", e))?;
+        }
+
+        // Step 9: Insert default row if it doesn't exist
         conn.execute(
             "INSERT OR IGNORE INTO playback_preferences (id, autoplay_mode, show_context_icon)
             VALUES (1, 'continue', 0)",
@@ -142,14 +337,25 @@ impl PlaybackPreferencesStore {
     pub fn get_preferences(&self) -> Result<PlaybackPreferences, String> {
         self.conn
             .query_row(
-                "SELECT autoplay_mode, show_context_icon FROM playback_preferences WHERE id = 1",
+                "SELECT autoplay_mode, show_context_icon, radio_freshness_days, end_of_queue, previous_restart_threshold_secs, radio_queue_ahead, radio_after_source FROM playback_preferences WHERE id = 1",
                 [],
                 |row| {
                     let autoplay_str: String = row.get(0)?;
                     let show_icon: i32 = row.get(1)?;
+                    let freshness_days: i64 = row.get(2)?;
+                    let end_of_queue: String = row.get(3)?;
+                    let restart_threshold: i64 = row.get(4)?;
+                    let queue_ahead: i64 = row.get(5)?;
+                    let radio_after_source: i32 = row.get(6)?;
                     Ok(PlaybackPreferences {
                         autoplay_mode: AutoplayMode::from_db_value(&autoplay_str),
                         show_context_icon: show_icon != 0,
+                        radio_freshness_days: freshness_days.max(0) as u32,
+                        end_of_queue: EndOfQueueBehavior::from_db_value(&end_of_queue),
+                        previous_restart_threshold_secs: restart_threshold.max(0) as u32,
+                        radio_queue_ahead: (queue_ahead.max(0) as u32)
+                            .clamp(MIN_RADIO_QUEUE_AHEAD, MAX_RADIO_QUEUE_AHEAD),
+                        radio_after_source: radio_after_source != 0,
                     })
                 },
             )
@@ -176,13 +382,71 @@ impl PlaybackPreferencesStore {
         Ok(())
     }
 
+    pub fn set_radio_freshness_days(&self, days: u32) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE playback_preferences SET radio_freshness_days = ?1 WHERE id = 1",
+                params![days.min(MAX_RADIO_FRESHNESS_DAYS)],
+            )
+            .map_err(|e| format!("Failed to set radio freshness: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_end_of_queue(&self, behavior: EndOfQueueBehavior) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE playback_preferences SET end_of_queue = ?1 WHERE id = 1",
+                params![behavior.to_db_value()],
+            )
+            .map_err(|e| format!("Failed to set end of queue behavior: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_previous_restart_threshold_secs(&self, secs: u32) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE playback_preferences SET previous_restart_threshold_secs = ?1 WHERE id = 1",
+                params![secs.min(MAX_PREVIOUS_RESTART_THRESHOLD_SECS)],
+            )
+            .map_err(|e| format!("Failed to set previous restart threshold: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_radio_queue_ahead(&self, tracks: u32) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE playback_preferences SET radio_queue_ahead = ?1 WHERE id = 1",
+                params![tracks.clamp(MIN_RADIO_QUEUE_AHEAD, MAX_RADIO_QUEUE_AHEAD)],
+            )
+            .map_err(|e| format!("Failed to set radio queue size: {}", e))?;
+        Ok(())
+    }
+
+    pub fn set_radio_after_source(&self, enabled: bool) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE playback_preferences SET radio_after_source = ?1 WHERE id = 1",
+                params![if enabled { 1 } else { 0 }],
+            )
+            .map_err(|e| format!("Failed to set radio after source: {}", e))?;
+        Ok(())
+    }
+
     /// Reset all playback preferences to their default values
     pub fn reset_all(&self) -> Result<PlaybackPreferences, String> {
         let defaults = PlaybackPreferences::default();
         self.conn
             .execute(
-                "UPDATE playback_preferences SET autoplay_mode = ?1, show_context_icon = ?2 WHERE id = 1",
-                params![defaults.autoplay_mode.to_db_value(), if defaults.show_context_icon { 1 } else { 0 }],
+                "UPDATE playback_preferences SET autoplay_mode = ?1, show_context_icon = ?2, radio_freshness_days = ?3, end_of_queue = ?4, previous_restart_threshold_secs = ?5, radio_queue_ahead = ?6, radio_after_source = ?7 WHERE id = 1",
+                params![
+                    defaults.autoplay_mode.to_db_value(),
+                    if defaults.show_context_icon { 1 } else { 0 },
+                    defaults.radio_freshness_days,
+                    defaults.end_of_queue.to_db_value(),
+                    defaults.previous_restart_threshold_secs,
+                    defaults.radio_queue_ahead,
+                    if defaults.radio_after_source { 1 } else { 0 }
+                ],
             )
             .map_err(|e| format!("Failed to reset playback preferences: {}", e))?;
         Ok(defaults)
@@ -253,6 +517,51 @@ impl PlaybackPreferencesState {
         let store = guard.as_ref().ok_or("No active session - please log in")?;
         store.set_show_context_icon(show)
     }
+
+    pub fn set_radio_freshness_days(&self, days: u32) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock playback preferences store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.set_radio_freshness_days(days)
+    }
+
+    pub fn set_end_of_queue(&self, behavior: EndOfQueueBehavior) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock playback preferences store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.set_end_of_queue(behavior)
+    }
+
+    pub fn set_previous_restart_threshold_secs(&self, secs: u32) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock playback preferences store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.set_previous_restart_threshold_secs(secs)
+    }
+
+    pub fn set_radio_queue_ahead(&self, tracks: u32) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock playback preferences store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.set_radio_queue_ahead(tracks)
+    }
+
+    pub fn set_radio_after_source(&self, enabled: bool) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock playback preferences store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.set_radio_after_source(enabled)
+    }
 }
 
 // Tauri commands
@@ -285,3 +594,67 @@ pub fn set_show_context_icon(
 ) -> Result<(), String> {
     state.set_show_context_icon(show)
 }
+
+#[tauri::command]
+pub fn set_radio_freshness(
+    days: u32,
+    state: tauri::State<PlaybackPreferencesState>,
+) -> Result<(), String> {
+    if days > MAX_RADIO_FRESHNESS_DAYS {
+        return Err(format!(
+            "Radio freshness must be at most {} days",
+            MAX_RADIO_FRESHNESS_DAYS
+        ));
+    }
+    state.set_radio_freshness_days(days)
+}
+
+#[tauri::command]
+pub fn set_end_of_queue_behavior(
+    behavior: String,
+    state: tauri::State<PlaybackPreferencesState>,
+) -> Result<(), String> {
+    let end_of_queue = match behavior.as_str() {
+        "stop" => EndOfQueueBehavior::Stop,
+        "radio" => EndOfQueueBehavior::StartRadio,
+        "loop" => EndOfQueueBehavior::LoopQueue,
+        _ => return Err(format!("Invalid end of queue behavior: {}", behavior)),
+    };
+    state.set_end_of_queue(end_of_queue)
+}
+
+#[tauri::command]
+pub fn set_previous_restart_threshold_secs(
+    secs: u32,
+    state: tauri::State<PlaybackPreferencesState>,
+) -> Result<(), String> {
+    if secs > MAX_PREVIOUS_RESTART_THRESHOLD_SECS {
+        return Err(format!(
+            "Previous restart threshold must be at most {} seconds",
+            MAX_PREVIOUS_RESTART_THRESHOLD_SECS
+        ));
+    }
+    state.set_previous_restart_threshold_secs(secs)
+}
+
+#[tauri::command]
+pub fn set_radio_queue_ahead(
+    tracks: u32,
+    state: tauri::State<PlaybackPreferencesState>,
+) -> Result<(), String> {
+    if !(MIN_RADIO_QUEUE_AHEAD..=MAX_RADIO_QUEUE_AHEAD).contains(&tracks) {
+        return Err(format!(
+            "Radio queue size must be between {} and {} tracks",
+            MIN_RADIO_QUEUE_AHEAD, MAX_RADIO_QUEUE_AHEAD
+        ));
+    }
+    state.set_radio_queue_ahead(tracks)
+}
+
+#[tauri::command]
+pub fn set_radio_after_source(
+    enabled: bool,
+    state: tauri::State<PlaybackPreferencesState>,
+) -> Result<(), String> {
+    state.set_radio_after_source(enabled)
+}
@@ -0,0 +1,266 @@
+//! Content region settings
+//!
+//! Stores the per-user language Qobuz content is requested in (`lang` on API
+//! calls: biographies, descriptions, genre and label names). When unset, the
+//! API follows the app's UI language.
+//!
+//! Catalogue availability itself is decided by the account's zone, which
+//! comes from the login response and can't be overridden client-side.
+
+use log::info;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use crate::AppState;
+
+/// Content languages the Qobuz API serves, as (code, native name)
+pub const SUPPORTED_LOCALES: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("fr", "Français"),
+    ("de", "Deutsch"),
+    ("es", "Español"),
+    ("it", "Italiano"),
+    ("nl", "Nederlands"),
+    ("pt", "Português"),
+    ("ja", "日本語"),
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionSettings {
+    /// Content language override (None = follow the UI language)
+    pub content_locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiLocale {
+    pub code: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionStatus {
+    pub content_locale: Option<String>,
+    /// Language currently sent to the API
+    pub effective_locale: String,
+    /// Country the account's catalogue comes from (e.g. "FR")
+    pub account_zone: Option<String>,
+}
+
+pub struct RegionSettingsStore {
+    conn: Connection,
+}
+
+impl RegionSettingsStore {
+    fn open_at(dir: &Path, db_name: &str) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+        let db_path = dir.join(db_name);
+        let conn = Connection::open(&db_path)
+            .map_err(|e| format!("Failed to open region settings database: {}", e))?;
+
+        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
+            .map_err(|e| format!("Failed to enable WAL for region settings database: {}", e))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS region_settings (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                content_locale TEXT
+            );",
+        )
+        .map_err(|e| format!("Failed to create region settings table: {}", e))?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO region_settings (id, content_locale) VALUES (1, NULL)",
+            [],
+        )
+        .map_err(|e| format!("Failed to insert default region settings: {}", e))?;
+
+        info!("[RegionSettings] Database initialized");
+
+        Ok(Self { conn })
+    }
+
+    pub fn new_at(base_dir: &Path) -> Result<Self, String> {
+        Self::open_at(base_dir, "region_settings.db")
+    }
+
+    pub fn get_settings(&self) -> Result<RegionSettings, String> {
+        self.conn
+            .query_row(
+                "SELECT content_locale FROM region_settings WHERE id = 1",
+                [],
+                |row| {
+                    Ok(RegionSettings {
+                        content_locale: row.get(0)?,
+                    })
+                },
+            )
+            .map_err(|e| format!("Failed to get region settings: {}", e))
+    }
+
+    pub fn set_content_locale(&self, locale: Option<&str>) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE region_settings SET content_locale = ?1 WHERE id = 1",
+                params![locale],
+            )
+            .map_err(|e| format!("Failed to set content_locale: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Normalize a locale code ("fr-FR", "FR_fr" -> "fr") and check it's supported
+pub fn normalize_locale(locale: &str) -> Option<&'static str> {
+    let lang = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+    SUPPORTED_LOCALES
+        .iter()
+        .find(|(code, _)| *code == lang)
+        .map(|(code, _)| *code)
+}
+
+/// Global state wrapper for thread-safe access
+pub struct RegionSettingsState {
+    pub store: Arc<Mutex<Option<RegionSettingsStore>>>,
+    /// UI language last reported by the frontend, used when there is no override
+    ui_locale: Mutex<String>,
+}
+
+impl RegionSettingsState {
+    pub fn new_empty() -> Self {
+        Self {
+            store: Arc::new(Mutex::new(None)),
+            ui_locale: Mutex::new("en".to_string()),
+        }
+    }
+
+    pub fn init_at(&self, base_dir: &Path) -> Result<(), String> {
+        let new_store = RegionSettingsStore::new_at(base_dir)?;
+        let mut guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock region settings store".to_string())?;
+        *guard = Some(new_store);
+        Ok(())
+    }
+
+    pub fn teardown(&self) -> Result<(), String> {
+        let mut guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock region settings store".to_string())?;
+        *guard = None;
+        Ok(())
+    }
+
+    pub fn get_settings(&self) -> Result<RegionSettings, String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock region settings store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.get_settings()
+    }
+
+    pub fn set_content_locale(&self, locale: Option<&str>) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|_| "Failed to lock region settings store".to_string())?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.set_content_locale(locale)
+    }
+
+    pub fn set_ui_locale(&self, locale: &str) {
+        if let Ok(mut guard) = self.ui_locale.lock() {
+            *guard = normalize_locale(locale).unwrap_or("en").to_string();
+        }
+    }
+
+    /// The language API requests should use: the user's override if set,
+    /// otherwise the UI language
+    pub fn effective_locale(&self) -> String {
+        self.get_settings()
+            .ok()
+            .and_then(|s| s.content_locale)
+            .or_else(|| self.ui_locale.lock().ok().map(|l| l.clone()))
+            .unwrap_or_else(|| "en".to_string())
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_available_locales() -> Vec<ApiLocale> {
+    SUPPORTED_LOCALES
+        .iter()
+        .map(|(code, name)| ApiLocale {
+            code: code.to_string(),
+            name: name.to_string(),
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_region_settings(
+    state: tauri::State<'_, RegionSettingsState>,
+    app_state: tauri::State<'_, AppState>,
+) -> Result<RegionStatus, String> {
+    let settings = state.get_settings()?;
+    let account_zone = app_state.client.read().await.get_zone().await;
+    Ok(RegionStatus {
+        content_locale: settings.content_locale,
+        effective_locale: state.effective_locale(),
+        account_zone,
+    })
+}
+
+/// Set (or clear, with None) the content language. Locale-sensitive caches
+/// are keyed by language, so subsequent requests fetch fresh content;
+/// `api-locale-changed` is emitted so open views can refetch.
+#[tauri::command]
+pub async fn set_content_locale(
+    locale: Option<String>,
+    app: AppHandle,
+    state: tauri::State<'_, RegionSettingsState>,
+    app_state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let locale = match locale.as_deref() {
+        Some(l) => Some(normalize_locale(l).ok_or_else(|| format!("Unsupported locale: {}", l))?),
+        None => None,
+    };
+    info!("[RegionSettings] Setting content_locale to {:?}", locale);
+    state.set_content_locale(locale)?;
+
+    let effective = state.effective_locale();
+    app_state
+        .client
+        .read()
+        .await
+        .set_locale(effective.clone())
+        .await;
+    let _ = app.emit("api-locale-changed", &effective);
+    Ok(effective)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_locale_accepts_region_variants() {
+        assert_eq!(normalize_locale("fr-FR"), Some("fr"));
+        assert_eq!(normalize_locale("DE_de"), Some("de"));
+        assert_eq!(normalize_locale("ja"), Some("ja"));
+        assert_eq!(normalize_locale("xx"), None);
+        assert_eq!(normalize_locale(""), None);
+    }
+}
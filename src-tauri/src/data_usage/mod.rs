@@ -0,0 +1,294 @@
+//! Data usage accounting
+//!
+//! Counts the bytes QBZ fetches for playback (streaming and full-file
+//! caching) and offline downloads. Download loops only bump in-memory
+//! counters through [`record`]; the counters are flushed into a small SQLite
+//! table keyed by local day and category, so "today" and "this month" roll
+//! over on their own. Device-level (not per-user).
+
+use chrono::{Datelike, Local, NaiveDate};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// What the bytes were fetched for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataUsageCategory {
+    /// Progressive playback of tracks that aren't cached yet
+    Streaming,
+    /// Full-file fetches into the playback cache (including prefetch)
+    Cache,
+    /// Offline library downloads
+    Downloads,
+}
+
+impl DataUsageCategory {
+    const ALL: [DataUsageCategory; 3] = [Self::Streaming, Self::Cache, Self::Downloads];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Streaming => "streaming",
+            Self::Cache => "cache",
+            Self::Downloads => "downloads",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Bytes recorded since the last flush, per category
+static PENDING: [AtomicU64; 3] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+/// Count `bytes` fetched for `category`. Cheap enough for per-chunk calls.
+pub fn record(category: DataUsageCategory, bytes: u64) {
+    PENDING[category.index()].fetch_add(bytes, Ordering::Relaxed);
+}
+
+/// Bytes per category over a period
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UsageTotals {
+    pub streaming: u64,
+    pub cache: u64,
+    pub downloads: u64,
+    pub total: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, category: &str, bytes: u64) {
+        match category {
+            "streaming" => self.streaming += bytes,
+            "cache" => self.cache += bytes,
+            "downloads" => self.downloads += bytes,
+            _ => {}
+        }
+        self.total += bytes;
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DataUsage {
+    pub today: UsageTotals,
+    pub this_month: UsageTotals,
+    pub total: UsageTotals,
+    /// First day counted (YYYY-MM-DD), if anything was recorded
+    pub since: Option<String>,
+}
+
+pub struct DataUsageStore {
+    conn: Connection,
+}
+
+impl DataUsageStore {
+    pub fn new() -> Result<Self, String> {
+        let data_dir = dirs::data_dir()
+            .ok_or("Could not determine data directory")?
+            .join("qbz");
+        Self::open_at(&data_dir)
+    }
+
+    pub fn open_at(dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+        let conn = Connection::open(dir.join("data_usage.db"))
+            .map_err(|e| format!("Failed to open data usage database: {}", e))?;
+
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;
+            CREATE TABLE IF NOT EXISTS data_usage (
+                day TEXT NOT NULL,
+                category TEXT NOT NULL,
+                bytes INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, category)
+            );",
+        )
+        .map_err(|e| format!("Failed to create data usage table: {}", e))?;
+
+        Ok(Self { conn })
+    }
+
+    pub fn add(
+        &self,
+        day: NaiveDate,
+        category: DataUsageCategory,
+        bytes: u64,
+    ) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO data_usage (day, category, bytes) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(day, category) DO UPDATE SET bytes = bytes + excluded.bytes",
+                params![
+                    day.format("%Y-%m-%d").to_string(),
+                    category.as_str(),
+                    bytes as i64
+                ],
+            )
+            .map_err(|e| format!("Failed to record data usage: {}", e))?;
+        Ok(())
+    }
+
+    /// Totals for `today`, its month and all time
+    pub fn summary(&self, today: NaiveDate) -> Result<DataUsage, String> {
+        let today_key = today.format("%Y-%m-%d").to_string();
+        let month_start = today
+            .with_day(1)
+            .unwrap_or(today)
+            .format("%Y-%m-%d")
+            .to_string();
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT day, category, bytes FROM data_usage ORDER BY day")
+            .map_err(|e| format!("Failed to query data usage: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })
+            .map_err(|e| format!("Failed to query data usage: {}", e))?;
+
+        let mut usage = DataUsage::default();
+        for (day, category, bytes) in rows.flatten() {
+            let bytes = bytes.max(0) as u64;
+            if usage.since.is_none() {
+                usage.since = Some(day.clone());
+            }
+            usage.total.add(&category, bytes);
+            if day >= month_start && day <= today_key {
+                usage.this_month.add(&category, bytes);
+            }
+            if day == today_key {
+                usage.today.add(&category, bytes);
+            }
+        }
+        Ok(usage)
+    }
+
+    pub fn reset(&self) -> Result<(), String> {
+        self.conn
+            .execute("DELETE FROM data_usage", [])
+            .map_err(|e| format!("Failed to reset data usage: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Thread-safe wrapper for Tauri state management
+pub struct DataUsageState {
+    pub store: Arc<Mutex<Option<DataUsageStore>>>,
+}
+
+impl DataUsageState {
+    pub fn new() -> Result<Self, String> {
+        Ok(Self {
+            store: Arc::new(Mutex::new(Some(DataUsageStore::new()?))),
+        })
+    }
+
+    pub fn new_empty() -> Self {
+        Self {
+            store: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Move the pending counters into the store, attributed to today
+    pub fn flush(&self) -> Result<(), String> {
+        let guard = self
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let Some(store) = guard.as_ref() else {
+            return Ok(());
+        };
+        let today = Local::now().date_naive();
+        for category in DataUsageCategory::ALL {
+            let bytes = PENDING[category.index()].swap(0, Ordering::Relaxed);
+            if bytes == 0 {
+                continue;
+            }
+            if let Err(e) = store.add(today, category, bytes) {
+                // Put the bytes back so the next flush retries them
+                PENDING[category.index()].fetch_add(bytes, Ordering::Relaxed);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_data_usage(state: tauri::State<'_, DataUsageState>) -> Result<DataUsage, String> {
+    state.flush()?;
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("Data usage store not initialized")?;
+    store.summary(Local::now().date_naive())
+}
+
+#[tauri::command]
+pub fn reset_data_usage(state: tauri::State<'_, DataUsageState>) -> Result<(), String> {
+    log::info!("Command: reset_data_usage");
+    for pending in &PENDING {
+        pending.store(0, Ordering::Relaxed);
+    }
+    let guard = state
+        .store
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let store = guard.as_ref().ok_or("Data usage store not initialized")?;
+    store.reset()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_summary_rolls_over_by_day_and_month() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DataUsageStore::open_at(dir.path()).unwrap();
+        store
+            .add(day("2026-09-30"), DataUsageCategory::Downloads, 500)
+            .unwrap();
+        store
+            .add(day("2026-10-01"), DataUsageCategory::Streaming, 100)
+            .unwrap();
+        store
+            .add(day("2026-10-15"), DataUsageCategory::Streaming, 40)
+            .unwrap();
+        store
+            .add(day("2026-10-15"), DataUsageCategory::Streaming, 2)
+            .unwrap();
+        store
+            .add(day("2026-10-15"), DataUsageCategory::Cache, 8)
+            .unwrap();
+
+        let usage = store.summary(day("2026-10-15")).unwrap();
+        assert_eq!(usage.today.streaming, 42);
+        assert_eq!(usage.today.total, 50);
+        assert_eq!(usage.this_month.total, 150);
+        assert_eq!(usage.this_month.downloads, 0);
+        assert_eq!(usage.total.total, 650);
+        assert_eq!(usage.since.as_deref(), Some("2026-09-30"));
+
+        // A new month starts from zero
+        let usage = store.summary(day("2026-11-01")).unwrap();
+        assert_eq!(usage.this_month.total, 0);
+        assert_eq!(usage.today.total, 0);
+        assert_eq!(usage.total.total, 650);
+    }
+}
@@ -130,15 +130,8 @@ pub struct DiscogsSearchResultExtended {
 impl DiscogsClient {
     /// Create a new Discogs client (proxy handles credentials)
     pub fn new() -> Self {
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            reqwest::header::USER_AGENT,
-            reqwest::header::HeaderValue::from_static("QBZ/1.0.0"),
-        );
-
-        let client = Client::builder()
+        let client = crate::network::http_client::builder("QBZ/1.0.0")
             .timeout(Duration::from_secs(10))
-            .default_headers(headers)
             .build()
             .expect("Failed to create HTTP client");
 
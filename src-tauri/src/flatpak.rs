@@ -6,19 +6,18 @@
 use std::path::{Path, PathBuf};
 use std::fs;
 
+use crate::migration::{self, MigrationAction, PlannedMove};
+
 /// Check if QBZ is running inside a Flatpak sandbox
 pub fn is_flatpak() -> bool {
     Path::new("/.flatpak-info").exists()
 }
 
-/// Migrate data from old App ID to new App ID
+/// Old and new App ID config, data and cache directories
 ///
-/// This migrates user data from:
 /// - `~/.config/com.blitzkriegfc.qbz/` → `~/.config/qbz/` (non-Flatpak)
 /// - `~/.var/app/com.blitzkriegfc.qbz/config/com.blitzkriegfc.qbz/` → `~/.var/app/com.blitzfc.qbz/config/qbz/` (Flatpak)
-///
-/// Returns Ok(true) if migration was performed, Ok(false) if not needed
-pub fn migrate_app_id_data() -> Result<bool, String> {
+fn app_id_dirs() -> Result<[(PathBuf, PathBuf); 3], String> {
     let (old_config, old_data, old_cache, new_config, new_data, new_cache) = if is_flatpak() {
         // In Flatpak, we need to migrate from the OLD sandbox to the NEW sandbox
         // dirs::config_dir() returns ~/.var/app/com.blitzfc.qbz/config/ (new sandbox)
@@ -63,56 +62,55 @@ pub fn migrate_app_id_data() -> Result<bool, String> {
         )
     };
 
-    let mut migrated = false;
-
-    // Only migrate if old data exists and new data doesn't
-    if old_config.exists() && !new_config.exists() {
-        log::info!("Migrating config: {:?} → {:?}", old_config, new_config);
-        copy_dir_all(&old_config, &new_config)
-            .map_err(|e| format!("Failed to migrate config: {}", e))?;
-        migrated = true;
-    }
+    Ok([
+        (old_config, new_config),
+        (old_data, new_data),
+        (old_cache, new_cache),
+    ])
+}
 
-    if old_data.exists() && !new_data.exists() {
-        log::info!("Migrating data: {:?} → {:?}", old_data, new_data);
-        copy_dir_all(&old_data, &new_data)
-            .map_err(|e| format!("Failed to migrate data: {}", e))?;
-        migrated = true;
-    }
+/// What `migrate_app_id_data` would copy, without touching anything
+///
+/// Only directories whose old location exists and new location doesn't.
+pub fn plan_app_id_migration() -> Result<Vec<PlannedMove>, String> {
+    Ok(app_id_dirs()?
+        .into_iter()
+        .filter(|(old, new)| old.exists() && !new.exists())
+        .map(|(from, to)| PlannedMove {
+            bytes: crate::user_data::dir_size(&from),
+            from,
+            to,
+            action: MigrationAction::Copy,
+        })
+        .collect())
+}
 
-    if old_cache.exists() && !new_cache.exists() {
-        log::info!("Migrating cache: {:?} → {:?}", old_cache, new_cache);
-        copy_dir_all(&old_cache, &new_cache)
-            .map_err(|e| format!("Failed to migrate cache: {}", e))?;
-        migrated = true;
+/// Migrate data from old App ID to new App ID
+///
+/// The old directories are copied and preserved; `confirm_migration`
+/// deletes them, `rollback_last_migration` deletes the copies instead.
+///
+/// Returns Ok(true) if migration was performed, Ok(false) if not needed
+pub fn migrate_app_id_data() -> Result<bool, String> {
+    let plan = plan_app_id_migration()?;
+    if plan.is_empty() {
+        return Ok(false);
     }
 
-    if migrated {
-        log::info!("App ID migration completed successfully");
-        log::info!("Old directories are preserved and can be manually deleted");
+    for planned in &plan {
+        log::info!("Migrating {:?} → {:?}", planned.from, planned.to);
+        migration::apply_move(planned)
+            .map_err(|e| format!("Failed to migrate {}: {}", planned.from.display(), e))?;
     }
 
-    Ok(migrated)
-}
-
-/// Recursively copy a directory
-fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
-    fs::create_dir_all(dst)?;
-
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        let src_path = entry.path();
-        let dst_path = dst.join(entry.file_name());
-
-        if ty.is_dir() {
-            copy_dir_all(&src_path, &dst_path)?;
-        } else {
-            fs::copy(&src_path, &dst_path)?;
-        }
-    }
+    let global_data_dir = crate::user_data::UserDataPaths::global_data_dir()?;
+    fs::create_dir_all(&global_data_dir)
+        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    migration::record_migration(&global_data_dir, "app_id", plan, None)?;
 
-    Ok(())
+    log::info!("App ID migration completed successfully");
+    log::info!("Old directories are preserved until the migration is confirmed");
+    Ok(true)
 }
 
 /// Get flatpak-specific guidance for user configuration
@@ -0,0 +1,165 @@
+//! Inactivity stop
+//!
+//! `pause_release_ms` only suspends the output stream during a pause. This
+//! goes further: once playback has stayed paused for the configured time it is
+//! fully stopped, releasing the audio device, and optionally the cast device
+//! is disconnected. Emits `playback:inactivity-stop` when it happens.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cast::dlna::DlnaState;
+use crate::cast::CastState;
+use crate::playback_router::PlaybackRoute;
+use crate::AppState;
+
+/// How often the paused state is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Tracks how long playback has been paused
+#[derive(Debug, Default)]
+pub struct InactivityTracker {
+    paused_since: Option<Instant>,
+    fired: bool,
+}
+
+impl InactivityTracker {
+    /// Record the paused state at `now`; returns true once per pause, when
+    /// it has lasted `timeout`
+    pub fn update(&mut self, paused: bool, timeout: Duration, now: Instant) -> bool {
+        if !paused {
+            self.paused_since = None;
+            self.fired = false;
+            return false;
+        }
+        let since = *self.paused_since.get_or_insert(now);
+        if self.fired || now.duration_since(since) < timeout {
+            return false;
+        }
+        self.fired = true;
+        true
+    }
+
+    /// Seconds paused so far
+    pub fn paused_secs(&self, now: Instant) -> u64 {
+        self.paused_since
+            .map(|since| now.duration_since(since).as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// Payload of `playback:inactivity-stop`
+#[derive(Debug, Clone, Serialize)]
+pub struct InactivityStopEvent {
+    pub paused_secs: u64,
+    /// Whether a cast device was disconnected
+    pub disconnected_cast: bool,
+}
+
+/// Watch for long pauses and stop playback when the timeout is reached
+pub fn start_monitor(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut tracker = InactivityTracker::default();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let app_state = app_handle.state::<AppState>();
+            let Some((timeout, while_casting)) = app_state.player.inactivity_stop() else {
+                tracker = InactivityTracker::default();
+                continue;
+            };
+
+            let route = app_state.router.route();
+            let paused = match &route {
+                PlaybackRoute::Local => is_locally_paused(&app_state),
+                _ if !while_casting => false,
+                PlaybackRoute::Chromecast(_) => {
+                    let cast = app_handle.state::<CastState>();
+                    let playback = cast.playback.lock().await;
+                    playback.track_id.is_some() && !playback.is_playing
+                }
+                PlaybackRoute::Dlna(_) => {
+                    let dlna = app_handle.state::<DlnaState>();
+                    let connection = dlna.connection.lock().await;
+                    connection.as_ref().is_some_and(|conn| {
+                        let status = conn.get_status();
+                        status.current_uri.is_some() && !status.is_playing
+                    })
+                }
+            };
+
+            let now = Instant::now();
+            if tracker.update(paused, timeout, now) {
+                let paused_secs = tracker.paused_secs(now);
+                log::info!(
+                    "Playback paused for {}s, stopping (route: {:?})",
+                    paused_secs,
+                    route
+                );
+                stop(&app_handle, &route, paused_secs).await;
+            }
+        }
+    });
+}
+
+fn is_locally_paused(app_state: &AppState) -> bool {
+    let state = &app_state.player.state;
+    state.current_track_id() != 0 && !state.is_playing() && state.format_info().is_some()
+}
+
+async fn stop(app: &AppHandle, route: &PlaybackRoute, paused_secs: u64) {
+    if let Err(e) = crate::commands::stop_playback(app.clone(), app.state()).await {
+        log::warn!("Inactivity stop failed: {}", e);
+    }
+
+    let disconnected = match route {
+        PlaybackRoute::Local => Ok(false),
+        PlaybackRoute::Chromecast(_) => {
+            crate::cast::commands::cast_disconnect(app.clone(), app.state())
+                .await
+                .map(|_| true)
+        }
+        PlaybackRoute::Dlna(_) => {
+            crate::cast::dlna::commands::dlna_disconnect(app.clone(), app.state())
+                .await
+                .map(|_| true)
+        }
+    };
+    let disconnected_cast = disconnected.unwrap_or_else(|e| {
+        log::warn!("Inactivity stop: failed to disconnect cast device: {}", e);
+        false
+    });
+
+    let _ = app.emit(
+        "playback:inactivity-stop",
+        InactivityStopEvent {
+            paused_secs,
+            disconnected_cast,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_fires_once_per_pause() {
+        let timeout = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut tracker = InactivityTracker::default();
+
+        assert!(!tracker.update(true, timeout, start));
+        assert!(!tracker.update(true, timeout, start + Duration::from_secs(30)));
+        assert!(tracker.update(true, timeout, start + Duration::from_secs(60)));
+        assert_eq!(tracker.paused_secs(start + Duration::from_secs(60)), 60);
+        assert!(!tracker.update(true, timeout, start + Duration::from_secs(120)));
+
+        // Resuming resets the timer
+        assert!(!tracker.update(false, timeout, start + Duration::from_secs(130)));
+        assert!(!tracker.update(true, timeout, start + Duration::from_secs(140)));
+        assert!(tracker.update(true, timeout, start + Duration::from_secs(200)));
+    }
+}
@@ -49,11 +49,94 @@ enum LastFmResponse<T> {
     Error { error: u32, message: String },
 }
 
+/// Maximum scrobbles per `track.scrobble` request
+pub const SCROBBLE_BATCH_SIZE: usize = 50;
+
+/// Last.fm "ignored" code for the daily scrobble limit (worth retrying later)
+const IGNORED_DAILY_LIMIT: u32 = 5;
+
+/// One entry of a batch scrobble
+#[derive(Debug, Clone)]
+pub struct BatchScrobble {
+    pub artist: String,
+    pub track: String,
+    pub album: Option<String>,
+    pub timestamp: u64,
+}
+
+/// What Last.fm did with one scrobble of a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScrobbleOutcome {
+    Accepted,
+    /// Ignored by Last.fm (e.g. timestamp too old, artist/track filtered)
+    Ignored { code: u32, message: String },
+}
+
+impl ScrobbleOutcome {
+    /// True when sending the same scrobble again later may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Ignored { code, .. } if *code == IGNORED_DAILY_LIMIT)
+    }
+}
+
+/// Read the per-scrobble results of a `track.scrobble` response.
+///
+/// Last.fm returns `scrobbles.scrobble` as an object for a single scrobble and
+/// as an array otherwise; `ignoredMessage.code` is "0" for accepted entries.
+fn parse_scrobble_outcomes(
+    value: &serde_json::Value,
+    expected: usize,
+) -> Result<Vec<ScrobbleOutcome>, String> {
+    if let Some(error) = value.get("error") {
+        let message = value.get("message").and_then(|m| m.as_str()).unwrap_or("");
+        return Err(format!("Last.fm error {}: {}", error, message));
+    }
+
+    let entries = match value.pointer("/scrobbles/scrobble") {
+        Some(serde_json::Value::Array(items)) => items.iter().collect::<Vec<_>>(),
+        Some(item @ serde_json::Value::Object(_)) => vec![item],
+        _ => return Err("Scrobble response has no results".to_string()),
+    };
+    if entries.len() != expected {
+        return Err(format!(
+            "Scrobble response has {} results for {} scrobbles",
+            entries.len(),
+            expected
+        ));
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let ignored = entry.get("ignoredMessage");
+            let code = ignored
+                .and_then(|m| m.get("code"))
+                .and_then(|c| match c {
+                    serde_json::Value::String(s) => s.parse().ok(),
+                    other => other.as_u64().map(|n| n as u32),
+                })
+                .unwrap_or(0);
+            if code == 0 {
+                ScrobbleOutcome::Accepted
+            } else {
+                let message = ignored
+                    .and_then(|m| m.get("#text"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                ScrobbleOutcome::Ignored { code, message }
+            }
+        })
+        .collect())
+}
+
 /// Last.fm API client
 /// Uses Cloudflare Workers proxy to handle API credentials and signature generation
 pub struct LastFmClient {
     client: Client,
     session_key: Option<String>,
+    /// User toggle; now-playing reports are skipped when off
+    scrobbling_enabled: bool,
 }
 
 impl Default for LastFmClient {
@@ -81,6 +164,7 @@ impl LastFmClient {
                 .build()
                 .unwrap_or_else(|_| Client::new()),
             session_key: None,
+            scrobbling_enabled: true,
         }
     }
 
@@ -92,6 +176,14 @@ impl LastFmClient {
         self.session_key.is_some()
     }
 
+    pub fn set_scrobbling_enabled(&mut self, enabled: bool) {
+        self.scrobbling_enabled = enabled;
+    }
+
+    pub fn is_scrobbling_enabled(&self) -> bool {
+        self.scrobbling_enabled
+    }
+
     /// Compatibility: proxy handles credentials, this is a no-op
     pub fn has_credentials(&self) -> bool {
         true
@@ -211,6 +303,68 @@ impl LastFmClient {
         }
     }
 
+    /// Scrobble up to `SCROBBLE_BATCH_SIZE` tracks in one request.
+    ///
+    /// Returns one outcome per input scrobble, in order. An `Err` means the
+    /// request as a whole failed and nothing was recorded.
+    pub async fn scrobble_batch(
+        &self,
+        scrobbles: &[BatchScrobble],
+    ) -> Result<Vec<ScrobbleOutcome>, String> {
+        if scrobbles.is_empty() {
+            return Ok(Vec::new());
+        }
+        if scrobbles.len() > SCROBBLE_BATCH_SIZE {
+            return Err(format!(
+                "At most {} scrobbles per request",
+                SCROBBLE_BATCH_SIZE
+            ));
+        }
+
+        let session_key = self
+            .session_key
+            .as_ref()
+            .ok_or("Not authenticated with Last.fm")?;
+
+        let url = format!("{}/track.scrobble", LASTFM_PROXY_URL);
+
+        // Batch form of track.scrobble: artist[i], track[i], timestamp[i], ...
+        let mut body = json!({ "sk": session_key });
+        for (i, scrobble) in scrobbles.iter().enumerate() {
+            body[format!("artist[{}]", i)] = json!(scrobble.artist);
+            body[format!("track[{}]", i)] = json!(scrobble.track);
+            body[format!("timestamp[{}]", i)] = json!(scrobble.timestamp.to_string());
+            if let Some(album_name) = &scrobble.album {
+                body[format!("album[{}]", i)] = json!(album_name);
+            }
+        }
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to scrobble: {}", e))?;
+
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!("Scrobble failed: {}", text));
+        }
+
+        let value: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| format!("Failed to parse response: {} - Raw: {}", e, text))?;
+        let outcomes = parse_scrobble_outcomes(&value, scrobbles.len())?;
+
+        let accepted = outcomes.iter().filter(|o| **o == ScrobbleOutcome::Accepted).count();
+        log::info!("Scrobbled batch: {}/{} accepted", accepted, scrobbles.len());
+        Ok(outcomes)
+    }
+
     /// Update "now playing" status
     pub async fn update_now_playing(
         &self,
@@ -252,3 +406,34 @@ impl LastFmClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_per_scrobble_outcomes() {
+        let batch = json!({
+            "scrobbles": {
+                "@attr": { "accepted": 1, "ignored": 1 },
+                "scrobble": [
+                    { "ignoredMessage": { "code": "0", "#text": "" } },
+                    { "ignoredMessage": { "code": "3", "#text": "Timestamp too old" } }
+                ]
+            }
+        });
+        let outcomes = parse_scrobble_outcomes(&batch, 2).unwrap();
+        assert_eq!(outcomes[0], ScrobbleOutcome::Accepted);
+        assert!(matches!(&outcomes[1], ScrobbleOutcome::Ignored { code: 3, .. }));
+        assert!(!outcomes[1].is_retryable());
+
+        // A single scrobble comes back as an object
+        let single = json!({
+            "scrobbles": { "scrobble": { "ignoredMessage": { "code": "5", "#text": "Daily limit" } } }
+        });
+        let outcomes = parse_scrobble_outcomes(&single, 1).unwrap();
+        assert!(outcomes[0].is_retryable());
+
+        assert!(parse_scrobble_outcomes(&json!({ "error": 9, "message": "Invalid session" }), 1).is_err());
+    }
+}
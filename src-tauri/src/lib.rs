@@ -13,9 +13,11 @@ pub mod cast;
 pub mod commands;
 pub mod config;
 pub mod credentials;
+pub mod data_usage;
 pub mod discogs;
 pub mod offline_cache;
 pub mod flatpak;
+pub mod inactivity;
 pub mod lastfm;
 pub mod library;
 pub mod listenbrainz;
@@ -25,11 +27,14 @@ pub mod media_controls;
 pub mod migration;
 pub mod musicbrainz;
 pub mod network;
+pub mod now_playing;
 pub mod offline;
 pub mod playback_context;
+pub mod playback_router;
 pub mod plex;
 pub mod player;
 pub mod playlist_import;
+pub mod quality;
 pub mod queue;
 pub mod reco_store;
 pub mod radio_engine;
@@ -62,9 +67,11 @@ pub struct AppState {
     pub context: ContextManager,
     pub media_controls: MediaControlsManager,
     pub audio_cache: Arc<AudioCache>,
+    pub waveforms: Arc<audio::WaveformCache>,
     pub lastfm: Arc<Mutex<LastFmClient>>,
     pub songlink: SongLinkClient,
     pub visualizer: Visualizer,
+    pub router: playback_router::PlaybackRouter,
 }
 
 impl AppState {
@@ -80,8 +87,8 @@ impl AppState {
         device_name: Option<String>,
         audio_settings: config::audio_settings::AudioSettings,
     ) -> Self {
-        // Create playback cache (L2 - disk, 500MB)
-        let playback_cache = match PlaybackCache::new(500 * 1024 * 1024) {
+        // Create playback cache (L2 - disk)
+        let playback_cache = match PlaybackCache::new(cache::mb_to_bytes(audio_settings.disk_cache_mb)) {
             Ok(cache) => Some(Arc::new(cache)),
             Err(e) => {
                 log::warn!("Failed to create playback cache: {}. Disk spillover disabled.", e);
@@ -89,11 +96,12 @@ impl AppState {
             }
         };
 
-        // Create audio cache (L1 - memory, 300MB) with optional disk spillover
+        // Create audio cache (L1 - memory) with optional disk spillover
+        let memory_cache_bytes = cache::mb_to_bytes(audio_settings.memory_cache_mb) as usize;
         let audio_cache = if let Some(pc) = playback_cache {
-            Arc::new(AudioCache::with_playback_cache(300 * 1024 * 1024, pc))
+            Arc::new(AudioCache::with_playback_cache(memory_cache_bytes, pc))
         } else {
-            Arc::new(AudioCache::default())
+            Arc::new(AudioCache::new(memory_cache_bytes))
         };
 
         // Create visualizer first to get the tap for the player
@@ -107,9 +115,11 @@ impl AppState {
             context: ContextManager::new(),
             media_controls: MediaControlsManager::new(),
             audio_cache,
+            waveforms: Arc::new(audio::WaveformCache::new()),
             lastfm: Arc::new(Mutex::new(LastFmClient::default())),
             songlink: SongLinkClient::new(),
             visualizer,
+            router: playback_router::PlaybackRouter::new(),
         }
     }
 }
@@ -139,23 +149,274 @@ pub fn update_media_controls_metadata(
     media_controls.set_metadata(&track_info);
 }
 
+/// Watch output devices for hotplug. Emits `audio:devices_changed` whenever
+/// devices are added or removed.
+///
+/// When the selected device disappears, playback is paused and the player
+/// falls back to the system default output. When it comes back it is
+/// reinitialized if `switch_to_reconnected_device` is on, and playback resumes
+/// if the user enabled `auto_resume_on_reconnect`. Emits `audio:device-changed`
+/// either way.
+///
+/// On the system default output, a default-sink switch pauses playback when
+/// `pause_on_audio_route_change` is on, and switching back resumes it under the
+/// same `auto_resume_on_reconnect` rule. Emits `audio:route-changed`.
+fn start_device_monitor(app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut devices = audio::DeviceListWatch::default();
+        let mut watch = audio::DeviceWatch::default();
+        let mut was_playing = false;
+        let mut on_fallback = false;
+        let mut route = audio::RouteWatch::default();
+        let mut route_paused = false;
+
+        // Sink changes wake the loop early; otherwise it polls
+        let (wake_tx, wake_rx) = std::sync::mpsc::channel();
+        audio::device_monitor::spawn_route_listener(wake_tx.clone());
+
+        loop {
+            let _ = wake_rx.recv_timeout(audio::DEVICE_POLL_INTERVAL);
+            while wake_rx.try_recv().is_ok() {}
+
+            let settings = app_handle
+                .try_state::<config::audio_settings::AudioSettingsState>()
+                .and_then(|state| {
+                    let guard = state.store.lock().ok()?;
+                    guard.as_ref()?.get_settings().ok()
+                });
+            let Some(settings) = settings else {
+                continue;
+            };
+
+            let listed = audio::device_monitor::list_devices(settings.backend_type);
+            if let Some(diff) = devices.update(settings.backend_type, listed.as_deref()) {
+                log::info!(
+                    "Output devices changed: +{:?} -{:?}",
+                    diff.added,
+                    diff.removed
+                );
+                let _ = app_handle.emit("audio:devices_changed", &diff);
+            }
+
+            let Some(device_id) = settings.output_device.clone() else {
+                // System default output follows the OS; watch where it routes to
+                watch.reset();
+                on_fallback = false;
+                if !settings.pause_on_audio_route_change {
+                    route.reset();
+                    route_paused = false;
+                    continue;
+                }
+                let sink = audio::device_monitor::default_sink(settings.backend_type);
+                let Some(change) = route.update(sink.as_deref()) else {
+                    continue;
+                };
+
+                let state = app_handle.state::<AppState>();
+                let event = match change {
+                    audio::RouteChange::Away { from, to } => {
+                        if state.player.state.is_playing() {
+                            log::info!("Default output switched from {} to {}, pausing", from, to);
+                            let _ = state.player.pause();
+                            route_paused = true;
+                        } else if !route_paused {
+                            // Nothing was interrupted, so there is nothing to come back for
+                            route.settle();
+                        }
+                        audio::RouteChangedEvent {
+                            sink: to,
+                            returned: false,
+                            paused: route_paused,
+                            resumed: false,
+                        }
+                    }
+                    audio::RouteChange::Back { sink } => {
+                        let resumed = route_paused
+                            && settings.auto_resume_on_reconnect
+                            && !state.player.state.is_playing();
+                        if resumed {
+                            log::info!("Default output switched back to {}, resuming", sink);
+                            let _ = state.player.resume();
+                        }
+                        route_paused = false;
+                        audio::RouteChangedEvent {
+                            sink,
+                            returned: true,
+                            paused: false,
+                            resumed,
+                        }
+                    }
+                };
+                let _ = app_handle.emit("audio:route-changed", &event);
+                continue;
+            };
+            route.reset();
+            route_paused = false;
+
+            let present = listed
+                .as_deref()
+                .map(|listed| audio::device_monitor::is_device_present(listed, &device_id));
+            let Some(change) = watch.update(&device_id, present) else {
+                continue;
+            };
+
+            let state = app_handle.state::<AppState>();
+            let event = match change {
+                audio::DeviceChange::Disappeared => {
+                    log::warn!(
+                        "Output device disappeared: {}, falling back to default",
+                        device_id
+                    );
+                    was_playing = state.player.state.is_playing();
+                    if was_playing {
+                        let _ = state.player.pause();
+                    }
+                    // The stored setting keeps the device so it can be picked up again
+                    let fallback = config::audio_settings::AudioSettings {
+                        output_device: None,
+                        ..settings.clone()
+                    };
+                    let _ = state.player.reload_settings(fallback);
+                    on_fallback = state.player.reinit_device(None).is_ok();
+                    state.player.state.set_stream_error(!on_fallback);
+                    audio::DeviceChangedEvent {
+                        device: device_id,
+                        available: false,
+                        resumed: false,
+                        fallback_to_default: on_fallback,
+                    }
+                }
+                audio::DeviceChange::Reappeared if !settings.switch_to_reconnected_device => {
+                    log::info!(
+                        "Output device reappeared: {}, staying on default",
+                        device_id
+                    );
+                    was_playing = false;
+                    audio::DeviceChangedEvent {
+                        device: device_id,
+                        available: true,
+                        resumed: false,
+                        fallback_to_default: on_fallback,
+                    }
+                }
+                audio::DeviceChange::Reappeared => {
+                    log::info!("Output device reappeared: {}, reinitializing", device_id);
+                    let _ = state.player.reload_settings(settings.clone());
+                    let reinit_ok = state.player.reinit_device(Some(device_id.clone())).is_ok();
+                    let resumed = reinit_ok && was_playing && settings.auto_resume_on_reconnect;
+                    if resumed {
+                        log::info!("Resuming playback after device reconnect");
+                        let _ = state.player.resume();
+                    }
+                    was_playing = false;
+                    on_fallback = !reinit_ok;
+                    audio::DeviceChangedEvent {
+                        device: device_id,
+                        available: true,
+                        resumed,
+                        fallback_to_default: on_fallback,
+                    }
+                }
+            };
+            let _ = app_handle.emit("audio:device-changed", &event);
+        }
+    });
+}
+
+/// Hand playback back to the local player when a Chromecast drops off
+fn start_cast_loss_monitor(app_handle: tauri::AppHandle) {
+    let Some(receiver) = app_handle.state::<cast::CastState>().chromecast.take_loss_receiver() else {
+        return;
+    };
+    std::thread::spawn(move || {
+        while let Ok(lost) = receiver.recv() {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                cast::commands::handle_connection_lost(&app_handle, lost).await;
+            });
+        }
+    });
+}
+
+/// Background check for favorite tracks that are no longer streamable.
+///
+/// Runs only while logged in; per-track throttling lives in the favorites
+/// cache, so most runs only touch tracks added since the last check. Emits
+/// `favorites:unavailable` when any are found.
+fn start_favorites_availability_check(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(config::favorites_cache::AVAILABILITY_BACKGROUND_INTERVAL).await;
+
+            let state = app_handle.state::<AppState>();
+            if !state.client.read().await.is_logged_in().await {
+                continue;
+            }
+
+            let cache = app_handle.state::<config::favorites_cache::FavoritesCacheState>();
+            match config::favorites_cache::check_favorites_availability(&state.client, &cache, false).await {
+                Ok(unavailable) if !unavailable.is_empty() => {
+                    log::info!("{} favorite tracks are no longer available", unavailable.len());
+                    let _ = app_handle.emit("favorites:unavailable", &unavailable);
+                }
+                Ok(_) => {}
+                Err(e) => log::debug!("Favorites availability check skipped: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically retry favorite changes queued while offline or after a failed sync
+fn start_favorites_sync(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(commands::FAVORITE_SYNC_INTERVAL).await;
+            if let Err(e) = commands::sync_favorite_queue(&app_handle).await {
+                log::debug!("Favorite sync skipped: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically persist the data usage counters
+fn start_data_usage_flush(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+            flush_data_usage(&app_handle);
+        }
+    });
+}
+
+/// Persist the data usage counters now (also on exit and restart, so the
+/// bytes of the last minute aren't lost)
+fn flush_data_usage(app_handle: &tauri::AppHandle) {
+    if let Some(state) = app_handle.try_state::<data_usage::DataUsageState>() {
+        if let Err(e) = state.flush() {
+            log::warn!("Failed to persist data usage: {}", e);
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 #[tauri::command]
 fn restart_app(app: tauri::AppHandle) {
     log::info!("App restart requested by user");
+    flush_data_usage(&app);
     app.restart();
 }
 
 pub fn run() {
+    api_server::mark_process_start();
+
     // Load .env file if present (for development)
     // Silently ignore if not found (production builds use compile-time env vars)
     dotenvy::dotenv().ok();
 
-    // Initialize logging with TeeWriter (captures to ring buffer + stderr)
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
-        .format_timestamp_millis()
-        .target(env_logger::Target::Pipe(Box::new(logging::TeeWriter)))
-        .init();
+    // Initialize logging with TeeWriter (captures to ring buffer + stderr);
+    // the filter can be changed later with set_log_level
+    logging::init();
+    logging::install_panic_hook();
 
     log::info!("QBZ starting...");
 
@@ -212,6 +473,25 @@ pub fn run() {
     );
     let use_system_titlebar = window_settings.use_system_titlebar;
 
+    // Apply network settings (proxy, User-Agent, timeouts) before any HTTP client is built
+    let network_settings_state = config::network_settings::NetworkSettingsState::new()
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to initialize network settings: {}. Using empty state.", e);
+            config::network_settings::NetworkSettingsState::new_empty()
+        });
+    if let Ok(settings) = network_settings_state.get_settings() {
+        if let Err(e) = network::http_client::configure(&settings) {
+            log::warn!("Ignoring saved network settings: {}", e);
+        }
+    }
+
+    // Data usage counters (device-level)
+    let data_usage_state = data_usage::DataUsageState::new()
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to initialize data usage store: {}. Usage won't be persisted.", e);
+            data_usage::DataUsageState::new_empty()
+        });
+
     // Initialize casting state (Chromecast, DLNA) — device-level, not per-user
     let cast_state = cast::CastState::new()
         .expect("Failed to initialize Chromecast state");
@@ -243,6 +523,7 @@ pub fn run() {
     let favorites_prefs_state = config::favorites_preferences::FavoritesPreferencesState::new_empty();
     let favorites_cache_state = config::favorites_cache::FavoritesCacheState::new_empty();
     let tray_settings_state = config::tray_settings::TraySettingsState::new_empty();
+    let region_settings_state = config::region_settings::RegionSettingsState::new_empty();
     let remote_control_settings_state = config::remote_control_settings::RemoteControlSettingsState::new_empty();
     let allowed_origins_state = config::remote_control_settings::AllowedOriginsState::new_empty();
     let legal_settings_state = config::legal_settings::create_empty_legal_settings_state();
@@ -268,6 +549,25 @@ pub fn run() {
             log::warn!("Failed to initialize window settings: {}. Using empty state.", e);
             config::window_settings::WindowSettingsState::new_empty()
         });
+    let media_keys_settings_state = config::media_keys_settings::MediaKeysSettingsState::new()
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to initialize media key settings: {}. Using empty state.", e);
+            config::media_keys_settings::MediaKeysSettingsState::new_empty()
+        });
+    let artwork_settings_state = config::artwork_settings::ArtworkSettingsState::new()
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to initialize artwork settings: {}. Using empty state.", e);
+            config::artwork_settings::ArtworkSettingsState::new_empty()
+        });
+    let artwork_cache_mb = artwork_settings_state
+        .get_settings()
+        .map(|settings| settings.cache_mb)
+        .unwrap_or(cache::artwork::DEFAULT_ARTWORK_CACHE_MB);
+    let artwork_cache_state = cache::ArtworkCacheState {
+        cache: cache::ArtworkCache::new(cache::mb_to_bytes(artwork_cache_mb))
+            .map_err(|e| log::warn!("Failed to initialize artwork cache: {}", e))
+            .ok(),
+    };
 
     // Clone settings for use in closures
     let enable_tray = tray_settings.enable_tray;
@@ -332,6 +632,27 @@ pub fn run() {
                 .media_controls
                 .init(app.handle().clone());
 
+            // Ask for a new login once the session can't be renewed silently
+            if let Ok(client) = app.state::<AppState>().client.try_read() {
+                let auth_app = app.handle().clone();
+                client.set_auth_expired_handler(Arc::new(move || {
+                    log::warn!("Qobuz session expired, asking the user to log in again");
+                    let _ = auth_app.emit("auth:expired", ());
+                }));
+            }
+
+            // Grab the media keys globally if the user opted in
+            let global_media_keys = app
+                .state::<config::media_keys_settings::MediaKeysSettingsState>()
+                .get_settings()
+                .map(|settings| settings.global_media_keys)
+                .unwrap_or(false);
+            if global_media_keys {
+                app.state::<AppState>()
+                    .media_controls
+                    .set_global_media_keys(true, app.handle().clone());
+            }
+
             // NOTE: Visualizer FFT thread and Remote Control API server are started
             // in activate_user_session (post-login), not here. They need per-user
             // state to be initialized first.
@@ -347,6 +668,9 @@ pub fn run() {
                 let mut last_position: u64 = 0;
                 let mut last_is_playing: bool = false;
                 let mut last_track_id: u64 = 0;
+                let mut last_buffering: Option<(bool, u8)> = None;
+                let mut audio_thread_reported = false;
+                let mut now_playing = now_playing::NowPlayingTracker::default();
 
                 loop {
                     // Check playing/track state first to determine sleep duration
@@ -354,10 +678,10 @@ pub fn run() {
                     let track_id = player_state.current_track_id();
 
                     // Adaptive polling:
-                    // - fast (500ms) when playing - balances UI responsiveness vs CPU
+                    // - fast (500ms) when playing or buffering - balances UI responsiveness vs CPU
                     // - slow (1000ms) when paused/stopped with a track loaded
                     // - very slow (5000ms) when no track is loaded (idle)
-                    let sleep_duration = if is_playing {
+                    let sleep_duration = if is_playing || player_state.buffering_status().is_some() {
                         std::time::Duration::from_millis(500)
                     } else if track_id == 0 {
                         std::time::Duration::from_millis(5000)
@@ -366,6 +690,25 @@ pub fn run() {
                     };
                     std::thread::sleep(sleep_duration);
 
+                    // The audio thread died (panic): restart it once, then
+                    // tell the user to restart QBZ
+                    if !player_state.is_audio_thread_alive() && !audio_thread_reported {
+                        let restarted = app_handle.state::<AppState>().player.respawn_audio_thread();
+                        let event = player::PlaybackErrorEvent {
+                            message: if restarted {
+                                "The audio engine crashed and was restarted. Start playback again; if it keeps failing, restart QBZ.".to_string()
+                            } else {
+                                player::AUDIO_THREAD_DEAD.to_string()
+                            },
+                            fatal: !restarted,
+                            restarted,
+                        };
+                        log::error!("Audio thread is gone (restarted: {})", restarted);
+                        let _ = app_handle.emit("playback:error", &event);
+                        // Report a second crash too, but only once
+                        audio_thread_reported = !restarted;
+                    }
+
                     // Re-check after sleep (state might have changed)
                     let is_playing = player_state.is_playing();
                     let position = player_state.current_position();
@@ -382,11 +725,19 @@ pub fn run() {
 
                     let should_update_mpris = should_emit || (track_id == 0 && last_track_id != 0);
 
+                    // Last.fm / ListenBrainz "now playing"
+                    if now_playing.update(track_id, is_playing, position, duration, std::time::Instant::now()) {
+                        now_playing::spawn_report(app_handle.clone(), track_id);
+                    }
+
                     if should_emit {
+                        // Keep the audio thread informed about album-contiguous next
+                        // tracks; only while something changed, so idle ticks skip the queue lock
+                        let queue_state = &app_handle.state::<AppState>().queue;
+                        player_state.set_gapless_boundary(queue_state.next_is_gapless_boundary());
+
                         let sample_rate = player_state.get_sample_rate();
                         let bit_depth = player_state.get_bit_depth();
-                        // Get queue state for shuffle/repeat
-                        let queue_state = &app_handle.state::<AppState>().queue;
                         let shuffle = queue_state.is_shuffle();
                         let repeat = match queue_state.get_repeat() {
                             queue::RepeatMode::Off => "off",
@@ -407,14 +758,51 @@ pub fn run() {
                             normalization_gain,
                             gapless_ready: player_state.is_gapless_ready(),
                             gapless_next_track_id: player_state.get_gapless_next_track_id(),
+                            gapless_boundary: player_state.is_gapless_boundary(),
+                            sleep_timer_remaining: player_state
+                                .sleep_timer_status()
+                                .map(|t| t.remaining_secs),
+                            playing_from_cache: player_state.is_playing_from_cache(),
+                            format_id: player_state.get_format_id(),
+                            reduced_quality: player_state.is_reduced_quality(),
+                            replaygain_mode: player_state.replaygain_mode(),
+                            replaygain_auto: app_handle.state::<AppState>().player.is_replaygain_auto(),
+                            buffered_ahead_secs: player_state.buffered_ahead_secs(),
                         };
                         let _ = app_handle.emit("playback:state", &event);
                         api_server::broadcast_playback_event(&app_handle, &event);
+                        if track_id != last_track_id {
+                            api_server::broadcast_now_playing(&app_handle);
+                        }
                         last_position = position;
                         last_is_playing = is_playing;
                         last_track_id = track_id;
                     }
 
+                    // Streaming buffer progress (initial fill and mid-track re-buffering)
+                    let buffering = player_state.buffering_status();
+                    if buffering != last_buffering {
+                        let (rebuffering, progress) = buffering.unwrap_or((false, 100));
+                        let _ = app_handle.emit("playback:buffering", &player::BufferingEvent {
+                            track_id,
+                            buffering: buffering.is_some(),
+                            progress,
+                            rebuffering,
+                            buffered_ahead_secs: player_state.buffered_ahead_secs(),
+                        });
+                        last_buffering = buffering;
+                    }
+
+                    // Streaming stopped on a download failure or stall
+                    if let Some(message) = player_state.take_stream_failure() {
+                        log::warn!("Streaming playback stopped: {}", message);
+                        let _ = app_handle.emit("playback:error", &player::PlaybackErrorEvent {
+                            message,
+                            fatal: false,
+                            restarted: false,
+                        });
+                    }
+
                     if should_update_mpris {
                         let media_controls = &app_handle.state::<AppState>().media_controls;
                         if track_id == 0 {
@@ -426,6 +814,27 @@ pub fn run() {
                 }
             });
 
+            // Watch for output device unplug/replug
+            start_device_monitor(app.handle().clone());
+
+            // Watch for Chromecasts dropping off the network
+            start_cast_loss_monitor(app.handle().clone());
+
+            // Periodically re-check favorites for tracks pulled from Qobuz
+            start_favorites_availability_check(app.handle().clone());
+
+            // Send favorite changes queued while offline
+            start_favorites_sync(app.handle().clone());
+
+            // Persist data usage counters
+            start_data_usage_flush(app.handle().clone());
+
+            // Checkpoint the playback position for crash recovery
+            session_store::start_autosave(app.handle().clone());
+
+            // Stop playback after a long pause
+            inactivity::start_monitor(app.handle().clone());
+
             Ok(())
         })
         .on_window_event(move |window, event| {
@@ -475,6 +884,7 @@ pub fn run() {
         .manage(favorites_prefs_state)
         .manage(favorites_cache_state)
         .manage(tray_settings_state)
+        .manage(region_settings_state)
         .manage(remote_control_settings_state)
         .manage(allowed_origins_state)
         .manage(api_server_state)
@@ -486,8 +896,13 @@ pub fn run() {
         .manage(artist_vectors_state)
         .manage(blacklist_state)
         .manage(developer_settings_state)
+        .manage(network_settings_state)
+        .manage(data_usage_state)
         .manage(graphics_settings_state)
         .manage(window_settings_state)
+        .manage(media_keys_settings_state)
+        .manage(artwork_settings_state)
+        .manage(artwork_cache_state)
         .invoke_handler(tauri::generate_handler![
             // Auth commands
             commands::init_client,
@@ -496,6 +911,8 @@ pub fn run() {
             commands::is_logged_in,
             commands::get_user_info,
             commands::set_api_locale,
+            commands::get_request_policy,
+            commands::set_request_policy,
             // Credential persistence commands
             commands::has_saved_credentials,
             commands::save_credentials,
@@ -505,12 +922,15 @@ pub fn run() {
             commands::get_last_user_id,
             commands::activate_user_session,
             commands::deactivate_user_session,
+            commands::switch_user_session,
+            commands::get_saved_user_ids,
             commands::factory_reset,
             // Search commands
             commands::search_albums,
             commands::search_tracks,
             commands::search_artists,
             commands::search_all,
+            commands::search_offline,
             commands::get_album,
             commands::get_featured_albums,
             commands::get_genres,
@@ -537,6 +957,11 @@ pub fn run() {
             commands::play_next_gapless,
             commands::set_volume,
             commands::seek,
+            commands::get_playback_route,
+            commands::get_current_format_info,
+            commands::set_sleep_timer,
+            commands::get_sleep_timer,
+            commands::cancel_sleep_timer,
             commands::get_playback_state,
             commands::set_media_metadata,
             commands::get_audio_devices,
@@ -548,14 +973,25 @@ pub fn run() {
             commands::get_hardware_audio_status,
             commands::start_bitdepth_capture,
             commands::stop_bitdepth_capture,
+            commands::get_audio_diagnostics,
+            commands::get_bitperfect_status,
+            commands::test_audio_output,
+            commands::get_eq_config,
+            commands::set_eq_config,
+            commands::set_eq_enabled,
+            commands::is_eq_active,
+            commands::get_eq_presets,
             // Queue commands
             commands::add_to_queue,
             commands::add_to_queue_next,
             commands::add_tracks_to_queue,
             commands::set_queue,
+            commands::play_collection,
             commands::clear_queue,
             commands::remove_from_queue,
             commands::move_queue_track,
+            commands::move_queue_tracks,
+            commands::insert_tracks_in_queue,
             commands::get_current_queue_track,
             commands::peek_next_track,
             commands::next_track,
@@ -566,12 +1002,16 @@ pub fn run() {
             commands::set_repeat,
             commands::get_repeat,
             commands::get_queue_state,
+            commands::get_queue_window,
+            commands::get_current_queue_index,
+            commands::get_queue_timing,
             // Radio engine commands
             commands::create_artist_radio,
             commands::create_track_radio,
             commands::refill_radio_queue,
             commands::get_queue_remaining,
             commands::create_infinite_radio,
+            commands::handle_queue_end,
             // Playback context commands
             commands::get_playback_context,
             commands::set_playback_context,
@@ -587,13 +1027,17 @@ pub fn run() {
             commands::create_playlist,
             commands::delete_playlist,
             commands::add_tracks_to_playlist,
+            commands::save_queue_as_playlist,
             commands::remove_tracks_from_playlist,
             commands::update_playlist,
             commands::get_tracks_by_ids,
+            commands::resolve_track_album,
+            commands::resolve_track_artist,
             commands::get_current_user_id,
             commands::subscribe_playlist,
             commands::get_track_info,
             commands::get_album_credits,
+            commands::get_album_credits_detailed,
             // Playlist import commands
             commands::playlist_import_preview,
             commands::playlist_import_execute,
@@ -606,13 +1050,24 @@ pub fn run() {
             commands::get_favorites,
             commands::add_favorite,
             commands::remove_favorite,
+            commands::add_favorites_batch,
+            commands::remove_favorites_batch,
+            commands::add_favorites,
+            commands::remove_favorites,
+            commands::sync_queued_favorites,
+            commands::get_queued_favorite_count,
             // Notification commands
             commands::show_track_notification,
             commands::show_notification,
             // Cache commands
             commands::get_cache_stats,
+            commands::set_cache_limits,
             commands::clear_cache,
             commands::clear_artist_cache,
+            commands::get_storage_usage,
+            commands::preview_migration,
+            commands::confirm_migration,
+            commands::rollback_last_migration,
             // Last.fm commands
             commands::lastfm_has_embedded_credentials,
             commands::lastfm_has_credentials,
@@ -625,6 +1080,8 @@ pub fn run() {
             commands::lastfm_disconnect,
             commands::lastfm_scrobble,
             commands::lastfm_now_playing,
+            commands::lastfm_set_scrobbling_enabled,
+            commands::flush_scrobble_queue,
             // Share commands
             commands::share_track_songlink,
             commands::share_album_songlink,
@@ -637,6 +1094,10 @@ pub fn run() {
             library::commands::library_cleanup_missing_files,
             library::commands::library_get_cache_stats,
             library::commands::library_clear_artwork_cache,
+            commands::artwork::get_artwork_url,
+            commands::artwork::get_cached_artwork,
+            commands::artwork::get_artwork_cache_report,
+            commands::artwork::clear_artwork_cache,
             library::commands::library_clear_thumbnails_cache,
             library::commands::library_get_folders,
             library::commands::library_get_folders_with_metadata,
@@ -663,6 +1124,7 @@ pub fn run() {
             library::commands::playlist_get_settings,
             library::commands::playlist_save_settings,
             library::commands::playlist_set_sort,
+            library::commands::playlist_set_view,
             library::commands::playlist_set_artwork,
             library::commands::playlist_add_local_track,
             library::commands::playlist_remove_local_track,
@@ -720,7 +1182,11 @@ pub fn run() {
             library::commands::library_get_artist_images,
             library::commands::library_cache_artist_image,
             library::commands::library_set_custom_artist_image,
+            library::commands::library_fetch_missing_artist_images,
             library::commands::library_get_canonical_names,
+            library::commands::library_get_artist_mbids,
+            library::commands::library_preview_artist_merge,
+            library::commands::library_merge_artists,
             // Playlist local content analysis commands (offline mode)
             library::commands::playlist_analyze_local_content,
             library::commands::playlist_get_local_content_status,
@@ -804,6 +1270,13 @@ pub fn run() {
             offline_cache::commands::open_album_folder,
             offline_cache::commands::open_track_folder,
             offline_cache::commands::check_album_fully_cached,
+            offline_cache::commands::cache_album_for_offline,
+            offline_cache::commands::cache_playlist_for_offline,
+            offline_cache::commands::pause_offline_batch,
+            offline_cache::commands::resume_offline_batch,
+            offline_cache::commands::cancel_offline_batch,
+            offline_cache::commands::get_offline_batches,
+            offline_cache::discography::download_artist_discography,
             offline_cache::commands::check_offline_root_mounted,
             offline_cache::commands::validate_offline_path,
             offline_cache::commands::move_offline_cache_to_path,
@@ -816,6 +1289,7 @@ pub fn run() {
             lyrics::commands::lyrics_clear_cache,
             // Recommendation store commands
             reco_store::commands::reco_log_event,
+            reco_store::commands::reco_get_stats,
             reco_store::commands::reco_get_home,
             reco_store::commands::reco_train_scores,
             reco_store::commands::reco_get_home_ml,
@@ -830,6 +1304,7 @@ pub fn run() {
             session_store::save_session_position,
             session_store::save_session_playback_mode,
             session_store::clear_session,
+            session_store::resume_last_session,
             // Audio settings commands
             config::audio_settings::get_audio_settings,
             config::audio_settings::set_audio_output_device,
@@ -844,9 +1319,28 @@ pub fn run() {
             config::audio_settings::set_audio_streaming_only,
             config::audio_settings::set_audio_limit_quality_to_device,
             config::audio_settings::set_audio_device_max_sample_rate,
+            config::audio_settings::set_audio_quality_limit_strategy,
             config::audio_settings::set_audio_normalization_enabled,
             config::audio_settings::set_audio_normalization_target,
             config::audio_settings::set_audio_gapless_enabled,
+            config::audio_settings::set_audio_silence_trim,
+            config::audio_settings::set_audio_replaygain_mode,
+            config::audio_settings::set_audio_pause_release_ms,
+            config::audio_settings::set_audio_keep_device_open,
+            config::audio_settings::set_audio_inactivity_stop_minutes,
+            config::audio_settings::set_audio_inactivity_stop_while_casting,
+            config::audio_settings::set_audio_quality_fallback_order,
+            config::audio_settings::set_audio_buffer_frames,
+            config::audio_settings::set_audio_alsa_period_frames,
+            config::audio_settings::set_audio_auto_resume_on_reconnect,
+            config::audio_settings::set_audio_pause_on_audio_route_change,
+            config::audio_settings::set_audio_switch_to_reconnected_device,
+            config::audio_settings::set_audio_stream_rebuffer_seconds,
+            config::audio_settings::set_audio_stream_max_wait_secs,
+            config::audio_settings::set_audio_limit_quality_on_metered,
+            config::audio_settings::set_audio_auto_resume_local_on_cast_loss,
+            config::audio_settings::set_audio_prefetch_quality,
+            config::audio_settings::set_audio_upgrade_prefetched_tracks,
             config::audio_settings::reset_audio_settings,
             // Audio backend commands
             commands::get_available_backends,
@@ -855,11 +1349,14 @@ pub fn run() {
             commands::check_alsa_utils_installed,
             commands::get_linux_distro,
             commands::query_dac_capabilities,
+            commands::get_device_format_matrix,
             // Download settings commands
             config::download_settings::get_download_settings,
             config::download_settings::set_download_root,
             config::download_settings::set_show_downloads_in_library,
             config::download_settings::validate_download_root,
+            config::download_settings::set_download_naming_template,
+            config::download_settings::preview_download_path,
             // Offline mode commands
             offline::commands::get_offline_status,
             offline::commands::get_offline_settings,
@@ -870,6 +1367,11 @@ pub fn run() {
             config::playback_preferences::get_playback_preferences,
             config::playback_preferences::set_autoplay_mode,
             config::playback_preferences::set_show_context_icon,
+            config::playback_preferences::set_radio_freshness,
+            config::playback_preferences::set_end_of_queue_behavior,
+            config::playback_preferences::set_previous_restart_threshold_secs,
+            config::playback_preferences::set_radio_queue_ahead,
+            config::playback_preferences::set_radio_after_source,
             config::favorites_preferences::get_favorites_preferences,
             config::favorites_preferences::save_favorites_preferences,
             // Favorites cache commands (local persistence)
@@ -885,7 +1387,12 @@ pub fn run() {
             config::favorites_cache::sync_cached_favorite_tracks,
             config::favorites_cache::sync_cached_favorite_albums,
             config::favorites_cache::sync_cached_favorite_artists,
+            config::favorites_cache::find_unavailable_favorites,
+            config::favorites_cache::get_unavailable_favorites,
             config::favorites_cache::clear_favorites_cache,
+            config::favorites_cache::export_favorites,
+            config::favorites_cache::preview_favorites_import,
+            config::favorites_cache::import_favorites,
             // Updates commands
             updates::get_update_preferences,
             updates::set_update_check_on_launch,
@@ -913,6 +1420,7 @@ pub fn run() {
             offline::commands::update_pending_playlist_qobuz_id,
             offline::commands::mark_pending_playlist_synced,
             offline::commands::delete_pending_playlist,
+            offline::commands::sync_pending_playlists,
             // Scrobble queue commands (for offline Last.fm scrobbling)
             offline::commands::queue_scrobble,
             offline::commands::get_queued_scrobbles,
@@ -924,6 +1432,7 @@ pub fn run() {
             network::commands::get_network_mounts_cmd,
             network::commands::check_mount_accessible,
             network::commands::check_network_paths_batch,
+            network::commands::get_connection_type_cmd,
             // Flatpak detection commands
             flatpak::is_running_in_flatpak,
             flatpak::get_flatpak_help_text,
@@ -932,6 +1441,10 @@ pub fn run() {
             config::tray_settings::set_enable_tray,
             config::tray_settings::set_minimize_to_tray,
             config::tray_settings::set_close_to_tray,
+            // Content region commands
+            config::region_settings::get_available_locales,
+            config::region_settings::get_region_settings,
+            config::region_settings::set_content_locale,
             // Remote control commands
             api_server::remote_control_get_status,
             api_server::remote_control_set_enabled,
@@ -943,6 +1456,11 @@ pub fn run() {
             api_server::remote_control_add_allowed_origin,
             api_server::remote_control_remove_allowed_origin,
             api_server::remote_control_restore_default_origins,
+            api_server::remote_control_create_party_session,
+            api_server::remote_control_get_party_session,
+            api_server::remote_control_end_party_session,
+            api_server::remote_control_approve_contribution,
+            api_server::remote_control_reject_contribution,
             // Legal settings commands
             config::legal_settings::get_legal_settings,
             config::legal_settings::get_qobuz_tos_accepted,
@@ -987,6 +1505,10 @@ pub fn run() {
             // Visualizer commands
             commands::set_visualizer_enabled,
             commands::is_visualizer_enabled,
+            commands::set_visualizer_visible,
+            commands::get_visualizer_config,
+            // Waveform commands
+            commands::get_track_waveform,
             // Artist blacklist commands
             commands::get_artist_blacklist,
             commands::add_to_artist_blacklist,
@@ -997,9 +1519,15 @@ pub fn run() {
             commands::get_blacklist_settings,
             commands::get_blacklist_count,
             commands::clear_artist_blacklist,
+            commands::export_artist_blacklist,
+            commands::import_artist_blacklist,
             // Developer settings commands
             config::developer_settings::get_developer_settings,
             config::developer_settings::set_developer_force_dmabuf,
+            config::network_settings::get_network_settings,
+            config::network_settings::set_network_settings,
+            data_usage::get_data_usage,
+            data_usage::reset_data_usage,
             // Graphics settings commands
             config::graphics_settings::get_graphics_settings,
             config::graphics_settings::get_graphics_startup_status,
@@ -1010,12 +1538,34 @@ pub fn run() {
             // Window settings commands
             config::window_settings::get_window_settings,
             config::window_settings::set_use_system_titlebar,
+            // Media key commands
+            config::media_keys_settings::get_media_keys_settings,
+            config::media_keys_settings::set_global_media_keys,
+            config::media_keys_settings::get_global_media_keys_status,
+            config::artwork_settings::get_artwork_settings,
+            config::artwork_settings::set_artwork_quality,
+            config::artwork_settings::set_artwork_cache_limit_mb,
             // App lifecycle commands
             restart_app,
             // Log capture commands
             logging::get_backend_logs,
             logging::upload_logs_to_paste,
+            logging::preview_redacted_logs,
+            commands::get_diagnostics_bundle,
+            commands::save_diagnostics_bundle,
+            logging::set_log_buffer_size,
+            logging::get_log_buffer_size,
+            logging::set_log_level,
+            logging::get_log_filter,
+            logging::export_logs_to_file,
+            logging::set_persistent_logging,
+            logging::get_persistent_logging,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                flush_data_usage(app_handle);
+            }
+        });
 }
@@ -0,0 +1,242 @@
+//! Artist portraits from external providers
+//!
+//! Used by `library_fetch_missing_artist_images` to fill in the Artists grid.
+//! Providers are tried in order:
+//!
+//! 1. MusicBrainz URL relationships: an "image" link to Wikimedia Commons, or
+//!    the Wikidata item's image (P18), which is also on Commons
+//! 2. Discogs artist search, when there's no MBID or neither MusicBrainz nor
+//!    Wikidata has an image
+//!
+//! Wikidata and Discogs have their own rate limiters here; MusicBrainz
+//! requests go through the shared client, which already has one.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use crate::discogs::DiscogsClient;
+use crate::musicbrainz::client::RateLimiter;
+use crate::musicbrainz::MusicBrainzClient;
+
+pub const SOURCE_WIKIMEDIA: &str = "wikimedia";
+pub const SOURCE_DISCOGS: &str = "discogs";
+/// Stored when no provider had an image, so backfills don't retry right away
+pub const SOURCE_NONE: &str = "none";
+
+/// Misses are retried after this long
+pub const MISS_RETRY_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Width requested from Commons (scaled server side; originals can be huge)
+const COMMONS_IMAGE_WIDTH: u32 = 1000;
+
+static WIKIDATA_LIMITER: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::with_interval(Duration::from_millis(500)));
+
+/// Discogs allows 25 unauthenticated requests per minute
+static DISCOGS_LIMITER: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::with_interval(Duration::from_millis(2500)));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtistImageMatch {
+    pub image_url: String,
+    pub source: String,
+}
+
+/// Portrait linked from the artist's MusicBrainz entry, directly or through
+/// Wikidata
+pub async fn from_musicbrainz(
+    client: &MusicBrainzClient,
+    mbid: &str,
+) -> Result<Option<ArtistImageMatch>, String> {
+    let artist = client.get_artist_with_urls(mbid).await?;
+    let relations = artist.relations.unwrap_or_default();
+    let url_of = |kind: &str| {
+        relations
+            .iter()
+            .filter(move |rel| rel.relation_type == kind)
+            .filter_map(|rel| rel.url.as_ref().map(|url| url.resource.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    if let Some(file_name) = url_of("image")
+        .iter()
+        .find_map(|url| commons_file_name(url))
+    {
+        return Ok(Some(wikimedia_match(&file_name)));
+    }
+
+    for entity_id in url_of("wikidata")
+        .iter()
+        .filter_map(|url| wikidata_entity_id(url))
+    {
+        if let Some(file_name) = wikidata_image(&entity_id).await? {
+            return Ok(Some(wikimedia_match(&file_name)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Image of the Discogs artist named exactly `name`
+pub async fn from_discogs(
+    discogs: &DiscogsClient,
+    name: &str,
+) -> Result<Option<ArtistImageMatch>, String> {
+    DISCOGS_LIMITER.wait().await;
+    let results = discogs.search_artist(name).await?;
+
+    Ok(results
+        .results
+        .into_iter()
+        .filter(|result| result.result_type == "artist")
+        .find(|result| discogs_name_matches(&result.title, name))
+        .and_then(|result| result.cover_image)
+        // Artists without a photo get a placeholder
+        .filter(|url| !url.is_empty() && !url.ends_with("spacer.gif"))
+        .map(|image_url| ArtistImageMatch {
+            image_url,
+            source: SOURCE_DISCOGS.to_string(),
+        }))
+}
+
+fn wikimedia_match(file_name: &str) -> ArtistImageMatch {
+    ArtistImageMatch {
+        image_url: commons_image_url(file_name),
+        source: SOURCE_WIKIMEDIA.to_string(),
+    }
+}
+
+/// The image (P18) of a Wikidata item, as a Commons file name
+async fn wikidata_image(entity_id: &str) -> Result<Option<String>, String> {
+    WIKIDATA_LIMITER.wait().await;
+
+    let url = format!(
+        "https://www.wikidata.org/wiki/Special:EntityData/{}.json",
+        entity_id
+    );
+    log::debug!("Wikidata entity lookup: {}", entity_id);
+
+    // Wikimedia asks for an identifying User-Agent
+    let entity: serde_json::Value = crate::network::http_client::shared()
+        .get(&url)
+        .header(
+            reqwest::header::USER_AGENT,
+            format!(
+                "QBZ/{} (https://github.com/vicrodh/qbz)",
+                env!("CARGO_PKG_VERSION")
+            ),
+        )
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Wikidata request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Wikidata response: {}", e))?;
+
+    Ok(wikidata_image_file(&entity, entity_id))
+}
+
+fn wikidata_image_file(entity: &serde_json::Value, entity_id: &str) -> Option<String> {
+    entity["entities"][entity_id]["claims"]["P18"][0]["mainsnak"]["datavalue"]["value"]
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Wikidata item id from an item URL (`https://www.wikidata.org/wiki/Q1299`)
+fn wikidata_entity_id(url: &str) -> Option<String> {
+    let id = url.trim_end_matches('/').rsplit('/').next()?;
+    let is_item =
+        id.len() > 1 && id.starts_with('Q') && id[1..].chars().all(|c| c.is_ascii_digit());
+    is_item.then(|| id.to_string())
+}
+
+/// File name from a Commons page URL (`.../wiki/File:Name.jpg`)
+fn commons_file_name(url: &str) -> Option<String> {
+    if !url.contains("commons.wikimedia.org") {
+        return None;
+    }
+    let (_, name) = url.split_once("/wiki/File:")?;
+    let name = urlencoding::decode(name).ok()?.replace('_', " ");
+    (!name.is_empty()).then_some(name)
+}
+
+/// Scaled image URL for a Commons file (redirects to upload.wikimedia.org)
+fn commons_image_url(file_name: &str) -> String {
+    format!(
+        "https://commons.wikimedia.org/wiki/Special:FilePath/{}?width={}",
+        urlencoding::encode(&file_name.replace(' ', "_")),
+        COMMONS_IMAGE_WIDTH
+    )
+}
+
+/// Discogs disambiguates homonyms with a suffix ("Nirvana (2)"), which is
+/// ignored when comparing
+fn discogs_name_matches(title: &str, name: &str) -> bool {
+    let title = title.trim();
+    let base = match title.rsplit_once(" (") {
+        Some((base, suffix))
+            if suffix
+                .strip_suffix(')')
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())) =>
+        {
+            base
+        }
+        _ => title,
+    };
+    base.trim().to_lowercase() == name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commons_urls() {
+        let file = commons_file_name(
+            "https://commons.wikimedia.org/wiki/File:Bj%C3%B6rk_at_Cirkus_2_(cropped).jpg",
+        )
+        .unwrap();
+        assert_eq!(file, "Björk at Cirkus 2 (cropped).jpg");
+        assert_eq!(
+            commons_image_url(&file),
+            "https://commons.wikimedia.org/wiki/Special:FilePath/Bj%C3%B6rk_at_Cirkus_2_%28cropped%29.jpg?width=1000"
+        );
+        assert_eq!(
+            commons_file_name("https://example.com/wiki/File:Photo.jpg"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_wikidata_image() {
+        assert_eq!(
+            wikidata_entity_id("https://www.wikidata.org/wiki/Q1299"),
+            Some("Q1299".to_string())
+        );
+        assert_eq!(
+            wikidata_entity_id("https://www.wikidata.org/wiki/Property:P18"),
+            None
+        );
+
+        let entity = serde_json::json!({
+            "entities": { "Q1299": { "claims": { "P18": [
+                { "mainsnak": { "datavalue": { "value": "The Beatles 1963.jpg" } } }
+            ] } } }
+        });
+        assert_eq!(
+            wikidata_image_file(&entity, "Q1299"),
+            Some("The Beatles 1963.jpg".to_string())
+        );
+        assert_eq!(wikidata_image_file(&entity, "Q2"), None);
+    }
+
+    #[test]
+    fn test_discogs_name_matches() {
+        assert!(discogs_name_matches("Nirvana (2)", "nirvana"));
+        assert!(discogs_name_matches("Sigur Rós", "Sigur Rós"));
+        assert!(discogs_name_matches("Band (UK)", "Band (UK)"));
+        assert!(!discogs_name_matches("Band (UK)", "Band"));
+        assert!(!discogs_name_matches("Nirvana Tribute", "Nirvana"));
+    }
+}
@@ -6,15 +6,19 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use serde::Deserialize;
-use tauri::{Emitter, State};
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex;
 
+pub use crate::library::ArtistMusicBrainzMapping;
+
 use crate::discogs::DiscogsClient;
 use crate::library::{
-    cue_to_tracks, get_artwork_cache_dir, CueParser, LibraryDatabase, LibraryFolder, LibraryScanner, LibraryStats,
-    LocalAlbum, LocalArtist, LocalTrack, MetadataExtractor, ScanError, ScanProgress, ScanStatus,
+    cue_to_tracks, get_artwork_cache_dir, CueParser, FileFingerprint, LibraryDatabase, LibraryFolder, LibraryScanner, LibraryStats,
+    LocalAlbum, LocalArtist, LocalTrack, MetadataExtractor, ScanProgress, ScanResult, ScanStatus,
     thumbnails,
 };
+use crate::library::artist_images;
+use crate::library::scan_progress::{FileOutcome, ScanTracker};
 use crate::network::{is_network_path, MountKind, NetworkFs};
 
 /// Library state shared across commands
@@ -473,9 +477,17 @@ pub async fn library_check_folder_accessible(path: String) -> Result<bool, Strin
 
 // === Scanning ===
 
+/// Scan all library folders. Files whose size and mtime haven't changed
+/// since they were indexed are skipped unless `force_full` is set (e.g.
+/// after editing tags or sidecars in another app).
 #[tauri::command]
-pub async fn library_scan(state: State<'_, LibraryState>) -> Result<(), String> {
-    log::info!("Command: library_scan");
+pub async fn library_scan(
+    force_full: Option<bool>,
+    app: AppHandle,
+    state: State<'_, LibraryState>,
+) -> Result<(), String> {
+    let force_full = force_full.unwrap_or(false);
+    log::info!("Command: library_scan (force_full: {})", force_full);
 
     // Get folders to scan
     let folders = {
@@ -490,183 +502,200 @@ pub async fn library_scan(state: State<'_, LibraryState>) -> Result<(), String>
 
     // Reset cancel flag and progress
     state.scan_cancel.store(false, Ordering::Relaxed);
-    {
-        let mut progress = state.scan_progress.lock().await;
-        *progress = ScanProgress {
-            status: ScanStatus::Scanning,
-            total_files: 0,
-            processed_files: 0,
-            current_file: None,
-            errors: Vec::new(),
-        };
-    }
+    let mut tracker = ScanTracker::start(app, state.scan_progress.clone()).await;
 
+    // Enumerate every folder first so the total (and the ETA) covers the
+    // whole scan
     let scanner = LibraryScanner::new();
-    let mut all_errors: Vec<ScanError> = Vec::new();
-    let mut sidecar_cache: HashMap<String, Option<crate::library::AlbumTagSidecar>> = HashMap::new();
-
+    let mut scans: Vec<(&String, ScanResult)> = Vec::new();
     for folder in &folders {
         log::info!("Scanning folder: {}", folder);
-
-        // Scan for files
-        let scan_result = match scanner.scan_directory(Path::new(folder)) {
-            Ok(result) => result,
-            Err(e) => {
-                all_errors.push(ScanError {
-                    file_path: folder.clone(),
-                    error: e.to_string(),
-                });
-                continue;
+        tracker.set_folder(folder).await;
+
+        match scanner.scan_directory(Path::new(folder)) {
+            Ok(result) => {
+                tracker
+                    .add_total(result.audio_files.len() + result.cue_files.len())
+                    .await;
+                scans.push((folder, result));
             }
-        };
+            Err(e) => tracker.report_error(folder.clone(), e.to_string()).await,
+        }
 
-        let total_files = scan_result.audio_files.len() + scan_result.cue_files.len();
+        if state.scan_cancel.load(Ordering::Relaxed) {
+            log::info!("Library scan cancelled by user");
+            tracker.finish(ScanStatus::Cancelled).await;
+            return Ok(());
+        }
+    }
 
-        // Update total count
+    let mut sidecar_cache: HashMap<String, Option<crate::library::AlbumTagSidecar>> = HashMap::new();
+    for (folder, scan_result) in &scans {
+        tracker.set_folder(folder).await;
+        if !index_scan_result(scan_result, &state, &mut tracker, &mut sidecar_cache, force_full)
+            .await?
         {
-            let mut progress = state.scan_progress.lock().await;
-            progress.total_files += total_files as u32;
+            log::info!("Library scan cancelled by user");
+            tracker.finish(ScanStatus::Cancelled).await;
+            return Ok(());
         }
+    }
 
-        // Process CUE files first (they create multiple tracks from one file)
-        for cue_path in &scan_result.cue_files {
-            // Check for cancellation
-            if state.scan_cancel.load(Ordering::Relaxed) {
-                let mut progress = state.scan_progress.lock().await;
-                progress.status = ScanStatus::Cancelled;
-                progress.current_file = None;
-                log::info!("Library scan cancelled by user");
-                return Ok(());
-            }
+    // Still complete if some files failed; they're listed in `errors`
+    tracker.finish(ScanStatus::Complete).await;
 
-            {
-                let mut progress = state.scan_progress.lock().await;
-                progress.current_file = Some(cue_path.to_string_lossy().to_string());
-            }
+    log::info!("Library scan complete");
+    Ok(())
+}
 
-            match process_cue_file(cue_path, &state).await {
-                Ok(_) => {}
-                Err(e) => {
-                    all_errors.push(ScanError {
-                        file_path: cue_path.to_string_lossy().to_string(),
-                        error: e,
-                    });
-                }
-            }
+/// Index the files found in one folder: CUE sheets first (they create
+/// multiple tracks from one file), then audio files not covered by a CUE
+/// sheet. Unless `force_full` is set, audio files that haven't changed since
+/// they were indexed are left alone. Returns false if the scan was cancelled.
+async fn index_scan_result(
+    scan_result: &ScanResult,
+    state: &State<'_, LibraryState>,
+    tracker: &mut ScanTracker,
+    sidecar_cache: &mut HashMap<String, Option<crate::library::AlbumTagSidecar>>,
+    force_full: bool,
+) -> Result<bool, String> {
+    for cue_path in &scan_result.cue_files {
+        if state.scan_cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
 
-            {
-                let mut progress = state.scan_progress.lock().await;
-                progress.processed_files += 1;
-            }
+        let path_str = cue_path.to_string_lossy().to_string();
+        tracker.begin_file(&path_str).await;
+
+        match process_cue_file(cue_path, state).await {
+            Ok(outcome) => tracker.finish_file(outcome).await,
+            Err(e) => tracker.fail_file(path_str, e).await,
         }
+    }
 
-        // Process regular audio files (skip if covered by CUE)
-        let cue_audio_files: std::collections::HashSet<String> = scan_result
-            .cue_files
-            .iter()
-            .filter_map(|p| {
-                CueParser::parse(p).ok().map(|cue| {
-                    normalize_library_path(Path::new(&cue.audio_file))
-                        .to_string_lossy()
-                        .to_string()
-                })
+    let cue_audio_files: HashSet<String> = scan_result
+        .cue_files
+        .iter()
+        .filter_map(|p| {
+            CueParser::parse(p).ok().map(|cue| {
+                normalize_library_path(Path::new(&cue.audio_file))
+                    .to_string_lossy()
+                    .to_string()
             })
-            .collect();
+        })
+        .collect();
 
-        for audio_path in &scan_result.audio_files {
-            // Check for cancellation
-            if state.scan_cancel.load(Ordering::Relaxed) {
-                let mut progress = state.scan_progress.lock().await;
-                progress.status = ScanStatus::Cancelled;
-                progress.current_file = None;
-                log::info!("Library scan cancelled by user");
-                return Ok(());
-            }
+    for audio_path in &scan_result.audio_files {
+        if state.scan_cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
 
-            // Skip if this file is referenced by a CUE sheet
-            let canonical_path = normalize_library_path(audio_path);
-            let path_str = canonical_path.to_string_lossy().to_string();
-            if cue_audio_files.contains(&path_str) {
-                let mut progress = state.scan_progress.lock().await;
-                progress.processed_files += 1;
-                continue;
-            }
+        // Skip if this file is referenced by a CUE sheet
+        let canonical_path = normalize_library_path(audio_path);
+        let path_str = canonical_path.to_string_lossy().to_string();
+        if cue_audio_files.contains(&path_str) {
+            tracker.finish_file(FileOutcome::Skipped).await;
+            continue;
+        }
+
+        tracker.begin_file(&path_str).await;
 
-            {
-                let mut progress = state.scan_progress.lock().await;
-                progress.current_file = Some(path_str.clone());
+        let fingerprint = FileFingerprint::of(&canonical_path);
+        let unchanged = match fingerprint {
+            Some(fingerprint) if !force_full => {
+                let guard__ = state.db.lock().await;
+                let db = guard__.as_ref().ok_or("No active session - please log in")?;
+                db.get_file_fingerprint(&path_str).ok().flatten() == Some(fingerprint)
             }
+            _ => false,
+        };
+        if unchanged {
+            tracker.finish_file(FileOutcome::Unchanged).await;
+            continue;
+        }
 
-            match MetadataExtractor::extract(&canonical_path) {
-                Ok(mut track) => {
-                    apply_sidecar_override_if_present(&mut track, &mut sidecar_cache);
-                    // Try to extract embedded artwork, fallback to cached folder artwork
-                    let artwork_cache = get_artwork_cache_dir();
-                    let mut artwork_path =
-                        MetadataExtractor::extract_artwork(&canonical_path, &artwork_cache);
-                    if artwork_path.is_none() {
-                        let album_hint = if !track.album_group_title.is_empty() {
-                            Some(track.album_group_title.as_str())
-                        } else {
-                            Some(track.album.as_str())
-                        };
-                        if let Some(folder_art) =
-                            MetadataExtractor::find_folder_artwork(&canonical_path, album_hint)
+        let result = match read_audio_track(&canonical_path, sidecar_cache) {
+            Ok(track) => {
+                let guard__ = state.db.lock().await;
+                let db = guard__.as_ref().ok_or("No active session - please log in")?;
+                insert_scanned_track(db, &track)
+                    .and_then(|outcome| {
+                        if let (Some(fingerprint), FileOutcome::Added | FileOutcome::Updated) =
+                            (fingerprint, outcome)
                         {
-                            artwork_path = MetadataExtractor::cache_artwork_file(
-                                Path::new(&folder_art),
-                                &artwork_cache,
-                            );
+                            db.set_file_fingerprint(&track.file_path, fingerprint)?;
                         }
-                    }
-                    track.artwork_path = artwork_path;
-
-                    let guard__ = state.db.lock().await;
-    let db = guard__.as_ref().ok_or("No active session - please log in")?;
-                    if let Err(e) = db.insert_track(&track) {
-                        all_errors.push(ScanError {
-                            file_path: path_str,
-                            error: e.to_string(),
-                        });
-                    } else if let (Some(artwork_path), false) =
-                        (track.artwork_path.as_ref(), track.album_group_key.is_empty())
-                    {
-                        let _ = db.update_album_group_artwork(&track.album_group_key, artwork_path);
-                    }
-                }
-                Err(e) => {
-                    all_errors.push(ScanError {
-                        file_path: path_str,
-                        error: e.to_string(),
-                    });
-                }
-            }
-
-            {
-                let mut progress = state.scan_progress.lock().await;
-                progress.processed_files += 1;
+                        Ok(outcome)
+                    })
+                    .map_err(|e| e.to_string())
             }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(outcome) => tracker.finish_file(outcome).await,
+            Err(e) => tracker.fail_file(path_str, e).await,
         }
     }
 
-    // Mark complete
-    {
-        let mut progress = state.scan_progress.lock().await;
-        progress.status = if all_errors.is_empty() {
-            ScanStatus::Complete
+    Ok(true)
+}
+
+/// Read tags and artwork for an audio file
+fn read_audio_track(
+    path: &Path,
+    sidecar_cache: &mut HashMap<String, Option<crate::library::AlbumTagSidecar>>,
+) -> Result<LocalTrack, String> {
+    let mut track = MetadataExtractor::extract(path).map_err(|e| e.to_string())?;
+    apply_sidecar_override_if_present(&mut track, sidecar_cache);
+
+    // Try to extract embedded artwork, fallback to cached folder artwork
+    let artwork_cache = get_artwork_cache_dir();
+    let mut artwork_path = MetadataExtractor::extract_artwork(path, &artwork_cache);
+    if artwork_path.is_none() {
+        let album_hint = if !track.album_group_title.is_empty() {
+            Some(track.album_group_title.as_str())
         } else {
-            ScanStatus::Complete // Still complete, but with errors
+            Some(track.album.as_str())
         };
-        progress.current_file = None;
-        progress.errors = all_errors;
+        if let Some(folder_art) = MetadataExtractor::find_folder_artwork(path, album_hint) {
+            artwork_path =
+                MetadataExtractor::cache_artwork_file(Path::new(&folder_art), &artwork_cache);
+        }
+    }
+    track.artwork_path = artwork_path;
+    Ok(track)
+}
+
+/// Store a scanned track, reporting whether it was new
+fn insert_scanned_track(
+    db: &LibraryDatabase,
+    track: &LocalTrack,
+) -> Result<FileOutcome, crate::library::LibraryError> {
+    // insert_track leaves Qobuz cached tracks alone
+    if db.is_qobuz_cached_track_by_path(&track.file_path)? {
+        return Ok(FileOutcome::Skipped);
     }
 
-    log::info!("Library scan complete");
-    Ok(())
+    let existed = db.has_track_path(&track.file_path)?;
+    db.insert_track(track)?;
+    if let (Some(artwork_path), false) =
+        (track.artwork_path.as_ref(), track.album_group_key.is_empty())
+    {
+        let _ = db.update_album_group_artwork(&track.album_group_key, artwork_path);
+    }
+
+    Ok(if existed {
+        FileOutcome::Updated
+    } else {
+        FileOutcome::Added
+    })
 }
 
 /// Process a CUE file and insert its tracks
-async fn process_cue_file(cue_path: &Path, state: &State<'_, LibraryState>) -> Result<(), String> {
+async fn process_cue_file(
+    cue_path: &Path,
+    state: &State<'_, LibraryState>,
+) -> Result<FileOutcome, String> {
     let mut cue = CueParser::parse(cue_path).map_err(|e| e.to_string())?;
 
     // Get audio file properties
@@ -725,6 +754,9 @@ async fn process_cue_file(cue_path: &Path, state: &State<'_, LibraryState>) -> R
         .first()
         .map(|track| track.album_group_key.clone())
         .unwrap_or_default();
+    let existed = db
+        .has_track_path(&cue.audio_file)
+        .map_err(|e| e.to_string())?;
 
     for track in tracks {
         db.insert_track(&track).map_err(|e| e.to_string())?;
@@ -734,7 +766,11 @@ async fn process_cue_file(cue_path: &Path, state: &State<'_, LibraryState>) -> R
         let _ = db.update_album_group_artwork(&group_key, path);
     }
 
-    Ok(())
+    Ok(if existed {
+        FileOutcome::Updated
+    } else {
+        FileOutcome::Added
+    })
 }
 
 fn apply_sidecar_override_if_present(
@@ -805,13 +841,21 @@ pub async fn library_stop_scan(state: State<'_, LibraryState>) -> Result<(), Str
     Ok(())
 }
 
-/// Scan a single folder (by ID)
+/// Scan a single folder (by ID). Incremental unless `force_full` is set,
+/// see `library_scan`.
 #[tauri::command]
 pub async fn library_scan_folder(
     folder_id: i64,
+    force_full: Option<bool>,
+    app: AppHandle,
     state: State<'_, LibraryState>,
 ) -> Result<(), String> {
-    log::info!("Command: library_scan_folder {}", folder_id);
+    let force_full = force_full.unwrap_or(false);
+    log::info!(
+        "Command: library_scan_folder {} (force_full: {})",
+        folder_id,
+        force_full
+    );
 
     // Get folder info
     let folder = {
@@ -862,19 +906,10 @@ pub async fn library_scan_folder(
 
     // Reset cancel flag and progress
     state.scan_cancel.store(false, Ordering::Relaxed);
-    {
-        let mut progress = state.scan_progress.lock().await;
-        *progress = ScanProgress {
-            status: ScanStatus::Scanning,
-            total_files: 0,
-            processed_files: 0,
-            current_file: None,
-            errors: Vec::new(),
-        };
-    }
+    let mut tracker = ScanTracker::start(app, state.scan_progress.clone()).await;
+    tracker.set_folder(&folder.path).await;
 
     let scanner = LibraryScanner::new();
-    let mut all_errors: Vec<ScanError> = Vec::new();
     let mut sidecar_cache: HashMap<String, Option<crate::library::AlbumTagSidecar>> = HashMap::new();
 
     log::info!("Scanning single folder: {}", folder.path);
@@ -883,134 +918,22 @@ pub async fn library_scan_folder(
     let scan_result = match scanner.scan_directory(Path::new(&folder.path)) {
         Ok(result) => result,
         Err(e) => {
-            let mut progress = state.scan_progress.lock().await;
-            progress.status = ScanStatus::Complete;
-            progress.errors = vec![ScanError {
-                file_path: folder.path.clone(),
-                error: e.to_string(),
-            }];
+            tracker.report_error(folder.path.clone(), e.to_string()).await;
+            tracker.finish(ScanStatus::Complete).await;
             return Err(e.to_string());
         }
     };
 
-    let total_files = scan_result.audio_files.len() + scan_result.cue_files.len();
+    tracker
+        .add_total(scan_result.audio_files.len() + scan_result.cue_files.len())
+        .await;
 
-    // Update total count
+    if !index_scan_result(&scan_result, &state, &mut tracker, &mut sidecar_cache, force_full)
+        .await?
     {
-        let mut progress = state.scan_progress.lock().await;
-        progress.total_files = total_files as u32;
-    }
-
-    // Process CUE files first
-    for cue_path in &scan_result.cue_files {
-        if state.scan_cancel.load(Ordering::Relaxed) {
-            let mut progress = state.scan_progress.lock().await;
-            progress.status = ScanStatus::Cancelled;
-            progress.current_file = None;
-            log::info!("Library scan cancelled by user");
-            return Ok(());
-        }
-
-        {
-            let mut progress = state.scan_progress.lock().await;
-            progress.current_file = Some(cue_path.to_string_lossy().to_string());
-        }
-
-        match process_cue_file(cue_path, &state).await {
-            Ok(_) => {}
-            Err(e) => {
-                all_errors.push(ScanError {
-                    file_path: cue_path.to_string_lossy().to_string(),
-                    error: e,
-                });
-            }
-        }
-
-        {
-            let mut progress = state.scan_progress.lock().await;
-            progress.processed_files += 1;
-        }
-    }
-
-    // Process regular audio files
-    let cue_audio_files: std::collections::HashSet<String> = scan_result
-        .cue_files
-        .iter()
-        .filter_map(|p| {
-            CueParser::parse(p).ok().map(|cue| {
-                normalize_library_path(Path::new(&cue.audio_file))
-                    .to_string_lossy()
-                    .to_string()
-            })
-        })
-        .collect();
-
-    for audio_path in &scan_result.audio_files {
-        if state.scan_cancel.load(Ordering::Relaxed) {
-            let mut progress = state.scan_progress.lock().await;
-            progress.status = ScanStatus::Cancelled;
-            progress.current_file = None;
-            log::info!("Library scan cancelled by user");
-            return Ok(());
-        }
-
-        let canonical_path = normalize_library_path(audio_path);
-        let path_str = canonical_path.to_string_lossy().to_string();
-        if cue_audio_files.contains(&path_str) {
-            let mut progress = state.scan_progress.lock().await;
-            progress.processed_files += 1;
-            continue;
-        }
-
-        {
-            let mut progress = state.scan_progress.lock().await;
-            progress.current_file = Some(path_str.clone());
-        }
-
-        match MetadataExtractor::extract(&canonical_path) {
-            Ok(mut track) => {
-                apply_sidecar_override_if_present(&mut track, &mut sidecar_cache);
-                let artwork_cache = get_artwork_cache_dir();
-                let mut artwork_path = MetadataExtractor::extract_artwork(&canonical_path, &artwork_cache);
-                if artwork_path.is_none() {
-                    if let Some(folder_art) = MetadataExtractor::find_folder_artwork(
-                        canonical_path.as_path(),
-                        Some(&track.album),
-                    ) {
-                        artwork_path = MetadataExtractor::cache_artwork_file(
-                            Path::new(&folder_art),
-                            &artwork_cache,
-                        );
-                    }
-                }
-                track.artwork_path = artwork_path.clone();
-
-                let guard__ = state.db.lock().await;
-    let db = guard__.as_ref().ok_or("No active session - please log in")?;
-                let group_key = track.album_group_key.clone();
-                if let Err(e) = db.insert_track(&track) {
-                    all_errors.push(ScanError {
-                        file_path: path_str,
-                        error: e.to_string(),
-                    });
-                } else if let Some(path) = artwork_path.as_ref() {
-                    if !group_key.is_empty() {
-                        let _ = db.update_album_group_artwork(&group_key, path);
-                    }
-                }
-            }
-            Err(e) => {
-                all_errors.push(ScanError {
-                    file_path: path_str,
-                    error: e.to_string(),
-                });
-            }
-        }
-
-        {
-            let mut progress = state.scan_progress.lock().await;
-            progress.processed_files += 1;
-        }
+        log::info!("Library scan cancelled by user");
+        tracker.finish(ScanStatus::Cancelled).await;
+        return Ok(());
     }
 
     // Update folder scan time
@@ -1024,13 +947,7 @@ pub async fn library_scan_folder(
         let _ = db.update_folder_scan_time(&folder.path, now);
     }
 
-    // Update final status
-    {
-        let mut progress = state.scan_progress.lock().await;
-        progress.status = ScanStatus::Complete;
-        progress.current_file = None;
-        progress.errors = all_errors;
-    }
+    tracker.finish(ScanStatus::Complete).await;
 
     log::info!("Single folder scan complete");
     Ok(())
@@ -1242,10 +1159,18 @@ pub async fn library_play_track(
     );
 
     // Play the audio (use track_id as u64 for player identification)
+    app_state
+        .player
+        .state
+        .set_keep_lead_in(app_state.queue.current_continues_album());
     app_state
         .player
         .play_data(audio_data, track_id as u64)
         .map_err(|e| format!("Failed to play: {}", e))?;
+    app_state
+        .player
+        .state
+        .set_track_source(crate::player::TrackSource::Local);
 
     // If this is a CUE track, seek to the start position
     if let Some(start_secs) = track.cue_start_secs {
@@ -1266,7 +1191,19 @@ pub async fn library_play_track(
 
 // === Playlist Local Settings ===
 
-use crate::library::{PlaylistFolder, PlaylistSettings, PlaylistStats};
+use crate::library::playlist_view::{apply_order, custom_positions, view_order, ViewEntry};
+use crate::library::{PlaylistFolder, PlaylistGrouping, PlaylistSettings, PlaylistStats};
+
+/// Notify the frontend that a playlist's settings (including its view) changed
+fn emit_playlist_settings_changed(app: &tauri::AppHandle, db: &LibraryDatabase, playlist_id: u64) {
+    match db.get_playlist_settings(playlist_id) {
+        Ok(Some(settings)) => {
+            let _ = app.emit("playlist:settings-changed", &settings);
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to read settings of playlist {}: {}", playlist_id, e),
+    }
+}
 
 /// Get playlist settings by Qobuz playlist ID
 #[tauri::command]
@@ -1287,13 +1224,16 @@ pub async fn playlist_get_settings(
 pub async fn playlist_save_settings(
     settings: PlaylistSettings,
     state: State<'_, LibraryState>,
+    app: tauri::AppHandle,
 ) -> Result<(), String> {
     log::info!("Command: playlist_save_settings {}", settings.qobuz_playlist_id);
 
     let guard__ = state.db.lock().await;
     let db = guard__.as_ref().ok_or("No active session - please log in")?;
     db.save_playlist_settings(&settings)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    emit_playlist_settings_changed(&app, db, settings.qobuz_playlist_id);
+    Ok(())
 }
 
 /// Update playlist sort settings
@@ -1303,13 +1243,44 @@ pub async fn playlist_set_sort(
     sort_by: String,
     sort_order: String,
     state: State<'_, LibraryState>,
+    app: tauri::AppHandle,
 ) -> Result<(), String> {
     log::info!("Command: playlist_set_sort {} {} {}", playlist_id, sort_by, sort_order);
 
     let guard__ = state.db.lock().await;
     let db = guard__.as_ref().ok_or("No active session - please log in")?;
     db.update_playlist_sort(playlist_id, &sort_by, &sort_order)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    emit_playlist_settings_changed(&app, db, playlist_id);
+    Ok(())
+}
+
+/// Update the persisted view of a playlist (sort, grouping, offline-only filter)
+#[tauri::command]
+pub async fn playlist_set_view(
+    playlist_id: u64,
+    sort_by: String,
+    sort_order: String,
+    group_by: PlaylistGrouping,
+    offline_only: bool,
+    state: State<'_, LibraryState>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    log::info!(
+        "Command: playlist_set_view {} {} {} {} offline_only={}",
+        playlist_id,
+        sort_by,
+        sort_order,
+        group_by.as_str(),
+        offline_only
+    );
+
+    let guard__ = state.db.lock().await;
+    let db = guard__.as_ref().ok_or("No active session - please log in")?;
+    db.update_playlist_view(playlist_id, &sort_by, &sort_order, group_by, offline_only)
+        .map_err(|e| e.to_string())?;
+    emit_playlist_settings_changed(&app, db, playlist_id);
+    Ok(())
 }
 
 /// Update playlist custom artwork
@@ -1387,14 +1358,45 @@ pub async fn playlist_remove_local_track(
 #[tauri::command]
 pub async fn playlist_get_local_tracks(
     playlist_id: u64,
+    apply_view: Option<bool>,
     state: State<'_, LibraryState>,
 ) -> Result<Vec<LocalTrack>, String> {
     log::info!("Command: playlist_get_local_tracks {}", playlist_id);
 
     let guard__ = state.db.lock().await;
     let db = guard__.as_ref().ok_or("No active session - please log in")?;
-    db.get_playlist_local_tracks(playlist_id)
-        .map_err(|e| e.to_string())
+    let tracks = db.get_playlist_local_tracks(playlist_id)
+        .map_err(|e| e.to_string())?;
+
+    if apply_view == Some(false) {
+        return Ok(tracks);
+    }
+    let Some(settings) = db.get_playlist_settings(playlist_id).map_err(|e| e.to_string())? else {
+        return Ok(tracks);
+    };
+    let custom = if settings.sort_by == "custom" {
+        custom_positions(&db.get_playlist_custom_order(playlist_id).map_err(|e| e.to_string())?)
+    } else {
+        HashMap::new()
+    };
+
+    // Local tracks are always available offline, so only sort and group
+    let entries: Vec<ViewEntry> = tracks
+        .iter()
+        .enumerate()
+        .map(|(position, track)| ViewEntry {
+            title: &track.title,
+            artist: &track.artist,
+            album: &track.album,
+            label: None,
+            duration_secs: track.duration_secs,
+            position,
+            added_at: None,
+            custom_position: custom.get(&(track.id, true)).copied(),
+        })
+        .collect();
+    let order = view_order(&entries, &settings);
+    Ok(apply_order(tracks, &order))
 }
 
 /// Get all local tracks in a playlist with their positions (for mixed ordering)
@@ -1826,6 +1828,15 @@ pub struct LibraryAlbumTrackMetadataUpdate {
     pub title: String,
     pub disc_number: Option<u32>,
     pub track_number: Option<u32>,
+    /// Track artist (written to files only; the album artist when missing)
+    #[serde(default)]
+    pub artist: Option<String>,
+    /// Written to files only; the library's composer for the track when missing
+    #[serde(default)]
+    pub composer: Option<String>,
+    /// Written to files only; kept as is in the file when missing
+    #[serde(default)]
+    pub isrc: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1838,6 +1849,19 @@ pub struct LibraryAlbumMetadataUpdateRequest {
     pub genre: Option<String>,
     pub catalog_number: Option<String>,
     pub tracks: Vec<LibraryAlbumTrackMetadataUpdate>,
+    /// Full release date ("YYYY-MM-DD"), written to files instead of the year
+    #[serde(default)]
+    pub release_date: Option<String>,
+    /// Embed the album's cached artwork when writing to files
+    #[serde(default = "default_embed_artwork")]
+    pub embed_artwork: bool,
+    /// Scale embedded artwork down to this many pixels (None keeps the original)
+    #[serde(default)]
+    pub artwork_max_size: Option<u32>,
+}
+
+fn default_embed_artwork() -> bool {
+    true
 }
 
 #[tauri::command]
@@ -1978,11 +2002,30 @@ pub async fn library_write_album_metadata_to_files(
         return Err("Album folder not found on disk.".to_string());
     }
 
+    // Cover art already cached by the library for this album
+    let artwork_path = if request.embed_artwork {
+        existing_tracks
+            .iter()
+            .filter_map(|t| t.artwork_path.as_deref())
+            .map(PathBuf::from)
+            .find(|p| p.is_file())
+    } else {
+        None
+    };
+
+    // Composer fallback: what the library read from the file last time
+    let library_composers: HashMap<i64, String> = existing_tracks
+        .iter()
+        .filter_map(|t| Some((t.id, t.composer.clone()?)))
+        .collect();
+
     // Write embedded tags for each track file.
     let write_result = tokio::task::spawn_blocking({
         let request = request.clone();
         move || -> Result<(), String> {
-            use lofty::{Accessor, AudioFile, ItemKey, Tag, TaggedFileExt};
+            use crate::library::tag_writer::{
+                load_cover, verify_track_tags, write_track_tags, AlbumTagValues, TrackTagValues,
+            };
 
             // Ensure we only write each file once.
             let mut by_file: HashMap<String, &LibraryAlbumTrackMetadataUpdate> = HashMap::new();
@@ -1990,6 +2033,33 @@ pub async fn library_write_album_metadata_to_files(
                 by_file.entry(track.file_path.clone()).or_insert(track);
             }
 
+            // Totals: tracks per disc, and the highest disc number
+            let mut tracks_per_disc: HashMap<u32, u32> = HashMap::new();
+            for track in by_file.values() {
+                *tracks_per_disc.entry(track.disc_number.unwrap_or(1)).or_default() += 1;
+            }
+            let disc_total = by_file.values().filter_map(|t| t.disc_number).max();
+
+            let cover = match &artwork_path {
+                Some(path) => match load_cover(path, request.artwork_max_size) {
+                    Ok(cover) => Some(cover),
+                    Err(e) => {
+                        log::warn!("Not embedding artwork {:?}: {}", path, e);
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            let album = AlbumTagValues {
+                title: request.album_title.clone(),
+                album_artist: request.album_artist.clone(),
+                year: request.year,
+                release_date: request.release_date.clone(),
+                genre: request.genre.clone(),
+                catalog_number: request.catalog_number.clone(),
+            };
+
             let total = by_file.len();
             let mut current = 0usize;
 
@@ -2005,74 +2075,30 @@ pub async fn library_write_album_metadata_to_files(
                     return Err("One or more audio files were not found on disk.".to_string());
                 }
 
-                let mut tagged_file =
-                    lofty::read_from_path(path).map_err(|_| "Failed to read audio file tags.".to_string())?;
-
-                let primary_type = tagged_file.primary_tag_type();
-                if tagged_file.primary_tag_mut().is_none() && tagged_file.first_tag_mut().is_none() {
-                    tagged_file.insert_tag(Tag::new(primary_type));
-                }
-
-                {
-                    let tag = if let Some(tag) = tagged_file.primary_tag_mut() {
-                        tag
-                    } else if let Some(tag) = tagged_file.first_tag_mut() {
-                        tag
-                    } else {
-                        return Err("Failed to access audio file tags.".to_string());
-                    };
-
-                    tag.set_title(track.title.trim().to_string());
-                    tag.set_album(request.album_title.trim().to_string());
-                    tag.set_artist(request.album_artist.trim().to_string());
-
-                    if let Some(no) = track.track_number {
-                        tag.set_track(no);
-                    }
-                    if let Some(disc) = track.disc_number {
-                        tag.set_disk(disc);
-                    }
-
-                    // Album artist (not part of Accessor).
-                    if request.album_artist.trim().is_empty() {
-                        tag.remove_key(&ItemKey::AlbumArtist);
-                    } else {
-                        tag.insert_text(ItemKey::AlbumArtist, request.album_artist.trim().to_string());
-                    }
-
-                    // Year / Genre
-                    if let Some(year) = request.year {
-                        tag.set_year(year);
-                    } else {
-                        tag.remove_year();
-                    }
-
-                    if let Some(ref genre) = request.genre {
-                        let g = genre.trim();
-                        if g.is_empty() {
-                            tag.remove_genre();
-                        } else {
-                            tag.set_genre(g.to_string());
-                        }
-                    } else {
-                        tag.remove_genre();
-                    }
-
-                    if let Some(ref cat) = request.catalog_number {
-                        let c = cat.trim();
-                        if c.is_empty() {
-                            tag.remove_key(&ItemKey::CatalogNumber);
-                        } else {
-                            tag.insert_text(ItemKey::CatalogNumber, c.to_string());
-                        }
-                    } else {
-                        tag.remove_key(&ItemKey::CatalogNumber);
-                    }
+                let values = TrackTagValues {
+                    title: track.title.clone(),
+                    artist: track.artist.clone(),
+                    track_number: track.track_number,
+                    track_total: tracks_per_disc.get(&track.disc_number.unwrap_or(1)).copied(),
+                    disc_number: track.disc_number,
+                    disc_total,
+                    composer: track
+                        .composer
+                        .clone()
+                        .or_else(|| library_composers.get(&track.id).cloned()),
+                    isrc: track.isrc.clone(),
+                };
+                write_track_tags(path, &album, &values, cover.as_ref())?;
+
+                // Read the file back to make sure the tags stuck
+                let mismatches = verify_track_tags(path, &album, &values, cover.is_some())?;
+                if !mismatches.is_empty() {
+                    return Err(format!(
+                        "Tags did not round-trip for {} ({}).",
+                        path.file_name().unwrap_or_default().to_string_lossy(),
+                        mismatches.join(", ")
+                    ));
                 }
-
-                tagged_file
-                    .save_to_path(path)
-                    .map_err(|_| "Failed to write tags to audio files. Check that the album folder is mounted read-write and you have permissions.".to_string())?;
             }
 
             Ok(())
@@ -2367,6 +2393,7 @@ pub struct ArtistImageInfo {
     pub source: Option<String>,
     pub custom_image_path: Option<String>,
     pub canonical_name: Option<String>,
+    pub fetched_at: i64,
 }
 
 /// Get cached artist image
@@ -2398,13 +2425,379 @@ pub async fn library_get_artist_images(
 }
 
 /// Get all canonical artist names mapping
+///
+/// With `resolve`, library artists without a cached resolution are first
+/// looked up on MusicBrainz (1 req/sec, so the first run on a large library
+/// is slow). Confident matches take precedence over Qobuz/Discogs names.
 #[tauri::command]
 pub async fn library_get_canonical_names(
+    resolve: Option<bool>,
     state: State<'_, LibraryState>,
+    mb_state: State<'_, crate::musicbrainz::MusicBrainzSharedState>,
 ) -> Result<std::collections::HashMap<String, String>, String> {
+    if resolve.unwrap_or(false) {
+        resolve_artist_names(&state, &mb_state).await?;
+    }
+
+    let guard__ = state.db.lock().await;
+    let db = guard__.as_ref().ok_or("No active session - please log in")?;
+    let mut names = db.get_all_canonical_names().map_err(|e| e.to_string())?;
+    for mapping in db
+        .get_artist_musicbrainz_mappings()
+        .map_err(|e| e.to_string())?
+    {
+        if let Some(canonical_name) = mapping.canonical_name {
+            names.insert(mapping.artist_name, canonical_name);
+        }
+    }
+    Ok(names)
+}
+
+/// Get cached MusicBrainz resolutions of library artist names
+#[tauri::command]
+pub async fn library_get_artist_mbids(
+    state: State<'_, LibraryState>,
+) -> Result<Vec<ArtistMusicBrainzMapping>, String> {
     let guard__ = state.db.lock().await;
     let db = guard__.as_ref().ok_or("No active session - please log in")?;
-    db.get_all_canonical_names().map_err(|e| e.to_string())
+    db.get_artist_musicbrainz_mappings()
+        .map_err(|e| e.to_string())
+}
+
+async fn resolve_artist_names(
+    state: &LibraryState,
+    mb_state: &crate::musicbrainz::MusicBrainzSharedState,
+) -> Result<(), String> {
+    if !mb_state.client.is_enabled().await {
+        return Err("MusicBrainz integration is disabled".to_string());
+    }
+
+    // Collect unresolved names up front so the library isn't locked during lookups
+    let pending: Vec<String> = {
+        let guard__ = state.db.lock().await;
+        let db = guard__.as_ref().ok_or("No active session - please log in")?;
+        let resolved: HashSet<String> = db
+            .get_artist_musicbrainz_mappings()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|m| m.artist_name)
+            .collect();
+        db.get_artists()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|a| a.name)
+            .filter(|name| !name.trim().is_empty() && !resolved.contains(name))
+            .collect()
+    };
+
+    log::info!("Resolving {} artist names via MusicBrainz", pending.len());
+
+    for name in pending {
+        resolve_artist_mbid(state, mb_state, &name).await?;
+    }
+
+    Ok(())
+}
+
+/// Look up an artist spelling on MusicBrainz and cache the result, returning
+/// (mbid, canonical_name) for confident matches
+async fn resolve_artist_mbid(
+    state: &LibraryState,
+    mb_state: &crate::musicbrainz::MusicBrainzSharedState,
+    name: &str,
+) -> Result<(Option<String>, Option<String>), String> {
+    use crate::musicbrainz::MatchConfidence;
+
+    // Only a confirmed answer is persisted; a failed lookup is retried next time
+    let Some(artist) = crate::commands::musicbrainz::try_resolve_artist(mb_state, name).await?
+    else {
+        return Ok((None, None));
+    };
+    let confident = matches!(
+        artist.confidence,
+        MatchConfidence::Exact | MatchConfidence::High
+    );
+    let (mbid, canonical_name) = match (artist.mbid, artist.name) {
+        (Some(mbid), Some(canonical)) if confident => (Some(mbid), Some(canonical)),
+        _ => (None, None),
+    };
+
+    let guard__ = state.db.lock().await;
+    let db = guard__.as_ref().ok_or("No active session - please log in")?;
+    db.set_artist_musicbrainz(name, mbid.as_deref(), canonical_name.as_deref())
+        .map_err(|e| e.to_string())?;
+    Ok((mbid, canonical_name))
+}
+
+/// Outcome of `library_fetch_missing_artist_images`
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ArtistImageFetchReport {
+    /// Artists without a custom or cached image before the run
+    pub missing: usize,
+    /// Artists that got an image
+    pub found: usize,
+    pub from_wikimedia: usize,
+    pub from_discogs: usize,
+    /// No provider had an image (retried after 30 days)
+    pub not_found: usize,
+    /// Lookups that failed, e.g. network errors (retried on the next run)
+    pub failed: usize,
+}
+
+/// Fetch portraits for library artists that don't have an image yet
+///
+/// Tries MusicBrainz/Wikidata, then Discogs (see `library::artist_images`).
+/// `folder_id` limits the run to artists with tracks in that folder; without
+/// it the whole library is backfilled. Custom images always win and are
+/// never replaced. Lookups are rate limited, so a first run on a large
+/// library takes a while.
+#[tauri::command]
+pub async fn library_fetch_missing_artist_images(
+    folder_id: Option<i64>,
+    state: State<'_, LibraryState>,
+    mb_state: State<'_, crate::musicbrainz::MusicBrainzSharedState>,
+) -> Result<ArtistImageFetchReport, String> {
+    log::info!("Command: library_fetch_missing_artist_images {:?}", folder_id);
+    let now = chrono::Utc::now().timestamp();
+
+    // Collect artists up front so the library isn't locked during lookups
+    let (pending, mut mappings) = {
+        let guard__ = state.db.lock().await;
+        let db = guard__.as_ref().ok_or("No active session - please log in")?;
+        let names = match folder_id {
+            Some(folder_id) => {
+                let folder = db
+                    .get_folder_by_id(folder_id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Folder with ID {} not found", folder_id))?;
+                db.get_artist_names_in_folder(&folder.path)
+            }
+            None => db
+                .get_artists()
+                .map(|artists| artists.into_iter().map(|a| a.name).collect()),
+        }
+        .map_err(|e| e.to_string())?;
+
+        let pending: Vec<String> = names
+            .into_iter()
+            .filter(|name| {
+                let trimmed = name.trim();
+                !trimmed.is_empty() && !trimmed.eq_ignore_ascii_case("various artists")
+            })
+            .filter(|name| match db.get_artist_image(name) {
+                Ok(Some(info)) => {
+                    let recent_miss = info.source.as_deref() == Some(artist_images::SOURCE_NONE)
+                        && now - info.fetched_at < artist_images::MISS_RETRY_SECS;
+                    info.custom_image_path.is_none() && info.image_url.is_none() && !recent_miss
+                }
+                _ => true,
+            })
+            .collect();
+
+        let mappings: HashMap<String, (Option<String>, Option<String>)> = db
+            .get_artist_musicbrainz_mappings()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|m| (m.artist_name, (m.mbid, m.canonical_name)))
+            .collect();
+
+        (pending, mappings)
+    };
+
+    let mb_client = if mb_state.client.is_enabled().await {
+        Some(mb_state.client.clone())
+    } else {
+        None
+    };
+    let discogs = DiscogsClient::new();
+    let mut report = ArtistImageFetchReport {
+        missing: pending.len(),
+        ..Default::default()
+    };
+    log::info!("Fetching images for {} artists", pending.len());
+
+    for name in pending {
+        let (mbid, canonical_name) = match mappings.remove(&name) {
+            Some(mapping) => mapping,
+            None if mb_client.is_some() => resolve_artist_mbid(&state, &mb_state, &name).await?,
+            None => (None, None),
+        };
+
+        let found = match fetch_artist_image(
+            &state,
+            mb_client.as_deref(),
+            &discogs,
+            &name,
+            mbid.as_deref(),
+            canonical_name.as_deref(),
+            now,
+        )
+        .await
+        {
+            Ok(found) => found,
+            Err(e) => {
+                log::warn!("Failed to fetch image for artist {}: {}", name, e);
+                report.failed += 1;
+                continue;
+            }
+        };
+
+        let (image_url, source) = match &found {
+            Some(found) => (Some(found.image_url.as_str()), found.source.as_str()),
+            None => (None, artist_images::SOURCE_NONE),
+        };
+        match source {
+            artist_images::SOURCE_WIKIMEDIA => report.from_wikimedia += 1,
+            artist_images::SOURCE_DISCOGS => report.from_discogs += 1,
+            _ => report.not_found += 1,
+        }
+
+        let guard__ = state.db.lock().await;
+        let db = guard__.as_ref().ok_or("No active session - please log in")?;
+        db.cache_artist_image_with_canonical(
+            &name,
+            image_url,
+            source,
+            None,
+            canonical_name.as_deref(),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    report.found = report.from_wikimedia + report.from_discogs;
+    log::info!(
+        "Artist images: found {} of {} ({} not found, {} failed)",
+        report.found,
+        report.missing,
+        report.not_found,
+        report.failed
+    );
+    Ok(report)
+}
+
+/// Run the provider chain for one artist, using the per-MBID cache so
+/// spellings of the same artist are only looked up once
+async fn fetch_artist_image(
+    state: &LibraryState,
+    mb_client: Option<&crate::musicbrainz::MusicBrainzClient>,
+    discogs: &DiscogsClient,
+    name: &str,
+    mbid: Option<&str>,
+    canonical_name: Option<&str>,
+    now: i64,
+) -> Result<Option<artist_images::ArtistImageMatch>, String> {
+    let Some(mbid) = mbid else {
+        return artist_images::from_discogs(discogs, name).await;
+    };
+
+    let cached = {
+        let guard__ = state.db.lock().await;
+        let db = guard__.as_ref().ok_or("No active session - please log in")?;
+        db.get_mbid_artist_image(mbid).map_err(|e| e.to_string())?
+    };
+    match cached {
+        Some((Some(image_url), source, _)) => {
+            return Ok(Some(artist_images::ArtistImageMatch { image_url, source }));
+        }
+        Some((None, _, fetched_at)) if now - fetched_at < artist_images::MISS_RETRY_SECS => {
+            return Ok(None);
+        }
+        _ => {}
+    }
+
+    let mut found = match mb_client {
+        Some(client) => artist_images::from_musicbrainz(client, mbid).await?,
+        None => None,
+    };
+    if found.is_none() {
+        found = artist_images::from_discogs(discogs, canonical_name.unwrap_or(name)).await?;
+    }
+
+    let guard__ = state.db.lock().await;
+    let db = guard__.as_ref().ok_or("No active session - please log in")?;
+    match &found {
+        Some(found) => db.set_mbid_artist_image(mbid, Some(&found.image_url), &found.source),
+        None => db.set_mbid_artist_image(mbid, None, artist_images::SOURCE_NONE),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(found)
+}
+
+/// Tracks an artist merge would touch
+#[derive(serde::Serialize)]
+pub struct ArtistMergePreview {
+    pub to_name: String,
+    pub from_names: Vec<String>,
+    pub album_count: usize,
+    pub tracks: Vec<LocalTrack>,
+}
+
+/// Drop blanks, duplicates and the target itself from the merged spellings
+fn merge_source_names(from_ids: Vec<String>, to_id: &str) -> Result<Vec<String>, String> {
+    if to_id.trim().is_empty() {
+        return Err("Target artist name is empty".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    let from_names: Vec<String> = from_ids
+        .into_iter()
+        .filter(|name| !name.trim().is_empty() && name != to_id)
+        .filter(|name| seen.insert(name.clone()))
+        .collect();
+
+    if from_names.is_empty() {
+        return Err("No artist spellings to merge".to_string());
+    }
+    Ok(from_names)
+}
+
+/// Preview the tracks `library_merge_artists` would re-point
+///
+/// Library artists are keyed by name, so the ids are artist names.
+#[tauri::command]
+pub async fn library_preview_artist_merge(
+    from_ids: Vec<String>,
+    to_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<ArtistMergePreview, String> {
+    let from_names = merge_source_names(from_ids, &to_id)?;
+
+    let guard__ = state.db.lock().await;
+    let db = guard__.as_ref().ok_or("No active session - please log in")?;
+    let tracks = db
+        .get_tracks_by_artist_names(&from_names)
+        .map_err(|e| e.to_string())?;
+    let album_count = tracks
+        .iter()
+        .map(|t| t.album_group_key.as_str())
+        .collect::<HashSet<_>>()
+        .len();
+
+    Ok(ArtistMergePreview {
+        to_name: to_id,
+        from_names,
+        album_count,
+        tracks,
+    })
+}
+
+/// Merge artist spellings into one, returning the number of tracks updated
+///
+/// Only the library database changes; file tags are left alone. The merge is
+/// re-applied when the files are scanned again.
+#[tauri::command]
+pub async fn library_merge_artists(
+    from_ids: Vec<String>,
+    to_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<usize, String> {
+    let from_names = merge_source_names(from_ids, &to_id)?;
+    log::info!("Command: library_merge_artists {:?} -> {}", from_names, to_id);
+
+    let mut guard__ = state.db.lock().await;
+    let db = guard__.as_mut().ok_or("No active session - please log in")?;
+    db.merge_artists(&from_names, &to_id)
+        .map_err(|e| e.to_string())
 }
 
 /// Cache artist image from Qobuz/Discogs with canonical name
@@ -2874,3 +3267,27 @@ pub async fn move_playlist_to_folder(
     db.move_playlist_to_folder(playlist_id, folder_id.as_deref())
         .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_source_names_drops_blanks_duplicates_and_target() {
+        let names = merge_source_names(
+            vec![
+                "Beatles".to_string(),
+                " ".to_string(),
+                "The Beatles".to_string(),
+                "Beatles".to_string(),
+                "beatles".to_string(),
+            ],
+            "The Beatles",
+        )
+        .unwrap();
+        assert_eq!(names, vec!["Beatles", "beatles"]);
+
+        assert!(merge_source_names(vec!["The Beatles".to_string()], "The Beatles").is_err());
+        assert!(merge_source_names(vec!["Beatles".to_string()], "  ").is_err());
+    }
+}
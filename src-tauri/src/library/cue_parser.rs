@@ -3,6 +3,7 @@
 use std::fs;
 use std::path::Path;
 
+use crate::api::classical::split_title;
 use crate::library::{AudioFormat, AudioProperties, LibraryError, LocalTrack, MetadataExtractor};
 
 /// Parsed CUE sheet
@@ -228,6 +229,8 @@ pub fn cue_to_tracks(
     let inferred_disc = MetadataExtractor::infer_disc_number(cue_audio_path);
 
     for (i, cue_track) in cue.tracks.iter().enumerate() {
+        let (work, movement) = split_title(&cue_track.title, None);
+
         // Calculate end time (next track's start or audio end)
         let end_secs = if i + 1 < cue.tracks.len() {
             cue.tracks[i + 1].start_secs
@@ -258,6 +261,9 @@ pub fn cue_to_tracks(
             year: None,
             genre: None,
             catalog_number: None,
+            composer: None,
+            work,
+            movement,
             duration_secs: duration,
             format: format.clone(),
             bit_depth: properties.bit_depth,
@@ -3,7 +3,10 @@
 use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
 
-use crate::library::{AudioFormat, LibraryError, LocalAlbum, LocalArtist, LocalTrack};
+use crate::library::{
+    ArtistMusicBrainzMapping, AudioFormat, FileFingerprint, LibraryError, LocalAlbum, LocalArtist,
+    LocalTrack,
+};
 
 #[derive(Debug, Clone)]
 pub struct AlbumTrackUpdate {
@@ -191,6 +194,29 @@ impl LibraryDatabase {
             );
 
             CREATE INDEX IF NOT EXISTS idx_artist_images_fetched ON artist_images(fetched_at);
+
+            -- MusicBrainz resolution of local artist spellings (NULL mbid = no match)
+            CREATE TABLE IF NOT EXISTS artist_musicbrainz (
+                artist_name TEXT PRIMARY KEY,
+                mbid TEXT,
+                canonical_name TEXT,
+                resolved_at INTEGER NOT NULL
+            );
+
+            -- Merged artist spellings, re-applied when tracks are re-indexed
+            CREATE TABLE IF NOT EXISTS artist_merges (
+                from_name TEXT PRIMARY KEY,
+                to_name TEXT NOT NULL,
+                merged_at INTEGER NOT NULL
+            );
+
+            -- Provider lookups of artist portraits by MBID (NULL image_url = none found)
+            CREATE TABLE IF NOT EXISTS artist_mbid_images (
+                mbid TEXT PRIMARY KEY,
+                image_url TEXT,
+                source TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL
+            );
         "#,
             )
             .map_err(|e| LibraryError::Database(format!("Failed to create schema: {}", e)))?;
@@ -446,6 +472,27 @@ impl LibraryDatabase {
             ).map_err(|e| LibraryError::Database(format!("Migration failed: {}", e)))?;
         }
 
+        // Migration: Add view state columns (grouping, offline filter) to playlist_settings
+        let has_group_by: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('playlist_settings') WHERE name = 'group_by'",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_group_by {
+            log::info!("Running migration: adding group_by and offline_only to playlist_settings");
+            self.conn
+                .execute_batch(
+                    "ALTER TABLE playlist_settings ADD COLUMN group_by TEXT DEFAULT 'none';
+                     ALTER TABLE playlist_settings ADD COLUMN offline_only INTEGER DEFAULT 0;",
+                )
+                .map_err(|e| LibraryError::Database(format!("Migration failed: {}", e)))?;
+        }
+
         // Migration: Add catalog_number column to local_tracks
         let has_catalog_number: bool = self
             .conn
@@ -618,6 +665,49 @@ impl LibraryDatabase {
             log::info!("Migration completed: sample_rate is now REAL");
         }
 
+        // Migration: Add classical metadata (composer, work, movement) to local_tracks
+        // Runs after the sample_rate rebuild, which only keeps the core columns
+        let has_composer: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('local_tracks') WHERE name = 'composer'",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_composer {
+            log::info!("Running migration: adding composer, work and movement to local_tracks");
+            self.conn
+                .execute_batch(
+                    "ALTER TABLE local_tracks ADD COLUMN composer TEXT;
+                     ALTER TABLE local_tracks ADD COLUMN work TEXT;
+                     ALTER TABLE local_tracks ADD COLUMN movement TEXT;
+                     CREATE INDEX IF NOT EXISTS idx_tracks_composer ON local_tracks(composer);",
+                )
+                .map_err(|e| LibraryError::Database(format!("Migration failed: {}", e)))?;
+        }
+
+        // Migration: Add file_mtime_ns to local_tracks for incremental scans.
+        // NULL until a file is next scanned, so existing tracks are re-read once
+        let has_file_mtime_ns: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('local_tracks') WHERE name = 'file_mtime_ns'",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_file_mtime_ns {
+            log::info!("Running migration: adding file_mtime_ns to local_tracks");
+            self.conn
+                .execute_batch("ALTER TABLE local_tracks ADD COLUMN file_mtime_ns INTEGER;")
+                .map_err(|e| LibraryError::Database(format!("Migration failed: {}", e)))?;
+        }
+
         // Migration: Add canonical_name column to artist_images for artist name normalization
         let has_canonical_name: bool = self.conn
             .query_row(
@@ -924,6 +1014,18 @@ impl LibraryDatabase {
                 .map_err(|e| LibraryError::Database(e.to_string()));
         }
 
+        // Keep spellings merged by the user from coming back on rescan
+        let artist = self
+            .merged_artist_name(&track.artist)?
+            .unwrap_or_else(|| track.artist.clone());
+        let album_artist = match track.album_artist.as_deref() {
+            Some(name) => Some(
+                self.merged_artist_name(name)?
+                    .unwrap_or_else(|| name.to_string()),
+            ),
+            None => None,
+        };
+
         self.conn
             .execute(
                 r#"INSERT OR REPLACE INTO local_tracks
@@ -931,14 +1033,14 @@ impl LibraryDatabase {
                 disc_number, year, genre, catalog_number, duration_secs, format, bit_depth,
                 sample_rate, channels, file_size_bytes, cue_file_path,
                 cue_start_secs, cue_end_secs, artwork_path, last_modified, indexed_at,
-                album_group_key, album_group_title)
-               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                album_group_key, album_group_title, composer, work, movement)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
                 params![
                     track.file_path,
                     track.title,
-                    track.artist,
+                    artist,
                     track.album,
-                    track.album_artist,
+                    album_artist,
                     track.track_number,
                     track.disc_number,
                     track.year,
@@ -957,7 +1059,10 @@ impl LibraryDatabase {
                     track.last_modified,
                     track.indexed_at,
                     track.album_group_key,
-                    track.album_group_title
+                    track.album_group_title,
+                    track.composer,
+                    track.work,
+                    track.movement
                 ],
             )
             .map_err(|e| LibraryError::Database(e.to_string()))?;
@@ -989,6 +1094,63 @@ impl LibraryDatabase {
             .map_err(|e| LibraryError::Database(e.to_string()))
     }
 
+    /// Whether any track (including CUE tracks) is indexed from this file
+    pub fn has_track_path(&self, file_path: &str) -> Result<bool, LibraryError> {
+        let count: i64 = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM local_tracks WHERE file_path = ?1",
+                params![file_path],
+                |row| row.get(0),
+            )
+            .map_err(|e| LibraryError::Database(e.to_string()))?;
+        Ok(count > 0)
+    }
+
+    /// Size and mtime a (non-CUE) track had when it was last scanned; None
+    /// if it isn't indexed or predates incremental scanning
+    pub fn get_file_fingerprint(
+        &self,
+        file_path: &str,
+    ) -> Result<Option<FileFingerprint>, LibraryError> {
+        self.conn
+            .query_row(
+                "SELECT file_size_bytes, file_mtime_ns FROM local_tracks
+                 WHERE file_path = ?1 AND cue_file_path IS NULL AND file_mtime_ns IS NOT NULL",
+                params![file_path],
+                |row| {
+                    Ok(FileFingerprint {
+                        size_bytes: row.get::<_, i64>(0)? as u64,
+                        mtime_ns: row.get(1)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| LibraryError::Database(e.to_string()))
+    }
+
+    /// Record the size and mtime a track was scanned at. `insert_track`
+    /// clears this (INSERT OR REPLACE), so edits made through the app are
+    /// re-read on the next scan.
+    pub fn set_file_fingerprint(
+        &self,
+        file_path: &str,
+        fingerprint: FileFingerprint,
+    ) -> Result<(), LibraryError> {
+        self.conn
+            .execute(
+                "UPDATE local_tracks SET file_size_bytes = ?2, file_mtime_ns = ?3
+                 WHERE file_path = ?1 AND cue_file_path IS NULL",
+                params![
+                    file_path,
+                    fingerprint.size_bytes as i64,
+                    fingerprint.mtime_ns
+                ],
+            )
+            .map_err(|e| LibraryError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// Delete all tracks in a folder
     pub fn delete_tracks_in_folder(&self, folder: &str) -> Result<usize, LibraryError> {
         let pattern = format!("{}%", folder);
@@ -1693,6 +1855,9 @@ impl LibraryDatabase {
             year: row.get(8)?,
             genre: row.get(9)?,
             catalog_number: row.get(26).ok().flatten(),
+            composer: row.get(27).ok().flatten(),
+            work: row.get(28).ok().flatten(),
+            movement: row.get(29).ok().flatten(),
             duration_secs: row.get(10)?,
             format: Self::parse_format(&row.get::<_, String>(11)?),
             bit_depth: row.get(12)?,
@@ -1764,6 +1929,39 @@ pub struct PlaylistSettings {
     pub folder_id: Option<String>, // ID of the folder this playlist belongs to (null = root)
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default)]
+    pub group_by: PlaylistGrouping,
+    /// Only show tracks that can be played offline (local copies)
+    #[serde(default)]
+    pub offline_only: bool,
+}
+
+/// How a playlist's tracks are grouped in its view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistGrouping {
+    #[default]
+    None,
+    Album,
+    AddedDate,
+}
+
+impl PlaylistGrouping {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "album" => Self::Album,
+            "added_date" => Self::AddedDate,
+            _ => Self::None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Album => "album",
+            Self::AddedDate => "added_date",
+        }
+    }
 }
 
 /// Status of local content availability for a playlist
@@ -1847,6 +2045,8 @@ impl Default for PlaylistSettings {
             folder_id: None,
             created_at: now,
             updated_at: now,
+            group_by: PlaylistGrouping::None,
+            offline_only: false,
         }
     }
 }
@@ -1877,7 +2077,8 @@ impl LibraryDatabase {
     ) -> Result<Option<PlaylistSettings>, LibraryError> {
         let result = self.conn.query_row(
             "SELECT qobuz_playlist_id, custom_artwork_path, sort_by, sort_order,
-                    last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at
+                    last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at,
+                    group_by, offline_only
              FROM playlist_settings WHERE qobuz_playlist_id = ?1",
             params![qobuz_playlist_id as i64],
             |row| {
@@ -1895,6 +2096,8 @@ impl LibraryDatabase {
                     folder_id: row.get(10)?,
                     created_at: row.get(11)?,
                     updated_at: row.get(12)?,
+                    group_by: PlaylistGrouping::from_str(&row.get::<_, Option<String>>(13)?.unwrap_or_default()),
+                    offline_only: row.get::<_, i32>(14).unwrap_or(0) != 0,
                 })
             },
         ).optional()
@@ -1913,8 +2116,9 @@ impl LibraryDatabase {
         self.conn.execute(
             "INSERT INTO playlist_settings
                 (qobuz_playlist_id, custom_artwork_path, sort_by, sort_order,
-                 last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                 last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at,
+                 group_by, offline_only)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
              ON CONFLICT(qobuz_playlist_id) DO UPDATE SET
                 custom_artwork_path = excluded.custom_artwork_path,
                 sort_by = excluded.sort_by,
@@ -1926,7 +2130,9 @@ impl LibraryDatabase {
                 has_local_content = excluded.has_local_content,
                 is_favorite = excluded.is_favorite,
                 folder_id = excluded.folder_id,
-                updated_at = excluded.updated_at",
+                updated_at = excluded.updated_at,
+                group_by = excluded.group_by,
+                offline_only = excluded.offline_only",
             params![
                 settings.qobuz_playlist_id as i64,
                 &settings.custom_artwork_path,
@@ -1941,6 +2147,8 @@ impl LibraryDatabase {
                 &settings.folder_id,
                 settings.created_at,
                 now,
+                settings.group_by.as_str(),
+                settings.offline_only as i32,
             ],
         ).map_err(|e| LibraryError::Database(format!("Failed to save playlist settings: {}", e)))?;
 
@@ -1982,6 +2190,53 @@ impl LibraryDatabase {
         Ok(())
     }
 
+    /// Update the persisted view state (sort, grouping, offline filter) of a playlist
+    pub fn update_playlist_view(
+        &self,
+        qobuz_playlist_id: u64,
+        sort_by: &str,
+        sort_order: &str,
+        group_by: PlaylistGrouping,
+        offline_only: bool,
+    ) -> Result<(), LibraryError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        // First check if settings exist, if not create default
+        let existing = self.get_playlist_settings(qobuz_playlist_id)?;
+        if existing.is_none() {
+            let mut settings = PlaylistSettings::default();
+            settings.qobuz_playlist_id = qobuz_playlist_id;
+            settings.sort_by = sort_by.to_string();
+            settings.sort_order = sort_order.to_string();
+            settings.group_by = group_by;
+            settings.offline_only = offline_only;
+            return self.save_playlist_settings(&settings);
+        }
+
+        self.conn
+            .execute(
+                "UPDATE playlist_settings
+             SET sort_by = ?1, sort_order = ?2, group_by = ?3, offline_only = ?4, updated_at = ?5
+             WHERE qobuz_playlist_id = ?6",
+                params![
+                    sort_by,
+                    sort_order,
+                    group_by.as_str(),
+                    offline_only as i32,
+                    now,
+                    qobuz_playlist_id as i64
+                ],
+            )
+            .map_err(|e| {
+                LibraryError::Database(format!("Failed to update playlist view: {}", e))
+            })?;
+
+        Ok(())
+    }
+
     /// Update custom artwork path for a playlist
     pub fn update_playlist_artwork(
         &self,
@@ -2066,7 +2321,8 @@ impl LibraryDatabase {
     pub fn get_all_playlist_settings(&self) -> Result<Vec<PlaylistSettings>, LibraryError> {
         let mut stmt = self.conn.prepare(
             "SELECT qobuz_playlist_id, custom_artwork_path, sort_by, sort_order,
-                    last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at
+                    last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at,
+                    group_by, offline_only
              FROM playlist_settings ORDER BY position ASC, updated_at DESC"
         ).map_err(|e| LibraryError::Database(format!("Failed to prepare statement: {}", e)))?;
 
@@ -2088,6 +2344,8 @@ impl LibraryDatabase {
                     folder_id: row.get(10)?,
                     created_at: row.get(11)?,
                     updated_at: row.get(12)?,
+                    group_by: PlaylistGrouping::from_str(&row.get::<_, Option<String>>(13)?.unwrap_or_default()),
+                    offline_only: row.get::<_, i32>(14).unwrap_or(0) != 0,
                 })
             })
             .map_err(|e| {
@@ -2697,6 +2955,9 @@ impl LibraryDatabase {
                     year: row.get(10)?,
                     genre: row.get(11)?,
                     catalog_number: None,
+                    composer: None,
+                    work: None,
+                    movement: None,
                     duration_secs: row.get(12)?,
                     format: Self::parse_format(&row.get::<_, String>(13)?),
                     bit_depth: row.get(14)?,
@@ -2760,6 +3021,9 @@ impl LibraryDatabase {
                         year: row.get(10)?,
                         genre: row.get(11)?,
                         catalog_number: None,
+                        composer: None,
+                        work: None,
+                        movement: None,
                         duration_secs: row.get(12)?,
                         format: Self::parse_format(&row.get::<_, String>(13)?),
                         bit_depth: row.get(14)?,
@@ -3430,7 +3694,7 @@ impl LibraryDatabase {
         artist_name: &str,
     ) -> Result<Option<crate::library::commands::ArtistImageInfo>, LibraryError> {
         let result = self.conn.query_row(
-            "SELECT artist_name, image_url, source, custom_image_path, canonical_name FROM artist_images WHERE artist_name = ?1",
+            "SELECT artist_name, image_url, source, custom_image_path, canonical_name, fetched_at FROM artist_images WHERE artist_name = ?1",
             params![artist_name],
             |row| {
                 Ok(crate::library::commands::ArtistImageInfo {
@@ -3439,6 +3703,7 @@ impl LibraryDatabase {
                     source: row.get(2)?,
                     custom_image_path: row.get(3)?,
                     canonical_name: row.get(4)?,
+                    fetched_at: row.get(5)?,
                 })
             }
         ).optional()
@@ -3489,6 +3754,9 @@ impl LibraryDatabase {
     }
 
     /// Cache artist image with canonical name from Qobuz/Discogs
+    ///
+    /// A custom image (source "custom") is only ever replaced by another
+    /// custom image.
     pub fn cache_artist_image_with_canonical(
         &self,
         artist_name: &str,
@@ -3503,15 +3771,250 @@ impl LibraryDatabase {
             .as_secs() as i64;
 
         self.conn.execute(
-            "INSERT OR REPLACE INTO artist_images
+            "INSERT INTO artist_images
              (artist_name, image_url, source, custom_image_path, canonical_name, fetched_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(artist_name) DO UPDATE SET
+                image_url = excluded.image_url,
+                source = excluded.source,
+                custom_image_path = excluded.custom_image_path,
+                canonical_name = excluded.canonical_name,
+                fetched_at = excluded.fetched_at,
+                updated_at = excluded.updated_at
+             WHERE artist_images.custom_image_path IS NULL OR excluded.source = 'custom'",
             params![artist_name, image_url, source, custom_image_path, canonical_name, now, now],
         )
         .map_err(|e| LibraryError::Database(format!("Failed to cache artist image: {}", e)))?;
         Ok(())
     }
 
+    /// Cached provider lookup for an MBID: (image_url, source, fetched_at)
+    pub fn get_mbid_artist_image(
+        &self,
+        mbid: &str,
+    ) -> Result<Option<(Option<String>, String, i64)>, LibraryError> {
+        self.conn
+            .query_row(
+                "SELECT image_url, source, fetched_at FROM artist_mbid_images WHERE mbid = ?1",
+                params![mbid],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .map_err(|e| LibraryError::Database(format!("Failed to get MBID artist image: {}", e)))
+    }
+
+    /// Cache a provider lookup for an MBID (`None` = no provider had an image)
+    pub fn set_mbid_artist_image(
+        &self,
+        mbid: &str,
+        image_url: Option<&str>,
+        source: &str,
+    ) -> Result<(), LibraryError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO artist_mbid_images (mbid, image_url, source, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![mbid, image_url, source, now],
+            )
+            .map_err(|e| {
+                LibraryError::Database(format!("Failed to cache MBID artist image: {}", e))
+            })?;
+        Ok(())
+    }
+
+    /// Artist names (album artist, else track artist) of the tracks in a folder
+    pub fn get_artist_names_in_folder(&self, folder: &str) -> Result<Vec<String>, LibraryError> {
+        let pattern = format!("{}%", folder);
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT DISTINCT COALESCE(album_artist, artist) FROM local_tracks
+                 WHERE file_path LIKE ?1",
+            )
+            .map_err(|e| LibraryError::Database(format!("Failed to prepare query: {}", e)))?;
+
+        let rows = stmt
+            .query_map(params![pattern], |row| row.get::<_, String>(0))
+            .map_err(|e| LibraryError::Database(format!("Failed to query artists: {}", e)))?;
+
+        let mut names = Vec::new();
+        for row in rows {
+            names.push(row.map_err(|e| LibraryError::Database(e.to_string()))?);
+        }
+        Ok(names)
+    }
+
+    // === Artist Name Resolution & Merging ===
+
+    /// Get all cached MusicBrainz resolutions of artist spellings
+    pub fn get_artist_musicbrainz_mappings(
+        &self,
+    ) -> Result<Vec<ArtistMusicBrainzMapping>, LibraryError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT artist_name, mbid, canonical_name FROM artist_musicbrainz")
+            .map_err(|e| LibraryError::Database(format!("Failed to prepare query: {}", e)))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ArtistMusicBrainzMapping {
+                    artist_name: row.get(0)?,
+                    mbid: row.get(1)?,
+                    canonical_name: row.get(2)?,
+                })
+            })
+            .map_err(|e| {
+                LibraryError::Database(format!("Failed to query MusicBrainz mappings: {}", e))
+            })?;
+
+        let mut mappings = Vec::new();
+        for row in rows {
+            mappings.push(row.map_err(|e| LibraryError::Database(e.to_string()))?);
+        }
+        Ok(mappings)
+    }
+
+    /// Cache the MusicBrainz resolution of an artist spelling (`None` = no match)
+    pub fn set_artist_musicbrainz(
+        &self,
+        artist_name: &str,
+        mbid: Option<&str>,
+        canonical_name: Option<&str>,
+    ) -> Result<(), LibraryError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO artist_musicbrainz
+                 (artist_name, mbid, canonical_name, resolved_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![artist_name, mbid, canonical_name, now],
+            )
+            .map_err(|e| {
+                LibraryError::Database(format!("Failed to cache MusicBrainz mapping: {}", e))
+            })?;
+        Ok(())
+    }
+
+    /// Tracks credited to any of `artist_names`, as artist or album artist
+    pub fn get_tracks_by_artist_names(
+        &self,
+        artist_names: &[String],
+    ) -> Result<Vec<LocalTrack>, LibraryError> {
+        if artist_names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders: Vec<String> = (1..=artist_names.len())
+            .map(|i| format!("?{}", i))
+            .collect();
+        let placeholders_str = placeholders.join(",");
+
+        let query = format!(
+            "SELECT * FROM local_tracks
+             WHERE artist IN ({0}) OR album_artist IN ({0})
+             ORDER BY album, disc_number, track_number, title",
+            placeholders_str
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(&query)
+            .map_err(|e| LibraryError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(artist_names.iter()), |row| {
+                Self::row_to_track(row)
+            })
+            .map_err(|e| LibraryError::Database(e.to_string()))?;
+
+        let mut tracks = Vec::new();
+        for track in rows {
+            tracks.push(track.map_err(|e| LibraryError::Database(e.to_string()))?);
+        }
+        Ok(tracks)
+    }
+
+    /// Re-point every track credited to `from_names` to `to_name`
+    ///
+    /// The merge is remembered so re-indexed files keep the merged spelling.
+    /// Returns the number of tracks updated.
+    pub fn merge_artists(
+        &mut self,
+        from_names: &[String],
+        to_name: &str,
+    ) -> Result<usize, LibraryError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| LibraryError::Database(e.to_string()))?;
+
+        let mut updated = 0;
+        for from_name in from_names {
+            updated += tx
+                .execute(
+                    r#"
+                    UPDATE local_tracks
+                    SET
+                        artist = CASE WHEN artist = ?1 THEN ?2 ELSE artist END,
+                        album_artist = CASE WHEN album_artist = ?1 THEN ?2 ELSE album_artist END
+                    WHERE artist = ?1 OR album_artist = ?1
+                    "#,
+                    params![from_name, to_name],
+                )
+                .map_err(|e| LibraryError::Database(e.to_string()))?;
+
+            // Earlier merges into this spelling follow it to the new target
+            tx.execute(
+                "UPDATE artist_merges SET to_name = ?2 WHERE to_name = ?1",
+                params![from_name, to_name],
+            )
+            .map_err(|e| LibraryError::Database(e.to_string()))?;
+            tx.execute(
+                "INSERT OR REPLACE INTO artist_merges (from_name, to_name, merged_at)
+                 VALUES (?1, ?2, ?3)",
+                params![from_name, to_name, now],
+            )
+            .map_err(|e| LibraryError::Database(e.to_string()))?;
+        }
+
+        // The target may itself have been merged away before
+        tx.execute(
+            "DELETE FROM artist_merges WHERE from_name = ?1",
+            params![to_name],
+        )
+        .map_err(|e| LibraryError::Database(e.to_string()))?;
+
+        tx.commit()
+            .map_err(|e| LibraryError::Database(e.to_string()))?;
+        Ok(updated)
+    }
+
+    /// Spelling an artist name was merged into, if any
+    fn merged_artist_name(&self, name: &str) -> Result<Option<String>, LibraryError> {
+        self.conn
+            .query_row(
+                "SELECT to_name FROM artist_merges WHERE from_name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| LibraryError::Database(e.to_string()))
+    }
+
     // === Offline Mode: Local Content Detection ===
 
     /// Check if a track exists locally by Qobuz track ID
@@ -3681,13 +4184,15 @@ impl LibraryDatabase {
     ) -> Result<Vec<PlaylistSettings>, LibraryError> {
         let query = if include_partial {
             "SELECT qobuz_playlist_id, custom_artwork_path, sort_by, sort_order,
-                    last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at
+                    last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at,
+                    group_by, offline_only
              FROM playlist_settings
              WHERE has_local_content IN ('some_local', 'all_local')
              ORDER BY position ASC, updated_at DESC"
         } else {
             "SELECT qobuz_playlist_id, custom_artwork_path, sort_by, sort_order,
-                    last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at
+                    last_search_query, notes, hidden, position, has_local_content, is_favorite, folder_id, created_at, updated_at,
+                    group_by, offline_only
              FROM playlist_settings
              WHERE has_local_content = 'all_local'
              ORDER BY position ASC, updated_at DESC"
@@ -3716,6 +4221,8 @@ impl LibraryDatabase {
                     folder_id: row.get(10)?,
                     created_at: row.get(11)?,
                     updated_at: row.get(12)?,
+                    group_by: PlaylistGrouping::from_str(&row.get::<_, Option<String>>(13)?.unwrap_or_default()),
+                    offline_only: row.get::<_, i32>(14).unwrap_or(0) != 0,
                 })
             })
             .map_err(|e| {
@@ -3727,3 +4234,75 @@ impl LibraryDatabase {
             .map_err(|e| LibraryError::Database(format!("Failed to collect playlists: {}", e)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str, artist: &str, album_artist: Option<&str>) -> LocalTrack {
+        LocalTrack {
+            file_path: path.to_string(),
+            title: path.to_string(),
+            artist: artist.to_string(),
+            album_artist: album_artist.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_artists_repoints_tracks_and_remembers_the_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = LibraryDatabase::open(&dir.path().join("library.db")).unwrap();
+        db.insert_track(&track("/a.flac", "Beatles", Some("The Beatles")))
+            .unwrap();
+        db.insert_track(&track("/b.flac", "beatles", Some("beatles")))
+            .unwrap();
+        db.insert_track(&track("/c.flac", "Wings", None)).unwrap();
+
+        let from = vec!["Beatles".to_string(), "beatles".to_string()];
+        assert_eq!(db.merge_artists(&from, "The Beatles").unwrap(), 2);
+        let tracks = db
+            .get_tracks_by_artist_names(&["The Beatles".to_string()])
+            .unwrap();
+        assert_eq!(tracks.len(), 2);
+        assert!(tracks
+            .iter()
+            .all(|t| t.album_artist.as_deref() == Some(t.artist.as_str())));
+        assert!(tracks.iter().all(|t| t.artist == "The Beatles"));
+        assert!(db.get_tracks_by_artist_names(&from).unwrap().is_empty());
+
+        // A re-indexed file keeps the merged spelling
+        db.insert_track(&track("/d.flac", "Beatles", None)).unwrap();
+        assert_eq!(
+            db.get_tracks_by_artist_names(&["The Beatles".to_string()])
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn merge_artists_follows_earlier_merges() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = LibraryDatabase::open(&dir.path().join("library.db")).unwrap();
+
+        db.merge_artists(&["beatles".to_string()], "The Beatles")
+            .unwrap();
+        // Merging the target onward carries the earlier merge along
+        db.merge_artists(&["The Beatles".to_string()], "Beatles, The")
+            .unwrap();
+        assert_eq!(
+            db.merged_artist_name("beatles").unwrap().as_deref(),
+            Some("Beatles, The")
+        );
+
+        // Merging back into a merged-away spelling drops its own mapping
+        db.merge_artists(&["Beatles, The".to_string()], "beatles")
+            .unwrap();
+        assert_eq!(db.merged_artist_name("beatles").unwrap(), None);
+        assert_eq!(
+            db.merged_artist_name("The Beatles").unwrap().as_deref(),
+            Some("beatles")
+        );
+    }
+}
@@ -1,10 +1,11 @@
 //! Metadata extraction for audio files
 
-use lofty::{Accessor, AudioFile, ItemKey, Probe, TaggedFileExt};
+use lofty::{Accessor, AudioFile, ItemKey, Probe, Tag, TaggedFileExt};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::api::classical::split_title;
 use crate::library::{AudioFormat, AudioProperties, LibraryError, LocalTrack};
 use crate::library::thumbnails::{generate_thumbnail, generate_thumbnail_from_bytes};
 
@@ -19,6 +20,14 @@ impl MetadataExtractor {
             .map(|s| s.to_string())
     }
 
+    /// Work and movement from the tags, or split from the title
+    fn classical_fields(tag: &Tag, title: &str) -> (Option<String>, Option<String>) {
+        let work = Self::normalize_field(tag.get_string(&ItemKey::Work));
+        let movement = Self::normalize_field(tag.get_string(&ItemKey::Movement));
+        let (work, title_movement) = split_title(title, work.as_deref());
+        (work, movement.or(title_movement))
+    }
+
     fn strip_year_suffix(name: &str) -> String {
         let trimmed = name.trim();
         for (open, close) in [("(", ")"), ("[", "]")] {
@@ -305,13 +314,16 @@ impl MetadataExtractor {
             let (album_group_key, album_group_title) =
                 Self::album_group_info(file_path, Some(album_title.as_str()));
 
+            let title = tag
+                .title()
+                .map(|s| s.to_string())
+                .unwrap_or(filename);
+            let (work, movement) = Self::classical_fields(tag, &title);
+
             LocalTrack {
                 id: 0,
                 file_path: file_path.to_string_lossy().to_string(),
-                title: tag
-                    .title()
-                    .map(|s| s.to_string())
-                    .unwrap_or(filename),
+                title,
                 artist: Self::normalize_field(tag.artist().as_deref())
                     .or_else(|| fallback_artist.clone())
                     .unwrap_or_else(|| "Unknown Artist".to_string()),
@@ -327,6 +339,9 @@ impl MetadataExtractor {
                 year: tag.year().map(|y| y as u32),
                 genre: tag.genre().map(|s| s.to_string()),
                 catalog_number: tag.get_string(&ItemKey::CatalogNumber).map(|s| s.to_string()),
+                composer: Self::normalize_field(tag.get_string(&ItemKey::Composer)),
+                work,
+                movement,
                 duration_secs,
                 format,
                 bit_depth,
@@ -368,6 +383,9 @@ impl MetadataExtractor {
                 year: None,
                 genre: None,
                 catalog_number: None,
+                composer: None,
+                work: None,
+                movement: None,
                 duration_secs,
                 format,
                 bit_depth,
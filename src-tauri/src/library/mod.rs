@@ -3,28 +3,32 @@
 //! Provides functionality for scanning, indexing, and playing local audio files.
 //! This module is completely independent of the Qobuz streaming functionality.
 
+pub mod artist_images;
 pub mod commands;
 pub mod cue_parser;
 pub mod database;
 pub mod errors;
 pub mod metadata;
 pub mod models;
+pub mod playlist_view;
 pub mod remote_metadata;
+pub mod scan_progress;
 pub mod scanner;
 pub mod tag_sidecar;
+pub mod tag_writer;
 pub mod thumbnails;
 
 pub use commands::LibraryState;
 pub use cue_parser::{cue_to_tracks, CueParser, CueSheet, CueTrack};
 pub use database::{
-    AlbumTrackUpdate, LibraryDatabase, LibraryFolder, LibraryStats, PlaylistFolder, PlaylistSettings,
-    PlaylistStats, TrackMetadataUpdateFull,
+    AlbumTrackUpdate, LibraryDatabase, LibraryFolder, LibraryStats, PlaylistFolder, PlaylistGrouping,
+    PlaylistSettings, PlaylistStats, TrackMetadataUpdateFull,
 };
 pub use errors::LibraryError;
 pub use metadata::MetadataExtractor;
 pub use models::*;
 pub use tag_sidecar::*;
-pub use scanner::{LibraryScanner, ScanResult};
+pub use scanner::{FileFingerprint, LibraryScanner, ScanResult};
 
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::quality::QualityInfo;
+
 /// Supported audio formats
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AudioFormat {
@@ -53,6 +55,11 @@ pub struct LocalTrack {
     pub genre: Option<String>,
     pub catalog_number: Option<String>,
 
+    // Classical metadata
+    pub composer: Option<String>,
+    pub work: Option<String>,
+    pub movement: Option<String>,
+
     // Audio properties
     pub duration_secs: u64,
     pub format: AudioFormat,
@@ -94,6 +101,9 @@ impl Default for LocalTrack {
             year: None,
             genre: None,
             catalog_number: None,
+            composer: None,
+            work: None,
+            movement: None,
             duration_secs: 0,
             format: AudioFormat::Unknown,
             bit_depth: None,
@@ -112,6 +122,21 @@ impl Default for LocalTrack {
     }
 }
 
+impl LocalTrack {
+    pub fn quality_info(&self) -> QualityInfo {
+        let codec = (self.format != AudioFormat::Unknown).then(|| self.format.to_string());
+        QualityInfo::from_specs(self.bit_depth, Some(self.sample_rate), codec.as_deref())
+    }
+}
+
+/// MusicBrainz resolution of a local artist spelling
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtistMusicBrainzMapping {
+    pub artist_name: String,
+    pub mbid: Option<String>,
+    pub canonical_name: Option<String>,
+}
+
 /// A local track within a playlist, including its position
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaylistLocalTrack {
@@ -157,13 +182,34 @@ pub struct LocalArtist {
     pub track_count: u32,
 }
 
-/// Scan progress for UI updates
+/// Scan progress for UI updates (also pushed as `library-scan-progress` events)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanProgress {
     pub status: ScanStatus,
+    /// Files found so far; grows while folders are still being enumerated
     pub total_files: u32,
     pub processed_files: u32,
     pub current_file: Option<String>,
+    #[serde(default)]
+    pub current_folder: Option<String>,
+    /// Breakdown of `processed_files`
+    #[serde(default)]
+    pub added: u32,
+    #[serde(default)]
+    pub updated: u32,
+    #[serde(default)]
+    pub skipped: u32,
+    /// Not re-read because the file is unchanged since the last scan
+    #[serde(default)]
+    pub unchanged: u32,
+    #[serde(default)]
+    pub errored: u32,
+    /// Recent throughput, once there's enough data for an estimate
+    #[serde(default)]
+    pub files_per_sec: Option<f64>,
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
+    /// Files that couldn't be read or stored (capped; see `errored` for the count)
     pub errors: Vec<ScanError>,
 }
 
@@ -174,6 +220,14 @@ impl Default for ScanProgress {
             total_files: 0,
             processed_files: 0,
             current_file: None,
+            current_folder: None,
+            added: 0,
+            updated: 0,
+            skipped: 0,
+            unchanged: 0,
+            errored: 0,
+            files_per_sec: None,
+            eta_secs: None,
             errors: Vec::new(),
         }
     }
@@ -0,0 +1,187 @@
+//! Persisted playlist view (sort, grouping, offline filter)
+//!
+//! The same ordering the playlist view applies client-side, so getters can
+//! return tracks the way the user left the playlist. Field names and the
+//! direction semantics match the `sort_by`/`sort_order` values the frontend
+//! stores ("added" ascending means newest first).
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::database::{PlaylistGrouping, PlaylistSettings};
+
+/// The fields of a playlist entry the view sorts and groups on
+#[derive(Debug, Clone, Default)]
+pub struct ViewEntry<'a> {
+    pub title: &'a str,
+    pub artist: &'a str,
+    pub album: &'a str,
+    pub label: Option<&'a str>,
+    pub duration_secs: u64,
+    /// Position in the playlist as returned by the source
+    pub position: usize,
+    /// When the track was added (Unix seconds), if known
+    pub added_at: Option<i64>,
+    /// Position from `playlist_track_custom_order`, if any
+    pub custom_position: Option<i32>,
+}
+
+/// Custom order rows as (track_id, is_local) -> position
+pub fn custom_positions(rows: &[(i64, bool, i32)]) -> HashMap<(i64, bool), i32> {
+    rows.iter()
+        .map(|(track_id, is_local, position)| ((*track_id, *is_local), *position))
+        .collect()
+}
+
+/// Indices of `entries` in the order the view shows them
+pub fn view_order(entries: &[ViewEntry], settings: &PlaylistSettings) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+
+    if settings.sort_by != "default" {
+        let desc = settings.sort_order == "desc";
+        order.sort_by(|&a, &b| {
+            let cmp = compare(&entries[a], &entries[b], &settings.sort_by);
+            if desc {
+                cmp.reverse()
+            } else {
+                cmp
+            }
+        });
+    }
+
+    // Groups appear in the order of their first track, tracks keep their order within
+    let mut group_rank: HashMap<String, usize> = HashMap::new();
+    let ranks: Vec<Option<usize>> = order
+        .iter()
+        .map(|&index| {
+            let key = group_key(&entries[index], settings.group_by)?;
+            let next = group_rank.len();
+            Some(*group_rank.entry(key).or_insert(next))
+        })
+        .collect();
+    if !group_rank.is_empty() {
+        let mut ranked: Vec<(Option<usize>, usize)> = ranks.into_iter().zip(order).collect();
+        ranked.sort_by_key(|(rank, _)| rank.unwrap_or(usize::MAX));
+        order = ranked.into_iter().map(|(_, index)| index).collect();
+    }
+
+    order
+}
+
+/// Reorder `items` by `order` (a permutation or subset of their indices)
+pub fn apply_order<T>(items: Vec<T>, order: &[usize]) -> Vec<T> {
+    let mut slots: Vec<Option<T>> = items.into_iter().map(Some).collect();
+    order
+        .iter()
+        .filter_map(|&index| slots[index].take())
+        .collect()
+}
+
+fn compare(a: &ViewEntry, b: &ViewEntry, sort_by: &str) -> Ordering {
+    match sort_by {
+        "title" => compare_text(a.title, b.title),
+        "artist" => compare_text(a.artist, b.artist),
+        "album" => compare_text(a.album, b.album),
+        "duration" => a.duration_secs.cmp(&b.duration_secs),
+        // Newest first when ascending
+        "added" => added_key(b).cmp(&added_key(a)),
+        // Tracks without a label go last
+        "label" => match (
+            a.label.filter(|l| !l.is_empty()),
+            b.label.filter(|l| !l.is_empty()),
+        ) {
+            (Some(a), Some(b)) => compare_text(a, b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        "custom" => a
+            .custom_position
+            .unwrap_or(a.position as i32)
+            .cmp(&b.custom_position.unwrap_or(b.position as i32)),
+        _ => Ordering::Equal,
+    }
+}
+
+fn compare_text(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
+
+fn added_key(entry: &ViewEntry) -> (i64, usize) {
+    (entry.added_at.unwrap_or(0), entry.position)
+}
+
+fn group_key(entry: &ViewEntry, group_by: PlaylistGrouping) -> Option<String> {
+    match group_by {
+        PlaylistGrouping::None => None,
+        PlaylistGrouping::Album => Some(entry.album.to_lowercase()),
+        // Calendar day (UTC) the track was added
+        PlaylistGrouping::AddedDate => entry.added_at.map(|t| t.div_euclid(86_400).to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry<'a>(title: &'a str, album: &'a str, position: usize) -> ViewEntry<'a> {
+        ViewEntry {
+            title,
+            album,
+            position,
+            ..Default::default()
+        }
+    }
+
+    fn settings(sort_by: &str, sort_order: &str, group_by: PlaylistGrouping) -> PlaylistSettings {
+        PlaylistSettings {
+            sort_by: sort_by.to_string(),
+            sort_order: sort_order.to_string(),
+            group_by,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_sort_keeps_playlist_order() {
+        let entries = vec![entry("b", "x", 0), entry("a", "y", 1)];
+        let order = view_order(
+            &entries,
+            &settings("default", "asc", PlaylistGrouping::None),
+        );
+        assert_eq!(order, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_sort_by_title_desc() {
+        let entries = vec![entry("b", "x", 0), entry("A", "y", 1), entry("c", "z", 2)];
+        let order = view_order(&entries, &settings("title", "desc", PlaylistGrouping::None));
+        assert_eq!(order, vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_group_by_album_keeps_sort_within_groups() {
+        let entries = vec![
+            entry("d", "Blue", 0),
+            entry("a", "Kind of Blue", 1),
+            entry("c", "Blue", 2),
+            entry("b", "Kind of Blue", 3),
+        ];
+        let order = view_order(&entries, &settings("title", "asc", PlaylistGrouping::Album));
+        assert_eq!(order, vec![1, 3, 2, 0]);
+        assert_eq!(
+            apply_order(vec!["d", "a", "c", "b"], &order),
+            vec!["a", "b", "c", "d"]
+        );
+    }
+
+    #[test]
+    fn test_added_ascending_is_newest_first() {
+        let mut entries = vec![entry("a", "x", 0), entry("b", "x", 1), entry("c", "x", 2)];
+        entries[0].added_at = Some(300);
+        entries[1].added_at = Some(100);
+        entries[2].added_at = Some(200);
+        let order = view_order(&entries, &settings("added", "asc", PlaylistGrouping::None));
+        assert_eq!(order, vec![0, 2, 1]);
+    }
+}
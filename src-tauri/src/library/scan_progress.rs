@@ -0,0 +1,237 @@
+//! Live progress reporting for library scans
+//!
+//! `ScanTracker` keeps the shared `ScanProgress` up to date while a scan runs
+//! and pushes it to the UI as `library-scan-progress` events (throttled), so
+//! the frontend doesn't have to poll. The ETA comes from the throughput over
+//! the last few seconds rather than the whole scan, since speed varies a lot
+//! between local disks, network shares and files with large embedded artwork.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use crate::library::{ScanError, ScanProgress, ScanStatus};
+
+/// Event carrying a `ScanProgress` snapshot
+pub const SCAN_PROGRESS_EVENT: &str = "library-scan-progress";
+
+/// Minimum time between progress events
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Throughput is measured over this trailing window
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Errors kept in the progress list; `errored` still counts all of them
+const MAX_LISTED_ERRORS: usize = 1000;
+
+/// What happened to a scanned file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOutcome {
+    /// Not in the library before
+    Added,
+    /// Already indexed, tags re-read
+    Updated,
+    /// Left alone (covered by a CUE sheet, or a Qobuz cached track)
+    Skipped,
+    /// Same size and mtime as when it was indexed (incremental scan)
+    Unchanged,
+}
+
+/// Rolling files-per-second estimate
+#[derive(Debug, Default)]
+pub struct ThroughputEstimator {
+    /// (time, files processed so far)
+    samples: VecDeque<(Instant, u32)>,
+}
+
+impl ThroughputEstimator {
+    pub fn record(&mut self, now: Instant, processed: u32) {
+        self.samples.push_back((now, processed));
+        while let Some(&(oldest, _)) = self.samples.front() {
+            if now.duration_since(oldest) > THROUGHPUT_WINDOW && self.samples.len() > 2 {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Files per second over the window; None until there's enough data
+    pub fn files_per_sec(&self) -> Option<f64> {
+        let (first_at, first) = *self.samples.front()?;
+        let (last_at, last) = *self.samples.back()?;
+        let elapsed = last_at.duration_since(first_at).as_secs_f64();
+        if elapsed < 1.0 || last <= first {
+            return None;
+        }
+        Some((last - first) as f64 / elapsed)
+    }
+
+    /// Seconds left for `remaining` files at the current rate
+    pub fn eta_secs(&self, remaining: u32) -> Option<u64> {
+        self.files_per_sec()
+            .map(|rate| (remaining as f64 / rate).ceil() as u64)
+    }
+}
+
+pub struct ScanTracker {
+    app: AppHandle,
+    progress: Arc<Mutex<ScanProgress>>,
+    throughput: ThroughputEstimator,
+    last_emit: Option<Instant>,
+}
+
+impl ScanTracker {
+    /// Reset the shared progress for a new scan
+    pub async fn start(app: AppHandle, progress: Arc<Mutex<ScanProgress>>) -> Self {
+        {
+            let mut guard = progress.lock().await;
+            *guard = ScanProgress {
+                status: ScanStatus::Scanning,
+                ..Default::default()
+            };
+        }
+        let mut tracker = Self {
+            app,
+            progress,
+            throughput: ThroughputEstimator::default(),
+            last_emit: None,
+        };
+        tracker.emit(true).await;
+        tracker
+    }
+
+    /// Folder currently being enumerated or processed
+    pub async fn set_folder(&mut self, folder: &str) {
+        self.progress.lock().await.current_folder = Some(folder.to_string());
+        self.emit(false).await;
+    }
+
+    pub async fn add_total(&mut self, files: usize) {
+        self.progress.lock().await.total_files += files as u32;
+        self.emit(false).await;
+    }
+
+    pub async fn begin_file(&mut self, path: &str) {
+        self.progress.lock().await.current_file = Some(path.to_string());
+        self.emit(false).await;
+    }
+
+    pub async fn finish_file(&mut self, outcome: FileOutcome) {
+        {
+            let mut progress = self.progress.lock().await;
+            match outcome {
+                FileOutcome::Added => progress.added += 1,
+                FileOutcome::Updated => progress.updated += 1,
+                FileOutcome::Skipped => progress.skipped += 1,
+                FileOutcome::Unchanged => progress.unchanged += 1,
+            }
+            advance(&mut self.throughput, &mut progress);
+        }
+        self.emit(false).await;
+    }
+
+    /// A file that couldn't be read, tagged or stored
+    pub async fn fail_file(&mut self, file_path: String, error: String) {
+        {
+            let mut progress = self.progress.lock().await;
+            progress.errored += 1;
+            if progress.errors.len() < MAX_LISTED_ERRORS {
+                progress.errors.push(ScanError { file_path, error });
+            }
+            advance(&mut self.throughput, &mut progress);
+        }
+        self.emit(false).await;
+    }
+
+    /// An error that isn't about a single file (e.g. a missing folder)
+    pub async fn report_error(&mut self, file_path: String, error: String) {
+        {
+            let mut progress = self.progress.lock().await;
+            if progress.errors.len() < MAX_LISTED_ERRORS {
+                progress.errors.push(ScanError { file_path, error });
+            }
+        }
+        self.emit(true).await;
+    }
+
+    pub async fn finish(mut self, status: ScanStatus) {
+        {
+            let mut progress = self.progress.lock().await;
+            progress.status = status;
+            progress.current_file = None;
+            progress.current_folder = None;
+            progress.eta_secs = None;
+            log::info!(
+                "Scan {:?}: {} added, {} updated, {} unchanged, {} skipped, {} failed",
+                progress.status,
+                progress.added,
+                progress.updated,
+                progress.unchanged,
+                progress.skipped,
+                progress.errored
+            );
+        }
+        self.emit(true).await;
+    }
+
+    async fn emit(&mut self, force: bool) {
+        let now = Instant::now();
+        if !force
+            && self
+                .last_emit
+                .is_some_and(|last| now.duration_since(last) < EMIT_INTERVAL)
+        {
+            return;
+        }
+        self.last_emit = Some(now);
+        let snapshot = self.progress.lock().await.clone();
+        let _ = self.app.emit(SCAN_PROGRESS_EVENT, snapshot);
+    }
+}
+
+/// Count one more processed file and refresh the rate and ETA
+fn advance(throughput: &mut ThroughputEstimator, progress: &mut ScanProgress) {
+    progress.processed_files += 1;
+    throughput.record(Instant::now(), progress.processed_files);
+    progress.files_per_sec = throughput.files_per_sec();
+    progress.eta_secs = throughput.eta_secs(
+        progress
+            .total_files
+            .saturating_sub(progress.processed_files),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eta_follows_recent_throughput() {
+        let start = Instant::now();
+        let mut estimator = ThroughputEstimator::default();
+        assert_eq!(estimator.files_per_sec(), None);
+
+        // 100 files/s for the first 20 seconds
+        for second in 0..=20u32 {
+            estimator.record(start + Duration::from_secs(second as u64), second * 100);
+        }
+        let rate = estimator.files_per_sec().unwrap();
+        assert!((rate - 100.0).abs() < 0.01);
+        assert_eq!(estimator.eta_secs(1000), Some(10));
+
+        // Then a slow network share: 10 files/s; the old samples age out
+        for second in 1..=15u32 {
+            estimator.record(
+                start + Duration::from_secs(20 + second as u64),
+                2000 + second * 10,
+            );
+        }
+        let rate = estimator.files_per_sec().unwrap();
+        assert!((rate - 10.0).abs() < 0.01);
+        assert_eq!(estimator.eta_secs(100), Some(10));
+    }
+}
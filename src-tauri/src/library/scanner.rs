@@ -1,6 +1,8 @@
 //! Filesystem scanner for audio files
 
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 use walkdir::WalkDir;
 
 use crate::library::LibraryError;
@@ -20,6 +22,32 @@ pub struct ScanResult {
     pub cue_files: Vec<PathBuf>,
 }
 
+/// Size and modification time of a file, used by incremental scans to
+/// skip files that haven't changed since they were indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileFingerprint {
+    pub size_bytes: u64,
+    /// Nanoseconds since the epoch; seconds aren't precise enough to catch
+    /// a tag edit made right after the file was indexed
+    pub mtime_ns: i64,
+}
+
+impl FileFingerprint {
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let mtime_ns = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos() as i64;
+        Some(Self {
+            size_bytes: metadata.len(),
+            mtime_ns,
+        })
+    }
+}
+
 /// Library scanner for discovering audio files
 pub struct LibraryScanner;
 
@@ -108,4 +136,24 @@ mod tests {
         assert!(LibraryScanner::is_supported_audio_extension("mp3"));
         assert!(!LibraryScanner::is_supported_audio_extension("txt"));
     }
+
+    #[test]
+    fn test_fingerprint_tracks_size_and_mtime() {
+        let path =
+            std::env::temp_dir().join(format!("qbz-fingerprint-{}.flac", std::process::id()));
+        fs::write(&path, [0u8; 16]).unwrap();
+        filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(1_000, 500)).unwrap();
+
+        let fingerprint = FileFingerprint::of(&path).unwrap();
+        assert_eq!(fingerprint.size_bytes, 16);
+        assert_eq!(fingerprint.mtime_ns, 1_000_000_000_500);
+        assert_eq!(FileFingerprint::of(&path), Some(fingerprint));
+
+        // Same size, touched a moment later
+        filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(1_000, 900)).unwrap();
+        assert_ne!(FileFingerprint::of(&path), Some(fingerprint));
+
+        let _ = fs::remove_file(&path);
+        assert_eq!(FileFingerprint::of(&path), None);
+    }
 }
@@ -0,0 +1,380 @@
+//! Writing album metadata into audio file tags
+//!
+//! Used when album edits are written to the files instead of a sidecar. The
+//! full tag set is written (album artist, composer, track/disc totals, ISRC,
+//! genre, release date, catalog number) and the album cover can be embedded,
+//! either as is or scaled down to a size cap. Each file is read back after
+//! saving to check that the tags round-trip.
+
+use std::io::Cursor;
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::{ImageFormat, ImageReader};
+use lofty::{Accessor, AudioFile, ItemKey, MimeType, Picture, PictureType, Tag, TaggedFileExt};
+
+/// JPEG quality used when a cover has to be scaled down
+const COVER_JPEG_QUALITY: u8 = 90;
+
+/// Album-wide tag values
+#[derive(Debug, Clone, Default)]
+pub struct AlbumTagValues {
+    pub title: String,
+    pub album_artist: String,
+    pub year: Option<u32>,
+    /// Full release date ("YYYY-MM-DD"); takes precedence over `year`
+    pub release_date: Option<String>,
+    pub genre: Option<String>,
+    pub catalog_number: Option<String>,
+}
+
+/// Per-file tag values
+#[derive(Debug, Clone, Default)]
+pub struct TrackTagValues {
+    pub title: String,
+    /// Track artist; the album artist is used when missing
+    pub artist: Option<String>,
+    pub track_number: Option<u32>,
+    pub track_total: Option<u32>,
+    pub disc_number: Option<u32>,
+    pub disc_total: Option<u32>,
+    /// Left untouched in the file when missing
+    pub composer: Option<String>,
+    /// Left untouched in the file when missing
+    pub isrc: Option<String>,
+}
+
+/// Load a cover for embedding, scaled down to `max_size` pixels if larger
+pub fn load_cover(path: &Path, max_size: Option<u32>) -> Result<Picture, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read cover art: {}", e))?;
+    let format =
+        image::guess_format(&data).map_err(|e| format!("Unknown cover art format: {}", e))?;
+
+    let (data, mime) = match max_size {
+        Some(max_size) => {
+            let img = ImageReader::with_format(Cursor::new(&data), format)
+                .decode()
+                .map_err(|e| format!("Failed to decode cover art: {}", e))?;
+            if img.width() > max_size || img.height() > max_size {
+                let scaled = img
+                    .resize(max_size, max_size, FilterType::Lanczos3)
+                    .into_rgb8();
+                let mut out = Vec::new();
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, COVER_JPEG_QUALITY)
+                    .encode_image(&scaled)
+                    .map_err(|e| format!("Failed to encode cover art: {}", e))?;
+                (out, MimeType::Jpeg)
+            } else {
+                (data, cover_mime(format))
+            }
+        }
+        None => (data, cover_mime(format)),
+    };
+
+    Ok(Picture::new_unchecked(
+        PictureType::CoverFront,
+        Some(mime),
+        None,
+        data,
+    ))
+}
+
+fn cover_mime(format: ImageFormat) -> MimeType {
+    match format {
+        ImageFormat::Png => MimeType::Png,
+        ImageFormat::Gif => MimeType::Gif,
+        ImageFormat::Bmp => MimeType::Bmp,
+        ImageFormat::Tiff => MimeType::Tiff,
+        _ => MimeType::Jpeg,
+    }
+}
+
+/// Write the tags (and cover, if given) to an audio file
+pub fn write_track_tags(
+    path: &Path,
+    album: &AlbumTagValues,
+    track: &TrackTagValues,
+    cover: Option<&Picture>,
+) -> Result<(), String> {
+    let mut tagged_file =
+        lofty::read_from_path(path).map_err(|_| "Failed to read audio file tags.".to_string())?;
+
+    let primary_type = tagged_file.primary_tag_type();
+    if tagged_file.primary_tag_mut().is_none() && tagged_file.first_tag_mut().is_none() {
+        tagged_file.insert_tag(Tag::new(primary_type));
+    }
+
+    let tag = match tagged_file.primary_tag_mut() {
+        Some(tag) => tag,
+        None => tagged_file
+            .first_tag_mut()
+            .ok_or("Failed to access audio file tags.")?,
+    };
+
+    tag.set_title(track.title.trim().to_string());
+    tag.set_album(album.title.trim().to_string());
+    let artist = track
+        .artist
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .unwrap_or(album.album_artist.trim());
+    tag.set_artist(artist.to_string());
+
+    // Album artist (not part of Accessor).
+    set_or_remove(tag, ItemKey::AlbumArtist, Some(&album.album_artist));
+
+    if let Some(no) = track.track_number {
+        tag.set_track(no);
+    }
+    if let Some(disc) = track.disc_number {
+        tag.set_disk(disc);
+    }
+    match track.track_total {
+        Some(total) => tag.set_track_total(total),
+        None => tag.remove_track_total(),
+    }
+    match track.disc_total {
+        Some(total) => tag.set_disk_total(total),
+        None => tag.remove_disk_total(),
+    }
+
+    // Release date / Year
+    match (release_date(album), album.year) {
+        (Some(date), _) => {
+            tag.remove_year();
+            tag.insert_text(ItemKey::RecordingDate, date.to_string());
+        }
+        (None, Some(year)) => tag.set_year(year),
+        (None, None) => tag.remove_year(),
+    }
+
+    match album
+        .genre
+        .as_deref()
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+    {
+        Some(genre) => tag.set_genre(genre.to_string()),
+        None => tag.remove_genre(),
+    }
+    set_or_remove(tag, ItemKey::CatalogNumber, album.catalog_number.as_ref());
+
+    if let Some(composer) = &track.composer {
+        set_or_remove(tag, ItemKey::Composer, Some(composer));
+    }
+    if let Some(isrc) = &track.isrc {
+        set_or_remove(tag, ItemKey::Isrc, Some(isrc));
+    }
+
+    if let Some(cover) = cover {
+        tag.remove_picture_type(PictureType::CoverFront);
+        tag.push_picture(cover.clone());
+    }
+
+    tagged_file
+        .save_to_path(path)
+        .map_err(|_| "Failed to write tags to audio files. Check that the album folder is mounted read-write and you have permissions.".to_string())
+}
+
+/// Read the file back and check the written values; returns the fields that differ
+pub fn verify_track_tags(
+    path: &Path,
+    album: &AlbumTagValues,
+    track: &TrackTagValues,
+    expect_cover: bool,
+) -> Result<Vec<&'static str>, String> {
+    let tagged_file =
+        lofty::read_from_path(path).map_err(|_| "Failed to read audio file tags.".to_string())?;
+    let tag = tagged_file
+        .primary_tag()
+        .or_else(|| tagged_file.first_tag())
+        .ok_or("Written tags are missing.")?;
+
+    let text = |key: &ItemKey| tag.get_string(key).map(str::to_string);
+    let trimmed = |value: Option<&String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let mut mismatches = Vec::new();
+    let mut check = |field: &'static str, matches: bool| {
+        if !matches {
+            mismatches.push(field);
+        }
+    };
+
+    check("title", tag.title().as_deref() == Some(track.title.trim()));
+    check("album", tag.album().as_deref() == Some(album.title.trim()));
+    check(
+        "album artist",
+        text(&ItemKey::AlbumArtist) == trimmed(Some(&album.album_artist)),
+    );
+    if track.track_number.is_some() {
+        check("track number", tag.track() == track.track_number);
+    }
+    if track.disc_number.is_some() {
+        check("disc number", tag.disk() == track.disc_number);
+    }
+    check("track total", tag.track_total() == track.track_total);
+    check("disc total", tag.disk_total() == track.disc_total);
+    match release_date(album) {
+        Some(date) => check(
+            "release date",
+            text(&ItemKey::RecordingDate).as_deref() == Some(date),
+        ),
+        None => check("year", tag.year() == album.year),
+    }
+    check(
+        "genre",
+        tag.genre().map(|g| g.to_string()) == trimmed(album.genre.as_ref()),
+    );
+    check(
+        "catalog number",
+        text(&ItemKey::CatalogNumber) == trimmed(album.catalog_number.as_ref()),
+    );
+    if let Some(composer) = &track.composer {
+        check(
+            "composer",
+            text(&ItemKey::Composer) == trimmed(Some(composer)),
+        );
+    }
+    if let Some(isrc) = &track.isrc {
+        check("ISRC", text(&ItemKey::Isrc) == trimmed(Some(isrc)));
+    }
+    if expect_cover {
+        check(
+            "cover art",
+            tag.pictures()
+                .iter()
+                .any(|p| p.pic_type() == PictureType::CoverFront),
+        );
+    }
+
+    Ok(mismatches)
+}
+
+fn release_date(album: &AlbumTagValues) -> Option<&str> {
+    album.release_date.as_deref().map(str::trim).filter(|d| {
+        d.get(..4)
+            .is_some_and(|y| y.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+fn set_or_remove(tag: &mut Tag, key: ItemKey, value: Option<&String>) {
+    match value.map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        Some(value) => {
+            tag.insert_text(key, value.to_string());
+        }
+        None => tag.remove_key(&key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Smallest FLAC lofty can tag: STREAMINFO, PADDING and no audio frames
+    fn write_empty_flac(path: &Path) {
+        let mut data = b"fLaC".to_vec();
+        // Metadata block type 0 (STREAMINFO), 34 bytes
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x22]);
+        // Block sizes 4096, frame sizes unknown
+        data.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+        // 44.1 kHz, 2 channels, 16 bits, no samples
+        data.extend_from_slice(&[0x0A, 0xC4, 0x42, 0xF0, 0, 0, 0, 0]);
+        data.extend_from_slice(&[0; 16]);
+        // Last metadata block, type 1 (PADDING), 16 bytes
+        data.extend_from_slice(&[0x81, 0x00, 0x00, 0x10]);
+        data.extend_from_slice(&[0; 16]);
+        std::fs::write(path, data).unwrap();
+    }
+
+    fn write_cover(path: &Path, size: u32) {
+        image::RgbImage::from_pixel(size, size, image::Rgb([200, 40, 40]))
+            .save_with_format(path, ImageFormat::Png)
+            .unwrap();
+    }
+
+    fn values() -> (AlbumTagValues, TrackTagValues) {
+        let album = AlbumTagValues {
+            title: "Kind of Blue".to_string(),
+            album_artist: "Miles Davis".to_string(),
+            year: Some(1959),
+            release_date: Some("1959-08-17".to_string()),
+            genre: Some("Jazz".to_string()),
+            catalog_number: Some("CS 8163".to_string()),
+        };
+        let track = TrackTagValues {
+            title: "So What".to_string(),
+            artist: None,
+            track_number: Some(1),
+            track_total: Some(5),
+            disc_number: Some(1),
+            disc_total: Some(1),
+            composer: Some("Miles Davis".to_string()),
+            isrc: Some("USSM15900113".to_string()),
+        };
+        (album, track)
+    }
+
+    #[test]
+    fn full_tag_set_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("01.flac");
+        let cover_path = dir.path().join("cover.png");
+        write_empty_flac(&file);
+        write_cover(&cover_path, 64);
+
+        let (album, track) = values();
+        let cover = load_cover(&cover_path, None).unwrap();
+        write_track_tags(&file, &album, &track, Some(&cover)).unwrap();
+        assert!(verify_track_tags(&file, &album, &track, true)
+            .unwrap()
+            .is_empty());
+
+        let tagged = lofty::read_from_path(&file).unwrap();
+        let tag = tagged.primary_tag().unwrap();
+        assert_eq!(tag.artist().as_deref(), Some("Miles Davis"));
+        assert_eq!(tag.pictures().len(), 1);
+        assert_eq!(tag.pictures()[0].mime_type(), Some(&MimeType::Png));
+    }
+
+    #[test]
+    fn verify_reports_mismatches() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("01.flac");
+        write_empty_flac(&file);
+
+        let (album, track) = values();
+        write_track_tags(&file, &album, &track, None).unwrap();
+        let other = TrackTagValues {
+            title: "Freddie Freeloader".to_string(),
+            ..track
+        };
+        assert_eq!(
+            verify_track_tags(&file, &album, &other, true).unwrap(),
+            vec!["title", "cover art"]
+        );
+    }
+
+    #[test]
+    fn cover_is_scaled_to_the_size_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let cover_path = dir.path().join("cover.png");
+        write_cover(&cover_path, 1200);
+
+        let original = load_cover(&cover_path, None).unwrap();
+        assert_eq!(original.mime_type(), Some(&MimeType::Png));
+
+        let capped = load_cover(&cover_path, Some(500)).unwrap();
+        assert_eq!(capped.mime_type(), Some(&MimeType::Jpeg));
+        let img = image::load_from_memory(capped.data()).unwrap();
+        assert_eq!((img.width(), img.height()), (500, 500));
+
+        // Covers within the cap are embedded unchanged
+        let small = load_cover(&cover_path, Some(2000)).unwrap();
+        assert_eq!(small.data(), original.data());
+    }
+}
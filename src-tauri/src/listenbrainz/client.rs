@@ -11,6 +11,36 @@ use super::models::*;
 /// ListenBrainz API base URL
 const LISTENBRAINZ_API_URL: &str = "https://api.listenbrainz.org/1";
 
+/// Listens per import request when flushing the offline queue
+pub const IMPORT_BATCH_SIZE: usize = 50;
+
+/// Why a submission failed
+#[derive(Debug, Clone)]
+pub enum SubmitError {
+    /// ListenBrainz refused the payload; sending it again unchanged will fail again
+    Rejected(String),
+    /// Network problem, rate limit, server error or no usable token; retry later
+    Unavailable(String),
+}
+
+impl std::fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rejected(msg) | Self::Unavailable(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Fill in the QBZ player/client identifiers
+fn with_client_identifiers(mut info: AdditionalInfo) -> AdditionalInfo {
+    let version = env!("CARGO_PKG_VERSION").to_string();
+    info.media_player = "QBZ".to_string();
+    info.media_player_version = version.clone();
+    info.submission_client = "QBZ".to_string();
+    info.submission_client_version = version;
+    info
+}
+
 /// ListenBrainz client configuration
 #[derive(Debug, Clone)]
 pub struct ListenBrainzConfig {
@@ -184,13 +214,8 @@ impl ListenBrainzClient {
 
         let token = token.ok_or("Not authenticated with ListenBrainz")?;
 
-        let mut info = additional_info.unwrap_or_default();
         // Ensure QBZ identifiers are set
-        let version = env!("CARGO_PKG_VERSION").to_string();
-        info.media_player = "QBZ".to_string();
-        info.media_player_version = version.clone();
-        info.submission_client = "QBZ".to_string();
-        info.submission_client_version = version;
+        let info = with_client_identifiers(additional_info.unwrap_or_default());
 
         let payload = SubmitListensPayload {
             listen_type: ListenType::PlayingNow,
@@ -227,13 +252,8 @@ impl ListenBrainzClient {
 
         let token = token.ok_or("Not authenticated with ListenBrainz")?;
 
-        let mut info = additional_info.unwrap_or_default();
         // Ensure QBZ identifiers are set
-        let version = env!("CARGO_PKG_VERSION").to_string();
-        info.media_player = "QBZ".to_string();
-        info.media_player_version = version.clone();
-        info.submission_client = "QBZ".to_string();
-        info.submission_client_version = version;
+        let info = with_client_identifiers(additional_info.unwrap_or_default());
 
         let payload = SubmitListensPayload {
             listen_type: ListenType::Single,
@@ -251,8 +271,55 @@ impl ListenBrainzClient {
         self.submit_listens(&token, &payload).await
     }
 
+    /// Submit several queued listens in one request.
+    ///
+    /// ListenBrainz accepts or refuses the request as a whole.
+    pub async fn submit_import(&self, listens: &[QueuedListen]) -> Result<(), SubmitError> {
+        let token = {
+            let config = self.config.lock().await;
+            if !config.enabled {
+                return Err(SubmitError::Unavailable("ListenBrainz is disabled".to_string()));
+            }
+            config.token.clone()
+        };
+
+        let token = token
+            .ok_or_else(|| SubmitError::Unavailable("Not authenticated with ListenBrainz".to_string()))?;
+
+        let payload = SubmitListensPayload {
+            listen_type: ListenType::Import,
+            payload: listens
+                .iter()
+                .map(|listen| {
+                    let mut info = AdditionalInfo::new();
+                    info.recording_mbid = listen.recording_mbid.clone();
+                    info.release_mbid = listen.release_mbid.clone();
+                    info.artist_mbids = listen.artist_mbids.clone();
+                    info.isrc = listen.isrc.clone();
+                    info.duration_ms = listen.duration_ms;
+
+                    Listen {
+                        listened_at: Some(listen.listened_at),
+                        track_metadata: TrackMetadata {
+                            artist_name: listen.artist_name.clone(),
+                            track_name: listen.track_name.clone(),
+                            release_name: listen.release_name.clone(),
+                            additional_info: Some(with_client_identifiers(info)),
+                        },
+                    }
+                })
+                .collect(),
+        };
+
+        self.send_listens(&token, &payload).await
+    }
+
     /// Internal: Submit listens to API
     async fn submit_listens(&self, token: &str, payload: &SubmitListensPayload) -> Result<(), String> {
+        self.send_listens(token, payload).await.map_err(|e| e.to_string())
+    }
+
+    async fn send_listens(&self, token: &str, payload: &SubmitListensPayload) -> Result<(), SubmitError> {
         let url = format!("{}/submit-listens", LISTENBRAINZ_API_URL);
 
         let response = self
@@ -263,12 +330,13 @@ impl ListenBrainzClient {
             .json(payload)
             .send()
             .await
-            .map_err(|e| format!("Failed to submit listen: {}", e))?;
+            .map_err(|e| SubmitError::Unavailable(format!("Failed to submit listen: {}", e)))?;
 
         if response.status().is_success() {
             let listen_type = match payload.listen_type {
                 ListenType::PlayingNow => "now playing",
                 ListenType::Single => "scrobble",
+                ListenType::Import => "import",
             };
             if let Some(listen) = payload.payload.first() {
                 log::debug!(
@@ -282,7 +350,16 @@ impl ListenBrainzClient {
         } else {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            Err(format!("ListenBrainz submission failed: {} - {}", status, text))
+            let message = format!("ListenBrainz submission failed: {} - {}", status, text);
+            // 401 means the token went bad, which a later retry can recover from
+            if status.is_client_error()
+                && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+                && status != reqwest::StatusCode::UNAUTHORIZED
+            {
+                Err(SubmitError::Rejected(message))
+            } else {
+                Err(SubmitError::Unavailable(message))
+            }
         }
     }
 }
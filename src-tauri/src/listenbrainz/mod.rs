@@ -32,7 +32,7 @@ pub mod client;
 pub mod models;
 
 pub use cache::{ListenBrainzCache, ListenBrainzCacheState, QueueStats};
-pub use client::{ListenBrainzClient, ListenBrainzConfig};
+pub use client::{ListenBrainzClient, ListenBrainzConfig, SubmitError, IMPORT_BATCH_SIZE};
 pub use models::{
     AdditionalInfo, Listen, ListenBrainzStatus, ListenType, QueuedListen,
     SubmitListensPayload, TrackMetadata, UserInfo,
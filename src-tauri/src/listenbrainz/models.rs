@@ -12,6 +12,8 @@ pub enum ListenType {
     PlayingNow,
     /// Single scrobble
     Single,
+    /// Several past listens at once (offline queue flush)
+    Import,
 }
 
 /// A single listen submission
@@ -68,6 +68,8 @@ impl LyricsCacheDb {
                     synced_lrc: row.get(6)?,
                     provider: LyricsProvider::from_str(&row.get::<_, String>(7)?),
                     cached: true,
+                    lines: Vec::new(),
+                    word_synced: false,
                 })
             },
         );
@@ -95,6 +97,8 @@ impl LyricsCacheDb {
                     synced_lrc: row.get(6)?,
                     provider: LyricsProvider::from_str(&row.get::<_, String>(7)?),
                     cached: true,
+                    lines: Vec::new(),
+                    word_synced: false,
                 })
             },
         );
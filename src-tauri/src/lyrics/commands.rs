@@ -41,7 +41,7 @@ pub async fn lyrics_get(
         if let Some(payload) = cached {
             let has_synced = payload.synced_lrc.as_ref().map(|s| !s.trim().is_empty()).unwrap_or(false);
             if has_synced {
-                return Ok(Some(payload));
+                return Ok(Some(payload.with_timings()));
             }
             // plain-only cache: fall through to re-fetch for synced
         }
@@ -74,12 +74,14 @@ pub async fn lyrics_get(
             synced_lrc: data.synced_lrc,
             provider: data.provider,
             cached: false,
+            lines: Vec::new(),
+            word_synced: false,
         };
 
         let db_opt__ = state.db.lock().await;
         let db = db_opt__.as_ref().ok_or("No active session - please log in")?;
         db.upsert(&cache_key, &payload)?;
-        return Ok(Some(payload));
+        return Ok(Some(payload.with_timings()));
     }
 
     if let Some(data) = fetch_lyrics_ovh(title_trimmed, artist_trimmed).await {
@@ -93,12 +95,14 @@ pub async fn lyrics_get(
             synced_lrc: data.synced_lrc,
             provider: data.provider,
             cached: false,
+            lines: Vec::new(),
+            word_synced: false,
         };
 
         let db_opt__ = state.db.lock().await;
         let db = db_opt__.as_ref().ok_or("No active session - please log in")?;
         db.upsert(&cache_key, &payload)?;
-        return Ok(Some(payload));
+        return Ok(Some(payload.with_timings()));
     }
 
     Ok(None)
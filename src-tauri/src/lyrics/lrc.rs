@@ -0,0 +1,176 @@
+//! Minimal LRC parsing, matching the lyrics view (`parseLRC` in lyricsStore)
+//!
+//! Supports `[mm:ss.xx]`, `[mm:ss.xxx]` and `[mm:ss]`; lines without text are
+//! dropped. Enhanced LRC word tags (`<mm:ss.xx>` before each word) are parsed
+//! into per-word timings and stripped from the line text.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+static LRC_LINE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[(\d{1,2}):(\d{2})(?:[.:](\d{2,3}))?\](.*)").unwrap());
+
+static LRC_WORD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(\d{1,2}):(\d{2})(?:[.:](\d{2,3}))?>").unwrap());
+
+/// A timed word within a line (enhanced LRC)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LrcWord {
+    pub time_ms: u64,
+    /// Word text including its trailing space, so words concatenate to the line
+    pub text: String,
+}
+
+/// A timed lyric line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LrcLine {
+    pub time_ms: u64,
+    pub text: String,
+    /// Per-word timings; empty when the lyrics are only line-synced
+    #[serde(default)]
+    pub words: Vec<LrcWord>,
+}
+
+fn timestamp_ms(minutes: &str, seconds: &str, fraction: Option<&str>) -> Option<u64> {
+    let minutes: u64 = minutes.parse().ok()?;
+    let seconds: u64 = seconds.parse().ok()?;
+    let millis: u64 = fraction
+        .map(|f| format!("{:0<3}", f).parse().unwrap_or(0))
+        .unwrap_or(0);
+    Some((minutes * 60 + seconds) * 1000 + millis)
+}
+
+/// Split a line's text on word tags. Text before the first tag starts at the
+/// line time. Returns the untagged text and the words (empty if untagged).
+fn parse_words(line_time_ms: u64, raw: &str) -> (String, Vec<LrcWord>) {
+    let mut words = Vec::new();
+    let mut time_ms = line_time_ms;
+    let mut start = 0;
+
+    for caps in LRC_WORD_RE.captures_iter(raw) {
+        let tag = caps.get(0).expect("whole match");
+        push_word(&mut words, time_ms, &raw[start..tag.start()]);
+        time_ms =
+            timestamp_ms(&caps[1], &caps[2], caps.get(3).map(|m| m.as_str())).unwrap_or(time_ms);
+        start = tag.end();
+    }
+    if start == 0 {
+        return (raw.trim().to_string(), Vec::new());
+    }
+    push_word(&mut words, time_ms, &raw[start..]);
+
+    if let Some(last) = words.last_mut() {
+        last.text = last.text.trim_end().to_string();
+    }
+    let text = words.iter().map(|w| w.text.as_str()).collect::<String>();
+    (text, words)
+}
+
+fn push_word(words: &mut Vec<LrcWord>, time_ms: u64, text: &str) {
+    // Leading whitespace belongs to the previous word's trailing space
+    let text = if words.is_empty() {
+        text.trim_start()
+    } else {
+        text
+    };
+    if text.trim().is_empty() {
+        if let Some(last) = words.last_mut() {
+            last.text.push_str(text);
+        }
+        return;
+    }
+    words.push(LrcWord {
+        time_ms,
+        text: text.to_string(),
+    });
+}
+
+/// Parse synced lyrics into lines sorted by time
+pub fn parse_lrc(lrc: &str) -> Vec<LrcLine> {
+    let mut lines: Vec<LrcLine> = LRC_LINE_RE
+        .captures_iter(lrc)
+        .filter_map(|caps| {
+            let time_ms = timestamp_ms(&caps[1], &caps[2], caps.get(3).map(|m| m.as_str()))?;
+            let (text, words) = parse_words(time_ms, &caps[4]);
+            if text.is_empty() {
+                return None;
+            }
+            Some(LrcLine {
+                time_ms,
+                text,
+                words,
+            })
+        })
+        .collect();
+    lines.sort_by_key(|line| line.time_ms);
+    lines
+}
+
+/// The line being sung at `position_ms`, if any has started yet
+pub fn line_at(lines: &[LrcLine], position_ms: u64) -> Option<&LrcLine> {
+    let started = lines.partition_point(|line| line.time_ms <= position_ms);
+    started.checked_sub(1).map(|index| &lines[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LRC: &str =
+        "[ar: Someone]\n[00:12.50]First line\n[00:05.00]Intro\n[00:20]\n[01:02.345]Later";
+
+    #[test]
+    fn test_parse_lrc_sorts_and_skips_empty_lines() {
+        let lines = parse_lrc(LRC);
+        let times: Vec<u64> = lines.iter().map(|l| l.time_ms).collect();
+        assert_eq!(times, vec![5_000, 12_500, 62_345]);
+        assert_eq!(lines[0].text, "Intro");
+    }
+
+    #[test]
+    fn test_parse_enhanced_lrc_word_timings() {
+        let lines = parse_lrc(
+            "[00:10.00]<00:10.00>Hello <00:10.50>big <00:11.20>world\n[00:15.00]Plain line",
+        );
+        assert_eq!(lines[0].text, "Hello big world");
+        let words: Vec<(u64, &str)> = lines[0]
+            .words
+            .iter()
+            .map(|w| (w.time_ms, w.text.as_str()))
+            .collect();
+        assert_eq!(
+            words,
+            vec![(10_000, "Hello "), (10_500, "big "), (11_200, "world")]
+        );
+        assert_eq!(lines[1].text, "Plain line");
+        assert!(lines[1].words.is_empty());
+
+        // Text before the first tag starts with the line; empty tagged lines are dropped
+        let lines = parse_lrc("[00:01.00]Oh <00:01.40>yeah\n[00:02.00]<00:02.00>  ");
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].words[0].time_ms, 1_000);
+        assert_eq!(lines[0].words[1].time_ms, 1_400);
+    }
+
+    #[test]
+    fn test_line_at_position() {
+        let lines = parse_lrc(LRC);
+        assert_eq!(line_at(&lines, 1_000), None);
+        assert_eq!(
+            line_at(&lines, 5_000).map(|l| l.text.as_str()),
+            Some("Intro")
+        );
+        assert_eq!(
+            line_at(&lines, 30_000).map(|l| l.text.as_str()),
+            Some("First line")
+        );
+        assert_eq!(
+            line_at(&lines, 90_000).map(|l| l.text.as_str()),
+            Some("Later")
+        );
+    }
+}
@@ -4,6 +4,7 @@
 
 pub mod cache;
 pub mod commands;
+pub mod lrc;
 pub mod providers;
 
 use serde::{Deserialize, Serialize};
@@ -25,6 +26,26 @@ pub struct LyricsPayload {
     pub synced_lrc: Option<String>,
     pub provider: LyricsProvider,
     pub cached: bool,
+    /// `synced_lrc` parsed into lines, with per-word timings when the
+    /// provider supplied enhanced LRC. Derived, not stored in the cache.
+    #[serde(default)]
+    pub lines: Vec<lrc::LrcLine>,
+    /// True when at least one line carries word timings
+    #[serde(default)]
+    pub word_synced: bool,
+}
+
+impl LyricsPayload {
+    /// Fill `lines` and `word_synced` from `synced_lrc`
+    pub fn with_timings(mut self) -> Self {
+        self.lines = self
+            .synced_lrc
+            .as_deref()
+            .map(lrc::parse_lrc)
+            .unwrap_or_default();
+        self.word_synced = self.lines.iter().any(|line| !line.words.is_empty());
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -0,0 +1,525 @@
+//! Global media keys
+//!
+//! On some Wayland compositors (Sway, Hyprland, ...) MPRIS works but nothing
+//! forwards the hardware Play/Pause/Next/Previous keys to it. When enabled,
+//! QBZ binds the keys itself, trying in order:
+//!
+//! 1. The XDG GlobalShortcuts portal (`org.freedesktop.portal.GlobalShortcuts`).
+//!    Needs xdg-desktop-portal 1.17+ with a backend implementing it: KDE Plasma
+//!    5.27+, GNOME 48+, or xdg-desktop-portal-hyprland. The compositor may ask
+//!    once to confirm the shortcuts and lets users rebind them in its settings.
+//! 2. An X11 key grab on the root window. Only useful in X11 sessions: under
+//!    XWayland the compositor doesn't send keys to unfocused X11 clients.
+//!
+//! Either way only one application can own a key, so this is opt-in to avoid
+//! fighting other media players (or the desktop's own media-key handling).
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use x11rb::connection::Connection as _;
+use x11rb::protocol::xproto::{ConnectionExt as _, GrabMode, Keycode, ModMask, Window};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+use super::{GlobalKeysBackend, GlobalMediaKeysStatus};
+
+/// Hardware media key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKey {
+    Play,
+    Pause,
+    Stop,
+    Next,
+    Previous,
+}
+
+impl MediaKey {
+    const ALL: [MediaKey; 5] = [
+        Self::Play,
+        Self::Pause,
+        Self::Stop,
+        Self::Next,
+        Self::Previous,
+    ];
+
+    /// Shortcut ID registered with the portal
+    fn id(self) -> &'static str {
+        match self {
+            Self::Play => "play-pause",
+            Self::Pause => "pause",
+            Self::Stop => "stop",
+            Self::Next => "next",
+            Self::Previous => "previous",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|key| key.id() == id)
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Play => "Play/Pause",
+            Self::Pause => "Pause",
+            Self::Stop => "Stop",
+            Self::Next => "Next track",
+            Self::Previous => "Previous track",
+        }
+    }
+
+    /// XF86 keysym, also used as the portal's preferred trigger
+    fn keysym(self) -> (u32, &'static str) {
+        match self {
+            Self::Play => (0x1008_FF14, "XF86AudioPlay"),
+            Self::Pause => (0x1008_FF31, "XF86AudioPause"),
+            Self::Stop => (0x1008_FF15, "XF86AudioStop"),
+            Self::Next => (0x1008_FF17, "XF86AudioNext"),
+            Self::Previous => (0x1008_FF16, "XF86AudioPrev"),
+        }
+    }
+
+    /// Matching `media:control` action (the Play key toggles, like MPRIS PlayPause)
+    pub fn action(self) -> &'static str {
+        match self {
+            Self::Play => "toggle",
+            Self::Pause => "pause",
+            Self::Stop => "stop",
+            Self::Next => "next",
+            Self::Previous => "previous",
+        }
+    }
+}
+
+pub type KeyHandler = Arc<dyn Fn(MediaKey) + Send + Sync>;
+
+enum Grabber {
+    Portal(PortalShortcuts),
+    X11(X11Grab),
+}
+
+impl Grabber {
+    fn connect(enabled: Arc<AtomicBool>, on_key: KeyHandler) -> Result<Self, String> {
+        let portal_error = match PortalShortcuts::connect(enabled.clone(), on_key.clone()) {
+            Ok(portal) => return Ok(Self::Portal(portal)),
+            Err(e) => e,
+        };
+        match X11Grab::connect(enabled, on_key) {
+            Ok(x11) => Ok(Self::X11(x11)),
+            Err(x11_error) => Err(format!(
+                "GlobalShortcuts portal: {}; X11: {}",
+                portal_error, x11_error
+            )),
+        }
+    }
+
+    fn backend(&self) -> GlobalKeysBackend {
+        match self {
+            Self::Portal(_) => GlobalKeysBackend::Portal,
+            Self::X11(_) => GlobalKeysBackend::X11,
+        }
+    }
+
+    fn grab(&self) -> Result<(), String> {
+        match self {
+            Self::Portal(portal) => portal.bind(),
+            Self::X11(x11) => x11.grab(),
+        }
+    }
+
+    fn release(&self) {
+        match self {
+            Self::Portal(portal) => portal.close(),
+            Self::X11(x11) => x11.ungrab(),
+        }
+    }
+}
+
+/// Opt-in global media key handling.
+///
+/// Binding and releasing happen in order on one worker thread, which owns
+/// the backend; the backend's listener thread lives for the rest of the
+/// process. Turning the feature off releases the keys and stops forwarding
+/// them.
+pub struct GlobalMediaKeys {
+    enabled: Arc<AtomicBool>,
+    requests: mpsc::Sender<(bool, KeyHandler)>,
+    status: Arc<Mutex<GlobalMediaKeysStatus>>,
+}
+
+impl GlobalMediaKeys {
+    pub fn new() -> Self {
+        let enabled = Arc::new(AtomicBool::new(false));
+        let status = Arc::new(Mutex::new(GlobalMediaKeysStatus::default()));
+        let (requests, receiver) = mpsc::channel();
+
+        let worker_enabled = enabled.clone();
+        let worker_status = status.clone();
+        thread::spawn(move || run_worker(receiver, worker_enabled, worker_status));
+
+        Self {
+            enabled,
+            requests,
+            status,
+        }
+    }
+
+    /// Bind or release the keys. Done on the worker thread since the portal
+    /// may wait for the user to confirm the shortcuts.
+    pub fn set_enabled(&self, enabled: bool, on_key: KeyHandler) {
+        // Stop forwarding right away, even while an earlier bind is pending
+        if !enabled {
+            self.enabled.store(false, Ordering::SeqCst);
+        }
+        if self.requests.send((enabled, on_key)).is_err() {
+            log::warn!("Global media keys worker is gone");
+        }
+    }
+
+    pub fn status(&self) -> GlobalMediaKeysStatus {
+        self.status
+            .lock()
+            .map(|status| status.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Apply enable/disable requests in order. Requests that queued up while
+/// the portal was busy collapse into the latest one.
+fn run_worker(
+    requests: mpsc::Receiver<(bool, KeyHandler)>,
+    enabled_flag: Arc<AtomicBool>,
+    status: Arc<Mutex<GlobalMediaKeysStatus>>,
+) {
+    let mut grabber: Option<Grabber> = None;
+
+    while let Ok(mut request) = requests.recv() {
+        while let Ok(newer) = requests.try_recv() {
+            request = newer;
+        }
+        let (enabled, on_key) = request;
+        let new_status = apply(&mut grabber, enabled, on_key, &enabled_flag);
+        if let Ok(mut status) = status.lock() {
+            *status = new_status;
+        }
+    }
+}
+
+fn apply(
+    grabber: &mut Option<Grabber>,
+    enabled: bool,
+    on_key: KeyHandler,
+    enabled_flag: &Arc<AtomicBool>,
+) -> GlobalMediaKeysStatus {
+    enabled_flag.store(enabled, Ordering::SeqCst);
+
+    if !enabled {
+        if let Some(active) = grabber.as_ref() {
+            active.release();
+        }
+        log::info!("Global media keys released");
+        return GlobalMediaKeysStatus::default();
+    }
+
+    if grabber.is_none() {
+        match Grabber::connect(enabled_flag.clone(), on_key) {
+            Ok(connected) => *grabber = Some(connected),
+            Err(e) => {
+                log::warn!("Global media keys unavailable: {}", e);
+                return GlobalMediaKeysStatus {
+                    enabled,
+                    backend: None,
+                    error: Some(e),
+                };
+            }
+        }
+    }
+
+    let Some(active) = grabber.as_ref() else {
+        return GlobalMediaKeysStatus::default();
+    };
+    let result = active.grab();
+    match &result {
+        Ok(()) => log::info!("Global media keys bound via {:?}", active.backend()),
+        Err(e) => log::warn!("Failed to bind global media keys: {}", e),
+    }
+    GlobalMediaKeysStatus {
+        enabled,
+        backend: result.is_ok().then(|| active.backend()),
+        error: result.err(),
+    }
+}
+
+impl Default for GlobalMediaKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// XDG GlobalShortcuts portal
+
+const PORTAL_DEST: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SHORTCUTS_IFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+
+struct PortalShortcuts {
+    conn: Connection,
+    session: Mutex<Option<OwnedObjectPath>>,
+    token: AtomicU32,
+}
+
+impl PortalShortcuts {
+    fn connect(enabled: Arc<AtomicBool>, on_key: KeyHandler) -> Result<Self, String> {
+        let conn = Connection::session().map_err(|e| e.to_string())?;
+        let proxy = Proxy::new(&conn, PORTAL_DEST, PORTAL_PATH, SHORTCUTS_IFACE)
+            .map_err(|e| e.to_string())?;
+        // Fails when the portal (or its backend) doesn't implement GlobalShortcuts
+        let version: u32 = proxy
+            .get_property("version")
+            .map_err(|e| format!("not available ({})", e))?;
+        log::debug!("GlobalShortcuts portal version {}", version);
+
+        let activated = proxy
+            .receive_signal("Activated")
+            .map_err(|e| e.to_string())?;
+        thread::spawn(move || {
+            for message in activated {
+                let Ok((_session, shortcut_id, _timestamp, _options)) = message
+                    .body()
+                    .deserialize::<(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>)>()
+                else {
+                    continue;
+                };
+                if !enabled.load(Ordering::SeqCst) {
+                    continue;
+                }
+                if let Some(key) = MediaKey::from_id(&shortcut_id) {
+                    on_key(key);
+                }
+            }
+        });
+
+        Ok(Self {
+            conn,
+            session: Mutex::new(None),
+            token: AtomicU32::new(0),
+        })
+    }
+
+    /// Create a session (first time) and bind the media key shortcuts to it
+    fn bind(&self) -> Result<(), String> {
+        let mut session = self.session.lock().map_err(|e| e.to_string())?;
+        if session.is_none() {
+            let token = self.next_token();
+            let mut options: HashMap<&str, Value> = HashMap::new();
+            options.insert("handle_token", Value::from(token.as_str()));
+            options.insert("session_handle_token", Value::from("qbz_media_keys"));
+            let results = self.request("CreateSession", &token, &(options,))?;
+            let handle = match results.get("session_handle").map(|value| &**value) {
+                Some(Value::Str(handle)) => handle.to_string(),
+                Some(Value::ObjectPath(handle)) => handle.to_string(),
+                _ => return Err("portal returned no session handle".to_string()),
+            };
+            *session = Some(OwnedObjectPath::try_from(handle).map_err(|e| e.to_string())?);
+        }
+        let Some(handle) = session.clone() else {
+            return Ok(());
+        };
+
+        let shortcuts: Vec<(&str, HashMap<&str, Value>)> = MediaKey::ALL
+            .into_iter()
+            .map(|key| {
+                let mut props: HashMap<&str, Value> = HashMap::new();
+                props.insert("description", Value::from(key.description()));
+                props.insert("preferred_trigger", Value::from(key.keysym().1));
+                (key.id(), props)
+            })
+            .collect();
+        let token = self.next_token();
+        let mut options: HashMap<&str, Value> = HashMap::new();
+        options.insert("handle_token", Value::from(token.as_str()));
+        self.request("BindShortcuts", &token, &(handle, shortcuts, "", options))
+            .map(|_| ())
+    }
+
+    /// Close the session, which unbinds its shortcuts
+    fn close(&self) {
+        let Ok(mut session) = self.session.lock() else {
+            return;
+        };
+        if let Some(handle) = session.take() {
+            let closed = Proxy::new(
+                &self.conn,
+                PORTAL_DEST,
+                handle,
+                "org.freedesktop.portal.Session",
+            )
+            .and_then(|proxy| proxy.call_method("Close", &()));
+            if let Err(e) = closed {
+                log::debug!("Failed to close GlobalShortcuts session: {}", e);
+            }
+        }
+    }
+
+    fn next_token(&self) -> String {
+        format!("qbz_{}", self.token.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Call a portal method that answers through a Request object and wait
+    /// for its Response. `token` is the `handle_token` passed in `body`.
+    fn request<B>(
+        &self,
+        method: &str,
+        token: &str,
+        body: &B,
+    ) -> Result<HashMap<String, OwnedValue>, String>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        // Subscribe before calling so the Response can't be missed
+        let sender = self
+            .conn
+            .unique_name()
+            .map(|name| name.trim_start_matches(':').replace('.', "_"))
+            .ok_or("no unique bus name")?;
+        let request_path = format!("{}/request/{}/{}", PORTAL_PATH, sender, token);
+        let request = Proxy::new(
+            &self.conn,
+            PORTAL_DEST,
+            ObjectPath::try_from(request_path).map_err(|e| e.to_string())?,
+            "org.freedesktop.portal.Request",
+        )
+        .map_err(|e| e.to_string())?;
+        let mut responses = request
+            .receive_signal("Response")
+            .map_err(|e| e.to_string())?;
+
+        let proxy = Proxy::new(&self.conn, PORTAL_DEST, PORTAL_PATH, SHORTCUTS_IFACE)
+            .map_err(|e| e.to_string())?;
+        proxy
+            .call_method(method, body)
+            .map_err(|e| format!("{} failed: {}", method, e))?;
+
+        let response = responses
+            .next()
+            .ok_or_else(|| format!("{}: no response from portal", method))?;
+        let (code, results) = response
+            .body()
+            .deserialize::<(u32, HashMap<String, OwnedValue>)>()
+            .map_err(|e| e.to_string())?;
+        match code {
+            0 => Ok(results),
+            1 => Err(format!("{} was cancelled", method)),
+            _ => Err(format!("{} failed", method)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// X11 key grab
+
+struct X11Grab {
+    conn: Arc<RustConnection>,
+    root: Window,
+    keycodes: Vec<(Keycode, MediaKey)>,
+}
+
+impl X11Grab {
+    fn connect(enabled: Arc<AtomicBool>, on_key: KeyHandler) -> Result<Self, String> {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            return Err("not an X11 session".to_string());
+        }
+        let (conn, screen) = x11rb::connect(None).map_err(|e| e.to_string())?;
+        let setup = conn.setup();
+        let root = setup.roots[screen].root;
+        let (min, max) = (setup.min_keycode, setup.max_keycode);
+
+        let mapping = conn
+            .get_keyboard_mapping(min, max - min + 1)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        let per_keycode = mapping.keysyms_per_keycode.max(1) as usize;
+        let keycodes: Vec<(Keycode, MediaKey)> = MediaKey::ALL
+            .into_iter()
+            .filter_map(|key| {
+                mapping
+                    .keysyms
+                    .chunks(per_keycode)
+                    .position(|syms| syms.contains(&key.keysym().0))
+                    .map(|offset| (min + offset as Keycode, key))
+            })
+            .collect();
+        if keycodes.is_empty() {
+            return Err("keyboard has no media keys".to_string());
+        }
+
+        let conn = Arc::new(conn);
+        let events = conn.clone();
+        let keys = keycodes.clone();
+        thread::spawn(move || {
+            while let Ok(event) = events.wait_for_event() {
+                let Event::KeyPress(press) = event else {
+                    continue;
+                };
+                if !enabled.load(Ordering::SeqCst) {
+                    continue;
+                }
+                if let Some((_, key)) = keys.iter().find(|(code, _)| *code == press.detail) {
+                    on_key(*key);
+                }
+            }
+        });
+
+        Ok(Self {
+            conn,
+            root,
+            keycodes,
+        })
+    }
+
+    fn grab(&self) -> Result<(), String> {
+        for (keycode, key) in &self.keycodes {
+            self.conn
+                .grab_key(
+                    false,
+                    self.root,
+                    ModMask::ANY,
+                    *keycode,
+                    GrabMode::ASYNC,
+                    GrabMode::ASYNC,
+                )
+                .map_err(|e| e.to_string())?
+                .check()
+                .map_err(|e| format!("{:?} is grabbed by another application ({:?})", key, e))?;
+        }
+        Ok(())
+    }
+
+    fn ungrab(&self) {
+        for (keycode, _) in &self.keycodes {
+            if let Ok(cookie) = self.conn.ungrab_key(*keycode, self.root, ModMask::ANY) {
+                let _ = cookie.check();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcut_ids_round_trip() {
+        for key in MediaKey::ALL {
+            assert_eq!(MediaKey::from_id(key.id()), Some(key));
+        }
+        assert_eq!(MediaKey::from_id("volume-up"), None);
+        assert_eq!(MediaKey::Play.action(), "toggle");
+    }
+}
@@ -2,9 +2,15 @@
 //!
 //! Provides system-level media control integration:
 //! - MPRIS on Linux (D-Bus based)
-//! - Media key support
+//! - Media key support, optionally grabbed globally (see `global_keys`)
 //! - Now playing notifications
 
+#[cfg(target_os = "linux")]
+mod global_keys;
+
+#[cfg(target_os = "linux")]
+pub use global_keys::{GlobalMediaKeys, MediaKey};
+
 use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, PlatformConfig, SeekDirection};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
@@ -24,10 +30,29 @@ pub struct TrackInfo {
     pub cover_url: Option<String>,
 }
 
+/// Backend holding the global media keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GlobalKeysBackend {
+    Portal,
+    X11,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GlobalMediaKeysStatus {
+    pub enabled: bool,
+    /// Backend currently holding the keys
+    pub backend: Option<GlobalKeysBackend>,
+    /// Why the keys couldn't be bound, if they couldn't
+    pub error: Option<String>,
+}
+
 /// Media controls manager
 pub struct MediaControlsManager {
     controls: Arc<Mutex<Option<MediaControls>>>,
     initialized: Arc<AtomicBool>,
+    #[cfg(target_os = "linux")]
+    global_keys: GlobalMediaKeys,
 }
 
 impl MediaControlsManager {
@@ -37,6 +62,8 @@ impl MediaControlsManager {
         Self {
             controls,
             initialized: Arc::new(AtomicBool::new(false)),
+            #[cfg(target_os = "linux")]
+            global_keys: GlobalMediaKeys::new(),
         }
     }
 
@@ -82,6 +109,39 @@ impl MediaControlsManager {
         });
     }
 
+    /// Grab (or release) the hardware media keys globally, for desktops that
+    /// don't forward them to MPRIS. Key presses go through the same
+    /// `media:control` event as MPRIS.
+    pub fn set_global_media_keys(&self, enabled: bool, app: AppHandle) {
+        #[cfg(target_os = "linux")]
+        self.global_keys.set_enabled(
+            enabled,
+            Arc::new(move |key: MediaKey| {
+                log::info!("Global media key: {:?}", key);
+                let payload = MediaControlPayload::action_only(key.action());
+                let _ = app.emit("media:control", &payload);
+            }),
+        );
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = app;
+            if enabled {
+                log::info!("Global media keys are only handled on Linux");
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn global_media_keys_status(&self) -> GlobalMediaKeysStatus {
+        self.global_keys.status()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn global_media_keys_status(&self) -> GlobalMediaKeysStatus {
+        GlobalMediaKeysStatus::default()
+    }
+
     /// Update the currently playing track metadata
     pub fn set_metadata(&self, track: &TrackInfo) {
         if let Ok(mut guard) = self.controls.lock() {
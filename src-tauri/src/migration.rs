@@ -1,14 +1,58 @@
-//! One-time migration of flat-layout databases to per-user directories
+//! One-time migration of flat-layout databases to per-user directories, and
+//! schema upgrades of the persisted session
 //!
 //! When upgrading from the global layout to per-user isolation, this module
 //! moves all existing DB files and cache directories into the first user's
-//! subdirectory. A `.migrated` marker prevents re-running.
+//! subdirectory. A `.migrated` marker prevents re-running. Databases are
+//! copied rather than moved and the originals kept until `confirm_migration_at`;
+//! `rollback_migration_at` restores the previous layout from the record the
+//! last migration left behind.
+//!
+//! The session database (queue and player state) records its layout in
+//! `PRAGMA user_version`; `migrate_session_db` upgrades older layouts step by
+//! step so a saved queue loads into the current `QueueTrack` shape.
 
 use std::path::{Path, PathBuf};
 
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
 /// Marker file that indicates migration has already been performed
 const MIGRATED_MARKER: &str = ".migrated";
 
+/// Record of the last migration, in the global data dir
+const MIGRATION_RECORD: &str = ".last-migration.json";
+
+/// How a migration relocates a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationAction {
+    /// Written to the new location; the original stays until confirmed
+    Copy,
+    /// Renamed into the new location
+    Move,
+}
+
+/// One file or directory a migration relocates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedMove {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub bytes: u64,
+    pub action: MigrationAction,
+}
+
+/// What the last migration did, kept until it is confirmed or rolled back
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    /// "flat_to_user" or "app_id"
+    pub kind: String,
+    pub moves: Vec<PlannedMove>,
+    /// Marker the migration wrote, removed on rollback
+    pub marker: Option<PathBuf>,
+    pub migrated_at: i64,
+}
+
 /// Database files in the flat data directory (relative to ~/.local/share/qbz/)
 const DATA_DIR_DB_FILES: &[&str] = &[
     "session.db",
@@ -62,8 +106,15 @@ pub fn is_migrated(global_data_dir: &Path) -> bool {
 pub fn migrate_flat_to_user(user_id: u64) -> Result<(), String> {
     let global_data_dir = crate::user_data::UserDataPaths::global_data_dir()?;
     let global_cache_dir = crate::user_data::UserDataPaths::global_cache_dir()?;
+    migrate_flat_to_user_at(&global_data_dir, &global_cache_dir, user_id)
+}
 
-    if is_migrated(&global_data_dir) {
+fn migrate_flat_to_user_at(
+    global_data_dir: &Path,
+    global_cache_dir: &Path,
+    user_id: u64,
+) -> Result<(), String> {
+    if is_migrated(global_data_dir) {
         log::debug!("Migration already completed, skipping");
         return Ok(());
     }
@@ -79,98 +130,254 @@ pub fn migrate_flat_to_user(user_id: u64) -> Result<(), String> {
     std::fs::create_dir_all(&user_cache_dir)
         .map_err(|e| format!("Failed to create user cache dir: {}", e))?;
 
-    // 1. Move flat DB files from data dir
-    for db_file in DATA_DIR_DB_FILES {
-        move_db_with_journals(&global_data_dir, &user_data_dir, db_file);
-    }
-
-    // 2. Move subdirectory DB files (reco/events.db, cache/*.db)
-    for (subdir, db_file) in DATA_DIR_SUBDIR_FILES {
-        let src_dir = global_data_dir.join(subdir);
-        let dst_dir = user_data_dir.join(subdir);
-
-        if src_dir.join(db_file).exists() {
-            std::fs::create_dir_all(&dst_dir)
-                .map_err(|e| format!("Failed to create {}: {}", dst_dir.display(), e))?;
-            move_db_with_journals(&src_dir, &dst_dir, db_file);
+    let plan = plan_flat_to_user(global_data_dir, global_cache_dir, user_id);
+    let mut done = Vec::with_capacity(plan.len());
+    for planned in plan {
+        match apply_move(&planned) {
+            Ok(()) => done.push(planned),
+            // Non-fatal: that store starts fresh in the user directory
+            Err(e) => log::error!("Failed to migrate {}: {}", planned.from.display(), e),
         }
     }
 
-    // 3. Move data directories (thumbnails)
-    for dir_name in DATA_DIRS {
-        move_directory(&global_data_dir.join(dir_name), &user_data_dir.join(dir_name));
-    }
-
-    // 4. Move cache directories
-    for dir_name in CACHE_DIRS {
-        move_directory(&global_cache_dir.join(dir_name), &user_cache_dir.join(dir_name));
-    }
-
-    // 5. Write marker file
+    // Write marker file
     let marker_path = global_data_dir.join(MIGRATED_MARKER);
     std::fs::write(&marker_path, format!("migrated_to_user={}\n", user_id))
         .map_err(|e| format!("Failed to write migration marker: {}", e))?;
 
+    record_migration(global_data_dir, "flat_to_user", done, Some(marker_path))?;
+
     log::info!("Migration completed for user {}", user_id);
     Ok(())
 }
 
-/// Move a DB file and its WAL/SHM journal files
-fn move_db_with_journals(src_dir: &Path, dst_dir: &Path, db_name: &str) {
-    let extensions = ["", "-wal", "-shm"];
-    for ext in extensions {
-        let filename = format!("{}{}", db_name, ext);
-        let src = src_dir.join(&filename);
-        let dst = dst_dir.join(&filename);
-
-        if src.exists() {
-            match std::fs::rename(&src, &dst) {
-                Ok(()) => log::debug!("Moved {} -> {}", src.display(), dst.display()),
-                Err(e) => {
-                    // Try copy + delete as fallback (cross-device moves)
-                    log::warn!("Rename failed for {}, trying copy: {}", filename, e);
-                    if let Err(copy_err) = copy_and_remove(&src, &dst) {
-                        log::error!("Failed to migrate {}: {}", filename, copy_err);
-                    }
-                }
+/// What `migrate_flat_to_user` would do for `user_id`, without touching
+/// anything
+///
+/// Databases are copied (the originals are kept until the migration is
+/// confirmed); cache directories are moved, since copying them would double
+/// the disk they take. Directories already present in the user layout are
+/// left alone.
+pub fn plan_flat_to_user(
+    global_data_dir: &Path,
+    global_cache_dir: &Path,
+    user_id: u64,
+) -> Vec<PlannedMove> {
+    if is_migrated(global_data_dir) {
+        return Vec::new();
+    }
+
+    let user_data_dir = global_data_dir.join("users").join(user_id.to_string());
+    let user_cache_dir = global_cache_dir.join("users").join(user_id.to_string());
+    let mut plan = Vec::new();
+
+    // 1. Flat DB files from data dir, 2. subdirectory DB files (reco/events.db, cache/*.db)
+    let db_files = DATA_DIR_DB_FILES
+        .iter()
+        .map(|db_file| ("", *db_file))
+        .chain(DATA_DIR_SUBDIR_FILES.iter().copied());
+    for (subdir, db_file) in db_files {
+        for ext in ["", "-wal", "-shm"] {
+            let filename = format!("{}{}", db_file, ext);
+            let from = global_data_dir.join(subdir).join(&filename);
+            if let Ok(metadata) = std::fs::metadata(&from) {
+                plan.push(PlannedMove {
+                    from,
+                    to: user_data_dir.join(subdir).join(&filename),
+                    bytes: metadata.len(),
+                    action: MigrationAction::Copy,
+                });
             }
         }
     }
+
+    // 3. Data directories (thumbnails), 4. cache directories
+    let dirs = DATA_DIRS
+        .iter()
+        .map(|dir_name| (global_data_dir, &user_data_dir, *dir_name))
+        .chain(
+            CACHE_DIRS
+                .iter()
+                .map(|dir_name| (global_cache_dir, &user_cache_dir, *dir_name)),
+        );
+    for (src_base, dst_base, dir_name) in dirs {
+        let from = src_base.join(dir_name);
+        let to = dst_base.join(dir_name);
+        if !from.is_dir() || to.exists() {
+            continue;
+        }
+        plan.push(PlannedMove {
+            bytes: crate::user_data::dir_size(&from),
+            from,
+            to,
+            action: MigrationAction::Move,
+        });
+    }
+
+    plan
 }
 
-/// Move a directory by renaming, with copy+delete fallback
-fn move_directory(src: &Path, dst: &Path) {
-    if !src.exists() || !src.is_dir() {
-        return;
+/// Carry out one planned move
+///
+/// Copies are written next to the destination first and renamed into place,
+/// so an interrupted migration never leaves a half-written database.
+pub(crate) fn apply_move(planned: &PlannedMove) -> Result<(), String> {
+    if let Some(parent) = planned.to.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
     }
 
-    // Don't overwrite if destination already exists
-    if dst.exists() {
-        log::debug!("Destination {} already exists, skipping", dst.display());
-        return;
+    match planned.action {
+        MigrationAction::Copy => {
+            let mut staging = planned.to.clone().into_os_string();
+            staging.push(".partial");
+            let staging = PathBuf::from(staging);
+            let copied = if planned.from.is_dir() {
+                copy_dir_recursive(&planned.from, &staging)
+            } else {
+                std::fs::copy(&planned.from, &staging)
+                    .map(|_| ())
+                    .map_err(|e| format!("Copy {} failed: {}", planned.from.display(), e))
+            };
+            let swapped = copied.and_then(|()| {
+                std::fs::rename(&staging, &planned.to)
+                    .map_err(|e| format!("Swap into {} failed: {}", planned.to.display(), e))
+            });
+            if swapped.is_err() {
+                remove_path(&staging);
+            }
+            swapped?;
+            log::debug!(
+                "Copied {} -> {}",
+                planned.from.display(),
+                planned.to.display()
+            );
+        }
+        MigrationAction::Move => move_path(&planned.from, &planned.to)?,
     }
+    Ok(())
+}
 
+/// Move a file or directory by renaming, with copy+delete fallback
+fn move_path(src: &Path, dst: &Path) -> Result<(), String> {
     match std::fs::rename(src, dst) {
-        Ok(()) => log::debug!("Moved dir {} -> {}", src.display(), dst.display()),
+        Ok(()) => log::debug!("Moved {} -> {}", src.display(), dst.display()),
         Err(e) => {
-            log::warn!("Rename dir failed for {}, trying recursive copy: {}", src.display(), e);
-            if let Err(copy_err) = copy_dir_recursive(src, dst) {
-                log::error!("Failed to migrate dir {}: {}", src.display(), copy_err);
+            log::warn!("Rename failed for {}, trying copy: {}", src.display(), e);
+            if src.is_dir() {
+                copy_dir_recursive(src, dst)?;
             } else {
-                // Only remove source if copy succeeded
-                let _ = std::fs::remove_dir_all(src);
+                std::fs::copy(src, dst).map_err(|e| {
+                    format!("Copy {} -> {} failed: {}", src.display(), dst.display(), e)
+                })?;
             }
+            // Only remove source once the copy succeeded
+            remove_path(src);
+        }
+    }
+    Ok(())
+}
+
+fn remove_path(path: &Path) {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    if let Err(e) = result {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            log::warn!("Failed to remove {}: {}", path.display(), e);
         }
     }
 }
 
-/// Copy a single file and remove the source
-fn copy_and_remove(src: &Path, dst: &Path) -> Result<(), String> {
-    std::fs::copy(src, dst)
-        .map_err(|e| format!("Copy {} -> {} failed: {}", src.display(), dst.display(), e))?;
-    std::fs::remove_file(src)
-        .map_err(|e| format!("Remove {} after copy failed: {}", src.display(), e))?;
-    Ok(())
+/// Remember what a migration did as the last migration, replacing any
+/// earlier record
+pub(crate) fn record_migration(
+    global_data_dir: &Path,
+    kind: &str,
+    moves: Vec<PlannedMove>,
+    marker: Option<PathBuf>,
+) -> Result<(), String> {
+    let record = MigrationRecord {
+        kind: kind.to_string(),
+        moves,
+        marker,
+        migrated_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64,
+    };
+    let json = serde_json::to_string_pretty(&record)
+        .map_err(|e| format!("Failed to serialize migration record: {}", e))?;
+    std::fs::write(global_data_dir.join(MIGRATION_RECORD), json)
+        .map_err(|e| format!("Failed to write migration record: {}", e))
+}
+
+/// The last migration, if it was neither confirmed nor rolled back
+pub fn last_migration(global_data_dir: &Path) -> Option<MigrationRecord> {
+    let json = std::fs::read_to_string(global_data_dir.join(MIGRATION_RECORD)).ok()?;
+    serde_json::from_str(&json)
+        .map_err(|e| log::warn!("Ignoring unreadable migration record: {}", e))
+        .ok()
+}
+
+/// Accept the last migration: delete the originals it copied
+///
+/// Returns the number of bytes freed.
+pub fn confirm_migration_at(global_data_dir: &Path) -> Result<u64, String> {
+    let record = last_migration(global_data_dir).ok_or("No migration to confirm")?;
+    let mut freed = 0;
+    for planned in &record.moves {
+        // Never delete the only copy
+        if planned.action == MigrationAction::Copy && planned.to.exists() {
+            remove_path(&planned.from);
+            freed += planned.bytes;
+        }
+    }
+    remove_path(&global_data_dir.join(MIGRATION_RECORD));
+    log::info!("Confirmed {} migration, freed {} bytes", record.kind, freed);
+    Ok(freed)
+}
+
+/// Undo the last migration: drop the copies, move moved directories back
+/// and clear the marker so the layout is as it was before
+///
+/// Changes made to the migrated data since then are lost.
+pub fn rollback_migration_at(global_data_dir: &Path) -> Result<MigrationRecord, String> {
+    let record = last_migration(global_data_dir).ok_or("No migration to roll back")?;
+    for planned in record.moves.iter().rev() {
+        match planned.action {
+            MigrationAction::Copy if planned.from.exists() => remove_path(&planned.to),
+            MigrationAction::Copy => log::warn!(
+                "Original {} is gone, keeping {}",
+                planned.from.display(),
+                planned.to.display()
+            ),
+            MigrationAction::Move if !planned.from.exists() && planned.to.exists() => {
+                if let Some(parent) = planned.from.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+                }
+                move_path(&planned.to, &planned.from)?;
+            }
+            MigrationAction::Move => log::warn!(
+                "Cannot move {} back to {}",
+                planned.to.display(),
+                planned.from.display()
+            ),
+        }
+    }
+    if let Some(marker) = &record.marker {
+        remove_path(marker);
+    }
+    remove_path(&global_data_dir.join(MIGRATION_RECORD));
+    log::info!(
+        "Rolled back {} migration ({} paths)",
+        record.kind,
+        record.moves.len()
+    );
+    Ok(record)
 }
 
 /// Recursively copy a directory
@@ -208,3 +415,224 @@ pub fn user_cache_path(user_id: u64) -> Result<PathBuf, String> {
     let global = crate::user_data::UserDataPaths::global_cache_dir()?;
     Ok(global.join("users").join(user_id.to_string()))
 }
+
+/// Layout version of the session database
+pub const SESSION_SCHEMA_VERSION: u32 = 5;
+
+/// Columns each session layout version added, and the table they went into
+///
+/// Version 0 is the original layout (and any database from before layouts
+/// were versioned).
+const SESSION_COLUMNS: &[(u32, &str, &[(&str, &str)])] = &[
+    (
+        1,
+        "queue_tracks",
+        &[
+            ("hires", "INTEGER NOT NULL DEFAULT 0"),
+            ("bit_depth", "INTEGER"),
+            ("sample_rate", "REAL"),
+        ],
+    ),
+    (
+        2,
+        "queue_tracks",
+        &[
+            ("is_local", "INTEGER NOT NULL DEFAULT 0"),
+            ("album_id", "TEXT"),
+            ("artist_id", "INTEGER"),
+        ],
+    ),
+    (3, "queue_tracks", &[("source", "TEXT")]),
+    (
+        4,
+        "queue_tracks",
+        &[
+            ("streamable", "INTEGER NOT NULL DEFAULT 1"),
+            ("track_number", "INTEGER"),
+            ("disc_number", "INTEGER"),
+        ],
+    ),
+    (5, "player_state", &[("current_track_id", "INTEGER")]),
+];
+
+/// Upgrade a session database to `SESSION_SCHEMA_VERSION`
+///
+/// Returns the version the database had. Unversioned databases may already
+/// have some of the columns (they used to be added on the fly), so each step
+/// only adds what is missing. A database written by a newer release is left
+/// as is; the columns this release knows about are still there.
+pub fn migrate_session_db(conn: &Connection) -> Result<u32, String> {
+    let version: u32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read session schema version: {}", e))?;
+
+    if version > SESSION_SCHEMA_VERSION {
+        log::warn!(
+            "Session database is from a newer release (schema v{}, this release uses v{})",
+            version,
+            SESSION_SCHEMA_VERSION
+        );
+        return Ok(version);
+    }
+
+    for (step, table, columns) in SESSION_COLUMNS {
+        if *step <= version {
+            continue;
+        }
+        for (name, definition) in columns.iter() {
+            let exists: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+                    [table, name],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map(|count| count > 0)
+                .map_err(|e| format!("Failed to inspect {}: {}", table, e))?;
+            if !exists {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE {} ADD COLUMN {} {};",
+                    table, name, definition
+                ))
+                .map_err(|e| format!("Session schema v{} migration failed: {}", step, e))?;
+            }
+        }
+    }
+
+    if version < SESSION_SCHEMA_VERSION {
+        conn.execute_batch(&format!("PRAGMA user_version = {};", SESSION_SCHEMA_VERSION))
+            .map_err(|e| format!("Failed to store session schema version: {}", e))?;
+        log::info!(
+            "Session database upgraded from schema v{} to v{}",
+            version,
+            SESSION_SCHEMA_VERSION
+        );
+    }
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn write(path: PathBuf, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// Every file under `root` with its contents
+    fn snapshot(root: &Path) -> BTreeMap<PathBuf, String> {
+        fn walk(dir: &Path, root: &Path, files: &mut BTreeMap<PathBuf, String>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    walk(&path, root, files);
+                } else {
+                    let contents = std::fs::read_to_string(&path).unwrap();
+                    files.insert(path.strip_prefix(root).unwrap().to_path_buf(), contents);
+                }
+            }
+        }
+        let mut files = BTreeMap::new();
+        walk(root, root, &mut files);
+        files
+    }
+
+    /// Flat layout from before per-user directories
+    fn flat_layout(root: &Path) -> (PathBuf, PathBuf) {
+        let data = root.join("data/qbz");
+        let cache = root.join("cache/qbz");
+        write(data.join("session.db"), "session");
+        write(data.join("session.db-wal"), "wal");
+        write(data.join("library.db"), "library");
+        write(data.join("cache/api_cache.db"), "api");
+        write(data.join("thumbnails/1.jpg"), "thumb");
+        write(cache.join("audio/1.flac"), "audio-data");
+        write(cache.join("lyrics/lyrics.db"), "lyrics");
+        // Already in the user layout: left alone
+        write(cache.join("users/7/lyrics/lyrics.db"), "newer");
+        (data, cache)
+    }
+
+    #[test]
+    fn dry_run_reports_exactly_what_the_migration_does() {
+        let root = tempfile::tempdir().unwrap();
+        let (data, cache) = flat_layout(root.path());
+        let before = snapshot(root.path());
+
+        let plan = plan_flat_to_user(&data, &cache, 7);
+        assert_eq!(snapshot(root.path()), before, "dry run touched files");
+
+        let user_data = data.join("users/7");
+        let user_cache = cache.join("users/7");
+        let summary: Vec<(PathBuf, u64, MigrationAction)> = plan
+            .iter()
+            .map(|planned| (planned.to.clone(), planned.bytes, planned.action))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (user_data.join("session.db"), 7, MigrationAction::Copy),
+                (user_data.join("session.db-wal"), 3, MigrationAction::Copy),
+                (user_data.join("library.db"), 7, MigrationAction::Copy),
+                (
+                    user_data.join("cache/api_cache.db"),
+                    3,
+                    MigrationAction::Copy
+                ),
+                (user_data.join("thumbnails"), 5, MigrationAction::Move),
+                (user_cache.join("audio"), 10, MigrationAction::Move),
+            ]
+        );
+
+        migrate_flat_to_user_at(&data, &cache, 7).unwrap();
+        for planned in &plan {
+            assert!(planned.to.exists(), "{} missing", planned.to.display());
+            assert_eq!(
+                planned.from.exists(),
+                planned.action == MigrationAction::Copy,
+                "{}",
+                planned.from.display()
+            );
+        }
+        assert_eq!(
+            std::fs::read_to_string(user_cache.join("lyrics/lyrics.db")).unwrap(),
+            "newer"
+        );
+        assert_eq!(last_migration(&data).unwrap().moves, plan);
+        assert!(plan_flat_to_user(&data, &cache, 7).is_empty());
+    }
+
+    #[test]
+    fn rollback_restores_the_previous_layout() {
+        let root = tempfile::tempdir().unwrap();
+        let (data, cache) = flat_layout(root.path());
+        let before = snapshot(root.path());
+
+        migrate_flat_to_user_at(&data, &cache, 7).unwrap();
+        assert!(is_migrated(&data));
+        assert_ne!(snapshot(root.path()), before);
+
+        let record = rollback_migration_at(&data).unwrap();
+        assert_eq!(record.kind, "flat_to_user");
+        assert_eq!(snapshot(root.path()), before);
+        assert!(!is_migrated(&data));
+        assert!(rollback_migration_at(&data).is_err());
+    }
+
+    #[test]
+    fn confirm_deletes_copied_originals_only() {
+        let root = tempfile::tempdir().unwrap();
+        let (data, cache) = flat_layout(root.path());
+
+        migrate_flat_to_user_at(&data, &cache, 7).unwrap();
+        let freed = confirm_migration_at(&data).unwrap();
+
+        assert_eq!(freed, 7 + 3 + 7 + 3);
+        assert!(!data.join("session.db").exists());
+        assert!(data.join("users/7/session.db").exists());
+        assert!(cache.join("users/7/audio/1.flac").exists());
+        assert!(last_migration(&data).is_none());
+    }
+}
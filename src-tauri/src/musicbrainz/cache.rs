@@ -8,6 +8,7 @@ use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 
+use super::credits::MbWork;
 use super::models::*;
 
 /// TTL for recording cache (30 days)
@@ -125,6 +126,14 @@ impl MusicBrainzCache {
                     fetched_at INTEGER NOT NULL
                 );
                 CREATE INDEX IF NOT EXISTS idx_mb_relations_fetched ON mb_artist_relations(fetched_at);
+
+                -- Works of a Qobuz album's tracks, indexed by album ID
+                CREATE TABLE IF NOT EXISTS mb_album_works (
+                    album_id TEXT PRIMARY KEY,
+                    data TEXT NOT NULL,
+                    fetched_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_mb_album_works_fetched ON mb_album_works(fetched_at);
                 "#,
             )
             .map_err(|e| format!("Failed to initialize MusicBrainz cache: {}", e))?;
@@ -314,6 +323,53 @@ impl MusicBrainzCache {
         Ok(())
     }
 
+    // ============ Album Works Cache ============
+
+    /// Get the cached works of an album, as (disc, position, work)
+    pub fn get_album_works(
+        &self,
+        album_id: &str,
+    ) -> Result<Option<Vec<(u32, u32, MbWork)>>, String> {
+        let min_fetched_at = Self::current_timestamp() - RELEASE_TTL_SECS;
+
+        let result: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT data FROM mb_album_works WHERE album_id = ? AND fetched_at > ?",
+                params![album_id, min_fetched_at],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query album works cache: {}", e))?;
+
+        if let Some(data) = result {
+            serde_json::from_str(&data)
+                .map(Some)
+                .map_err(|e| format!("Failed to parse cached album works: {}", e))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Cache the works of an album
+    pub fn set_album_works(
+        &self,
+        album_id: &str,
+        works: &[(u32, u32, MbWork)],
+    ) -> Result<(), String> {
+        let fetched_at = Self::current_timestamp();
+        let json = serde_json::to_string(works)
+            .map_err(|e| format!("Failed to serialize album works: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO mb_album_works (album_id, data, fetched_at) VALUES (?, ?, ?)",
+                params![album_id, json, fetched_at],
+            )
+            .map_err(|e| format!("Failed to cache album works: {}", e))?;
+        Ok(())
+    }
+
     // ============ Maintenance ============
 
     /// Clear expired entries from all tables
@@ -353,6 +409,14 @@ impl MusicBrainzCache {
             )
             .map_err(|e| format!("Failed to cleanup relations: {}", e))?;
 
+        total_deleted += self
+            .conn
+            .execute(
+                "DELETE FROM mb_album_works WHERE fetched_at <= ?",
+                params![now - RELEASE_TTL_SECS],
+            )
+            .map_err(|e| format!("Failed to cleanup album works: {}", e))?;
+
         if total_deleted > 0 {
             log::info!(
                 "MusicBrainz cache cleanup: removed {} expired entries",
@@ -372,6 +436,7 @@ impl MusicBrainzCache {
                 DELETE FROM mb_artists;
                 DELETE FROM mb_releases;
                 DELETE FROM mb_artist_relations;
+                DELETE FROM mb_album_works;
                 "#,
             )
             .map_err(|e| format!("Failed to clear MusicBrainz cache: {}", e))?;
@@ -292,6 +292,38 @@ impl MusicBrainzClient {
             .map_err(|e| format!("Failed to parse MusicBrainz response: {}", e))
     }
 
+    /// Get artist details with URL relationships (Wikidata, image, ...)
+    pub async fn get_artist_with_urls(&self, mbid: &str) -> Result<ArtistFullResponse, String> {
+        if !self.is_enabled().await {
+            return Err("MusicBrainz integration is disabled".to_string());
+        }
+
+        self.rate_limiter.wait().await;
+
+        let base_url = self.base_url().await;
+        let url = format!("{}/artist/{}?inc=url-rels&fmt=json", base_url, mbid);
+
+        log::debug!("MusicBrainz artist lookup with URLs: {}", mbid);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("MusicBrainz request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("MusicBrainz API error {}: {}", status, text));
+        }
+
+        response
+            .json::<ArtistFullResponse>()
+            .await
+            .map_err(|e| format!("Failed to parse MusicBrainz response: {}", e))
+    }
+
     /// Search releases by barcode (UPC/EAN)
     pub async fn search_release_by_barcode(&self, barcode: &str) -> Result<ReleaseSearchResponse, String> {
         if !self.is_enabled().await {
@@ -409,6 +441,23 @@ impl MusicBrainzClient {
     /// Get full release details including tracks
     /// Fetches media, recordings, artist credits, labels, and tags
     pub async fn get_release_with_tracks(&self, release_id: &str) -> Result<ReleaseFullResponse, String> {
+        // inc=recordings gets track info, artist-credits for artist info,
+        // labels for label/catalog, tags for genres
+        self.lookup_release(release_id, "recordings+artist-credits+labels+tags").await
+    }
+
+    /// Get release with the credit relationships of the release and of each
+    /// recording (performers, instruments, producers, engineers), and the
+    /// works the recordings perform with their composers
+    pub async fn get_release_with_credits(&self, release_id: &str) -> Result<ReleaseFullResponse, String> {
+        self.lookup_release(
+            release_id,
+            "recordings+artist-rels+recording-level-rels+work-rels+work-level-rels",
+        )
+        .await
+    }
+
+    async fn lookup_release(&self, release_id: &str, inc: &str) -> Result<ReleaseFullResponse, String> {
         if !self.is_enabled().await {
             return Err("MusicBrainz integration is disabled".to_string());
         }
@@ -416,14 +465,9 @@ impl MusicBrainzClient {
         self.rate_limiter.wait().await;
 
         let base_url = self.base_url().await;
-        // inc=recordings gets track info, artist-credits for artist info,
-        // labels for label/catalog, tags for genres
-        let url = format!(
-            "{}/release/{}?inc=recordings+artist-credits+labels+tags&fmt=json",
-            base_url, release_id
-        );
+        let url = format!("{}/release/{}?inc={}&fmt=json", base_url, release_id, inc);
 
-        log::debug!("MusicBrainz release lookup with tracks: {}", release_id);
+        log::debug!("MusicBrainz release lookup ({}): {}", inc, release_id);
 
         let response = self
             .client
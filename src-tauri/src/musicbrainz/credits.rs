@@ -0,0 +1,460 @@
+//! Credits from MusicBrainz relationships
+//!
+//! Turns release and recording relationships (instrument, vocal, producer,
+//! engineer, ...) into role credits and merges them with the performers Qobuz
+//! lists for a track. People are matched by name, so the same musician
+//! credited by both sources shows up once with the union of their roles.
+//! The works recordings perform are collected too, with the composers of
+//! each work credited on its tracks.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::models::{Relation, ReleaseFullResponse};
+use crate::api::performers::Performer;
+
+/// Attributes that qualify a credit rather than name an instrument or voice
+const QUALIFIER_ATTRIBUTES: &[&str] = &["guest", "additional", "solo", "co", "assistant"];
+
+/// One person/role pair from a MusicBrainz relationship
+#[derive(Debug, Clone, PartialEq)]
+pub struct MbCredit {
+    pub name: String,
+    pub mbid: String,
+    pub role: String,
+}
+
+/// A person credited on a track, with all of their roles
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PersonCredit {
+    pub name: String,
+    pub mbid: Option<String>,
+    pub roles: Vec<String>,
+}
+
+/// People credited with one role
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RoleCredits {
+    pub role: String,
+    pub people: Vec<String>,
+}
+
+/// A person across the whole album
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CreditedPerson {
+    pub name: String,
+    pub mbid: Option<String>,
+    pub roles: Vec<String>,
+    /// Tracks the person is credited on (empty for album-wide credits only)
+    pub track_ids: Vec<u64>,
+}
+
+/// The work a recording performs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MbWork {
+    pub mbid: String,
+    pub title: String,
+    pub composers: Vec<String>,
+}
+
+/// Credits of a release: album-wide, and per track keyed by (disc, position)
+#[derive(Debug, Default)]
+pub struct ReleaseCredits {
+    pub album: Vec<MbCredit>,
+    pub tracks: HashMap<(u32, u32), Vec<MbCredit>>,
+    pub works: HashMap<(u32, u32), MbWork>,
+}
+
+impl ReleaseCredits {
+    pub fn from_release(release: &ReleaseFullResponse) -> Self {
+        let mut credits = Self {
+            album: relation_credits(release.relations.as_deref().unwrap_or_default()),
+            tracks: HashMap::new(),
+            works: HashMap::new(),
+        };
+
+        for (index, medium) in release.media.iter().flatten().enumerate() {
+            let disc = medium.position.map(u32::from).unwrap_or(index as u32 + 1);
+            for (track_index, track) in medium.tracks.iter().flatten().enumerate() {
+                let position = track
+                    .position
+                    .map(u32::from)
+                    .unwrap_or(track_index as u32 + 1);
+                let relations = track
+                    .recording
+                    .as_ref()
+                    .and_then(|r| r.relations.as_deref())
+                    .unwrap_or_default();
+                let mut track_credits = relation_credits(relations);
+
+                if let Some(work) = relations.iter().find_map(|r| r.work.as_ref()) {
+                    let composers = relation_credits(work.relations.as_deref().unwrap_or_default())
+                        .into_iter()
+                        .filter(|c| c.role == "Composer")
+                        .collect::<Vec<_>>();
+                    credits.works.insert(
+                        (disc, position),
+                        MbWork {
+                            mbid: work.id.clone(),
+                            title: work.title.clone(),
+                            composers: composers.iter().map(|c| c.name.clone()).collect(),
+                        },
+                    );
+                    track_credits.extend(composers);
+                }
+
+                if !track_credits.is_empty() {
+                    credits.tracks.insert((disc, position), track_credits);
+                }
+            }
+        }
+
+        credits
+    }
+
+    pub fn track(&self, disc: u32, position: u32) -> &[MbCredit] {
+        self.tracks
+            .get(&(disc, position))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn work(&self, disc: u32, position: u32) -> Option<&MbWork> {
+        self.works.get(&(disc, position))
+    }
+}
+
+/// Role credits for the artist relationships in `relations`
+pub fn relation_credits(relations: &[Relation]) -> Vec<MbCredit> {
+    let mut credits = Vec::new();
+    for relation in relations {
+        let Some(artist) = relation.artist.as_ref() else {
+            continue;
+        };
+        for role in relation_roles(relation) {
+            credits.push(MbCredit {
+                name: artist.name.clone(),
+                mbid: artist.id.clone(),
+                role,
+            });
+        }
+    }
+    credits
+}
+
+fn relation_roles(relation: &Relation) -> Vec<String> {
+    let attributes: Vec<String> = relation
+        .attributes
+        .iter()
+        .flatten()
+        .filter(|a| !QUALIFIER_ATTRIBUTES.contains(&a.as_str()))
+        .map(|a| title_case(a))
+        .collect();
+
+    let role = match relation.relation_type.as_str() {
+        "instrument" | "vocal" if !attributes.is_empty() => return attributes,
+        "instrument" => "Instruments",
+        "vocal" => "Vocals",
+        "mix" => "Mixing Engineer",
+        "mastering" => "Mastering Engineer",
+        "recording" => "Recording Engineer",
+        "performing orchestra" => "Orchestra",
+        other => return vec![title_case(other)],
+    };
+    vec![role.to_string()]
+}
+
+fn title_case(s: &str) -> String {
+    s.split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Key people and roles are compared by ("Lead Vocals" = "LeadVocals")
+fn match_key(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn add_role(roles: &mut Vec<String>, role: &str) {
+    let key = match_key(role);
+    if !roles.iter().any(|r| match_key(r) == key) {
+        roles.push(role.to_string());
+    }
+}
+
+/// Merge the Qobuz performers of a track with its MusicBrainz credits
+pub fn merge_credits(performers: &[Performer], mb_credits: &[MbCredit]) -> Vec<PersonCredit> {
+    let mut people: Vec<PersonCredit> = Vec::new();
+
+    for performer in performers {
+        let key = match_key(&performer.name);
+        match people.iter_mut().find(|p| match_key(&p.name) == key) {
+            Some(person) => performer
+                .roles
+                .iter()
+                .for_each(|r| add_role(&mut person.roles, r)),
+            None => {
+                let mut roles = Vec::new();
+                performer.roles.iter().for_each(|r| add_role(&mut roles, r));
+                people.push(PersonCredit {
+                    name: performer.name.clone(),
+                    mbid: None,
+                    roles,
+                });
+            }
+        }
+    }
+
+    for credit in mb_credits {
+        let key = match_key(&credit.name);
+        match people
+            .iter_mut()
+            .find(|p| p.mbid.as_deref() == Some(credit.mbid.as_str()) || match_key(&p.name) == key)
+        {
+            Some(person) => {
+                person.mbid.get_or_insert_with(|| credit.mbid.clone());
+                add_role(&mut person.roles, &credit.role);
+            }
+            None => people.push(PersonCredit {
+                name: credit.name.clone(),
+                mbid: Some(credit.mbid.clone()),
+                roles: vec![credit.role.clone()],
+            }),
+        }
+    }
+
+    people
+}
+
+/// Group people by role, in order of first appearance
+pub fn group_by_role(people: &[PersonCredit]) -> Vec<RoleCredits> {
+    let mut groups: Vec<RoleCredits> = Vec::new();
+    for person in people {
+        for role in &person.roles {
+            let key = match_key(role);
+            match groups.iter_mut().find(|g| match_key(&g.role) == key) {
+                Some(group) => group.people.push(person.name.clone()),
+                None => groups.push(RoleCredits {
+                    role: role.clone(),
+                    people: vec![person.name.clone()],
+                }),
+            }
+        }
+    }
+    groups
+}
+
+/// Everyone credited on the album, each person once
+pub fn collect_people(
+    album_credits: &[PersonCredit],
+    tracks: &[(u64, &[PersonCredit])],
+) -> Vec<CreditedPerson> {
+    let mut people: Vec<CreditedPerson> = Vec::new();
+
+    let album = album_credits.iter().map(|p| (None, p));
+    let tracks = tracks
+        .iter()
+        .flat_map(|(id, credits)| credits.iter().map(move |p| (Some(*id), p)));
+
+    for (track_id, credit) in album.chain(tracks) {
+        let key = match_key(&credit.name);
+        let index = match people
+            .iter()
+            .position(|p| (p.mbid.is_some() && p.mbid == credit.mbid) || match_key(&p.name) == key)
+        {
+            Some(index) => index,
+            None => {
+                people.push(CreditedPerson {
+                    name: credit.name.clone(),
+                    mbid: None,
+                    roles: Vec::new(),
+                    track_ids: Vec::new(),
+                });
+                people.len() - 1
+            }
+        };
+
+        let person = &mut people[index];
+        if person.mbid.is_none() {
+            person.mbid = credit.mbid.clone();
+        }
+        credit
+            .roles
+            .iter()
+            .for_each(|r| add_role(&mut person.roles, r));
+        if let Some(id) = track_id {
+            if !person.track_ids.contains(&id) {
+                person.track_ids.push(id);
+            }
+        }
+    }
+
+    people
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relation(kind: &str, attributes: &[&str], name: &str, mbid: &str) -> Relation {
+        serde_json::from_value(serde_json::json!({
+            "type": kind,
+            "attributes": attributes,
+            "artist": { "id": mbid, "name": name },
+        }))
+        .unwrap()
+    }
+
+    fn mb(name: &str, mbid: &str, role: &str) -> MbCredit {
+        MbCredit {
+            name: name.to_string(),
+            mbid: mbid.to_string(),
+            role: role.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_relation_roles() {
+        let credits = relation_credits(&[
+            relation(
+                "instrument",
+                &["guest", "electric guitar"],
+                "Jim Hall",
+                "jh",
+            ),
+            relation("vocal", &[], "Jo Stafford", "js"),
+            relation("mix", &[], "Rudy Van Gelder", "rvg"),
+            relation("producer", &["co"], "Teo Macero", "tm"),
+        ]);
+        assert_eq!(
+            credits,
+            vec![
+                mb("Jim Hall", "jh", "Electric Guitar"),
+                mb("Jo Stafford", "js", "Vocals"),
+                mb("Rudy Van Gelder", "rvg", "Mixing Engineer"),
+                mb("Teo Macero", "tm", "Producer"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_release_credits_by_position() {
+        let release: ReleaseFullResponse = serde_json::from_value(serde_json::json!({
+            "id": "r1",
+            "title": "Kind of Blue",
+            "relations": [
+                { "type": "producer", "artist": { "id": "tm", "name": "Teo Macero" } }
+            ],
+            "media": [{
+                "position": 1,
+                "tracks": [{
+                    "position": 2,
+                    "recording": {
+                        "id": "rec",
+                        "relations": [{
+                            "type": "instrument",
+                            "attributes": ["piano"],
+                            "artist": { "id": "be", "name": "Bill Evans" }
+                        }, {
+                            "type": "performance",
+                            "work": {
+                                "id": "w1",
+                                "title": "Blue in Green",
+                                "relations": [{
+                                    "type": "composer",
+                                    "artist": { "id": "md", "name": "Miles Davis" }
+                                }]
+                            }
+                        }]
+                    }
+                }]
+            }]
+        }))
+        .unwrap();
+
+        let credits = ReleaseCredits::from_release(&release);
+        assert_eq!(credits.album, vec![mb("Teo Macero", "tm", "Producer")]);
+        assert_eq!(
+            credits.track(1, 2),
+            &[
+                mb("Bill Evans", "be", "Piano"),
+                mb("Miles Davis", "md", "Composer")
+            ]
+        );
+        assert_eq!(
+            credits.work(1, 2),
+            Some(&MbWork {
+                mbid: "w1".to_string(),
+                title: "Blue in Green".to_string(),
+                composers: vec!["Miles Davis".to_string()],
+            })
+        );
+        assert!(credits.track(1, 1).is_empty());
+    }
+
+    #[test]
+    fn test_merge_deduplicates_people_and_roles() {
+        let performers = vec![
+            Performer::new(
+                "Miles Davis".to_string(),
+                vec!["Trumpet".to_string(), "MainArtist".to_string()],
+            ),
+            Performer::new("Bill Evans".to_string(), vec!["Piano".to_string()]),
+        ];
+        let merged = merge_credits(
+            &performers,
+            &[
+                mb("Bill Evans", "be", "piano"),
+                mb("Miles Davis", "md", "Bandleader"),
+                mb("Jimmy Cobb", "jc", "Drums"),
+            ],
+        );
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].mbid.as_deref(), Some("md"));
+        assert_eq!(merged[0].roles, vec!["Trumpet", "MainArtist", "Bandleader"]);
+        assert_eq!(merged[1].roles, vec!["Piano"]);
+        assert_eq!(merged[2].name, "Jimmy Cobb");
+
+        let groups = group_by_role(&merged);
+        assert_eq!(groups[1].role, "MainArtist");
+        assert_eq!(
+            groups.iter().find(|g| g.role == "Piano").unwrap().people,
+            vec!["Bill Evans"]
+        );
+    }
+
+    #[test]
+    fn test_collect_people_across_tracks() {
+        let person = |name: &str, mbid: Option<&str>, role: &str| PersonCredit {
+            name: name.to_string(),
+            mbid: mbid.map(str::to_string),
+            roles: vec![role.to_string()],
+        };
+        let track1 = vec![person("Paul Chambers", None, "Bass")];
+        let track2 = vec![
+            person("Paul Chambers", Some("pc"), "Double Bass"),
+            person("Cannonball Adderley", None, "Alto Saxophone"),
+        ];
+        let album = vec![person("Teo Macero", Some("tm"), "Producer")];
+
+        let people = collect_people(&album, &[(1, &track1), (2, &track2)]);
+        assert_eq!(people.len(), 3);
+        assert_eq!(people[0].track_ids, Vec::<u64>::new());
+        assert_eq!(people[1].name, "Paul Chambers");
+        assert_eq!(people[1].mbid.as_deref(), Some("pc"));
+        assert_eq!(people[1].roles, vec!["Bass", "Double Bass"]);
+        assert_eq!(people[1].track_ids, vec![1, 2]);
+    }
+}
@@ -9,6 +9,7 @@
 //! - `client.rs`: HTTP client with rate limiting (1 req/sec)
 //! - `models.rs`: API response types and resolved entity types
 //! - `cache.rs`: SQLite-based cache with TTL expiration
+//! - `credits.rs`: Role credits from release and recording relationships
 //!
 //! ## Usage
 //!
@@ -16,6 +17,7 @@
 
 pub mod cache;
 pub mod client;
+pub mod credits;
 pub mod models;
 pub mod smart_playlists;
 
@@ -195,6 +195,26 @@ pub struct Relation {
     pub ended: Option<bool>,
     pub attributes: Option<Vec<String>>,
     pub artist: Option<ArtistRef>,
+    /// Target work of a recording's "performance" relationship
+    pub work: Option<WorkRef>,
+    /// Target of a URL relationship (with `inc=url-rels`)
+    pub url: Option<UrlRef>,
+}
+
+/// Reference to an external URL (Wikidata, Wikimedia Commons, ...)
+#[derive(Debug, Deserialize)]
+pub struct UrlRef {
+    pub id: String,
+    pub resource: String,
+}
+
+/// Reference to a work (composition)
+#[derive(Debug, Deserialize)]
+pub struct WorkRef {
+    pub id: String,
+    pub title: String,
+    /// Work-level relationships such as the composer (with `inc=work-level-rels`)
+    pub relations: Option<Vec<Relation>>,
 }
 
 /// Release search response
@@ -274,6 +294,8 @@ pub struct ReleaseFullResponse {
     pub media: Option<Vec<Medium>>,
     /// Community tags (genres)
     pub tags: Option<Vec<Tag>>,
+    /// Release-level relationships (with `inc=artist-rels`)
+    pub relations: Option<Vec<Relation>>,
 }
 
 /// A medium (disc) containing tracks
@@ -313,6 +335,8 @@ pub struct TrackRecording {
     pub length: Option<i64>,
     #[serde(rename = "artist-credit")]
     pub artist_credit: Option<Vec<ArtistCredit>>,
+    /// Recording-level relationships (with `inc=recording-level-rels`)
+    pub relations: Option<Vec<Relation>>,
 }
 
 /// Community tag (used for genres)
@@ -0,0 +1,204 @@
+//! Connection type detection (wired, Wi-Fi, metered)
+//!
+//! Finds the interface carrying the default route and classifies it from
+//! /sys/class/net. NetworkManager's metered flag (which also covers phone
+//! hotspots joined over Wi-Fi) is read through `nmcli` when available.
+//! Anything that cannot be determined is reported as `Unknown`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Kind of connection currently used for internet access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionType {
+    Ethernet,
+    Wifi,
+    /// Mobile broadband, USB tethering or a connection flagged metered
+    Metered,
+    #[default]
+    Unknown,
+}
+
+impl ConnectionType {
+    pub fn is_metered(self) -> bool {
+        self == ConnectionType::Metered
+    }
+}
+
+/// How long a detection result is reused (detection spawns `nmcli`)
+const DETECTION_TTL: Duration = Duration::from_secs(15);
+
+static LAST_DETECTION: Mutex<Option<(Instant, ConnectionType)>> = Mutex::new(None);
+
+/// USB drivers used by phones for tethering and by mobile broadband modems
+const TETHERING_DRIVERS: &[&str] = &["rndis_host", "ipheth", "qmi_wwan", "cdc_mbim"];
+
+/// Current connection type, cached for a few seconds.
+///
+/// Blocking (may run `nmcli`); call from `spawn_blocking` in async code.
+pub fn get_connection_type() -> ConnectionType {
+    if let Ok(last) = LAST_DETECTION.lock() {
+        if let Some((at, connection)) = *last {
+            if at.elapsed() < DETECTION_TTL {
+                return connection;
+            }
+        }
+    }
+
+    let connection = detect_connection_type();
+    if let Ok(mut last) = LAST_DETECTION.lock() {
+        *last = Some((Instant::now(), connection));
+    }
+    connection
+}
+
+/// Detect the connection type without the cache
+pub fn detect_connection_type() -> ConnectionType {
+    #[cfg(target_os = "linux")]
+    {
+        detect_linux()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        ConnectionType::Unknown
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect_linux() -> ConnectionType {
+    use std::path::Path;
+
+    let Some(iface) = std::fs::read_to_string("/proc/net/route")
+        .ok()
+        .and_then(|table| default_route_interface(&table))
+    else {
+        return ConnectionType::Unknown;
+    };
+
+    let sys = Path::new("/sys/class/net").join(&iface);
+    let is_wireless = sys.join("wireless").exists() || sys.join("phy80211").exists();
+    let driver = std::fs::read_link(sys.join("device/driver"))
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()));
+    let arp_type = std::fs::read_to_string(sys.join("type"))
+        .ok()
+        .and_then(|t| t.trim().parse().ok());
+
+    let connection = classify_interface(&iface, is_wireless, driver.as_deref(), arp_type);
+    if nm_metered(&iface) == Some(true) {
+        return ConnectionType::Metered;
+    }
+    connection
+}
+
+/// Ask NetworkManager whether the device is metered (None when NM is not available)
+#[cfg(target_os = "linux")]
+fn nm_metered(iface: &str) -> Option<bool> {
+    let output = std::process::Command::new("nmcli")
+        .args(["-t", "-g", "GENERAL.METERED", "device", "show", iface])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_nm_metered(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse `nmcli`'s GENERAL.METERED value ("yes", "no (guessed)", "unknown", ...)
+fn parse_nm_metered(value: &str) -> Option<bool> {
+    let value = value.trim();
+    if value.starts_with("yes") {
+        Some(true)
+    } else if value.starts_with("no") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Interface of the default route with the lowest metric, from /proc/net/route
+fn default_route_interface(route_table: &str) -> Option<String> {
+    route_table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            // Iface Destination Gateway Flags RefCnt Use Metric Mask ...
+            if cols.len() < 8 || cols[1] != "00000000" || cols[7] != "00000000" {
+                return None;
+            }
+            let metric: u32 = cols[6].parse().unwrap_or(u32::MAX);
+            Some((metric, cols[0]))
+        })
+        .min_by_key(|(metric, _)| *metric)
+        .map(|(_, iface)| iface.to_string())
+}
+
+/// Classify an interface from its name, sysfs attributes and driver
+fn classify_interface(
+    name: &str,
+    is_wireless: bool,
+    driver: Option<&str>,
+    arp_type: Option<u32>,
+) -> ConnectionType {
+    if name.starts_with("wwan") || name.starts_with("wwp") || name.starts_with("ppp") {
+        return ConnectionType::Metered;
+    }
+    if driver.is_some_and(|d| TETHERING_DRIVERS.contains(&d)) {
+        return ConnectionType::Metered;
+    }
+    if is_wireless {
+        return ConnectionType::Wifi;
+    }
+    // ARPHRD_ETHER
+    if arp_type == Some(1) {
+        return ConnectionType::Ethernet;
+    }
+    ConnectionType::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_default_route_with_lowest_metric() {
+        let table =
+            "Iface\tDestination\tGateway\tFlags\tRefCnt\tUse\tMetric\tMask\tMTU\tWindow\tIRTT\n\
+                     wlp3s0\t00000000\t0102A8C0\t0003\t0\t0\t600\t00000000\t0\t0\t0\n\
+                     enp0s31f6\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n\
+                     enp0s31f6\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n";
+        assert_eq!(default_route_interface(table).as_deref(), Some("enp0s31f6"));
+        assert_eq!(default_route_interface("Iface\tDestination\n"), None);
+    }
+
+    #[test]
+    fn classifies_interfaces_and_nm_flag() {
+        assert_eq!(
+            classify_interface("enp0s31f6", false, Some("e1000e"), Some(1)),
+            ConnectionType::Ethernet
+        );
+        assert_eq!(
+            classify_interface("wlp3s0", true, Some("iwlwifi"), Some(1)),
+            ConnectionType::Wifi
+        );
+        assert_eq!(
+            classify_interface("usb0", false, Some("rndis_host"), Some(1)),
+            ConnectionType::Metered
+        );
+        assert_eq!(
+            classify_interface("wwan0", false, None, None),
+            ConnectionType::Metered
+        );
+        assert_eq!(
+            classify_interface("tun0", false, None, Some(65534)),
+            ConnectionType::Unknown
+        );
+
+        assert_eq!(parse_nm_metered("yes (guessed)\n"), Some(true));
+        assert_eq!(parse_nm_metered("no"), Some(false));
+        assert_eq!(parse_nm_metered("unknown"), None);
+    }
+}
@@ -0,0 +1,142 @@
+//! Shared, configurable HTTP client
+//!
+//! Downloads and web APIs go through one reqwest client built from the
+//! network settings, so they share a connection pool and honor the user's
+//! proxy, User-Agent and timeouts. Without a configured proxy reqwest falls
+//! back to `HTTP_PROXY`/`HTTPS_PROXY`. Services that need their own headers or
+//! cookies start from [`builder`] to get the same settings.
+
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+use reqwest::{Client, ClientBuilder, Proxy};
+
+use crate::config::network_settings::NetworkSettings;
+
+/// User-Agent for downloads when the user hasn't set one
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0";
+
+static SETTINGS: LazyLock<RwLock<NetworkSettings>> =
+    LazyLock::new(|| RwLock::new(NetworkSettings::default()));
+
+static SHARED: LazyLock<RwLock<Client>> = LazyLock::new(|| {
+    let settings = current_settings();
+    RwLock::new(
+        build_client(&settings, DEFAULT_USER_AGENT)
+            .or_else(|_| build_client(&NetworkSettings::default(), DEFAULT_USER_AGENT))
+            .expect("Failed to create HTTP client"),
+    )
+});
+
+fn current_settings() -> NetworkSettings {
+    SETTINGS.read().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// A client builder with the user's network settings applied.
+///
+/// `default_user_agent` is sent unless the user configured their own.
+pub fn builder(default_user_agent: &str) -> ClientBuilder {
+    apply(Client::builder(), &current_settings(), default_user_agent).unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid network settings: {}", e);
+        apply(
+            Client::builder(),
+            &NetworkSettings::default(),
+            default_user_agent,
+        )
+        .unwrap_or_else(|_| Client::builder())
+    })
+}
+
+/// The shared client (cheap to clone, pools connections)
+pub fn shared() -> Client {
+    SHARED
+        .read()
+        .map(|c| c.clone())
+        .unwrap_or_else(|e| e.into_inner().clone())
+}
+
+/// Apply new network settings and rebuild the shared client.
+///
+/// Fails without changing anything if the proxy URL is invalid.
+pub fn configure(settings: &NetworkSettings) -> Result<(), String> {
+    let client = build_client(settings, DEFAULT_USER_AGENT)?;
+    if let Ok(mut current) = SETTINGS.write() {
+        *current = settings.clone();
+    }
+    if let Ok(mut shared) = SHARED.write() {
+        *shared = client;
+    }
+    log::info!(
+        "HTTP client configured (proxy: {}, custom user agent: {})",
+        if settings.proxy.is_some() {
+            "custom"
+        } else {
+            "environment"
+        },
+        settings.user_agent.is_some()
+    );
+    Ok(())
+}
+
+fn build_client(settings: &NetworkSettings, default_user_agent: &str) -> Result<Client, String> {
+    apply(Client::builder(), settings, default_user_agent)?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn apply(
+    builder: ClientBuilder,
+    settings: &NetworkSettings,
+    default_user_agent: &str,
+) -> Result<ClientBuilder, String> {
+    let mut builder = builder
+        .user_agent(settings.user_agent.as_deref().unwrap_or(default_user_agent))
+        .connect_timeout(Duration::from_secs(settings.connect_timeout_secs as u64))
+        .read_timeout(Duration::from_secs(settings.read_timeout_secs as u64));
+    if let Some(ref proxy) = settings.proxy {
+        // reqwest is built without SOCKS support
+        let scheme = proxy.split_once("://").map(|(scheme, _)| scheme);
+        if !matches!(scheme, Some("http" | "https")) {
+            return Err(
+                "Invalid proxy URL: only http:// and https:// proxies are supported".to_string(),
+            );
+        }
+        let proxy = Proxy::all(proxy).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_proxy_is_rejected() {
+        let settings = NetworkSettings {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(build_client(&settings, DEFAULT_USER_AGENT).is_err());
+    }
+
+    #[test]
+    fn test_socks_proxy_is_rejected() {
+        let settings = NetworkSettings {
+            proxy: Some("socks5://127.0.0.1:1080".to_string()),
+            ..Default::default()
+        };
+        assert!(build_client(&settings, DEFAULT_USER_AGENT).is_err());
+    }
+
+    #[test]
+    fn test_valid_settings_build() {
+        let settings = NetworkSettings {
+            proxy: Some("http://proxy.example:3128".to_string()),
+            user_agent: Some("QBZ test".to_string()),
+            connect_timeout_secs: 5,
+            read_timeout_secs: 20,
+        };
+        assert!(build_client(&settings, DEFAULT_USER_AGENT).is_ok());
+    }
+}
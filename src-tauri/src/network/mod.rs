@@ -7,6 +7,16 @@
 //! - Download folder selection (warning for network folders)
 //! - Library folder management (network folder indicator)
 //! - Offline mode (accessibility of network content)
+//!
+//! The `connection` submodule detects the kind of internet connection
+//! (wired, Wi-Fi, metered) so heavy downloads can be held back on metered links.
+//! The `http_client` submodule holds the shared HTTP client built from the
+//! user's network settings.
+
+mod connection;
+pub mod http_client;
+
+pub use connection::{detect_connection_type, get_connection_type, ConnectionType};
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -391,6 +401,14 @@ pub mod commands {
         check_mount_accessibility(&mount_point)
     }
 
+    /// Get the kind of connection currently used (ethernet, wifi, metered, unknown)
+    #[tauri::command]
+    pub async fn get_connection_type_cmd() -> ConnectionType {
+        tokio::task::spawn_blocking(detect_connection_type)
+            .await
+            .unwrap_or_default()
+    }
+
     /// Batch check multiple paths for network status
     #[tauri::command]
     pub fn check_network_paths_batch(paths: Vec<String>) -> HashMap<String, NetworkPathInfo> {
@@ -0,0 +1,236 @@
+//! "Now playing" reports to Last.fm and ListenBrainz
+//!
+//! Driven by the playback poller rather than the frontend, so every track
+//! start is reported exactly once, whatever started it (UI, remote control,
+//! MPRIS, gapless advance). Following the scrobbling specs, tracks of 30
+//! seconds or less are never reported.
+//!
+//! Neither service can clear a now-playing entry, so pausing just stops
+//! reports. Both expire the entry based on the track length, so resuming or
+//! seeking reports the track again.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+
+use tauri::{AppHandle, Manager};
+
+use crate::listenbrainz::{AdditionalInfo, ListenBrainzSharedState};
+use crate::musicbrainz::{MatchConfidence, MusicBrainzSharedState, ResolvedTrack};
+use crate::offline::OfflineState;
+use crate::queue::QueueTrack;
+use crate::AppState;
+
+/// Tracks this short or shorter are not reported
+pub const MIN_TRACK_SECS: u64 = 30;
+
+/// Position drift (against wall-clock time) that counts as a seek
+const SEEK_TOLERANCE_SECS: u64 = 3;
+
+/// MusicBrainz match of the last reported track, so resume and seek reports
+/// don't look it up again
+static LAST_RESOLVED: LazyLock<Mutex<Option<(u64, Option<ResolvedTrack>)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Decides from poller samples when the current track should be reported
+#[derive(Debug, Default)]
+pub struct NowPlayingTracker {
+    track_id: u64,
+    is_playing: bool,
+    position: u64,
+    sampled_at: Option<Instant>,
+    /// A report is owed but the duration isn't known yet
+    pending: bool,
+}
+
+impl NowPlayingTracker {
+    /// Feed one poller sample; true when now-playing should be sent
+    pub fn update(
+        &mut self,
+        track_id: u64,
+        is_playing: bool,
+        position: u64,
+        duration_secs: u64,
+        now: Instant,
+    ) -> bool {
+        let track_changed = track_id != self.track_id;
+        let resumed = is_playing && !self.is_playing;
+        let seeked = !track_changed && is_playing && self.is_playing && self.is_seek(position, now);
+
+        self.track_id = track_id;
+        self.is_playing = is_playing;
+        self.position = position;
+        self.sampled_at = Some(now);
+
+        if track_id == 0 || !is_playing {
+            self.pending = false;
+            return false;
+        }
+        if track_changed || resumed || seeked {
+            self.pending = true;
+        }
+        // The duration shows up a moment after the track starts
+        if !self.pending || duration_secs == 0 {
+            return false;
+        }
+        self.pending = false;
+        duration_secs > MIN_TRACK_SECS
+    }
+
+    fn is_seek(&self, position: u64, now: Instant) -> bool {
+        let Some(sampled_at) = self.sampled_at else {
+            return false;
+        };
+        let expected = self.position + now.duration_since(sampled_at).as_secs();
+        position.abs_diff(expected) > SEEK_TOLERANCE_SECS
+    }
+}
+
+/// Report `track_id` in the background, if it's still the current track
+pub fn spawn_report(app_handle: AppHandle, track_id: u64) {
+    tauri::async_runtime::spawn(async move {
+        let track = app_handle
+            .state::<AppState>()
+            .queue
+            .current_track()
+            .filter(|track| track.id == track_id);
+        let Some(track) = track else {
+            return;
+        };
+        if !reports_allowed(&app_handle) {
+            log::debug!("Now playing: offline, not reporting {}", track.title);
+            return;
+        }
+        tokio::join!(
+            report_lastfm(&app_handle, &track),
+            report_listenbrainz(&app_handle, &track)
+        );
+    });
+}
+
+/// Manual offline mode blocks reports unless immediate scrobbling is allowed
+fn reports_allowed(app_handle: &AppHandle) -> bool {
+    let Some(offline_state) = app_handle.try_state::<OfflineState>() else {
+        return true;
+    };
+    let Ok(guard) = offline_state.store.lock() else {
+        return true;
+    };
+    match guard.as_ref().map(|store| store.get_settings()) {
+        Some(Ok(settings)) => !settings.manual_offline_mode || settings.allow_immediate_scrobbling,
+        _ => true,
+    }
+}
+
+async fn report_lastfm(app_handle: &AppHandle, track: &QueueTrack) {
+    let app_state = app_handle.state::<AppState>();
+    let client = app_state.lastfm.lock().await;
+    if !client.is_authenticated() || !client.is_scrobbling_enabled() {
+        return;
+    }
+    match client
+        .update_now_playing(&track.artist, &track.title, album_name(track))
+        .await
+    {
+        Ok(()) => log::info!("Last.fm now playing: {} - {}", track.artist, track.title),
+        Err(e) => log::warn!("Last.fm now playing failed: {}", e),
+    }
+}
+
+async fn report_listenbrainz(app_handle: &AppHandle, track: &QueueTrack) {
+    let Some(lb_state) = app_handle.try_state::<ListenBrainzSharedState>() else {
+        return;
+    };
+    {
+        let client = lb_state.client.lock().await;
+        if !client.is_enabled().await || !client.is_authenticated().await {
+            return;
+        }
+    }
+
+    let mut info = AdditionalInfo::new();
+    info.duration_ms = Some(track.duration_secs * 1000);
+    if let Some(resolved) = resolve_recording(app_handle, track).await {
+        info.recording_mbid = resolved.mbid;
+        info.release_mbid = resolved.release_mbid;
+        info.artist_mbids = resolved.artist_mbids;
+    }
+
+    let client = lb_state.client.lock().await;
+    match client
+        .submit_playing_now(&track.artist, &track.title, album_name(track), Some(info))
+        .await
+    {
+        Ok(()) => log::info!(
+            "ListenBrainz now playing: {} - {}",
+            track.artist,
+            track.title
+        ),
+        Err(e) => log::warn!("ListenBrainz now playing failed: {}", e),
+    }
+}
+
+/// MusicBrainz recording for the track, when the match is confident enough
+/// to help ListenBrainz link it
+async fn resolve_recording(app_handle: &AppHandle, track: &QueueTrack) -> Option<ResolvedTrack> {
+    if let Ok(guard) = LAST_RESOLVED.lock() {
+        if let Some((track_id, resolved)) = guard.as_ref() {
+            if *track_id == track.id {
+                return resolved.clone();
+            }
+        }
+    }
+
+    let mb_state = app_handle.try_state::<MusicBrainzSharedState>()?;
+    let resolved =
+        crate::commands::musicbrainz::resolve_track(&mb_state, None, &track.title, &track.artist)
+            .await
+            .ok()
+            .filter(|resolved| resolved.confidence != MatchConfidence::None);
+
+    if let Ok(mut guard) = LAST_RESOLVED.lock() {
+        *guard = Some((track.id, resolved.clone()));
+    }
+    resolved
+}
+
+fn album_name(track: &QueueTrack) -> Option<&str> {
+    Some(track.album.as_str()).filter(|album| !album.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_reports_once_per_track_start() {
+        let start = Instant::now();
+        let mut tracker = NowPlayingTracker::default();
+        // Duration not known yet: the report waits for it
+        assert!(!tracker.update(1, true, 0, 0, start));
+        assert!(tracker.update(1, true, 0, 200, start + Duration::from_millis(500)));
+        assert!(!tracker.update(1, true, 1, 200, start + Duration::from_secs(1)));
+        assert!(!tracker.update(1, true, 2, 200, start + Duration::from_secs(2)));
+        // Next track
+        assert!(tracker.update(2, true, 0, 180, start + Duration::from_secs(3)));
+        // Too short to report
+        assert!(!tracker.update(3, true, 0, 30, start + Duration::from_secs(4)));
+        assert!(!tracker.update(0, false, 0, 0, start + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_pause_resume_and_seek() {
+        let start = Instant::now();
+        let mut tracker = NowPlayingTracker::default();
+        assert!(tracker.update(1, true, 0, 200, start));
+        assert!(!tracker.update(1, true, 10, 200, start + Duration::from_secs(10)));
+        // Paused for a minute, then resumed
+        assert!(!tracker.update(1, false, 10, 200, start + Duration::from_secs(11)));
+        assert!(!tracker.update(1, false, 10, 200, start + Duration::from_secs(70)));
+        assert!(tracker.update(1, true, 10, 200, start + Duration::from_secs(71)));
+        // Seek forward, then back
+        assert!(tracker.update(1, true, 120, 200, start + Duration::from_secs(72)));
+        assert!(!tracker.update(1, true, 121, 200, start + Duration::from_secs(73)));
+        assert!(tracker.update(1, true, 5, 200, start + Duration::from_secs(74)));
+    }
+}
@@ -13,6 +13,8 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
+use crate::network::ConnectionType;
+
 /// Reason why the app is in offline mode
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -29,6 +31,9 @@ pub struct OfflineStatus {
     pub is_offline: bool,
     pub reason: Option<OfflineReason>,
     pub manual_mode_enabled: bool,
+    /// Kind of connection in use, so the UI can warn before large downloads on metered links
+    #[serde(default)]
+    pub connection_type: ConnectionType,
 }
 
 /// Persistent offline settings
@@ -61,6 +66,79 @@ pub struct PendingPlaylist {
     pub created_at: i64,
     pub synced: bool,
     pub qobuz_playlist_id: Option<u64>,
+    /// Entries of `track_ids` already added to the Qobuz playlist
+    pub synced_track_ids: Vec<u64>,
+    /// Entries of `local_track_paths` already handled by a previous sync
+    pub synced_local_paths: Vec<String>,
+}
+
+impl PendingPlaylist {
+    /// Qobuz tracks that still have to be added to the remote playlist.
+    ///
+    /// A playlist may contain the same track twice, so each synced entry
+    /// only cancels out one occurrence.
+    pub fn unsynced_track_ids(&self) -> Vec<u64> {
+        let mut synced = self.synced_track_ids.clone();
+        self.track_ids
+            .iter()
+            .filter(|id| match synced.iter().position(|s| s == *id) {
+                Some(pos) => {
+                    synced.swap_remove(pos);
+                    false
+                }
+                None => true,
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Local track paths not handled yet, with their index in `local_track_paths`
+    pub fn unsynced_local_paths(&self) -> Vec<(usize, String)> {
+        let mut synced = self.synced_local_paths.clone();
+        self.local_track_paths
+            .iter()
+            .enumerate()
+            .filter(|(_, path)| match synced.iter().position(|s| s == *path) {
+                Some(pos) => {
+                    synced.swap_remove(pos);
+                    false
+                }
+                None => true,
+            })
+            .map(|(index, path)| (index, path.clone()))
+            .collect()
+    }
+}
+
+/// What a sync run did with one pending playlist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PendingPlaylistSyncOutcome {
+    /// The Qobuz playlist was created by this run
+    Created,
+    /// A playlist created by an earlier, interrupted run was completed
+    Updated,
+    /// Sync stopped with an error; the playlist stays queued
+    Failed,
+}
+
+/// Per-playlist sync result (payload of `offline:playlist_sync`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPlaylistSyncResult {
+    pub pending_id: i64,
+    pub name: String,
+    pub qobuz_playlist_id: Option<u64>,
+    pub outcome: PendingPlaylistSyncOutcome,
+    /// Qobuz tracks added by this run
+    pub added_tracks: usize,
+    /// Local files that resolved to a Qobuz track and were added as such
+    pub matched_local_tracks: usize,
+    /// Local files linked to the playlist as local tracks
+    pub linked_local_tracks: usize,
+    /// Local files no longer in the library
+    pub skipped_local_tracks: usize,
+    pub error: Option<String>,
 }
 
 /// A scrobble queued while offline, pending sync to Last.fm
@@ -76,6 +154,46 @@ pub struct QueuedScrobble {
     pub sent: bool,
 }
 
+/// Direction of a queued favorite change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FavoriteAction {
+    Add,
+    Remove,
+}
+
+impl FavoriteAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FavoriteAction::Add => "add",
+            FavoriteAction::Remove => "remove",
+        }
+    }
+
+    fn from_db(value: &str) -> Self {
+        if value == "remove" {
+            FavoriteAction::Remove
+        } else {
+            FavoriteAction::Add
+        }
+    }
+}
+
+/// A favorite change applied to the local cache, pending sync to Qobuz.
+/// Only the latest change per item is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedFavorite {
+    /// "track", "album" or "artist"
+    pub fav_type: String,
+    pub item_id: String,
+    pub action: FavoriteAction,
+    /// Failed sync attempts so far
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+}
+
 /// SQLite-backed storage for offline settings
 pub struct OfflineStore {
     conn: Connection,
@@ -139,7 +257,17 @@ impl OfflineStore {
                 created_at INTEGER NOT NULL,
                 sent INTEGER NOT NULL DEFAULT 0
             );
-            CREATE INDEX IF NOT EXISTS idx_scrobble_queue_sent ON scrobble_queue(sent);",
+            CREATE INDEX IF NOT EXISTS idx_scrobble_queue_sent ON scrobble_queue(sent);
+
+            CREATE TABLE IF NOT EXISTS favorite_sync_queue (
+                fav_type TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                action TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (fav_type, item_id)
+            );",
         )
         .map_err(|e| format!("Failed to create offline settings table: {}", e))?;
 
@@ -151,6 +279,8 @@ impl OfflineStore {
             "ALTER TABLE offline_settings ADD COLUMN show_network_folders_in_manual_offline INTEGER NOT NULL DEFAULT 0",
             "ALTER TABLE pending_playlist_sync ADD COLUMN local_track_ids TEXT",
             "ALTER TABLE pending_playlist_sync ADD COLUMN local_track_paths TEXT",
+            "ALTER TABLE pending_playlist_sync ADD COLUMN synced_track_ids TEXT",
+            "ALTER TABLE pending_playlist_sync ADD COLUMN synced_local_paths TEXT",
         ];
 
         for migration in migrations {
@@ -284,7 +414,9 @@ impl OfflineStore {
                 "SELECT id, name, description, is_public, track_ids,
                         COALESCE(local_track_ids, '[]'),
                         COALESCE(local_track_paths, '[]'),
-                        created_at, synced, qobuz_playlist_id
+                        created_at, synced, qobuz_playlist_id,
+                        COALESCE(synced_track_ids, '[]'),
+                        COALESCE(synced_local_paths, '[]')
                  FROM pending_playlist_sync WHERE synced = 0 ORDER BY created_at ASC",
             )
             .map_err(|e| format!("Failed to prepare statement: {}", e))?;
@@ -302,6 +434,14 @@ impl OfflineStore {
                 let local_track_paths: Vec<String> =
                     serde_json::from_str(&local_track_paths_json).unwrap_or_default();
 
+                let synced_track_ids_json: String = row.get(10)?;
+                let synced_track_ids: Vec<u64> =
+                    serde_json::from_str(&synced_track_ids_json).unwrap_or_default();
+
+                let synced_local_paths_json: String = row.get(11)?;
+                let synced_local_paths: Vec<String> =
+                    serde_json::from_str(&synced_local_paths_json).unwrap_or_default();
+
                 Ok(PendingPlaylist {
                     id: row.get(0)?,
                     name: row.get(1)?,
@@ -313,6 +453,8 @@ impl OfflineStore {
                     created_at: row.get(7)?,
                     synced: row.get::<_, i64>(8)? != 0,
                     qobuz_playlist_id: row.get::<_, Option<i64>>(9)?.map(|id| id as u64),
+                    synced_track_ids,
+                    synced_local_paths,
                 })
             })
             .map_err(|e| format!("Failed to query pending playlists: {}", e))?;
@@ -323,17 +465,29 @@ impl OfflineStore {
     }
 
     /// Update the Qobuz playlist ID without marking as synced (for partial sync recovery)
+    ///
+    /// Setting the same ID again is a no-op; linking a pending playlist to a
+    /// different Qobuz playlist is refused so a retried sync cannot orphan the
+    /// playlist it created first.
     pub fn update_qobuz_playlist_id(
         &self,
         pending_id: i64,
         qobuz_playlist_id: u64,
     ) -> Result<(), String> {
-        self.conn
+        let updated = self
+            .conn
             .execute(
-                "UPDATE pending_playlist_sync SET qobuz_playlist_id = ?1 WHERE id = ?2",
+                "UPDATE pending_playlist_sync SET qobuz_playlist_id = ?1
+                 WHERE id = ?2 AND (qobuz_playlist_id IS NULL OR qobuz_playlist_id = ?1)",
                 params![qobuz_playlist_id as i64, pending_id],
             )
             .map_err(|e| format!("Failed to update Qobuz playlist ID: {}", e))?;
+        if updated == 0 {
+            return Err(format!(
+                "Pending playlist {} not found or already linked to another Qobuz playlist",
+                pending_id
+            ));
+        }
         Ok(())
     }
 
@@ -343,12 +497,57 @@ impl OfflineStore {
         pending_id: i64,
         qobuz_playlist_id: u64,
     ) -> Result<(), String> {
-        self.conn
+        let updated = self
+            .conn
             .execute(
-                "UPDATE pending_playlist_sync SET synced = 1, qobuz_playlist_id = ?1 WHERE id = ?2",
+                "UPDATE pending_playlist_sync SET synced = 1, qobuz_playlist_id = ?1
+                 WHERE id = ?2 AND (qobuz_playlist_id IS NULL OR qobuz_playlist_id = ?1)",
                 params![qobuz_playlist_id as i64, pending_id],
             )
             .map_err(|e| format!("Failed to mark playlist as synced: {}", e))?;
+        if updated == 0 {
+            return Err(format!(
+                "Pending playlist {} not found or already linked to another Qobuz playlist",
+                pending_id
+            ));
+        }
+        Ok(())
+    }
+
+    /// Record tracks that made it into the Qobuz playlist, so a resumed sync skips them
+    pub fn record_synced_tracks(
+        &self,
+        pending_id: i64,
+        qobuz_track_ids: &[u64],
+        local_track_paths: &[String],
+    ) -> Result<(), String> {
+        let (synced_ids_json, synced_paths_json): (String, String) = self
+            .conn
+            .query_row(
+                "SELECT COALESCE(synced_track_ids, '[]'), COALESCE(synced_local_paths, '[]')
+                 FROM pending_playlist_sync WHERE id = ?1",
+                params![pending_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("Failed to get pending playlist: {}", e))?;
+
+        let mut synced_ids: Vec<u64> = serde_json::from_str(&synced_ids_json).unwrap_or_default();
+        let mut synced_paths: Vec<String> =
+            serde_json::from_str(&synced_paths_json).unwrap_or_default();
+        synced_ids.extend_from_slice(qobuz_track_ids);
+        synced_paths.extend_from_slice(local_track_paths);
+
+        let synced_ids_json = serde_json::to_string(&synced_ids)
+            .map_err(|e| format!("Failed to serialize synced track IDs: {}", e))?;
+        let synced_paths_json = serde_json::to_string(&synced_paths)
+            .map_err(|e| format!("Failed to serialize synced local paths: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE pending_playlist_sync SET synced_track_ids = ?1, synced_local_paths = ?2 WHERE id = ?3",
+                params![synced_ids_json, synced_paths_json, pending_id],
+            )
+            .map_err(|e| format!("Failed to record synced tracks: {}", e))?;
         Ok(())
     }
 
@@ -529,6 +728,124 @@ impl OfflineStore {
     }
 }
 
+impl OfflineStore {
+    // === Favorite Sync Queue Methods ===
+
+    /// Queue favorite changes, replacing any pending change for the same item
+    /// (favoriting then unfavoriting before a sync only sends the removal)
+    pub fn queue_favorites(
+        &self,
+        fav_type: &str,
+        item_ids: &[String],
+        action: FavoriteAction,
+    ) -> Result<(), String> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        for item_id in item_ids {
+            tx.execute(
+                "INSERT INTO favorite_sync_queue (fav_type, item_id, action, created_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(fav_type, item_id) DO UPDATE SET
+                    action = excluded.action,
+                    attempts = 0,
+                    last_error = NULL,
+                    created_at = excluded.created_at",
+                params![fav_type, item_id, action.as_str(), now],
+            )
+            .map_err(|e| format!("Failed to queue favorite: {}", e))?;
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit favorite queue: {}", e))
+    }
+
+    /// Pending favorite changes, oldest first
+    pub fn get_queued_favorites(&self) -> Result<Vec<QueuedFavorite>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT fav_type, item_id, action, attempts, last_error, created_at
+                 FROM favorite_sync_queue ORDER BY created_at ASC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let favorites = stmt
+            .query_map([], |row| {
+                Ok(QueuedFavorite {
+                    fav_type: row.get(0)?,
+                    item_id: row.get(1)?,
+                    action: FavoriteAction::from_db(&row.get::<_, String>(2)?),
+                    attempts: row.get::<_, i64>(3)? as u32,
+                    last_error: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query queued favorites: {}", e))?;
+
+        favorites
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect queued favorites: {}", e))
+    }
+
+    /// Drop synced changes. A change queued for the same item since the sync
+    /// started (a different action) is kept. Returns how many were dropped.
+    pub fn remove_queued_favorites(
+        &self,
+        fav_type: &str,
+        item_ids: &[String],
+        action: FavoriteAction,
+    ) -> Result<usize, String> {
+        let mut removed = 0;
+        for item_id in item_ids {
+            removed += self
+                .conn
+                .execute(
+                    "DELETE FROM favorite_sync_queue
+                     WHERE fav_type = ?1 AND item_id = ?2 AND action = ?3",
+                    params![fav_type, item_id, action.as_str()],
+                )
+                .map_err(|e| format!("Failed to remove queued favorite: {}", e))?;
+        }
+        Ok(removed)
+    }
+
+    /// Count a failed sync attempt for each item
+    pub fn record_favorite_sync_failure(
+        &self,
+        fav_type: &str,
+        item_ids: &[String],
+        action: FavoriteAction,
+        error: &str,
+    ) -> Result<(), String> {
+        for item_id in item_ids {
+            self.conn
+                .execute(
+                    "UPDATE favorite_sync_queue SET attempts = attempts + 1, last_error = ?4
+                     WHERE fav_type = ?1 AND item_id = ?2 AND action = ?3",
+                    params![fav_type, item_id, action.as_str(), error],
+                )
+                .map_err(|e| format!("Failed to record favorite sync failure: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Get count of favorite changes waiting to be synced
+    pub fn get_queued_favorite_count(&self) -> Result<u32, String> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM favorite_sync_queue", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map(|count| count as u32)
+            .map_err(|e| format!("Failed to count queued favorites: {}", e))
+    }
+}
+
 /// Thread-safe wrapper for OfflineStore
 pub struct OfflineState {
     pub store: Arc<Mutex<Option<OfflineStore>>>,
@@ -666,6 +983,7 @@ pub mod commands {
                 is_offline: true,
                 reason: Some(OfflineReason::ManualOverride),
                 manual_mode_enabled: true,
+                connection_type: ConnectionType::Unknown,
             });
         }
 
@@ -677,13 +995,19 @@ pub mod commands {
                 is_offline: true,
                 reason: Some(OfflineReason::NoNetwork),
                 manual_mode_enabled: false,
+                connection_type: ConnectionType::Unknown,
             });
         }
 
+        let connection_type = tokio::task::spawn_blocking(crate::network::get_connection_type)
+            .await
+            .unwrap_or_default();
+
         Ok(OfflineStatus {
             is_offline: false,
             reason: None,
             manual_mode_enabled: false,
+            connection_type,
         })
     }
 
@@ -937,6 +1261,195 @@ pub mod commands {
         store.delete_pending_playlist(pending_id)
     }
 
+    fn with_store<T>(
+        state: &OfflineState,
+        f: impl FnOnce(&OfflineStore) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let guard__ = state
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard__
+            .as_ref()
+            .ok_or("No active session - please log in")?;
+        f(store)
+    }
+
+    /// Sync all pending playlists to Qobuz.
+    ///
+    /// Safe to run again after an interrupted sync: a playlist that already has
+    /// a Qobuz ID is completed instead of recreated, and only tracks not yet
+    /// recorded as added are sent. Playlists that fail stay queued.
+    #[tauri::command]
+    pub async fn sync_pending_playlists(
+        app: AppHandle,
+        offline_state: State<'_, OfflineState>,
+        library_state: State<'_, crate::library::commands::LibraryState>,
+        app_state: State<'_, crate::AppState>,
+    ) -> Result<Vec<PendingPlaylistSyncResult>, String> {
+        let pending = with_store(&offline_state, |store| store.get_pending_playlists())?;
+        log::info!(
+            "Command: sync_pending_playlists ({} pending)",
+            pending.len()
+        );
+
+        let mut results = Vec::with_capacity(pending.len());
+        for playlist in pending {
+            let mut result = PendingPlaylistSyncResult {
+                pending_id: playlist.id,
+                name: playlist.name.clone(),
+                qobuz_playlist_id: playlist.qobuz_playlist_id,
+                outcome: if playlist.qobuz_playlist_id.is_some() {
+                    PendingPlaylistSyncOutcome::Updated
+                } else {
+                    PendingPlaylistSyncOutcome::Created
+                },
+                added_tracks: 0,
+                matched_local_tracks: 0,
+                linked_local_tracks: 0,
+                skipped_local_tracks: 0,
+                error: None,
+            };
+
+            if let Err(e) = sync_pending_playlist(
+                &playlist,
+                &mut result,
+                &offline_state,
+                &library_state,
+                &app_state,
+            )
+            .await
+            {
+                log::warn!(
+                    "Failed to sync pending playlist \"{}\": {}",
+                    playlist.name,
+                    e
+                );
+                result.outcome = PendingPlaylistSyncOutcome::Failed;
+                result.error = Some(e);
+            }
+
+            let _ = app.emit("offline:playlist_sync", &result);
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    async fn sync_pending_playlist(
+        playlist: &PendingPlaylist,
+        result: &mut PendingPlaylistSyncResult,
+        offline_state: &OfflineState,
+        library_state: &crate::library::commands::LibraryState,
+        app_state: &crate::AppState,
+    ) -> Result<(), String> {
+        let qobuz_playlist_id = match playlist.qobuz_playlist_id {
+            Some(id) => id,
+            None => {
+                let created = app_state
+                    .client
+                    .read()
+                    .await
+                    .create_playlist(
+                        &playlist.name,
+                        playlist.description.as_deref(),
+                        playlist.is_public,
+                    )
+                    .await
+                    .map_err(|e| format!("Failed to create playlist: {}", e))?;
+                // Persist the link right away so a retry never creates a second copy
+                with_store(offline_state, |store| {
+                    store.update_qobuz_playlist_id(playlist.id, created.id)
+                })?;
+                created.id
+            }
+        };
+        result.qobuz_playlist_id = Some(qobuz_playlist_id);
+
+        let remaining = playlist.unsynced_track_ids();
+        if !remaining.is_empty() {
+            app_state
+                .client
+                .read()
+                .await
+                .add_tracks_to_playlist(qobuz_playlist_id, &remaining)
+                .await
+                .map_err(|e| format!("Failed to add tracks to playlist: {}", e))?;
+            with_store(offline_state, |store| {
+                store.record_synced_tracks(playlist.id, &remaining, &[])
+            })?;
+            result.added_tracks += remaining.len();
+        }
+
+        // Local files: add the Qobuz original when the file came from Qobuz,
+        // otherwise link the file to the playlist as a local track
+        for (index, path) in playlist.unsynced_local_paths() {
+            let track = {
+                let guard__ = library_state.db.lock().await;
+                let db = guard__
+                    .as_ref()
+                    .ok_or("No active session - please log in")?;
+                db.get_track_by_path(&path).map_err(|e| e.to_string())?
+            };
+
+            match track {
+                None => {
+                    log::warn!("Pending playlist file not in library, skipping: {}", path);
+                    result.skipped_local_tracks += 1;
+                }
+                Some(track) => match track.qobuz_track_id {
+                    Some(qobuz_track_id) => {
+                        app_state
+                            .client
+                            .read()
+                            .await
+                            .add_tracks_to_playlist(qobuz_playlist_id, &[qobuz_track_id as u64])
+                            .await
+                            .map_err(|e| format!("Failed to add tracks to playlist: {}", e))?;
+                        result.matched_local_tracks += 1;
+                    }
+                    None => {
+                        let position = (playlist.track_ids.len() + index) as i32;
+                        let guard__ = library_state.db.lock().await;
+                        let db = guard__
+                            .as_ref()
+                            .ok_or("No active session - please log in")?;
+                        db.add_local_track_to_playlist(qobuz_playlist_id, track.id, position)
+                            .map_err(|e| format!("Failed to add local track: {}", e))?;
+                        result.linked_local_tracks += 1;
+                    }
+                },
+            }
+
+            with_store(offline_state, |store| {
+                store.record_synced_tracks(playlist.id, &[], std::slice::from_ref(&path))
+            })?;
+        }
+
+        // Legacy playlists queued before paths were stored only have library IDs.
+        // Linking is an upsert, so repeating it on a resumed sync is harmless.
+        if playlist.local_track_paths.is_empty() {
+            for (index, local_track_id) in playlist.local_track_ids.iter().enumerate() {
+                let position = (playlist.track_ids.len() + index) as i32;
+                let guard__ = library_state.db.lock().await;
+                let db = guard__
+                    .as_ref()
+                    .ok_or("No active session - please log in")?;
+                match db.add_local_track_to_playlist(qobuz_playlist_id, *local_track_id, position) {
+                    Ok(()) => result.linked_local_tracks += 1,
+                    Err(e) if e.to_string().contains("FOREIGN KEY constraint") => {
+                        result.skipped_local_tracks += 1;
+                    }
+                    Err(e) => return Err(format!("Failed to add local track: {}", e)),
+                }
+            }
+        }
+
+        with_store(offline_state, |store| {
+            store.mark_playlist_synced(playlist.id, qobuz_playlist_id)
+        })
+    }
+
     // === Scrobble Queue Commands ===
 
     /// Queue a scrobble for later submission (when offline)
@@ -1019,3 +1532,79 @@ pub mod commands {
         store.cleanup_sent_scrobbles(older_than_days.unwrap_or(7))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn resumed_sync_only_sends_remaining_tracks() {
+        let dir = tempdir().unwrap();
+        let store = OfflineStore::new_at(dir.path()).unwrap();
+        let paths = vec!["/music/a.flac".to_string(), "/music/b.flac".to_string()];
+        let pending_id = store
+            .create_pending_playlist("Road trip", None, false, &[10, 20, 10], &paths)
+            .unwrap();
+
+        // First run: playlist created, one batch of tracks and one file made it
+        store.update_qobuz_playlist_id(pending_id, 900).unwrap();
+        store
+            .record_synced_tracks(pending_id, &[10, 20], &paths[..1])
+            .unwrap();
+
+        let playlist = store.get_pending_playlists().unwrap().remove(0);
+        assert_eq!(playlist.qobuz_playlist_id, Some(900));
+        assert_eq!(playlist.unsynced_track_ids(), vec![10]);
+        assert_eq!(
+            playlist.unsynced_local_paths(),
+            vec![(1, "/music/b.flac".to_string())]
+        );
+
+        // Second run reuses the playlist and refuses to relink it elsewhere
+        store.update_qobuz_playlist_id(pending_id, 900).unwrap();
+        assert!(store.update_qobuz_playlist_id(pending_id, 901).is_err());
+        assert!(store.mark_playlist_synced(pending_id, 901).is_err());
+
+        store
+            .record_synced_tracks(pending_id, &[10], &paths[1..])
+            .unwrap();
+        store.mark_playlist_synced(pending_id, 900).unwrap();
+        assert!(store.get_pending_playlists().unwrap().is_empty());
+    }
+
+    #[test]
+    fn favorite_queue_keeps_latest_change_per_item() {
+        let dir = tempdir().unwrap();
+        let store = OfflineStore::new_at(dir.path()).unwrap();
+        let ids = vec!["1".to_string(), "2".to_string()];
+
+        store
+            .queue_favorites("track", &ids, FavoriteAction::Add)
+            .unwrap();
+        store
+            .record_favorite_sync_failure("track", &ids[..1], FavoriteAction::Add, "500")
+            .unwrap();
+        store
+            .queue_favorites("track", &ids[1..], FavoriteAction::Remove)
+            .unwrap();
+
+        let queued = store.get_queued_favorites().unwrap();
+        assert_eq!(queued.len(), 2);
+        let first = queued.iter().find(|f| f.item_id == "1").unwrap();
+        assert_eq!(first.attempts, 1);
+        assert_eq!(first.last_error.as_deref(), Some("500"));
+        let second = queued.iter().find(|f| f.item_id == "2").unwrap();
+        assert_eq!(second.action, FavoriteAction::Remove);
+
+        // Syncing the add must not drop the removal queued since
+        let removed = store
+            .remove_queued_favorites("track", &ids, FavoriteAction::Add)
+            .unwrap();
+        assert_eq!(removed, 1);
+        let queued = store.get_queued_favorites().unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].item_id, "2");
+        assert_eq!(store.get_queued_favorite_count().unwrap(), 1);
+    }
+}
@@ -0,0 +1,338 @@
+//! Batch caching of whole albums and playlists
+//!
+//! A batch walks its track list in order and hands each track to the shared
+//! cache semaphore, so batches and single-track requests share the same
+//! concurrency limit. Pausing and cancelling take effect between tracks;
+//! downloads already running finish (an interrupted one would be resumed
+//! from its partial file anyway).
+//!
+//! On a metered connection a batch pauses itself before its next track and
+//! resumes once the connection is unmetered again, or when the user resumes
+//! it explicitly (which allows metered downloads for the rest of the batch).
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+/// How often a batch paused for a metered connection re-checks the connection
+pub const METERED_RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What a batch was created from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBatchKind {
+    Album,
+    Playlist,
+    /// An artist's discography downloaded to the download folder
+    Discography,
+}
+
+/// Lifecycle of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBatchStatus {
+    Running,
+    Paused,
+    Cancelled,
+    Completed,
+}
+
+/// Overall progress of a batch (payload of `offline:batch_progress`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheBatchProgress {
+    pub batch_id: u64,
+    pub kind: CacheBatchKind,
+    /// Album or playlist ID
+    pub source_id: String,
+    pub title: String,
+    pub total_tracks: usize,
+    /// Tracks that were already cached when the batch started
+    pub skipped_tracks: usize,
+    pub completed_tracks: usize,
+    pub failed_tracks: usize,
+    pub status: CacheBatchStatus,
+    /// Paused automatically because the connection is metered
+    pub paused_for_metered: bool,
+}
+
+impl CacheBatchProgress {
+    /// Tracks that still have to be downloaded (or were dropped by a cancel)
+    pub fn pending_tracks(&self) -> usize {
+        self.total_tracks
+            .saturating_sub(self.skipped_tracks + self.completed_tracks + self.failed_tracks)
+    }
+
+    pub fn progress_percent(&self) -> u8 {
+        if self.total_tracks == 0 {
+            return 100;
+        }
+        let done = self.total_tracks - self.pending_tracks();
+        ((done * 100) / self.total_tracks) as u8
+    }
+}
+
+/// A running batch: its progress plus the pause/cancel switches
+pub struct CacheBatch {
+    progress: Mutex<CacheBatchProgress>,
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    /// Set when the user resumed a batch paused for a metered connection
+    allow_metered: AtomicBool,
+    resumed: Notify,
+}
+
+impl CacheBatch {
+    pub fn progress(&self) -> CacheBatchProgress {
+        self.progress.lock().unwrap().clone()
+    }
+
+    /// Apply a change to the progress and return the updated snapshot
+    pub fn update(&self, f: impl FnOnce(&mut CacheBatchProgress)) -> CacheBatchProgress {
+        let mut progress = self.progress.lock().unwrap();
+        f(&mut progress);
+        progress.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn pause(&self) -> CacheBatchProgress {
+        self.paused.store(true, Ordering::SeqCst);
+        self.update(|p| {
+            if p.status == CacheBatchStatus::Running {
+                p.status = CacheBatchStatus::Paused;
+            }
+        })
+    }
+
+    /// Resume on user request; a metered connection no longer pauses the batch
+    pub fn resume(&self) -> CacheBatchProgress {
+        if self.progress().paused_for_metered {
+            self.allow_metered.store(true, Ordering::SeqCst);
+        }
+        self.resume_with(|_| true)
+    }
+
+    pub fn allows_metered(&self) -> bool {
+        self.allow_metered.load(Ordering::SeqCst)
+    }
+
+    /// Pause because the connection became metered
+    pub fn pause_for_metered(&self) -> CacheBatchProgress {
+        self.paused.store(true, Ordering::SeqCst);
+        self.update(|p| {
+            if p.status == CacheBatchStatus::Running {
+                p.status = CacheBatchStatus::Paused;
+                p.paused_for_metered = true;
+            }
+        })
+    }
+
+    /// Resume a batch paused for a metered connection (a manual pause is kept)
+    pub fn resume_unmetered(&self) -> Option<CacheBatchProgress> {
+        if !self.progress().paused_for_metered {
+            return None;
+        }
+        Some(self.resume_with(|p| p.paused_for_metered))
+    }
+
+    fn resume_with(&self, allowed: impl FnOnce(&CacheBatchProgress) -> bool) -> CacheBatchProgress {
+        let mut progress = self.progress.lock().unwrap();
+        if allowed(&progress) && progress.status == CacheBatchStatus::Paused {
+            progress.status = CacheBatchStatus::Running;
+            progress.paused_for_metered = false;
+            self.paused.store(false, Ordering::SeqCst);
+            self.resumed.notify_waiters();
+        }
+        progress.clone()
+    }
+
+    pub fn cancel(&self) -> CacheBatchProgress {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
+        self.resumed.notify_waiters();
+        self.update(|p| {
+            if p.status != CacheBatchStatus::Completed {
+                p.status = CacheBatchStatus::Cancelled;
+            }
+        })
+    }
+
+    /// Wait while the batch is paused; returns false once it was cancelled
+    pub async fn wait_until_runnable(&self) -> bool {
+        loop {
+            let resumed = self.resumed.notified();
+            if self.is_cancelled() {
+                return false;
+            }
+            if !self.paused.load(Ordering::SeqCst) {
+                return true;
+            }
+            resumed.await;
+        }
+    }
+
+    /// Take a slot of the shared cache semaphore for the next track.
+    ///
+    /// A slot that arrives after the batch was paused is handed back so other
+    /// downloads can use it while this batch waits. Returns None once cancelled.
+    pub async fn acquire_slot(&self, semaphore: &Arc<Semaphore>) -> Option<OwnedSemaphorePermit> {
+        loop {
+            if !self.wait_until_runnable().await {
+                return None;
+            }
+            let permit = semaphore.clone().acquire_owned().await.ok()?;
+            if self.is_cancelled() {
+                return None;
+            }
+            if !self.paused.load(Ordering::SeqCst) {
+                return Some(permit);
+            }
+        }
+    }
+}
+
+/// All batches of the current session, finished ones included until pruned
+#[derive(Default)]
+pub struct CacheBatchRegistry {
+    next_id: AtomicU64,
+    batches: Mutex<HashMap<u64, Arc<CacheBatch>>>,
+}
+
+impl CacheBatchRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create(
+        &self,
+        kind: CacheBatchKind,
+        source_id: String,
+        title: String,
+        total_tracks: usize,
+        skipped_tracks: usize,
+    ) -> Arc<CacheBatch> {
+        let batch_id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let batch = Arc::new(CacheBatch {
+            progress: Mutex::new(CacheBatchProgress {
+                batch_id,
+                kind,
+                source_id,
+                title,
+                total_tracks,
+                skipped_tracks,
+                completed_tracks: 0,
+                failed_tracks: 0,
+                status: CacheBatchStatus::Running,
+                paused_for_metered: false,
+            }),
+            paused: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            allow_metered: AtomicBool::new(false),
+            resumed: Notify::new(),
+        });
+
+        let mut batches = self.batches.lock().unwrap();
+        // Forget batches that are over so the list does not grow forever
+        batches.retain(|_, b| {
+            !matches!(
+                b.progress().status,
+                CacheBatchStatus::Completed | CacheBatchStatus::Cancelled
+            )
+        });
+        batches.insert(batch_id, batch.clone());
+        batch
+    }
+
+    pub fn get(&self, batch_id: u64) -> Option<Arc<CacheBatch>> {
+        self.batches.lock().unwrap().get(&batch_id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<CacheBatchProgress> {
+        let mut list: Vec<_> = self
+            .batches
+            .lock()
+            .unwrap()
+            .values()
+            .map(|b| b.progress())
+            .collect();
+        list.sort_by_key(|p| p.batch_id);
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_counts_skipped_tracks_as_done() {
+        let registry = CacheBatchRegistry::new();
+        let batch = registry.create(CacheBatchKind::Album, "a1".into(), "Album".into(), 4, 1);
+        let progress = batch.update(|p| p.completed_tracks += 1);
+        assert_eq!(progress.pending_tracks(), 2);
+        assert_eq!(progress.progress_percent(), 50);
+    }
+
+    #[test]
+    fn cancel_wins_over_pause() {
+        let registry = CacheBatchRegistry::new();
+        let batch = registry.create(CacheBatchKind::Playlist, "7".into(), "Mix".into(), 2, 0);
+        assert_eq!(batch.pause().status, CacheBatchStatus::Paused);
+        assert_eq!(batch.cancel().status, CacheBatchStatus::Cancelled);
+        assert_eq!(batch.resume().status, CacheBatchStatus::Cancelled);
+        assert!(batch.is_cancelled());
+    }
+
+    #[test]
+    fn metered_pause_resumes_on_its_own_or_by_user() {
+        let registry = CacheBatchRegistry::new();
+        let batch = registry.create(CacheBatchKind::Album, "a2".into(), "Live".into(), 3, 0);
+        assert!(batch.pause_for_metered().paused_for_metered);
+        assert_eq!(
+            batch.resume_unmetered().unwrap().status,
+            CacheBatchStatus::Running
+        );
+        assert!(!batch.allows_metered());
+
+        // A manual pause is not lifted by the connection check
+        batch.pause();
+        assert!(batch.resume_unmetered().is_none());
+
+        batch.resume();
+        batch.pause_for_metered();
+        assert!(!batch.resume().paused_for_metered);
+        assert!(batch.allows_metered());
+    }
+
+    #[tokio::test]
+    async fn paused_batch_gives_its_slot_back() {
+        let registry = CacheBatchRegistry::new();
+        let batch = registry.create(CacheBatchKind::Album, "a3".into(), "Box".into(), 2, 0);
+        let semaphore = Arc::new(Semaphore::new(1));
+
+        // The batch waits for the only slot, then gets paused before it frees up
+        let other = semaphore.clone().acquire_owned().await.unwrap();
+        let waiting = tokio::spawn({
+            let batch = batch.clone();
+            let semaphore = semaphore.clone();
+            async move { batch.acquire_slot(&semaphore).await.is_some() }
+        });
+        tokio::task::yield_now().await;
+        batch.pause();
+        drop(other);
+        for _ in 0..3 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(semaphore.available_permits(), 1);
+
+        batch.resume();
+        assert!(waiting.await.unwrap());
+    }
+}
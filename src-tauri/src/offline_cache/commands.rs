@@ -3,14 +3,17 @@
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, State};
 
-use crate::api::models::Quality;
+use crate::api::models::{Quality, Track};
+use crate::commands::playback::parse_quality;
 use crate::AppState;
 
 use crate::offline_cache::OfflineCacheState;
+use crate::offline_cache::downloader::{partial_path, partial_paths};
 use crate::offline_cache::metadata::{fetch_complete_metadata, write_flac_tags, embed_artwork, organize_cached_file, save_album_artwork};
+use super::batch::METERED_RECHECK_INTERVAL;
 use super::{
-    CachedTrackInfo, OfflineCacheStats, OfflineCacheStatus,
-    TrackCacheInfo,
+    CacheBatchKind, CacheBatchProgress, CacheBatchStatus, CachedTrackInfo, OfflineCacheStats,
+    OfflineCacheStatus, TrackCacheInfo,
 };
 
 /// Post-process a cached track: fetch metadata, tag FLAC, embed artwork, organize files
@@ -153,13 +156,7 @@ pub async fn cache_track_for_offline(
         db.insert_track(&track_info, &file_path_str)?;
     }
 
-    // Clone what we need for the spawn
-    let client = state.client.clone();
-    let fetcher = cache_state.fetcher.clone();
-    let db = cache_state.db.clone();
-    let offline_root = cache_state.get_cache_path();
-    let library_db = library_state.db.clone();
-    let app = app_handle.clone();
+    let ctx = CacheTaskContext::new(&state, &cache_state, &library_state, &app_handle);
     let semaphore = cache_state.cache_semaphore.clone();
 
     // Spawn caching task
@@ -168,14 +165,14 @@ pub async fn cache_track_for_offline(
             Ok(permit) => permit,
             Err(err) => {
                 log::error!("Failed to acquire cache slot for track {}: {}", track_id, err);
-                if let Some(db_guard) = db.lock().await.as_ref() {
+                if let Some(db_guard) = ctx.db.lock().await.as_ref() {
                     let _ = db_guard.update_status(
                         track_id,
                         OfflineCacheStatus::Failed,
                         Some("Failed to start caching"),
                     );
                 }
-                let _ = app.emit("offline:caching_failed", serde_json::json!({
+                let _ = ctx.app.emit("offline:caching_failed", serde_json::json!({
                     "trackId": track_id,
                     "error": "Failed to acquire cache slot"
                 }));
@@ -183,107 +180,462 @@ pub async fn cache_track_for_offline(
             }
         };
 
-        // Update status to caching
-        {
-            if let Some(db_guard) = db.lock().await.as_ref() {
-                let _ = db_guard.update_status(track_id, OfflineCacheStatus::Downloading, None);
-            }
+        let _ = run_cache_task(&ctx, track_id, file_path, Quality::UltraHiRes).await;
+    });
+
+    Ok(())
+}
+
+/// Handles a background caching task needs after the command returned
+#[derive(Clone)]
+struct CacheTaskContext {
+    client: Arc<tokio::sync::RwLock<crate::api::QobuzClient>>,
+    fetcher: Arc<crate::offline_cache::StreamFetcher>,
+    db: Arc<tokio::sync::Mutex<Option<crate::offline_cache::OfflineCacheDb>>>,
+    offline_root: String,
+    library_db: Arc<tokio::sync::Mutex<Option<crate::library::database::LibraryDatabase>>>,
+    limit_bytes: Arc<tokio::sync::Mutex<Option<u64>>>,
+    cache_dir: Arc<std::sync::RwLock<std::path::PathBuf>>,
+    app: AppHandle,
+}
+
+impl CacheTaskContext {
+    fn new(
+        state: &AppState,
+        cache_state: &OfflineCacheState,
+        library_state: &crate::library::commands::LibraryState,
+        app_handle: &AppHandle,
+    ) -> Self {
+        Self {
+            client: state.client.clone(),
+            fetcher: cache_state.fetcher.clone(),
+            db: cache_state.db.clone(),
+            offline_root: cache_state.get_cache_path(),
+            library_db: library_state.db.clone(),
+            limit_bytes: cache_state.limit_bytes.clone(),
+            cache_dir: cache_state.cache_dir.clone(),
+            app: app_handle.clone(),
         }
+    }
+}
 
-        let _ = app.emit("offline:caching_started", serde_json::json!({
-            "trackId": track_id
-        }));
+/// Download, tag and organize a queued track, emitting the per-track events.
+/// The caller holds a cache semaphore permit.
+async fn run_cache_task(
+    ctx: &CacheTaskContext,
+    track_id: u64,
+    file_path: std::path::PathBuf,
+    quality: Quality,
+) -> Result<u64, String> {
+    let CacheTaskContext { client, fetcher, db, offline_root, library_db, app, .. } = ctx;
 
-        // Get stream URL with highest quality available
-        let stream_url = {
-            let client_guard = client.read().await;
-            client_guard
-                .get_stream_url_with_fallback(track_id, Quality::UltraHiRes)
-                .await
-        };
+    // Update status to caching
+    {
+        if let Some(db_guard) = db.lock().await.as_ref() {
+            let _ = db_guard.update_status(track_id, OfflineCacheStatus::Downloading, None);
+        }
+    }
+
+    let _ = app.emit("offline:caching_started", serde_json::json!({
+        "trackId": track_id
+    }));
+
+    // Get stream URL, falling back to lower qualities when needed
+    let stream_url = {
+        let client_guard = client.read().await;
+        client_guard
+            .get_stream_url_with_fallback(track_id, quality)
+            .await
+    };
 
-        let url = match stream_url {
-            Ok(s) => s.url,
-            Err(e) => {
-                log::error!("Failed to get stream URL for track {}: {}", track_id, e);
+    let (url, format_id) = match stream_url {
+        Ok(s) => (s.url, s.format_id),
+        Err(e) => {
+            log::error!("Failed to get stream URL for track {}: {}", track_id, e);
+            if let Some(db_guard) = db.lock().await.as_ref() {
+                let _ = db_guard.update_status(
+                    track_id,
+                    OfflineCacheStatus::Failed,
+                    Some(&format!("Failed to get stream URL: {}", e)),
+                );
+            }
+            let _ = app.emit("offline:caching_failed", serde_json::json!({
+                "trackId": track_id,
+                "error": e.to_string()
+            }));
+            return Err(e.to_string());
+        }
+    };
+
+    // Fetch and cache the file
+    match fetcher
+        .fetch_to_file(&url, &file_path, track_id, format_id, Some(app))
+        .await
+    {
+        Ok(size) => {
+            log::info!("Caching complete for track {}: {} bytes", track_id, size);
+            {
                 if let Some(db_guard) = db.lock().await.as_ref() {
-                    let _ = db_guard.update_status(
-                        track_id,
-                        OfflineCacheStatus::Failed,
-                        Some(&format!("Failed to get stream URL: {}", e)),
-                    );
+                    let _ = db_guard.mark_complete(track_id, size);
                 }
-                let _ = app.emit("offline:caching_failed", serde_json::json!({
-                    "trackId": track_id,
-                    "error": e.to_string()
-                }));
-                return;
             }
-        };
 
-        // Fetch and cache the file
-        match fetcher
-            .fetch_to_file(&url, &file_path, track_id, Some(&app))
-            .await
-        {
-            Ok(size) => {
-                log::info!("Caching complete for track {}: {} bytes", track_id, size);
-                {
+            let _ = app.emit("offline:caching_completed", serde_json::json!({
+                "trackId": track_id,
+                "size": size
+            }));
+
+            // Post-processing: metadata, tagging, artwork, organization
+            log::info!("Starting post-processing for cached track {}", track_id);
+
+            let file_path_str = file_path.to_string_lossy().to_string();
+            let qobuz_client = client.read().await;
+            match post_process_cached_track(
+                track_id,
+                &file_path_str,
+                offline_root,
+                &qobuz_client,
+                library_db.clone(),
+            ).await {
+                Ok(new_path) => {
+                    // Update database with new path
                     if let Some(db_guard) = db.lock().await.as_ref() {
-                        let _ = db_guard.mark_complete(track_id, size);
+                        if let Err(e) = db_guard.update_file_path(track_id, &new_path) {
+                            log::error!("Failed to update path for track {}: {}", track_id, e);
+                        }
                     }
+
+                    let _ = app.emit("offline:caching_processed", serde_json::json!({
+                        "trackId": track_id,
+                        "path": new_path
+                    }));
+                }
+                Err(e) => {
+                    log::error!("Post-processing failed for cached track {}: {}", track_id, e);
+                    // File still exists and is playable, just not organized
                 }
+            }
+            Ok(size)
+        }
+        Err(e) => {
+            // Bytes kept in the partial file; caching again resumes from there
+            let resumable_bytes = std::fs::metadata(partial_path(&file_path, format_id))
+                .map(|m| m.len())
+                .unwrap_or(0);
+            log::error!(
+                "Caching failed for track {} ({} bytes kept for resume): {}",
+                track_id,
+                resumable_bytes,
+                e
+            );
+            if let Some(db_guard) = db.lock().await.as_ref() {
+                let _ = db_guard.update_status(track_id, OfflineCacheStatus::Failed, Some(&e));
+            }
+            let _ = app.emit("offline:caching_failed", serde_json::json!({
+                "trackId": track_id,
+                "error": e,
+                "resumableBytes": resumable_bytes
+            }));
+            Err(e)
+        }
+    }
+}
 
-                let _ = app.emit("offline:caching_completed", serde_json::json!({
-                    "trackId": track_id,
-                    "size": size
-                }));
+/// Cache every track of an album for offline playback
+///
+/// Returns the batch ID used by `offline:batch_progress` events and the
+/// pause/resume/cancel commands.
+#[tauri::command]
+pub async fn cache_album_for_offline(
+    album_id: String,
+    quality: Option<String>,
+    state: State<'_, AppState>,
+    cache_state: State<'_, OfflineCacheState>,
+    library_state: State<'_, crate::library::commands::LibraryState>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    log::info!("Command: cache_album_for_offline {} (quality: {:?})", album_id, quality);
 
-                // Post-processing: metadata, tagging, artwork, organization
-                log::info!("Starting post-processing for cached track {}", track_id);
+    let album = {
+        let client = state.client.read().await;
+        client.get_album(&album_id).await.map_err(|e| e.to_string())?
+    };
+    let quality = parse_quality(quality.as_deref());
+    // album/get does not repeat the album on each track
+    let tracks = album
+        .tracks
+        .as_ref()
+        .map(|t| t.items.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|t| t.streamable)
+        .map(|t| {
+            let mut info = track_cache_info(t, quality);
+            info.album.get_or_insert_with(|| album.title.clone());
+            info.album_id.get_or_insert_with(|| album.id.clone());
+            if info.artist.is_empty() {
+                info.artist = album.artist.name.clone();
+            }
+            info
+        })
+        .collect();
 
-                let file_path_str = file_path.to_string_lossy().to_string();
-                let qobuz_client = client.read().await;
-                match post_process_cached_track(
-                    track_id,
-                    &file_path_str,
-                    &offline_root,
-                    &*qobuz_client,
-                    library_db.clone(),
-                ).await {
-                    Ok(new_path) => {
-                        // Update database with new path
-                        if let Some(db_guard) = db.lock().await.as_ref() {
-                            if let Err(e) = db_guard.update_file_path(track_id, &new_path) {
-                                log::error!("Failed to update path for track {}: {}", track_id, e);
-                            }
-                        }
+    start_cache_batch(
+        CacheBatchKind::Album,
+        album_id,
+        album.title.clone(),
+        tracks,
+        quality,
+        &state,
+        &cache_state,
+        &library_state,
+        &app_handle,
+    )
+    .await
+}
 
-                        let _ = app.emit("offline:caching_processed", serde_json::json!({
-                            "trackId": track_id,
-                            "path": new_path
-                        }));
-                    }
-                    Err(e) => {
-                        log::error!("Post-processing failed for cached track {}: {}", track_id, e);
-                        // File still exists and is playable, just not organized
+/// Cache every track of a playlist for offline playback
+///
+/// Returns the batch ID, as `cache_album_for_offline` does.
+#[tauri::command]
+pub async fn cache_playlist_for_offline(
+    playlist_id: u64,
+    quality: Option<String>,
+    state: State<'_, AppState>,
+    cache_state: State<'_, OfflineCacheState>,
+    library_state: State<'_, crate::library::commands::LibraryState>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    log::info!("Command: cache_playlist_for_offline {} (quality: {:?})", playlist_id, quality);
+
+    let playlist = {
+        let client = state.client.read().await;
+        client.get_playlist(playlist_id).await.map_err(|e| e.to_string())?
+    };
+    let quality = parse_quality(quality.as_deref());
+    let tracks = playlist
+        .tracks
+        .map(|t| t.items)
+        .unwrap_or_default()
+        .iter()
+        .filter(|t| t.streamable)
+        .map(|t| track_cache_info(t, quality))
+        .collect();
+
+    start_cache_batch(
+        CacheBatchKind::Playlist,
+        playlist_id.to_string(),
+        playlist.name,
+        tracks,
+        quality,
+        &state,
+        &cache_state,
+        &library_state,
+        &app_handle,
+    )
+    .await
+}
+
+fn track_cache_info(track: &Track, quality: Quality) -> TrackCacheInfo {
+    TrackCacheInfo {
+        track_id: track.id,
+        title: track.title.clone(),
+        artist: track
+            .performer
+            .as_ref()
+            .map(|a| a.name.clone())
+            .unwrap_or_default(),
+        album: track.album.as_ref().map(|a| a.title.clone()),
+        album_id: track.album.as_ref().map(|a| a.id.clone()),
+        duration_secs: track.duration as u64,
+        quality: quality.label().to_string(),
+        bit_depth: track.maximum_bit_depth,
+        sample_rate: track.maximum_sampling_rate,
+    }
+}
+
+async fn on_metered_connection() -> bool {
+    tokio::task::spawn_blocking(crate::network::get_connection_type)
+        .await
+        .is_ok_and(|connection| connection.is_metered())
+}
+
+/// Queue the uncached tracks of an album/playlist and run them in the background
+#[allow(clippy::too_many_arguments)]
+async fn start_cache_batch(
+    kind: CacheBatchKind,
+    source_id: String,
+    title: String,
+    tracks: Vec<TrackCacheInfo>,
+    quality: Quality,
+    state: &AppState,
+    cache_state: &OfflineCacheState,
+    library_state: &crate::library::commands::LibraryState,
+    app_handle: &AppHandle,
+) -> Result<u64, String> {
+    let total = tracks.len();
+
+    // Skip tracks that are already cached (or being cached by another request)
+    let mut queued = Vec::new();
+    {
+        let guard__ = cache_state.db.lock().await;
+        let db = guard__.as_ref().ok_or("No active session - please log in")?;
+        for info in tracks {
+            let already_queued = db.get_track(info.track_id)?.is_some_and(|t| {
+                t.status != OfflineCacheStatus::Failed
+            });
+            if already_queued {
+                continue;
+            }
+
+            let file_path = cache_state.track_file_path(info.track_id, "flac");
+            db.insert_track(&info, &file_path.to_string_lossy())?;
+            queued.push((info.track_id, file_path));
+        }
+    }
+
+    let batch = cache_state.batches.create(kind, source_id, title, total, total - queued.len());
+    let batch_id = batch.progress().batch_id;
+    log::info!(
+        "Offline batch {}: {} of {} tracks queued",
+        batch_id,
+        queued.len(),
+        total
+    );
+
+    let ctx = CacheTaskContext::new(state, cache_state, library_state, app_handle);
+    let semaphore = cache_state.cache_semaphore.clone();
+    let _ = app_handle.emit("offline:batch_progress", batch.progress());
+
+    tokio::spawn(async move {
+        let mut running = tokio::task::JoinSet::new();
+        let mut pending = queued.into_iter();
+        let mut unstarted = Vec::new();
+
+        while let Some((track_id, file_path)) = pending.next() {
+            if !batch.allows_metered() && on_metered_connection().await {
+                let progress = batch.pause_for_metered();
+                log::info!("Offline batch {}: paused on metered connection", progress.batch_id);
+                let _ = ctx.app.emit("offline:batch_progress", progress);
+
+                // Ends on a user resume or cancel, or once the connection is unmetered
+                while tokio::time::timeout(METERED_RECHECK_INTERVAL, batch.wait_until_runnable())
+                    .await
+                    .is_err()
+                {
+                    if !on_metered_connection().await {
+                        if let Some(progress) = batch.resume_unmetered() {
+                            let _ = ctx.app.emit("offline:batch_progress", progress);
+                        }
                     }
                 }
             }
-            Err(e) => {
-                log::error!("Caching failed for track {}: {}", track_id, e);
-                if let Some(db_guard) = db.lock().await.as_ref() {
-                    let _ = db_guard.update_status(track_id, OfflineCacheStatus::Failed, Some(&e));
+
+            // Cancelled (possibly while waiting for a slot)
+            let Some(permit) = batch.acquire_slot(&semaphore).await else {
+                unstarted.push(track_id);
+                unstarted.extend(pending.map(|(id, _)| id));
+                break;
+            };
+
+            let ctx = ctx.clone();
+            let batch = batch.clone();
+            running.spawn(async move {
+                let result = run_cache_task(&ctx, track_id, file_path, quality).await;
+                drop(permit);
+
+                if result.is_ok() {
+                    if let Some(limit) = *ctx.limit_bytes.lock().await {
+                        if let Err(e) = evict_to_limit(&ctx.db, &ctx.cache_dir, limit).await {
+                            log::warn!("Offline batch: eviction failed: {}", e);
+                        }
+                    }
+                }
+                let progress = batch.update(|p| match result {
+                    Ok(_) => p.completed_tracks += 1,
+                    Err(_) => p.failed_tracks += 1,
+                });
+                let _ = ctx.app.emit("offline:batch_progress", progress);
+            });
+        }
+
+        while running.join_next().await.is_some() {}
+
+        // Tracks that never started go back out of the cache index
+        if !unstarted.is_empty() {
+            if let Some(db_guard) = ctx.db.lock().await.as_ref() {
+                for track_id in &unstarted {
+                    let _ = db_guard.delete_track(*track_id);
                 }
-                let _ = app.emit("offline:caching_failed", serde_json::json!({
-                    "trackId": track_id,
-                    "error": e
-                }));
             }
         }
+
+        let progress = batch.update(|p| {
+            if p.status != CacheBatchStatus::Cancelled {
+                p.status = CacheBatchStatus::Completed;
+            }
+        });
+        log::info!(
+            "Offline batch {} finished: {} completed, {} failed, {} skipped",
+            progress.batch_id,
+            progress.completed_tracks,
+            progress.failed_tracks,
+            progress.skipped_tracks
+        );
+        let _ = ctx.app.emit("offline:batch_progress", progress);
     });
 
-    Ok(())
+    Ok(batch_id)
+}
+
+/// Pause an album/playlist batch (running downloads finish)
+#[tauri::command]
+pub async fn pause_offline_batch(
+    batch_id: u64,
+    cache_state: State<'_, OfflineCacheState>,
+    app_handle: AppHandle,
+) -> Result<CacheBatchProgress, String> {
+    log::info!("Command: pause_offline_batch {}", batch_id);
+    let batch = cache_state.batches.get(batch_id).ok_or("Unknown batch")?;
+    let progress = batch.pause();
+    let _ = app_handle.emit("offline:batch_progress", &progress);
+    Ok(progress)
+}
+
+/// Resume a paused album/playlist batch
+#[tauri::command]
+pub async fn resume_offline_batch(
+    batch_id: u64,
+    cache_state: State<'_, OfflineCacheState>,
+    app_handle: AppHandle,
+) -> Result<CacheBatchProgress, String> {
+    log::info!("Command: resume_offline_batch {}", batch_id);
+    let batch = cache_state.batches.get(batch_id).ok_or("Unknown batch")?;
+    let progress = batch.resume();
+    let _ = app_handle.emit("offline:batch_progress", &progress);
+    Ok(progress)
+}
+
+/// Cancel an album/playlist batch; tracks not started yet are dropped
+#[tauri::command]
+pub async fn cancel_offline_batch(
+    batch_id: u64,
+    cache_state: State<'_, OfflineCacheState>,
+    app_handle: AppHandle,
+) -> Result<CacheBatchProgress, String> {
+    log::info!("Command: cancel_offline_batch {}", batch_id);
+    let batch = cache_state.batches.get(batch_id).ok_or("Unknown batch")?;
+    let progress = batch.cancel();
+    let _ = app_handle.emit("offline:batch_progress", &progress);
+    Ok(progress)
+}
+
+/// List album/playlist batches of this session
+#[tauri::command]
+pub async fn get_offline_batches(
+    cache_state: State<'_, OfflineCacheState>,
+) -> Result<Vec<CacheBatchProgress>, String> {
+    Ok(cache_state.batches.list())
 }
 
 /// Check if a track is cached and ready for playback
@@ -367,6 +719,11 @@ pub async fn remove_cached_track(
                 cleanup_empty_folder(album_dir, &cache_state.cache_dir.read().unwrap());
             }
         }
+
+        // Drop the partial files of interrupted downloads
+        for partial in partial_paths(&cache_state.track_file_path(track_id, "flac")) {
+            let _ = std::fs::remove_file(partial);
+        }
     }
 
     // Also remove from library if it was added
@@ -632,9 +989,18 @@ async fn evict_if_needed(
     cache_state: &OfflineCacheState,
     limit_bytes: u64,
 ) -> Result<(), String> {
-    let guard__ = cache_state.db.lock().await;
+    evict_to_limit(&cache_state.db, &cache_state.cache_dir, limit_bytes).await
+}
+
+async fn evict_to_limit(
+    db: &tokio::sync::Mutex<Option<crate::offline_cache::OfflineCacheDb>>,
+    cache_dir: &std::sync::RwLock<std::path::PathBuf>,
+    limit_bytes: u64,
+) -> Result<(), String> {
+    let guard__ = db.lock().await;
     let db = guard__.as_ref().ok_or("No active session - please log in")?;
-    let stats = db.get_stats(&cache_state.get_cache_path(), Some(limit_bytes))?;
+    let cache_root = cache_dir.read().unwrap().clone();
+    let stats = db.get_stats(&cache_root.to_string_lossy(), Some(limit_bytes))?;
 
     if stats.total_size_bytes <= limit_bytes {
         return Ok(());
@@ -663,7 +1029,7 @@ async fn evict_if_needed(
 
             // Clean up empty album folder (and artist folder if also empty)
             if let Some(album_dir) = path.parent() {
-                cleanup_empty_folder(album_dir, &cache_state.cache_dir.read().unwrap());
+                cleanup_empty_folder(album_dir, &cache_root);
             }
         }
     }
@@ -210,25 +210,7 @@ impl OfflineCacheDb {
             "SELECT track_id, title, artist, album, album_id, duration_secs, file_size_bytes, quality, bit_depth, sample_rate, status, progress_percent, error_message, created_at, last_accessed_at
              FROM cached_tracks WHERE track_id = ?1",
             params![track_id as i64],
-            |row| {
-                Ok(CachedTrackInfo {
-                    track_id: row.get::<_, i64>(0)? as u64,
-                    title: row.get(1)?,
-                    artist: row.get(2)?,
-                    album: row.get(3)?,
-                    album_id: row.get(4)?,
-                    duration_secs: row.get::<_, i64>(5)? as u64,
-                    file_size_bytes: row.get::<_, i64>(6)? as u64,
-                    quality: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
-                    bit_depth: row.get::<_, Option<i64>>(8)?.map(|v| v as u32),
-                    sample_rate: row.get(9)?,
-                    status: OfflineCacheStatus::from_str(&row.get::<_, String>(10)?),
-                    progress_percent: row.get::<_, i64>(11)? as u8,
-                    error_message: row.get(12)?,
-                    created_at: row.get(13)?,
-                    last_accessed_at: row.get(14)?,
-                })
-            },
+            cached_track_from_row,
         );
 
         match result {
@@ -246,25 +228,7 @@ impl OfflineCacheDb {
         ).map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let tracks = stmt
-            .query_map([], |row| {
-                Ok(CachedTrackInfo {
-                    track_id: row.get::<_, i64>(0)? as u64,
-                    title: row.get(1)?,
-                    artist: row.get(2)?,
-                    album: row.get(3)?,
-                    album_id: row.get(4)?,
-                    duration_secs: row.get::<_, i64>(5)? as u64,
-                    file_size_bytes: row.get::<_, i64>(6)? as u64,
-                    quality: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
-                    bit_depth: row.get::<_, Option<i64>>(8)?.map(|v| v as u32),
-                    sample_rate: row.get(9)?,
-                    status: OfflineCacheStatus::from_str(&row.get::<_, String>(10)?),
-                    progress_percent: row.get::<_, i64>(11)? as u8,
-                    error_message: row.get(12)?,
-                    created_at: row.get(13)?,
-                    last_accessed_at: row.get(14)?,
-                })
-            })
+            .query_map([], cached_track_from_row)
             .map_err(|e| format!("Failed to query tracks: {}", e))?;
 
         let mut result = Vec::new();
@@ -275,6 +239,30 @@ impl OfflineCacheDb {
         Ok(result)
     }
 
+    /// Search ready tracks by title, artist or album
+    pub fn search_ready(&self, query: &str, limit: u32) -> Result<Vec<CachedTrackInfo>, String> {
+        let needle = format!("%{}%", query.trim().to_lowercase());
+        let mut stmt = self.conn.prepare(
+            "SELECT track_id, title, artist, album, album_id, duration_secs, file_size_bytes, quality, bit_depth, sample_rate, status, progress_percent, error_message, created_at, last_accessed_at
+             FROM cached_tracks
+             WHERE status = 'ready' AND
+                   (lower(title) LIKE ?1 OR lower(artist) LIKE ?1 OR lower(COALESCE(album, '')) LIKE ?1)
+             ORDER BY artist COLLATE NOCASE, album COLLATE NOCASE, title COLLATE NOCASE
+             LIMIT ?2"
+        ).map_err(|e| format!("Failed to prepare search query: {}", e))?;
+
+        let tracks = stmt
+            .query_map(params![needle, limit as i64], cached_track_from_row)
+            .map_err(|e| format!("Failed to search tracks: {}", e))?;
+
+        let mut result = Vec::new();
+        for track in tracks {
+            result.push(track.map_err(|e| format!("Failed to read track: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
     /// Delete a track from cache
     pub fn delete_track(&self, track_id: u64) -> Result<Option<String>, String> {
         // Get file path before deleting
@@ -432,3 +420,24 @@ impl OfflineCacheDb {
         Ok(())
     }
 }
+
+/// Map a row selected with the standard cached_tracks column list
+fn cached_track_from_row(row: &rusqlite::Row) -> rusqlite::Result<CachedTrackInfo> {
+    Ok(CachedTrackInfo {
+        track_id: row.get::<_, i64>(0)? as u64,
+        title: row.get(1)?,
+        artist: row.get(2)?,
+        album: row.get(3)?,
+        album_id: row.get(4)?,
+        duration_secs: row.get::<_, i64>(5)? as u64,
+        file_size_bytes: row.get::<_, i64>(6)? as u64,
+        quality: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+        bit_depth: row.get::<_, Option<i64>>(8)?.map(|v| v as u32),
+        sample_rate: row.get(9)?,
+        status: OfflineCacheStatus::from_str(&row.get::<_, String>(10)?),
+        progress_percent: row.get::<_, i64>(11)? as u8,
+        error_message: row.get(12)?,
+        created_at: row.get(13)?,
+        last_accessed_at: row.get(14)?,
+    })
+}
@@ -0,0 +1,450 @@
+//! Download an artist's discography as tagged files in the download folder
+//!
+//! Unlike the offline cache, the result is a plain music library under the
+//! download root, laid out by the download naming template, tagged, with
+//! embedded artwork and a `cover.jpg` per album folder. Tracks whose file
+//! already exists are skipped, so starting the
+//! download again picks up where an interrupted one stopped; albums with every
+//! track present are skipped as a whole.
+//!
+//! A run is a regular cache batch (kind `discography`): it is paused, resumed
+//! and cancelled with the offline batch commands, and shares the cache
+//! semaphore with offline caching. Tracks are downloaded one at a time.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::api::models::{Album, Quality, Track};
+use crate::commands::playback::parse_quality;
+use crate::config::DownloadSettingsState;
+use crate::AppState;
+
+use super::batch::CacheBatch;
+use super::metadata::{embed_artwork, save_album_artwork, write_flac_tags, CompleteTrackMetadata};
+use super::naming::{NamingFields, NamingTemplate};
+use super::{CacheBatchKind, CacheBatchProgress, CacheBatchStatus, OfflineCacheState};
+
+/// Albums requested per page while listing the artist's releases
+const ALBUM_PAGE_SIZE: u32 = 100;
+
+/// Folder (under the download root) holding downloads that are not tagged yet
+const STAGING_DIR: &str = ".qbz-incomplete";
+
+/// Progress of a discography download (payload of `offline:discography_progress`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscographyProgress {
+    #[serde(flatten)]
+    pub batch: CacheBatchProgress,
+    /// Albums in the discography
+    pub total_albums: usize,
+    /// Albums whose tracks were all present already
+    pub skipped_albums: usize,
+    pub completed_albums: usize,
+    /// Albums that couldn't be fetched or whose downloads all failed
+    pub failed_albums: usize,
+    pub current_album: Option<String>,
+    pub current_track: Option<String>,
+}
+
+#[derive(Default)]
+struct AlbumCounts {
+    total: usize,
+    skipped: usize,
+    completed: usize,
+    failed: usize,
+}
+
+/// Tag metadata for a track, taken from its (full) album
+pub fn album_track_metadata(album: &Album, track: &Track) -> CompleteTrackMetadata {
+    CompleteTrackMetadata {
+        track_id: track.id,
+        title: track.title.clone(),
+        artist: track
+            .performer
+            .as_ref()
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| album.artist.name.clone()),
+        album: album.title.clone(),
+        album_artist: Some(album.artist.name.clone()),
+        track_number: Some(track.track_number),
+        disc_number: track.media_number,
+        year: album
+            .release_date_original
+            .as_deref()
+            .and_then(|date| date.split('-').next())
+            .and_then(|year| year.parse().ok()),
+        genre: album.genre.as_ref().map(|g| g.name.clone()),
+        isrc: track.isrc.clone(),
+        label: album.label.as_ref().map(|l| l.name.clone()),
+        copyright: track.copyright.clone(),
+        composer: track.composer.as_ref().map(|c| c.name.clone()),
+        duration_secs: track.duration as u64,
+        artwork_url: album.image.large.clone(),
+    }
+}
+
+/// Where a track of the discography is stored
+pub fn download_path(
+    template: &NamingTemplate,
+    root: &Path,
+    metadata: &CompleteTrackMetadata,
+    multi_disc: bool,
+) -> PathBuf {
+    template.render(
+        root,
+        &NamingFields::from_metadata(metadata, "flac"),
+        multi_disc,
+    )
+}
+
+/// The artist's name and own albums (compilations by other artists are left out)
+async fn fetch_artist_albums(
+    client: &crate::api::QobuzClient,
+    artist_id: u64,
+) -> Result<(String, Vec<Album>), String> {
+    let mut name;
+    let mut albums = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let artist = client
+            .get_artist_with_pagination(artist_id, true, Some(ALBUM_PAGE_SIZE), Some(offset))
+            .await
+            .map_err(|e| e.to_string())?;
+        name = artist.name;
+
+        let Some(page) = artist.albums else { break };
+        let fetched = page.items.len() as u32;
+        albums.extend(
+            page.items
+                .into_iter()
+                .filter(|a| a.artist.id == 0 || a.artist.id == artist_id),
+        );
+
+        offset += fetched;
+        if fetched == 0 || offset >= page.total {
+            break;
+        }
+    }
+
+    Ok((name, albums))
+}
+
+/// Download one track into the staging folder, tag it and move it into place
+async fn download_track(
+    client: &tokio::sync::RwLock<crate::api::QobuzClient>,
+    fetcher: &super::StreamFetcher,
+    staging_dir: &Path,
+    metadata: &CompleteTrackMetadata,
+    dest: &Path,
+    quality: Quality,
+) -> Result<(), String> {
+    let track_id = metadata.track_id;
+    let stream = {
+        let client = client.read().await;
+        client
+            .get_stream_url_with_fallback(track_id, quality)
+            .await
+            .map_err(|e| format!("Failed to get stream URL: {}", e))?
+    };
+    if !stream.mime_type.contains("flac") {
+        return Err(format!("Track {} is not available as FLAC", track_id));
+    }
+
+    // Staged by track ID, so an interrupted download resumes from its partial file
+    let staged = staging_dir.join(format!("{}.flac", track_id));
+    fetcher
+        .fetch_to_file(&stream.url, &staged, track_id, stream.format_id, None)
+        .await?;
+
+    let staged_str = staged.to_string_lossy().to_string();
+    write_flac_tags(&staged_str, metadata)?;
+    if let Some(artwork_url) = &metadata.artwork_url {
+        if let Err(e) = embed_artwork(&staged_str, artwork_url).await {
+            log::warn!("Failed to embed artwork for track {}: {}", track_id, e);
+        }
+    }
+
+    let album_dir = dest.parent().ok_or("Invalid download path")?;
+    std::fs::create_dir_all(album_dir)
+        .map_err(|e| format!("Failed to create album folder: {}", e))?;
+    std::fs::rename(&staged, dest).map_err(|e| format!("Failed to move file: {}", e))?;
+
+    if let Some(artwork_url) = &metadata.artwork_url {
+        if let Err(e) = save_album_artwork(album_dir, artwork_url).await {
+            log::warn!("Failed to save cover for {:?}: {}", album_dir, e);
+        }
+    }
+
+    Ok(())
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    batch: &CacheBatch,
+    albums: &AlbumCounts,
+    current_album: Option<&str>,
+    current_track: Option<&str>,
+) {
+    let progress = batch.progress();
+    let _ = app.emit("offline:batch_progress", &progress);
+    let _ = app.emit(
+        "offline:discography_progress",
+        DiscographyProgress {
+            batch: progress,
+            total_albums: albums.total,
+            skipped_albums: albums.skipped,
+            completed_albums: albums.completed,
+            failed_albums: albums.failed,
+            current_album: current_album.map(str::to_string),
+            current_track: current_track.map(str::to_string),
+        },
+    );
+}
+
+/// Download an artist's whole discography as tagged FLAC files
+///
+/// Returns the batch ID used by the progress events and the offline batch
+/// pause/resume/cancel commands.
+#[tauri::command]
+pub async fn download_artist_discography(
+    artist_id: u64,
+    quality: Option<String>,
+    state: State<'_, AppState>,
+    cache_state: State<'_, OfflineCacheState>,
+    download_settings: State<'_, DownloadSettingsState>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    log::info!(
+        "Command: download_artist_discography {} (quality: {:?})",
+        artist_id,
+        quality
+    );
+
+    let quality = parse_quality(quality.as_deref());
+    if quality == Quality::Mp3 {
+        return Err("Discography downloads are FLAC only".to_string());
+    }
+
+    let settings = {
+        let guard = download_settings
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.get_settings()?
+    };
+    let root = PathBuf::from(&settings.download_root);
+    let template = NamingTemplate::parse(&settings.naming_template).unwrap_or_else(|e| {
+        log::warn!("Invalid download naming template, using the default: {}", e);
+        NamingTemplate::default()
+    });
+    let staging_dir = root.join(STAGING_DIR);
+    std::fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("Failed to create download folder: {}", e))?;
+
+    let (artist_name, albums) = {
+        let client = state.client.read().await;
+        fetch_artist_albums(&client, artist_id).await?
+    };
+
+    let batch = cache_state.batches.create(
+        CacheBatchKind::Discography,
+        artist_id.to_string(),
+        artist_name.clone(),
+        0,
+        0,
+    );
+    let batch_id = batch.progress().batch_id;
+    log::info!(
+        "Discography batch {}: {} albums of {} into {:?}",
+        batch_id,
+        albums.len(),
+        artist_name,
+        root
+    );
+
+    let client = state.client.clone();
+    let fetcher = cache_state.fetcher.clone();
+    let semaphore = cache_state.cache_semaphore.clone();
+    let app = app_handle.clone();
+
+    tokio::spawn(async move {
+        let mut counts = AlbumCounts {
+            total: albums.len(),
+            ..Default::default()
+        };
+        emit_progress(&app, &batch, &counts, None, None);
+
+        'albums: for summary in albums {
+            if !batch.wait_until_runnable().await {
+                break;
+            }
+
+            // The artist listing does not include tracks
+            let album = {
+                let client = client.read().await;
+                client.get_album(&summary.id).await
+            };
+            let album = match album {
+                Ok(album) => album,
+                Err(e) => {
+                    log::warn!(
+                        "Discography batch {}: album {} failed: {}",
+                        batch_id,
+                        summary.id,
+                        e
+                    );
+                    counts.failed += 1;
+                    emit_progress(&app, &batch, &counts, Some(&summary.title), None);
+                    continue;
+                }
+            };
+
+            let tracks: Vec<&Track> = album
+                .tracks
+                .as_ref()
+                .map(|t| t.items.iter().filter(|t| t.streamable).collect())
+                .unwrap_or_default();
+            let multi_disc = tracks.iter().any(|t| t.media_number.unwrap_or(1) > 1);
+
+            let mut pending = Vec::new();
+            for track in &tracks {
+                let metadata = album_track_metadata(&album, track);
+                let dest = download_path(&template, &root, &metadata, multi_disc);
+                if !dest.exists() {
+                    pending.push((metadata, dest));
+                }
+            }
+
+            let already_present = tracks.len() - pending.len();
+            batch.update(|p| {
+                p.total_tracks += tracks.len();
+                p.skipped_tracks += already_present;
+            });
+            if pending.is_empty() {
+                counts.skipped += 1;
+                emit_progress(&app, &batch, &counts, Some(&album.title), None);
+                continue;
+            }
+
+            let mut downloaded = 0;
+            for (metadata, dest) in pending {
+                let Some(permit) = batch.acquire_slot(&semaphore).await else {
+                    break 'albums;
+                };
+
+                emit_progress(
+                    &app,
+                    &batch,
+                    &counts,
+                    Some(&album.title),
+                    Some(&metadata.title),
+                );
+                let result =
+                    download_track(&client, &fetcher, &staging_dir, &metadata, &dest, quality)
+                        .await;
+                drop(permit);
+
+                if let Err(e) = &result {
+                    log::error!(
+                        "Discography batch {}: track {} failed: {}",
+                        batch_id,
+                        metadata.track_id,
+                        e
+                    );
+                }
+                batch.update(|p| match result {
+                    Ok(()) => {
+                        downloaded += 1;
+                        p.completed_tracks += 1;
+                    }
+                    Err(_) => p.failed_tracks += 1,
+                });
+            }
+
+            if downloaded > 0 {
+                counts.completed += 1;
+            } else {
+                counts.failed += 1;
+            }
+            emit_progress(&app, &batch, &counts, Some(&album.title), None);
+        }
+
+        batch.update(|p| {
+            if p.status != CacheBatchStatus::Cancelled {
+                p.status = CacheBatchStatus::Completed;
+            }
+        });
+        let progress = batch.progress();
+        log::info!(
+            "Discography batch {} finished: {} completed, {} failed, {} skipped ({} albums skipped, {} failed)",
+            batch_id,
+            progress.completed_tracks,
+            progress.failed_tracks,
+            progress.skipped_tracks,
+            counts.skipped,
+            counts.failed
+        );
+        emit_progress(&app, &batch, &counts, None, None);
+    });
+
+    Ok(batch_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album_and_track(media_number: u32) -> (Album, Track) {
+        let album: Album = serde_json::from_value(serde_json::json!({
+            "id": "a1",
+            "title": "Kind of Blue",
+            "artist": { "id": 1, "name": "Miles Davis" },
+            "release_date_original": "1959-08-17",
+            "genre": { "id": 80, "name": "Jazz" }
+        }))
+        .unwrap();
+        let track: Track = serde_json::from_value(serde_json::json!({
+            "id": 10,
+            "title": "So What",
+            "track_number": 1,
+            "media_number": media_number,
+            "performer": { "id": 1, "name": "Miles Davis Sextet" }
+        }))
+        .unwrap();
+        (album, track)
+    }
+
+    #[test]
+    fn metadata_comes_from_the_album() {
+        let (album, track) = album_and_track(1);
+        let metadata = album_track_metadata(&album, &track);
+        assert_eq!(metadata.artist, "Miles Davis Sextet");
+        assert_eq!(metadata.album_artist.as_deref(), Some("Miles Davis"));
+        assert_eq!(metadata.year, Some(1959));
+        assert_eq!(metadata.genre.as_deref(), Some("Jazz"));
+    }
+
+    #[test]
+    fn path_follows_the_naming_template() {
+        let root = Path::new("/music");
+        let template = NamingTemplate::default();
+        let (album, track) = album_and_track(2);
+        let metadata = album_track_metadata(&album, &track);
+        assert_eq!(
+            download_path(&template, root, &metadata, false),
+            root.join("Miles Davis")
+                .join("Kind of Blue")
+                .join("01 - So What.flac")
+        );
+        assert_eq!(
+            download_path(&template, root, &metadata, true),
+            root.join("Miles Davis")
+                .join("Kind of Blue")
+                .join("2-01 - So What.flac")
+        );
+    }
+}
@@ -1,11 +1,58 @@
 //! Stream fetcher for caching tracks to disk
 
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
+use reqwest::header::{ACCEPT_RANGES, CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
 use tauri::{AppHandle, Emitter};
 
 use super::{CacheProgress, OfflineCacheStatus};
+use crate::data_usage::{self, DataUsageCategory};
+use crate::network::http_client;
+
+/// Fetch attempts per call; each retry resumes the partial file when possible
+const MAX_FETCH_ATTEMPTS: u32 = 4;
+
+/// Delay before a retry, multiplied by the number of failed attempts
+const RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Path of the in-progress download for `dest_path` in Qobuz format `format_id`
+///
+/// The format is part of the name so a resume never appends bytes of a
+/// different encode (e.g. after the cache quality changed).
+pub fn partial_path(dest_path: &Path, format_id: u32) -> PathBuf {
+    dest_path.with_extension(format!("{}.part", format_id))
+}
+
+/// Partial downloads for `dest_path` in any format
+pub fn partial_paths(dest_path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (dest_path.parent(), dest_path.file_stem()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", stem.to_string_lossy());
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension().is_some_and(|ext| ext == "part")
+                        && path
+                            .file_name()
+                            .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+enum FetchError {
+    /// Connection problem; the partial file (if any) can be resumed
+    Retryable(String),
+    /// Retrying would not help (HTTP error, disk error, size mismatch)
+    Fatal(String),
+}
 
 /// StreamFetcher handles fetching audio streams and caching them to disk
 pub struct StreamFetcher {
@@ -14,9 +61,8 @@ pub struct StreamFetcher {
 
 impl StreamFetcher {
     pub fn new() -> Self {
-        let client = reqwest::Client::builder()
+        let client = http_client::builder(http_client::DEFAULT_USER_AGENT)
             .timeout(Duration::from_secs(300)) // 5 minute timeout for large files
-            .connect_timeout(Duration::from_secs(15))
             .build()
             .expect("Failed to create HTTP client");
 
@@ -24,11 +70,17 @@ impl StreamFetcher {
     }
 
     /// Fetch a stream and cache it to disk with progress updates
+    ///
+    /// Data is written to a `.part` file next to `dest_path`. If the connection
+    /// drops, the partial file is kept and the fetch resumes with a Range request
+    /// (here on retry, or on a later call for the same track and format). The
+    /// file is only moved into place once its length and a decode check pass.
     pub async fn fetch_to_file(
         &self,
         url: &str,
         dest_path: &Path,
         track_id: u64,
+        format_id: u32,
         app_handle: Option<&AppHandle>,
     ) -> Result<u64, String> {
         log::info!("Caching track {} to {:?}", track_id, dest_path);
@@ -39,32 +91,124 @@ impl StreamFetcher {
                 .map_err(|e| format!("Failed to create directory: {}", e))?;
         }
 
-        // Start fetching
-        let response = self
-            .client
-            .get(url)
-            .header("User-Agent", "Mozilla/5.0")
+        let part_path = partial_path(dest_path, format_id);
+        // A partial file of another format would corrupt the resumed download
+        for stale in partial_paths(dest_path) {
+            if stale != part_path {
+                log::info!("Discarding partial download {:?}", stale);
+                let _ = std::fs::remove_file(stale);
+            }
+        }
+        let mut last_error = String::new();
+
+        for attempt in 1..=MAX_FETCH_ATTEMPTS {
+            if attempt > 1 {
+                tokio::time::sleep(RETRY_BACKOFF * (attempt - 1)).await;
+                log::info!(
+                    "Retrying cache fetch for track {} (attempt {}/{})",
+                    track_id,
+                    attempt,
+                    MAX_FETCH_ATTEMPTS
+                );
+            }
+
+            match self.fetch_part(url, &part_path, track_id, app_handle).await {
+                Ok(total) => {
+                    verify_cached_file(&part_path, total).await.inspect_err(|_| {
+                        // A complete but undecodable file cannot be resumed
+                        let _ = std::fs::remove_file(&part_path);
+                    })?;
+
+                    std::fs::rename(&part_path, dest_path)
+                        .map_err(|e| format!("Failed to move partial file: {}", e))?;
+
+                    log::info!("Caching complete for track {}: {} bytes", track_id, total);
+                    return Ok(total);
+                }
+                Err(FetchError::Retryable(e)) => {
+                    log::warn!("Cache fetch for track {} interrupted: {}", track_id, e);
+                    last_error = e;
+                }
+                Err(FetchError::Fatal(e)) => {
+                    let _ = std::fs::remove_file(&part_path);
+                    return Err(e);
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Download (the rest of) a track into `part_path`, returning the total size
+    async fn fetch_part(
+        &self,
+        url: &str,
+        part_path: &Path,
+        track_id: u64,
+        app_handle: Option<&AppHandle>,
+    ) -> Result<u64, FetchError> {
+        let offset = std::fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+
+        let mut request = self.client.get(url);
+        if offset > 0 {
+            log::info!("Resuming cache of track {} from byte {}", track_id, offset);
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+
+        let response = request
             .send()
             .await
-            .map_err(|e| format!("Failed to start fetch: {}", e))?;
+            .map_err(|e| FetchError::Retryable(format!("Failed to start fetch: {}", e)))?;
 
-        if !response.status().is_success() {
-            return Err(format!("HTTP error: {}", response.status()));
+        let status = response.status();
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // Stale or oversized partial file: start over
+            let _ = std::fs::remove_file(part_path);
+            return Err(FetchError::Retryable(format!("HTTP error: {}", status)));
+        }
+        if !status.is_success() {
+            return Err(FetchError::Fatal(format!("HTTP error: {}", status)));
         }
 
-        let total_size = response.content_length();
+        let resumed = offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+        let supports_ranges = resumed
+            || response
+                .headers()
+                .get(ACCEPT_RANGES)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.eq_ignore_ascii_case("bytes"))
+                .unwrap_or(false);
+
+        let total_size = if resumed {
+            response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(content_range_total)
+                .or_else(|| response.content_length().map(|len| offset + len))
+        } else {
+            response.content_length()
+        };
+
         log::info!(
-            "Caching started for track {}, total size: {:?} bytes",
+            "Caching started for track {}, total size: {:?} bytes{}",
             track_id,
-            total_size
+            total_size,
+            if resumed { " (resumed)" } else { "" }
         );
+        if offset > 0 && !resumed {
+            log::info!("Server ignored range request for track {}, re-downloading", track_id);
+        }
 
-        // Create temp file for caching
-        let temp_path = dest_path.with_extension("tmp");
-        let mut file = std::fs::File::create(&temp_path)
-            .map_err(|e| format!("Failed to create temp file: {}", e))?;
+        // Append when resuming, otherwise (re)create the partial file
+        let mut file = if resumed {
+            std::fs::OpenOptions::new().append(true).open(part_path)
+        } else {
+            std::fs::File::create(part_path)
+        }
+        .map_err(|e| FetchError::Fatal(format!("Failed to create partial file: {}", e)))?;
 
-        let mut cached: u64 = 0;
+        let mut cached: u64 = if resumed { offset } else { 0 };
         let mut last_progress: u8 = 0;
 
         // Stream the response body
@@ -72,10 +216,21 @@ impl StreamFetcher {
         use futures_util::StreamExt;
 
         while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| format!("Fetch error: {}", e))?;
+            let chunk = match chunk_result {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    let _ = file.flush();
+                    if !supports_ranges {
+                        // The server cannot resume, so the partial data is useless
+                        let _ = std::fs::remove_file(part_path);
+                    }
+                    return Err(FetchError::Retryable(format!("Fetch error: {}", e)));
+                }
+            };
 
+            data_usage::record(DataUsageCategory::Downloads, chunk.len() as u64);
             file.write_all(&chunk)
-                .map_err(|e| format!("Failed to write chunk: {}", e))?;
+                .map_err(|e| FetchError::Fatal(format!("Failed to write chunk: {}", e)))?;
 
             cached += chunk.len() as u64;
 
@@ -88,7 +243,7 @@ impl StreamFetcher {
             };
 
             // Emit progress event every 2% change
-            if progress != last_progress && (progress - last_progress >= 2 || progress == 100) {
+            if progress != last_progress && (progress.saturating_sub(last_progress) >= 2 || progress == 100) {
                 last_progress = progress;
 
                 if let Some(app) = app_handle {
@@ -116,20 +271,20 @@ impl StreamFetcher {
 
         // Ensure all data is written
         file.flush()
-            .map_err(|e| format!("Failed to flush file: {}", e))?;
+            .map_err(|e| FetchError::Fatal(format!("Failed to flush file: {}", e)))?;
         drop(file);
 
-        // Move temp file to final destination
-        std::fs::rename(&temp_path, dest_path)
-            .map_err(|e| format!("Failed to move temp file: {}", e))?;
-
-        log::info!(
-            "Caching complete for track {}: {} bytes",
-            track_id,
-            cached
-        );
-
-        Ok(cached)
+        match total_size {
+            Some(total) if cached < total => Err(FetchError::Retryable(format!(
+                "Incomplete download: {} of {} bytes",
+                cached, total
+            ))),
+            Some(total) if cached > total => Err(FetchError::Fatal(format!(
+                "Downloaded {} bytes, expected {}",
+                cached, total
+            ))),
+            _ => Ok(cached),
+        }
     }
 
     /// Fetch to memory (for smaller files or streaming)
@@ -137,7 +292,6 @@ impl StreamFetcher {
         let response = self
             .client
             .get(url)
-            .header("User-Agent", "Mozilla/5.0")
             .send()
             .await
             .map_err(|e| format!("Failed to fetch: {}", e))?;
@@ -150,6 +304,7 @@ impl StreamFetcher {
             .bytes()
             .await
             .map_err(|e| format!("Failed to read bytes: {}", e))?;
+        data_usage::record(DataUsageCategory::Downloads, bytes.len() as u64);
 
         Ok(bytes.to_vec())
     }
@@ -161,3 +316,111 @@ impl Default for StreamFetcher {
     }
 }
 
+/// Total size from a `Content-Range: bytes start-end/total` header
+fn content_range_total(value: &str) -> Option<u64> {
+    let (_, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    total.trim().parse().ok()
+}
+
+/// Check a finished download before it replaces the cached file: the length
+/// must match and the first audio packet must decode.
+async fn verify_cached_file(path: &Path, expected_len: u64) -> Result<(), String> {
+    let len = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read partial file: {}", e))?
+        .len();
+    if len != expected_len {
+        return Err(format!("Cached file is {} bytes, expected {}", len, expected_len));
+    }
+
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || decode_first_packet(&path))
+        .await
+        .map_err(|e| format!("Decode check failed: {}", e))?
+}
+
+fn decode_first_packet(path: &Path) -> Result<(), String> {
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open cached file: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension("flac");
+
+    let mut probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Cached file is not valid audio: {}", e))?;
+    let track = probed
+        .format
+        .default_track()
+        .ok_or("Cached file has no audio track")?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported codec in cached file: {}", e))?;
+
+    loop {
+        let packet = probed
+            .format
+            .next_packet()
+            .map_err(|e| format!("Failed to read cached file: {}", e))?;
+        if packet.track_id() != track_id {
+            continue;
+        }
+        decoder
+            .decode(&packet)
+            .map_err(|e| format!("Failed to decode cached file: {}", e))?;
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_content_range_total() {
+        assert_eq!(content_range_total("bytes 1000-4999/5000"), Some(5000));
+        assert_eq!(content_range_total("bytes 0-0/*"), None);
+        assert_eq!(content_range_total("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn partial_file_sits_next_to_destination() {
+        let dest = Path::new("/cache/tracks/42.flac");
+        assert_eq!(
+            partial_path(dest, 27),
+            PathBuf::from("/cache/tracks/42.27.part")
+        );
+    }
+
+    #[test]
+    fn partial_files_of_every_format_are_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("42.flac");
+        for name in [
+            "42.27.part",
+            "42.6.part",
+            "42.part",
+            "420.27.part",
+            "42.flac",
+        ] {
+            std::fs::write(dir.path().join(name), b"x").unwrap();
+        }
+
+        let mut found = partial_paths(&dest);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                dir.path().join("42.27.part"),
+                dir.path().join("42.6.part"),
+                dir.path().join("42.part"),
+            ]
+        );
+    }
+}
+
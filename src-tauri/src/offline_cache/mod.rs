@@ -6,11 +6,14 @@
 //! - LRU eviction with configurable limits
 //! - Progress events for UI updates
 
+pub mod batch;
 pub mod commands;
 pub mod db;
+pub mod discography;
 pub mod downloader;
 pub mod path_validator;
 pub mod metadata;
+pub mod naming;
 pub mod migration;
 
 use std::path::PathBuf;
@@ -18,6 +21,7 @@ use std::sync::{Arc, RwLock};
 use tokio::sync::{Mutex, Semaphore};
 use serde::{Deserialize, Serialize};
 
+pub use batch::{CacheBatchKind, CacheBatchProgress, CacheBatchRegistry, CacheBatchStatus};
 pub use db::OfflineCacheDb;
 pub use downloader::StreamFetcher;
 pub use path_validator::{is_offline_root_available, validate_path, PathStatus};
@@ -135,6 +139,8 @@ pub struct OfflineCacheState {
     /// Cache limit in bytes (None = unlimited)
     pub limit_bytes: Arc<Mutex<Option<u64>>>,
     pub cache_semaphore: Arc<Semaphore>,
+    /// Album/playlist caching batches
+    pub batches: Arc<CacheBatchRegistry>,
 }
 
 impl OfflineCacheState {
@@ -165,6 +171,7 @@ impl OfflineCacheState {
             cache_dir: Arc::new(RwLock::new(cache_dir.clone())),
             limit_bytes: Arc::new(Mutex::new(default_limit)),
             cache_semaphore: Arc::new(Semaphore::new(3)),
+            batches: Arc::new(CacheBatchRegistry::new()),
         };
 
         log::info!("Offline cache initialized at: {:?}", cache_dir);
@@ -183,6 +190,7 @@ impl OfflineCacheState {
             cache_dir: Arc::new(RwLock::new(cache_dir)),
             limit_bytes: Arc::new(Mutex::new(Some(2 * 1024 * 1024 * 1024u64))),
             cache_semaphore: Arc::new(Semaphore::new(3)),
+            batches: Arc::new(CacheBatchRegistry::new()),
         }
     }
 
@@ -0,0 +1,351 @@
+//! File naming templates for downloads
+//!
+//! A template is a relative path with `{token}` placeholders, e.g.
+//! `{albumartist}/{year} - {album}/{track:02} - {title}`. Numeric tokens take
+//! an optional zero-padded width (`{track:02}`). Every rendered path component
+//! is sanitized for the filesystem and the file extension is appended from the
+//! format. Tokens without a value render empty.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::metadata::{sanitize_filename, CompleteTrackMetadata};
+
+/// Layout used until the user picks another one
+pub const DEFAULT_NAMING_TEMPLATE: &str = "{albumartist}/{album}/{track:02} - {title}";
+
+/// Stand-in for a path component that renders empty
+const EMPTY_COMPONENT: &str = "Unknown";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Artist,
+    AlbumArtist,
+    Album,
+    Year,
+    Track,
+    Disc,
+    Title,
+    Format,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "artist" => Self::Artist,
+            "albumartist" => Self::AlbumArtist,
+            "album" => Self::Album,
+            "year" => Self::Year,
+            "track" => Self::Track,
+            "disc" => Self::Disc,
+            "title" => Self::Title,
+            "format" => Self::Format,
+            _ => return None,
+        })
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, Self::Year | Self::Track | Self::Disc)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Token { field: Field, width: Option<usize> },
+}
+
+/// Values a template is rendered from
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NamingFields {
+    pub artist: String,
+    pub album_artist: Option<String>,
+    pub album: String,
+    pub year: Option<u32>,
+    pub track: Option<u32>,
+    pub disc: Option<u32>,
+    pub title: String,
+    /// File extension, e.g. "flac"
+    pub format: String,
+}
+
+impl NamingFields {
+    pub fn from_metadata(metadata: &CompleteTrackMetadata, format: &str) -> Self {
+        Self {
+            artist: metadata.artist.clone(),
+            album_artist: metadata.album_artist.clone(),
+            album: metadata.album.clone(),
+            year: metadata.year,
+            track: metadata.track_number,
+            disc: metadata.disc_number,
+            title: metadata.title.clone(),
+            format: format.to_string(),
+        }
+    }
+
+    /// Track used to preview a template
+    pub fn sample() -> Self {
+        Self {
+            artist: "Miles Davis".to_string(),
+            album_artist: Some("Miles Davis".to_string()),
+            album: "Kind of Blue".to_string(),
+            year: Some(1959),
+            track: Some(1),
+            disc: Some(1),
+            title: "So What".to_string(),
+            format: "flac".to_string(),
+        }
+    }
+
+    fn text(&self, field: Field) -> String {
+        match field {
+            Field::Artist => self.artist.clone(),
+            Field::AlbumArtist => self
+                .album_artist
+                .clone()
+                .unwrap_or_else(|| self.artist.clone()),
+            Field::Album => self.album.clone(),
+            Field::Title => self.title.clone(),
+            Field::Format => self.format.clone(),
+            Field::Year | Field::Track | Field::Disc => String::new(),
+        }
+    }
+
+    fn number(&self, field: Field) -> Option<u32> {
+        match field {
+            Field::Year => self.year,
+            Field::Track => self.track,
+            Field::Disc => self.disc,
+            _ => None,
+        }
+    }
+}
+
+/// A parsed naming template
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamingTemplate {
+    components: Vec<Vec<Part>>,
+}
+
+impl Default for NamingTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_NAMING_TEMPLATE).expect("default naming template is valid")
+    }
+}
+
+impl NamingTemplate {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let template = template.trim();
+        if template.is_empty() {
+            return Err("Naming template is empty".to_string());
+        }
+        if template.starts_with('/') || template.starts_with('\\') {
+            return Err("Naming template must be a relative path".to_string());
+        }
+
+        let components = template
+            .split(['/', '\\'])
+            .map(parse_component)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if components.iter().any(|c| c.is_empty()) {
+            return Err("Naming template has an empty folder name".to_string());
+        }
+        if !components
+            .iter()
+            .flatten()
+            .any(|p| matches!(p, Part::Token { .. }))
+        {
+            return Err("Naming template has no tokens".to_string());
+        }
+
+        Ok(Self { components })
+    }
+
+    /// Whether the template places the disc number itself
+    pub fn uses_disc(&self) -> bool {
+        self.components.iter().flatten().any(|p| {
+            matches!(
+                p,
+                Part::Token {
+                    field: Field::Disc,
+                    ..
+                }
+            )
+        })
+    }
+
+    /// Path of a file under `root`
+    ///
+    /// On multi-disc albums a template without `{disc}` gets a `D-` prefix on
+    /// the file name, so the discs do not overwrite each other.
+    pub fn render(&self, root: &Path, fields: &NamingFields, multi_disc: bool) -> PathBuf {
+        let mut path = root.to_path_buf();
+        let last = self.components.len() - 1;
+
+        for (i, component) in self.components.iter().enumerate() {
+            let mut name = render_component(component, fields);
+            if i == last {
+                if let Some(disc) = fields.disc.filter(|_| multi_disc && !self.uses_disc()) {
+                    name = format!("{}-{}", disc, name);
+                }
+                name = format!("{}.{}", name, fields.format);
+            }
+            path.push(name);
+        }
+
+        path
+    }
+}
+
+fn parse_component(component: &str) -> Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = component.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                let mut token = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    token.push(c);
+                }
+                if !closed || token.contains('{') {
+                    return Err(format!("Unclosed token in \"{}\"", component));
+                }
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(parse_token(&token)?);
+            }
+            '}' => return Err(format!("Unexpected '}}' in \"{}\"", component)),
+            _ => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    Ok(parts)
+}
+
+fn parse_token(token: &str) -> Result<Part, String> {
+    let (name, width) = match token.split_once(':') {
+        Some((name, width)) => {
+            let width = width
+                .parse::<usize>()
+                .ok()
+                .filter(|w| (1..=4).contains(w))
+                .ok_or_else(|| format!("Invalid width in {{{}}}", token))?;
+            (name, Some(width))
+        }
+        None => (token, None),
+    };
+
+    let field = Field::parse(name.trim()).ok_or_else(|| format!("Unknown token {{{}}}", name))?;
+    if width.is_some() && !field.is_numeric() {
+        return Err(format!("{{{}}} cannot be padded", name));
+    }
+    Ok(Part::Token { field, width })
+}
+
+fn render_component(parts: &[Part], fields: &NamingFields) -> String {
+    let mut rendered = String::new();
+    for part in parts {
+        match part {
+            Part::Text(text) => rendered.push_str(text),
+            Part::Token { field, width } if field.is_numeric() => {
+                if let Some(n) = fields.number(*field) {
+                    let width = width.unwrap_or(0);
+                    rendered.push_str(&format!("{:0width$}", n));
+                }
+            }
+            Part::Token { field, .. } => rendered.push_str(&fields.text(*field)),
+        }
+    }
+
+    // Separators left around empty tokens ("{year} - {album}") are dropped too
+    let trimmed = rendered.trim_matches(|c: char| c.is_whitespace() || c == '-' || c == '.');
+    if trimmed.is_empty() {
+        EMPTY_COMPONENT.to_string()
+    } else {
+        sanitize_filename(trimmed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_the_default_layout() {
+        let path =
+            NamingTemplate::default().render(Path::new("/music"), &NamingFields::sample(), false);
+        assert_eq!(
+            path,
+            Path::new("/music/Miles Davis/Kind of Blue/01 - So What.flac")
+        );
+    }
+
+    #[test]
+    fn renders_tokens_and_padding() {
+        let template = NamingTemplate::parse(
+            "{albumartist}/{year} - {album} [{format}]/{disc}.{track:03} {title}",
+        )
+        .unwrap();
+        let path = template.render(Path::new("/music"), &NamingFields::sample(), true);
+        assert_eq!(
+            path,
+            Path::new("/music/Miles Davis/1959 - Kind of Blue [flac]/1.001 So What.flac")
+        );
+    }
+
+    #[test]
+    fn sanitizes_values_and_fills_empty_components() {
+        let fields = NamingFields {
+            album: "AC/DC: Live".to_string(),
+            title: "..".to_string(),
+            format: "flac".to_string(),
+            ..Default::default()
+        };
+        let template = NamingTemplate::parse("{year}/{album}/{title}").unwrap();
+        assert_eq!(
+            template.render(Path::new("/m"), &fields, false),
+            Path::new("/m/Unknown/AC-DC- Live/Unknown.flac")
+        );
+    }
+
+    #[test]
+    fn prefixes_disc_on_multi_disc_albums() {
+        let fields = NamingFields {
+            disc: Some(2),
+            ..NamingFields::sample()
+        };
+        let path = NamingTemplate::default().render(Path::new("/m"), &fields, true);
+        assert_eq!(path.file_name().unwrap(), "2-01 - So What.flac");
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        for template in [
+            "",
+            "/{album}",
+            "{album}//{title}",
+            "{album",
+            "{album}}",
+            "{genre}",
+            "{title:02}",
+            "{track:x}",
+            "static/name",
+        ] {
+            assert!(NamingTemplate::parse(template).is_err(), "{}", template);
+        }
+    }
+}
@@ -13,6 +13,8 @@
 use std::sync::Mutex;
 use serde::{Deserialize, Serialize};
 
+use crate::config::playback_preferences::AutoplayMode;
+
 /// Type of playback context
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -128,6 +130,23 @@ impl PlaybackContext {
         };
         format!("{} · {}", type_str, self.label)
     }
+
+    /// Whether playback should roll into radio seeded from the last track
+    /// now that this context has no more tracks. Only Qobuz sources that
+    /// aren't radio already, and only in continue-within-source mode with
+    /// `radio_after_source` on.
+    pub fn continues_into_radio(&self, mode: AutoplayMode, radio_after_source: bool) -> bool {
+        radio_after_source
+            && mode == AutoplayMode::ContinueWithinSource
+            && self.source == ContentSource::Qobuz
+            && self.context_type != ContextType::Radio
+            && !self.has_next()
+    }
+
+    /// Label of the radio continuing this context
+    pub fn radio_continuation_label(&self) -> String {
+        format!("Radio from {}", self.label)
+    }
 }
 
 /// Manager for the current playback context
@@ -216,6 +235,14 @@ impl ContextManager {
         }
     }
 
+    /// Rename the current context (e.g. radio continuing an album)
+    pub fn set_label(&self, label: String) {
+        let mut current = self.current.lock().unwrap();
+        if let Some(ctx) = current.as_mut() {
+            ctx.label = label;
+        }
+    }
+
     /// Append new track IDs to the current context (for radio refill)
     pub fn append_track_ids(&self, new_track_ids: Vec<u64>) {
         let mut current = self.current.lock().unwrap();
@@ -226,3 +253,42 @@ impl ContextManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album(position: usize) -> PlaybackContext {
+        PlaybackContext::new(
+            ContextType::Album,
+            "album-1".to_string(),
+            "Kind of Blue".to_string(),
+            ContentSource::Qobuz,
+            vec![11, 12, 13],
+            position,
+        )
+    }
+
+    #[test]
+    fn exhausted_album_continues_into_radio_only_in_continue_mode() {
+        let finished = album(2);
+        assert!(finished.continues_into_radio(AutoplayMode::ContinueWithinSource, true));
+        assert_eq!(finished.radio_continuation_label(), "Radio from Kind of Blue");
+
+        // Option off, or a mode that doesn't continue within the source
+        assert!(!finished.continues_into_radio(AutoplayMode::ContinueWithinSource, false));
+        assert!(!finished.continues_into_radio(AutoplayMode::PlayTrackOnly, true));
+        assert!(!finished.continues_into_radio(AutoplayMode::InfiniteRadio, true));
+
+        // Tracks left in the album
+        assert!(!album(1).continues_into_radio(AutoplayMode::ContinueWithinSource, true));
+
+        // Radio doesn't chain into another radio, local albums have no radio
+        let mut radio = album(2);
+        radio.context_type = ContextType::Radio;
+        assert!(!radio.continues_into_radio(AutoplayMode::ContinueWithinSource, true));
+        let mut local = album(2);
+        local.source = ContentSource::Local;
+        assert!(!local.continues_into_radio(AutoplayMode::ContinueWithinSource, true));
+    }
+}
@@ -0,0 +1,243 @@
+//! Playback routing: which sink the transport commands drive
+//!
+//! The local player is the default route. While a Chromecast or DLNA device is
+//! connected, pause/resume/stop/seek/volume and track changes are forwarded to
+//! it, so the controls act on the audio that is actually heard. Route changes
+//! are emitted as `playback:route` so the UI can label the transport.
+
+use std::sync::RwLock;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cast::commands as cast_commands;
+use crate::cast::dlna::commands as dlna_commands;
+use crate::cast::dlna::{DlnaMetadata, DlnaState};
+use crate::cast::{CastState, MediaMetadata};
+use crate::queue::QueueTrack;
+use crate::AppState;
+
+/// A cast device as shown in the UI
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteDevice {
+    pub id: String,
+    pub name: String,
+}
+
+/// Where playback currently goes (payload of `playback:route`)
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlaybackRoute {
+    #[default]
+    Local,
+    Chromecast(RouteDevice),
+    Dlna(RouteDevice),
+}
+
+impl PlaybackRoute {
+    pub fn is_local(&self) -> bool {
+        matches!(self, PlaybackRoute::Local)
+    }
+
+    pub fn device(&self) -> Option<&RouteDevice> {
+        match self {
+            PlaybackRoute::Local => None,
+            PlaybackRoute::Chromecast(device) | PlaybackRoute::Dlna(device) => Some(device),
+        }
+    }
+}
+
+/// Transport actions that follow the active route
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransportAction {
+    Pause,
+    Resume,
+    Stop,
+    Seek(u64),
+    SetVolume(f32),
+}
+
+/// Holds the active route (kept in `AppState`)
+#[derive(Default)]
+pub struct PlaybackRouter {
+    route: RwLock<PlaybackRoute>,
+}
+
+impl PlaybackRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(&self) -> PlaybackRoute {
+        self.route.read().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    /// Replace the route; returns true when it changed
+    pub fn set_route(&self, route: PlaybackRoute) -> bool {
+        let Ok(mut current) = self.route.write() else {
+            return false;
+        };
+        if *current == route {
+            return false;
+        }
+        *current = route;
+        true
+    }
+
+    /// Go back to the local route if `leaving` matches the active one
+    pub fn reset_if(&self, leaving: impl Fn(&PlaybackRoute) -> bool) -> bool {
+        let Ok(mut current) = self.route.write() else {
+            return false;
+        };
+        if current.is_local() || !leaving(&current) {
+            return false;
+        }
+        *current = PlaybackRoute::Local;
+        true
+    }
+}
+
+/// Switch the active route, emitting `playback:route` when it changed
+pub fn set_route(app: &AppHandle, route: PlaybackRoute) {
+    if app.state::<AppState>().router.set_route(route.clone()) {
+        log::info!("Playback route: {:?}", route);
+        let _ = app.emit("playback:route", &route);
+    }
+}
+
+/// Return to the local player after disconnecting from a device of the given kind
+pub fn reset_route_if(app: &AppHandle, leaving: impl Fn(&PlaybackRoute) -> bool) {
+    if app.state::<AppState>().router.reset_if(leaving) {
+        log::info!("Playback route: Local");
+        let _ = app.emit("playback:route", &PlaybackRoute::Local);
+    }
+}
+
+/// Forward a transport action to the device of the active route.
+///
+/// Returns false when the route is local and the caller drives the local player.
+pub async fn dispatch(app: &AppHandle, action: TransportAction) -> Result<bool, String> {
+    match app.state::<AppState>().router.route() {
+        PlaybackRoute::Local => Ok(false),
+        PlaybackRoute::Chromecast(_) => {
+            let cast = app.state::<CastState>();
+            match action {
+                TransportAction::Pause => cast_commands::cast_pause(cast).await,
+                TransportAction::Resume => cast_commands::cast_play(cast).await,
+                TransportAction::Stop => cast_commands::cast_stop(cast).await,
+                TransportAction::Seek(position) => {
+                    cast_commands::cast_seek(position as f64, cast).await
+                }
+                TransportAction::SetVolume(volume) => {
+                    cast_commands::cast_set_volume(volume, cast).await
+                }
+            }?;
+            Ok(true)
+        }
+        PlaybackRoute::Dlna(_) => {
+            let dlna = app.state::<DlnaState>();
+            match action {
+                TransportAction::Pause => dlna_commands::dlna_pause(dlna).await,
+                TransportAction::Resume => dlna_commands::dlna_play(dlna).await,
+                TransportAction::Stop => dlna_commands::dlna_stop(dlna).await,
+                TransportAction::Seek(position) => dlna_commands::dlna_seek(position, dlna).await,
+                TransportAction::SetVolume(volume) => {
+                    dlna_commands::dlna_set_volume(volume, dlna).await
+                }
+            }?;
+            Ok(true)
+        }
+    }
+}
+
+/// Play a queue track on the device of the active route.
+///
+/// Returns false when the route is local and the caller plays it locally.
+pub async fn play_track(app: &AppHandle, track: &QueueTrack) -> Result<bool, String> {
+    let route = app.state::<AppState>().router.route();
+    if route.is_local() {
+        return Ok(false);
+    }
+    if track.is_local {
+        return Err("Library tracks cannot be cast from the queue".to_string());
+    }
+
+    match route {
+        PlaybackRoute::Local => Ok(false),
+        PlaybackRoute::Chromecast(_) => {
+            let metadata = MediaMetadata {
+                title: track.title.clone(),
+                artist: track.artist.clone(),
+                album: track.album.clone(),
+                artwork_url: track.artwork_url.clone(),
+                duration_secs: Some(track.duration_secs),
+            };
+            cast_commands::cast_play_track(
+                track.id,
+                metadata,
+                app.state::<CastState>(),
+                app.state::<AppState>(),
+            )
+            .await?;
+            Ok(true)
+        }
+        PlaybackRoute::Dlna(_) => {
+            let metadata = DlnaMetadata {
+                title: track.title.clone(),
+                artist: track.artist.clone(),
+                album: track.album.clone(),
+                artwork_url: track.artwork_url.clone(),
+                duration_secs: Some(track.duration_secs),
+            };
+            dlna_commands::dlna_play_track(
+                track.id,
+                metadata,
+                app.state::<DlnaState>(),
+                app.state::<AppState>(),
+            )
+            .await?;
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(name: &str) -> RouteDevice {
+        RouteDevice {
+            id: format!("{}-id", name),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn route_changes_are_reported_once() {
+        let router = PlaybackRouter::new();
+        assert!(router.route().is_local());
+        assert!(router.set_route(PlaybackRoute::Chromecast(device("Living Room TV"))));
+        assert!(!router.set_route(PlaybackRoute::Chromecast(device("Living Room TV"))));
+        assert_eq!(router.route().device().unwrap().name, "Living Room TV");
+    }
+
+    #[test]
+    fn reset_only_leaves_the_matching_route() {
+        let router = PlaybackRouter::new();
+        router.set_route(PlaybackRoute::Dlna(device("Receiver")));
+        assert!(!router.reset_if(|r| matches!(r, PlaybackRoute::Chromecast(_))));
+        assert!(router.reset_if(|r| matches!(r, PlaybackRoute::Dlna(_))));
+        assert!(router.route().is_local());
+    }
+
+    #[test]
+    fn route_serializes_with_kind_tag() {
+        let json = serde_json::to_value(PlaybackRoute::Chromecast(device("Kitchen"))).unwrap();
+        assert_eq!(json["kind"], "chromecast");
+        assert_eq!(json["name"], "Kitchen");
+        assert_eq!(
+            serde_json::to_value(PlaybackRoute::Local).unwrap()["kind"],
+            "local"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Decoded audio format details for the current track
+//!
+//! Filled from the container probe when a track is handed to the player and
+//! completed by the audio thread once it knows which decoder produced the
+//! samples. Lets users check what they are actually hearing (e.g. a real
+//! hi-res stream rather than a down-sampled one).
+
+use serde::Serialize;
+use symphonia::core::codecs::{CodecParameters, CodecType};
+use symphonia::default::get_codecs;
+
+/// Where the current track's audio came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackSource {
+    Qobuz,
+    Local,
+    Plex,
+}
+
+/// Code path that decoded the samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioDecoder {
+    /// rodio's decoder (including its mp4 variants)
+    Rodio,
+    /// Full-file symphonia decode, used when rodio can't handle the file
+    SymphoniaFallback,
+    /// Incremental symphonia decode while streaming
+    SymphoniaStreaming,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecodedFormatInfo {
+    pub track_id: u64,
+    pub source: Option<TrackSource>,
+    /// Codec short name, e.g. "FLAC", "ALAC", "AAC", "MP3"
+    pub codec: Option<String>,
+    /// Container, e.g. "flac", "mp4", "ogg" (None when unknown, e.g. streaming)
+    pub container: Option<String>,
+    pub sample_rate: u32,
+    pub bit_depth: Option<u32>,
+    pub channels: u16,
+    /// Average bitrate over the whole file
+    pub bitrate_kbps: Option<u32>,
+    /// None until the audio thread has decoded the track
+    pub decoder: Option<AudioDecoder>,
+}
+
+impl DecodedFormatInfo {
+    /// Details from the container probe of a complete file
+    pub(super) fn from_probe(
+        track_id: u64,
+        params: &CodecParameters,
+        data: &[u8],
+        sample_rate: u32,
+        channels: u16,
+    ) -> Self {
+        let duration_secs = params
+            .n_frames
+            .map(|frames| frames as f64 / sample_rate.max(1) as f64);
+
+        Self {
+            track_id,
+            source: None,
+            codec: codec_name(params.codec),
+            container: container_name(data).map(str::to_string),
+            sample_rate,
+            bit_depth: params.bits_per_sample,
+            channels,
+            bitrate_kbps: duration_secs
+                .and_then(|secs| average_bitrate_kbps(data.len() as u64, secs)),
+            decoder: None,
+        }
+    }
+
+    /// Details known before a stream starts (codec is filled in once probed)
+    pub(super) fn streaming(
+        track_id: u64,
+        sample_rate: u32,
+        bit_depth: Option<u32>,
+        channels: u16,
+        content_length: u64,
+        duration_secs: u64,
+    ) -> Self {
+        Self {
+            track_id,
+            source: None,
+            codec: None,
+            container: None,
+            sample_rate,
+            bit_depth,
+            channels,
+            bitrate_kbps: average_bitrate_kbps(content_length, duration_secs as f64),
+            decoder: None,
+        }
+    }
+}
+
+/// Display name of a symphonia codec
+pub(super) fn codec_name(codec: CodecType) -> Option<String> {
+    get_codecs()
+        .get_codec(codec)
+        .map(|descriptor| descriptor.short_name.to_uppercase())
+}
+
+/// Container format from the file's magic bytes
+pub(super) fn container_name(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"fLaC") {
+        Some("flac")
+    } else if data.len() >= 8 && &data[4..8] == b"ftyp" {
+        Some("mp4")
+    } else if data.starts_with(b"OggS") {
+        Some("ogg")
+    } else if data.starts_with(b"RIFF") {
+        Some("wav")
+    } else if data.starts_with(b"FORM") {
+        Some("aiff")
+    } else if data.starts_with(b"ID3")
+        || (data.len() >= 2 && data[0] == 0xFF && data[1] & 0xE0 == 0xE0)
+    {
+        Some("mp3")
+    } else {
+        None
+    }
+}
+
+/// Average bitrate in kbps for `bytes` of audio lasting `duration_secs`
+pub(super) fn average_bitrate_kbps(bytes: u64, duration_secs: f64) -> Option<u32> {
+    if duration_secs <= 0.0 || bytes == 0 {
+        return None;
+    }
+    Some((bytes as f64 * 8.0 / duration_secs / 1000.0).round() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_from_magic_bytes() {
+        assert_eq!(container_name(b"fLaC\0\0\0\x22"), Some("flac"));
+        assert_eq!(container_name(b"\0\0\0\x20ftypM4A "), Some("mp4"));
+        assert_eq!(container_name(b"ID3\x04\0"), Some("mp3"));
+        assert_eq!(container_name(&[0xFF, 0xFB, 0x90]), Some("mp3"));
+        assert_eq!(container_name(b"junk"), None);
+    }
+
+    #[test]
+    fn test_average_bitrate() {
+        // 10 MB over 60s of audio
+        assert_eq!(average_bitrate_kbps(10_000_000, 60.0), Some(1333));
+        assert_eq!(average_bitrate_kbps(10_000_000, 0.0), None);
+    }
+}
@@ -5,19 +5,27 @@
 //! - FLAC, MP3 decoding via symphonia
 //! - Gapless playback
 //! - Volume control
+//! - Sleep timer (fade and pause, or stop after the current track)
 //! - Real-time position tracking via events
 //!
 //! Uses a dedicated audio thread since rodio's OutputStream is not Send.
 //! Supports both rodio (PipeWire/Pulse) and direct ALSA (hw: devices).
 
+mod format_info;
+mod output_test;
 mod playback_engine;
+mod sleep_timer;
 mod streaming_source;
 
+pub use format_info::{AudioDecoder, DecodedFormatInfo, TrackSource};
+pub use output_test::play_test_tone;
+pub use sleep_timer::{SleepTimer, SleepTimerAction, SleepTimerStatus};
 pub use streaming_source::{BufferedMediaSource, BufferWriter, StreamingConfig, IncrementalStreamingSource};
+use streaming_source::STALL_NOTICE_AFTER;
 
 use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::panic::{self, AssertUnwindSafe};
-use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
 use std::sync::mpsc::{self, Sender, SyncSender, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -28,7 +36,7 @@ use rodio::decoder::Mp4Type;
 use rodio::cpal::traits::{DeviceTrait, HostTrait};
 use rodio::cpal::{StreamConfig, SampleRate, BufferSize, SupportedStreamConfig, SupportedBufferSize, SampleFormat};
 use symphonia::core::audio::SampleBuffer;
-use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::codecs::{CodecParameters, DecoderOptions};
 use symphonia::core::errors::Error as SymphoniaError;
 use symphonia::core::formats::FormatOptions;
 use symphonia::core::io::{MediaSource, MediaSourceStream};
@@ -39,11 +47,14 @@ use symphonia::default::{get_codecs, get_probe};
 use crate::api::{client::QobuzClient, models::Quality};
 use crate::audio::{
     AudioBackendType, AudioDiagnostic, BackendConfig, BackendManager, DiagnosticSource,
-    extract_replaygain, calculate_gain_factor, db_to_linear,
+    buffer_size_frames, resolve_buffer_size,
+    extract_replaygain, calculate_gain_factor, db_to_linear, ReplayGainData, ReplayGainMode,
     DynamicAmplify, AnalyzerTap, AnalyzerMessage, LoudnessCache, LoudnessAnalyzer,
+    EqControl, Equalizer, SilenceTrim, TrimState,
 };
-use crate::config::audio_settings::AudioSettings;
+use crate::config::audio_settings::{AudioSettings, DEFAULT_PAUSE_RELEASE_MS};
 use crate::visualizer::{VisualizerTap, TappedSource};
+use sleep_timer::ActiveSleepTimer;
 use playback_engine::PlaybackEngine;
 
 /// Commands sent to the audio thread
@@ -83,6 +94,11 @@ enum AudioCommand {
         track_id: u64,
         sample_rate: u32,
         channels: u16,
+        format_info: DecodedFormatInfo,
+        /// Qobuz format of the cached copy (None = unknown/local)
+        format_id: Option<u32>,
+        /// True when the cached copy was prefetched below the playback quality
+        reduced_quality: bool,
     },
 }
 
@@ -92,6 +108,14 @@ struct GaplessPending {
     duration_secs: u64,
     data: Vec<u8>,
     normalization_gain: Option<f32>,
+    /// Dynamic normalization gain (None = static or no normalization)
+    gain_atomic: Option<Arc<AtomicU32>>,
+    replaygain_mode: Option<ReplayGainMode>,
+    /// Silence trimming of the queued track (None = not trimmed)
+    silence_trimmed: Option<Arc<TrimState>>,
+    format_info: DecodedFormatInfo,
+    format_id: Option<u32>,
+    reduced_quality: bool,
 }
 
 struct CursorMediaSource {
@@ -226,6 +250,7 @@ struct AudioMetadata {
     sample_rate: u32,
     channels: u16,
     bit_depth: Option<u32>,
+    codec_params: CodecParameters,
 }
 
 #[allow(dead_code)]
@@ -277,17 +302,34 @@ fn extract_audio_metadata_full(data: &[u8]) -> Result<AudioMetadata, String> {
         sample_rate,
         channels,
         bit_depth,
+        codec_params: track.codec_params.clone(),
     })
 }
 
+/// Album gain factor, when album gain is in effect and the file is tagged with it.
+/// Applied as a static gain: per-track loudness analysis would undo the album balance.
+fn album_gain_factor(
+    replaygain: Option<&ReplayGainData>,
+    mode: ReplayGainMode,
+    target_lufs: f32,
+) -> Option<f32> {
+    if mode != ReplayGainMode::Album {
+        return None;
+    }
+    replaygain
+        .and_then(ReplayGainData::album)
+        .map(|album| calculate_gain_factor(&album, target_lufs))
+}
+
+/// Decode a complete file, returning the source and the decoder that produced it
 fn decode_with_fallback(
     data: &[u8],
-) -> Result<Box<dyn Source<Item = f32> + Send>, String> {
+) -> Result<(Box<dyn Source<Item = f32> + Send>, AudioDecoder), String> {
     if is_isomp4(data) {
         return decode_with_symphonia(data)
             .map(|specs| {
                 log::info!("Decoded audio using symphonia fallback (isomp4)");
-                Box::new(specs.samples) as Box<dyn Source<Item = f32> + Send>
+                (Box::new(specs.samples) as Box<dyn Source<Item = f32> + Send>, AudioDecoder::SymphoniaFallback)
             });
     }
 
@@ -296,7 +338,7 @@ fn decode_with_fallback(
     }));
 
     match primary {
-        Ok(Ok(decoder)) => return Ok(Box::new(decoder)),
+        Ok(Ok(decoder)) => return Ok((Box::new(decoder), AudioDecoder::Rodio)),
         Ok(Err(err)) => {
             log::warn!("Primary decode failed, attempting mp4 fallback: {}", err);
         }
@@ -315,7 +357,7 @@ fn decode_with_fallback(
         match attempt {
             Ok(Ok(decoder)) => {
                 log::info!("Decoded audio using mp4 fallback ({})", hint_label);
-                return Ok(Box::new(decoder));
+                return Ok((Box::new(decoder), AudioDecoder::Rodio));
             }
             Ok(Err(err)) => {
                 log::warn!("mp4 fallback ({}) failed: {}", hint_label, err);
@@ -329,35 +371,37 @@ fn decode_with_fallback(
     match decode_with_symphonia(data) {
         Ok(specs) => {
             log::info!("Decoded audio using symphonia fallback");
-            Ok(Box::new(specs.samples))
+            Ok((Box::new(specs.samples), AudioDecoder::SymphoniaFallback))
         }
         Err(err) => Err(err),
     }
 }
 
 /// Create OutputStream with custom sample rate configuration
+///
+/// `buffer_frames` requests a fixed buffer size (clamped to the device range);
+/// None keeps the backend default, or 512 frames in exclusive mode. Too-small
+/// values may cause xruns. Returns the buffer size the stream was opened with.
 fn create_output_stream_with_config(
     device: &rodio::cpal::Device,
     sample_rate: u32,
     channels: u16,
     exclusive_mode: bool,
-) -> Result<(OutputStream, rodio::OutputStreamHandle), String> {
+    buffer_frames: Option<u32>,
+) -> Result<(OutputStream, rodio::OutputStreamHandle, BufferSize), String> {
     log::info!(
-        "Creating OutputStream: {}Hz, {} channels, exclusive: {}",
+        "Creating OutputStream: {}Hz, {} channels, exclusive: {}, buffer: {:?}",
         sample_rate,
         channels,
-        exclusive_mode
+        exclusive_mode,
+        buffer_frames
     );
 
     // Create StreamConfig with desired sample rate
     let config = StreamConfig {
         channels,
         sample_rate: SampleRate(sample_rate),
-        buffer_size: if exclusive_mode {
-            BufferSize::Fixed(512)  // Lower latency for exclusive mode
-        } else {
-            BufferSize::Default
-        },
+        buffer_size: BufferSize::Default,
     };
 
     // Check if device supports this configuration
@@ -366,12 +410,14 @@ fn create_output_stream_with_config(
         .map_err(|e| format!("Failed to get supported configs: {}", e))?;
 
     let mut found_matching = false;
+    let mut supported_buffer: Option<SupportedBufferSize> = None;
     for range in supported_configs {
         if range.channels() == channels
             && sample_rate >= range.min_sample_rate().0
             && sample_rate <= range.max_sample_rate().0
         {
             found_matching = true;
+            supported_buffer = Some(*range.buffer_size());
             log::info!(
                 "Device supports {}Hz (range: {}-{}Hz)",
                 sample_rate,
@@ -397,11 +443,13 @@ fn create_output_stream_with_config(
         SampleFormat::F32,
     );
 
+    let buffer_size = resolve_buffer_size(buffer_frames, exclusive_mode, supported_buffer.as_ref());
+
     // Create OutputStream with custom config
-    match OutputStream::try_from_device_config(device, supported_config) {
+    match OutputStream::try_from_device_config_with_buffer_size(device, supported_config, buffer_size) {
         Ok((stream, handle)) => {
             log::info!("OutputStream created successfully at {}Hz", sample_rate);
-            Ok((stream, handle))
+            Ok((stream, handle, buffer_size))
         }
         Err(e) => {
             log::error!("❌ Failed to create OutputStream at {}Hz: {}", sample_rate, e);
@@ -410,13 +458,143 @@ fn create_output_stream_with_config(
     }
 }
 
+/// Digital silence played on a freshly opened stream before the track starts.
+///
+/// After a sample-rate switch the DAC needs time to lock to the new clock; feeding
+/// it silence instead of blocking the audio thread avoids clipping the first notes.
+const STREAM_PREROLL_MS: u64 = 150;
+
+/// Length of the gain ramp from the pre-roll silence into the track.
+///
+/// Jumping from silence straight to a non-zero sample clicks on some DACs. Only
+/// the samples inside this window are scaled; everything after it is bit-perfect.
+const STREAM_RAMP_MS: u64 = 5;
+
+/// Prepend the pre-roll silence and ramp into the track when the stream was just (re)created
+fn with_stream_preroll(
+    source: Box<dyn Source<Item = f32> + Send>,
+    preroll: bool,
+) -> Box<dyn Source<Item = f32> + Send> {
+    if preroll {
+        Box::new(
+            source
+                .fade_in(Duration::from_millis(STREAM_RAMP_MS))
+                .delay(Duration::from_millis(STREAM_PREROLL_MS)),
+        )
+    } else {
+        source
+    }
+}
+
+/// Trim state for a new track when the user enabled silence trimming
+fn new_trim_state(settings: &Mutex<AudioSettings>) -> Option<Arc<TrimState>> {
+    settings
+        .lock()
+        .ok()
+        .filter(|s| s.silence_trim_enabled)
+        .map(|s| Arc::new(TrimState::new(s.silence_trim_threshold_db)))
+}
+
+/// Wrap a decoded source, starting `start_secs` into the track, with silence
+/// trimming (no-op without a trim state).
+///
+/// Trailing silence is kept while `album_boundary` is set. The leading
+/// silence is read here, on the player thread.
+fn with_silence_trim(
+    source: Box<dyn Source<Item = f32> + Send>,
+    trim: Option<&Arc<TrimState>>,
+    start_secs: u64,
+    trim_leading: bool,
+    album_boundary: &Arc<AtomicBool>,
+) -> Box<dyn Source<Item = f32> + Send> {
+    let Some(trim) = trim else {
+        return source;
+    };
+    let start_frame = start_secs * source.sample_rate() as u64;
+    Box::new(SilenceTrim::new(
+        source,
+        trim.clone(),
+        start_frame,
+        trim_leading,
+        album_boundary.clone(),
+    ))
+}
+
+/// Find the trailing silence of a complete file on a background thread
+fn spawn_tail_scan(data: Vec<u8>, trim: Arc<TrimState>) {
+    std::thread::spawn(move || match decode_with_fallback(&data) {
+        Ok((source, _)) => trim.scan(source),
+        Err(e) => log::warn!("Silence trim: failed to decode track for tail scan: {}", e),
+    });
+}
+
+/// Find the trailing silence of a streamed track once its download is done
+///
+/// Gives up when the download fails or the track stopped playing (nothing
+/// else holds the trim state any more).
+fn spawn_stream_tail_scan(source: Arc<BufferedMediaSource>, trim: Arc<TrimState>) {
+    std::thread::spawn(move || {
+        while !source.is_complete() {
+            if source.download_error().is_some() || Arc::strong_count(&trim) == 1 {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        if let Some(data) = source.take_complete_data() {
+            spawn_tail_scan(data, trim);
+        }
+    });
+}
+
+/// Release the previous track's engine before starting a new one.
+///
+/// With `reuse` (stream kept, same sample rate/channels) the live Rodio sink is
+/// cleared and handed back, skipping the teardown and the settle delay a new
+/// sink needs. Otherwise the engine is stopped and rebuilt as before.
+fn release_engine(
+    current_engine: &mut Option<PlaybackEngine>,
+    reuse: bool,
+) -> Option<PlaybackEngine> {
+    let engine = current_engine.take()?;
+    if reuse && engine.clear_for_reuse() {
+        return Some(engine);
+    }
+    engine.stop();
+    // Small delay to allow the audio sink to fully release its
+    // reference to the OutputStreamHandle before creating a new sink.
+    // This prevents "resource busy" errors on rapid track switches.
+    std::thread::sleep(Duration::from_millis(50));
+    None
+}
+
 /// Output stream type - either rodio or ALSA Direct
 enum StreamType {
-    Rodio(OutputStream, rodio::OutputStreamHandle),
+    Rodio(OutputStream, rodio::OutputStreamHandle, BufferSize),
     #[cfg(target_os = "linux")]
     AlsaDirect(Arc<crate::audio::AlsaDirectStream>),
 }
 
+/// Effective buffer size of a stream in frames (0 = backend default/unknown).
+/// ALSA Direct reports the negotiated hardware buffer; rodio streams report the
+/// size they were opened with after clamping to the device range.
+fn stream_buffer_frames(stream: &StreamType) -> u32 {
+    match stream {
+        StreamType::Rodio(_, _, buffer_size) => buffer_size_frames(buffer_size),
+        #[cfg(target_os = "linux")]
+        StreamType::AlsaDirect(alsa_stream) => alsa_stream.buffer_frames(),
+    }
+}
+
+/// Hook ALSA Direct streams up to the xrun counters and the ALSA path report
+/// (rodio streams report none).
+fn attach_stream_diagnostic(stream: &StreamType, diagnostic: &AudioDiagnostic) {
+    match stream {
+        StreamType::Rodio(..) => diagnostic.set_alsa_path(None),
+        #[cfg(target_os = "linux")]
+        StreamType::AlsaDirect(alsa_stream) => alsa_stream.attach_diagnostic(diagnostic.clone()),
+    }
+}
+
 /// Try to create output stream using the backend system (if configured)
 /// Returns None if backend system is not configured (backend_type = None)
 ///
@@ -460,6 +638,8 @@ fn try_init_stream_with_backend(
         channels,
         exclusive_mode: audio_settings.exclusive_mode,
         alsa_plugin: audio_settings.alsa_plugin,
+        buffer_frames: audio_settings.buffer_frames,
+        period_frames: audio_settings.alsa_period_frames,
     };
 
     // For ALSA backend with hw: devices, try direct ALSA first (Linux only)
@@ -487,7 +667,7 @@ fn try_init_stream_with_backend(
     match backend.create_output_stream(&config) {
         Ok(stream) => {
             log::info!("Stream created via {:?} backend at {}Hz", backend_type, sample_rate);
-            Some(Ok(StreamType::Rodio(stream.0, stream.1)))
+            Some(Ok(StreamType::Rodio(stream.0, stream.1, stream.2)))
         }
         Err(e) => {
             log::error!("❌ Backend stream creation failed: {}", e);
@@ -520,6 +700,54 @@ pub struct PlaybackEvent {
     /// Track ID of the gapless-queued next track (0 = none queued)
     #[serde(default)]
     pub gapless_next_track_id: u64,
+    /// True when the upcoming queue track is the next track of the same album
+    #[serde(default)]
+    pub gapless_boundary: bool,
+    /// Seconds left on the sleep timer (None = no timer set)
+    #[serde(default)]
+    pub sleep_timer_remaining: Option<u64>,
+    /// True when the offline copy is playing because streaming failed
+    #[serde(default)]
+    pub playing_from_cache: bool,
+    /// Qobuz format of the playing data (5=MP3, 6=CD, 7=Hi-Res, 27=Hi-Res+; None = unknown)
+    #[serde(default)]
+    pub format_id: Option<u32>,
+    /// True when the track plays at the lower prefetch quality instead of the requested one
+    #[serde(default)]
+    pub reduced_quality: bool,
+    /// ReplayGain value applied to the current track ("track"/"album", None = normalization off)
+    #[serde(default)]
+    pub replaygain_mode: Option<ReplayGainMode>,
+    /// True when the ReplayGain value follows the playback context (auto mode)
+    #[serde(default)]
+    pub replaygain_auto: bool,
+    /// Seconds of streamed audio downloaded ahead of playback (None when not streaming)
+    #[serde(default)]
+    pub buffered_ahead_secs: Option<f32>,
+}
+
+/// Payload of the `playback:buffering` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BufferingEvent {
+    pub track_id: u64,
+    /// False once playback has resumed (or buffering was abandoned)
+    pub buffering: bool,
+    /// Buffer fill towards the resume threshold (0-100)
+    pub progress: u8,
+    /// True for a mid-track stall, false for the initial buffer
+    pub rebuffering: bool,
+    /// Seconds of audio downloaded ahead of playback (None when not streaming)
+    pub buffered_ahead_secs: Option<f32>,
+}
+
+/// Payload of the `playback:error` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlaybackErrorEvent {
+    pub message: String,
+    /// True when playback cannot continue without restarting QBZ
+    pub fatal: bool,
+    /// True when the audio engine was restarted automatically
+    pub restarted: bool,
 }
 
 /// Shared state between main thread and audio thread
@@ -553,6 +781,43 @@ pub struct SharedState {
     gapless_ready: Arc<AtomicBool>,
     /// Track ID of the gapless-queued next track (0 = none)
     gapless_next_track_id: Arc<AtomicU64>,
+    /// True when the queue reports the next track as a contiguous album track
+    gapless_boundary: Arc<AtomicBool>,
+    /// Silence trimmed from the current track so far (ms), None when not trimming
+    silence_trimmed: Arc<std::sync::RwLock<Option<Arc<TrimState>>>>,
+    /// The next Play continues the album of the track before it, so its
+    /// lead-in isn't trimmed (consumed by the Play)
+    keep_lead_in: Arc<AtomicBool>,
+    /// Decoded format of the current track
+    format_info: Arc<std::sync::RwLock<Option<DecodedFormatInfo>>>,
+    /// True while the playback context is an album (auto ReplayGain mode picks album gain)
+    album_context: Arc<AtomicBool>,
+    /// ReplayGain value applied to the current track (None = normalization off)
+    replaygain_mode: Arc<std::sync::RwLock<Option<ReplayGainMode>>>,
+    /// True when the current track is the offline copy played after a stream failure
+    playing_from_cache: Arc<AtomicBool>,
+    /// Qobuz format of the current track (0 = unknown)
+    format_id: Arc<AtomicU32>,
+    /// True when the current track was prefetched below the requested quality
+    reduced_quality: Arc<AtomicBool>,
+    /// Effective output buffer size in frames (0 = backend default/unknown)
+    output_buffer_frames: Arc<AtomicU32>,
+    /// Sample rate the output stream was opened at (0 = no stream)
+    output_sample_rate: Arc<AtomicU32>,
+    /// True when the output stream is ALSA Direct (straight to hardware)
+    output_alsa_direct: Arc<AtomicBool>,
+    /// Running sleep timer (checked by the audio thread on every tick)
+    sleep_timer: Arc<std::sync::RwLock<Option<ActiveSleepTimer>>>,
+    /// 0 = not buffering, 1 = initial buffer, 2 = re-buffering after a stall
+    buffering: Arc<AtomicU8>,
+    /// Buffer fill towards the resume threshold (0-100)
+    buffering_progress: Arc<AtomicU8>,
+    /// Streamed audio downloaded ahead of playback in ms (u32::MAX = not streaming)
+    buffered_ahead_ms: Arc<AtomicU32>,
+    /// Why streaming playback was stopped, until reported to the user
+    stream_failure: Arc<Mutex<Option<String>>>,
+    /// False once the audio thread has exited (e.g. after a panic)
+    audio_thread_alive: Arc<AtomicBool>,
 }
 
 impl Default for SharedState {
@@ -578,9 +843,32 @@ impl SharedState {
             normalization_gain: Arc::new(AtomicU32::new(0)),
             gapless_ready: Arc::new(AtomicBool::new(false)),
             gapless_next_track_id: Arc::new(AtomicU64::new(0)),
+            gapless_boundary: Arc::new(AtomicBool::new(false)),
+            silence_trimmed: Arc::new(std::sync::RwLock::new(None)),
+            keep_lead_in: Arc::new(AtomicBool::new(false)),
+            format_info: Arc::new(std::sync::RwLock::new(None)),
+            album_context: Arc::new(AtomicBool::new(false)),
+            replaygain_mode: Arc::new(std::sync::RwLock::new(None)),
+            playing_from_cache: Arc::new(AtomicBool::new(false)),
+            format_id: Arc::new(AtomicU32::new(0)),
+            reduced_quality: Arc::new(AtomicBool::new(false)),
+            output_buffer_frames: Arc::new(AtomicU32::new(0)),
+            output_sample_rate: Arc::new(AtomicU32::new(0)),
+            output_alsa_direct: Arc::new(AtomicBool::new(false)),
+            sleep_timer: Arc::new(std::sync::RwLock::new(None)),
+            buffering: Arc::new(AtomicU8::new(0)),
+            buffering_progress: Arc::new(AtomicU8::new(0)),
+            buffered_ahead_ms: Arc::new(AtomicU32::new(u32::MAX)),
+            stream_failure: Arc::new(Mutex::new(None)),
+            audio_thread_alive: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Whether the audio thread is running and accepting commands
+    pub fn is_audio_thread_alive(&self) -> bool {
+        self.audio_thread_alive.load(Ordering::SeqCst)
+    }
+
     pub fn set_stream_error(&self, error: bool) {
         self.stream_error.store(error, Ordering::SeqCst);
     }
@@ -643,6 +931,224 @@ impl SharedState {
         self.gapless_next_track_id.load(Ordering::SeqCst)
     }
 
+    /// Set by the queue owner: whether the next track continues the current album
+    pub fn set_gapless_boundary(&self, boundary: bool) {
+        self.gapless_boundary.store(boundary, Ordering::SeqCst);
+    }
+
+    pub fn is_gapless_boundary(&self) -> bool {
+        self.gapless_boundary.load(Ordering::SeqCst)
+    }
+
+    /// Set by the queue owner before a Play: whether the track directly
+    /// follows the previous one on the same album
+    pub fn set_keep_lead_in(&self, keep: bool) {
+        self.keep_lead_in.store(keep, Ordering::SeqCst);
+    }
+
+    fn take_keep_lead_in(&self) -> bool {
+        self.keep_lead_in.swap(false, Ordering::SeqCst)
+    }
+
+    fn set_silence_trimmed(&self, trimmed: Option<Arc<TrimState>>) {
+        if let Ok(mut guard) = self.silence_trimmed.write() {
+            *guard = trimmed;
+        }
+    }
+
+    fn silence_trim_state(&self) -> Option<Arc<TrimState>> {
+        self.silence_trimmed
+            .read()
+            .ok()
+            .and_then(|guard| guard.clone())
+    }
+
+    /// Seconds of silence skipped in the current track so far. The track's
+    /// audio ends this much earlier than its duration.
+    fn silence_trimmed_secs(&self) -> u64 {
+        self.silence_trim_state()
+            .map(|trim| trim.trimmed_ms() / 1000)
+            .unwrap_or(0)
+    }
+
+    /// Tell the audio thread whether the playback context is an album
+    pub fn set_album_context(&self, is_album: bool) {
+        self.album_context.store(is_album, Ordering::SeqCst);
+    }
+
+    pub fn is_album_context(&self) -> bool {
+        self.album_context.load(Ordering::SeqCst)
+    }
+
+    fn set_replaygain_mode(&self, mode: Option<ReplayGainMode>) {
+        if let Ok(mut guard) = self.replaygain_mode.write() {
+            *guard = mode;
+        }
+    }
+
+    /// ReplayGain value applied to the current track (None = normalization off)
+    pub fn replaygain_mode(&self) -> Option<ReplayGainMode> {
+        self.replaygain_mode.read().ok().and_then(|guard| *guard)
+    }
+
+    fn set_format_info(&self, info: Option<DecodedFormatInfo>) {
+        if let Ok(mut guard) = self.format_info.write() {
+            *guard = info;
+        }
+    }
+
+    /// Update the format info, if it still belongs to `track_id`
+    fn update_format_info(&self, track_id: u64, update: impl FnOnce(&mut DecodedFormatInfo)) {
+        if let Ok(mut guard) = self.format_info.write() {
+            if let Some(info) = guard.as_mut().filter(|info| info.track_id == track_id) {
+                update(info);
+            }
+        }
+    }
+
+    /// Switch to a gapless-queued track's format (same source as the current one)
+    fn set_gapless_format_info(&self, mut info: DecodedFormatInfo) {
+        if let Ok(mut guard) = self.format_info.write() {
+            info.source = guard.as_ref().and_then(|current| current.source);
+            *guard = Some(info);
+        }
+    }
+
+    /// Record where the current track's audio came from (call after play_data/play_streaming)
+    pub fn set_track_source(&self, source: TrackSource) {
+        if let Ok(mut guard) = self.format_info.write() {
+            if let Some(info) = guard.as_mut() {
+                info.source = Some(source);
+            }
+        }
+    }
+
+    /// Decoded format of the current track (None before anything was played)
+    pub fn format_info(&self) -> Option<DecodedFormatInfo> {
+        self.format_info.read().ok().and_then(|guard| guard.clone())
+    }
+
+    /// Mark the current track as the offline copy played after a stream failure
+    pub fn set_playing_from_cache(&self, from_cache: bool) {
+        self.playing_from_cache.store(from_cache, Ordering::SeqCst);
+    }
+
+    pub fn is_playing_from_cache(&self) -> bool {
+        self.playing_from_cache.load(Ordering::SeqCst)
+    }
+
+    /// Record the Qobuz format of the current track and whether it is below
+    /// the requested quality
+    pub fn set_track_format(&self, format_id: Option<u32>, reduced_quality: bool) {
+        self.format_id.store(format_id.unwrap_or(0), Ordering::SeqCst);
+        self.reduced_quality.store(reduced_quality, Ordering::SeqCst);
+    }
+
+    pub fn get_format_id(&self) -> Option<u32> {
+        match self.format_id.load(Ordering::SeqCst) {
+            0 => None,
+            id => Some(id),
+        }
+    }
+
+    pub fn is_reduced_quality(&self) -> bool {
+        self.reduced_quality.load(Ordering::SeqCst)
+    }
+
+    pub fn set_output_buffer_frames(&self, frames: u32) {
+        self.output_buffer_frames.store(frames, Ordering::SeqCst);
+    }
+
+    /// Effective output buffer size in frames (0 = backend default/unknown)
+    pub fn get_output_buffer_frames(&self) -> u32 {
+        self.output_buffer_frames.load(Ordering::SeqCst)
+    }
+
+    pub fn set_output_format(&self, sample_rate: u32, alsa_direct: bool) {
+        self.output_sample_rate.store(sample_rate, Ordering::SeqCst);
+        self.output_alsa_direct.store(alsa_direct, Ordering::SeqCst);
+    }
+
+    /// Sample rate the output stream was opened at (0 = no stream)
+    pub fn get_output_sample_rate(&self) -> u32 {
+        self.output_sample_rate.load(Ordering::SeqCst)
+    }
+
+    pub fn is_output_alsa_direct(&self) -> bool {
+        self.output_alsa_direct.load(Ordering::SeqCst)
+    }
+
+    fn set_sleep_timer(&self, timer: Option<ActiveSleepTimer>) {
+        if let Ok(mut guard) = self.sleep_timer.write() {
+            *guard = timer;
+        }
+    }
+
+    fn sleep_timer(&self) -> Option<ActiveSleepTimer> {
+        self.sleep_timer.read().ok().and_then(|guard| *guard)
+    }
+
+    pub fn sleep_timer_status(&self) -> Option<SleepTimerStatus> {
+        self.sleep_timer().map(|timer| timer.status(Instant::now()))
+    }
+
+    fn set_buffering(&self, rebuffering: bool, progress: u8) {
+        self.buffering_progress.store(progress, Ordering::SeqCst);
+        self.buffering.store(if rebuffering { 2 } else { 1 }, Ordering::SeqCst);
+    }
+
+    fn clear_buffering(&self) {
+        self.buffering.store(0, Ordering::SeqCst);
+        self.buffering_progress.store(0, Ordering::SeqCst);
+    }
+
+    /// Buffering status: (rebuffering, progress percent), None when not buffering
+    pub fn buffering_status(&self) -> Option<(bool, u8)> {
+        match self.buffering.load(Ordering::SeqCst) {
+            0 => None,
+            mode => Some((mode == 2, self.buffering_progress.load(Ordering::SeqCst))),
+        }
+    }
+
+    fn set_buffered_ahead(&self, secs: Option<f32>) {
+        let ms = secs.map_or(u32::MAX, |secs| {
+            (secs * 1000.0).min(u32::MAX as f32 - 1.0) as u32
+        });
+        self.buffered_ahead_ms.store(ms, Ordering::Relaxed);
+    }
+
+    /// Seconds of streamed audio downloaded ahead of playback (None when not streaming)
+    pub fn buffered_ahead_secs(&self) -> Option<f32> {
+        match self.buffered_ahead_ms.load(Ordering::Relaxed) {
+            u32::MAX => None,
+            ms => Some(ms as f32 / 1000.0),
+        }
+    }
+
+    fn set_stream_failure(&self, message: String) {
+        if let Ok(mut failure) = self.stream_failure.lock() {
+            *failure = Some(message);
+        }
+    }
+
+    /// Why streaming playback was stopped, once (None when nothing failed since)
+    pub fn take_stream_failure(&self) -> Option<String> {
+        self.stream_failure
+            .lock()
+            .ok()
+            .and_then(|mut failure| failure.take())
+    }
+
+    /// Drop a sleep timer that ran out while nothing was playing
+    fn clear_expired_sleep_timer(&self) {
+        if let Some(timer) = self.sleep_timer() {
+            if timer.is_expired(Instant::now()) {
+                log::info!("Sleep timer expired while paused, clearing");
+                self.set_sleep_timer(None);
+            }
+        }
+    }
+
     /// Get current position based on elapsed time since playback started
     pub fn current_position(&self) -> u64 {
         if !self.is_playing.load(Ordering::SeqCst) {
@@ -706,11 +1212,33 @@ impl SharedState {
     }
 }
 
+/// Name of the audio thread (shows up in panic reports)
+pub const AUDIO_THREAD_NAME: &str = "qbz-audio";
+
+/// Error returned once the audio thread is gone for good
+pub const AUDIO_THREAD_DEAD: &str =
+    "The audio engine stopped unexpectedly. Please restart QBZ to restore playback.";
+
+/// Clears `audio_thread_alive` when the audio thread exits or unwinds
+struct AudioThreadGuard(SharedState);
+
+impl Drop for AudioThreadGuard {
+    fn drop(&mut self) {
+        self.0.audio_thread_alive.store(false, Ordering::SeqCst);
+        self.0.is_playing.store(false, Ordering::SeqCst);
+        if thread::panicking() {
+            log::error!("Audio thread panicked");
+        }
+    }
+}
+
 /// Audio player that handles streaming playback
 /// Uses a dedicated thread for audio output
 pub struct Player {
-    /// Channel to send commands to the audio thread
-    tx: Sender<AudioCommand>,
+    /// Channel to send commands to the audio thread (replaced on respawn)
+    tx: Mutex<Sender<AudioCommand>>,
+    /// Set once the audio thread has been restarted after dying
+    audio_thread_respawned: AtomicBool,
     /// Shared state accessible from any thread
     pub state: SharedState,
     /// Audio settings (exclusive mode, DAC passthrough, etc.)
@@ -720,6 +1248,8 @@ pub struct Player {
     visualizer_tap: Option<VisualizerTap>,
     /// Bit-depth diagnostic capture (always available, zero-cost when idle)
     pub diagnostic: AudioDiagnostic,
+    /// Parametric EQ shared with the audio path (changes apply live)
+    pub equalizer: Arc<EqControl>,
 }
 
 impl Default for Player {
@@ -733,20 +1263,48 @@ impl Player {
     /// If device_name is None, uses the system default device
     /// visualizer_tap is optional - if provided, audio samples are captured for visualization
     pub fn new(device_name: Option<String>, audio_settings: AudioSettings, visualizer_tap: Option<VisualizerTap>, diagnostic: AudioDiagnostic) -> Self {
-        let (tx, rx) = mpsc::channel::<AudioCommand>();
         let state = SharedState::new();
-        let thread_state = state.clone();
-
-        // Clone settings for thread
         let settings = Arc::new(Mutex::new(audio_settings.clone()));
-        let thread_settings = settings.clone();
+        let equalizer = Arc::new(EqControl::new(audio_settings.eq_config.clone()));
+
+        let tx = Self::spawn_audio_thread(
+            device_name,
+            state.clone(),
+            settings.clone(),
+            visualizer_tap.clone(),
+            diagnostic.clone(),
+            equalizer.clone(),
+        );
 
-        // Clone visualizer tap and diagnostic for audio thread
-        let thread_viz_tap = visualizer_tap.clone();
-        let thread_diagnostic = diagnostic.clone();
+        Self {
+            tx: Mutex::new(tx),
+            audio_thread_respawned: AtomicBool::new(false),
+            state,
+            audio_settings: settings,
+            visualizer_tap,
+            diagnostic,
+            equalizer,
+        }
+    }
+
+    /// Spawn the dedicated audio thread and return its command channel
+    fn spawn_audio_thread(
+        device_name: Option<String>,
+        thread_state: SharedState,
+        thread_settings: Arc<Mutex<AudioSettings>>,
+        thread_viz_tap: Option<VisualizerTap>,
+        thread_diagnostic: AudioDiagnostic,
+        thread_equalizer: Arc<EqControl>,
+    ) -> Sender<AudioCommand> {
+        let (tx, rx) = mpsc::channel::<AudioCommand>();
+        thread_state.audio_thread_alive.store(true, Ordering::SeqCst);
 
         // Spawn dedicated audio thread
-        thread::spawn(move || {
+        thread::Builder::new()
+            .name(AUDIO_THREAD_NAME.to_string())
+            .spawn(move || {
+            // Flags the thread as dead when it exits, also when it panics
+            let _alive_guard = AudioThreadGuard(thread_state.clone());
             log::info!("Audio thread starting...");
 
             // Initialize loudness analysis system
@@ -763,11 +1321,11 @@ impl Player {
             let _analyzer_handle = LoudnessAnalyzer::spawn(analyzer_rx, loudness_cache.clone());
             let analyzer_enabled = Arc::new(AtomicBool::new(false));
 
-            // Helper to wrap source with visualizer tap, normalization, and diagnostic capture
+            // Helper to wrap source with visualizer tap, normalization, EQ, and diagnostic capture
             // Pipeline order (normalization ON):
-            //   Diagnostic (raw) → AnalyzerTap → DynamicAmplify → Visualizer
+            //   Diagnostic (raw) → AnalyzerTap → DynamicAmplify → Equalizer → Visualizer
             // Pipeline order (normalization OFF — bit-perfect):
-            //   Diagnostic (raw) → Visualizer
+            //   Diagnostic (raw) → Equalizer (bypassed) → Visualizer
             let wrap_source = |source: Box<dyn Source<Item = f32> + Send>,
                                normalization_gain: Option<f32>,
                                gain_atomic: Option<Arc<AtomicU32>>,
@@ -791,6 +1349,18 @@ impl Player {
                     source
                 };
 
+                // EQ is bypassed on bit-perfect paths unless the user overrides it
+                let dac_passthrough = thread_settings
+                    .lock()
+                    .map(|s| s.dac_passthrough)
+                    .unwrap_or(false);
+                thread_equalizer.set_bit_perfect_output(thread_state.is_output_alsa_direct() || dac_passthrough);
+                if thread_equalizer.is_active() {
+                    log::info!("Audio thread: equalizer active");
+                }
+                let source: Box<dyn Source<Item = f32> + Send> =
+                    Box::new(Equalizer::new(source, thread_equalizer.clone()));
+
                 // Visualizer tap (outermost)
                 if let Some(ref tap) = thread_viz_tap {
                     Box::new(TappedSource::new(source, tap.ring_buffer.clone(), tap.enabled.clone()))
@@ -883,14 +1453,14 @@ impl Player {
                 match OutputStream::try_from_device(&device) {
                     Ok((stream, handle)) => {
                         log::info!("Audio output initialized successfully");
-                        Some(StreamType::Rodio(stream, handle))
+                        Some(StreamType::Rodio(stream, handle, BufferSize::Default))
                     }
                     Err(e) => {
                         log::error!("Failed to create audio output on device: {}. Trying default...", e);
                         match OutputStream::try_default() {
                             Ok((stream, handle)) => {
                                 log::info!("Fallback to default audio output succeeded");
-                                Some(StreamType::Rodio(stream, handle))
+                                Some(StreamType::Rodio(stream, handle, BufferSize::Default))
                             }
                             Err(e2) => {
                                 log::error!("Failed to create default audio output: {}", e2);
@@ -921,16 +1491,23 @@ impl Player {
             // Track consecutive sink creation failures to detect broken streams
             let mut consecutive_sink_failures: u32 = 0;
             const MAX_SINK_FAILURES: u32 = 3;
-            // Delay dropping the audio stream after pause to reduce CPU usage.
-            const PAUSE_SUSPEND_DELAY_MS: u64 = 2000;
+            // Delay dropping the audio stream after pause to reduce CPU usage
+            // (`pause_release_ms` / `keep_device_open` in the audio settings).
             let mut pause_suspend_deadline: Option<Instant> = None;
             let mut last_empty_check = Instant::now();
+            // When the current streaming track stalled (output paused while re-buffering)
+            let mut rebuffer_started: Option<Instant> = None;
+            // True while the download is stalled and buffering is reported ahead of a stall
+            let mut download_stall_noticed = false;
             // Current track's normalization gain factor (stored for reuse on resume/seek)
             let mut current_normalization_gain: Option<f32> = None;
             // Current track's dynamic gain atomic (shared with DynamicAmplify + LoudnessAnalyzer)
             let mut current_gain_atomic: Option<Arc<AtomicU32>> = None;
             // Gapless: pending next track that has been appended to the Sink
             let mut gapless_pending: Option<GaplessPending> = None;
+            // Sleep timer: whether the engine volume is currently faded below the user volume
+            let mut sleep_fade_active = false;
+            let mut was_playing = false;
 
             log::info!("Audio thread ready and waiting for commands");
 
@@ -956,6 +1533,7 @@ impl Player {
                             channels
                         );
                         *pause_suspend_deadline = None;
+                        thread_state.clear_buffering();
                         // Clear any pending gapless state (new Play supersedes queued gapless)
                         *gapless_pending = None;
                         thread_state.set_gapless_ready(false);
@@ -967,6 +1545,10 @@ impl Player {
                             .ok()
                             .map(|s| s.dac_passthrough)
                             .unwrap_or(false);
+                        let buffer_frames = thread_settings
+                            .lock()
+                            .ok()
+                            .and_then(|s| s.buffer_frames);
 
                         // Check if we need to recreate the stream
                         // Recreate on format change if DAC passthrough OR ALSA Direct is enabled (both require bit-perfect)
@@ -984,6 +1566,7 @@ impl Player {
                         let needs_new_stream = stream_opt.is_none()
                             || (dac_passthrough && format_changed)
                             || (using_alsa_direct && format_changed);
+                        let mut stream_preroll = false;
 
                         if needs_new_stream {
                             if stream_opt.is_some() {
@@ -1070,7 +1653,8 @@ impl Player {
                                             sample_rate,
                                             channels,
                                             dac_passthrough,
-                                        ).map(|(stream, handle)| StreamType::Rodio(stream, handle))
+                                            buffer_frames,
+                                        ).map(|(stream, handle, buffer_size)| StreamType::Rodio(stream, handle, buffer_size))
                                     }
                                 }
                             } else {
@@ -1106,12 +1690,16 @@ impl Player {
                                     sample_rate,
                                     channels,
                                     dac_passthrough,
-                                ).map(|(stream, handle)| StreamType::Rodio(stream, handle))
+                                    buffer_frames,
+                                ).map(|(stream, handle, buffer_size)| StreamType::Rodio(stream, handle, buffer_size))
                             };
 
                             // Handle stream creation result
                             match stream_result {
                                 Ok(stream) => {
+                                    thread_state.set_output_buffer_frames(stream_buffer_frames(&stream));
+                                    attach_stream_diagnostic(&stream, &thread_diagnostic);
+                                    thread_state.set_output_format(sample_rate, !matches!(stream, StreamType::Rodio(..)));
                                     *stream_opt = Some(stream);
                                     *current_sample_rate = Some(sample_rate);
                                     *current_channels = Some(channels);
@@ -1130,10 +1718,11 @@ impl Player {
                                         log::info!("Audio stream ready at {}Hz", sample_rate);
                                     }
 
-                                    // Delay to ensure stream is fully initialized before decoder starts
-                                    // This prevents sync gaps and allows hardware to stabilize after sample rate changes
-                                    // Extra time needed for large sample rate changes (e.g., 88.2kHz → 44.1kHz)
-                                    std::thread::sleep(Duration::from_millis(150));
+                                    // Let the hardware stabilize after the (re)open by playing a short
+                                    // silence pre-roll instead of sleeping here; the sleep left the
+                                    // DAC idle and could still clip the start after large rate changes
+                                    // (e.g., 88.2kHz → 44.1kHz)
+                                    stream_preroll = true;
                                 }
                                 Err(e) => {
                                     log::error!("❌ Failed to create stream at {}Hz: {}", sample_rate, e);
@@ -1158,21 +1747,24 @@ impl Player {
                             return;
                         };
 
-                        // Stop previous engine and wait for sink to release resources
-                        if let Some(engine) = current_engine.take() {
-                            engine.stop();
-                            // Small delay to allow the audio sink to fully release its
-                            // reference to the OutputStreamHandle before creating a new sink.
-                            // This prevents "resource busy" errors on rapid track switches.
-                            std::thread::sleep(Duration::from_millis(50));
+                        // Reuse the live sink for a same-format track, otherwise stop the
+                        // previous engine and wait for the sink to release resources
+                        let reused_engine = release_engine(
+                            current_engine,
+                            !needs_new_stream && !format_changed && !thread_state.has_stream_error(),
+                        );
+                        let reused = reused_engine.is_some();
+                        if reused {
+                            log::info!("Audio thread: same format, reusing sink");
                         }
 
                         *current_audio_data = Some(data.clone());
                         *current_streaming_source = None; // Clear streaming source for non-streaming playback
 
                         // Create PlaybackEngine from StreamType
-                        let mut engine = match stream {
-                            StreamType::Rodio(_stream, handle) => {
+                        let mut engine = match (reused_engine, stream) {
+                            (Some(engine), _) => engine,
+                            (None, StreamType::Rodio(_stream, handle, _)) => {
                                 match PlaybackEngine::new_rodio(handle) {
                                     Ok(e) => {
                                         *consecutive_sink_failures = 0;
@@ -1201,6 +1793,9 @@ impl Player {
                                             let sr = current_sample_rate.unwrap_or(48000);
                                             let ch = current_channels.unwrap_or(2);
                                             *stream_opt = init_device(current_device_name, &thread_state, sr, ch);
+                                            if let Some(ref stream) = *stream_opt {
+                                                thread_state.set_output_format(sr, !matches!(stream, StreamType::Rodio(..)));
+                                            }
                                             if stream_opt.is_some() {
                                                 log::info!("Audio stream auto-reinitialized successfully at {}Hz", sr);
                                                 *consecutive_sink_failures = 0;
@@ -1216,7 +1811,7 @@ impl Player {
                                 }
                             }
                             #[cfg(target_os = "linux")]
-                            StreamType::AlsaDirect(alsa_stream) => {
+                            (None, StreamType::AlsaDirect(alsa_stream)) => {
                                 *consecutive_sink_failures = 0;
                                 thread_state.set_stream_error(false);
                                 let hardware_volume = thread_settings
@@ -1232,7 +1827,10 @@ impl Player {
                         engine.set_volume(volume);
 
                         let source = match decode_with_fallback(&data) {
-                            Ok(s) => s,
+                            Ok((s, decoder)) => {
+                                thread_state.update_format_info(track_id, |info| info.decoder = Some(decoder));
+                                s
+                            }
                             Err(e) => {
                                 log::error!("Failed to decode audio: {}", e);
                                 return;
@@ -1245,53 +1843,74 @@ impl Player {
                             .unwrap_or(duration_secs);
                         thread_state.duration.store(actual_duration, Ordering::SeqCst);
 
+                        let trim = new_trim_state(&thread_settings);
+                        let trim_leading = !thread_state.take_keep_lead_in();
+                        let source = with_silence_trim(source, trim.as_ref(), 0, trim_leading, &thread_state.gapless_boundary);
+                        if let Some(ref trim) = trim {
+                            spawn_tail_scan(data.clone(), trim.clone());
+                        }
+                        thread_state.set_silence_trimmed(trim);
+
                         // Calculate normalization gain if enabled
                         let norm_settings = thread_settings
                             .lock()
                             .ok()
                             .filter(|s| s.normalization_enabled)
-                            .map(|s| s.normalization_target_lufs);
+                            .map(|s| (s.normalization_target_lufs, s.replaygain_mode.resolve(thread_state.is_album_context())));
 
-                        let (normalization, gain_atomic) = if let Some(target_lufs) = norm_settings {
+                        let (normalization, gain_atomic, gain_mode) = if let Some((target_lufs, gain_mode)) = norm_settings {
                             // Check for ReplayGain metadata first (initial gain hint)
-                            let rg_gain = extract_replaygain(&data).map(|rg| calculate_gain_factor(&rg, target_lufs));
-
-                            // Create shared atomic for dynamic normalization
-                            let atomic = Arc::new(AtomicU32::new(
-                                rg_gain.unwrap_or(1.0).to_bits()
-                            ));
-
-                            // Check loudness cache for pre-computed EBU R128 gain
-                            if let Some(cached) = loudness_cache.get(track_id) {
-                                let cached_gain = db_to_linear(cached.gain_db.min(6.0));
-                                atomic.store(cached_gain.to_bits(), Ordering::Relaxed);
-                                log::info!("Normalization: cache hit for track {}, gain {:.4}", track_id, cached_gain);
-                            }
+                            let replaygain = extract_replaygain(&data);
 
-                            // Notify analyzer of new track
-                            let _ = analyzer_tx.try_send(AnalyzerMessage::NewTrack {
-                                track_id,
-                                sample_rate,
-                                channels,
-                                target_lufs,
-                                gain_atomic: atomic.clone(),
-                            });
+                            if let Some(album_gain) = album_gain_factor(replaygain.as_ref(), gain_mode, target_lufs) {
+                                log::info!("Normalization: album gain for track {}, gain {:.4}", track_id, album_gain);
+                                (Some(album_gain), None, Some(ReplayGainMode::Album))
+                            } else {
+                                let rg_gain = replaygain.map(|rg| calculate_gain_factor(&rg, target_lufs));
+
+                                // Create shared atomic for dynamic normalization
+                                let atomic = Arc::new(AtomicU32::new(
+                                    rg_gain.unwrap_or(1.0).to_bits()
+                                ));
+
+                                // Check loudness cache for pre-computed EBU R128 gain
+                                if let Some(cached) = loudness_cache.get(track_id) {
+                                    let cached_gain = db_to_linear(cached.gain_db.min(6.0));
+                                    atomic.store(cached_gain.to_bits(), Ordering::Relaxed);
+                                    log::info!("Normalization: cache hit for track {}, gain {:.4}", track_id, cached_gain);
+                                }
+
+                                // Notify analyzer of new track
+                                let _ = analyzer_tx.try_send(AnalyzerMessage::NewTrack {
+                                    track_id,
+                                    sample_rate,
+                                    channels,
+                                    target_lufs,
+                                    gain_atomic: atomic.clone(),
+                                });
 
-                            (rg_gain, Some(atomic))
+                                (rg_gain, Some(atomic), Some(ReplayGainMode::Track))
+                            }
                         } else {
-                            (None, None)
+                            (None, None, None)
                         };
 
                         *current_normalization_gain = normalization;
                         *current_gain_atomic = gain_atomic.clone();
                         thread_state.set_normalization_gain(normalization);
+                        thread_state.set_replaygain_mode(gain_mode);
 
                         // Wrap source with diagnostic, normalization, and visualizer
                         let source = wrap_source(source, normalization, gain_atomic, &analyzer_tx, &analyzer_enabled);
+                        let source = with_stream_preroll(source, stream_preroll);
                         if let Err(e) = engine.append(source) {
                             log::error!("Failed to append source to engine: {}", e);
                             return;
                         }
+                        if reused {
+                            // Clearing left the sink paused
+                            engine.play();
+                        }
 
                         thread_state.is_playing.store(true, Ordering::SeqCst);
                         thread_state.position.store(0, Ordering::SeqCst);
@@ -1314,11 +1933,19 @@ impl Player {
                             duration_secs
                         );
                         *pause_suspend_deadline = None;
+                        thread_state.clear_buffering();
 
                         // Store streaming source for resume capability
                         // When download completes, we can extract the data for resume
                         *current_streaming_source = Some(source.clone());
                         *current_audio_data = None; // Clear regular audio data
+                        // The tail is scanned once the download completes
+                        let trim = new_trim_state(&thread_settings);
+                        let trim_leading = !thread_state.take_keep_lead_in();
+                        if let Some(ref trim) = trim {
+                            spawn_stream_tail_scan(source.clone(), trim.clone());
+                        }
+                        thread_state.set_silence_trimmed(trim.clone());
 
                         // Get DAC passthrough setting
                         let dac_passthrough = thread_settings
@@ -1326,6 +1953,10 @@ impl Player {
                             .ok()
                             .map(|s| s.dac_passthrough)
                             .unwrap_or(false);
+                        let buffer_frames = thread_settings
+                            .lock()
+                            .ok()
+                            .and_then(|s| s.buffer_frames);
 
                         // Check if we need to recreate the stream
                         let format_changed = *current_sample_rate != Some(sample_rate)
@@ -1341,6 +1972,7 @@ impl Player {
                         let needs_new_stream = stream_opt.is_none()
                             || (dac_passthrough && format_changed)
                             || (using_alsa_direct && format_changed);
+                        let mut stream_preroll = false;
 
                         if needs_new_stream {
                             if stream_opt.is_some() {
@@ -1396,7 +2028,8 @@ impl Player {
                                             sample_rate,
                                             channels,
                                             dac_passthrough,
-                                        ).map(|(stream, handle)| StreamType::Rodio(stream, handle))
+                                            buffer_frames,
+                                        ).map(|(stream, handle, buffer_size)| StreamType::Rodio(stream, handle, buffer_size))
                                     }
                                 }
                             } else {
@@ -1406,18 +2039,21 @@ impl Player {
                                     thread_state.set_stream_error(true);
                                     return;
                                 };
-                                create_output_stream_with_config(&device, sample_rate, channels, dac_passthrough)
-                                    .map(|(stream, handle)| StreamType::Rodio(stream, handle))
+                                create_output_stream_with_config(&device, sample_rate, channels, dac_passthrough, buffer_frames)
+                                    .map(|(stream, handle, buffer_size)| StreamType::Rodio(stream, handle, buffer_size))
                             };
 
                             match stream_result {
                                 Ok(stream) => {
+                                    thread_state.set_output_buffer_frames(stream_buffer_frames(&stream));
+                                    attach_stream_diagnostic(&stream, &thread_diagnostic);
+                                    thread_state.set_output_format(sample_rate, !matches!(stream, StreamType::Rodio(..)));
                                     *stream_opt = Some(stream);
                                     *current_sample_rate = Some(sample_rate);
                                     *current_channels = Some(channels);
                                     thread_state.set_stream_error(false);
                                     log::info!("Streaming audio stream ready at {}Hz", sample_rate);
-                                    std::thread::sleep(Duration::from_millis(150));
+                                    stream_preroll = true;
                                 }
                                 Err(e) => {
                                     log::error!("❌ Failed to create stream for streaming at {}Hz: {}", sample_rate, e);
@@ -1432,15 +2068,21 @@ impl Player {
                             return;
                         };
 
-                        // Stop previous engine
-                        if let Some(engine) = current_engine.take() {
-                            engine.stop();
-                            std::thread::sleep(Duration::from_millis(50));
+                        // Reuse the live sink for a same-format track, otherwise stop the
+                        // previous engine
+                        let reused_engine = release_engine(
+                            current_engine,
+                            !needs_new_stream && !format_changed && !thread_state.has_stream_error(),
+                        );
+                        let reused = reused_engine.is_some();
+                        if reused {
+                            log::info!("Audio thread: same format, reusing sink for streaming");
                         }
 
                         // Create PlaybackEngine
-                        let mut engine = match stream {
-                            StreamType::Rodio(_stream, handle) => {
+                        let mut engine = match (reused_engine, stream) {
+                            (Some(engine), _) => engine,
+                            (None, StreamType::Rodio(_stream, handle, _)) => {
                                 match PlaybackEngine::new_rodio(handle) {
                                     Ok(e) => {
                                         *consecutive_sink_failures = 0;
@@ -1454,7 +2096,7 @@ impl Player {
                                 }
                             }
                             #[cfg(target_os = "linux")]
-                            StreamType::AlsaDirect(alsa_stream) => {
+                            (None, StreamType::AlsaDirect(alsa_stream)) => {
                                 let hardware_volume = thread_settings
                                     .lock()
                                     .ok()
@@ -1467,20 +2109,44 @@ impl Player {
                         let volume = thread_state.volume.load(Ordering::SeqCst) as f32 / 100.0;
                         engine.set_volume(volume);
 
+                        let (max_wait_secs, rebuffer_secs) = thread_settings
+                            .lock()
+                            .map(|s| (s.stream_max_wait_secs, s.stream_rebuffer_seconds))
+                            .unwrap_or((30, 3));
+
                         // Wait for minimum buffer before starting playback
-                        log::info!("Streaming: waiting for initial buffer...");
+                        log::info!("Streaming: waiting for initial buffer (max {}s)...", max_wait_secs);
                         let start_wait = Instant::now();
-                        let max_wait = Duration::from_secs(30);
+                        let max_wait = Duration::from_secs(max_wait_secs as u64);
 
-                        while !source.has_min_buffer() && start_wait.elapsed() < max_wait {
+                        while !source.has_min_buffer()
+                            && source.download_error().is_none()
+                            && start_wait.elapsed() < max_wait
+                        {
+                            thread_state.set_buffering(false, source.min_buffer_progress());
                             std::thread::sleep(Duration::from_millis(50));
                         }
+                        thread_state.clear_buffering();
 
                         if !source.has_min_buffer() {
                             log::error!("Streaming: timeout waiting for initial buffer");
+                            thread_state.set_stream_failure(match source.download_error() {
+                                Some(e) => format!("Playback stopped: {}", e),
+                                None => format!("Playback stopped: no audio data after {}s", max_wait_secs),
+                            });
                             return;
                         }
 
+                        // Re-buffer threshold in bytes, from the file's average bitrate
+                        // (falls back to ~1MB/s, as StreamingConfig::from_seconds does)
+                        let bytes_per_sec = source
+                            .byte_len()
+                            .filter(|_| duration_secs > 0)
+                            .map(|len| (len / duration_secs) as usize)
+                            .unwrap_or(1024 * 1024);
+                        source.set_rebuffer_bytes(bytes_per_sec * rebuffer_secs as usize);
+                        source.set_byte_rate(bytes_per_sec);
+
                         let buffer_wait_ms = start_wait.elapsed().as_millis();
                         log::info!(
                             "Streaming: initial buffer ready in {}ms, creating incremental decoder...",
@@ -1497,6 +2163,12 @@ impl Player {
                             }
                         };
 
+                        let codec = format_info::codec_name(incremental_source.codec());
+                        thread_state.update_format_info(track_id, |info| {
+                            info.codec = codec;
+                            info.decoder = Some(AudioDecoder::SymphoniaStreaming);
+                        });
+
                         // Verify sample rate/channels match what we expected
                         let actual_sr = incremental_source.get_sample_rate();
                         let actual_ch = incremental_source.get_channels();
@@ -1517,52 +2189,64 @@ impl Player {
                             .lock()
                             .ok()
                             .filter(|s| s.normalization_enabled)
-                            .map(|s| s.normalization_target_lufs);
+                            .map(|s| (s.normalization_target_lufs, s.replaygain_mode.resolve(thread_state.is_album_context())));
 
-                        let (normalization, gain_atomic) = if let Some(target_lufs) = norm_settings {
+                        let (normalization, gain_atomic, gain_mode) = if let Some((target_lufs, gain_mode)) = norm_settings {
                             // Try ReplayGain metadata from buffered data
-                            let rg_gain = source.get_buffered_data().and_then(|data| {
-                                extract_replaygain(&data).map(|rg| calculate_gain_factor(&rg, target_lufs))
-                            });
-
-                            // Create shared atomic for dynamic normalization
-                            let atomic = Arc::new(AtomicU32::new(
-                                rg_gain.unwrap_or(1.0).to_bits()
-                            ));
+                            let replaygain = source.get_buffered_data().and_then(|data| extract_replaygain(&data));
 
-                            // Check loudness cache
-                            if let Some(cached) = loudness_cache.get(track_id) {
-                                let cached_gain = db_to_linear(cached.gain_db.min(6.0));
-                                atomic.store(cached_gain.to_bits(), Ordering::Relaxed);
-                                log::info!("Streaming normalization: cache hit for track {}, gain {:.4}", track_id, cached_gain);
-                            }
+                            if let Some(album_gain) = album_gain_factor(replaygain.as_ref(), gain_mode, target_lufs) {
+                                log::info!("Streaming normalization: album gain for track {}, gain {:.4}", track_id, album_gain);
+                                (Some(album_gain), None, Some(ReplayGainMode::Album))
+                            } else {
+                                let rg_gain = replaygain.map(|rg| calculate_gain_factor(&rg, target_lufs));
+
+                                // Create shared atomic for dynamic normalization
+                                let atomic = Arc::new(AtomicU32::new(
+                                    rg_gain.unwrap_or(1.0).to_bits()
+                                ));
+
+                                // Check loudness cache
+                                if let Some(cached) = loudness_cache.get(track_id) {
+                                    let cached_gain = db_to_linear(cached.gain_db.min(6.0));
+                                    atomic.store(cached_gain.to_bits(), Ordering::Relaxed);
+                                    log::info!("Streaming normalization: cache hit for track {}, gain {:.4}", track_id, cached_gain);
+                                }
 
-                            // Notify analyzer of new track
-                            let _ = analyzer_tx.try_send(AnalyzerMessage::NewTrack {
-                                track_id,
-                                sample_rate,
-                                channels,
-                                target_lufs,
-                                gain_atomic: atomic.clone(),
-                            });
+                                // Notify analyzer of new track
+                                let _ = analyzer_tx.try_send(AnalyzerMessage::NewTrack {
+                                    track_id,
+                                    sample_rate,
+                                    channels,
+                                    target_lufs,
+                                    gain_atomic: atomic.clone(),
+                                });
 
-                            (rg_gain, Some(atomic))
+                                (rg_gain, Some(atomic), Some(ReplayGainMode::Track))
+                            }
                         } else {
-                            (None, None)
+                            (None, None, None)
                         };
 
                         *current_normalization_gain = normalization;
                         *current_gain_atomic = gain_atomic.clone();
                         thread_state.set_normalization_gain(normalization);
+                        thread_state.set_replaygain_mode(gain_mode);
 
                         // Box the incremental source to match the expected type
                         let source_to_play: Box<dyn Source<Item = f32> + Send> = Box::new(incremental_source);
+                        let source_to_play = with_silence_trim(source_to_play, trim.as_ref(), 0, trim_leading, &thread_state.gapless_boundary);
                         // Wrap source with diagnostic, normalization, and visualizer
                         let source_to_play = wrap_source(source_to_play, normalization, gain_atomic, &analyzer_tx, &analyzer_enabled);
+                        let source_to_play = with_stream_preroll(source_to_play, stream_preroll);
                         if let Err(e) = engine.append(source_to_play) {
                             log::error!("Failed to append streaming source to engine: {}", e);
                             return;
                         }
+                        if reused {
+                            // Clearing left the sink paused
+                            engine.play();
+                        }
 
                         thread_state.is_playing.store(true, Ordering::SeqCst);
                         thread_state.position.store(0, Ordering::SeqCst);
@@ -1580,8 +2264,13 @@ impl Player {
                             engine.pause();
                             thread_state.pause_playback_timer();
                             thread_state.is_playing.store(false, Ordering::SeqCst);
-                            *pause_suspend_deadline =
-                                Some(Instant::now() + Duration::from_millis(PAUSE_SUSPEND_DELAY_MS));
+                            let (keep_device_open, pause_release_ms) = thread_settings
+                                .lock()
+                                .map(|s| (s.keep_device_open, s.pause_release_ms))
+                                .unwrap_or((false, DEFAULT_PAUSE_RELEASE_MS));
+                            *pause_suspend_deadline = (!keep_device_open).then(|| {
+                                Instant::now() + Duration::from_millis(pause_release_ms as u64)
+                            });
                             log::info!(
                                 "Audio thread: paused at {}s",
                                 thread_state.position.load(Ordering::SeqCst)
@@ -1625,6 +2314,9 @@ impl Player {
                                 let ch = current_channels.unwrap_or(2);
                                 log::info!("Resume: reinitializing stream at {}Hz/{}ch", sr, ch);
                                 *stream_opt = init_device(current_device_name, &thread_state, sr, ch);
+                                if let Some(ref stream) = *stream_opt {
+                                    thread_state.set_output_format(sr, !matches!(stream, StreamType::Rodio(..)));
+                                }
                             }
 
                             let Some(ref stream) = *stream_opt else {
@@ -1633,7 +2325,7 @@ impl Player {
                             };
 
                             let mut engine = match stream {
-                                StreamType::Rodio(_stream, handle) => {
+                                StreamType::Rodio(_stream, handle, _) => {
                                     match PlaybackEngine::new_rodio(handle) {
                                         Ok(e) => e,
                                         Err(e) => {
@@ -1657,7 +2349,7 @@ impl Player {
                             engine.set_volume(volume);
 
                             let source = match decode_with_fallback(&audio_data) {
-                                Ok(s) => s,
+                                Ok((s, _)) => s,
                                 Err(e) => {
                                     log::error!("Failed to decode audio for resume: {}", e);
                                     return;
@@ -1670,6 +2362,7 @@ impl Player {
                             } else {
                                 source
                             };
+                            let skipped_source = with_silence_trim(skipped_source, thread_state.silence_trim_state().as_ref(), resume_pos, resume_pos == 0, &thread_state.gapless_boundary);
 
                             // Wrap source with diagnostic, normalization, and visualizer
                             // Reuse the gain + atomic from the original Play
@@ -1705,15 +2398,21 @@ impl Player {
                         *gapless_pending = None;
                         thread_state.set_gapless_ready(false);
                         thread_state.set_gapless_next_track_id(0);
+                        thread_state.set_silence_trimmed(None);
+                        thread_state.set_format_info(None);
                         analyzer_enabled.store(false, Ordering::SeqCst);
                         thread_state.set_normalization_gain(None);
+                        thread_state.set_replaygain_mode(None);
                         thread_state.is_playing.store(false, Ordering::SeqCst);
                         thread_state.position.store(0, Ordering::SeqCst);
                         thread_state.playback_start_millis.store(0, Ordering::SeqCst);
                         thread_state.position_at_start.store(0, Ordering::SeqCst);
                         // Drop the stream to release the device and stop background CPU use.
                         drop(stream_opt.take());
+                        thread_state.set_output_format(0, false);
                         *pause_suspend_deadline = None;
+                        thread_state.set_sleep_timer(None);
+                        thread_state.clear_buffering();
                         log::info!("Audio thread: stopped");
                     }
                     AudioCommand::SetVolume(volume) => {
@@ -1748,7 +2447,7 @@ impl Player {
                         }
 
                         let mut engine = match stream {
-                            StreamType::Rodio(_stream, handle) => {
+                            StreamType::Rodio(_stream, handle, _) => {
                                 match PlaybackEngine::new_rodio(handle) {
                                     Ok(e) => e,
                                     Err(e) => {
@@ -1772,7 +2471,7 @@ impl Player {
                         engine.set_volume(volume);
 
                         let source = match decode_with_fallback(audio_data) {
-                            Ok(s) => s,
+                            Ok((s, _)) => s,
                             Err(e) => {
                                 log::error!("Failed to decode audio for seek: {}", e);
                                 return;
@@ -1781,6 +2480,7 @@ impl Player {
 
                         let skip_duration = Duration::from_secs(position_secs);
                         let skipped_source: Box<dyn Source<Item = f32> + Send> = Box::new(source.skip_duration(skip_duration));
+                        let skipped_source = with_silence_trim(skipped_source, thread_state.silence_trim_state().as_ref(), position_secs, false, &thread_state.gapless_boundary);
 
                         // Send Reset to analyzer (seek invalidates accumulated samples)
                         let _ = analyzer_tx.try_send(AnalyzerMessage::Reset);
@@ -1832,6 +2532,9 @@ impl Player {
                         let ch = current_channels.unwrap_or(2);
                         log::info!("ReinitDevice: reinitializing at {}Hz/{}ch", sr, ch);
                         *stream_opt = init_device(current_device_name, &thread_state, sr, ch);
+                        if let Some(ref stream) = *stream_opt {
+                            thread_state.set_output_format(sr, !matches!(stream, StreamType::Rodio(..)));
+                        }
 
                         if stream_opt.is_some() {
                             log::info!("Audio thread: device reinitialized successfully");
@@ -1848,7 +2551,7 @@ impl Player {
                         // Keep current_audio_data and current_streaming_source
                         // intact so Resume can recreate the engine and seek.
                     }
-                    AudioCommand::PlayNext { data, track_id, sample_rate, channels } => {
+                    AudioCommand::PlayNext { data, track_id, sample_rate, channels, format_info, format_id, reduced_quality } => {
                         // Gapless: append next track to existing Rodio Sink
                         let engine = match current_engine.as_mut() {
                             Some(e) => e,
@@ -1882,8 +2585,12 @@ impl Player {
                         }
 
                         // Decode the next track's audio
+                        let mut format_info = format_info;
                         let source = match decode_with_fallback(&data) {
-                            Ok(s) => s,
+                            Ok((s, decoder)) => {
+                                format_info.decoder = Some(decoder);
+                                s
+                            }
                             Err(e) => {
                                 log::error!("Gapless: failed to decode track {}: {}", track_id, e);
                                 return;
@@ -1895,36 +2602,48 @@ impl Player {
                             .map(|d| d.as_secs())
                             .unwrap_or(0);
 
+                        // Keep the lead-in of a track that continues the same album
+                        let silence_trimmed = new_trim_state(&thread_settings);
+                        let source = with_silence_trim(source, silence_trimmed.as_ref(), 0, !thread_state.is_gapless_boundary(), &thread_state.gapless_boundary);
+                        if let Some(ref trim) = silence_trimmed {
+                            spawn_tail_scan(data.clone(), trim.clone());
+                        }
+
                         // Calculate normalization for the next track
                         let norm_settings = thread_settings
                             .lock()
                             .ok()
                             .filter(|s| s.normalization_enabled)
-                            .map(|s| s.normalization_target_lufs);
-
-                        let (normalization, gain_atomic) = if let Some(target_lufs) = norm_settings {
-                            let rg_gain = extract_replaygain(&data).map(|rg| calculate_gain_factor(&rg, target_lufs));
-                            let atomic = Arc::new(AtomicU32::new(
-                                rg_gain.unwrap_or(1.0).to_bits()
-                            ));
-                            if let Some(cached) = loudness_cache.get(track_id) {
-                                let cached_gain = db_to_linear(cached.gain_db.min(6.0));
-                                atomic.store(cached_gain.to_bits(), Ordering::Relaxed);
+                            .map(|s| (s.normalization_target_lufs, s.replaygain_mode.resolve(thread_state.is_album_context())));
+
+                        let (normalization, gain_atomic, gain_mode) = if let Some((target_lufs, gain_mode)) = norm_settings {
+                            let replaygain = extract_replaygain(&data);
+                            if let Some(album_gain) = album_gain_factor(replaygain.as_ref(), gain_mode, target_lufs) {
+                                (Some(album_gain), None, Some(ReplayGainMode::Album))
+                            } else {
+                                let rg_gain = replaygain.map(|rg| calculate_gain_factor(&rg, target_lufs));
+                                let atomic = Arc::new(AtomicU32::new(
+                                    rg_gain.unwrap_or(1.0).to_bits()
+                                ));
+                                if let Some(cached) = loudness_cache.get(track_id) {
+                                    let cached_gain = db_to_linear(cached.gain_db.min(6.0));
+                                    atomic.store(cached_gain.to_bits(), Ordering::Relaxed);
+                                }
+                                let _ = analyzer_tx.try_send(AnalyzerMessage::NewTrack {
+                                    track_id,
+                                    sample_rate,
+                                    channels,
+                                    target_lufs,
+                                    gain_atomic: atomic.clone(),
+                                });
+                                (rg_gain, Some(atomic), Some(ReplayGainMode::Track))
                             }
-                            let _ = analyzer_tx.try_send(AnalyzerMessage::NewTrack {
-                                track_id,
-                                sample_rate,
-                                channels,
-                                target_lufs,
-                                gain_atomic: atomic.clone(),
-                            });
-                            (rg_gain, Some(atomic))
                         } else {
-                            (None, None)
+                            (None, None, None)
                         };
 
                         // Wrap source with normalization/visualizer pipeline
-                        let source = wrap_source(source, normalization, gain_atomic, &analyzer_tx, &analyzer_enabled);
+                        let source = wrap_source(source, normalization, gain_atomic.clone(), &analyzer_tx, &analyzer_enabled);
 
                         // Append to existing Sink (gapless queue)
                         if let Err(e) = engine.append(source) {
@@ -1938,6 +2657,12 @@ impl Player {
                             duration_secs: actual_duration,
                             data,
                             normalization_gain: normalization,
+                            gain_atomic,
+                            replaygain_mode: gain_mode,
+                            silence_trimmed,
+                            format_info,
+                            format_id,
+                            reduced_quality,
                         });
                         thread_state.set_gapless_next_track_id(track_id);
                         thread_state.set_gapless_ready(false); // Request fulfilled
@@ -1951,7 +2676,13 @@ impl Player {
             };
 
             loop {
-                if thread_state.is_playing.load(Ordering::SeqCst) {
+                let playing = thread_state.is_playing.load(Ordering::SeqCst);
+                if playing && !was_playing {
+                    thread_state.clear_expired_sleep_timer();
+                }
+                was_playing = playing;
+
+                if playing {
                     match rx.recv_timeout(Duration::from_millis(100)) {
                         Ok(command) => handle_command(
                             command,
@@ -1970,16 +2701,144 @@ impl Player {
                         ),
                         Err(RecvTimeoutError::Timeout) => {
                             let now = Instant::now();
+
+                            thread_state.set_buffered_ahead(
+                                current_streaming_source
+                                    .as_ref()
+                                    .and_then(|src| src.buffered_ahead_secs()),
+                            );
+
+                            // Streaming stall: hold output until the buffer refills
+                            let stalled_source = current_streaming_source
+                                .as_ref()
+                                .filter(|src| src.is_stalled())
+                                .cloned();
+                            if let Some(streaming_src) = stalled_source {
+                                let started = *rebuffer_started.get_or_insert_with(|| {
+                                    log::warn!("Streaming: buffer underrun, pausing output to re-buffer");
+                                    now
+                                });
+                                let progress = streaming_src.rebuffer_progress();
+                                let max_wait = thread_settings
+                                    .lock()
+                                    .map(|s| s.stream_max_wait_secs)
+                                    .unwrap_or(30);
+
+                                let failure = streaming_src.download_error().map(|e| {
+                                    log::error!("Streaming: download failed while re-buffering: {}", e);
+                                    format!("Playback stopped: {}", e)
+                                });
+                                let failure = failure.or_else(|| {
+                                    (progress < 100
+                                        && now.duration_since(started) >= Duration::from_secs(max_wait as u64))
+                                    .then(|| {
+                                        log::error!("Streaming: re-buffer timed out after {}s, stopping", max_wait);
+                                        format!("Playback stopped: no audio data for {}s", max_wait)
+                                    })
+                                });
+
+                                if let Some(failure) = failure {
+                                    rebuffer_started = None;
+                                    thread_state.set_stream_failure(failure);
+                                    handle_command(
+                                        AudioCommand::Stop,
+                                        &mut current_engine,
+                                        &mut current_audio_data,
+                                        &mut current_streaming_source,
+                                        &mut stream_opt,
+                                        &mut current_device_name,
+                                        &mut consecutive_sink_failures,
+                                        &mut pause_suspend_deadline,
+                                        &mut current_sample_rate,
+                                        &mut current_channels,
+                                        &mut current_normalization_gain,
+                                        &mut current_gain_atomic,
+                                        &mut gapless_pending,
+                                    );
+                                } else if progress >= 100 {
+                                    log::info!(
+                                        "Streaming: re-buffered in {}ms, resuming",
+                                        now.duration_since(started).as_millis()
+                                    );
+                                    streaming_src.set_stalled(false);
+                                    if let Some(ref engine) = current_engine {
+                                        engine.play();
+                                    }
+                                    let current_pos = thread_state.position.load(Ordering::SeqCst);
+                                    thread_state.start_playback_timer(current_pos);
+                                    thread_state.clear_buffering();
+                                    rebuffer_started = None;
+                                } else {
+                                    // Also re-applies the pause if the user resumed mid-stall
+                                    if let Some(ref engine) = current_engine {
+                                        engine.pause();
+                                    }
+                                    thread_state.pause_playback_timer();
+                                    thread_state.set_buffering(true, progress);
+                                }
+                                continue;
+                            }
+                            rebuffer_started = None;
+
+                            // Network stall: report buffering while the buffered audio plays out
+                            let download_stalled = current_streaming_source
+                                .as_ref()
+                                .filter(|src| src.is_download_stalled(STALL_NOTICE_AFTER));
+                            if let Some(streaming_src) = download_stalled {
+                                if !download_stall_noticed {
+                                    log::warn!(
+                                        "Streaming: no data for {}s and buffer low, buffering",
+                                        STALL_NOTICE_AFTER.as_secs()
+                                    );
+                                    download_stall_noticed = true;
+                                }
+                                thread_state.set_buffering(true, streaming_src.rebuffer_progress());
+                            } else if download_stall_noticed {
+                                download_stall_noticed = false;
+                                thread_state.clear_buffering();
+                            }
+
+                            // Sleep timer: ramp the volume down, pause when it runs out
+                            let sleep_timer = thread_state.sleep_timer();
+                            let mut sleep_command: Option<AudioCommand> = None;
+                            let fade_factor = sleep_timer.map(|t| t.fade_factor(now)).unwrap_or(1.0);
+                            if fade_factor < 1.0 || sleep_fade_active {
+                                if let Some(ref engine) = current_engine {
+                                    engine.set_volume(thread_state.volume() * fade_factor);
+                                }
+                                sleep_fade_active = fade_factor < 1.0;
+                            }
+                            let stop_after_track = sleep_timer.filter(|t| {
+                                t.action == SleepTimerAction::StopAfterCurrentTrack
+                            });
+                            if let Some(timer) = sleep_timer {
+                                if timer.action == SleepTimerAction::FadeAndPause && timer.is_expired(now) {
+                                    log::info!("Sleep timer: fade complete, pausing");
+                                    thread_state.set_sleep_timer(None);
+                                    sleep_command = Some(AudioCommand::Pause);
+                                }
+                            }
+
                             if now.duration_since(last_empty_check) >= Duration::from_millis(500) {
                                 last_empty_check = now;
 
-                                let pos = thread_state.current_position();
+                                // Trimmed silence is never played, so the track ends that much early
+                                let pos = thread_state.current_position() + thread_state.silence_trimmed_secs();
                                 let dur = thread_state.duration.load(Ordering::SeqCst);
+                                let stop_at_track_end = stop_after_track
+                                    .map(|t| t.remaining(now).as_secs() <= dur.saturating_sub(pos))
+                                    .unwrap_or(false);
+
+                                // Sleep timer ran out during a gapless-queued track: stop at the boundary
+                                if gapless_pending.is_some() && dur > 0 && pos >= dur && stop_at_track_end {
+                                    log::info!("Sleep timer: track finished, stopping before gapless transition");
+                                    sleep_command = Some(AudioCommand::Stop);
+                                }
 
                                 // Gapless transition detection: when position exceeds current
                                 // track duration, the queued next track has started playing
                                 if let Some(ref pending) = gapless_pending {
-                                    if dur > 0 && pos >= dur {
+                                    if dur > 0 && pos >= dur && sleep_command.is_none() {
                                         log::info!(
                                             "Gapless transition: track {} -> {} (pos {}s >= dur {}s)",
                                             thread_state.current_track_id.load(Ordering::SeqCst),
@@ -1990,8 +2849,14 @@ impl Player {
                                         thread_state.start_playback_timer(0);
                                         current_audio_data = Some(pending.data.clone());
                                         current_normalization_gain = pending.normalization_gain;
+                                        current_gain_atomic = pending.gain_atomic.clone();
                                         thread_state.set_normalization_gain(pending.normalization_gain);
+                                        thread_state.set_replaygain_mode(pending.replaygain_mode);
+                                        thread_state.set_silence_trimmed(pending.silence_trimmed.clone());
+                                        thread_state.set_gapless_format_info(pending.format_info.clone());
                                         thread_state.set_gapless_next_track_id(0);
+                                        thread_state.set_playing_from_cache(false);
+                                        thread_state.set_track_format(pending.format_id, pending.reduced_quality);
                                         gapless_pending = None;
                                     }
                                 }
@@ -2010,8 +2875,12 @@ impl Player {
                                     && !thread_state.is_gapless_ready()
                                     && thread_state.get_gapless_next_track_id() == 0
                                     && current_streaming_source.is_none()
+                                    && !stop_at_track_end
                                 {
-                                    log::info!("Gapless: approaching end of track ({}s/{}s), requesting next", pos, dur);
+                                    log::info!(
+                                        "Gapless: approaching end of track ({}s/{}s), requesting next (album boundary: {})",
+                                        pos, dur, thread_state.is_gapless_boundary()
+                                    );
                                     thread_state.set_gapless_ready(true);
                                 }
 
@@ -2019,6 +2888,14 @@ impl Player {
                                 if let Some(ref engine) = current_engine {
                                     if engine.empty()
                                         && thread_state.is_playing.load(Ordering::SeqCst)
+                                        && stop_at_track_end
+                                    {
+                                        // Stop instead of reporting a finished track, so the
+                                        // frontend does not advance the queue
+                                        log::info!("Sleep timer: track finished, stopping");
+                                        sleep_command = Some(AudioCommand::Stop);
+                                    } else if engine.empty()
+                                        && thread_state.is_playing.load(Ordering::SeqCst)
                                     {
                                         log::info!("Audio thread: track finished (engine empty)");
                                         thread_state.is_playing.store(false, Ordering::SeqCst);
@@ -2028,10 +2905,37 @@ impl Player {
                                         // Clear gapless state on track end
                                         thread_state.set_gapless_ready(false);
                                         thread_state.set_gapless_next_track_id(0);
+                                        thread_state.set_playing_from_cache(false);
                                         gapless_pending = None;
                                     }
                                 }
                             }
+
+                            if let Some(command) = sleep_command {
+                                let paused = matches!(command, AudioCommand::Pause);
+                                handle_command(
+                                    command,
+                                    &mut current_engine,
+                                    &mut current_audio_data,
+                                    &mut current_streaming_source,
+                                    &mut stream_opt,
+                                    &mut current_device_name,
+                                    &mut consecutive_sink_failures,
+                                    &mut pause_suspend_deadline,
+                                    &mut current_sample_rate,
+                                    &mut current_channels,
+                                    &mut current_normalization_gain,
+                                    &mut current_gain_atomic,
+                                    &mut gapless_pending,
+                                );
+                                // Restore the user volume so resuming is not silent
+                                if paused {
+                                    if let Some(ref engine) = current_engine {
+                                        engine.set_volume(thread_state.volume());
+                                    }
+                                }
+                                sleep_fade_active = false;
+                            }
                         }
                         Err(RecvTimeoutError::Disconnected) => {
                             log::info!("Audio thread: channel closed, exiting");
@@ -2048,6 +2952,7 @@ impl Player {
                                 }
                                 drop(stream_opt.take());
                                 pause_suspend_deadline = None;
+                                thread_state.set_output_format(0, false);
                                 log::info!("Audio thread: suspended stream after pause");
                                 continue;
                             }
@@ -2104,9 +3009,60 @@ impl Player {
                     }
                 }
             }
-        });
+        })
+            .expect("Failed to spawn audio thread");
 
-        Self { tx, state, audio_settings: settings, visualizer_tap, diagnostic }
+        tx
+    }
+
+    /// Send a command to the audio thread. If the thread died, it is respawned
+    /// once and the command retried; after that the error is returned.
+    fn send(&self, command: AudioCommand) -> Result<(), String> {
+        let Ok(tx) = self.tx.lock() else {
+            return Err("Audio command channel poisoned".to_string());
+        };
+        let command = match tx.send(command) {
+            Ok(()) => return Ok(()),
+            Err(mpsc::SendError(command)) => command,
+        };
+        drop(tx);
+
+        log::error!("Player: audio thread is not running");
+        self.state.audio_thread_alive.store(false, Ordering::SeqCst);
+        if !self.respawn_audio_thread() {
+            return Err(AUDIO_THREAD_DEAD.to_string());
+        }
+        self.tx
+            .lock()
+            .map_err(|_| "Audio command channel poisoned".to_string())?
+            .send(command)
+            .map_err(|_| AUDIO_THREAD_DEAD.to_string())
+    }
+
+    /// Start a new audio thread after the previous one died (only once per
+    /// session, so a thread that keeps crashing is not restarted in a loop).
+    /// Returns false when the restart was already used.
+    pub fn respawn_audio_thread(&self) -> bool {
+        if self.audio_thread_respawned.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        log::warn!("Player: restarting the audio thread");
+        self.state.is_playing.store(false, Ordering::SeqCst);
+        let tx = Self::spawn_audio_thread(
+            self.state.current_device(),
+            self.state.clone(),
+            self.audio_settings.clone(),
+            self.visualizer_tap.clone(),
+            self.diagnostic.clone(),
+            self.equalizer.clone(),
+        );
+        match self.tx.lock() {
+            Ok(mut current) => {
+                *current = tx;
+                true
+            }
+            Err(_) => false,
+        }
     }
 
     /// Play a track by ID (downloads audio)
@@ -2163,8 +3119,17 @@ impl Player {
 
         // Update shared state with actual stream quality
         self.state.set_stream_quality(sample_rate, bit_depth);
+        self.state.set_playing_from_cache(false);
+        self.state.set_track_format(None, false);
+        self.state.set_format_info(Some(DecodedFormatInfo::from_probe(
+            track_id,
+            &meta.codec_params,
+            &data,
+            sample_rate,
+            channels,
+        )));
 
-        self.tx
+        self
             .send(AudioCommand::Play {
                 data,
                 track_id,
@@ -2182,7 +3147,16 @@ impl Player {
     }
 
     /// Queue next track for gapless playback (appends to current Sink without stopping)
-    pub fn play_next(&self, data: Vec<u8>, track_id: u64) -> Result<(), String> {
+    ///
+    /// `format_id`/`reduced_quality` describe the cached copy; they become the
+    /// track format once the transition happens.
+    pub fn play_next(
+        &self,
+        data: Vec<u8>,
+        track_id: u64,
+        format_id: Option<u32>,
+        reduced_quality: bool,
+    ) -> Result<(), String> {
         let meta = extract_audio_metadata_full(&data)
             .map_err(|e| format!("Failed to extract audio metadata for gapless: {}", e))?;
 
@@ -2190,13 +3164,23 @@ impl Player {
             "Player: Queueing gapless track {} ({}Hz, {}ch, {} bytes)",
             track_id, meta.sample_rate, meta.channels, data.len()
         );
+        let format_info = DecodedFormatInfo::from_probe(
+            track_id,
+            &meta.codec_params,
+            &data,
+            meta.sample_rate,
+            meta.channels,
+        );
 
-        self.tx
+        self
             .send(AudioCommand::PlayNext {
                 data,
                 track_id,
                 sample_rate: meta.sample_rate,
                 channels: meta.channels,
+                format_info,
+                format_id,
+                reduced_quality,
             })
             .map_err(|e| {
                 log::error!("Player: Failed to send PlayNext to audio thread: {}", e);
@@ -2224,13 +3208,24 @@ impl Player {
             duration_secs
         );
 
+        self.state.set_playing_from_cache(false);
+        self.state.set_track_format(None, false);
+        self.state.set_format_info(Some(DecodedFormatInfo::streaming(
+            track_id,
+            sample_rate,
+            None,
+            channels,
+            content_length,
+            duration_secs,
+        )));
+
         // Use StreamingConfig::from_seconds for proper buffer sizing
         let config = StreamingConfig::from_seconds(buffer_seconds);
 
         let (source, writer) = BufferedMediaSource::new(config, Some(content_length));
         let source = Arc::new(source);
 
-        self.tx
+        self
             .send(AudioCommand::PlayStreaming {
                 source: source.clone(),
                 track_id,
@@ -2273,13 +3268,24 @@ impl Player {
         // Update shared state with actual stream quality
         self.state.set_stream_quality(sample_rate, bit_depth);
 
+        self.state.set_playing_from_cache(false);
+        self.state.set_track_format(None, false);
+        self.state.set_format_info(Some(DecodedFormatInfo::streaming(
+            track_id,
+            sample_rate,
+            Some(bit_depth),
+            channels,
+            content_length,
+            duration_secs,
+        )));
+
         // Use StreamingConfig::from_speed_mbps for dynamic buffer sizing
         let config = StreamingConfig::from_speed_mbps(speed_mbps);
 
         let (source, writer) = BufferedMediaSource::new(config, Some(content_length));
         let source = Arc::new(source);
 
-        self.tx
+        self
             .send(AudioCommand::PlayStreaming {
                 source: source.clone(),
                 track_id,
@@ -2300,17 +3306,11 @@ impl Player {
     async fn download_audio(&self, url: &str) -> Result<Vec<u8>, String> {
         use std::time::Duration;
 
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(60))
-            .connect_timeout(Duration::from_secs(10))
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
         log::info!("Caching audio from URL...");
 
-        let response = client
+        let response = crate::network::http_client::shared()
             .get(url)
-            .header("User-Agent", "Mozilla/5.0")
+            .timeout(Duration::from_secs(60))
             .send()
             .await
             .map_err(|e| format!("Failed to fetch audio: {}", e))?;
@@ -2325,6 +3325,7 @@ impl Player {
             .bytes()
             .await
             .map_err(|e| format!("Failed to read audio bytes: {}", e))?;
+        crate::data_usage::record(crate::data_usage::DataUsageCategory::Cache, bytes.len() as u64);
 
         log::info!("Cached {} bytes", bytes.len());
         Ok(bytes.to_vec())
@@ -2332,21 +3333,42 @@ impl Player {
 
     /// Pause playback
     pub fn pause(&self) -> Result<(), String> {
-        self.tx
+        self
             .send(AudioCommand::Pause)
             .map_err(|e| format!("Failed to send pause command: {}", e))
     }
 
     /// Resume playback
     pub fn resume(&self) -> Result<(), String> {
-        self.tx
+        self
             .send(AudioCommand::Resume)
             .map_err(|e| format!("Failed to send resume command: {}", e))
     }
 
+    /// Start (or replace) the sleep timer
+    pub fn set_sleep_timer(&self, timer: SleepTimer) {
+        log::info!(
+            "Player: sleep timer set for {}s ({:?})",
+            timer.duration.as_secs(),
+            timer.action
+        );
+        self.state.set_sleep_timer(Some(ActiveSleepTimer::start(timer)));
+    }
+
+    /// Current sleep timer, if one is running
+    pub fn get_sleep_timer(&self) -> Option<SleepTimerStatus> {
+        self.state.sleep_timer_status()
+    }
+
+    /// Cancel the sleep timer (the audio thread restores any faded volume)
+    pub fn cancel_sleep_timer(&self) {
+        log::info!("Player: sleep timer cancelled");
+        self.state.set_sleep_timer(None);
+    }
+
     /// Stop playback
     pub fn stop(&self) -> Result<(), String> {
-        self.tx
+        self
             .send(AudioCommand::Stop)
             .map_err(|e| format!("Failed to send stop command: {}", e))
     }
@@ -2361,7 +3383,7 @@ impl Player {
             return Ok(());
         }
 
-        self.tx
+        self
             .send(AudioCommand::SetVolume(clamped))
             .map_err(|e| format!("Failed to send volume command: {}", e))
     }
@@ -2376,7 +3398,7 @@ impl Player {
             position
         };
 
-        self.tx
+        self
             .send(AudioCommand::Seek(clamped_position))
             .map_err(|e| format!("Failed to send seek command: {}", e))
     }
@@ -2384,14 +3406,23 @@ impl Player {
     /// Reinitialize audio device (releases and re-acquires the device)
     /// Use this when changing audio settings like exclusive mode
     pub fn reinit_device(&self, device_name: Option<String>) -> Result<(), String> {
-        self.tx
+        self
             .send(AudioCommand::ReinitDevice { device_name })
             .map_err(|e| format!("Failed to send reinit command: {}", e))
     }
 
+    /// Audio settings the player is currently running with
+    pub fn current_settings(&self) -> AudioSettings {
+        self.audio_settings
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_default()
+    }
+
     /// Reload audio settings from fresh config (e.g., after database update)
     /// Call this before reinit_device() to ensure Player uses latest settings
     pub fn reload_settings(&self, settings: AudioSettings) -> Result<(), String> {
+        self.equalizer.set_config(settings.eq_config.clone());
         if let Ok(mut current_settings) = self.audio_settings.lock() {
             *current_settings = settings;
             Ok(())
@@ -2411,6 +3442,26 @@ impl Player {
         })
     }
 
+    /// True when album/track gain is picked from the playback context
+    pub fn is_replaygain_auto(&self) -> bool {
+        self.audio_settings
+            .lock()
+            .map(|s| s.replaygain_mode == ReplayGainMode::Auto)
+            .unwrap_or(false)
+    }
+
+    /// How long playback may stay paused before it is stopped, and whether
+    /// that applies while casting too (None = never)
+    pub fn inactivity_stop(&self) -> Option<(Duration, bool)> {
+        let settings = self.audio_settings.lock().ok()?;
+        (settings.inactivity_stop_minutes > 0).then(|| {
+            (
+                Duration::from_secs(settings.inactivity_stop_minutes as u64 * 60),
+                settings.inactivity_stop_while_casting,
+            )
+        })
+    }
+
     /// Get playback event for emitting to frontend
     pub fn get_playback_event(&self) -> PlaybackEvent {
         let sample_rate = self.state.get_sample_rate();
@@ -2428,6 +3479,14 @@ impl Player {
             normalization_gain: self.state.get_normalization_gain(),
             gapless_ready: self.state.is_gapless_ready(),
             gapless_next_track_id: self.state.get_gapless_next_track_id(),
+            gapless_boundary: self.state.is_gapless_boundary(),
+            sleep_timer_remaining: self.state.sleep_timer_status().map(|t| t.remaining_secs),
+            playing_from_cache: self.state.is_playing_from_cache(),
+            format_id: self.state.get_format_id(),
+            reduced_quality: self.state.is_reduced_quality(),
+            replaygain_mode: self.state.replaygain_mode(),
+            replaygain_auto: self.is_replaygain_auto(),
+            buffered_ahead_secs: self.state.buffered_ahead_secs(),
         }
     }
 }
@@ -0,0 +1,147 @@
+//! Output test tone
+//!
+//! Opens a stream the same way the player does (backend system first, then
+//! the legacy CPAL path) and plays a short sine sweep through it, so users can
+//! check a device, exclusive mode or a sample rate before playing music.
+
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+
+use rodio::buffer::SamplesBuffer;
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+
+use super::{
+    create_output_stream_with_config, try_init_stream_with_backend, PlaybackEngine, StreamType,
+};
+use crate::config::audio_settings::AudioSettings;
+
+/// Length of the test tone
+pub const TEST_TONE_DURATION: Duration = Duration::from_millis(1500);
+
+/// Sweep range (Hz), logarithmic
+const SWEEP_START_HZ: f32 = 200.0;
+const SWEEP_END_HZ: f32 = 2000.0;
+
+/// Peak level (-18 dBFS), loud enough to hear without startling anyone
+const TONE_AMPLITUDE: f32 = 0.125;
+
+/// Fade in/out to avoid clicks
+const FADE: Duration = Duration::from_millis(20);
+
+const CHANNELS: u16 = 2;
+
+/// Interleaved stereo sine sweep at `sample_rate`, quantized to `bit_depth`
+/// so the samples carry exactly that precision
+pub fn sine_sweep(sample_rate: u32, bit_depth: u32, duration: Duration) -> Vec<f32> {
+    let frames = (duration.as_secs_f64() * sample_rate as f64) as usize;
+    let fade_frames = ((FADE.as_secs_f64() * sample_rate as f64) as usize).max(1);
+    let length = duration.as_secs_f32();
+    let ratio = (SWEEP_END_HZ / SWEEP_START_HZ).ln();
+    // f32 already holds 24 bits of mantissa; deeper formats need no rounding
+    let steps = (bit_depth < 24).then(|| ((1u32 << (bit_depth - 1)) - 1) as f32);
+
+    let mut samples = Vec::with_capacity(frames * CHANNELS as usize);
+    for frame in 0..frames {
+        let t = frame as f32 / sample_rate as f32;
+        // Phase of an exponential sweep: 2π·f0·L/ln(f1/f0)·(e^(t/L·ln(f1/f0)) − 1)
+        let phase = 2.0 * PI * SWEEP_START_HZ * length / ratio * ((t / length * ratio).exp() - 1.0);
+        let envelope = (frame.min(frames - 1 - frame) as f32 / fade_frames as f32).min(1.0);
+        let mut sample = phase.sin() * TONE_AMPLITUDE * envelope;
+        if let Some(steps) = steps {
+            sample = (sample * steps).round() / steps;
+        }
+        samples.extend(std::iter::repeat_n(sample, CHANNELS as usize));
+    }
+    samples
+}
+
+/// Play the test tone with `settings` (device already overridden by the
+/// caller). `during` runs while the tone plays, e.g. to read the hardware
+/// format. Returns whether ALSA Direct was used and what `during` returned.
+///
+/// Errors when the stream can't be opened.
+pub fn play_test_tone<T>(
+    settings: &AudioSettings,
+    sample_rate: u32,
+    bit_depth: u32,
+    during: impl FnOnce() -> T,
+) -> Result<(bool, T), String> {
+    let stream = open_stream(settings, sample_rate)?;
+    let alsa_direct = !matches!(stream, StreamType::Rodio(..));
+
+    let mut engine = match &stream {
+        StreamType::Rodio(_, handle, _) => PlaybackEngine::new_rodio(handle)?,
+        #[cfg(target_os = "linux")]
+        StreamType::AlsaDirect(alsa_stream) => {
+            PlaybackEngine::new_alsa_direct(alsa_stream.clone(), false)
+        }
+    };
+    let samples = sine_sweep(sample_rate, bit_depth, TEST_TONE_DURATION);
+    engine.append(SamplesBuffer::new(CHANNELS, sample_rate, samples))?;
+    engine.play();
+
+    // Let the device settle into the new format before reading it
+    std::thread::sleep(TEST_TONE_DURATION / 2);
+    let observed = during();
+
+    let deadline = Instant::now() + TEST_TONE_DURATION + Duration::from_secs(1);
+    while !engine.empty() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    engine.stop();
+    drop(stream);
+
+    Ok((alsa_direct, observed))
+}
+
+fn open_stream(settings: &AudioSettings, sample_rate: u32) -> Result<StreamType, String> {
+    if let Some(result) = try_init_stream_with_backend(settings, sample_rate, CHANNELS) {
+        return result;
+    }
+
+    // Backend system not configured: legacy CPAL path
+    let host = rodio::cpal::default_host();
+    let device = match settings.output_device.as_deref() {
+        Some(name) => host
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().ok().as_deref() == Some(name)))
+            .ok_or_else(|| format!("Audio device '{}' not found", name))?,
+        None => host
+            .default_output_device()
+            .ok_or("No audio output device available")?,
+    };
+    create_output_stream_with_config(
+        &device,
+        sample_rate,
+        CHANNELS,
+        settings.dac_passthrough,
+        settings.buffer_frames,
+    )
+    .map(|(stream, handle, buffer_size)| StreamType::Rodio(stream, handle, buffer_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sine_sweep_shape() {
+        let samples = sine_sweep(48_000, 24, TEST_TONE_DURATION);
+        assert_eq!(samples.len(), 72_000 * 2);
+        assert!(samples.chunks(2).all(|frame| frame[0] == frame[1]));
+        assert!(samples.iter().all(|s| s.abs() <= TONE_AMPLITUDE));
+        // Faded in and out
+        assert_eq!(samples[0], 0.0);
+        assert_eq!(samples[samples.len() - 1], 0.0);
+    }
+
+    #[test]
+    fn test_sine_sweep_quantized_to_bit_depth() {
+        let steps = ((1u32 << 15) - 1) as f32;
+        let samples = sine_sweep(44_100, 16, Duration::from_millis(100));
+        assert!(samples
+            .iter()
+            .all(|s| ((s * steps).round() - s * steps).abs() < 1e-3));
+    }
+}
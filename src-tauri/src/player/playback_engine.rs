@@ -225,6 +225,20 @@ impl PlaybackEngine {
         }
     }
 
+    /// Drop all queued sources so the next track can go into the live sink.
+    ///
+    /// Rodio only: the sink is emptied and left paused. Returns false when the
+    /// engine can't be reused (ALSA Direct runs one thread per source).
+    pub fn clear_for_reuse(&self) -> bool {
+        match self {
+            Self::Rodio { sink } => {
+                sink.clear();
+                true
+            }
+            Self::AlsaDirect { .. } => false,
+        }
+    }
+
     /// Set volume (0.0 - 1.0)
     pub fn set_volume(&self, volume: f32) {
         match self {
@@ -0,0 +1,114 @@
+//! Sleep timer
+//!
+//! Either fades the volume out over the final seconds and pauses, or lets the
+//! current track finish and then stops. The timer lives in `SharedState`; the
+//! audio thread checks it on every playback tick.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// How long the fade-out lasts before pausing (clamped to the timer duration)
+pub const SLEEP_TIMER_FADE: Duration = Duration::from_secs(30);
+
+/// What happens when the sleep timer runs out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SleepTimerAction {
+    /// Ramp the volume down over the final seconds, then pause
+    FadeAndPause,
+    /// Let the current track finish, then stop
+    StopAfterCurrentTrack,
+}
+
+/// Sleep timer request
+#[derive(Debug, Clone, Copy)]
+pub struct SleepTimer {
+    pub duration: Duration,
+    pub action: SleepTimerAction,
+}
+
+/// A running sleep timer
+#[derive(Debug, Clone, Copy)]
+pub struct ActiveSleepTimer {
+    pub deadline: Instant,
+    pub action: SleepTimerAction,
+    pub fade: Duration,
+}
+
+impl ActiveSleepTimer {
+    pub fn start(timer: SleepTimer) -> Self {
+        Self {
+            deadline: Instant::now() + timer.duration,
+            action: timer.action,
+            fade: SLEEP_TIMER_FADE.min(timer.duration),
+        }
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.deadline.saturating_duration_since(now)
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now >= self.deadline
+    }
+
+    /// Volume multiplier for the fade-out (1.0 until the fade window starts)
+    pub fn fade_factor(&self, now: Instant) -> f32 {
+        if self.action != SleepTimerAction::FadeAndPause || self.fade.is_zero() {
+            return 1.0;
+        }
+        let remaining = self.remaining(now);
+        if remaining >= self.fade {
+            1.0
+        } else {
+            remaining.as_secs_f32() / self.fade.as_secs_f32()
+        }
+    }
+
+    pub fn status(&self, now: Instant) -> SleepTimerStatus {
+        SleepTimerStatus {
+            action: self.action,
+            remaining_secs: self.remaining(now).as_secs(),
+            waiting_for_track_end: self.action == SleepTimerAction::StopAfterCurrentTrack
+                && self.is_expired(now),
+        }
+    }
+}
+
+/// Sleep timer state returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct SleepTimerStatus {
+    pub action: SleepTimerAction,
+    pub remaining_secs: u64,
+    /// True once the timer ran out and playback stops at the end of this track
+    pub waiting_for_track_end: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fade_ramps_down_over_final_window() {
+        let timer = ActiveSleepTimer::start(SleepTimer {
+            duration: Duration::from_secs(120),
+            action: SleepTimerAction::FadeAndPause,
+        });
+        let start = timer.deadline - Duration::from_secs(120);
+        assert_eq!(timer.fade_factor(start), 1.0);
+        let halfway = timer.deadline - SLEEP_TIMER_FADE / 2;
+        assert!((timer.fade_factor(halfway) - 0.5).abs() < 0.01);
+        assert_eq!(timer.fade_factor(timer.deadline), 0.0);
+    }
+
+    #[test]
+    fn stop_after_track_never_fades() {
+        let timer = ActiveSleepTimer::start(SleepTimer {
+            duration: Duration::from_secs(10),
+            action: SleepTimerAction::StopAfterCurrentTrack,
+        });
+        assert_eq!(timer.fade_factor(timer.deadline), 1.0);
+        assert!(timer.status(timer.deadline).waiting_for_track_end);
+    }
+}
@@ -22,15 +22,28 @@
 //! - The writer (download task, async)
 //!
 //! Communication uses `Mutex` + `Condvar` for blocking synchronization.
+//!
+//! # Stalls
+//!
+//! Blocking inside the output callback would underrun the device, so the
+//! incremental source checks how far the decoder is ahead of the download
+//! before decoding. When it runs dry it flags the buffered source as stalled
+//! instead of blocking; the audio thread pauses output until the buffer has
+//! refilled to the re-buffering threshold.
+//!
+//! A network stall is noticed earlier: when no data has arrived for
+//! `STALL_NOTICE_AFTER` and the buffer is below that threshold, the audio
+//! thread reports buffering while the remaining audio plays out.
 
 use std::collections::VecDeque;
 use std::io::{Error as IoError, ErrorKind, Read, Result as IoResult, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use rodio::Source;
 use symphonia::core::audio::SampleBuffer;
-use symphonia::core::codecs::{Decoder, DecoderOptions};
+use symphonia::core::codecs::{CodecType, Decoder, DecoderOptions};
 use symphonia::core::errors::Error as SymphoniaError;
 use symphonia::core::formats::{FormatOptions, FormatReader};
 use symphonia::core::io::{MediaSource, MediaSourceStream};
@@ -38,6 +51,15 @@ use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 use symphonia::default::{get_codecs, get_probe};
 
+/// Below this many undecoded bytes the download is considered starved
+const STARVATION_LOW_WATER_BYTES: usize = 64 * 1024;
+
+/// Samples between starvation checks (each check takes the buffer lock)
+const STARVATION_CHECK_INTERVAL: u32 = 1024;
+
+/// No data for this long with a low buffer counts as a network stall
+pub const STALL_NOTICE_AFTER: Duration = Duration::from_secs(5);
+
 /// Configuration for the streaming buffer
 #[derive(Debug, Clone)]
 pub struct StreamingConfig {
@@ -126,6 +148,10 @@ struct BufferState {
     download_error: Option<String>,
     /// Total expected size (from Content-Length), if known
     total_size: Option<u64>,
+    /// Position right after the most recent read or seek
+    read_pos: usize,
+    /// When the last chunk arrived (or the download started)
+    last_chunk_at: Instant,
 }
 
 /// A media source that buffers from an async HTTP stream.
@@ -138,6 +164,13 @@ pub struct BufferedMediaSource {
     config: StreamingConfig,
     /// Each reader has its own read position
     read_pos: std::sync::atomic::AtomicU64,
+    /// Set by the decoder when it ran out of data; cleared by the audio
+    /// thread once the buffer refilled
+    stalled: AtomicBool,
+    /// Undecoded bytes required before playback resumes after a stall
+    rebuffer_bytes: AtomicUsize,
+    /// Average bytes per second of audio (0 = unknown)
+    byte_rate: AtomicUsize,
 }
 
 impl BufferedMediaSource {
@@ -152,6 +185,8 @@ impl BufferedMediaSource {
                 download_complete: false,
                 download_error: None,
                 total_size,
+                read_pos: 0,
+                last_chunk_at: Instant::now(),
             }),
             Condvar::new(),
         ));
@@ -160,6 +195,9 @@ impl BufferedMediaSource {
             state: Arc::clone(&state),
             config: config.clone(),
             read_pos: std::sync::atomic::AtomicU64::new(0),
+            stalled: AtomicBool::new(false),
+            rebuffer_bytes: AtomicUsize::new(config.initial_buffer_bytes),
+            byte_rate: AtomicUsize::new(0),
         };
 
         let writer = BufferWriter { state };
@@ -174,6 +212,9 @@ impl BufferedMediaSource {
             state: Arc::clone(&self.state),
             config: self.config.clone(),
             read_pos: std::sync::atomic::AtomicU64::new(0),
+            stalled: AtomicBool::new(false),
+            rebuffer_bytes: AtomicUsize::new(self.rebuffer_bytes.load(Ordering::Relaxed)),
+            byte_rate: AtomicUsize::new(self.byte_rate.load(Ordering::Relaxed)),
         }
     }
 
@@ -290,12 +331,113 @@ impl BufferedMediaSource {
             false
         }
     }
+
+    /// Initial buffer fill as a percentage (100 once complete)
+    pub fn min_buffer_progress(&self) -> u8 {
+        let (lock, _) = &*self.state;
+        match lock.lock() {
+            Ok(state) if state.download_complete => 100,
+            Ok(state) => percent(state.data.len(), self.config.initial_buffer_bytes),
+            Err(_) => 0,
+        }
+    }
+
+    /// Downloaded bytes the decoder has not consumed yet
+    pub fn bytes_ahead(&self) -> usize {
+        let (lock, _) = &*self.state;
+        if let Ok(state) = lock.lock() {
+            state.data.len().saturating_sub(state.read_pos)
+        } else {
+            0
+        }
+    }
+
+    /// True when decoding further would block waiting for the download
+    pub fn is_starved(&self) -> bool {
+        let (lock, _) = &*self.state;
+        if let Ok(state) = lock.lock() {
+            !state.download_complete
+                && state.download_error.is_none()
+                && state.data.len().saturating_sub(state.read_pos) < STARVATION_LOW_WATER_BYTES
+        } else {
+            false
+        }
+    }
+
+    /// Set how many undecoded bytes must be buffered before resuming after a stall
+    pub fn set_rebuffer_bytes(&self, bytes: usize) {
+        self.rebuffer_bytes
+            .store(bytes.max(STARVATION_LOW_WATER_BYTES * 2), Ordering::Relaxed);
+    }
+
+    /// Re-buffering progress as a percentage (100 = ready to resume)
+    pub fn rebuffer_progress(&self) -> u8 {
+        let (lock, _) = &*self.state;
+        match lock.lock() {
+            Ok(state) if state.download_complete || state.download_error.is_some() => 100,
+            Ok(state) => percent(
+                state.data.len().saturating_sub(state.read_pos),
+                self.rebuffer_bytes.load(Ordering::Relaxed),
+            ),
+            Err(_) => 0,
+        }
+    }
+
+    /// Set the average bytes per second of audio, used for `buffered_ahead_secs`
+    pub fn set_byte_rate(&self, bytes_per_sec: usize) {
+        self.byte_rate.store(bytes_per_sec, Ordering::Relaxed);
+    }
+
+    /// Seconds of audio downloaded but not decoded yet (None while the byte
+    /// rate is unknown)
+    pub fn buffered_ahead_secs(&self) -> Option<f32> {
+        let byte_rate = self.byte_rate.load(Ordering::Relaxed);
+        (byte_rate > 0).then(|| self.bytes_ahead() as f32 / byte_rate as f32)
+    }
+
+    /// True when nothing has been downloaded for `after` while less than the
+    /// re-buffering threshold is buffered: playback runs dry unless the
+    /// download picks up again
+    pub fn is_download_stalled(&self, after: Duration) -> bool {
+        let (lock, _) = &*self.state;
+        if let Ok(state) = lock.lock() {
+            !state.download_complete
+                && state.download_error.is_none()
+                && state.last_chunk_at.elapsed() >= after
+                && state.data.len().saturating_sub(state.read_pos)
+                    < self.rebuffer_bytes.load(Ordering::Relaxed)
+        } else {
+            false
+        }
+    }
+
+    /// Error the download failed with, if any
+    pub fn download_error(&self) -> Option<String> {
+        let (lock, _) = &*self.state;
+        lock.lock()
+            .ok()
+            .and_then(|state| state.download_error.clone())
+    }
+
+    pub fn is_stalled(&self) -> bool {
+        self.stalled.load(Ordering::SeqCst)
+    }
+
+    pub fn set_stalled(&self, stalled: bool) {
+        self.stalled.store(stalled, Ordering::SeqCst);
+    }
+}
+
+fn percent(value: usize, target: usize) -> u8 {
+    if target == 0 {
+        100
+    } else {
+        ((value as u64 * 100) / target as u64).min(100) as u8
+    }
 }
 
 impl Read for BufferedMediaSource {
     fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
-        use std::sync::atomic::Ordering;
-
         let (lock, cvar) = &*self.state;
         let mut state = lock.lock().map_err(|_| {
             IoError::new(ErrorKind::Other, "Failed to acquire buffer lock")
@@ -328,6 +470,7 @@ impl Read for BufferedMediaSource {
         let to_read = buf.len().min(available);
         buf[..to_read].copy_from_slice(&state.data[read_pos..read_pos + to_read]);
         self.read_pos.store((read_pos + to_read) as u64, Ordering::SeqCst);
+        state.read_pos = read_pos + to_read;
 
         Ok(to_read)
     }
@@ -335,8 +478,6 @@ impl Read for BufferedMediaSource {
 
 impl Seek for BufferedMediaSource {
     fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
-        use std::sync::atomic::Ordering;
-
         let (lock, cvar) = &*self.state;
         let mut state = lock.lock().map_err(|_| {
             IoError::new(ErrorKind::Other, "Failed to acquire buffer lock")
@@ -395,6 +536,7 @@ impl Seek for BufferedMediaSource {
         }
 
         self.read_pos.store(new_pos as u64, Ordering::SeqCst);
+        state.read_pos = new_pos_usize;
         Ok(new_pos as u64)
     }
 }
@@ -434,6 +576,7 @@ impl BufferWriter {
         let mut state = lock.lock().map_err(|_| "Failed to acquire buffer lock")?;
 
         state.data.extend_from_slice(chunk);
+        state.last_chunk_at = Instant::now();
         cvar.notify_all();
 
         Ok(())
@@ -506,6 +649,10 @@ pub struct IncrementalStreamingSource {
     packets_decoded: u64,
     /// Reference to the buffered source (for cache retrieval after playback)
     buffered_source: Arc<BufferedMediaSource>,
+    /// True while the download cannot keep up (decoding is skipped)
+    starved: bool,
+    /// Samples until the next starvation check
+    starvation_check_countdown: u32,
 }
 
 impl IncrementalStreamingSource {
@@ -573,6 +720,8 @@ impl IncrementalStreamingSource {
             finished: false,
             packets_decoded: 0,
             buffered_source,
+            starved: false,
+            starvation_check_countdown: 0,
         })
     }
 
@@ -586,6 +735,11 @@ impl IncrementalStreamingSource {
         self.channels
     }
 
+    /// Get the codec being decoded
+    pub fn codec(&self) -> CodecType {
+        self.decoder.codec_params().codec
+    }
+
     /// Get reference to the buffered source for cache retrieval
     pub fn buffered_source(&self) -> &Arc<BufferedMediaSource> {
         &self.buffered_source
@@ -685,11 +839,35 @@ impl Iterator for IncrementalStreamingSource {
         // If queue is running low, decode more
         // Keep at least 0.5 seconds of audio buffered
         let min_buffer = (self.sample_rate as usize * self.channels as usize) / 2;
-        if self.sample_queue.len() < min_buffer {
-            self.decode_more(min_buffer);
+        if self.sample_queue.len() < min_buffer && !self.finished {
+            // The audio thread cleared the stall: the buffer has refilled
+            if self.starved && !self.buffered_source.is_stalled() {
+                self.starved = false;
+            }
+
+            if self.starvation_check_countdown == 0 {
+                self.starvation_check_countdown = STARVATION_CHECK_INTERVAL;
+                if !self.starved && self.buffered_source.is_starved() {
+                    log::warn!("IncrementalStreamingSource: download stalled, requesting re-buffer");
+                    self.starved = true;
+                    self.buffered_source.set_stalled(true);
+                }
+            } else {
+                self.starvation_check_countdown -= 1;
+            }
+
+            // Never block the output callback on the download
+            if !self.starved {
+                self.decode_more(min_buffer);
+            }
         }
 
-        self.sample_queue.pop_front()
+        match self.sample_queue.pop_front() {
+            Some(sample) => Some(sample),
+            // Output is being paused for re-buffering; do not end the track
+            None if self.starved => Some(0.0),
+            None => None,
+        }
     }
 }
 
@@ -747,6 +925,74 @@ mod tests {
         assert_eq!(&buf, b"34567");
     }
 
+    #[test]
+    fn test_starvation_and_rebuffer_progress() {
+        let config = StreamingConfig {
+            initial_buffer_bytes: 16,
+            max_buffer_bytes: 1024 * 1024,
+        };
+        let (source, writer) = BufferedMediaSource::new(config, None);
+        let mut reader = source.create_reader();
+        source.set_rebuffer_bytes(0);
+
+        writer.push_chunk(&vec![0u8; STARVATION_LOW_WATER_BYTES]).unwrap();
+        assert!(!source.is_starved());
+
+        let mut buf = vec![0u8; STARVATION_LOW_WATER_BYTES / 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert!(source.is_starved());
+        assert_eq!(source.rebuffer_progress(), 25);
+
+        writer.push_chunk(&vec![0u8; STARVATION_LOW_WATER_BYTES * 2]).unwrap();
+        assert_eq!(source.rebuffer_progress(), 100);
+
+        writer.complete().unwrap();
+        assert!(!source.is_starved());
+    }
+
+    #[test]
+    fn test_slow_download_reports_stall_until_data_resumes() {
+        let config = StreamingConfig {
+            initial_buffer_bytes: 16,
+            max_buffer_bytes: 1024 * 1024,
+        };
+        let (source, writer) = BufferedMediaSource::new(config, None);
+        const LOW_WATER: usize = STARVATION_LOW_WATER_BYTES;
+        let mut reader = source.create_reader();
+        let stall_after = Duration::from_millis(40);
+        source.set_rebuffer_bytes(LOW_WATER * 4);
+        source.set_byte_rate(LOW_WATER);
+        assert_eq!(source.buffered_ahead_secs(), Some(0.0));
+
+        // A slow server: one chunk, then nothing for a while
+        writer.push_chunk(&vec![0u8; LOW_WATER * 2]).unwrap();
+        assert_eq!(source.buffered_ahead_secs(), Some(2.0));
+        let mut buf = vec![0u8; LOW_WATER];
+        reader.read_exact(&mut buf).unwrap();
+        assert!(!source.is_download_stalled(stall_after));
+        thread::sleep(stall_after * 2);
+        assert!(source.is_download_stalled(stall_after));
+
+        // Data arriving again ends the stall
+        let slow_writer = writer.clone();
+        thread::spawn(move || {
+            slow_writer.push_chunk(&vec![0u8; LOW_WATER]).unwrap();
+        })
+        .join()
+        .unwrap();
+        assert!(!source.is_download_stalled(stall_after));
+        assert_eq!(source.buffered_ahead_secs(), Some(2.0));
+
+        // Quiet again, but now with enough buffered the stall isn't reported
+        writer.push_chunk(&vec![0u8; LOW_WATER * 4]).unwrap();
+        thread::sleep(stall_after * 2);
+        assert!(!source.is_download_stalled(stall_after));
+
+        writer.error("connection reset".to_string()).unwrap();
+        assert!(!source.is_download_stalled(stall_after));
+        assert_eq!(source.download_error().as_deref(), Some("connection reset"));
+    }
+
     #[test]
     fn test_complete_data_retrieval() {
         let config = StreamingConfig {
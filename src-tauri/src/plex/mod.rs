@@ -13,6 +13,8 @@ use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
+use crate::network::http_client;
+use crate::quality::QualityInfo;
 use crate::AppState;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +50,16 @@ pub struct PlexTrack {
     pub bit_depth: Option<u32>,
 }
 
+impl PlexTrack {
+    pub fn quality_info(&self) -> QualityInfo {
+        QualityInfo::from_specs(
+            self.bit_depth,
+            self.sampling_rate_hz.map(f64::from),
+            self.codec.as_deref().or(self.container.as_deref()),
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PlexPlayResult {
@@ -93,6 +105,8 @@ pub struct PlexCachedAlbum {
     pub sample_rate: u32,
     pub source: String,
     pub likely_single_file_album: bool,
+    /// Best quality among the album's tracks
+    pub quality: QualityInfo,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -110,6 +124,7 @@ pub struct PlexCachedTrack {
     pub artwork_path: Option<String>,
     pub source: String,
     pub album_key: String,
+    pub quality: QualityInfo,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -223,10 +238,9 @@ fn build_plex_client() -> Result<reqwest::Client, String> {
         HeaderValue::from_static("qbz-plex-lan-poc"),
     );
 
-    reqwest::Client::builder()
+    http_client::builder(http_client::DEFAULT_USER_AGENT)
         .default_headers(headers)
         .timeout(Duration::from_secs(120))
-        .connect_timeout(Duration::from_secs(8))
         .build()
         .map_err(|e| format!("Failed to create Plex HTTP client: {}", e))
 }
@@ -247,10 +261,9 @@ fn build_plex_auth_client(client_identifier: &str) -> Result<reqwest::Client, St
     );
     headers.insert("Accept", HeaderValue::from_static("application/json"));
 
-    reqwest::Client::builder()
+    http_client::builder(http_client::DEFAULT_USER_AGENT)
         .default_headers(headers)
         .timeout(Duration::from_secs(20))
-        .connect_timeout(Duration::from_secs(8))
         .build()
         .map_err(|e| format!("Failed to create Plex auth HTTP client: {}", e))
 }
@@ -559,6 +572,20 @@ fn parse_track_block(start_tag: &str, inner_xml: &str) -> Option<PlexTrack> {
     })
 }
 
+/// Quality of a Plex cache row, from the stored specs rather than the
+/// display defaults (flac, 44.1 kHz) used when they're missing
+fn cached_track_quality(
+    container: &Option<String>,
+    bit_depth: Option<i64>,
+    sampling_rate_hz: Option<i64>,
+) -> QualityInfo {
+    QualityInfo::from_specs(
+        bit_depth.map(|v| v as u32),
+        sampling_rate_hz.map(|v| v as f64),
+        container.as_deref(),
+    )
+}
+
 fn parse_tracks(xml: &str, limit: Option<u32>) -> Vec<PlexTrack> {
     let mut tracks = Vec::new();
 
@@ -966,6 +993,7 @@ pub fn plex_cache_get_albums() -> Result<Vec<PlexCachedAlbum>, String> {
                 sample_rate: sampling_rate_hz_opt.map(|v| v as u32).unwrap_or(44100),
                 source: "plex".to_string(),
                 likely_single_file_album: false,
+                quality: QualityInfo::default(),
             });
 
         entry.track_count += 1;
@@ -1001,6 +1029,11 @@ pub fn plex_cache_get_albums() -> Result<Vec<PlexCachedAlbum>, String> {
         if album.track_count == 1 && album.total_duration_secs > 600 {
             album.likely_single_file_album = true;
         }
+        album.quality = QualityInfo::from_specs(
+            album.bit_depth,
+            Some(f64::from(album.sample_rate)),
+            Some(&album.format),
+        );
     }
 
     albums.sort_by(|a, b| {
@@ -1072,6 +1105,7 @@ pub fn plex_cache_get_album_tracks(album_key: String) -> Result<Vec<PlexCachedTr
             artist,
             album,
             duration_secs: duration_ms_opt.map(|v| (v as u64) / 1000).unwrap_or(0),
+            quality: cached_track_quality(&container_opt, bit_depth_opt, sampling_rate_opt),
             format: container_opt.unwrap_or_else(|| "flac".to_string()),
             bit_depth: bit_depth_opt.map(|v| v as u32),
             sample_rate: sampling_rate_opt.map(|v| v as u32).unwrap_or(44100),
@@ -1083,6 +1117,43 @@ pub fn plex_cache_get_album_tracks(album_key: String) -> Result<Vec<PlexCachedTr
     Ok(tracks)
 }
 
+/// Artist name and album key of a cached Plex track, by playback track ID
+pub(crate) fn plex_cache_track_album(track_id: u64) -> Result<Option<(String, String)>, String> {
+    let conn = open_plex_cache_db()?;
+    let mut stmt = conn
+        .prepare("SELECT rating_key, artist, album FROM plex_cache_tracks")
+        .map_err(|e| format!("Failed to prepare Plex cache track lookup: {}", e))?;
+    let mut rows = stmt
+        .query([])
+        .map_err(|e| format!("Failed to query Plex cache tracks: {}", e))?;
+
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| format!("Failed to read Plex cache track row: {}", e))?
+    {
+        let rating_key: String = row.get(0).map_err(|e| e.to_string())?;
+        if playback_track_id(&rating_key) != track_id {
+            continue;
+        }
+        let artist = row
+            .get::<_, Option<String>>(1)
+            .map_err(|e| e.to_string())?
+            .map(|v| decode_xml_entities(v.trim()))
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "Unknown Artist".to_string());
+        let album_raw = row
+            .get::<_, Option<String>>(2)
+            .map_err(|e| e.to_string())?
+            .map(|v| decode_xml_entities(v.trim()))
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "Unknown Album".to_string());
+        let album = normalize_album_title(Some(&artist), &album_raw);
+        let album_key = plex_album_key(&artist, &album);
+        return Ok(Some((artist, album_key)));
+    }
+    Ok(None)
+}
+
 #[tauri::command]
 pub fn plex_cache_search_tracks(
     query: String,
@@ -1149,6 +1220,7 @@ pub fn plex_cache_search_tracks(
             artist: artist.clone(),
             album: album.clone(),
             duration_secs: duration_ms_opt.map(|v| (v as u64) / 1000).unwrap_or(0),
+            quality: cached_track_quality(&container_opt, bit_depth_opt, sampling_rate_opt),
             format: container_opt.unwrap_or_else(|| "flac".to_string()),
             bit_depth: bit_depth_opt.map(|v| v as u32),
             sample_rate: sampling_rate_opt.map(|v| v as u32).unwrap_or(44100),
@@ -1388,10 +1460,18 @@ pub async fn plex_play_track(
         .map_err(|e| format!("Failed to read Plex media bytes: {}", e))?;
 
     let playback_id = playback_track_id(&rating_key);
+    app_state
+        .player
+        .state
+        .set_keep_lead_in(app_state.queue.current_continues_album());
     app_state
         .player
         .play_data(bytes.to_vec(), playback_id)
         .map_err(|e| format!("Failed to play Plex track: {}", e))?;
+    app_state
+        .player
+        .state
+        .set_track_source(crate::player::TrackSource::Plex);
 
     Ok(PlexPlayResult {
         rating_key,
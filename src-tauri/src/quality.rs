@@ -0,0 +1,125 @@
+//! Audio quality model shared by Qobuz, Plex and local tracks
+//!
+//! Each source reports quality differently (Qobuz `maximum_bit_depth` and a
+//! kHz sampling rate, Plex `bit_depth`/`sampling_rate_hz`, local file specs).
+//! `QualityInfo` normalizes them so every source gets the same badges.
+
+use serde::{Deserialize, Serialize};
+
+/// Bit depths above this are hi-res
+pub const HIRES_BIT_DEPTH_ABOVE: u32 = 16;
+
+/// Sample rates (Hz) above this are hi-res
+pub const HIRES_SAMPLE_RATE_ABOVE_HZ: f64 = 48_000.0;
+
+/// Codecs that never lose information
+const LOSSLESS_CODECS: &[&str] = &[
+    "flac", "alac", "wav", "aiff", "aif", "ape", "wv", "wavpack", "dsf", "dff",
+];
+
+/// Codecs that always lose information
+const LOSSY_CODECS: &[&str] = &["mp3", "aac", "vorbis", "opus", "wma"];
+
+/// Normalized quality of a track, whatever its source
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QualityInfo {
+    pub bit_depth: Option<u32>,
+    /// Sample rate in Hz
+    pub sample_rate: Option<f64>,
+    /// Codec or container name as reported by the source (e.g. "FLAC")
+    pub codec: Option<String>,
+    pub lossless: bool,
+    pub hires: bool,
+}
+
+impl QualityInfo {
+    /// Build from raw specs. When the codec is missing or ambiguous (an mp4
+    /// container can hold AAC or ALAC), a known bit depth is taken as lossless,
+    /// since lossy formats have none.
+    pub fn from_specs(
+        bit_depth: Option<u32>,
+        sample_rate_hz: Option<f64>,
+        codec: Option<&str>,
+    ) -> Self {
+        let bit_depth = bit_depth.filter(|&depth| depth > 0);
+        let sample_rate = sample_rate_hz.filter(|&rate| rate > 0.0);
+        let codec = codec.map(str::trim).filter(|codec| !codec.is_empty());
+        let lossless = match codec {
+            Some(codec) if is_lossless_codec(codec) => true,
+            Some(codec) if is_lossy_codec(codec) => false,
+            _ => bit_depth.is_some(),
+        };
+        Self {
+            bit_depth,
+            sample_rate,
+            codec: codec.map(str::to_string),
+            lossless,
+            hires: lossless && is_hires(bit_depth, sample_rate),
+        }
+    }
+}
+
+/// Hi-res means more than 16 bits or more than 48 kHz
+pub fn is_hires(bit_depth: Option<u32>, sample_rate_hz: Option<f64>) -> bool {
+    bit_depth.is_some_and(|depth| depth > HIRES_BIT_DEPTH_ABOVE)
+        || sample_rate_hz.is_some_and(|rate| rate > HIRES_SAMPLE_RATE_ABOVE_HZ)
+}
+
+pub fn is_lossless_codec(codec: &str) -> bool {
+    LOSSLESS_CODECS.contains(&codec.to_ascii_lowercase().as_str())
+}
+
+pub fn is_lossy_codec(codec: &str) -> bool {
+    LOSSY_CODECS.contains(&codec.to_ascii_lowercase().as_str())
+}
+
+/// Sample rate in Hz from a value that may be in kHz (Qobuz reports 44.1,
+/// 96, ...; files and Plex report 44100, 96000, ...)
+pub fn sample_rate_hz(rate: f64) -> f64 {
+    if rate > 0.0 && rate < 1000.0 {
+        rate * 1000.0
+    } else {
+        rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hires_thresholds() {
+        assert!(!is_hires(Some(16), Some(44_100.0)));
+        assert!(!is_hires(Some(16), Some(48_000.0)));
+        assert!(is_hires(Some(24), Some(44_100.0)));
+        assert!(is_hires(Some(16), Some(96_000.0)));
+        assert!(!is_hires(None, None));
+    }
+
+    #[test]
+    fn test_from_specs() {
+        let flac = QualityInfo::from_specs(Some(24), Some(96_000.0), Some("FLAC"));
+        assert!(flac.lossless && flac.hires);
+
+        let mp3 = QualityInfo::from_specs(None, Some(48_000.0), Some("mp3"));
+        assert!(!mp3.lossless && !mp3.hires);
+
+        // No codec: a bit depth means lossless
+        let cd = QualityInfo::from_specs(Some(16), Some(44_100.0), None);
+        assert!(cd.lossless && !cd.hires);
+        assert_eq!(cd.codec, None);
+
+        // Ambiguous container: decided by the bit depth
+        let alac = QualityInfo::from_specs(Some(24), Some(48_000.0), Some("mp4"));
+        assert!(alac.lossless && alac.hires);
+        let aac = QualityInfo::from_specs(None, Some(44_100.0), Some("mp4"));
+        assert!(!aac.lossless);
+    }
+
+    #[test]
+    fn test_sample_rate_hz() {
+        assert_eq!(sample_rate_hz(44.1), 44_100.0);
+        assert_eq!(sample_rate_hz(192.0), 192_000.0);
+        assert_eq!(sample_rate_hz(96_000.0), 96_000.0);
+    }
+}
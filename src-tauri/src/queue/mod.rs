@@ -10,6 +10,8 @@
 use std::collections::VecDeque;
 use std::sync::Mutex;
 
+use crate::quality::QualityInfo;
+
 /// Track info stored in the queue
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct QueueTrack {
@@ -36,12 +38,63 @@ pub struct QueueTrack {
     /// Optional origin source (e.g. "qobuz", "local", "plex")
     #[serde(default)]
     pub source: Option<String>,
+    /// Track number within its disc (used for gapless album boundary detection)
+    #[serde(default)]
+    pub track_number: Option<u32>,
+    /// Disc number within the album (Qobuz `media_number`)
+    #[serde(default)]
+    pub disc_number: Option<u32>,
+    /// Guest who added the track during a party session
+    #[serde(default)]
+    pub contributor: Option<String>,
 }
 
 fn default_streamable() -> bool {
     true // Default to true for backwards compatibility with existing queue data
 }
 
+impl QueueTrack {
+    /// Whether the player can play this track (local and Plex files always can)
+    pub fn is_playable(&self) -> bool {
+        self.streamable || self.is_local || self.source.as_deref() == Some("plex")
+    }
+
+    /// Quality from the stored specs; `hires` as sent by the frontend isn't
+    /// trusted since each view used to compute it differently
+    pub fn quality_info(&self) -> QualityInfo {
+        QualityInfo::from_specs(
+            self.bit_depth,
+            self.sample_rate.map(crate::quality::sample_rate_hz),
+            None,
+        )
+    }
+}
+
+/// Why a track was passed over while advancing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Marked as not streamable (region-locked or pulled from Qobuz)
+    NotStreamable,
+}
+
+/// Payload of the `playback:skipped` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SkippedTrack {
+    pub track_id: u64,
+    pub title: String,
+    pub artist: String,
+    pub reason: SkipReason,
+}
+
+/// Result of advancing the queue
+#[derive(Debug, Default)]
+pub struct QueueAdvance {
+    pub track: Option<QueueTrack>,
+    /// Unplayable tracks passed over on the way
+    pub skipped: Vec<SkippedTrack>,
+}
+
 /// Repeat mode options
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum RepeatMode {
@@ -68,6 +121,34 @@ pub struct QueueState {
     pub total_tracks: usize,
 }
 
+/// Aggregate queue timing in seconds
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub struct QueueTiming {
+    pub total_secs: u64,
+    pub elapsed_secs: u64,
+    pub remaining_secs: u64,
+}
+
+/// A track in a queue window, with its index for play/move/remove calls
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QueueWindowTrack {
+    /// Index in the queue's track list
+    pub index: usize,
+    #[serde(flatten)]
+    pub track: QueueTrack,
+}
+
+/// Slice of the queue in play order (shuffle order when shuffle is on)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QueueWindow {
+    pub tracks: Vec<QueueWindowTrack>,
+    /// Play-order position of the first track in `tracks`
+    pub start: usize,
+    /// Play-order position of the current track (None = nothing playing)
+    pub current_position: Option<usize>,
+    pub total_tracks: usize,
+}
+
 /// Internal queue state - all in one struct to avoid deadlocks
 struct InternalState {
     /// All tracks in the queue (original order)
@@ -86,6 +167,28 @@ struct InternalState {
     history: VecDeque<usize>,
 }
 
+/// Fisher-Yates shuffle seeded from the current time.
+///
+/// Used for the persistent shuffle order and for one-shot shuffles of a
+/// collection that should not turn on the global shuffle mode.
+pub fn shuffle_once<T>(items: &mut [T]) {
+    use rand::{Rng, SeedableRng};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // Create seeded RNG from current timestamp
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+
+    // Proper Fisher-Yates shuffle - each iteration gets a NEW random number
+    for i in (1..items.len()).rev() {
+        let j = rng.gen_range(0..=i);
+        items.swap(i, j);
+    }
+}
+
 /// Queue manager for handling playback queue
 pub struct QueueManager {
     state: Mutex<InternalState>,
@@ -265,45 +368,225 @@ impl QueueManager {
         true
     }
 
+    /// Move a selection of tracks (contiguous or scattered) as one block.
+    ///
+    /// The moved tracks keep their relative order and the first of them ends up
+    /// at `to_index` in the resulting queue. The current track stays current,
+    /// and shuffle order and history follow the tracks they point at.
+    pub fn move_tracks(&self, indices: Vec<usize>, to_index: usize) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let len = state.tracks.len();
+
+        let mut selected = indices;
+        selected.sort_unstable();
+        selected.dedup();
+        if selected.is_empty() || selected.iter().any(|&idx| idx >= len) {
+            return false;
+        }
+
+        // New order expressed as old indices: unselected tracks with the block spliced in
+        let mut is_selected = vec![false; len];
+        for &idx in &selected {
+            is_selected[idx] = true;
+        }
+        let mut new_order: Vec<usize> = (0..len).filter(|&idx| !is_selected[idx]).collect();
+        let insert_at = to_index.min(new_order.len());
+        let tail = new_order.split_off(insert_at);
+        new_order.extend(selected);
+        new_order.extend(tail);
+
+        if new_order.iter().enumerate().all(|(new_idx, &old_idx)| new_idx == old_idx) {
+            return false;
+        }
+
+        let mut old_to_new = vec![0; len];
+        for (new_idx, &old_idx) in new_order.iter().enumerate() {
+            old_to_new[old_idx] = new_idx;
+        }
+
+        let mut old_tracks: Vec<Option<QueueTrack>> = std::mem::take(&mut state.tracks)
+            .into_iter()
+            .map(Some)
+            .collect();
+        state.tracks = new_order
+            .iter()
+            .filter_map(|&old_idx| old_tracks[old_idx].take())
+            .collect();
+
+        Self::remap_indices_internal(&mut state, |idx| old_to_new[idx]);
+        true
+    }
+
+    /// Insert tracks at a position in the queue (e.g. dropping an album mid-queue).
+    ///
+    /// `index` is clamped to the queue length. The current track stays current;
+    /// with shuffle on, the new tracks are appended to the shuffle order.
+    pub fn insert_tracks_at(&self, index: usize, new_tracks: Vec<QueueTrack>) {
+        if new_tracks.is_empty() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let insert_at = index.min(state.tracks.len());
+        let count = new_tracks.len();
+
+        let tail = state.tracks.split_off(insert_at);
+        state.tracks.extend(new_tracks);
+        state.tracks.extend(tail);
+        Self::remap_indices_internal(&mut state, |idx| {
+            if idx >= insert_at { idx + count } else { idx }
+        });
+
+        if state.shuffle {
+            for i in insert_at..insert_at + count {
+                state.shuffle_order.push(i);
+            }
+        }
+    }
+
     /// Get current track
     pub fn current_track(&self) -> Option<QueueTrack> {
         let state = self.state.lock().unwrap();
         state.current_index.and_then(|idx| state.tracks.get(idx).cloned())
     }
 
+    /// First queued track with `track_id`
+    pub fn find_track(&self, track_id: u64) -> Option<QueueTrack> {
+        let state = self.state.lock().unwrap();
+        state.tracks.iter().find(|t| t.id == track_id).cloned()
+    }
+
     /// Get next track without advancing
     pub fn peek_next(&self) -> Option<QueueTrack> {
         let state = self.state.lock().unwrap();
+        Self::peek_next_index_internal(&state).and_then(|idx| state.tracks.get(idx).cloned())
+    }
+
+    /// Whether the upcoming track continues the current one on the same album.
+    ///
+    /// True when both tracks share an `album_id` and the next one is the following
+    /// track number on the same disc (or track 1 of the next disc). The player uses
+    /// this to prefer an instant hand-off over a normal transition.
+    pub fn next_is_gapless_boundary(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        if state.repeat == RepeatMode::One {
+            return false;
+        }
+
+        let current = state.current_index.and_then(|idx| state.tracks.get(idx));
+        let next = Self::peek_next_index_internal(&state).and_then(|idx| state.tracks.get(idx));
+
+        match (current, next) {
+            (Some(current), Some(next)) => Self::is_album_continuation(current, next),
+            _ => false,
+        }
+    }
+
+    /// True when the current track directly follows the previously played
+    /// one on the same album (the lead-in of such a track is kept)
+    pub fn current_continues_album(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        let previous = state.history.back().and_then(|&idx| state.tracks.get(idx));
+        let current = state.current_index.and_then(|idx| state.tracks.get(idx));
+
+        match (previous, current) {
+            (Some(previous), Some(current)) => Self::is_album_continuation(previous, current),
+            _ => false,
+        }
+    }
+
+    /// Check whether `next` directly follows `current` on the same album
+    fn is_album_continuation(current: &QueueTrack, next: &QueueTrack) -> bool {
+        let same_album = match (&current.album_id, &next.album_id) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        };
+        if !same_album || current.is_local != next.is_local {
+            return false;
+        }
+
+        let (Some(cur_num), Some(next_num)) = (current.track_number, next.track_number) else {
+            return false;
+        };
+
+        let cur_disc = current.disc_number.unwrap_or(1);
+        let next_disc = next.disc_number.unwrap_or(1);
+
+        if cur_disc == next_disc {
+            next_num == cur_num + 1
+        } else {
+            next_disc == cur_disc + 1 && next_num == 1
+        }
+    }
+
+    /// Resolve the index of the next track without advancing (must be called with lock held)
+    fn peek_next_index_internal(state: &InternalState) -> Option<usize> {
         if state.tracks.is_empty() {
             return None;
         }
 
         if state.repeat == RepeatMode::One {
-            return state.current_index.and_then(|idx| state.tracks.get(idx).cloned());
+            return state.current_index;
         }
 
-        let next_idx = if state.shuffle {
-            let next_pos = state.shuffle_position + 1;
-            if next_pos < state.shuffle_order.len() {
-                Some(state.shuffle_order[next_pos])
-            } else if state.repeat == RepeatMode::All {
-                state.shuffle_order.first().copied()
-            } else {
-                None
-            }
+        Self::next_playable_position(state)
+            .0
+            .and_then(|pos| Self::index_at_position(state, pos))
+    }
+
+    /// Play-order position of the current track (track index unless shuffled)
+    fn current_position(state: &InternalState) -> usize {
+        if state.shuffle {
+            state.shuffle_position
         } else {
-            let curr_idx = state.current_index.unwrap_or(0);
-            let next_idx = curr_idx + 1;
-            if next_idx < state.tracks.len() {
-                Some(next_idx)
-            } else if state.repeat == RepeatMode::All {
-                Some(0)
-            } else {
-                None
-            }
+            state.current_index.unwrap_or(0)
+        }
+    }
+
+    /// Track index at a play-order position
+    fn index_at_position(state: &InternalState, pos: usize) -> Option<usize> {
+        if state.shuffle {
+            state.shuffle_order.get(pos).copied()
+        } else {
+            (pos < state.tracks.len()).then_some(pos)
+        }
+    }
+
+    /// Play-order position following `pos`, wrapping around on repeat all
+    fn position_after(state: &InternalState, pos: usize) -> Option<usize> {
+        let len = if state.shuffle {
+            state.shuffle_order.len()
+        } else {
+            state.tracks.len()
         };
+        if pos + 1 < len {
+            Some(pos + 1)
+        } else if state.repeat == RepeatMode::All && len > 0 {
+            Some(0)
+        } else {
+            None
+        }
+    }
 
-        next_idx.and_then(|idx| state.tracks.get(idx).cloned())
+    /// Next play-order position holding a playable track, plus the track
+    /// indices of the unplayable ones passed on the way
+    fn next_playable_position(state: &InternalState) -> (Option<usize>, Vec<usize>) {
+        let mut skipped = Vec::new();
+        let mut pos = Self::current_position(state);
+        for _ in 0..state.tracks.len() {
+            let Some(next) = Self::position_after(state, pos) else {
+                break;
+            };
+            match Self::index_at_position(state, next) {
+                Some(idx) if !state.tracks[idx].is_playable() => {
+                    skipped.push(idx);
+                    pos = next;
+                }
+                Some(_) => return (Some(next), skipped),
+                None => break,
+            }
+        }
+        (None, skipped)
     }
 
     /// Get multiple upcoming tracks without advancing (for prefetching)
@@ -350,14 +633,22 @@ impl QueueManager {
             }
         }
 
+        // Nothing to prefetch for tracks the queue will skip
+        result.retain(QueueTrack::is_playable);
         result
     }
 
     /// Advance to next track and return it
     pub fn next(&self) -> Option<QueueTrack> {
+        self.advance().track
+    }
+
+    /// Advance to the next playable track, skipping tracks that can't be
+    /// streamed (see [`QueueTrack::is_playable`])
+    pub fn advance(&self) -> QueueAdvance {
         let mut state = self.state.lock().unwrap();
         if state.tracks.is_empty() {
-            return None;
+            return QueueAdvance::default();
         }
 
         // Save current to history before moving
@@ -369,33 +660,50 @@ impl QueueManager {
         }
 
         if state.repeat == RepeatMode::One {
-            return state.current_index.and_then(|idx| state.tracks.get(idx).cloned());
+            return QueueAdvance {
+                track: state.current_index.and_then(|idx| state.tracks.get(idx).cloned()),
+                skipped: Vec::new(),
+            };
         }
 
-        let next_idx = if state.shuffle {
-            state.shuffle_position += 1;
-            if state.shuffle_position < state.shuffle_order.len() {
-                Some(state.shuffle_order[state.shuffle_position])
-            } else if state.repeat == RepeatMode::All {
-                state.shuffle_position = 0;
-                state.shuffle_order.first().copied()
-            } else {
-                None
-            }
-        } else {
-            let curr_idx = state.current_index.unwrap_or(0);
-            let next_idx = curr_idx + 1;
-            if next_idx < state.tracks.len() {
-                Some(next_idx)
-            } else if state.repeat == RepeatMode::All {
-                Some(0)
-            } else {
-                None
-            }
-        };
+        let (next_pos, skipped) = Self::next_playable_position(&state);
+        let skipped = skipped
+            .into_iter()
+            .map(|idx| {
+                let track = &state.tracks[idx];
+                SkippedTrack {
+                    track_id: track.id,
+                    title: track.title.clone(),
+                    artist: track.artist.clone(),
+                    reason: SkipReason::NotStreamable,
+                }
+            })
+            .collect();
 
+        let next_idx = next_pos.and_then(|pos| Self::index_at_position(&state, pos));
+        if state.shuffle {
+            state.shuffle_position = next_pos.unwrap_or(state.shuffle_order.len());
+        }
         state.current_index = next_idx;
-        next_idx.and_then(|idx| state.tracks.get(idx).cloned())
+
+        QueueAdvance {
+            track: next_idx.and_then(|idx| state.tracks.get(idx).cloned()),
+            skipped,
+        }
+    }
+
+    /// Flag a track as not streamable so later passes through the queue skip it
+    pub fn mark_unstreamable(&self, track_id: u64) {
+        let mut state = self.state.lock().unwrap();
+        for track in state.tracks.iter_mut().filter(|t| t.id == track_id && !t.is_local) {
+            track.streamable = false;
+        }
+    }
+
+    /// Whether the current track is `track_id` and known not to be streamable
+    pub fn is_current_unstreamable(&self, track_id: u64) -> bool {
+        self.current_track()
+            .is_some_and(|track| track.id == track_id && !track.is_playable())
     }
 
     /// Go to previous track and return it
@@ -470,6 +778,25 @@ impl QueueManager {
         state.tracks.get(index).cloned()
     }
 
+    /// Start over from the first track in play order (shuffle order if on)
+    pub fn restart(&self) -> Option<QueueTrack> {
+        let mut state = self.state.lock().unwrap();
+        let first = if state.shuffle {
+            state.shuffle_position = 0;
+            state.shuffle_order.first().copied()
+        } else {
+            (!state.tracks.is_empty()).then_some(0)
+        };
+        if let Some(curr_idx) = state.current_index {
+            state.history.push_back(curr_idx);
+            while state.history.len() > 50 {
+                state.history.pop_front();
+            }
+        }
+        state.current_index = first;
+        first.and_then(|idx| state.tracks.get(idx).cloned())
+    }
+
     /// Toggle shuffle mode
     pub fn set_shuffle(&self, enabled: bool) {
         let mut state = self.state.lock().unwrap();
@@ -541,27 +868,208 @@ impl QueueManager {
         }
     }
 
-    /// Regenerate shuffle order (internal, must be called with lock held)
-    fn regenerate_shuffle_order_internal(state: &mut InternalState) {
-        let mut order: Vec<usize> = (0..state.tracks.len()).collect();
+    /// Rewrite every stored track index after the track list was reordered
+    /// (internal, must be called with lock held)
+    fn remap_indices_internal(state: &mut InternalState, map: impl Fn(usize) -> usize) {
+        state.current_index = state.current_index.map(&map);
+        for idx in state.shuffle_order.iter_mut() {
+            *idx = map(*idx);
+        }
+        for idx in state.history.iter_mut() {
+            *idx = map(*idx);
+        }
+    }
+
+    /// Sum of all track durations in the queue
+    pub fn total_duration(&self) -> u64 {
+        let state = self.state.lock().unwrap();
+        state.tracks.iter().map(|t| t.duration_secs).sum()
+    }
 
-        // Fisher-Yates shuffle with proper PRNG
-        use rand::{Rng, SeedableRng};
-        use std::time::{SystemTime, UNIX_EPOCH};
+    /// Time left until the queue ends: the rest of the current track plus every
+    /// upcoming track in play order
+    pub fn remaining_duration_from_current(&self, current_position_secs: u64) -> u64 {
+        self.timing(current_position_secs).remaining_secs
+    }
+
+    /// Total, elapsed and remaining time of the queue in play order
+    pub fn timing(&self, current_position_secs: u64) -> QueueTiming {
+        let state = self.state.lock().unwrap();
+        let total_secs: u64 = state.tracks.iter().map(|t| t.duration_secs).sum();
+
+        let Some(curr_idx) = state.current_index else {
+            return QueueTiming {
+                total_secs,
+                elapsed_secs: 0,
+                remaining_secs: total_secs,
+            };
+        };
+
+        let play_order: Vec<usize> = if state.shuffle && state.shuffle_order.len() == state.tracks.len() {
+            state.shuffle_order.clone()
+        } else {
+            (0..state.tracks.len()).collect()
+        };
+        let current_pos = play_order.iter().position(|&idx| idx == curr_idx).unwrap_or(0);
+
+        let played: u64 = play_order[..current_pos]
+            .iter()
+            .filter_map(|&idx| state.tracks.get(idx))
+            .map(|t| t.duration_secs)
+            .sum();
+        let current_duration = state.tracks.get(curr_idx).map(|t| t.duration_secs).unwrap_or(0);
+        let elapsed_secs = played + current_position_secs.min(current_duration);
+
+        QueueTiming {
+            total_secs,
+            elapsed_secs,
+            remaining_secs: total_secs.saturating_sub(elapsed_secs),
+        }
+    }
+
+    /// Current track index (cheap "jump to now playing" lookup)
+    pub fn current_index(&self) -> Option<usize> {
+        self.state.lock().unwrap().current_index
+    }
+
+    /// Number of tracks after the current one in play order (all of them when
+    /// nothing is playing)
+    pub fn upcoming_count(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        match state.current_index {
+            None => state.tracks.len(),
+            Some(_) if state.shuffle => state.shuffle_order.len().saturating_sub(state.shuffle_position + 1),
+            Some(curr_idx) => state.tracks.len().saturating_sub(curr_idx + 1),
+        }
+    }
 
-        // Create seeded RNG from current timestamp
-        let seed = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as u64;
-        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    /// Drop tracks played before the current one, keeping the last `keep` of
+    /// them plus any track the history still points at (so "previous" keeps
+    /// working). Returns how many tracks were removed.
+    pub fn trim_played(&self, keep: usize) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let Some(curr_idx) = state.current_index else {
+            return 0;
+        };
+        let len = state.tracks.len();
+        if state.shuffle && state.shuffle_order.len() != len {
+            return 0;
+        }
 
-        // Proper Fisher-Yates shuffle - each iteration gets a NEW random number
-        for i in (1..order.len()).rev() {
-            let j = rng.gen_range(0..=i);
-            order.swap(i, j);
+        let play_order: Vec<usize> = if state.shuffle {
+            state.shuffle_order.clone()
+        } else {
+            (0..len).collect()
+        };
+        let Some(current_pos) = play_order.iter().position(|&idx| idx == curr_idx) else {
+            return 0;
+        };
+
+        let mut remove = vec![false; len];
+        for &idx in &play_order[..current_pos.saturating_sub(keep)] {
+            remove[idx] = true;
+        }
+        state.history.retain(|&idx| idx < len);
+        for &idx in state.history.iter() {
+            remove[idx] = false;
+        }
+        let removed = remove.iter().filter(|&&r| r).count();
+        if removed == 0 {
+            return 0;
         }
 
+        // Old index -> index after removal
+        let mut new_index = Vec::with_capacity(len);
+        let mut kept = 0;
+        for &r in &remove {
+            new_index.push(kept);
+            if !r {
+                kept += 1;
+            }
+        }
+
+        let mut idx = 0;
+        state.tracks.retain(|_| {
+            let keep_track = !remove[idx];
+            idx += 1;
+            keep_track
+        });
+        state.shuffle_order.retain(|&idx| remove.get(idx) == Some(&false));
+        Self::remap_indices_internal(&mut state, |idx| new_index[idx]);
+        if state.shuffle {
+            let current = state.current_index;
+            state.shuffle_position = state.shuffle_order.iter().position(|&idx| Some(idx) == current).unwrap_or(0);
+        }
+
+        removed
+    }
+
+    /// Every track in the queue, in play order (shuffle order when shuffled)
+    pub fn tracks_in_play_order(&self) -> Vec<QueueTrack> {
+        let state = self.state.lock().unwrap();
+        if state.shuffle && state.shuffle_order.len() == state.tracks.len() {
+            state
+                .shuffle_order
+                .iter()
+                .filter_map(|&idx| state.tracks.get(idx).cloned())
+                .collect()
+        } else {
+            state.tracks.clone()
+        }
+    }
+
+    /// Get a slice of the queue in play order, for virtualized lists.
+    ///
+    /// With `center_on_current`, returns `before` tracks before the current one and
+    /// `after` tracks after it. Otherwise the window starts at the top of the queue.
+    pub fn get_window(&self, center_on_current: bool, before: usize, after: usize) -> QueueWindow {
+        let state = self.state.lock().unwrap();
+        let total_tracks = state.tracks.len();
+
+        let play_order: Vec<usize> = if state.shuffle && state.shuffle_order.len() == total_tracks {
+            state.shuffle_order.clone()
+        } else {
+            (0..total_tracks).collect()
+        };
+
+        let current_position = state.current_index.and_then(|curr_idx| {
+            if state.shuffle {
+                play_order.iter().position(|&idx| idx == curr_idx)
+            } else {
+                Some(curr_idx)
+            }
+        });
+
+        let (start, end) = match current_position {
+            Some(pos) if center_on_current => {
+                (pos.saturating_sub(before), (pos + after + 1).min(total_tracks))
+            }
+            _ => (0, (before + after + 1).min(total_tracks)),
+        };
+        let start = start.min(end);
+
+        let tracks = play_order[start..end]
+            .iter()
+            .filter_map(|&idx| {
+                state.tracks.get(idx).map(|track| QueueWindowTrack {
+                    index: idx,
+                    track: track.clone(),
+                })
+            })
+            .collect();
+
+        QueueWindow {
+            tracks,
+            start,
+            current_position,
+            total_tracks,
+        }
+    }
+
+    /// Regenerate shuffle order (internal, must be called with lock held)
+    fn regenerate_shuffle_order_internal(state: &mut InternalState) {
+        let mut order: Vec<usize> = (0..state.tracks.len()).collect();
+        shuffle_once(&mut order);
         state.shuffle_order = order;
 
         // If there's a current track, find its position in the new shuffle order
@@ -577,3 +1085,273 @@ impl QueueManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album_track(id: u64, album_id: &str, disc: u32, number: u32) -> QueueTrack {
+        QueueTrack {
+            id,
+            title: format!("Track {}", id),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            duration_secs: 180,
+            artwork_url: None,
+            hires: false,
+            bit_depth: None,
+            sample_rate: None,
+            is_local: false,
+            album_id: Some(album_id.to_string()),
+            artist_id: None,
+            streamable: true,
+            source: None,
+            track_number: Some(number),
+            disc_number: Some(disc),
+            contributor: None,
+        }
+    }
+
+    #[test]
+    fn shuffle_once_keeps_every_item() {
+        let mut items: Vec<u64> = (0..50).collect();
+        shuffle_once(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..50).collect::<Vec<u64>>());
+
+        let mut empty: Vec<u64> = Vec::new();
+        shuffle_once(&mut empty);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn gapless_boundary_for_consecutive_album_tracks() {
+        let queue = QueueManager::new();
+        queue.set_queue(
+            vec![album_track(1, "a", 1, 1), album_track(2, "a", 1, 2)],
+            Some(0),
+        );
+        assert!(queue.next_is_gapless_boundary());
+    }
+
+    #[test]
+    fn gapless_boundary_across_discs() {
+        let queue = QueueManager::new();
+        queue.set_queue(
+            vec![album_track(1, "a", 1, 12), album_track(2, "a", 2, 1)],
+            Some(0),
+        );
+        assert!(queue.next_is_gapless_boundary());
+    }
+
+    #[test]
+    fn current_continues_album_after_advancing() {
+        let queue = QueueManager::new();
+        queue.set_queue(
+            vec![
+                album_track(1, "a", 1, 1),
+                album_track(2, "a", 1, 2),
+                album_track(3, "b", 1, 3),
+            ],
+            Some(0),
+        );
+        assert!(!queue.current_continues_album());
+
+        queue.next();
+        assert!(queue.current_continues_album());
+
+        queue.next();
+        assert!(!queue.current_continues_album());
+    }
+
+    #[test]
+    fn no_gapless_boundary_for_unrelated_tracks() {
+        let queue = QueueManager::new();
+        queue.set_queue(
+            vec![album_track(1, "a", 1, 1), album_track(2, "b", 1, 2)],
+            Some(0),
+        );
+        assert!(!queue.next_is_gapless_boundary());
+
+        queue.set_queue(
+            vec![album_track(1, "a", 1, 1), album_track(2, "a", 1, 5)],
+            Some(0),
+        );
+        assert!(!queue.next_is_gapless_boundary());
+    }
+
+    #[test]
+    fn no_gapless_boundary_on_repeat_one() {
+        let queue = QueueManager::new();
+        queue.set_queue(
+            vec![album_track(1, "a", 1, 1), album_track(2, "a", 1, 2)],
+            Some(0),
+        );
+        queue.set_repeat(RepeatMode::One);
+        assert!(!queue.next_is_gapless_boundary());
+    }
+
+    fn ids(queue: &QueueManager) -> Vec<u64> {
+        let state = queue.state.lock().unwrap();
+        state.tracks.iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn move_tracks_moves_scattered_selection_as_block() {
+        let queue = QueueManager::new();
+        queue.set_queue((1..=6).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(2));
+        assert!(queue.move_tracks(vec![4, 0], 1));
+        assert_eq!(ids(&queue), vec![2, 1, 5, 3, 4, 6]);
+        assert_eq!(queue.current_track().map(|t| t.id), Some(3));
+    }
+
+    #[test]
+    fn move_tracks_keeps_shuffle_order_pointing_at_same_tracks() {
+        let queue = QueueManager::new();
+        queue.set_queue((1..=5).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(0));
+        queue.set_shuffle(true);
+        let before: Vec<u64> = {
+            let state = queue.state.lock().unwrap();
+            state.shuffle_order.iter().map(|&i| state.tracks[i].id).collect()
+        };
+        assert!(queue.move_tracks(vec![3, 4], 0));
+        let after: Vec<u64> = {
+            let state = queue.state.lock().unwrap();
+            state.shuffle_order.iter().map(|&i| state.tracks[i].id).collect()
+        };
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn insert_tracks_at_keeps_current_track() {
+        let queue = QueueManager::new();
+        queue.set_queue((1..=4).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(2));
+        queue.insert_tracks_at(1, vec![album_track(10, "b", 1, 1), album_track(11, "b", 1, 2)]);
+        assert_eq!(ids(&queue), vec![1, 10, 11, 2, 3, 4]);
+        assert_eq!(queue.current_track().map(|t| t.id), Some(3));
+    }
+
+    #[test]
+    fn window_centers_on_current_track() {
+        let queue = QueueManager::new();
+        queue.set_queue((1..=10).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(5));
+        let window = queue.get_window(true, 2, 3);
+        assert_eq!(window.start, 3);
+        assert_eq!(window.current_position, Some(5));
+        assert_eq!(window.tracks.iter().map(|t| t.track.id).collect::<Vec<_>>(), vec![4, 5, 6, 7, 8, 9]);
+        assert_eq!(window.total_tracks, 10);
+
+        // Clamped at the end of the queue
+        let window = queue.get_window(true, 0, 50);
+        assert_eq!(window.tracks.len(), 5);
+    }
+
+    #[test]
+    fn timing_counts_played_current_and_upcoming() {
+        let queue = QueueManager::new();
+        queue.set_queue((1..=4).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(1));
+        assert_eq!(queue.total_duration(), 720);
+        let timing = queue.timing(60);
+        assert_eq!(timing.elapsed_secs, 240);
+        assert_eq!(timing.remaining_secs, 480);
+        assert_eq!(queue.remaining_duration_from_current(60), 480);
+    }
+
+    #[test]
+    fn restart_after_queue_end_returns_first_track() {
+        let queue = QueueManager::new();
+        assert!(queue.restart().is_none());
+
+        queue.set_queue((1..=3).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(2));
+        assert!(queue.next().is_none());
+        assert_eq!(queue.restart().map(|t| t.id), Some(1));
+        assert_eq!(queue.current_index(), Some(0));
+        assert_eq!(queue.next().map(|t| t.id), Some(2));
+    }
+
+    #[test]
+    fn tracks_in_play_order_follows_shuffle() {
+        let queue = QueueManager::new();
+        queue.set_queue((1..=5).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(0));
+        assert_eq!(
+            queue.tracks_in_play_order().iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        queue.set_shuffle(true);
+        let shuffled: Vec<u64> = {
+            let state = queue.state.lock().unwrap();
+            state.shuffle_order.iter().map(|&i| state.tracks[i].id).collect()
+        };
+        assert_eq!(
+            queue.tracks_in_play_order().iter().map(|t| t.id).collect::<Vec<_>>(),
+            shuffled
+        );
+    }
+
+    #[test]
+    fn next_skips_unstreamable_tracks() {
+        let queue = QueueManager::new();
+        let mut tracks: Vec<QueueTrack> =
+            (1..=4).map(|id| album_track(id, "a", 1, id as u32)).collect();
+        tracks[1].streamable = false;
+        tracks[2].streamable = false;
+        queue.set_queue(tracks, Some(0));
+
+        assert_eq!(queue.peek_next().map(|t| t.id), Some(4));
+        let advance = queue.advance();
+        assert_eq!(advance.track.map(|t| t.id), Some(4));
+        assert_eq!(
+            advance.skipped.iter().map(|s| s.track_id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        // Skipped tracks don't end up in history
+        assert_eq!(queue.previous().map(|t| t.id), Some(1));
+    }
+
+    #[test]
+    fn trim_played_keeps_window_and_history() {
+        let queue = QueueManager::new();
+        queue.set_queue((1..=10).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(7));
+        assert_eq!(queue.upcoming_count(), 2);
+        assert_eq!(queue.trim_played(2), 5);
+        assert_eq!(ids(&queue), vec![6, 7, 8, 9, 10]);
+        assert_eq!(queue.current_track().map(|t| t.id), Some(8));
+        assert_eq!(queue.upcoming_count(), 2);
+        assert_eq!(queue.trim_played(2), 0);
+
+        // Tracks in the history survive the trim
+        queue.set_queue((1..=10).map(|id| album_track(id, "a", 1, id as u32)).collect(), Some(0));
+        for _ in 0..3 {
+            queue.advance();
+        }
+        queue.play_index(8);
+        assert_eq!(queue.trim_played(1), 3);
+        assert_eq!(ids(&queue), vec![1, 2, 3, 4, 8, 9, 10]);
+        assert_eq!(queue.current_track().map(|t| t.id), Some(9));
+        assert_eq!(queue.upcoming_count(), 1);
+        assert_eq!(queue.previous().map(|t| t.id), Some(4));
+    }
+
+    #[test]
+    fn next_returns_none_when_nothing_left_is_playable() {
+        let queue = QueueManager::new();
+        let mut tracks: Vec<QueueTrack> =
+            (1..=3).map(|id| album_track(id, "a", 1, id as u32)).collect();
+        tracks[2].is_local = true;
+        queue.set_queue(tracks, Some(0));
+        queue.mark_unstreamable(2);
+        // Local files are always playable
+        queue.mark_unstreamable(3);
+        assert!(!queue.is_current_unstreamable(1));
+
+        let advance = queue.advance();
+        assert_eq!(advance.track.map(|t| t.id), Some(3));
+        assert_eq!(advance.skipped.len(), 1);
+
+        let advance = queue.advance();
+        assert!(advance.track.is_none());
+        assert!(advance.skipped.is_empty());
+    }
+}
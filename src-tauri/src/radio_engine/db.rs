@@ -142,6 +142,13 @@ impl RadioDb {
                 "#,
             )
             .map_err(|e| format!("Failed to initialize radio database: {}", e))?;
+
+        // Tracks played recently (in other sessions or elsewhere); only picked
+        // when nothing fresh is left. Added after the initial schema.
+        let _ = self.conn.execute(
+            "ALTER TABLE radio_pool ADD COLUMN stale INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
         Ok(())
     }
 
@@ -355,6 +362,49 @@ impl RadioDb {
         Ok(())
     }
 
+    /// Flag pool tracks as stale when they were played since `since_ts` by
+    /// another radio session, or are in `played_track_ids` (play history).
+    /// Returns the number of stale tracks in the pool.
+    pub fn mark_stale_tracks(
+        &self,
+        session_id: &str,
+        since_ts: i64,
+        played_track_ids: &[u64],
+    ) -> Result<u32, String> {
+        self.conn
+            .execute(
+                r#"
+                UPDATE radio_pool SET stale = 1
+                WHERE session_id = ?1 AND track_id IN (
+                    SELECT track_id FROM radio_history
+                    WHERE session_id != ?1 AND played_at >= ?2
+                )
+                "#,
+                params![session_id, since_ts],
+            )
+            .map_err(|e| format!("Failed to mark stale radio tracks: {}", e))?;
+
+        if !played_track_ids.is_empty() {
+            let mut stmt = self
+                .conn
+                .prepare("UPDATE radio_pool SET stale = 1 WHERE session_id = ? AND track_id = ?")
+                .map_err(|e| format!("Failed to prepare stale track update: {}", e))?;
+            for track_id in played_track_ids {
+                stmt.execute(params![session_id, *track_id as i64])
+                    .map_err(|e| format!("Failed to mark stale radio track: {}", e))?;
+            }
+        }
+
+        self.conn
+            .query_row(
+                "SELECT COUNT(*) FROM radio_pool WHERE session_id = ? AND stale = 1",
+                params![session_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|v| v as u32)
+            .map_err(|e| format!("Failed to count stale radio tracks: {}", e))
+    }
+
     pub fn get_recent_artist_ids(&self, session_id: &str, n: u32) -> Result<Vec<u64>, String> {
         if n == 0 {
             return Ok(Vec::new());
@@ -389,6 +439,7 @@ impl RadioDb {
         &self,
         session_id: &str,
         exclude_artist_ids: &[u64],
+        include_stale: bool,
     ) -> Result<Vec<RadioTrackRef>, String> {
         let mut params_vec: Vec<rusqlite::types::Value> = Vec::new();
         params_vec.push(rusqlite::types::Value::Text(session_id.to_string()));
@@ -401,6 +452,10 @@ impl RadioDb {
             "#,
         );
 
+        if !include_stale {
+            sql.push_str(" AND stale = 0");
+        }
+
         if !exclude_artist_ids.is_empty() {
             sql.push_str(" AND artist_id NOT IN (");
             for (idx, artist_id) in exclude_artist_ids.iter().enumerate() {
@@ -1,12 +1,25 @@
+use std::collections::HashSet;
+
 use super::db::{RadioDb, RadioTrackRef};
 
 pub struct RadioEngine {
     db: RadioDb,
+    /// Artists never to pick (e.g. the artist blacklist)
+    excluded_artists: HashSet<u64>,
 }
 
 impl RadioEngine {
     pub fn new(db: RadioDb) -> Self {
-        Self { db }
+        Self {
+            db,
+            excluded_artists: HashSet::new(),
+        }
+    }
+
+    /// Never pick tracks by these artists, however the pool was built
+    pub fn with_excluded_artists(mut self, artist_ids: HashSet<u64>) -> Self {
+        self.excluded_artists = artist_ids;
+        self
     }
 
     pub fn db(&self) -> &RadioDb {
@@ -47,18 +60,36 @@ impl RadioEngine {
         w
     }
 
-    pub fn next_track(&self, session_id: &str) -> Result<RadioTrackRef, String> {
-        let session = self.db.load_session(session_id)?;
-
-        let mut spacing = session.artist_spacing;
-        let candidates = loop {
+    /// Unused candidates, relaxing the artist spacing until some are found
+    fn candidates(
+        &self,
+        session_id: &str,
+        artist_spacing: u32,
+        include_stale: bool,
+    ) -> Result<Vec<RadioTrackRef>, String> {
+        let mut spacing = artist_spacing;
+        loop {
             let recent_artists = self.db.get_recent_artist_ids(session_id, spacing)?;
-            let cands = self.db.get_unused_candidates(session_id, &recent_artists)?;
+            let mut cands =
+                self.db
+                    .get_unused_candidates(session_id, &recent_artists, include_stale)?;
+            cands.retain(|t| !self.excluded_artists.contains(&t.artist_id));
             if !cands.is_empty() || spacing == 0 {
-                break cands;
+                return Ok(cands);
             }
             spacing = spacing.saturating_sub(1);
-        };
+        }
+    }
+
+    pub fn next_track(&self, session_id: &str) -> Result<RadioTrackRef, String> {
+        let session = self.db.load_session(session_id)?;
+
+        // Prefer tracks not played recently; fall back to stale ones rather
+        // than running dry when the pool is small
+        let mut candidates = self.candidates(session_id, session.artist_spacing, false)?;
+        if candidates.is_empty() {
+            candidates = self.candidates(session_id, session.artist_spacing, true)?;
+        }
 
         if candidates.is_empty() {
             return Err("Radio session exhausted: no eligible tracks left".to_string());
@@ -150,3 +150,101 @@ fn radio_stability_over_time_pool_constant_seed_present() {
     let seed_in_last_60 = picks.iter().rev().take(60).any(|a| *a == seed_artist);
     assert!(seed_in_last_60, "Seed artist missing late in session");
 }
+
+#[test]
+fn radio_prefers_tracks_not_played_recently() {
+    let db = RadioDb::open_in_memory().unwrap();
+    let seed = RadioSeed::Artist {
+        artist_id: seed_artist_id(),
+    };
+
+    // An earlier session plays tracks 1..=10
+    let earlier = db.create_session(seed, 7, 0, 0).unwrap();
+    for track_id in 1u64..=10u64 {
+        db.insert_pool_track(&earlier.id, track_id, 1, "seed_tracks", 0)
+            .unwrap();
+    }
+    let engine = RadioEngine::new(db);
+    for _ in 0..10 {
+        engine.next_track(&earlier.id).unwrap();
+    }
+
+    // A new session over tracks 1..=20, with track 15 in the play history
+    let db = engine.db();
+    let session = db.create_session(seed, 8, 0, 0).unwrap();
+    for track_id in 1u64..=20u64 {
+        db.insert_pool_track(&session.id, track_id, 1, "seed_tracks", 0)
+            .unwrap();
+    }
+    let stale = db.mark_stale_tracks(&session.id, 0, &[15]).unwrap();
+    assert_eq!(stale, 11);
+
+    for _ in 0..9 {
+        let t = engine.next_track(&session.id).unwrap();
+        assert!(
+            t.track_id > 10 && t.track_id != 15,
+            "Recently played track picked while fresh ones remain: {}",
+            t.track_id
+        );
+    }
+}
+
+#[test]
+fn radio_freshness_relaxes_when_pool_is_small() {
+    let db = RadioDb::open_in_memory().unwrap();
+    let session = db
+        .create_session(
+            RadioSeed::Artist {
+                artist_id: seed_artist_id(),
+            },
+            31,
+            5,
+            25,
+        )
+        .unwrap();
+
+    for track_id in 1u64..=5u64 {
+        db.insert_pool_track(&session.id, track_id, 1, "seed_tracks", 0)
+            .unwrap();
+    }
+    db.mark_stale_tracks(&session.id, 0, &[1, 2, 3, 4, 5]).unwrap();
+
+    let engine = RadioEngine::new(db);
+    for _ in 0..5 {
+        assert!(engine.next_track(&session.id).is_ok());
+    }
+    assert!(engine.next_track(&session.id).is_err());
+}
+
+#[test]
+fn radio_never_picks_excluded_artists() {
+    let db = RadioDb::open_in_memory().unwrap();
+    let session = db
+        .create_session(
+            RadioSeed::Artist {
+                artist_id: seed_artist_id(),
+            },
+            77,
+            5,
+            25,
+        )
+        .unwrap();
+
+    for track_id in 1u64..=40u64 {
+        let artist_id = 1000 + (track_id % 4);
+        db.insert_pool_track(&session.id, track_id, artist_id, "test_pool", 0)
+            .unwrap();
+    }
+
+    let excluded: std::collections::HashSet<u64> = [1001, 1003].into_iter().collect();
+    let engine = RadioEngine::new(db).with_excluded_artists(excluded.clone());
+    for _ in 0..20 {
+        let t = engine.next_track(&session.id).unwrap();
+        assert!(
+            !excluded.contains(&t.artist_id),
+            "Excluded artist picked: {}",
+            t.artist_id
+        );
+    }
+    assert!(engine.next_track(&session.id).is_err());
+}
@@ -1,13 +1,15 @@
 //! Tauri commands for recommendation store
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use tauri::State;
 
 use crate::api::models::{Album, Artist, ImageSet, Track};
 use crate::api_cache::ApiCacheState;
+use crate::artist_blacklist::BlacklistState;
 use crate::reco_store::db::{RecoEventRecord, RecoScoreEntry};
+use crate::reco_store::stats::{compute_stats, ListeningStats, StatsPeriod};
 use crate::reco_store::{
     AlbumCardMeta, ArtistCardMeta, HomeResolved, HomeSeeds, RecoEventInput, RecoState,
     TopArtistSeed, TrackDisplayMeta,
@@ -52,6 +54,28 @@ pub async fn reco_log_event(
     db.insert_event(&event)
 }
 
+/// Listening stats (top artists/tracks, per-day and per-source counts)
+/// computed from the logged play events
+#[tauri::command]
+pub async fn reco_get_stats(
+    period: StatsPeriod,
+    state: State<'_, RecoState>,
+) -> Result<ListeningStats, String> {
+    log::info!("Command: reco_get_stats period={:?}", period);
+
+    let today = chrono::Local::now().date_naive();
+    let plays = {
+        let guard__ = state.db.lock().await;
+        let db = guard__.as_ref().ok_or("No active session - please log in")?;
+        db.get_plays_since(period.since_ts(today))?
+    };
+
+    Ok(compute_stats(&plays, period, today, |ts| {
+        chrono::DateTime::from_timestamp(ts, 0)
+            .map(|dt| dt.with_timezone(&chrono::Local).date_naive())
+    }))
+}
+
 #[tauri::command]
 pub async fn reco_get_home(
     limit_recent_albums: Option<u32>,
@@ -59,6 +83,7 @@ pub async fn reco_get_home(
     limit_top_artists: Option<u32>,
     limit_favorites: Option<u32>,
     state: State<'_, RecoState>,
+    blacklist_state: State<'_, BlacklistState>,
 ) -> Result<HomeSeeds, String> {
     let limit_recent_albums = limit_recent_albums.unwrap_or(12);
     let limit_continue_tracks = limit_continue_tracks.unwrap_or(10);
@@ -74,13 +99,15 @@ pub async fn reco_get_home(
     let favorite_album_ids = db.get_favorite_album_ids(limit_favorites)?;
     let favorite_track_ids = db.get_favorite_track_ids(limit_favorites)?;
 
-    Ok(HomeSeeds {
+    let mut seeds = HomeSeeds {
         recently_played_album_ids,
         continue_listening_track_ids,
         top_artist_ids,
         favorite_album_ids,
         favorite_track_ids,
-    })
+    };
+    filter_blacklisted_seeds(db, &mut seeds, &blacklist_state)?;
+    Ok(seeds)
 }
 
 #[tauri::command]
@@ -150,6 +177,7 @@ pub async fn reco_get_home_ml(
     limit_top_artists: Option<u32>,
     limit_favorites: Option<u32>,
     state: State<'_, RecoState>,
+    blacklist_state: State<'_, BlacklistState>,
 ) -> Result<HomeSeeds, String> {
     let limit_recent_albums = limit_recent_albums.unwrap_or(12);
     let limit_continue_tracks = limit_continue_tracks.unwrap_or(10);
@@ -159,13 +187,15 @@ pub async fn reco_get_home_ml(
     let guard__ = state.db.lock().await;
     let db = guard__.as_ref().ok_or("No active session - please log in")?;
 
-    get_home_seeds_internal(
+    let mut seeds = get_home_seeds_internal(
         db,
         limit_recent_albums,
         limit_continue_tracks,
         limit_top_artists,
         limit_favorites,
-    )
+    )?;
+    filter_blacklisted_seeds(db, &mut seeds, &blacklist_state)?;
+    Ok(seeds)
 }
 
 /// Merge two lists preserving order: fresh items first, then scored items (excluding duplicates)
@@ -712,6 +742,86 @@ fn get_home_seeds_internal(
     })
 }
 
+fn is_album_meta_blacklisted(meta: &AlbumCardMeta, blacklist: &BlacklistState) -> bool {
+    meta.artist_id.is_some_and(|id| blacklist.is_blacklisted(id))
+        || blacklist.is_name_blacklisted(&meta.artist)
+}
+
+fn is_track_meta_blacklisted(meta: &TrackDisplayMeta, blacklist: &BlacklistState) -> bool {
+    meta.artist_id.is_some_and(|id| blacklist.is_blacklisted(id))
+        || blacklist.is_name_blacklisted(&meta.artist)
+}
+
+/// Drop home seeds by blacklisted artists
+///
+/// Album and track seeds are looked up in the reco meta cache to find their
+/// artist; seeds without meta are checked again once resolved.
+fn filter_blacklisted_seeds(
+    db: &crate::reco_store::db::RecoStoreDb,
+    seeds: &mut HomeSeeds,
+    blacklist: &BlacklistState,
+) -> Result<(), String> {
+    if !blacklist.is_enabled() || blacklist.count() == 0 {
+        return Ok(());
+    }
+
+    seeds
+        .top_artist_ids
+        .retain(|seed| !blacklist.is_blacklisted(seed.artist_id));
+
+    let album_ids: Vec<String> = seeds
+        .recently_played_album_ids
+        .iter()
+        .chain(&seeds.favorite_album_ids)
+        .cloned()
+        .collect();
+    let blocked_albums: HashSet<String> = db
+        .get_album_metas(&album_ids)?
+        .into_iter()
+        .filter(|meta| is_album_meta_blacklisted(meta, blacklist))
+        .map(|meta| meta.id)
+        .collect();
+    seeds
+        .recently_played_album_ids
+        .retain(|id| !blocked_albums.contains(id));
+    seeds
+        .favorite_album_ids
+        .retain(|id| !blocked_albums.contains(id));
+
+    let track_ids: Vec<u64> = seeds
+        .continue_listening_track_ids
+        .iter()
+        .chain(&seeds.favorite_track_ids)
+        .copied()
+        .collect();
+    let blocked_tracks: HashSet<u64> = db
+        .get_track_metas(&track_ids)?
+        .into_iter()
+        .filter(|meta| is_track_meta_blacklisted(meta, blacklist))
+        .map(|meta| meta.id)
+        .collect();
+    seeds
+        .continue_listening_track_ids
+        .retain(|id| !blocked_tracks.contains(id));
+    seeds
+        .favorite_track_ids
+        .retain(|id| !blocked_tracks.contains(id));
+
+    Ok(())
+}
+
+/// Drop resolved home items by blacklisted artists
+fn filter_blacklisted_resolved(home: &mut HomeResolved, blacklist: &BlacklistState) {
+    home.recently_played_albums
+        .retain(|album| !is_album_meta_blacklisted(album, blacklist));
+    home.favorite_albums
+        .retain(|album| !is_album_meta_blacklisted(album, blacklist));
+    home.continue_listening_tracks
+        .retain(|track| !is_track_meta_blacklisted(track, blacklist));
+    home.top_artists
+        .retain(|artist| !blacklist.is_blacklisted(artist.id));
+}
+
 /// Return fully-resolved home page data in a single IPC call.
 ///
 /// 3-tier resolution per entity type:
@@ -727,6 +837,7 @@ pub async fn reco_get_home_resolved(
     reco_state: State<'_, RecoState>,
     app_state: State<'_, AppState>,
     cache_state: State<'_, ApiCacheState>,
+    blacklist_state: State<'_, BlacklistState>,
 ) -> Result<HomeResolved, String> {
     let limit_recent_albums = limit_recent_albums.unwrap_or(12);
     let limit_continue_tracks = limit_continue_tracks.unwrap_or(10);
@@ -737,13 +848,15 @@ pub async fn reco_get_home_resolved(
     let seeds = {
         let guard__ = reco_state.db.lock().await;
         let db = guard__.as_ref().ok_or("No active session - please log in")?;
-        get_home_seeds_internal(
+        let mut seeds = get_home_seeds_internal(
             db,
             limit_recent_albums,
             limit_continue_tracks,
             limit_top_artists,
             limit_favorites,
-        )?
+        )?;
+        filter_blacklisted_seeds(db, &mut seeds, &blacklist_state)?;
+        seeds
     };
 
     // Step 2: Collect all unique IDs needed
@@ -812,12 +925,16 @@ pub async fn reco_get_home_resolved(
     let top_artists =
         resolve_artists(&all_artist_ids, &artist_play_counts, &reco_state, &app_state, &cache_state).await?;
 
-    Ok(HomeResolved {
+    // Step 7: Drop anything by a blacklisted artist that the seed filter
+    // couldn't see (items without reco meta yet)
+    let mut home = HomeResolved {
         recently_played_albums,
         continue_listening_tracks,
         top_artists,
         favorite_albums,
-    })
+    };
+    filter_blacklisted_resolved(&mut home, &blacklist_state);
+    Ok(home)
 }
 
 /// Resolve album IDs → AlbumCardMeta with 3-tier cache
@@ -1194,3 +1311,177 @@ async fn resolve_artists(
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reco_store::db::RecoStoreDb;
+    use crate::reco_store::{RecoEventType, RecoItemType, RecoSource};
+
+    const BLOCKED: u64 = 7;
+
+    fn blacklist(dir: &std::path::Path) -> BlacklistState {
+        let state = BlacklistState::new_empty();
+        state.init_at(dir).unwrap();
+        state.add(BLOCKED, "Blocked Artist", None).unwrap();
+        state
+    }
+
+    fn album(id: &str, artist_id: u64, artist: &str) -> AlbumCardMeta {
+        AlbumCardMeta {
+            id: id.to_string(),
+            artwork: String::new(),
+            title: id.to_string(),
+            artist: artist.to_string(),
+            artist_id: Some(artist_id),
+            genre: String::new(),
+            quality: String::new(),
+            release_date: None,
+        }
+    }
+
+    fn track(id: u64, artist_id: Option<u64>, artist: &str) -> TrackDisplayMeta {
+        TrackDisplayMeta {
+            id,
+            title: String::new(),
+            artist: artist.to_string(),
+            album: String::new(),
+            album_art: String::new(),
+            album_id: None,
+            artist_id,
+            duration: String::new(),
+            duration_seconds: 0,
+            hires: false,
+            bit_depth: None,
+            sampling_rate: None,
+            isrc: None,
+        }
+    }
+
+    #[test]
+    fn seeds_drop_blacklisted_artists() {
+        let dir = tempfile::tempdir().unwrap();
+        let blacklist = blacklist(dir.path());
+        let db = RecoStoreDb::new(&dir.path().join("reco.db")).unwrap();
+        db.set_album_meta(&album("blocked", BLOCKED, "Blocked Artist"))
+            .unwrap();
+        db.set_album_meta(&album("ok", 1, "Someone")).unwrap();
+        db.set_track_meta(&track(10, Some(BLOCKED), "Blocked Artist"))
+            .unwrap();
+        db.set_track_meta(&track(11, None, "Blocked Artist"))
+            .unwrap();
+        db.set_track_meta(&track(12, Some(1), "Someone")).unwrap();
+
+        let mut seeds = HomeSeeds {
+            recently_played_album_ids: vec!["blocked".into(), "ok".into(), "unknown".into()],
+            continue_listening_track_ids: vec![10, 11, 12],
+            top_artist_ids: vec![
+                TopArtistSeed {
+                    artist_id: BLOCKED,
+                    play_count: 9,
+                },
+                TopArtistSeed {
+                    artist_id: 1,
+                    play_count: 3,
+                },
+            ],
+            favorite_album_ids: vec!["blocked".into()],
+            favorite_track_ids: vec![10, 12],
+        };
+        filter_blacklisted_seeds(&db, &mut seeds, &blacklist).unwrap();
+
+        assert_eq!(seeds.recently_played_album_ids, vec!["ok", "unknown"]);
+        assert_eq!(seeds.continue_listening_track_ids, vec![12]);
+        assert_eq!(
+            seeds
+                .top_artist_ids
+                .iter()
+                .map(|s| s.artist_id)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert!(seeds.favorite_album_ids.is_empty());
+        assert_eq!(seeds.favorite_track_ids, vec![12]);
+    }
+
+    #[test]
+    fn local_plays_feed_stats_but_not_recommendations() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = RecoStoreDb::new(&dir.path().join("reco.db")).unwrap();
+        let play = |track_id: u64, source: RecoSource| RecoEventInput {
+            event_type: RecoEventType::Play,
+            item_type: RecoItemType::Track,
+            track_id: Some(track_id),
+            album_id: None,
+            artist_id: None,
+            playlist_id: None,
+            genre_id: None,
+            source,
+            duration_secs: Some(120),
+            title: Some(format!("Track {}", track_id)),
+            artist_name: Some("Someone".into()),
+        };
+        db.insert_event(&play(1, RecoSource::Qobuz)).unwrap();
+        db.insert_event(&play(2, RecoSource::Local)).unwrap();
+
+        assert_eq!(db.get_recent_track_ids(10).unwrap(), vec![1]);
+
+        let plays = db.get_plays_since(0).unwrap();
+        assert_eq!(plays.len(), 2);
+        assert!(plays
+            .iter()
+            .any(|p| p.source == "local" && p.duration_secs == Some(120)));
+    }
+
+    #[test]
+    fn resolved_home_drops_blacklisted_artists() {
+        let dir = tempfile::tempdir().unwrap();
+        let blacklist = blacklist(dir.path());
+
+        let mut home = HomeResolved {
+            recently_played_albums: vec![
+                album("blocked", BLOCKED, "Blocked Artist"),
+                album("ok", 1, "Someone"),
+            ],
+            continue_listening_tracks: vec![
+                track(10, Some(BLOCKED), "x"),
+                track(12, Some(1), "Someone"),
+            ],
+            top_artists: vec![
+                ArtistCardMeta {
+                    id: BLOCKED,
+                    name: "Blocked Artist".into(),
+                    image: None,
+                    play_count: None,
+                },
+                ArtistCardMeta {
+                    id: 1,
+                    name: "Someone".into(),
+                    image: None,
+                    play_count: None,
+                },
+            ],
+            favorite_albums: vec![album("by-name", 99, "BLOCKED ARTIST")],
+        };
+        filter_blacklisted_resolved(&mut home, &blacklist);
+
+        assert_eq!(home.recently_played_albums.len(), 1);
+        assert_eq!(home.recently_played_albums[0].id, "ok");
+        assert_eq!(home.continue_listening_tracks.len(), 1);
+        assert_eq!(home.continue_listening_tracks[0].id, 12);
+        assert_eq!(home.top_artists.len(), 1);
+        assert_eq!(home.top_artists[0].id, 1);
+        assert!(home.favorite_albums.is_empty());
+
+        // Nothing is filtered while the blacklist is disabled
+        blacklist.set_enabled(false).unwrap();
+        let mut home = HomeResolved {
+            recently_played_albums: vec![album("blocked", BLOCKED, "Blocked Artist")],
+            continue_listening_tracks: Vec::new(),
+            top_artists: Vec::new(),
+            favorite_albums: Vec::new(),
+        };
+        filter_blacklisted_resolved(&mut home, &blacklist);
+        assert_eq!(home.recently_played_albums.len(), 1);
+    }
+}
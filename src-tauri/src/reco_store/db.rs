@@ -19,6 +19,18 @@ pub struct RecoEventRecord {
     pub created_at: i64,
 }
 
+/// A play event with the details needed for listening stats
+#[derive(Debug, Clone)]
+pub struct PlayRecord {
+    pub source: String,
+    pub track_id: Option<u64>,
+    pub artist_id: Option<u64>,
+    pub title: Option<String>,
+    pub artist_name: Option<String>,
+    pub duration_secs: Option<u32>,
+    pub created_at: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct RecoScoreEntry {
     pub track_id: Option<u64>,
@@ -86,6 +98,7 @@ impl RecoStoreDb {
         // Migrations - run after base schema
         self.migrate_add_genre_id()?;
         self.migrate_add_meta_tables()?;
+        self.migrate_add_play_details()?;
 
         Ok(())
     }
@@ -118,6 +131,36 @@ impl RecoStoreDb {
         Ok(())
     }
 
+    /// Migration adding the per-play details used by listening stats.
+    ///
+    /// Events logged before this have no source and are all Qobuz plays.
+    fn migrate_add_play_details(&self) -> Result<(), String> {
+        let columns: Vec<String> = self
+            .conn
+            .prepare("PRAGMA table_info(reco_events)")
+            .map_err(|e| format!("Failed to query table info: {}", e))?
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(|e| format!("Failed to read table info: {}", e))?
+            .filter_map(Result::ok)
+            .collect();
+
+        if !columns.iter().any(|col| col == "source") {
+            log::info!("Migrating reco_events: adding play detail columns");
+            self.conn
+                .execute_batch(
+                    r#"
+                    ALTER TABLE reco_events ADD COLUMN source TEXT NOT NULL DEFAULT 'qobuz';
+                    ALTER TABLE reco_events ADD COLUMN duration_secs INTEGER;
+                    ALTER TABLE reco_events ADD COLUMN title TEXT;
+                    ALTER TABLE reco_events ADD COLUMN artist_name TEXT;
+                    "#,
+                )
+                .map_err(|e| format!("Failed to add play detail columns: {}", e))?;
+        }
+
+        Ok(())
+    }
+
     pub fn insert_event(&self, event: &RecoEventInput) -> Result<(), String> {
         let created_at = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -135,8 +178,12 @@ impl RecoStoreDb {
                     artist_id,
                     playlist_id,
                     genre_id,
+                    source,
+                    duration_secs,
+                    title,
+                    artist_name,
                     created_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
                 params![
                     event.event_type.as_str(),
@@ -146,6 +193,10 @@ impl RecoStoreDb {
                     event.artist_id,
                     event.playlist_id,
                     event.genre_id,
+                    event.source.as_str(),
+                    event.duration_secs,
+                    event.title.as_deref(),
+                    event.artist_name.as_deref(),
                     created_at,
                 ],
             )
@@ -161,7 +212,7 @@ impl RecoStoreDb {
                 r#"
                 SELECT album_id, MAX(created_at) AS last_played
                 FROM reco_events
-                WHERE event_type = 'play' AND album_id IS NOT NULL
+                WHERE event_type = 'play' AND source = 'qobuz' AND album_id IS NOT NULL
                 GROUP BY album_id
                 ORDER BY last_played DESC
                 LIMIT ?
@@ -187,7 +238,7 @@ impl RecoStoreDb {
                 r#"
                 SELECT track_id, MAX(created_at) AS last_played
                 FROM reco_events
-                WHERE event_type = 'play' AND track_id IS NOT NULL
+                WHERE event_type = 'play' AND source = 'qobuz' AND track_id IS NOT NULL
                 GROUP BY track_id
                 ORDER BY last_played DESC
                 LIMIT ?
@@ -206,6 +257,30 @@ impl RecoStoreDb {
         Ok(tracks)
     }
 
+    /// Distinct track IDs with a play event since `since_ts` (unix seconds)
+    pub fn get_played_track_ids_since(&self, since_ts: i64) -> Result<Vec<u64>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"
+                SELECT DISTINCT track_id
+                FROM reco_events
+                WHERE event_type = 'play' AND source = 'qobuz' AND track_id IS NOT NULL AND created_at >= ?
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare played tracks query: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![since_ts], |row| row.get::<_, u64>(0))
+            .map_err(|e| format!("Failed to query played tracks: {}", e))?;
+
+        let mut tracks = Vec::new();
+        for row in rows {
+            tracks.push(row.map_err(|e| format!("Failed to read played track row: {}", e))?);
+        }
+        Ok(tracks)
+    }
+
     pub fn get_events_since(
         &self,
         since_ts: i64,
@@ -219,7 +294,7 @@ impl RecoStoreDb {
                     r#"
                     SELECT event_type, item_type, track_id, album_id, artist_id, genre_id, created_at
                     FROM reco_events
-                    WHERE created_at >= ?
+                    WHERE created_at >= ? AND source = 'qobuz'
                     ORDER BY created_at DESC
                     LIMIT ?
                     "#,
@@ -249,7 +324,7 @@ impl RecoStoreDb {
                     r#"
                     SELECT event_type, item_type, track_id, album_id, artist_id, genre_id, created_at
                     FROM reco_events
-                    WHERE created_at >= ?
+                    WHERE created_at >= ? AND source = 'qobuz'
                     ORDER BY created_at DESC
                     "#,
                 )
@@ -277,6 +352,52 @@ impl RecoStoreDb {
         Ok(events)
     }
 
+    /// Play events since `since_ts` (unix seconds), oldest first.
+    ///
+    /// Qobuz plays logged before per-play details were recorded fall back to
+    /// the cached track metadata for title, artist and duration.
+    pub fn get_plays_since(&self, since_ts: i64) -> Result<Vec<PlayRecord>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"
+                SELECT e.source, e.track_id, e.artist_id,
+                       COALESCE(e.title, m.title),
+                       COALESCE(e.artist_name, NULLIF(m.artist_name, ''), a.name),
+                       COALESCE(e.duration_secs, m.duration_secs),
+                       e.created_at
+                FROM reco_events e
+                LEFT JOIN reco_track_meta m
+                    ON e.source = 'qobuz' AND m.track_id = e.track_id
+                LEFT JOIN reco_artist_meta a
+                    ON e.source = 'qobuz' AND a.artist_id = e.artist_id
+                WHERE e.event_type = 'play' AND e.created_at >= ?
+                ORDER BY e.created_at ASC
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare plays query: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![since_ts], |row| {
+                Ok(PlayRecord {
+                    source: row.get(0)?,
+                    track_id: row.get(1)?,
+                    artist_id: row.get(2)?,
+                    title: row.get(3)?,
+                    artist_name: row.get(4)?,
+                    duration_secs: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query plays: {}", e))?;
+
+        let mut plays = Vec::new();
+        for row in rows {
+            plays.push(row.map_err(|e| format!("Failed to read play row: {}", e))?);
+        }
+        Ok(plays)
+    }
+
     /// Get unique album_ids that have NULL genre_id (for backfill)
     pub fn get_album_ids_without_genre(&self, limit: u32) -> Result<Vec<String>, String> {
         let mut stmt = self
@@ -321,7 +442,7 @@ impl RecoStoreDb {
                 r#"
                 SELECT artist_id, COUNT(*) AS play_count, MAX(created_at) AS last_played
                 FROM reco_events
-                WHERE event_type = 'play' AND artist_id IS NOT NULL
+                WHERE event_type = 'play' AND source = 'qobuz' AND artist_id IS NOT NULL
                 GROUP BY artist_id
                 ORDER BY play_count DESC, last_played DESC
                 LIMIT ?
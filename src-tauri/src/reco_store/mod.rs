@@ -4,6 +4,7 @@
 
 pub mod commands;
 pub mod db;
+pub mod stats;
 
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -48,6 +49,36 @@ impl RecoItemType {
     }
 }
 
+/// Where a logged track was played from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoSource {
+    #[default]
+    Qobuz,
+    Local,
+    Plex,
+}
+
+impl RecoSource {
+    pub const ALL: [RecoSource; 3] = [Self::Qobuz, Self::Local, Self::Plex];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Qobuz => "qobuz",
+            Self::Local => "local",
+            Self::Plex => "plex",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "local" => Self::Local,
+            "plex" => Self::Plex,
+            _ => Self::Qobuz,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RecoEventInput {
@@ -58,6 +89,14 @@ pub struct RecoEventInput {
     pub artist_id: Option<u64>,
     pub playlist_id: Option<u64>,
     pub genre_id: Option<u64>,
+    #[serde(default)]
+    pub source: RecoSource,
+    /// Track length, summed into listening time
+    pub duration_secs: Option<u32>,
+    /// Track title and artist name at play time (local and Plex tracks have
+    /// no cached metadata to resolve them from)
+    pub title: Option<String>,
+    pub artist_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -0,0 +1,385 @@
+//! Listening stats computed from logged play events
+//!
+//! Listening time is the sum of the lengths of the tracks started in the
+//! period; plays are logged when a track starts, not when it finishes.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::reco_store::db::PlayRecord;
+use crate::reco_store::RecoSource;
+
+/// Number of entries returned in the top artists and top tracks lists
+pub const STATS_TOP_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatsPeriod {
+    #[serde(rename = "last_7_days")]
+    Last7Days,
+    #[serde(rename = "last_30_days")]
+    Last30Days,
+    #[serde(rename = "all_time")]
+    AllTime,
+}
+
+impl StatsPeriod {
+    /// First day of the period (inclusive), or None for all time
+    pub fn start_day(self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Last7Days => Some(today - Duration::days(6)),
+            Self::Last30Days => Some(today - Duration::days(29)),
+            Self::AllTime => None,
+        }
+    }
+
+    /// Unix timestamp of local midnight at the start of the period
+    pub fn since_ts(self, today: NaiveDate) -> i64 {
+        self.start_day(today)
+            .and_then(|day| day.and_hms_opt(0, 0, 0))
+            .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+            .map(|start| start.timestamp())
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsArtist {
+    /// Qobuz artist ID; None for local and Plex artists
+    pub artist_id: Option<u64>,
+    pub name: Option<String>,
+    pub play_count: u32,
+    pub listening_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsTrack {
+    pub track_id: Option<u64>,
+    pub source: RecoSource,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub play_count: u32,
+    pub listening_secs: u64,
+}
+
+/// Listens on one calendar day (YYYY-MM-DD, local time)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyListens {
+    pub date: String,
+    pub plays: u32,
+    pub listening_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceListens {
+    pub source: RecoSource,
+    pub plays: u32,
+    pub listening_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningStats {
+    pub period: StatsPeriod,
+    pub total_plays: u32,
+    pub total_listening_secs: u64,
+    pub top_artists: Vec<StatsArtist>,
+    pub top_tracks: Vec<StatsTrack>,
+    /// One entry per day of the period, oldest first, including empty days
+    pub daily: Vec<DailyListens>,
+    /// One entry per source, always in qobuz/local/plex order
+    pub by_source: Vec<SourceListens>,
+}
+
+#[derive(Default)]
+struct Tally {
+    plays: u32,
+    secs: u64,
+    last_played: i64,
+}
+
+impl Tally {
+    fn add(&mut self, secs: u64, created_at: i64) {
+        self.plays += 1;
+        self.secs += secs;
+        self.last_played = self.last_played.max(created_at);
+    }
+}
+
+/// Aggregate play events into stats for `period`.
+///
+/// `to_day` maps an event timestamp to its calendar day.
+pub fn compute_stats(
+    plays: &[PlayRecord],
+    period: StatsPeriod,
+    today: NaiveDate,
+    to_day: impl Fn(i64) -> Option<NaiveDate>,
+) -> ListeningStats {
+    let mut total = Tally::default();
+    let mut artists: HashMap<String, (Tally, Option<u64>, Option<String>)> = HashMap::new();
+    let mut tracks: HashMap<String, (Tally, StatsTrack)> = HashMap::new();
+    let mut days: HashMap<NaiveDate, Tally> = HashMap::new();
+    let mut sources: HashMap<RecoSource, Tally> = HashMap::new();
+    let mut first_day: Option<NaiveDate> = None;
+
+    for play in plays {
+        let secs = play.duration_secs.unwrap_or(0) as u64;
+        let source = RecoSource::from_db(&play.source);
+        let artist_name = play.artist_name.as_deref().filter(|name| !name.is_empty());
+        let title = play.title.as_deref().filter(|title| !title.is_empty());
+
+        total.add(secs, play.created_at);
+        sources
+            .entry(source)
+            .or_default()
+            .add(secs, play.created_at);
+
+        if let Some(day) = to_day(play.created_at) {
+            days.entry(day).or_default().add(secs, play.created_at);
+            first_day = Some(first_day.map_or(day, |first| first.min(day)));
+        }
+
+        // Qobuz artists are keyed by ID, local and Plex ones by name
+        let artist_id = play.artist_id.filter(|_| source == RecoSource::Qobuz);
+        let artist_key = match (artist_id, artist_name) {
+            (Some(id), _) => Some(format!("id:{}", id)),
+            (None, Some(name)) => Some(format!("name:{}", name.to_lowercase())),
+            (None, None) => None,
+        };
+        if let Some(key) = artist_key {
+            let entry = artists
+                .entry(key)
+                .or_insert_with(|| (Tally::default(), artist_id, None));
+            entry.0.add(secs, play.created_at);
+            if let Some(name) = artist_name {
+                entry.2 = Some(name.to_string());
+            }
+        }
+
+        let track_key = match (play.track_id, title) {
+            (Some(id), _) => Some(format!("{}:id:{}", source.as_str(), id)),
+            (None, Some(title)) => Some(format!(
+                "{}:name:{}:{}",
+                source.as_str(),
+                title.to_lowercase(),
+                artist_name.unwrap_or_default().to_lowercase()
+            )),
+            (None, None) => None,
+        };
+        if let Some(key) = track_key {
+            let entry = tracks.entry(key).or_insert_with(|| {
+                (
+                    Tally::default(),
+                    StatsTrack {
+                        track_id: play.track_id,
+                        source,
+                        title: None,
+                        artist: None,
+                        play_count: 0,
+                        listening_secs: 0,
+                    },
+                )
+            });
+            entry.0.add(secs, play.created_at);
+            if let Some(title) = title {
+                entry.1.title = Some(title.to_string());
+            }
+            if let Some(name) = artist_name {
+                entry.1.artist = Some(name.to_string());
+            }
+        }
+    }
+
+    let mut top_artists: Vec<(Tally, StatsArtist)> = artists
+        .into_values()
+        .map(|(tally, artist_id, name)| {
+            let artist = StatsArtist {
+                artist_id,
+                name,
+                play_count: tally.plays,
+                listening_secs: tally.secs,
+            };
+            (tally, artist)
+        })
+        .collect();
+    top_artists.sort_by(|a, b| {
+        b.0.plays
+            .cmp(&a.0.plays)
+            .then(b.0.last_played.cmp(&a.0.last_played))
+    });
+
+    let mut top_tracks: Vec<(Tally, StatsTrack)> = tracks
+        .into_values()
+        .map(|(tally, mut track)| {
+            track.play_count = tally.plays;
+            track.listening_secs = tally.secs;
+            (tally, track)
+        })
+        .collect();
+    top_tracks.sort_by(|a, b| {
+        b.0.plays
+            .cmp(&a.0.plays)
+            .then(b.0.last_played.cmp(&a.0.last_played))
+    });
+
+    // Zero-filled so the days line up on a chart
+    let start = period
+        .start_day(today)
+        .or(first_day)
+        .unwrap_or(today)
+        .min(today);
+    let daily = start
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .map(|day| {
+            let tally = days.remove(&day).unwrap_or_default();
+            DailyListens {
+                date: day.format("%Y-%m-%d").to_string(),
+                plays: tally.plays,
+                listening_secs: tally.secs,
+            }
+        })
+        .collect();
+
+    let by_source = RecoSource::ALL
+        .iter()
+        .map(|source| {
+            let tally = sources.remove(source).unwrap_or_default();
+            SourceListens {
+                source: *source,
+                plays: tally.plays,
+                listening_secs: tally.secs,
+            }
+        })
+        .collect();
+
+    ListeningStats {
+        period,
+        total_plays: total.plays,
+        total_listening_secs: total.secs,
+        top_artists: top_artists
+            .into_iter()
+            .take(STATS_TOP_LIMIT)
+            .map(|(_, artist)| artist)
+            .collect(),
+        top_tracks: top_tracks
+            .into_iter()
+            .take(STATS_TOP_LIMIT)
+            .map(|(_, track)| track)
+            .collect(),
+        daily,
+        by_source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    const DAY: i64 = 86_400;
+
+    fn utc_day(ts: i64) -> Option<NaiveDate> {
+        DateTime::<Utc>::from_timestamp(ts, 0).map(|dt| dt.date_naive())
+    }
+
+    fn play(
+        source: &str,
+        track_id: Option<u64>,
+        artist_id: Option<u64>,
+        title: &str,
+        artist: &str,
+        created_at: i64,
+    ) -> PlayRecord {
+        PlayRecord {
+            source: source.to_string(),
+            track_id,
+            artist_id,
+            title: Some(title.to_string()),
+            artist_name: Some(artist.to_string()),
+            duration_secs: Some(200),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn stats_group_by_artist_track_day_and_source() {
+        // 2024-01-10 00:00 UTC
+        let base = 1_704_844_800;
+        let today = utc_day(base + 2 * DAY).unwrap();
+        let plays = vec![
+            play("qobuz", Some(1), Some(10), "Song", "Band", base),
+            play("qobuz", Some(1), Some(10), "Song", "Band", base + 60),
+            play("qobuz", Some(2), Some(10), "Other", "Band", base + DAY),
+            play("local", None, None, "Demo", "Garage Act", base + 2 * DAY),
+            play(
+                "plex",
+                Some(1),
+                None,
+                "Live",
+                "garage act",
+                base + 2 * DAY + 5,
+            ),
+        ];
+
+        let stats = compute_stats(&plays, StatsPeriod::Last7Days, today, utc_day);
+
+        assert_eq!(stats.total_plays, 5);
+        assert_eq!(stats.total_listening_secs, 1000);
+
+        // Qobuz artist by ID, local and Plex merged by name
+        assert_eq!(stats.top_artists.len(), 2);
+        assert_eq!(stats.top_artists[0].artist_id, Some(10));
+        assert_eq!(stats.top_artists[0].play_count, 3);
+        assert_eq!(stats.top_artists[1].artist_id, None);
+        assert_eq!(stats.top_artists[1].play_count, 2);
+
+        // Same track ID from different sources is not the same track
+        assert_eq!(stats.top_tracks.len(), 4);
+        assert_eq!(stats.top_tracks[0].track_id, Some(1));
+        assert_eq!(stats.top_tracks[0].source, RecoSource::Qobuz);
+        assert_eq!(stats.top_tracks[0].play_count, 2);
+
+        assert_eq!(stats.daily.len(), 7);
+        assert_eq!(stats.daily[0].date, "2024-01-06");
+        assert_eq!(stats.daily[6].date, "2024-01-12");
+        let counts: Vec<u32> = stats.daily.iter().map(|d| d.plays).collect();
+        assert_eq!(counts, vec![0, 0, 0, 0, 2, 1, 2]);
+
+        let by_source: Vec<(RecoSource, u32)> = stats
+            .by_source
+            .iter()
+            .map(|s| (s.source, s.plays))
+            .collect();
+        assert_eq!(
+            by_source,
+            vec![
+                (RecoSource::Qobuz, 3),
+                (RecoSource::Local, 1),
+                (RecoSource::Plex, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn all_time_days_start_at_first_play() {
+        let base = 1_704_844_800;
+        let today = utc_day(base + 3 * DAY).unwrap();
+        let plays = vec![play("qobuz", Some(1), Some(10), "Song", "Band", base)];
+
+        let stats = compute_stats(&plays, StatsPeriod::AllTime, today, utc_day);
+        assert_eq!(stats.daily.len(), 4);
+        assert_eq!(stats.daily[0].plays, 1);
+
+        let empty = compute_stats(&[], StatsPeriod::AllTime, today, utc_day);
+        assert_eq!(empty.daily.len(), 1);
+        assert_eq!(empty.total_plays, 0);
+        assert!(empty.top_artists.is_empty());
+        assert_eq!(empty.by_source.len(), 3);
+    }
+}
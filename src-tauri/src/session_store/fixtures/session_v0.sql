@@ -0,0 +1,30 @@
+-- Session database as written by releases before the queue layout was
+-- versioned: no PRAGMA user_version and only the original queue columns.
+CREATE TABLE player_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    current_index INTEGER,
+    current_position_secs INTEGER NOT NULL DEFAULT 0,
+    volume REAL NOT NULL DEFAULT 0.75,
+    shuffle_enabled INTEGER NOT NULL DEFAULT 0,
+    repeat_mode TEXT NOT NULL DEFAULT 'off',
+    was_playing INTEGER NOT NULL DEFAULT 0,
+    saved_at INTEGER NOT NULL
+);
+
+CREATE TABLE queue_tracks (
+    position INTEGER PRIMARY KEY,
+    track_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    artist TEXT NOT NULL,
+    album TEXT NOT NULL,
+    duration_secs INTEGER NOT NULL,
+    artwork_url TEXT
+);
+
+INSERT INTO player_state (id, current_index, current_position_secs, volume, shuffle_enabled, repeat_mode, was_playing, saved_at)
+VALUES (1, 1, 83, 0.5, 1, 'all', 1, 1700000000);
+
+INSERT INTO queue_tracks (position, track_id, title, artist, album, duration_secs, artwork_url)
+VALUES
+    (0, 52197581, 'So What', 'Miles Davis', 'Kind of Blue', 562, 'https://static.qobuz.com/images/covers/kob.jpg'),
+    (1, 52197582, 'Freddie Freeloader', 'Miles Davis', 'Kind of Blue', 586, NULL);
@@ -2,7 +2,15 @@ use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::migration::{migrate_session_db, SESSION_SCHEMA_VERSION};
+use crate::queue::{QueueTrack, RepeatMode};
+use crate::AppState;
+
+/// How often the playback position is checkpointed while playing
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(10);
 
 fn default_streamable() -> bool {
     true
@@ -29,13 +37,23 @@ pub struct PersistedQueueTrack {
     pub streamable: bool,
     #[serde(default)]
     pub source: Option<String>,
+    #[serde(default)]
+    pub track_number: Option<u32>,
+    #[serde(default)]
+    pub disc_number: Option<u32>,
 }
 
 /// Represents the full persisted session state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistedSession {
+    /// Layout version the session was stored with (see `migration`)
+    #[serde(default)]
+    pub version: u32,
     pub queue_tracks: Vec<PersistedQueueTrack>,
     pub current_index: Option<usize>,
+    /// Track playing at the last checkpoint; outlives queue reordering
+    #[serde(default)]
+    pub current_track_id: Option<u64>,
     pub current_position_secs: u64,
     pub volume: f32,
     pub shuffle_enabled: bool,
@@ -47,8 +65,10 @@ pub struct PersistedSession {
 impl Default for PersistedSession {
     fn default() -> Self {
         Self {
+            version: SESSION_SCHEMA_VERSION,
             queue_tracks: Vec::new(),
             current_index: None,
+            current_track_id: None,
             current_position_secs: 0,
             volume: 0.75,
             shuffle_enabled: false,
@@ -88,12 +108,25 @@ impl SessionStore {
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
             .map_err(|e| format!("Failed to set WAL mode: {}", e))?;
 
-        // Create tables
+        if let Err(e) = Self::create_and_migrate(&conn) {
+            // An unreadable session is not worth failing login over
+            log::error!("Session database is incompatible, starting with an empty session: {}", e);
+            conn.execute_batch("DROP TABLE IF EXISTS queue_tracks; DROP TABLE IF EXISTS player_state; PRAGMA user_version = 0;")
+                .map_err(|e| format!("Failed to reset session database: {}", e))?;
+            Self::create_and_migrate(&conn)?;
+        }
+
+        Ok(Self { conn })
+    }
+
+    /// Create the tables in the current layout, or upgrade older ones
+    fn create_and_migrate(conn: &Connection) -> Result<(), String> {
         conn.execute_batch(
             "
             CREATE TABLE IF NOT EXISTS player_state (
                 id INTEGER PRIMARY KEY CHECK (id = 1),
                 current_index INTEGER,
+                current_track_id INTEGER,
                 current_position_secs INTEGER NOT NULL DEFAULT 0,
                 volume REAL NOT NULL DEFAULT 0.75,
                 shuffle_enabled INTEGER NOT NULL DEFAULT 0,
@@ -113,7 +146,13 @@ impl SessionStore {
                 hires INTEGER NOT NULL DEFAULT 0,
                 bit_depth INTEGER,
                 sample_rate REAL,
-                source TEXT
+                is_local INTEGER NOT NULL DEFAULT 0,
+                album_id TEXT,
+                artist_id INTEGER,
+                source TEXT,
+                streamable INTEGER NOT NULL DEFAULT 1,
+                track_number INTEGER,
+                disc_number INTEGER
             );
 
             -- Insert default row if not exists
@@ -122,65 +161,9 @@ impl SessionStore {
             "
         ).map_err(|e| format!("Failed to create session tables: {}", e))?;
 
-        // Migrate existing queue_tracks table to add new columns if they don't exist
-        // SQLite doesn't support IF NOT EXISTS for ALTER TABLE, so we check the schema
-        let has_hires: bool = conn
-            .query_row(
-                "SELECT COUNT(*) FROM pragma_table_info('queue_tracks') WHERE name = 'hires'",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0)
-            > 0;
-
-        if !has_hires {
-            let _ = conn.execute_batch(
-                "
-                ALTER TABLE queue_tracks ADD COLUMN hires INTEGER NOT NULL DEFAULT 0;
-                ALTER TABLE queue_tracks ADD COLUMN bit_depth INTEGER;
-                ALTER TABLE queue_tracks ADD COLUMN sample_rate REAL;
-                ",
-            );
-        }
-
-        // Add is_local, album_id, artist_id columns if they don't exist
-        let has_is_local: bool = conn
-            .query_row(
-                "SELECT COUNT(*) FROM pragma_table_info('queue_tracks') WHERE name = 'is_local'",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0)
-            > 0;
-
-        if !has_is_local {
-            let _ = conn.execute_batch(
-                "
-                ALTER TABLE queue_tracks ADD COLUMN is_local INTEGER NOT NULL DEFAULT 0;
-                ALTER TABLE queue_tracks ADD COLUMN album_id TEXT;
-                ALTER TABLE queue_tracks ADD COLUMN artist_id INTEGER;
-                ",
-            );
-        }
-
-        let has_source: bool = conn
-            .query_row(
-                "SELECT COUNT(*) FROM pragma_table_info('queue_tracks') WHERE name = 'source'",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0)
-            > 0;
-
-        if !has_source {
-            let _ = conn.execute_batch(
-                "
-                ALTER TABLE queue_tracks ADD COLUMN source TEXT;
-                ",
-            );
-        }
-
-        Ok(Self { conn })
+        // Tables from older releases keep their layout above; add what they lack
+        migrate_session_db(conn)?;
+        Ok(())
     }
 
     /// Save the complete session state
@@ -204,8 +187,8 @@ impl SessionStore {
         // Insert queue tracks
         for (pos, track) in session.queue_tracks.iter().enumerate() {
             if let Err(e) = self.conn.execute(
-                "INSERT INTO queue_tracks (position, track_id, title, artist, album, duration_secs, artwork_url, hires, bit_depth, sample_rate, is_local, album_id, artist_id, source)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                "INSERT INTO queue_tracks (position, track_id, title, artist, album, duration_secs, artwork_url, hires, bit_depth, sample_rate, is_local, album_id, artist_id, source, streamable, track_number, disc_number)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
                 params![
                     pos as i64,
                     track.id as i64,
@@ -221,6 +204,9 @@ impl SessionStore {
                     track.album_id,
                     track.artist_id.map(|v| v as i64),
                     track.source,
+                    track.streamable as i64,
+                    track.track_number,
+                    track.disc_number,
                 ],
             ) {
                 let _ = self.conn.execute("ROLLBACK", []);
@@ -237,7 +223,8 @@ impl SessionStore {
                 shuffle_enabled = ?4,
                 repeat_mode = ?5,
                 was_playing = ?6,
-                saved_at = ?7
+                saved_at = ?7,
+                current_track_id = ?8
              WHERE id = 1",
             params![
                 session.current_index.map(|i| i as i64),
@@ -247,6 +234,15 @@ impl SessionStore {
                 session.repeat_mode,
                 session.was_playing as i64,
                 now,
+                session
+                    .current_track_id
+                    .or_else(|| {
+                        session
+                            .current_index
+                            .and_then(|i| session.queue_tracks.get(i))
+                            .map(|t| t.id)
+                    })
+                    .map(|id| id as i64),
             ],
         ) {
             let _ = self.conn.execute("ROLLBACK", []);
@@ -263,10 +259,10 @@ impl SessionStore {
     /// Load the persisted session state
     pub fn load_session(&self) -> Result<PersistedSession, String> {
         // Load player state
-        let (current_index, current_position_secs, volume, shuffle_enabled, repeat_mode, was_playing, saved_at):
-            (Option<i64>, i64, f64, i64, String, i64, i64) = self.conn
+        let (current_index, current_position_secs, volume, shuffle_enabled, repeat_mode, was_playing, saved_at, current_track_id):
+            (Option<i64>, i64, f64, i64, String, i64, i64, Option<i64>) = self.conn
             .query_row(
-                "SELECT current_index, current_position_secs, volume, shuffle_enabled, repeat_mode, was_playing, saved_at
+                "SELECT current_index, current_position_secs, volume, shuffle_enabled, repeat_mode, was_playing, saved_at, current_track_id
                  FROM player_state WHERE id = 1",
                 [],
                 |row| Ok((
@@ -277,13 +273,14 @@ impl SessionStore {
                     row.get(4)?,
                     row.get(5)?,
                     row.get(6)?,
+                    row.get(7)?,
                 )),
             )
             .map_err(|e| format!("Failed to load player state: {}", e))?;
 
         // Load queue tracks
         let mut stmt = self.conn
-            .prepare("SELECT track_id, title, artist, album, duration_secs, artwork_url, hires, bit_depth, sample_rate, is_local, album_id, artist_id, source FROM queue_tracks ORDER BY position")
+            .prepare("SELECT track_id, title, artist, album, duration_secs, artwork_url, hires, bit_depth, sample_rate, is_local, album_id, artist_id, source, streamable, track_number, disc_number FROM queue_tracks ORDER BY position")
             .map_err(|e| format!("Failed to prepare queue query: {}", e))?;
 
         let tracks: Vec<PersistedQueueTrack> = stmt
@@ -301,17 +298,26 @@ impl SessionStore {
                     is_local: row.get::<_, i64>(9).unwrap_or(0) != 0,
                     album_id: row.get(10)?,
                     artist_id: row.get::<_, Option<i64>>(11)?.map(|v| v as u64),
-                    streamable: true, // Default to true for persisted tracks
                     source: row.get(12)?,
+                    streamable: row.get::<_, i64>(13).unwrap_or(1) != 0,
+                    track_number: row.get(14)?,
+                    disc_number: row.get(15)?,
                 })
             })
             .map_err(|e| format!("Failed to query queue tracks: {}", e))?
-            .filter_map(|r| r.ok())
+            .filter_map(|r| r.map_err(|e| log::warn!("Skipping unreadable queue track: {}", e)).ok())
             .collect();
 
+        let version = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap_or(SESSION_SCHEMA_VERSION);
+
         Ok(PersistedSession {
+            version,
             queue_tracks: tracks,
             current_index: current_index.map(|i| i as usize),
+            current_track_id: current_track_id.map(|id| id as u64),
             current_position_secs: current_position_secs as u64,
             volume: volume as f32,
             shuffle_enabled: shuffle_enabled != 0,
@@ -338,6 +344,44 @@ impl SessionStore {
         Ok(())
     }
 
+    /// Record where playback is without rewriting the queue
+    ///
+    /// A single UPDATE, so a crash mid-write leaves either the previous
+    /// checkpoint or this one, never a mix of both.
+    pub fn save_checkpoint(
+        &self,
+        current_index: Option<usize>,
+        current_track_id: Option<u64>,
+        position_secs: u64,
+        was_playing: bool,
+    ) -> Result<(), String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        self.conn
+            .execute(
+                "UPDATE player_state SET
+                    current_index = ?1,
+                    current_track_id = ?2,
+                    current_position_secs = ?3,
+                    was_playing = ?4,
+                    saved_at = ?5
+                 WHERE id = 1",
+                params![
+                    current_index.map(|i| i as i64),
+                    current_track_id.map(|id| id as i64),
+                    position_secs as i64,
+                    was_playing as i64,
+                    now,
+                ],
+            )
+            .map_err(|e| format!("Failed to save checkpoint: {}", e))?;
+
+        Ok(())
+    }
+
     /// Quick save of volume
     pub fn save_volume(&self, volume: f32) -> Result<(), String> {
         self.conn
@@ -377,6 +421,95 @@ impl SessionStore {
     }
 }
 
+impl PersistedSession {
+    /// Queue index to resume at
+    ///
+    /// The checkpoint and the queue are written separately, so the stored
+    /// index can be stale after the queue was reordered; the track id wins
+    /// when it is still in the queue.
+    pub fn resume_index(&self) -> Option<usize> {
+        if let Some(track_id) = self.current_track_id {
+            let by_id = self.queue_tracks.iter().position(|t| t.id == track_id);
+            if by_id.is_some() {
+                return by_id;
+            }
+        }
+        self.current_index.filter(|&i| i < self.queue_tracks.len())
+    }
+}
+
+/// Where playback is, as written by the autosave
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub current_index: Option<usize>,
+    pub current_track_id: Option<u64>,
+    pub position_secs: u64,
+    pub was_playing: bool,
+}
+
+/// Decides which autosave ticks write a checkpoint
+///
+/// Ticks while playing always write (the position moved); a paused or
+/// stopped player is written once, so the last position before the pause
+/// is kept without rewriting the same row every interval.
+#[derive(Debug, Default)]
+pub struct AutosaveTracker {
+    last_saved: Option<Checkpoint>,
+}
+
+impl AutosaveTracker {
+    pub fn should_save(&self, checkpoint: &Checkpoint) -> bool {
+        checkpoint.current_track_id.is_some()
+            && (checkpoint.was_playing || self.last_saved.as_ref() != Some(checkpoint))
+    }
+
+    pub fn saved(&mut self, checkpoint: Checkpoint) {
+        self.last_saved = Some(checkpoint);
+    }
+}
+
+/// Checkpoint the playback position every `AUTOSAVE_INTERVAL`
+///
+/// Session saves from the frontend are event driven (track change, close),
+/// so a crash used to lose everything played since the last event.
+pub fn start_autosave(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut tracker = AutosaveTracker::default();
+        loop {
+            tokio::time::sleep(AUTOSAVE_INTERVAL).await;
+
+            let app_state = app_handle.state::<AppState>();
+            let checkpoint = Checkpoint {
+                current_index: app_state.queue.current_index(),
+                current_track_id: app_state.queue.current_track().map(|t| t.id),
+                position_secs: app_state.player.state.current_position(),
+                was_playing: app_state.player.state.is_playing(),
+            };
+            if !tracker.should_save(&checkpoint) {
+                continue;
+            }
+
+            let session_state = app_handle.state::<SessionStoreState>();
+            let Ok(guard) = session_state.store.lock() else {
+                continue;
+            };
+            // Logged out: nothing to save into
+            let Some(store) = guard.as_ref() else {
+                continue;
+            };
+            match store.save_checkpoint(
+                checkpoint.current_index,
+                checkpoint.current_track_id,
+                checkpoint.position_secs,
+                checkpoint.was_playing,
+            ) {
+                Ok(()) => tracker.saved(checkpoint),
+                Err(e) => log::warn!("Session autosave failed: {}", e),
+            }
+        }
+    });
+}
+
 /// Thread-safe wrapper for SessionStore
 pub struct SessionStoreState {
     pub store: Arc<Mutex<Option<SessionStore>>>,
@@ -427,8 +560,10 @@ pub fn save_session_state(
     was_playing: bool,
 ) -> Result<(), String> {
     let session = PersistedSession {
+        version: SESSION_SCHEMA_VERSION,
         queue_tracks,
         current_index,
+        current_track_id: None, // Taken from the queue in save_session
         current_position_secs,
         volume,
         shuffle_enabled,
@@ -454,7 +589,11 @@ pub fn load_session_state(
         .lock()
         .map_err(|e| format!("Lock error: {}", e))?;
     let store = guard.as_ref().ok_or("No active session - please log in")?;
-    store.load_session()
+    // A session that can't be read is dropped, not fatal
+    Ok(store.load_session().unwrap_or_else(|e| {
+        log::warn!("Saved session could not be loaded, starting empty: {}", e);
+        PersistedSession::default()
+    }))
 }
 
 #[tauri::command]
@@ -497,6 +636,92 @@ pub fn save_session_playback_mode(
     store.save_playback_mode(shuffle, &repeat_mode)
 }
 
+/// Playback state restored by `resume_last_session`
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumedSession {
+    pub track: QueueTrack,
+    pub queue_index: usize,
+    pub position_secs: u64,
+    pub volume: f32,
+    pub shuffle_enabled: bool,
+    pub repeat_mode: String,
+}
+
+/// Restore the last saved queue, current track and modes without playing
+///
+/// The frontend loads `track` paused and seeks to `position_secs` once the
+/// user presses play. Returns None when there is nothing to resume.
+#[tauri::command]
+pub fn resume_last_session(
+    state: tauri::State<'_, SessionStoreState>,
+    app_state: tauri::State<'_, AppState>,
+) -> Result<Option<ResumedSession>, String> {
+    let session = {
+        let guard = state
+            .store
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let store = guard.as_ref().ok_or("No active session - please log in")?;
+        store.load_session()?
+    };
+    let Some(queue_index) = session.resume_index() else {
+        return Ok(None);
+    };
+
+    let tracks: Vec<QueueTrack> = session
+        .queue_tracks
+        .iter()
+        .cloned()
+        .map(|t| QueueTrack {
+            id: t.id,
+            title: t.title,
+            artist: t.artist,
+            album: t.album,
+            duration_secs: t.duration_secs,
+            artwork_url: t.artwork_url,
+            hires: t.hires,
+            bit_depth: t.bit_depth,
+            sample_rate: t.sample_rate,
+            is_local: t.is_local,
+            album_id: t.album_id,
+            artist_id: t.artist_id,
+            streamable: t.streamable,
+            source: t.source,
+            track_number: t.track_number,
+            disc_number: t.disc_number,
+            contributor: None,
+        })
+        .collect();
+    let track = tracks[queue_index].clone();
+
+    let repeat_mode = match session.repeat_mode.as_str() {
+        "all" => RepeatMode::All,
+        "one" => RepeatMode::One,
+        _ => RepeatMode::Off,
+    };
+    app_state.queue.set_queue(tracks, Some(queue_index));
+    app_state.queue.set_shuffle(session.shuffle_enabled);
+    app_state.queue.set_repeat(repeat_mode);
+    if let Err(e) = app_state.player.set_volume(session.volume) {
+        log::warn!("Failed to restore volume: {}", e);
+    }
+
+    log::info!(
+        "Resumed session at queue index {} ({}s into track {})",
+        queue_index,
+        session.current_position_secs,
+        track.id
+    );
+    Ok(Some(ResumedSession {
+        track,
+        queue_index,
+        position_secs: session.current_position_secs,
+        volume: session.volume,
+        shuffle_enabled: session.shuffle_enabled,
+        repeat_mode: session.repeat_mode,
+    }))
+}
+
 #[tauri::command]
 pub fn clear_session(state: tauri::State<'_, SessionStoreState>) -> Result<(), String> {
     let guard = state
@@ -506,3 +731,162 @@ pub fn clear_session(state: tauri::State<'_, SessionStoreState>) -> Result<(), S
     let store = guard.as_ref().ok_or("No active session - please log in")?;
     store.clear_session()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v0_session_upgrades_to_current_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let conn = Connection::open(dir.path().join("session.db")).unwrap();
+            conn.execute_batch(include_str!("fixtures/session_v0.sql"))
+                .unwrap();
+        }
+
+        let store = SessionStore::new_at(dir.path()).unwrap();
+        let session = store.load_session().unwrap();
+
+        assert_eq!(session.version, SESSION_SCHEMA_VERSION);
+        assert_eq!(session.current_index, Some(1));
+        assert_eq!(session.current_position_secs, 83);
+        assert!(session.shuffle_enabled);
+        assert_eq!(session.repeat_mode, "all");
+        assert_eq!(session.queue_tracks.len(), 2);
+
+        let track = &session.queue_tracks[0];
+        assert_eq!(track.id, 52197581);
+        assert_eq!(track.title, "So What");
+        assert_eq!(track.duration_secs, 562);
+        assert!(!track.hires);
+        assert!(!track.is_local);
+        assert!(track.streamable);
+        assert_eq!(track.source, None);
+        assert_eq!(track.track_number, None);
+        assert_eq!(session.queue_tracks[1].artwork_url, None);
+
+        let version: u32 = store
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, SESSION_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_upgraded_session_round_trips_new_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let conn = Connection::open(dir.path().join("session.db")).unwrap();
+            conn.execute_batch(include_str!("fixtures/session_v0.sql"))
+                .unwrap();
+        }
+        let store = SessionStore::new_at(dir.path()).unwrap();
+
+        let mut session = store.load_session().unwrap();
+        session.queue_tracks[0].streamable = false;
+        session.queue_tracks[0].track_number = Some(1);
+        session.queue_tracks[0].disc_number = Some(1);
+        session.queue_tracks[0].source = Some("qobuz".to_string());
+        store.save_session(&session).unwrap();
+
+        // Reopening must not migrate (or reset) again
+        drop(store);
+        let store = SessionStore::new_at(dir.path()).unwrap();
+        let track = &store.load_session().unwrap().queue_tracks[0];
+        assert!(!track.streamable);
+        assert_eq!(track.track_number, Some(1));
+        assert_eq!(track.disc_number, Some(1));
+        assert_eq!(track.source.as_deref(), Some("qobuz"));
+    }
+
+    fn queue_track(id: u64) -> PersistedQueueTrack {
+        PersistedQueueTrack {
+            id,
+            title: format!("Track {}", id),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            duration_secs: 300,
+            artwork_url: None,
+            hires: false,
+            bit_depth: None,
+            sample_rate: None,
+            is_local: false,
+            album_id: None,
+            artist_id: None,
+            streamable: true,
+            source: None,
+            track_number: None,
+            disc_number: None,
+        }
+    }
+
+    #[test]
+    fn test_interleaved_checkpoints_restore_latest_position() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::new_at(dir.path()).unwrap();
+
+        let mut session = PersistedSession {
+            queue_tracks: vec![queue_track(10), queue_track(20), queue_track(30)],
+            current_index: Some(0),
+            ..Default::default()
+        };
+        store.save_session(&session).unwrap();
+        store.save_checkpoint(Some(1), Some(20), 42, true).unwrap();
+
+        // A full save from the frontend (queue reordered: 20 is now last)
+        // lands between two autosave ticks with its own, older position
+        session.queue_tracks = vec![queue_track(10), queue_track(30), queue_track(20)];
+        session.current_index = Some(2);
+        session.current_position_secs = 40;
+        session.was_playing = true;
+        store.save_session(&session).unwrap();
+        store.save_checkpoint(Some(2), Some(20), 52, true).unwrap();
+        store.save_volume(0.5).unwrap();
+
+        // Crash: reopen and restore
+        drop(store);
+        let store = SessionStore::new_at(dir.path()).unwrap();
+        let restored = store.load_session().unwrap();
+        assert_eq!(restored.queue_tracks.len(), 3);
+        assert_eq!(restored.current_track_id, Some(20));
+        assert_eq!(restored.current_position_secs, 52);
+        assert_eq!(restored.resume_index(), Some(2));
+        assert_eq!(restored.volume, 0.5);
+        assert!(restored.was_playing);
+
+        // A stale index still finds the checkpointed track
+        store.save_checkpoint(Some(0), Some(20), 60, false).unwrap();
+        let restored = store.load_session().unwrap();
+        assert_eq!(restored.resume_index(), Some(2));
+        assert!(!restored.was_playing);
+    }
+
+    #[test]
+    fn test_autosave_writes_while_playing_and_once_when_paused() {
+        let mut tracker = AutosaveTracker::default();
+        let playing = Checkpoint {
+            current_index: Some(0),
+            current_track_id: Some(10),
+            position_secs: 10,
+            was_playing: true,
+        };
+        assert!(tracker.should_save(&playing));
+        tracker.saved(playing);
+        assert!(tracker.should_save(&playing));
+
+        let paused = Checkpoint {
+            was_playing: false,
+            ..playing
+        };
+        assert!(tracker.should_save(&paused));
+        tracker.saved(paused);
+        assert!(!tracker.should_save(&paused));
+
+        let empty = Checkpoint {
+            current_track_id: None,
+            ..paused
+        };
+        assert!(!tracker.should_save(&empty));
+    }
+}
@@ -2,10 +2,16 @@
 //!
 //! Each Qobuz user gets their own subdirectory under the app's data/cache paths.
 //! This module provides the central path provider that all per-user state modules
-//! use to determine where to store their databases.
+//! use to determine where to store their databases. `compute_usage` reports
+//! how much disk each subsystem takes under those directories.
 
-use std::path::PathBuf;
-use std::sync::RwLock;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long a computed usage breakdown is served before walking the disk again
+pub const USAGE_CACHE_TTL: Duration = Duration::from_secs(30);
 
 /// Central path provider for per-user data isolation.
 ///
@@ -13,24 +19,29 @@ use std::sync::RwLock;
 /// user-scoped data and cache directories.
 pub struct UserDataPaths {
     user_id: RwLock<Option<u64>>,
+    /// Last `compute_usage` result and when it was taken
+    usage_cache: Mutex<Option<(Instant, UsageBreakdown)>>,
 }
 
 impl UserDataPaths {
     pub fn new() -> Self {
         Self {
             user_id: RwLock::new(None),
+            usage_cache: Mutex::new(None),
         }
     }
 
     /// Set the current user after login
     pub fn set_user(&self, user_id: u64) {
         *self.user_id.write().expect("UserDataPaths write lock poisoned") = Some(user_id);
+        self.invalidate_usage();
         log::info!("UserDataPaths: active user set to {}", user_id);
     }
 
     /// Clear the current user on logout
     pub fn clear_user(&self) {
         *self.user_id.write().expect("UserDataPaths write lock poisoned") = None;
+        self.invalidate_usage();
         log::info!("UserDataPaths: active user cleared");
     }
 
@@ -83,6 +94,24 @@ impl UserDataPaths {
             .map(|d| d.join("qbz"))
     }
 
+    /// Ids of all users with a data directory, i.e. accounts that have been
+    /// used on this machine
+    pub fn saved_user_ids() -> Vec<u64> {
+        let Ok(users_dir) = Self::global_data_dir().map(|d| d.join("users")) else {
+            return Vec::new();
+        };
+        let Ok(entries) = std::fs::read_dir(&users_dir) else {
+            return Vec::new();
+        };
+        let mut ids: Vec<u64> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
     /// Save the last active user_id to a flat-path file so the session
     /// can be restored on next app launch (when "remember me" is active).
     pub fn save_last_user_id(user_id: u64) -> Result<(), String> {
@@ -112,4 +141,169 @@ impl UserDataPaths {
         let dir = Self::global_data_dir()?;
         Ok(dir.join("last_user_id"))
     }
+
+    /// Usage breakdown computed less than `USAGE_CACHE_TTL` ago
+    pub fn cached_usage(&self) -> Option<UsageBreakdown> {
+        let guard = self.usage_cache.lock().ok()?;
+        guard
+            .as_ref()
+            .filter(|(at, _)| at.elapsed() < USAGE_CACHE_TTL)
+            .map(|(_, usage)| usage.clone())
+    }
+
+    pub fn store_usage(&self, usage: UsageBreakdown) {
+        if let Ok(mut guard) = self.usage_cache.lock() {
+            *guard = Some((Instant::now(), usage));
+        }
+    }
+
+    /// Drop the cached breakdown (user switch, or after a cleanup)
+    pub fn invalidate_usage(&self) {
+        if let Ok(mut guard) = self.usage_cache.lock() {
+            *guard = None;
+        }
+    }
+}
+
+/// Bytes on disk per subsystem
+///
+/// Each category is freed by an existing command: `clear_cache` (audio
+/// cache), `clear_offline_cache`, `clear_artwork_cache` plus
+/// `library_clear_thumbnails_cache` (artwork), `musicbrainz_clear_cache` and
+/// friends (api cache), `lyrics_clear_cache`. Databases hold settings and
+/// the library and are only removed by a factory reset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UsageBreakdown {
+    /// Streamed audio kept for replay (shared by all users)
+    pub audio_cache_bytes: u64,
+    /// Tracks saved for offline listening
+    pub offline_cache_bytes: u64,
+    /// Cover art and library thumbnails
+    pub artwork_bytes: u64,
+    /// API responses and metadata lookups (MusicBrainz, ListenBrainz, ...)
+    pub api_cache_bytes: u64,
+    pub lyrics_bytes: u64,
+    /// Per-user databases: settings, library, session, history
+    pub databases_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Directories `compute_usage` walks
+#[derive(Debug, Clone)]
+pub struct UsageRoots {
+    pub user_data_dir: PathBuf,
+    pub user_cache_dir: PathBuf,
+    pub global_cache_dir: PathBuf,
+    pub thumbnails_dir: PathBuf,
+}
+
+impl UsageRoots {
+    /// Directories of the active user
+    pub fn for_user(paths: &UserDataPaths) -> Result<Self, String> {
+        let thumbnails_dir = dirs::data_local_dir()
+            .ok_or("Could not determine data directory")?
+            .join("qbz")
+            .join("thumbnails");
+        Ok(Self {
+            user_data_dir: paths.user_data_dir()?,
+            user_cache_dir: paths.user_cache_dir()?,
+            global_cache_dir: UserDataPaths::global_cache_dir()?,
+            thumbnails_dir,
+        })
+    }
+}
+
+/// Walk the data and cache directories and total them per subsystem
+///
+/// Blocking and proportional to the number of cached files; call it off the
+/// main thread. Missing directories count as empty.
+pub fn compute_usage(roots: &UsageRoots) -> UsageBreakdown {
+    let api_cache_bytes = dir_size(&roots.user_data_dir.join("cache"));
+    let mut usage = UsageBreakdown {
+        audio_cache_bytes: dir_size(&roots.global_cache_dir.join("playback")),
+        offline_cache_bytes: dir_size(&roots.user_cache_dir.join("audio")),
+        artwork_bytes: dir_size(&roots.global_cache_dir.join("artwork"))
+            + dir_size(&roots.thumbnails_dir),
+        api_cache_bytes,
+        lyrics_bytes: dir_size(&roots.user_cache_dir.join("lyrics")),
+        databases_bytes: dir_size(&roots.user_data_dir).saturating_sub(api_cache_bytes),
+        total_bytes: 0,
+    };
+    usage.total_bytes = usage.audio_cache_bytes
+        + usage.offline_cache_bytes
+        + usage.artwork_bytes
+        + usage.api_cache_bytes
+        + usage.lyrics_bytes
+        + usage.databases_bytes;
+    usage
+}
+
+/// Total size of the files under `path`, not following symlinks
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_type = entry.file_type().ok()?;
+            if file_type.is_dir() {
+                Some(dir_size(&entry.path()))
+            } else if file_type.is_file() {
+                entry.metadata().ok().map(|m| m.len())
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: PathBuf, len: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; len]).unwrap();
+    }
+
+    #[test]
+    fn usage_is_totalled_per_subsystem() {
+        let root = tempfile::tempdir().unwrap();
+        let roots = UsageRoots {
+            user_data_dir: root.path().join("data/qbz/users/42"),
+            user_cache_dir: root.path().join("cache/qbz/users/42"),
+            global_cache_dir: root.path().join("cache/qbz"),
+            thumbnails_dir: root.path().join("local/qbz/thumbnails"),
+        };
+
+        write(roots.global_cache_dir.join("playback/1.flac"), 1000);
+        write(roots.global_cache_dir.join("playback/2.flac"), 500);
+        write(roots.user_cache_dir.join("audio/tracks/9.flac"), 4000);
+        write(roots.user_cache_dir.join("audio/artwork/9.jpg"), 300);
+        write(roots.user_cache_dir.join("audio/index.db"), 100);
+        write(roots.global_cache_dir.join("artwork/a.jpg"), 70);
+        write(roots.thumbnails_dir.join("t.jpg"), 30);
+        write(roots.user_data_dir.join("cache/api_cache.db"), 200);
+        write(roots.user_data_dir.join("cache/musicbrainz_cache.db"), 50);
+        write(roots.user_cache_dir.join("lyrics/lyrics.db"), 60);
+        write(roots.user_data_dir.join("library.db"), 800);
+        write(roots.user_data_dir.join("reco/events.db"), 40);
+
+        let usage = compute_usage(&roots);
+        assert_eq!(usage.audio_cache_bytes, 1500);
+        assert_eq!(usage.offline_cache_bytes, 4400);
+        assert_eq!(usage.artwork_bytes, 100);
+        assert_eq!(usage.api_cache_bytes, 250);
+        assert_eq!(usage.lyrics_bytes, 60);
+        assert_eq!(usage.databases_bytes, 840);
+        assert_eq!(usage.total_bytes, 7150);
+
+        // Nothing on disk yet (fresh install)
+        let empty = compute_usage(&UsageRoots {
+            user_data_dir: root.path().join("missing"),
+            ..roots
+        });
+        assert_eq!(empty.databases_bytes, 0);
+    }
 }
@@ -2,29 +2,130 @@
 //!
 //! Runs on a dedicated thread, completely separate from audio playback.
 //! Uses spectrum-analyzer crate for efficient FFT computation.
+//!
+//! The FFT time of each frame is measured; when it stays over budget the
+//! update rate and then the FFT size are lowered (see `AdaptiveRate`) so slow
+//! machines aren't starved. With nothing to draw for, the thread parks.
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread::Thread;
 use std::time::{Duration, Instant};
 
 use spectrum_analyzer::{samples_fft_to_spectrum, FrequencyLimit};
 use spectrum_analyzer::scaling::divide_by_N_sqrt;
 use spectrum_analyzer::windows::hann_window;
 use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
 
 use super::ring_buffer::RingBuffer;
-use super::{NUM_BARS, FFT_SIZE, TARGET_FPS};
+use super::{NUM_BARS, FFT_SIZE, REMOTE_NUM_BANDS, TARGET_FPS};
+
+/// Smoothing factor: 0 = no smoothing, higher = more smoothing
+const SMOOTHING: f32 = 0.65;
+
+/// (updates per second, FFT size) steps, from full quality down
+const RATE_LEVELS: [(u64, usize); 5] = [
+    (TARGET_FPS, FFT_SIZE),
+    (20, FFT_SIZE),
+    (15, FFT_SIZE / 2),
+    (10, FFT_SIZE / 2),
+    (10, FFT_SIZE / 4),
+];
+
+/// Share of the frame interval the FFT work may take
+const FRAME_BUDGET_SHARE: f64 = 0.25;
+
+/// Weight of the latest frame in the average processing time
+const AVERAGE_WEIGHT: f64 = 0.2;
+
+/// Frames over budget before stepping down (~1/3 s at full rate)
+const STEP_DOWN_AFTER: u32 = 10;
+
+/// Frames well under budget before stepping back up (several seconds)
+const STEP_UP_AFTER: u32 = 150;
+
+/// Picks the update rate and FFT size from measured processing time
+#[derive(Debug, Default)]
+pub struct AdaptiveRate {
+    level: usize,
+    /// Average processing time per frame (seconds, 0 = not measured yet)
+    average: f64,
+    over_budget: u32,
+    under_budget: u32,
+}
+
+impl AdaptiveRate {
+    pub fn fps(&self) -> u64 {
+        RATE_LEVELS[self.level].0
+    }
+
+    pub fn fft_size(&self) -> usize {
+        RATE_LEVELS[self.level].1
+    }
+
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_micros(1_000_000 / self.fps())
+    }
+
+    /// Record one frame's processing time; returns true when the level changed
+    pub fn record(&mut self, took: Duration) -> bool {
+        let took = took.as_secs_f64();
+        self.average = if self.average == 0.0 {
+            took
+        } else {
+            self.average * (1.0 - AVERAGE_WEIGHT) + took * AVERAGE_WEIGHT
+        };
+
+        let budget = self.frame_interval().as_secs_f64() * FRAME_BUDGET_SHARE;
+        if self.average > budget {
+            self.under_budget = 0;
+            self.over_budget += 1;
+            if self.over_budget >= STEP_DOWN_AFTER && self.level + 1 < RATE_LEVELS.len() {
+                return self.set_level(self.level + 1);
+            }
+        } else if self.average < budget / 4.0 {
+            self.over_budget = 0;
+            self.under_budget += 1;
+            if self.under_budget >= STEP_UP_AFTER && self.level > 0 {
+                return self.set_level(self.level - 1);
+            }
+        } else {
+            self.over_budget = 0;
+            self.under_budget = 0;
+        }
+        false
+    }
+
+    fn set_level(&mut self, level: usize) -> bool {
+        self.level = level;
+        self.average = 0.0;
+        self.over_budget = 0;
+        self.under_budget = 0;
+        true
+    }
+}
 
 /// Shared state for the visualizer thread
 pub struct VisualizerState {
     pub ring_buffer: Arc<RingBuffer>,
+    /// Sample capture flag, kept in sync with the consumers below
     pub enabled: Arc<AtomicBool>,
+    pub desktop_enabled: Arc<AtomicBool>,
+    /// Whether the desktop UI is visible (nothing to draw for otherwise)
+    pub ui_visible: Arc<AtomicBool>,
     pub sample_rate: Arc<AtomicU32>,
+    pub remote_tx: broadcast::Sender<Vec<u8>>,
+    /// Current updates per second (0 while idle)
+    pub effective_fps: Arc<AtomicU32>,
+    /// Current FFT size
+    pub fft_size: Arc<AtomicU32>,
 }
 
-/// Start the FFT processing thread
-pub fn start_visualizer_thread(state: VisualizerState, app_handle: AppHandle) {
-    std::thread::Builder::new()
+/// Start the FFT processing thread. The thread parks while idle; unpark the
+/// returned handle when a consumer appears.
+pub fn start_visualizer_thread(state: VisualizerState, app_handle: AppHandle) -> Thread {
+    let handle = std::thread::Builder::new()
         .name("visualizer-fft".to_string())
         .spawn(move || {
             run_fft_loop(state, app_handle);
@@ -32,6 +133,7 @@ pub fn start_visualizer_thread(state: VisualizerState, app_handle: AppHandle) {
         .expect("Failed to spawn visualizer thread");
 
     log::info!("Visualizer FFT thread started");
+    handle.thread().clone()
 }
 
 /// Main FFT processing loop
@@ -41,57 +143,69 @@ fn run_fft_loop(state: VisualizerState, app_handle: AppHandle) {
     let mut windowed = vec![0.0f32; FFT_SIZE];
     let mut output = vec![0.0f32; NUM_BARS];
     let mut smoothed = vec![0.0f32; NUM_BARS];
-
-    // Smoothing factor: 0 = no smoothing, higher = more smoothing
-    const SMOOTHING: f32 = 0.65;
-
-    let frame_duration = Duration::from_micros(1_000_000 / TARGET_FPS);
+    let mut remote_output = vec![0.0f32; REMOTE_NUM_BANDS];
+    let mut remote_smoothed = vec![0.0f32; REMOTE_NUM_BANDS];
+    let mut rate = AdaptiveRate::default();
 
     loop {
-        let frame_start = Instant::now();
+        let desktop = state.desktop_enabled.load(Ordering::Relaxed)
+            && state.ui_visible.load(Ordering::Relaxed);
+        let remote = state.remote_tx.receiver_count() > 0;
+        // Stop capturing once the last remote client has gone
+        state.enabled.store(desktop || remote, Ordering::Relaxed);
+
+        if !desktop && !remote {
+            // Fully idle until a consumer shows up (see `Visualizer::wake`)
+            state.effective_fps.store(0, Ordering::Relaxed);
+            std::thread::park();
+            continue;
+        }
+        state.effective_fps.store(rate.fps() as u32, Ordering::Relaxed);
+        state.fft_size.store(rate.fft_size() as u32, Ordering::Relaxed);
 
-        if state.enabled.load(Ordering::Relaxed) {
+        let frame_start = Instant::now();
+        {
             let sample_rate = state.sample_rate.load(Ordering::Relaxed);
+            let size = rate.fft_size();
+            let samples = &mut samples[..size];
+            let windowed = &mut windowed[..size];
 
             // Get samples from ring buffer
-            state.ring_buffer.snapshot(&mut samples);
+            state.ring_buffer.snapshot(samples);
 
             // Apply Hann window to reduce spectral leakage
-            let window = hann_window(&samples);
+            let window = hann_window(samples);
             for (i, (sample, win)) in samples.iter().zip(window.iter()).enumerate() {
                 windowed[i] = sample * win;
             }
 
             // Compute FFT spectrum
             match samples_fft_to_spectrum(
-                &windowed,
+                windowed,
                 sample_rate,
                 FrequencyLimit::Range(20.0, 20000.0),
                 Some(&divide_by_N_sqrt),
             ) {
                 Ok(spectrum) => {
-                    // Map spectrum to logarithmic frequency bars
-                    map_to_log_bars(&spectrum, &mut output);
-
-                    // Apply smoothing for visual continuity
-                    for i in 0..NUM_BARS {
-                        let new = output[i];
-                        // Faster attack, slower decay for punchy visuals
-                        if new > smoothed[i] {
-                            smoothed[i] = smoothed[i] * 0.3 + new * 0.7; // Fast attack
-                        } else {
-                            smoothed[i] = smoothed[i] * SMOOTHING + new * (1.0 - SMOOTHING); // Slow decay
-                        }
-                        output[i] = smoothed[i];
+                    if desktop {
+                        // Map spectrum to logarithmic frequency bars
+                        map_to_log_bars(&spectrum, &mut output);
+                        smooth(&mut output, &mut smoothed);
+
+                        // Send to frontend as binary data
+                        let bytes: Vec<u8> = output
+                            .iter()
+                            .flat_map(|f| f.to_le_bytes())
+                            .collect();
+
+                        let _ = app_handle.emit("viz:data", bytes);
                     }
 
-                    // Send to frontend as binary data
-                    let bytes: Vec<u8> = output
-                        .iter()
-                        .flat_map(|f| f.to_le_bytes())
-                        .collect();
-
-                    let _ = app_handle.emit("viz:data", bytes);
+                    if remote {
+                        map_to_log_bars(&spectrum, &mut remote_output);
+                        smooth(&mut remote_output, &mut remote_smoothed);
+                        let _ = state.remote_tx.send(quantize(&remote_output));
+                    }
                 }
                 Err(e) => {
                     log::debug!("FFT error: {:?}", e);
@@ -99,14 +213,44 @@ fn run_fft_loop(state: VisualizerState, app_handle: AppHandle) {
             }
         }
 
-        // Maintain target FPS
+        // Adapt to how long the work took, then keep to the current rate
         let elapsed = frame_start.elapsed();
+        if rate.record(elapsed) {
+            log::info!(
+                "Visualizer FFT took {:.1}ms, now {} fps with FFT size {}",
+                elapsed.as_secs_f64() * 1000.0,
+                rate.fps(),
+                rate.fft_size()
+            );
+        }
+        let frame_duration = rate.frame_interval();
         if elapsed < frame_duration {
             std::thread::sleep(frame_duration - elapsed);
         }
     }
 }
 
+/// Apply smoothing for visual continuity: faster attack, slower decay for
+/// punchy visuals
+fn smooth(output: &mut [f32], smoothed: &mut [f32]) {
+    for (value, previous) in output.iter_mut().zip(smoothed.iter_mut()) {
+        if *value > *previous {
+            *previous = *previous * 0.3 + *value * 0.7; // Fast attack
+        } else {
+            *previous = *previous * SMOOTHING + *value * (1.0 - SMOOTHING); // Slow decay
+        }
+        *value = *previous;
+    }
+}
+
+/// One byte per band (0.0-1.0 -> 0-255), to keep remote frames small
+fn quantize(bands: &[f32]) -> Vec<u8> {
+    bands
+        .iter()
+        .map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+        .collect()
+}
+
 /// Map spectrum data to logarithmically-spaced frequency bars
 ///
 /// Human hearing is logarithmic, so we use log-spaced bars to match
@@ -196,4 +340,42 @@ mod tests {
         // Last bar should approach 20000Hz
         assert!(freqs[num_bars - 1] > 15000.0);
     }
+
+    #[test]
+    fn test_adaptive_rate_steps_down_and_back_up() {
+        let mut rate = AdaptiveRate::default();
+        assert_eq!((rate.fps(), rate.fft_size()), (TARGET_FPS, FFT_SIZE));
+
+        // Slow frames: lower the rate first, then the FFT size, never past the last step
+        for _ in 0..1000 {
+            rate.record(Duration::from_millis(40));
+        }
+        assert_eq!((rate.fps(), rate.fft_size()), *RATE_LEVELS.last().unwrap());
+
+        // A single spike doesn't change anything
+        let mut rate = AdaptiveRate::default();
+        for _ in 0..20 {
+            assert!(!rate.record(Duration::from_micros(100)));
+        }
+        assert!(!rate.record(Duration::from_millis(40)));
+        for _ in 0..20 {
+            assert!(!rate.record(Duration::from_micros(100)));
+        }
+        assert_eq!(rate.fps(), TARGET_FPS);
+
+        // Fast frames for a while: back to full quality
+        rate.set_level(2);
+        for _ in 0..(STEP_UP_AFTER * 2) {
+            rate.record(Duration::from_micros(100));
+        }
+        assert_eq!((rate.fps(), rate.fft_size()), (TARGET_FPS, FFT_SIZE));
+    }
+
+    #[test]
+    fn test_quantize_remote_frame() {
+        assert_eq!(
+            quantize(&[0.0, 0.5, 1.0, 1.5, -0.2]),
+            vec![0, 128, 255, 255, 0]
+        );
+    }
 }
@@ -12,9 +12,12 @@ pub use ring_buffer::RingBuffer;
 pub use fft_processor::{VisualizerState, start_visualizer_thread};
 pub use tapped_source::TappedSource;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread::Thread;
+use serde::Serialize;
 use tauri::AppHandle;
+use tokio::sync::broadcast;
 
 /// Number of frequency bins to send to frontend
 /// 16 bins, mirrored on frontend for symmetric look
@@ -27,12 +30,16 @@ pub const FFT_SIZE: usize = 1024;
 /// Target frames per second for visualization updates
 pub const TARGET_FPS: u64 = 30;
 
+/// Number of frequency bands in frames sent to remote clients (`/api/viz`)
+pub const REMOTE_NUM_BANDS: usize = 32;
+
 /// Shared state for visualization that can be passed to the audio thread
 #[derive(Clone)]
 pub struct VisualizerTap {
     /// Ring buffer for sample capture
     pub ring_buffer: Arc<RingBuffer>,
-    /// Whether visualization is enabled
+    /// Whether samples are being captured (desktop visualizer on, or a
+    /// remote client connected)
     pub enabled: Arc<AtomicBool>,
     /// Current sample rate
     pub sample_rate: Arc<AtomicU32>,
@@ -69,10 +76,37 @@ impl Default for VisualizerTap {
     }
 }
 
+/// Current visualizer settings and effective rate (`get_visualizer_config`)
+#[derive(Debug, Clone, Serialize)]
+pub struct VisualizerConfig {
+    /// Desktop visualizer switched on
+    pub enabled: bool,
+    /// Desktop UI visible
+    pub ui_visible: bool,
+    pub num_bars: usize,
+    /// Updates per second when nothing is throttled
+    pub target_fps: u64,
+    /// Updates per second right now (0 while idle, lower when throttled)
+    pub effective_fps: u32,
+    /// FFT size right now (lower when throttled)
+    pub fft_size: u32,
+}
+
 /// Manages the audio visualizer lifecycle
 pub struct Visualizer {
     /// Shared tap state (given to Player for sample capture)
     tap: VisualizerTap,
+    /// Whether the desktop UI visualizer is on
+    desktop_enabled: Arc<AtomicBool>,
+    /// Whether the desktop UI is visible
+    ui_visible: Arc<AtomicBool>,
+    effective_fps: Arc<AtomicU32>,
+    fft_size: Arc<AtomicU32>,
+    /// FFT thread, unparked when a consumer appears
+    thread: Mutex<Option<Thread>>,
+    /// Frames for remote clients; the FFT thread only produces them while
+    /// there are subscribers
+    remote_tx: broadcast::Sender<Vec<u8>>,
     /// Whether the FFT thread has been started (prevents double-start)
     started: AtomicBool,
 }
@@ -80,8 +114,15 @@ pub struct Visualizer {
 impl Visualizer {
     /// Create a new visualizer instance
     pub fn new() -> Self {
+        let (remote_tx, _) = broadcast::channel(4);
         Self {
             tap: VisualizerTap::new(),
+            desktop_enabled: Arc::new(AtomicBool::new(false)),
+            ui_visible: Arc::new(AtomicBool::new(true)),
+            effective_fps: Arc::new(AtomicU32::new(0)),
+            fft_size: Arc::new(AtomicU32::new(FFT_SIZE as u32)),
+            thread: Mutex::new(None),
+            remote_tx,
             started: AtomicBool::new(false),
         }
     }
@@ -100,20 +141,76 @@ impl Visualizer {
         let state = VisualizerState {
             ring_buffer: self.tap.ring_buffer.clone(),
             enabled: self.tap.enabled.clone(),
+            desktop_enabled: self.desktop_enabled.clone(),
+            ui_visible: self.ui_visible.clone(),
             sample_rate: self.tap.sample_rate.clone(),
+            remote_tx: self.remote_tx.clone(),
+            effective_fps: self.effective_fps.clone(),
+            fft_size: self.fft_size.clone(),
         };
-        start_visualizer_thread(state, app_handle);
+        let thread = start_visualizer_thread(state, app_handle);
+        if let Ok(mut guard) = self.thread.lock() {
+            *guard = Some(thread);
+        }
     }
 
-    /// Enable or disable visualization
+    /// Enable or disable the desktop visualization
     pub fn set_enabled(&self, enabled: bool) {
-        self.tap.enabled.store(enabled, Ordering::Relaxed);
+        self.desktop_enabled.store(enabled, Ordering::Relaxed);
+        self.update_capture();
         log::info!("Visualizer {}", if enabled { "enabled" } else { "disabled" });
     }
 
-    /// Check if visualization is enabled
+    /// Check if the desktop visualization is enabled
     pub fn is_enabled(&self) -> bool {
-        self.tap.enabled.load(Ordering::Relaxed)
+        self.desktop_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Tell the visualizer whether the desktop UI is visible; while hidden
+    /// the desktop visualization is not computed
+    pub fn set_ui_visible(&self, visible: bool) {
+        self.ui_visible.store(visible, Ordering::Relaxed);
+        self.update_capture();
+    }
+
+    /// Current settings and effective rate
+    pub fn config(&self) -> VisualizerConfig {
+        VisualizerConfig {
+            enabled: self.is_enabled(),
+            ui_visible: self.ui_visible.load(Ordering::Relaxed),
+            num_bars: NUM_BARS,
+            target_fps: TARGET_FPS,
+            effective_fps: self.effective_fps.load(Ordering::Relaxed),
+            fft_size: self.fft_size.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Start or stop capture to match the consumers, waking the FFT thread
+    fn update_capture(&self) {
+        let desktop = self.desktop_enabled.load(Ordering::Relaxed)
+            && self.ui_visible.load(Ordering::Relaxed);
+        let capture = desktop || self.remote_tx.receiver_count() > 0;
+        self.tap.enabled.store(capture, Ordering::Relaxed);
+        if capture {
+            self.wake();
+        }
+    }
+
+    /// Unpark the FFT thread so it notices new consumers
+    fn wake(&self) {
+        if let Some(thread) = self.thread.lock().ok().and_then(|guard| guard.clone()) {
+            thread.unpark();
+        }
+    }
+
+    /// Receive remote frames: `REMOTE_NUM_BANDS` bytes, one per band
+    /// (0-255, low to high frequency). Capture starts right away and stops
+    /// once every receiver has been dropped.
+    pub fn subscribe_remote(&self) -> broadcast::Receiver<Vec<u8>> {
+        let rx = self.remote_tx.subscribe();
+        self.tap.enabled.store(true, Ordering::Relaxed);
+        self.wake();
+        rx
     }
 
     /// Update the sample rate (call when audio format changes)
@@ -16,6 +16,7 @@ import type {
   PageArtistRelease,
   PageArtistReleaseGroup
 } from '$lib/types';
+import { applyArtworkQuality } from '$lib/stores/artworkStore';
 
 // ============ Formatting Utilities ============
 
@@ -34,10 +35,11 @@ export function formatDuration(seconds: number): string {
 export const formatDurationMinutes = formatDuration;
 
 /**
- * Extract best available image from Qobuz image object
+ * Extract best available image from Qobuz image object, sized for the
+ * artwork quality setting
  */
 export function getQobuzImage(image?: { large?: string; thumbnail?: string; small?: string }): string {
-  return image?.large || image?.thumbnail || image?.small || '';
+  return applyArtworkQuality(image?.large || image?.thumbnail || image?.small || '');
 }
 
 /**
@@ -350,9 +352,18 @@ export function convertQobuzAlbum(album: QobuzAlbum): AlbumDetail {
       albumId: album.id,
       artistId: track.performer?.id ?? album.artist?.id,
       isrc: track.isrc,
-      streamable: track.streamable ?? true
+      streamable: track.streamable ?? true,
+      composer: track.composer?.name,
+      work: track.work,
+      movement: track.movement
     })) || [],
-    upc: album.upc
+    upc: album.upc,
+    composer: album.composer?.name,
+    works: album.works?.map(group => ({
+      work: group.work,
+      composer: group.composer,
+      trackIds: group.track_ids
+    }))
   };
 }
 
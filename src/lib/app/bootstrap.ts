@@ -14,6 +14,7 @@ import { initOfflineStore, cleanupOfflineStore, onOnlineTransition, syncPendingP
 import { loadUnavailableTracks } from '$lib/stores/unavailableTracksStore';
 import { getNextZoomLevel } from '$lib/utils/zoom';
 import { getZoom, setZoom } from '$lib/stores/zoomStore';
+import { getUserItem } from '$lib/utils/userStorage';
 
 // ============ Theme Management ============
 
@@ -83,6 +84,10 @@ export async function restoreLastfmSession(): Promise<void> {
       await invoke('lastfm_set_session', { sessionKey: savedSessionKey });
       console.log('Last.fm session restored on startup');
     }
+
+    // The backend reports "now playing" and needs the user's toggle
+    const scrobbling = getUserItem('qbz-lastfm-scrobbling') !== 'false';
+    await invoke('lastfm_set_scrobbling_enabled', { enabled: scrobbling });
   } catch (err) {
     console.error('Failed to restore Last.fm session:', err);
   }
@@ -145,7 +150,7 @@ export function bootstrapApp(): BootstrapResult {
   // Load unavailable tracks from localStorage
   loadUnavailableTracks();
 
-  // Register callback to flush scrobble queue and sync playlists when transitioning to online
+  // Register callback to flush scrobble queue and sync playlists and favorites when transitioning to online
   onOnlineTransition(() => {
     console.log('[Bootstrap] Online transition detected - flushing scrobble queue and syncing playlists');
 
@@ -160,6 +165,17 @@ export function bootstrapApp(): BootstrapResult {
     syncPendingPlaylists().catch(err => {
       console.error('[Bootstrap] Failed to sync pending playlists:', err);
     });
+
+    // Send favorite changes made while offline
+    invoke<{ synced: number; failed: number; reverted: number }>('sync_queued_favorites')
+      .then(({ synced, failed }) => {
+        if (synced > 0 || failed > 0) {
+          console.log(`[Bootstrap] Favorite sync complete: ${synced} synced, ${failed} failed`);
+        }
+      })
+      .catch(err => {
+        console.error('[Bootstrap] Failed to sync queued favorites:', err);
+      });
   });
 
   return {
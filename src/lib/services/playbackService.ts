@@ -39,8 +39,6 @@ import {
 import {
   isOffline as checkIsOffline,
   queueScrobble,
-  getQueuedScrobbles,
-  markScrobblesSent,
   cleanupSentScrobbles
 } from '$lib/stores/offlineStore';
 import {
@@ -50,11 +48,35 @@ import {
 
 // ============ Types ============
 
+type StreamQuality = 'Mp3' | 'Lossless' | 'HiRes' | 'UltraHiRes';
+
 /** Result from play_track command */
 interface PlayTrackResult {
   format_id: number | null;
+  quality: StreamQuality | null;
+  /** Set when the stream resolved below the requested tier */
+  downgrade: { requested: StreamQuality; achieved: StreamQuality } | null;
+  /** Set when the device can't play the preferred tier natively (limit quality to device) */
+  device_limit: {
+    strategy: 'request_lower' | 'fetch_hires_and_resample';
+    device_max_sample_rate: number;
+    device_quality: StreamQuality;
+    requested: StreamQuality;
+  } | null;
 }
 
+const QUALITY_LABELS: Record<StreamQuality, string> = {
+  Mp3: 'MP3',
+  Lossless: 'CD Quality',
+  HiRes: 'Hi-Res',
+  UltraHiRes: 'Hi-Res+'
+};
+
+// Downgrade notices are shown once per album and tier, not on every track
+let lastDowngradeNotice: string | null = null;
+// Same for device limit notices
+let lastDeviceLimitNotice: string | null = null;
+
 /** Result from plex_play_track command */
 interface PlexPlayTrackResult {
   sampling_rate_hz?: number | null;
@@ -198,6 +220,33 @@ export async function playTrack(
             // Re-set current track to update the UI with actual format
             setCurrentTrack(track);
           }
+
+          if (result.device_limit) {
+            const { strategy, device_max_sample_rate, requested } = result.device_limit;
+            const noticeKey = `${track.albumId ?? track.id}:${strategy}`;
+            if (noticeKey !== lastDeviceLimitNotice) {
+              lastDeviceLimitNotice = noticeKey;
+              const deviceKHz = device_max_sample_rate / 1000;
+              showToast(
+                strategy === 'request_lower'
+                  ? `Device plays up to ${deviceKHz} kHz, requested ${QUALITY_LABELS[requested]}`
+                  : `Device plays up to ${deviceKHz} kHz, ${QUALITY_LABELS[requested]} will be resampled`,
+                'info'
+              );
+            }
+          }
+
+          if (result.downgrade) {
+            const { requested, achieved } = result.downgrade;
+            const noticeKey = `${track.albumId ?? track.id}:${achieved}`;
+            if (noticeKey !== lastDowngradeNotice) {
+              lastDowngradeNotice = noticeKey;
+              showToast(
+                `${QUALITY_LABELS[requested]} isn't available for this track, playing ${QUALITY_LABELS[achieved]}`,
+                'warning'
+              );
+            }
+          }
         }
       }
     }
@@ -208,16 +257,20 @@ export async function playTrack(
       showToast(`Playing: ${track.title}`, 'success');
     }
 
-    // Log play event for recommendations (fire-and-forget)
-    if (!isLocal && source !== 'plex') {
-      void logRecoEvent({
-        eventType: 'play',
-        itemType: 'track',
-        trackId: track.id,
-        albumId: track.albumId,
-        artistId: track.artistId
-      });
-    }
+    // Log play event for recommendations and listening stats (fire-and-forget).
+    // Local and Plex plays only feed the stats; recommendations stay Qobuz-only.
+    const isQobuz = !isLocal && source !== 'plex';
+    void logRecoEvent({
+      eventType: 'play',
+      itemType: 'track',
+      trackId: track.id,
+      albumId: isQobuz ? track.albumId : undefined,
+      artistId: isQobuz ? track.artistId : undefined,
+      source: isQobuz ? 'qobuz' : source === 'plex' ? 'plex' : 'local',
+      durationSecs: track.duration ? Math.round(track.duration) : undefined,
+      title: track.title,
+      artistName: track.artist
+    });
 
     // Update MPRIS metadata
     await updateMediaMetadata({
@@ -238,10 +291,10 @@ export async function playTrack(
       track.samplingRate
     );
 
-    // Update Last.fm
+    // Schedule Last.fm scrobble ("now playing" is reported by the backend)
     await updateLastfmNowPlaying(track.title, track.artist, track.album, track.duration, track.id);
 
-    // Update ListenBrainz (with MusicBrainz enrichment)
+    // Schedule ListenBrainz scrobble (with MusicBrainz enrichment)
     await updateListenBrainzNowPlaying(
       track.title,
       track.artist,
@@ -488,7 +541,8 @@ async function resolveMusicBrainzTrack(
 }
 
 /**
- * Update ListenBrainz "now playing" and schedule scrobble
+ * Schedule a ListenBrainz scrobble. "Now playing" is reported by the
+ * backend's playback poller on track start, resume and seek.
  */
 export async function updateListenBrainzNowPlaying(
   title: string,
@@ -511,25 +565,6 @@ export async function updateListenBrainzNowPlaying(
     mbData = await resolveMusicBrainzTrack(title, artist, isrc);
   }
 
-  // Skip "now playing" update when offline (requires network)
-  if (!isOffline) {
-    try {
-      await invoke('listenbrainz_now_playing', {
-        artist,
-        track: title,
-        album: album || null,
-        recordingMbid: mbData?.mbid || null,
-        releaseMbid: mbData?.releaseMbid || null,
-        artistMbids: mbData?.artistMbids || null,
-        isrc: isrc || null,
-        durationMs
-      });
-      console.log('ListenBrainz: Updated now playing');
-    } catch (err) {
-      console.error('ListenBrainz now playing failed:', err);
-    }
-  }
-
   // Schedule scrobble after 50% of track or 4 minutes (whichever is shorter)
   if (listenbrainzScrobbleTimeout) {
     clearTimeout(listenbrainzScrobbleTimeout);
@@ -612,7 +647,8 @@ export async function flushListenBrainzQueue(): Promise<number> {
 }
 
 /**
- * Update Last.fm "now playing" and schedule scrobble
+ * Schedule a Last.fm scrobble. "Now playing" is reported by the backend's
+ * playback poller on track start, resume and seek.
  */
 export async function updateLastfmNowPlaying(
   title: string,
@@ -627,22 +663,6 @@ export async function updateLastfmNowPlaying(
 
   if (!scrobblingEnabled || !sessionKey) return;
 
-  const isOffline = checkIsOffline();
-
-  // Skip "now playing" update when offline (requires network)
-  if (!isOffline) {
-    try {
-      await invoke('lastfm_now_playing', {
-        artist,
-        track: title,
-        album: album || null
-      });
-      console.log('Last.fm: Updated now playing');
-    } catch (err) {
-      console.error('Last.fm now playing failed:', err);
-    }
-  }
-
   // Schedule scrobble after 50% of track or 4 minutes (whichever is shorter)
   if (scrobbleTimeout) {
     clearTimeout(scrobbleTimeout);
@@ -702,56 +722,16 @@ export async function flushScrobbleQueue(): Promise<{ sent: number; failed: numb
   }
 
   try {
-    const queued = await getQueuedScrobbles(50); // Last.fm batch limit
-    if (queued.length === 0) {
-      return { sent: 0, failed: 0 };
-    }
-
-    console.log(`Last.fm: Flushing ${queued.length} queued scrobbles`);
-
-    let sent = 0;
-    let failed = 0;
-    const sentIds: number[] = [];
-
-    // Send scrobbles one by one (could be optimized to batch later)
-    for (const scrobble of queued) {
-      // Check if timestamp is too old (>14 days)
-      const now = Math.floor(Date.now() / 1000);
-      const fourteenDaysAgo = now - (14 * 24 * 60 * 60);
-
-      if (scrobble.timestamp < fourteenDaysAgo) {
-        console.log(`Last.fm: Skipping old scrobble (>14 days): ${scrobble.artist} - ${scrobble.track}`);
-        sentIds.push(scrobble.id); // Mark as sent to remove from queue
-        failed++;
-        continue;
-      }
-
-      try {
-        await invoke('lastfm_scrobble', {
-          artist: scrobble.artist,
-          track: scrobble.track,
-          album: scrobble.album,
-          timestamp: scrobble.timestamp
-        });
-        sentIds.push(scrobble.id);
-        sent++;
-        console.log(`Last.fm: Flushed scrobble: ${scrobble.artist} - ${scrobble.track}`);
-      } catch (err) {
-        console.error(`Last.fm: Failed to flush scrobble: ${scrobble.artist} - ${scrobble.track}`, err);
-        failed++;
-      }
-    }
-
-    // Mark successfully sent scrobbles
-    if (sentIds.length > 0) {
-      await markScrobblesSent(sentIds);
-    }
+    // Batches of 50; only accepted (or permanently ignored) scrobbles leave the queue
+    const result = await invoke<{ sent: number; ignored: number; failed: number }>('flush_scrobble_queue');
 
     // Cleanup old sent scrobbles
     await cleanupSentScrobbles(7);
 
-    console.log(`Last.fm: Flush complete - sent: ${sent}, failed: ${failed}`);
-    return { sent, failed };
+    console.log(
+      `Last.fm: Flush complete - sent: ${result.sent}, ignored: ${result.ignored}, kept: ${result.failed}`
+    );
+    return { sent: result.sent, failed: result.ignored + result.failed };
   } catch (err) {
     console.error('Last.fm: Failed to flush scrobble queue:', err);
     return { sent: 0, failed: 0 };
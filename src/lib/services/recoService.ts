@@ -8,6 +8,8 @@ import { invoke } from '@tauri-apps/api/core';
 
 export type RecoEventType = 'play' | 'favorite' | 'playlist_add';
 export type RecoItemType = 'track' | 'album' | 'artist';
+export type RecoSource = 'qobuz' | 'local' | 'plex';
+export type StatsPeriod = 'last_7_days' | 'last_30_days' | 'all_time';
 
 export interface RecoEventInput {
   eventType: RecoEventType;
@@ -16,6 +18,10 @@ export interface RecoEventInput {
   albumId?: string;
   artistId?: number;
   playlistId?: number;
+  source?: RecoSource;
+  durationSecs?: number;
+  title?: string;
+  artistName?: string;
 }
 
 export interface TopArtistSeed {
@@ -31,6 +37,30 @@ export interface HomeSeeds {
   favoriteTrackIds: number[];
 }
 
+export interface ListeningStats {
+  period: StatsPeriod;
+  totalPlays: number;
+  totalListeningSecs: number;
+  topArtists: {
+    artistId: number | null;
+    name: string | null;
+    playCount: number;
+    listeningSecs: number;
+  }[];
+  topTracks: {
+    trackId: number | null;
+    source: RecoSource;
+    title: string | null;
+    artist: string | null;
+    playCount: number;
+    listeningSecs: number;
+  }[];
+  /** One entry per day (YYYY-MM-DD), oldest first, including days without plays */
+  daily: { date: string; plays: number; listeningSecs: number }[];
+  /** Always qobuz, local, plex in that order */
+  bySource: { source: RecoSource; plays: number; listeningSecs: number }[];
+}
+
 export async function logRecoEvent(event: RecoEventInput): Promise<void> {
   try {
     await invoke('reco_log_event', { event });
@@ -66,6 +96,10 @@ export async function getHomeSeeds(limits?: {
   });
 }
 
+export async function getListeningStats(period: StatsPeriod): Promise<ListeningStats> {
+  return invoke<ListeningStats>('reco_get_stats', { period });
+}
+
 export async function trainScores(options?: {
   lookbackDays?: number;
   halfLifeDays?: number;
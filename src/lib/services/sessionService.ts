@@ -20,11 +20,19 @@ export interface PersistedQueueTrack {
   is_local?: boolean;
   album_id?: string | null;
   artist_id?: number | null;
+  streamable?: boolean;
+  source?: string | null;
+  track_number?: number | null;
+  disc_number?: number | null;
 }
 
 export interface PersistedSession {
+  /** Layout version the session was stored with */
+  version?: number;
   queue_tracks: PersistedQueueTrack[];
   current_index: number | null;
+  /** Track playing at the last autosave checkpoint */
+  current_track_id?: number | null;
   current_position_secs: number;
   volume: number;
   shuffle_enabled: boolean;
@@ -80,6 +88,27 @@ export async function loadSessionState(): Promise<PersistedSession | null> {
   }
 }
 
+export interface ResumedSession {
+  track: PersistedQueueTrack;
+  queue_index: number;
+  position_secs: number;
+  volume: number;
+  shuffle_enabled: boolean;
+  repeat_mode: string;
+}
+
+/**
+ * Restore the last saved queue and track into the backend without playing
+ */
+export async function resumeLastSession(): Promise<ResumedSession | null> {
+  try {
+    return await invoke<ResumedSession | null>('resume_last_session');
+  } catch (err) {
+    console.error('[Session] Failed to resume last session:', err);
+    return null;
+  }
+}
+
 /**
  * Quick save of just the playback position (debounced during playback)
  */
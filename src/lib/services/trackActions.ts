@@ -16,6 +16,7 @@ import { addTrackToFavorites } from '$lib/services/playbackService';
 import { openPlaylistModal } from '$lib/stores/uiStore';
 import { showToast as storeShowToast, type ToastType } from '$lib/stores/toastStore';
 import type { QobuzTrack, Track, PlaylistTrack, LocalLibraryTrack } from '$lib/types';
+import { isHiResSpecs } from '$lib/utils/quality';
 
 // ============ Toast Integration ============
 
@@ -110,8 +111,7 @@ export function buildQueueTrackFromLocalTrack(track: LocalLibraryTrack): Backend
   const artwork = track.artwork_path
     ? (/^https?:\/\//i.test(track.artwork_path) ? track.artwork_path : convertFileSrc(track.artwork_path))
     : null;
-  // Local tracks are hi-res if bit_depth > 16 or sample_rate > 44100
-  const isHires = Boolean((track.bit_depth && track.bit_depth > 16) || (track.sample_rate && track.sample_rate > 44100));
+  const isHires = isHiResSpecs(track.bit_depth, track.sample_rate);
   const isPlexTrack = track.source === 'plex';
   return {
     id: track.id,
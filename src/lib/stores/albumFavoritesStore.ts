@@ -7,11 +7,13 @@
  * Sync strategy:
  * - On login: Fetch from Qobuz API → sync to local cache → populate store
  * - On toggle: API call first → on success → update local cache → notify UI
+ * - On batch change: update store + local cache → backend syncs to Qobuz in background
  * - UI reads from in-memory store (backed by local cache)
  */
 
 import { invoke } from '@tauri-apps/api/core';
 import { logRecoEvent } from '$lib/services/recoService';
+import type { QobuzAlbum } from '$lib/types';
 
 let favoriteAlbumIds = new Set<string>();
 let togglingAlbumIds = new Set<string>(); // Album IDs currently being toggled
@@ -88,11 +90,12 @@ export async function loadAlbumFavorites(): Promise<void> {
 export async function syncFromApi(): Promise<void> {
   try {
     const allAlbumIds: string[] = [];
+    const entries: Array<Record<string, unknown>> = [];
     let offset = 0;
     const limit = 500;
 
     while (true) {
-      const result = await invoke<{ albums?: { items: Array<{ id: string }>; total?: number } }>('get_favorites', {
+      const result = await invoke<{ albums?: { items: QobuzAlbum[]; total?: number } }>('get_favorites', {
         favType: 'albums',
         limit,
         offset
@@ -102,6 +105,16 @@ export async function syncFromApi(): Promise<void> {
       if (!items.length) break;
 
       allAlbumIds.push(...items.map(item => item.id));
+      entries.push(...items.map(item => ({
+        album_id: item.id,
+        title: item.title,
+        artist: item.artist?.name ?? null,
+        genre: item.genre?.name ?? null,
+        release_year: item.release_date_original ? parseInt(item.release_date_original.slice(0, 4), 10) || null : null,
+        hires: item.hires_streamable ?? false,
+        bit_depth: item.maximum_bit_depth ?? null,
+        added_at: item.favorited_at ?? null
+      })));
       offset += items.length;
 
       if (items.length < limit) break;
@@ -109,10 +122,10 @@ export async function syncFromApi(): Promise<void> {
     }
 
     // Sync to local cache
-    await invoke('sync_cached_favorite_albums', { albumIds: allAlbumIds });
+    await invoke('sync_cached_favorite_albums', { albumIds: allAlbumIds, entries });
 
-    // Update in-memory store
-    favoriteAlbumIds = new Set(allAlbumIds);
+    // Update in-memory store (read back so changes still queued for Qobuz stay)
+    favoriteAlbumIds = new Set(await invoke<string[]>('get_cached_favorite_albums'));
     isLoaded = true;
     notifyListeners();
 
@@ -193,3 +206,48 @@ export function unmarkAsFavorite(albumId: string): void {
     notifyListeners();
   }
 }
+
+/**
+ * Favorite or unfavorite many albums at once.
+ * The in-memory store and local cache update immediately; the backend syncs
+ * the change to Qobuz in the background (queued while offline).
+ */
+export async function setAlbumFavoritesBatch(albumIds: string[], favorite: boolean): Promise<boolean> {
+  const changed = albumIds.filter(id => favoriteAlbumIds.has(id) !== favorite);
+  if (changed.length === 0) return true;
+
+  for (const id of changed) {
+    if (favorite) favoriteAlbumIds.add(id);
+    else favoriteAlbumIds.delete(id);
+  }
+  notifyListeners();
+
+  try {
+    await invoke(favorite ? 'add_favorites_batch' : 'remove_favorites_batch', {
+      favType: 'album',
+      itemIds: changed.map(id => id)
+    });
+    return true;
+  } catch (err) {
+    console.error('[AlbumFavorites] Failed to queue batch change:', err);
+    for (const id of changed) {
+      if (favorite) favoriteAlbumIds.delete(id);
+      else favoriteAlbumIds.add(id);
+    }
+    notifyListeners();
+    return false;
+  }
+}
+
+/**
+ * Reload the in-memory store from the local cache
+ * (e.g. after the backend reverted changes it could not sync)
+ */
+export async function reloadFromCache(): Promise<void> {
+  try {
+    favoriteAlbumIds = new Set(await invoke<string[]>('get_cached_favorite_albums'));
+    notifyListeners();
+  } catch (err) {
+    console.error('[AlbumFavorites] Failed to reload from cache:', err);
+  }
+}
@@ -20,6 +20,12 @@ export interface BlacklistSettings {
   enabled: boolean;
 }
 
+export interface BlacklistImportSummary {
+  added: number;
+  skipped: number;
+  invalid: number;
+}
+
 // ============ State ============
 
 let blacklist: BlacklistedArtist[] = [];
@@ -132,6 +138,23 @@ export async function clearBlacklist(): Promise<void> {
   notifyListeners();
 }
 
+/**
+ * Export the blacklist as JSON (IDs and names) for backup or sharing
+ */
+export async function exportBlacklist(): Promise<string> {
+  return invoke<string>('export_artist_blacklist');
+}
+
+/**
+ * Import a blacklist from an exported JSON file.
+ * With merge=false, the current blacklist is replaced.
+ */
+export async function importBlacklist(json: string, merge: boolean): Promise<BlacklistImportSummary> {
+  const summary = await invoke<BlacklistImportSummary>('import_artist_blacklist', { json, merge });
+  await loadBlacklist();
+  return summary;
+}
+
 /**
  * Subscribe to blacklist changes
  */
@@ -7,6 +7,7 @@
  * Sync strategy:
  * - On login: Fetch from Qobuz API → sync to local cache → populate store
  * - On toggle: API call first → on success → update local cache → notify UI
+ * - On batch change: update store + local cache → backend syncs to Qobuz in background
  * - UI reads from in-memory store (backed by local cache)
  */
 
@@ -88,11 +89,12 @@ export async function loadArtistFavorites(): Promise<void> {
 export async function syncFromApi(): Promise<void> {
   try {
     const allArtistIds: number[] = [];
+    const entries: Array<{ artist_id: number; name: string | null; added_at: number | null }> = [];
     let offset = 0;
     const limit = 500;
 
     while (true) {
-      const result = await invoke<{ artists?: { items: Array<{ id: number }>; total?: number } }>('get_favorites', {
+      const result = await invoke<{ artists?: { items: Array<{ id: number; name?: string; favorited_at?: number }>; total?: number } }>('get_favorites', {
         favType: 'artists',
         limit,
         offset
@@ -102,6 +104,11 @@ export async function syncFromApi(): Promise<void> {
       if (!items.length) break;
 
       allArtistIds.push(...items.map(item => item.id));
+      entries.push(...items.map(item => ({
+        artist_id: item.id,
+        name: item.name ?? null,
+        added_at: item.favorited_at ?? null
+      })));
       offset += items.length;
 
       if (items.length < limit) break;
@@ -109,10 +116,10 @@ export async function syncFromApi(): Promise<void> {
     }
 
     // Sync to local cache
-    await invoke('sync_cached_favorite_artists', { artistIds: allArtistIds });
+    await invoke('sync_cached_favorite_artists', { artistIds: allArtistIds, entries });
 
-    // Update in-memory store
-    favoriteArtistIds = new Set(allArtistIds);
+    // Update in-memory store (read back so changes still queued for Qobuz stay)
+    favoriteArtistIds = new Set(await invoke<number[]>('get_cached_favorite_artists'));
     isLoaded = true;
     notifyListeners();
 
@@ -193,3 +200,48 @@ export function unmarkAsFavorite(artistId: number): void {
     notifyListeners();
   }
 }
+
+/**
+ * Favorite or unfavorite many artists at once.
+ * The in-memory store and local cache update immediately; the backend syncs
+ * the change to Qobuz in the background (queued while offline).
+ */
+export async function setArtistFavoritesBatch(artistIds: number[], favorite: boolean): Promise<boolean> {
+  const changed = artistIds.filter(id => favoriteArtistIds.has(id) !== favorite);
+  if (changed.length === 0) return true;
+
+  for (const id of changed) {
+    if (favorite) favoriteArtistIds.add(id);
+    else favoriteArtistIds.delete(id);
+  }
+  notifyListeners();
+
+  try {
+    await invoke(favorite ? 'add_favorites_batch' : 'remove_favorites_batch', {
+      favType: 'artist',
+      itemIds: changed.map(id => String(id))
+    });
+    return true;
+  } catch (err) {
+    console.error('[ArtistFavorites] Failed to queue batch change:', err);
+    for (const id of changed) {
+      if (favorite) favoriteArtistIds.delete(id);
+      else favoriteArtistIds.add(id);
+    }
+    notifyListeners();
+    return false;
+  }
+}
+
+/**
+ * Reload the in-memory store from the local cache
+ * (e.g. after the backend reverted changes it could not sync)
+ */
+export async function reloadFromCache(): Promise<void> {
+  try {
+    favoriteArtistIds = new Set(await invoke<number[]>('get_cached_favorite_artists'));
+    notifyListeners();
+  } catch (err) {
+    console.error('[ArtistFavorites] Failed to reload from cache:', err);
+  }
+}
@@ -0,0 +1,90 @@
+/**
+ * Artwork Quality Store
+ *
+ * Mirrors the backend artwork quality setting so cover URLs can be rewritten
+ * synchronously (getQobuzImage). The value is also kept in localStorage so
+ * the first render after startup already uses the right size.
+ *
+ * - low: 230px covers (less data)
+ * - standard: 600px covers, as returned by the API
+ * - high: original-resolution covers, for HiDPI/4K displays
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type ArtworkQuality = 'low' | 'standard' | 'high';
+
+export interface ArtworkSettings {
+  quality: ArtworkQuality;
+  cache_mb: number;
+}
+
+const STORAGE_KEY = 'qbz-artwork-quality';
+
+// Sizes Qobuz serves covers at (besides "max"), see api/artwork.rs
+const COVER_SIZES = [50, 150, 230, 300, 600];
+const COVER_URL = /^(.*static\.qobuz\.com\/images\/covers\/.*)_(\d+|max|org)\.(\w+)$/;
+
+let quality: ArtworkQuality = 'standard';
+
+function isQuality(value: unknown): value is ArtworkQuality {
+  return value === 'low' || value === 'standard' || value === 'high';
+}
+
+/**
+ * Initialize from localStorage, then sync from the backend
+ */
+export async function initArtworkStore(): Promise<void> {
+  try {
+    const saved = localStorage.getItem(STORAGE_KEY);
+    if (isQuality(saved)) {
+      quality = saved;
+    }
+  } catch {
+    // localStorage unavailable
+  }
+
+  try {
+    const settings = await invoke<ArtworkSettings>('get_artwork_settings');
+    if (isQuality(settings.quality)) {
+      quality = settings.quality;
+      localStorage.setItem(STORAGE_KEY, quality);
+    }
+  } catch (e) {
+    console.error('[ArtworkStore] Failed to load artwork settings:', e);
+  }
+}
+
+export function getArtworkQuality(): ArtworkQuality {
+  return quality;
+}
+
+export async function setArtworkQuality(value: ArtworkQuality): Promise<void> {
+  await invoke('set_artwork_quality', { quality: value });
+  quality = value;
+  try {
+    localStorage.setItem(STORAGE_KEY, value);
+  } catch {
+    // localStorage unavailable
+  }
+}
+
+/**
+ * Rewrite a Qobuz cover URL to the best matching size for `desiredSize`
+ * pixels (Infinity = original). Other URLs are returned unchanged.
+ */
+export function getArtworkUrl(base: string, desiredSize: number): string {
+  const match = COVER_URL.exec(base);
+  if (!match) return base;
+  const size = COVER_SIZES.find((s) => s >= desiredSize);
+  return `${match[1]}_${size ?? 'max'}.${match[3]}`;
+}
+
+/**
+ * Apply the configured quality to a cover URL. Standard leaves URLs as the
+ * API returned them.
+ */
+export function applyArtworkQuality(url: string): string {
+  if (!url || quality === 'standard') return url;
+  return getArtworkUrl(url, quality === 'low' ? 230 : Infinity);
+}
@@ -7,11 +7,13 @@
  * Sync strategy:
  * - On login: Fetch from Qobuz API → sync to local cache → populate store
  * - On toggle: API call first → on success → update local cache → notify UI
+ * - On batch change: update store + local cache → backend syncs to Qobuz in background
  * - UI reads from in-memory store (backed by local cache)
  */
 
 import { invoke } from '@tauri-apps/api/core';
 import { logRecoEvent } from '$lib/services/recoService';
+import type { QobuzTrack } from '$lib/types';
 
 // State
 let favoriteTrackIds = new Set<number>();
@@ -110,11 +112,12 @@ export async function syncFromApi(): Promise<void> {
   try {
     // Fetch all favorites from API (paginated)
     const allTrackIds: number[] = [];
+    const entries: Array<Record<string, unknown>> = [];
     let offset = 0;
     const limit = 500;
 
     while (true) {
-      const response = await invoke<{ tracks?: { items?: Array<{ id: number }>; total?: number } }>('get_favorites', {
+      const response = await invoke<{ tracks?: { items?: QobuzTrack[]; total?: number } }>('get_favorites', {
         favType: 'tracks',
         limit,
         offset
@@ -124,6 +127,16 @@ export async function syncFromApi(): Promise<void> {
       if (items.length === 0) break;
 
       allTrackIds.push(...items.map(t => t.id));
+      entries.push(...items.map(t => ({
+        track_id: t.id,
+        title: t.title,
+        artist: t.performer?.name ?? null,
+        genre: t.album?.genre?.name ?? null,
+        release_year: t.album?.release_date_original ? parseInt(t.album.release_date_original.slice(0, 4), 10) || null : null,
+        hires: t.hires_streamable ?? false,
+        bit_depth: t.maximum_bit_depth ?? null,
+        added_at: t.favorited_at ?? null
+      })));
       offset += items.length;
 
       // Check if we've fetched all
@@ -132,10 +145,10 @@ export async function syncFromApi(): Promise<void> {
     }
 
     // Sync to local cache
-    await invoke('sync_cached_favorite_tracks', { trackIds: allTrackIds });
+    await invoke('sync_cached_favorite_tracks', { trackIds: allTrackIds, entries });
 
-    // Update in-memory store
-    favoriteTrackIds = new Set(allTrackIds);
+    // Update in-memory store (read back so changes still queued for Qobuz stay)
+    favoriteTrackIds = new Set(await invoke<number[]>('get_cached_favorite_tracks'));
     isLoaded = true;
     notifyListeners();
 
@@ -229,6 +242,46 @@ export async function syncCache(trackIds: number[]): Promise<void> {
   }
 }
 
+export interface FavoritesImportSummary {
+  added: number;
+  removed: number;
+  skipped: number;
+  invalid: number;
+  failed: number;
+}
+
+/**
+ * Export cached favorites (tracks, albums, artists) as JSON for backup
+ */
+export async function exportFavorites(): Promise<string> {
+  return invoke<string>('export_favorites');
+}
+
+/**
+ * Counts an import would produce, without touching Qobuz.
+ * Show `removed` to the user before a replace import.
+ */
+export async function previewFavoritesImport(json: string, merge: boolean): Promise<FavoritesImportSummary> {
+  return invoke<FavoritesImportSummary>('preview_favorites_import', { json, merge });
+}
+
+/**
+ * Import favorites from an exported JSON file, favoriting them on Qobuz.
+ * With merge=false, favorites missing from the file are removed; the backend
+ * refuses that unless confirmRemovals is set after showing the preview.
+ */
+export async function importFavorites(
+  json: string,
+  merge: boolean,
+  confirmRemovals = false
+): Promise<FavoritesImportSummary> {
+  const summary = await invoke<FavoritesImportSummary>('import_favorites', { json, merge, confirmRemovals });
+  const cachedIds = await invoke<number[]>('get_cached_favorite_tracks');
+  favoriteTrackIds = new Set(cachedIds);
+  notifyListeners();
+  return summary;
+}
+
 /**
  * Reset store (for logout)
  */
@@ -263,3 +316,48 @@ export function unmarkAsFavorite(trackId: number): void {
     notifyListeners();
   }
 }
+
+/**
+ * Favorite or unfavorite many tracks at once.
+ * The in-memory store and local cache update immediately; the backend syncs
+ * the change to Qobuz in the background (queued while offline).
+ */
+export async function setTrackFavoritesBatch(trackIds: number[], favorite: boolean): Promise<boolean> {
+  const changed = trackIds.filter(id => favoriteTrackIds.has(id) !== favorite);
+  if (changed.length === 0) return true;
+
+  for (const id of changed) {
+    if (favorite) favoriteTrackIds.add(id);
+    else favoriteTrackIds.delete(id);
+  }
+  notifyListeners();
+
+  try {
+    await invoke(favorite ? 'add_favorites_batch' : 'remove_favorites_batch', {
+      favType: 'track',
+      itemIds: changed.map(id => String(id))
+    });
+    return true;
+  } catch (err) {
+    console.error('[Favorites] Failed to queue batch change:', err);
+    for (const id of changed) {
+      if (favorite) favoriteTrackIds.delete(id);
+      else favoriteTrackIds.add(id);
+    }
+    notifyListeners();
+    return false;
+  }
+}
+
+/**
+ * Reload the in-memory store from the local cache
+ * (e.g. after the backend reverted changes it could not sync)
+ */
+export async function reloadFromCache(): Promise<void> {
+  try {
+    favoriteTrackIds = new Set(await invoke<number[]>('get_cached_favorite_tracks'));
+    notifyListeners();
+  } catch (err) {
+    console.error('[Favorites] Failed to reload from cache:', err);
+  }
+}
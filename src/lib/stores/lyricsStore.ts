@@ -20,11 +20,22 @@ export interface LyricsPayload {
   syncedLrc: string | null;
   provider: 'lrclib' | 'ovh';
   cached: boolean;
+  /** syncedLrc parsed by the backend, with word timings when available */
+  lines?: LyricsLine[];
+  wordSynced?: boolean;
+}
+
+export interface LyricsWord {
+  timeMs: number;
+  /** Includes the trailing space, so words concatenate to the line text */
+  text: string;
 }
 
 export interface LyricsLine {
   timeMs: number;
   text: string;
+  /** Per-word timings (enhanced LRC); empty or absent for line-level sync */
+  words?: LyricsWord[];
 }
 
 export interface ParsedLyrics {
@@ -162,6 +173,10 @@ function parsePlain(plain: string): LyricsLine[] {
  * Parse lyrics payload into lines
  */
 function parsePayload(p: LyricsPayload): ParsedLyrics {
+  if (p.lines && p.lines.length > 0) {
+    return { lines: p.lines, isSynced: true };
+  }
+
   if (p.syncedLrc && p.syncedLrc.trim()) {
     const lines = parseLRC(p.syncedLrc);
     if (lines.length > 0) {
@@ -201,6 +216,27 @@ function findActiveLineIndex(lines: LyricsLine[], currentTimeMs: number): number
   return result;
 }
 
+/**
+ * Progress through a word-timed line (0-1), by characters sung.
+ * Each word fills from its own start to the next word's start.
+ */
+function calculateWordProgress(words: LyricsWord[], lineEndMs: number, currentTimeMs: number): number {
+  const totalChars = words.reduce((sum, word) => sum + word.text.length, 0);
+  if (totalChars === 0) return 0;
+
+  let sungChars = 0;
+  for (let i = 0; i < words.length; i++) {
+    const word = words[i];
+    if (currentTimeMs < word.timeMs) break;
+    const wordEnd = words[i + 1]?.timeMs ?? lineEndMs;
+    const wordDuration = wordEnd - word.timeMs;
+    const fraction = wordDuration > 0 ? Math.min(1, (currentTimeMs - word.timeMs) / wordDuration) : 1;
+    sungChars += word.text.length * fraction;
+  }
+
+  return Math.min(1, sungChars / totalChars);
+}
+
 /**
  * Calculate progress within current line (0-1)
  */
@@ -210,6 +246,14 @@ function calculateLineProgress(lines: LyricsLine[], index: number, currentTimeMs
   const currentLine = lines[index];
   const nextLine = lines[index + 1];
 
+  // Word timings give real karaoke progress; otherwise interpolate over the line
+  if (currentLine.words && currentLine.words.length > 0) {
+    const lastWord = currentLine.words[currentLine.words.length - 1];
+    // Without a next line, give the last word a second
+    const lineEndMs = nextLine ? nextLine.timeMs : lastWord.timeMs + 1000;
+    return calculateWordProgress(currentLine.words, lineEndMs, currentTimeMs);
+  }
+
   if (!nextLine) {
     // Last line - assume 5 seconds duration
     const duration = 5000;
@@ -46,6 +46,9 @@ let selectedPlaylistId: number | null = null;
 // Selected local album ID (for library-album view)
 let selectedLocalAlbumId: string | null = null;
 
+// Library artist to open once the library view picks it up
+let pendingLocalArtistName: string | null = null;
+
 // Last visited Favorites tab (used as default when navigating to Favorites)
 let lastFavoritesTab: FavoritesTab = 'tracks';
 
@@ -226,6 +229,31 @@ export function getSelectedLocalAlbumId(): string | null {
   return selectedLocalAlbumId;
 }
 
+/**
+ * Navigate to the library artists tab with an artist selected
+ * (local and Plex artists have no artist page of their own)
+ */
+export function selectLocalArtist(name: string): void {
+  pendingLocalArtistName = name;
+  selectedLocalAlbumId = null;
+
+  // If already on the library view, still notify so the component picks it up
+  if (activeView === 'library') {
+    notifyListeners();
+  } else {
+    navigateTo('library');
+  }
+}
+
+/**
+ * Take the artist requested by selectLocalArtist, if any
+ */
+export function takePendingLocalArtist(): string | null {
+  const name = pendingLocalArtistName;
+  pendingLocalArtistName = null;
+  return name;
+}
+
 // ============ Favorites Navigation ============
 export function navigateToFavorites(tab?: FavoritesTab): void {
   const targetTab = tab ?? lastFavoritesTab;
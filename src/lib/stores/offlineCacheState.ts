@@ -249,6 +249,64 @@ export async function refreshCachedTrack(track: {
   await cacheTrackForOffline(track);
 }
 
+export type CacheBatchStatus = 'running' | 'paused' | 'cancelled' | 'completed';
+
+// Overall progress of an album/playlist batch (also sent as offline:batch_progress)
+export interface CacheBatchProgress {
+  batchId: number;
+  kind: 'album' | 'playlist' | 'discography';
+  sourceId: string;
+  title: string;
+  totalTracks: number;
+  skippedTracks: number;
+  completedTracks: number;
+  failedTracks: number;
+  status: CacheBatchStatus;
+  pausedForMetered: boolean; // Auto-paused on a metered connection
+}
+
+// Cache all tracks of an album; returns the batch ID
+export async function cacheAlbumForOffline(albumId: string, quality?: string): Promise<number> {
+  return invoke<number>('cache_album_for_offline', { albumId, quality });
+}
+
+// Cache all tracks of a playlist; returns the batch ID
+export async function cachePlaylistForOffline(playlistId: number, quality?: string): Promise<number> {
+  return invoke<number>('cache_playlist_for_offline', { playlistId, quality });
+}
+
+// Progress of a discography download (offline:discography_progress)
+export interface DiscographyProgress extends CacheBatchProgress {
+  totalAlbums: number;
+  skippedAlbums: number; // Every track already in the download folder
+  completedAlbums: number;
+  failedAlbums: number; // Couldn't be fetched, or every download failed
+  currentAlbum: string | null;
+  currentTrack: string | null;
+}
+
+// Download an artist's discography as tagged FLAC files into the download folder;
+// returns the batch ID (paused/resumed/cancelled like offline batches)
+export async function downloadArtistDiscography(artistId: number, quality?: string): Promise<number> {
+  return invoke<number>('download_artist_discography', { artistId, quality });
+}
+
+export async function pauseOfflineBatch(batchId: number): Promise<CacheBatchProgress> {
+  return invoke<CacheBatchProgress>('pause_offline_batch', { batchId });
+}
+
+export async function resumeOfflineBatch(batchId: number): Promise<CacheBatchProgress> {
+  return invoke<CacheBatchProgress>('resume_offline_batch', { batchId });
+}
+
+export async function cancelOfflineBatch(batchId: number): Promise<CacheBatchProgress> {
+  return invoke<CacheBatchProgress>('cancel_offline_batch', { batchId });
+}
+
+export async function getOfflineBatches(): Promise<CacheBatchProgress[]> {
+  return invoke<CacheBatchProgress[]>('get_offline_batches');
+}
+
 // ============================================================================
 // COMPATIBILITY ALIASES - These maintain backward compatibility during refactor
 // Will be removed once all references are updated
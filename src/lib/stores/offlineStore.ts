@@ -24,12 +24,17 @@ export interface PendingPlaylist {
   createdAt: number;
   synced: boolean;
   qobuzPlaylistId: number | null;
+  syncedTrackIds: number[]; // Already added to the Qobuz playlist by an earlier sync
+  syncedLocalPaths: string[];
 }
 
+export type ConnectionType = 'ethernet' | 'wifi' | 'metered' | 'unknown';
+
 export interface OfflineStatus {
   isOffline: boolean;
   reason: OfflineReason | null;
   manualModeEnabled: boolean;
+  connectionType: ConnectionType;
 }
 
 export interface OfflineSettings {
@@ -46,6 +51,7 @@ let status: OfflineStatus = {
   isOffline: false,
   reason: null,
   manualModeEnabled: false,
+  connectionType: 'unknown',
 };
 
 let settings: OfflineSettings = {
@@ -448,155 +454,42 @@ export async function cleanupSentScrobbles(olderThanDays?: number): Promise<numb
   return invoke<number>('cleanup_sent_scrobbles', { olderThanDays });
 }
 
+export interface PendingPlaylistSyncResult {
+  pendingId: number;
+  name: string;
+  qobuzPlaylistId: number | null;
+  outcome: 'created' | 'updated' | 'failed';
+  addedTracks: number;
+  matchedLocalTracks: number;
+  linkedLocalTracks: number;
+  skippedLocalTracks: number;
+  error: string | null;
+}
+
 /**
- * Sync all pending playlists to Qobuz when back online
+ * Sync all pending playlists to Qobuz when back online.
+ * The backend resumes interrupted syncs and keeps failed playlists queued.
  */
-export async function syncPendingPlaylists(): Promise<void> {
+export async function syncPendingPlaylists(): Promise<PendingPlaylistSyncResult[]> {
   try {
     console.log('[Offline] Syncing pending playlists...');
-    const pending = await getPendingPlaylists();
-
-    if (pending.length === 0) {
-      console.log('[Offline] No pending playlists to sync');
-      return;
-    }
-
-    console.log(`[Offline] Found ${pending.length} pending playlists to sync`);
-
-    for (const playlist of pending) {
-      try {
-        console.log(`[Offline] Syncing playlist: ${playlist.name}`);
-
-        let qobuzPlaylistId: number;
-
-        // Check if this playlist was already partially synced
-        if (playlist.qobuzPlaylistId) {
-          qobuzPlaylistId = playlist.qobuzPlaylistId;
-          console.log(`[Offline] Using existing Qobuz playlist ID: ${qobuzPlaylistId}`);
-        } else {
-          // Create playlist on Qobuz
-          const createdPlaylist = await invoke<{ id: number }>('create_playlist', {
-            name: playlist.name,
-            description: playlist.description,
-            isPublic: playlist.isPublic
-          });
-
-          qobuzPlaylistId = createdPlaylist.id;
-          console.log(`[Offline] Created playlist on Qobuz with ID: ${qobuzPlaylistId}`);
-
-          // Save the Qobuz ID immediately (but keep synced=0 until fully complete)
-          await invoke('update_pending_playlist_qobuz_id', {
-            pendingId: playlist.id,
-            qobuzPlaylistId
-          });
-        }
-
-        // Add Qobuz tracks if any
-        if (playlist.trackIds.length > 0) {
-          console.log(`[Offline] Adding ${playlist.trackIds.length} Qobuz tracks`);
-          await invoke('add_tracks_to_playlist', {
-            playlistId: qobuzPlaylistId,
-            trackIds: playlist.trackIds
-          });
-        }
-
-        // Add local tracks if any
-        // Prefer localTrackPaths (new system), fall back to localTrackIds (migration)
-        const localTracks = playlist.localTrackPaths?.length > 0
-          ? playlist.localTrackPaths
-          : playlist.localTrackIds;
-
-        if (localTracks && localTracks.length > 0) {
-          const usePathSystem = playlist.localTrackPaths?.length > 0;
-          console.log(`[Offline] ======================================`);
-          console.log(`[Offline] Adding ${localTracks.length} local tracks to playlist ${qobuzPlaylistId}`);
-          console.log(`[Offline] Using ${usePathSystem ? 'PATH-based' : 'ID-based (legacy)'} system`);
-          console.log(`[Offline] Local tracks:`, localTracks);
-          console.log(`[Offline] Qobuz tracks count:`, playlist.trackIds.length);
-          console.log(`[Offline] ======================================`);
-
-          for (let i = 0; i < localTracks.length; i++) {
-            const trackIdentifier = localTracks[i];
-            const position = playlist.trackIds.length + i;
-
-            console.log(`[Offline] ------ Track ${i + 1}/${localTracks.length} ------`);
-
-            try {
-              let localTrackId: number;
-
-              if (usePathSystem) {
-                // NEW SYSTEM: Resolve file path to current track ID
-                const filePath = trackIdentifier as string;
-                console.log(`[Offline] Resolving path: ${filePath}`);
-
-                // Get track ID from file path
-                const trackInfo = await invoke<{ id: number } | null>('get_track_by_path', {
-                  filePath
-                });
-
-                if (!trackInfo) {
-                  console.warn(`[Offline] ⚠️ File not found in library: ${filePath}`);
-                  console.warn(`[Offline] Skipping - file may have been moved or deleted`);
-                  continue;
-                }
-
-                localTrackId = trackInfo.id;
-                console.log(`[Offline] Resolved to track ID: ${localTrackId}`);
-              } else {
-                // OLD SYSTEM: Use ID directly (for migration)
-                localTrackId = trackIdentifier as number;
-                console.log(`[Offline] Using legacy track ID: ${localTrackId}`);
-              }
-
-              console.log(`[Offline] Position: ${position}`);
-              console.log(`[Offline] Calling playlist_add_local_track with params:`, {
-                playlistId: qobuzPlaylistId,
-                localTrackId: localTrackId,
-                position: position
-              });
-
-              await invoke('playlist_add_local_track', {
-                playlistId: qobuzPlaylistId,
-                localTrackId: localTrackId,
-                position: position
-              });
-              console.log(`[Offline] ✓✓✓ SUCCESS: Local track ${localTrackId} added at position ${position}`);
-            } catch (localTrackErr) {
-              console.error(`[Offline] ✗✗✗ ERROR: Failed to add track`);
-              console.error(`[Offline] Error:`, localTrackErr);
-
-              // Check if it's a FOREIGN KEY error (track doesn't exist in library)
-              const errorStr = String(localTrackErr);
-              if (errorStr.includes('FOREIGN KEY constraint')) {
-                console.warn(`[Offline] ⚠️ Track no longer exists in library - skipping`);
-                // Don't throw - continue with next track
-              } else {
-                // Other errors should fail the sync
-                throw new Error(`Failed to add local track: ${localTrackErr}`);
-              }
-            }
-          }
-
-          console.log(`[Offline] ======================================`);
-          console.log(`[Offline] All local tracks processed`);
-          console.log(`[Offline] ======================================`);
-        }
-
-        // Mark as synced ONLY if everything succeeded
-        await markPendingPlaylistSynced(playlist.id, qobuzPlaylistId);
-        console.log(`[Offline] Successfully synced playlist: ${playlist.name}`);
-
-        showToast(`Synced playlist "${playlist.name}"`, 'success');
-      } catch (err) {
-        console.error(`[Offline] Failed to sync playlist "${playlist.name}":`, err);
-        showToast(`Failed to sync playlist "${playlist.name}": ${err}`, 'error');
-        // Don't mark as synced if there was an error - will retry on next online transition
+    const results = await invoke<PendingPlaylistSyncResult[]>('sync_pending_playlists');
+
+    for (const result of results) {
+      if (result.outcome === 'failed') {
+        console.error(`[Offline] Failed to sync playlist "${result.name}":`, result.error);
+        showToast(`Failed to sync playlist "${result.name}": ${result.error}`, 'error');
+      } else {
+        console.log(`[Offline] Synced playlist "${result.name}" (${result.outcome})`, result);
+        showToast(`Synced playlist "${result.name}"`, 'success');
       }
     }
 
     console.log('[Offline] Finished syncing pending playlists');
+    return results;
   } catch (err) {
     console.error('[Offline] Error syncing pending playlists:', err);
+    return [];
   }
 }
 
@@ -10,16 +10,28 @@ import { invoke } from '@tauri-apps/api/core';
 
 export type AutoplayMode = 'continue' | 'track_only';
 
+export type EndOfQueueBehavior = 'stop' | 'radio' | 'loop';
+
 export interface PlaybackPreferences {
   autoplay_mode: AutoplayMode;
   show_context_icon: boolean;
+  radio_freshness_days: number; // Radio skips tracks played within this window (0 = off)
+  end_of_queue: EndOfQueueBehavior; // What happens after the last track of the queue
+  previous_restart_threshold_secs: number; // "Previous" restarts the track after this many seconds (0 = never)
+  radio_queue_ahead: number; // Upcoming tracks radio keeps queued
+  radio_after_source: boolean; // Roll into radio when an album/playlist/artist context ends
 }
 
 // ============ State ============
 
 let preferences: PlaybackPreferences = {
   autoplay_mode: 'continue',
-  show_context_icon: true
+  show_context_icon: true,
+  radio_freshness_days: 7,
+  end_of_queue: 'stop',
+  previous_restart_threshold_secs: 3,
+  radio_queue_ahead: 50,
+  radio_after_source: false
 };
 
 const listeners = new Set<() => void>();
@@ -60,6 +72,51 @@ export async function setShowContextIcon(show: boolean): Promise<void> {
   notifyListeners();
 }
 
+/**
+ * Set how many days radio avoids recently played tracks (0 = off)
+ */
+export async function setRadioFreshness(days: number): Promise<void> {
+  await invoke('set_radio_freshness', { days });
+  preferences.radio_freshness_days = days;
+  notifyListeners();
+}
+
+/**
+ * Set what happens once the queue runs out
+ */
+export async function setEndOfQueueBehavior(behavior: EndOfQueueBehavior): Promise<void> {
+  await invoke('set_end_of_queue_behavior', { behavior });
+  preferences.end_of_queue = behavior;
+  notifyListeners();
+}
+
+/**
+ * Set after how many seconds "previous" restarts the current track (0 = never)
+ */
+export async function setPreviousRestartThreshold(secs: number): Promise<void> {
+  await invoke('set_previous_restart_threshold_secs', { secs });
+  preferences.previous_restart_threshold_secs = secs;
+  notifyListeners();
+}
+
+/**
+ * Set how many upcoming tracks radio keeps queued
+ */
+export async function setRadioQueueAhead(tracks: number): Promise<void> {
+  await invoke('set_radio_queue_ahead', { tracks });
+  preferences.radio_queue_ahead = tracks;
+  notifyListeners();
+}
+
+/**
+ * Set whether an ended album/playlist/artist context continues as radio
+ */
+export async function setRadioAfterSource(enabled: boolean): Promise<void> {
+  await invoke('set_radio_after_source', { enabled });
+  preferences.radio_after_source = enabled;
+  notifyListeners();
+}
+
 /**
  * Get cached preferences (no backend call)
  */
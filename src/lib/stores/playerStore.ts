@@ -34,6 +34,7 @@ import {
   setOnCastDisconnected
 } from '$lib/stores/castStore';
 import { syncQueueState } from '$lib/stores/queueStore';
+import { showToast } from '$lib/stores/toastStore';
 
 // ============ Types ============
 
@@ -80,6 +81,19 @@ interface PlaybackEvent {
   normalization_gain: number | null;  // Active normalization gain factor (null = not applied)
   gapless_ready: boolean;       // Backend wants next track queued for gapless
   gapless_next_track_id: number; // Track ID queued for gapless (0 = none)
+  playing_from_cache: boolean;  // Offline copy playing because the stream failed
+  format_id?: number | null;    // Qobuz format of the playing data (null = unknown)
+  reduced_quality?: boolean;    // Prefetched at the lower prefetch quality
+  replaygain_mode?: 'track' | 'album' | null;  // ReplayGain value applied (null = normalization off)
+  replaygain_auto?: boolean;    // Album/track gain follows the playback context
+  buffered_ahead_secs?: number | null;  // Seconds downloaded past the playhead (null = fully buffered)
+}
+
+// Audio engine failure (audio thread crashed)
+interface PlaybackErrorEvent {
+  message: string;
+  fatal: boolean;       // Playback needs an app restart
+  restarted: boolean;   // Audio engine was restarted automatically
 }
 
 // Queue track from backend (for external track sync)
@@ -138,11 +152,15 @@ let preMuteVolume: number | null = null; // Volume before mute, null when not mu
 let isFavorite = false;
 // Event listener state (replaces polling)
 let eventUnlisten: UnlistenFn | null = null;
+let errorUnlisten: UnlistenFn | null = null;
 let castUnsubscribe: (() => void) | null = null;
 let isAdvancingTrack = false;
 let isSkipping = false;
 let queueEnded = false;
 let normalizationGain: number | null = null;  // Current normalization gain (null = not active)
+let playingFromCache = false;  // Offline copy playing because the stream failed
+let normalizationMode: 'track' | 'album' | null = null;  // ReplayGain value applied to the current track
+let replayGainAuto = true;  // Album/track gain picked from the playback context
 
 // Callbacks for track advancement (set by consumer)
 let onTrackEnded: (() => Promise<void>) | null = null;
@@ -210,6 +228,14 @@ export function getNormalizationGain(): number | null {
   return normalizationGain;
 }
 
+export function getPlayingFromCache(): boolean {
+  return playingFromCache;
+}
+
+export function getNormalizationMode(): 'track' | 'album' | null {
+  return normalizationMode;
+}
+
 // ============ State Setter ============
 
 export interface PlayerState {
@@ -221,6 +247,9 @@ export interface PlayerState {
   isFavorite: boolean;
   isSkipping: boolean;
   normalizationGain: number | null;
+  normalizationMode: 'track' | 'album' | null;
+  replayGainAuto: boolean;
+  playingFromCache: boolean;
 }
 
 export function getPlayerState(): PlayerState {
@@ -232,7 +261,10 @@ export function getPlayerState(): PlayerState {
     volume,
     isFavorite,
     isSkipping,
-    normalizationGain
+    normalizationGain,
+    normalizationMode,
+    replayGainAuto,
+    playingFromCache
   };
 }
 
@@ -645,6 +677,9 @@ async function handlePlaybackEvent(event: PlaybackEvent): Promise<void> {
 
     // Update normalization gain state
     normalizationGain = event.normalization_gain;
+    normalizationMode = event.replaygain_mode ?? null;
+    replayGainAuto = event.replaygain_auto ?? true;
+    playingFromCache = event.playing_from_cache ?? false;
 
     notifyListeners();
 
@@ -654,7 +689,7 @@ async function handlePlaybackEvent(event: PlaybackEvent): Promise<void> {
       if (nextId && nextId > 0) {
         gaplessRequestInFlight = true;
         console.log('[Gapless] Backend ready, queueing track', nextId);
-        invoke<boolean>('play_next_gapless', { trackId: nextId })
+        invoke<boolean>('play_next_gapless', { trackId: nextId, quality: getStreamingQuality() })
           .then((queued) => {
             if (queued) {
               console.log('[Gapless] Track', nextId, 'queued successfully');
@@ -704,6 +739,12 @@ export async function startPolling(): Promise<void> {
     eventUnlisten = await listen<PlaybackEvent>('playback:state', (event) => {
       handlePlaybackEvent(event.payload);
     });
+    errorUnlisten = await listen<PlaybackErrorEvent>('playback:error', (event) => {
+      console.error('[Player] Audio engine error:', event.payload);
+      isPlaying = false;
+      notifyListeners();
+      showToast(event.payload.message, 'error', event.payload.fatal ? 15000 : 8000);
+    });
     console.log('Started listening for playback events');
 
     // Sync persisted volume to backend on startup
@@ -751,6 +792,10 @@ export function stopPolling(): void {
     eventUnlisten = null;
     console.log('Stopped listening for playback events');
   }
+  if (errorUnlisten) {
+    errorUnlisten();
+    errorUnlisten = null;
+  }
   if (castUnsubscribe) {
     castUnsubscribe();
     castUnsubscribe = null;
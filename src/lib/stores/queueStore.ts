@@ -6,6 +6,7 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { getPlaybackContext, type ContentSource, type ContextType } from './playbackContextStore';
 
 // ============ Types ============
 
@@ -35,6 +36,12 @@ export interface BackendQueueTrack {
   streamable?: boolean;
   /** Track source: qobuz | local | plex */
   source?: string;
+  /** Track number within its disc (gapless album boundaries) */
+  track_number?: number | null;
+  /** Disc number within the album */
+  disc_number?: number | null;
+  /** Guest who added the track during a party session */
+  contributor?: string | null;
 }
 
 interface BackendQueueState {
@@ -324,6 +331,41 @@ export async function setQueue(tracks: BackendQueueTrack[], startIndex: number,
   }
 }
 
+export interface CollectionContext {
+  contextType: ContextType;
+  id: string;
+  label: string;
+  source: ContentSource;
+}
+
+/**
+ * Replace the queue with a collection (album, playlist, ...).
+ * With shuffle set, the tracks are shuffled once for this collection only;
+ * the global shuffle mode is not changed. Returns the track to start with.
+ */
+export async function playCollection(
+  tracks: BackendQueueTrack[],
+  options: { shuffle?: boolean; startIndex?: number; context?: CollectionContext } = {}
+): Promise<BackendQueueTrack | null> {
+  try {
+    const first = await invoke<BackendQueueTrack | null>('play_collection', {
+      tracks,
+      shuffle: options.shuffle ?? false,
+      startIndex: options.startIndex ?? null,
+      context: options.context ?? null
+    });
+    localTrackIds = new Set(tracks.filter(t => t.is_local).map(t => t.id));
+    if (options.context) {
+      await getPlaybackContext();
+    }
+    await syncQueueState();
+    return first;
+  } catch (err) {
+    console.error('Failed to play collection:', err);
+    return null;
+  }
+}
+
 /**
  * Clear the queue
  */
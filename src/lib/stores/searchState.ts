@@ -1,13 +1,45 @@
+import type { LocalLibraryTrack } from '$lib/types';
+import type { CachedTrackInfo } from '$lib/stores/offlineCacheState';
+import type { QualityInfo } from '$lib/utils/quality';
+
 export type SearchTab = 'all' | 'albums' | 'tracks' | 'artists' | 'playlists';
 
 // Search filter types supported by Qobuz API
 export type SearchFilterType = 'MainArtist' | 'Performer' | 'Composer' | 'Label' | 'ReleaseName' | null;
 
+// Genre/year/explicit filters for album and track search (applied by the backend)
+export interface AdvancedSearchFilters {
+  genre_id: number | null;
+  year_range: { from: number | null; to: number | null } | null;
+  exclude_explicit: boolean;
+}
+
+export function emptyAdvancedFilters(): AdvancedSearchFilters {
+  return { genre_id: null, year_range: null, exclude_explicit: false };
+}
+
+// Ordering of duplicate track versions in track results (applied by the backend)
+export type QualityRanking = 'off' | 'prefer_best' | 'collapse_duplicates';
+
+const QUALITY_RANKING_KEY = 'qbz-search-quality-ranking';
+
+export function getQualityRanking(): QualityRanking {
+  const saved = localStorage.getItem(QUALITY_RANKING_KEY);
+  return saved === 'prefer_best' || saved === 'collapse_duplicates' ? saved : 'off';
+}
+
+export function setQualityRanking(ranking: QualityRanking): void {
+  localStorage.setItem(QUALITY_RANKING_KEY, ranking);
+}
+
 export interface SearchResults<T> {
   items: T[];
   total: number;
   offset: number;
   limit: number;
+  // Album/track search only: filters in effect and where the next page starts
+  applied_filters?: AdvancedSearchFilters | null;
+  next_offset?: number | null;
 }
 
 // Most popular item from Qobuz catalog search
@@ -40,10 +72,41 @@ export interface SearchAllResults<Album, Track, Artist> {
   most_popular: MostPopularItem<Album, Track, Artist> | null;
 }
 
+// Where an offline-aware search result plays from
+export type SearchSource = 'local' | 'plex' | 'offline_cache' | 'qobuz';
+
+export interface PlexSearchTrack {
+  id: number;
+  ratingKey: string;
+  title: string;
+  artist: string;
+  album: string;
+  durationSecs: number;
+  format: string;
+  bitDepth?: number;
+  sampleRate: number;
+  artworkPath?: string;
+  source: string;
+  albumKey: string;
+  quality: QualityInfo;
+}
+
+// Results of search_offline, grouped by source. Qobuz is only searched online.
+export interface OfflineSearchResults<Track> {
+  query: string;
+  offline: boolean;
+  local: LocalLibraryTrack[];
+  plex: PlexSearchTrack[];
+  offline_cache: CachedTrackInfo[];
+  qobuz: Track[];
+  sources: SearchSource[];
+}
+
 export interface SearchState<Album, Track, Artist> {
   query: string;
   activeTab: SearchTab;
   filterType: SearchFilterType;
+  advancedFilters: AdvancedSearchFilters;
   albumResults: SearchResults<Album> | null;
   trackResults: SearchResults<Track> | null;
   artistResults: SearchResults<Artist> | null;
@@ -55,6 +118,7 @@ let searchState: SearchState<unknown, unknown, unknown> = {
   query: '',
   activeTab: 'all',
   filterType: null,
+  advancedFilters: emptyAdvancedFilters(),
   albumResults: null,
   trackResults: null,
   artistResults: null,
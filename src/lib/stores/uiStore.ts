@@ -15,7 +15,7 @@ let isCastPickerOpen = false;
 
 // Playlist modal states
 let isPlaylistModalOpen = false;
-let playlistModalMode: 'create' | 'edit' | 'addTrack' = 'create';
+let playlistModalMode: 'create' | 'edit' | 'addTrack' | 'saveQueue' = 'create';
 let playlistModalTrackIds: number[] = [];
 let playlistModalTracksAreLocal = false;
 let isPlaylistImportOpen = false;
@@ -132,7 +132,7 @@ export function getPlaylistModalOpen(): boolean {
   return isPlaylistModalOpen;
 }
 
-export function getPlaylistModalMode(): 'create' | 'edit' | 'addTrack' {
+export function getPlaylistModalMode(): 'create' | 'edit' | 'addTrack' | 'saveQueue' {
   return playlistModalMode;
 }
 
@@ -144,7 +144,7 @@ export function getPlaylistModalTracksAreLocal(): boolean {
   return playlistModalTracksAreLocal;
 }
 
-export function openPlaylistModal(mode: 'create' | 'edit' | 'addTrack', trackIds: number[] = [], isLocal = false): void {
+export function openPlaylistModal(mode: 'create' | 'edit' | 'addTrack' | 'saveQueue', trackIds: number[] = [], isLocal = false): void {
   isPlaylistModalOpen = true;
   playlistModalMode = mode;
   playlistModalTrackIds = trackIds;
@@ -217,7 +217,7 @@ export interface UIState {
   isFocusModeOpen: boolean;
   isCastPickerOpen: boolean;
   isPlaylistModalOpen: boolean;
-  playlistModalMode: 'create' | 'edit' | 'addTrack';
+  playlistModalMode: 'create' | 'edit' | 'addTrack' | 'saveQueue';
   playlistModalTrackIds: number[];
   playlistModalTracksAreLocal: boolean;
   isPlaylistImportOpen: boolean;
@@ -0,0 +1,130 @@
+/**
+ * Waveform Store
+ *
+ * Fetches the seekbar waveform (peak/RMS overview) for the current track.
+ * The backend builds it from audio that is already local, so for a streamed
+ * Qobuz track it only becomes available once the download has finished;
+ * until then the fetch is retried in the background.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { getCurrentTrack, subscribe as subscribePlayer } from './playerStore';
+
+// ============ Types ============
+
+export interface TrackWaveform {
+  track_id: number;
+  /** Peak amplitude per point, 0..1 */
+  peaks: number[];
+  /** RMS amplitude per point, 0..1 */
+  rms: number[];
+  duration_secs: number;
+}
+
+// ============ State ============
+
+/** Delay before the first request so it never competes with playback start */
+const INITIAL_DELAY_MS = 1500;
+/** Interval between retries while the audio isn't local yet */
+const RETRY_INTERVAL_MS = 5000;
+/** Give up after this many attempts (~2 minutes) */
+const MAX_ATTEMPTS = 24;
+
+let waveform: TrackWaveform | null = null;
+let lastTrackId: number | null = null;
+let retryTimer: ReturnType<typeof setTimeout> | null = null;
+let playerUnsubscribe: (() => void) | null = null;
+
+const listeners = new Set<() => void>();
+
+function notifyListeners(): void {
+  for (const listener of listeners) {
+    listener();
+  }
+}
+
+// ============ Subscribe ============
+
+export function subscribe(listener: () => void): () => void {
+  listeners.add(listener);
+  listener();
+  return () => listeners.delete(listener);
+}
+
+// ============ Getters ============
+
+export function getWaveform(): TrackWaveform | null {
+  return waveform;
+}
+
+// ============ Fetching ============
+
+function clearRetry(): void {
+  if (retryTimer !== null) {
+    clearTimeout(retryTimer);
+    retryTimer = null;
+  }
+}
+
+function schedule(trackId: number, source: string, attempt: number, delayMs: number): void {
+  clearRetry();
+  retryTimer = setTimeout(() => {
+    retryTimer = null;
+    void fetchWaveform(trackId, source, attempt);
+  }, delayMs);
+}
+
+async function fetchWaveform(trackId: number, source: string, attempt: number): Promise<void> {
+  try {
+    const result = await invoke<TrackWaveform | null>('get_track_waveform', { trackId, source });
+    if (Math.abs(lastTrackId ?? 0) !== trackId) return;
+
+    if (result) {
+      waveform = result;
+      notifyListeners();
+    } else if (attempt + 1 < MAX_ATTEMPTS) {
+      schedule(trackId, source, attempt + 1, RETRY_INTERVAL_MS);
+    }
+  } catch (err) {
+    console.warn('[Waveform] Failed to get waveform:', err);
+  }
+}
+
+/**
+ * Start watching the player and fetch waveforms on track change
+ */
+export function startWatching(): void {
+  if (playerUnsubscribe) return;
+
+  playerUnsubscribe = subscribePlayer(() => {
+    const track = getCurrentTrack();
+    const trackId = track?.id ?? null;
+    if (trackId === lastTrackId) return;
+
+    lastTrackId = trackId;
+    clearRetry();
+    if (waveform) {
+      waveform = null;
+      notifyListeners();
+    }
+    // Plex audio is streamed straight from the server and never cached locally
+    if (track && trackId !== null && track.source !== 'plex') {
+      if (track.isLocal || trackId < 0) {
+        schedule(Math.abs(trackId), 'local', 0, INITIAL_DELAY_MS);
+      } else {
+        schedule(trackId, 'qobuz', 0, INITIAL_DELAY_MS);
+      }
+    }
+  });
+}
+
+/**
+ * Stop watching the player
+ */
+export function stopWatching(): void {
+  if (playerUnsubscribe) {
+    playerUnsubscribe();
+    playerUnsubscribe = null;
+  }
+  clearRetry();
+}
@@ -23,6 +23,7 @@ export interface QobuzTrack {
     image?: QobuzImage;
     label?: { id: number; name: string };
     genre?: { name: string };
+    release_date_original?: string;
   };
   performer?: { id?: number; name: string };
   hires_streamable?: boolean;
@@ -34,6 +35,12 @@ export interface QobuzTrack {
   performers?: string;
   composer?: { id?: number; name: string };
   copyright?: string;
+  /** Classical work the track belongs to */
+  work?: string;
+  /** Movement within the work */
+  movement?: string;
+  /** When the user favorited the track (unix seconds, favorites lists only) */
+  favorited_at?: number;
 }
 
 // Parsed performer from performers string
@@ -86,6 +93,42 @@ export interface TrackCredits {
   artist_id?: number;
 }
 
+// Detailed album credits - Qobuz performers merged with MusicBrainz relationships
+export interface DetailedAlbumCredits {
+  album: AlbumInfo;
+  tracks: DetailedTrackCredits[];
+  /** Credits MusicBrainz lists for the whole release */
+  album_roles: RoleCredits[];
+  /** Everyone credited on the album, each person once */
+  people: CreditedPerson[];
+  musicbrainz_release_id?: string;
+}
+
+export interface DetailedTrackCredits extends TrackCredits {
+  disc_number: number;
+  people: PersonCredit[];
+  roles: RoleCredits[];
+}
+
+export interface PersonCredit {
+  name: string;
+  mbid?: string;
+  roles: string[];
+}
+
+export interface RoleCredits {
+  role: string;
+  people: string[];
+}
+
+export interface CreditedPerson {
+  name: string;
+  mbid?: string;
+  roles: string[];
+  /** Tracks the person is credited on */
+  track_ids: number[];
+}
+
 export interface QobuzAlbum {
   id: string;
   title: string;
@@ -101,6 +144,17 @@ export interface QobuzAlbum {
   maximum_sampling_rate?: number;
   tracks?: { items: QobuzTrack[] };
   upc?: string;
+  composer?: { id?: number; name: string };
+  /** Tracks grouped by classical work */
+  works?: QobuzWorkGroup[];
+  /** When the user favorited the album (unix seconds, favorites lists only) */
+  favorited_at?: number;
+}
+
+export interface QobuzWorkGroup {
+  work: string;
+  composer?: string;
+  track_ids: number[];
 }
 
 export interface QobuzPlaylist {
@@ -163,6 +217,15 @@ export interface Track {
   isrc?: string;
   /** Whether the track is streamable (false = unavailable on Qobuz) */
   streamable?: boolean;
+  composer?: string;
+  work?: string;
+  movement?: string;
+}
+
+export interface AlbumWork {
+  work: string;
+  composer?: string;
+  trackIds: number[];
 }
 
 export interface AlbumDetail {
@@ -181,6 +244,9 @@ export interface AlbumDetail {
   duration: string;
   tracks: Track[];
   upc?: string; // Universal Product Code for album.link sharing
+  composer?: string;
+  /** Tracks grouped by classical work (classical albums only) */
+  works?: AlbumWork[];
 }
 
 export interface ArtistDetail {
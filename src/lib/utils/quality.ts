@@ -0,0 +1,61 @@
+/**
+ * Audio quality helpers shared by Qobuz, Plex and local tracks.
+ * Mirrors the backend `quality` module so every source gets the same badges.
+ */
+
+// Normalized quality as sent by the backend (sample_rate in Hz)
+export interface QualityInfo {
+  bit_depth: number | null;
+  sample_rate: number | null;
+  codec: string | null;
+  lossless: boolean;
+  hires: boolean;
+}
+
+// Hi-res means more than 16 bits or more than 48 kHz
+export const HIRES_BIT_DEPTH_ABOVE = 16;
+export const HIRES_SAMPLE_RATE_ABOVE_HZ = 48000;
+
+const LOSSLESS_CODECS = ['flac', 'alac', 'wav', 'aiff', 'aif', 'ape', 'wv', 'wavpack', 'dsf', 'dff'];
+const LOSSY_CODECS = ['mp3', 'aac', 'vorbis', 'opus', 'wma'];
+
+/**
+ * Sample rate in Hz from a value that may be in kHz
+ * (Qobuz reports 44.1, 96, ...; files and Plex report 44100, 96000, ...)
+ */
+export function toSampleRateHz(rate: number): number {
+  return rate > 0 && rate < 1000 ? rate * 1000 : rate;
+}
+
+export function isHiResSpecs(bitDepth?: number | null, sampleRate?: number | null): boolean {
+  return Boolean(
+    (bitDepth && bitDepth > HIRES_BIT_DEPTH_ABOVE) ||
+    (sampleRate && toSampleRateHz(sampleRate) > HIRES_SAMPLE_RATE_ABOVE_HZ)
+  );
+}
+
+/**
+ * Whether a track is lossless. When the codec is missing or ambiguous (an mp4
+ * container can hold AAC or ALAC), a known bit depth means lossless.
+ */
+export function isLosslessSpecs(bitDepth?: number | null, codec?: string | null): boolean {
+  const normalized = codec?.trim().toLowerCase();
+  if (normalized && LOSSLESS_CODECS.includes(normalized)) return true;
+  if (normalized && LOSSY_CODECS.includes(normalized)) return false;
+  return Boolean(bitDepth && bitDepth > 0);
+}
+
+export function getQualityInfo(
+  bitDepth?: number | null,
+  sampleRate?: number | null,
+  codec?: string | null
+): QualityInfo {
+  const lossless = isLosslessSpecs(bitDepth, codec);
+  return {
+    bit_depth: bitDepth && bitDepth > 0 ? bitDepth : null,
+    sample_rate: sampleRate && sampleRate > 0 ? toSampleRateHz(sampleRate) : null,
+    codec: codec?.trim() || null,
+    lossless,
+    hires: lossless && isHiResSpecs(bitDepth, sampleRate)
+  };
+}